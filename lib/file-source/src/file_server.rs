@@ -28,8 +28,10 @@ use crate::{
 /// `FileServer` is a Source which cooperatively schedules reads over files,
 /// converting the lines of said files into `LogLine` structures. As
 /// `FileServer` is intended to be useful across multiple operating systems with
-/// POSIX filesystem semantics `FileServer` must poll for changes. That is, no
-/// event notification is used by `FileServer`.
+/// POSIX filesystem semantics `FileServer` polls for changes, unless its
+/// `PathsProvider` is notified of them. In that case, paths are listed again
+/// only when files are created, renamed or removed, and only the files written
+/// to are read.
 ///
 /// `FileServer` is configured on a path to watch. The files do _not_ need to
 /// exist at startup. `FileServer` will discover new files which match
@@ -73,7 +75,7 @@ where
     E: FileSourceInternalEvents,
 {
     pub fn run<C, S>(
-        self,
+        mut self,
         mut chans: C,
         shutdown: S,
         mut checkpointer: Checkpointer,
@@ -172,10 +174,20 @@ where
         // we do not re-scan for major file changes (new files, moves, deletes),
         // or write new checkpoints, on every iteration.
         let mut next_glob_time = time::Instant::now();
+        let mut rediscover = false;
         loop {
+            // When notified of changes, only glob after files were created,
+            // renamed or removed.
+            let changes = self.paths_provider.changes();
+            if let Some(changes) = &changes {
+                rediscover |= changes.rediscover;
+            }
+            let should_glob = changes.is_none() || rediscover;
+
             // Glob find files to follow, but not too often.
             let now_time = time::Instant::now();
-            if next_glob_time <= now_time {
+            if should_glob && next_glob_time <= now_time {
+                rediscover = false;
                 // Schedule the next glob time.
                 next_glob_time = now_time.checked_add(self.glob_minimum_cooldown).unwrap();
 
@@ -249,11 +261,35 @@ where
                 stats.record("discovery", start.elapsed());
             }
 
+            if let Some(changes) = &changes {
+                let mut unwatched = changes.modified.iter().collect::<HashSet<_>>();
+                for watcher in fp_map.values_mut() {
+                    // Files no longer found are read to their end before
+                    // being unwatched.
+                    if unwatched.remove(&watcher.path) || changes.rescan || !watcher.file_findable()
+                    {
+                        watcher.set_modified();
+                    }
+                }
+                // Files written to before they could be fingerprinted have to
+                // be found again.
+                rediscover |= !unwatched.is_empty();
+            }
+
             // Collect lines by polling files.
             let mut global_bytes_read: usize = 0;
             let mut maxed_out_reading_single_file = false;
             for (&file_id, watcher) in &mut fp_map {
-                if !watcher.should_read() {
+                let should_read = match changes {
+                    // Idle files are still polled when they may have to be
+                    // removed.
+                    Some(_) => {
+                        watcher.is_modified()
+                            || (self.remove_after.is_some() && watcher.should_read())
+                    }
+                    None => watcher.should_read(),
+                };
+                if !should_read {
                     continue;
                 }
 
@@ -31,6 +31,8 @@ pub struct FileWatcher {
     devno: u64,
     inode: u64,
     is_dead: bool,
+    /// Whether the file may have data left to read, when notified of changes.
+    modified: bool,
     last_read_attempt: Instant,
     last_read_success: Instant,
     max_line_bytes: usize,
@@ -132,6 +134,7 @@ impl FileWatcher {
             devno,
             inode: ino,
             is_dead: false,
+            modified: true,
             last_read_attempt: ts,
             last_read_success: ts,
             max_line_bytes,
@@ -179,6 +182,14 @@ impl FileWatcher {
         self.is_dead
     }
 
+    pub fn set_modified(&mut self) {
+        self.modified = true;
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
     pub fn get_file_position(&self) -> FilePosition {
         self.file_position
     }
@@ -205,6 +216,7 @@ impl FileWatcher {
                 Ok(Some(self.buf.split().freeze()))
             }
             Ok(None) => {
+                self.modified = false;
                 if !self.file_findable() {
                     self.set_dead();
                     // File has been deleted, so return what we have in the buffer, even though it
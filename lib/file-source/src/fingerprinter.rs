@@ -223,11 +223,12 @@ fn fingerprinter_read_until(
 
 #[cfg(test)]
 mod test {
-    use std::{collections::HashSet, fs, io::Error, path::Path, time::Duration};
+    use std::{collections::HashSet, fs};
 
     use tempfile::tempdir;
 
-    use super::{FileSourceInternalEvents, FingerprintStrategy, Fingerprinter};
+    use super::{FingerprintStrategy, Fingerprinter};
+    use crate::internal_events::tests::NoErrors;
 
     #[test]
     fn test_checksum_fingerprint() {
@@ -458,43 +459,4 @@ mod test {
             .get_fingerprint_or_log_error(target_dir.path(), &mut buf, &mut small_files, &NoErrors)
             .is_none());
     }
-
-    #[derive(Clone)]
-    struct NoErrors;
-
-    impl FileSourceInternalEvents for NoErrors {
-        fn emit_file_added(&self, _: &Path) {}
-
-        fn emit_file_resumed(&self, _: &Path, _: u64) {}
-
-        fn emit_file_watch_error(&self, _: &Path, _: Error) {
-            panic!();
-        }
-
-        fn emit_file_unwatched(&self, _: &Path) {}
-
-        fn emit_file_deleted(&self, _: &Path) {}
-
-        fn emit_file_delete_error(&self, _: &Path, _: Error) {
-            panic!();
-        }
-
-        fn emit_file_fingerprint_read_error(&self, _: &Path, _: Error) {
-            panic!();
-        }
-
-        fn emit_file_checkpointed(&self, _: usize, _: Duration) {}
-
-        fn emit_file_checksum_failed(&self, _: &Path) {
-            panic!();
-        }
-
-        fn emit_file_checkpoint_write_error(&self, _: Error) {
-            panic!();
-        }
-
-        fn emit_files_open(&self, _: usize) {}
-
-        fn emit_path_globbing_failed(&self, _: &Path, _: &Error) {}
-    }
 }
@@ -26,4 +26,52 @@ pub trait FileSourceInternalEvents: Send + Sync + Clone + 'static {
     fn emit_files_open(&self, count: usize);
 
     fn emit_path_globbing_failed(&self, path: &Path, error: &Error);
+
+    fn emit_path_watch_failed(&self, path: &Path, error: Error);
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    pub(crate) struct NoErrors;
+
+    impl FileSourceInternalEvents for NoErrors {
+        fn emit_file_added(&self, _: &Path) {}
+
+        fn emit_file_resumed(&self, _: &Path, _: u64) {}
+
+        fn emit_file_watch_error(&self, _: &Path, _: Error) {
+            panic!();
+        }
+
+        fn emit_file_unwatched(&self, _: &Path) {}
+
+        fn emit_file_deleted(&self, _: &Path) {}
+
+        fn emit_file_delete_error(&self, _: &Path, _: Error) {
+            panic!();
+        }
+
+        fn emit_file_fingerprint_read_error(&self, _: &Path, _: Error) {
+            panic!();
+        }
+
+        fn emit_file_checkpointed(&self, _: usize, _: Duration) {}
+
+        fn emit_file_checksum_failed(&self, _: &Path) {
+            panic!();
+        }
+
+        fn emit_file_checkpoint_write_error(&self, _: Error) {
+            panic!();
+        }
+
+        fn emit_files_open(&self, _: usize) {}
+
+        fn emit_path_globbing_failed(&self, _: &Path, _: &Error) {}
+
+        fn emit_path_watch_failed(&self, _: &Path, _: Error) {}
+    }
 }
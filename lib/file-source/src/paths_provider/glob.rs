@@ -1,6 +1,6 @@
 //! [`Glob`] paths provider.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub use glob::MatchOptions;
 use glob::Pattern;
@@ -46,6 +46,22 @@ impl<E: FileSourceInternalEvents> Glob<E> {
             emitter,
         })
     }
+
+    pub(crate) fn include_patterns(&self) -> &[String] {
+        &self.include_patterns
+    }
+
+    pub(crate) fn glob_match_options(&self) -> MatchOptions {
+        self.glob_match_options
+    }
+
+    /// Whether the path matches one of the exclude patterns.
+    pub(crate) fn is_excluded(&self, path: &Path) -> bool {
+        let path = path.to_str().unwrap();
+        self.exclude_patterns
+            .iter()
+            .any(|exclude_pattern| exclude_pattern.matches(path))
+    }
 }
 
 impl<E: FileSourceInternalEvents> PathsProvider for Glob<E> {
@@ -65,12 +81,7 @@ impl<E: FileSourceInternalEvents> PathsProvider for Glob<E> {
                         .ok()
                     })
             })
-            .filter(|candidate_path: &PathBuf| -> bool {
-                !self.exclude_patterns.iter().any(|exclude_pattern| {
-                    let candidate_path_str = candidate_path.to_str().unwrap();
-                    exclude_pattern.matches(candidate_path_str)
-                })
-            })
+            .filter(|candidate_path: &PathBuf| -> bool { !self.is_excluded(candidate_path) })
             .collect()
     }
 }
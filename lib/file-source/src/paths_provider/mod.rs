@@ -3,9 +3,10 @@
 
 #![deny(missing_docs)]
 
-use std::path::PathBuf;
+use std::{collections::HashSet, path::PathBuf};
 
 pub mod glob;
+pub mod notify;
 
 /// Represents the ability to enumerate paths.
 ///
@@ -30,4 +31,48 @@ pub trait PathsProvider {
 
     /// Provides a set of paths.
     fn paths(&self) -> Self::IntoIter;
+
+    /// Provides the changes to the paths since the last call, for providers
+    /// notified of them.
+    ///
+    /// Returns `None` when the provider isn't notified of changes, in which
+    /// case the paths are listed periodically and all the files are polled.
+    fn changes(&mut self) -> Option<Changes> {
+        None
+    }
+}
+
+impl<P: PathsProvider + ?Sized> PathsProvider for Box<P> {
+    type IntoIter = P::IntoIter;
+
+    fn paths(&self) -> Self::IntoIter {
+        (**self).paths()
+    }
+
+    fn changes(&mut self) -> Option<Changes> {
+        (**self).changes()
+    }
+}
+
+/// The changes to the paths a [`PathsProvider`] was notified of.
+#[derive(Debug, Default, PartialEq)]
+pub struct Changes {
+    /// Paths were created, renamed or removed, so they have to be listed again.
+    pub rediscover: bool,
+    /// Changes may have been missed, so the paths have to be listed again and
+    /// all the files read.
+    pub rescan: bool,
+    /// The paths of the files written to.
+    pub modified: HashSet<PathBuf>,
+}
+
+impl Changes {
+    /// Changes requiring a full rescan.
+    pub fn rescan() -> Self {
+        Self {
+            rediscover: true,
+            rescan: true,
+            modified: HashSet::new(),
+        }
+    }
 }
@@ -0,0 +1,275 @@
+//! [`Notify`] paths provider.
+
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+    sync::mpsc::{channel, Receiver, TryRecvError},
+    time::{Duration, Instant},
+};
+
+use ::notify::{raw_watcher, Op, RawEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use glob::Pattern;
+use tracing::{debug, info};
+
+use super::{glob::Glob, Changes, PathsProvider};
+use crate::FileSourceInternalEvents;
+
+/// How long to wait before trying to watch the directories again, when they
+/// couldn't be watched.
+const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A paths provider notified of the changes to the file system, using
+/// `inotify` on Linux and the native notifications on other platforms.
+///
+/// Provides the same paths as the [`Glob`] it wraps, and the changes to them
+/// as it's notified of them, so that the paths only have to be listed again
+/// when files are created, renamed or removed, and only the files written to
+/// have to be read. When the directories can't be watched, it falls back to
+/// the periodic listing and polling of the [`Glob`] provider until they can.
+pub struct Notify<E: FileSourceInternalEvents> {
+    glob: Glob<E>,
+    include_patterns: Vec<Pattern>,
+    directories: Vec<(PathBuf, RecursiveMode)>,
+    watcher: Option<(RecommendedWatcher, Receiver<RawEvent>)>,
+    next_attempt: Instant,
+    emitter: E,
+}
+
+impl<E: FileSourceInternalEvents> Notify<E> {
+    /// Create a new [`Notify`], watching the directories the include patterns
+    /// of the [`Glob`] match files in.
+    pub fn new(glob: Glob<E>, emitter: E) -> Self {
+        let include_patterns = glob
+            .include_patterns()
+            .iter()
+            .filter_map(|pattern| Pattern::new(pattern).ok())
+            .collect();
+        let directories = glob
+            .include_patterns()
+            .iter()
+            .map(|pattern| watched_directory(pattern))
+            .collect();
+
+        let mut notify = Self {
+            glob,
+            include_patterns,
+            directories,
+            watcher: None,
+            next_attempt: Instant::now(),
+            emitter,
+        };
+        notify.watch();
+        notify
+    }
+
+    fn watch(&mut self) {
+        let (sender, receiver) = channel();
+        let mut watcher = match raw_watcher(sender) {
+            Ok(watcher) => watcher,
+            Err(error) => {
+                let directory = self
+                    .directories
+                    .first()
+                    .map(|(directory, _)| directory.clone())
+                    .unwrap_or_default();
+                return self.watch_failed(&directory, error);
+            }
+        };
+        for (directory, mode) in &self.directories {
+            if let Err(error) = watcher.watch(directory, *mode) {
+                let directory = directory.clone();
+                return self.watch_failed(&directory, error);
+            }
+        }
+
+        info!(message = "Watching directories for changes.", directories = ?self.directories.iter().map(|(directory, _)| directory).collect::<Vec<_>>());
+        self.watcher = Some((watcher, receiver));
+    }
+
+    fn watch_failed(&mut self, directory: &Path, error: ::notify::Error) {
+        let error = match error {
+            ::notify::Error::Io(error) => error,
+            error => io::Error::new(io::ErrorKind::Other, error),
+        };
+        self.emitter.emit_path_watch_failed(directory, error);
+        self.watcher = None;
+        self.next_attempt = Instant::now() + RETRY_INTERVAL;
+    }
+
+    /// Whether the path is one the [`Glob`] provides.
+    fn is_provided(&self, path: &Path) -> bool {
+        let options = self.glob.glob_match_options();
+        self.include_patterns
+            .iter()
+            .any(|pattern| pattern.matches_path_with(path, options))
+            && !self.glob.is_excluded(path)
+    }
+
+    fn record(&self, changes: &mut Changes, path: Option<PathBuf>, op: Op) {
+        if op.contains(Op::RESCAN) {
+            changes.rediscover = true;
+            changes.rescan = true;
+            return;
+        }
+
+        let path = match path {
+            Some(path) => path,
+            None => return,
+        };
+        if op.intersects(Op::CREATE | Op::RENAME | Op::REMOVE) {
+            // Files may have been created in a new directory before it was
+            // watched, so new directories are listed too.
+            if self.is_provided(&path) || (op.contains(Op::CREATE) && path.is_dir()) {
+                changes.rediscover = true;
+            }
+        }
+        if op.intersects(Op::WRITE | Op::CLOSE_WRITE) && self.is_provided(&path) {
+            changes.modified.insert(path);
+        }
+    }
+}
+
+impl<E: FileSourceInternalEvents> PathsProvider for Notify<E> {
+    type IntoIter = Vec<PathBuf>;
+
+    fn paths(&self) -> Self::IntoIter {
+        self.glob.paths()
+    }
+
+    fn changes(&mut self) -> Option<Changes> {
+        if self.watcher.is_none() {
+            if Instant::now() < self.next_attempt {
+                return None;
+            }
+            self.watch();
+            // Files may have changed while they weren't watched.
+            return self.watcher.as_ref().map(|_| Changes::rescan());
+        }
+
+        let mut changes = Changes::default();
+        loop {
+            let event = self
+                .watcher
+                .as_ref()
+                .map(|(_, receiver)| receiver.try_recv());
+            match event {
+                Some(Ok(RawEvent {
+                    path, op: Ok(op), ..
+                })) => self.record(&mut changes, path, op),
+                Some(Ok(RawEvent { op: Err(error), .. })) => {
+                    debug!(message = "Error notified, rescanning.", %error);
+                    changes.rediscover = true;
+                    changes.rescan = true;
+                }
+                Some(Err(TryRecvError::Empty)) => return Some(changes),
+                Some(Err(TryRecvError::Disconnected)) | None => {
+                    self.watcher = None;
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// The directory to watch for the files matching a pattern: its longest
+/// prefix without glob metacharacters, watched recursively if the pattern
+/// matches files in its subdirectories. The directory of a plain path is
+/// watched to see the file being created.
+fn watched_directory(pattern: &str) -> (PathBuf, RecursiveMode) {
+    let path = Path::new(pattern);
+    let components = path.components().collect::<Vec<_>>();
+    let glob_index = components.iter().position(|component| match component {
+        Component::Normal(name) => name.to_str().map_or(false, |name| {
+            name.contains(|c| matches!(c, '*' | '?' | '['))
+        }),
+        _ => false,
+    });
+
+    match glob_index {
+        None => (
+            path.parent().unwrap_or(path).to_path_buf(),
+            RecursiveMode::NonRecursive,
+        ),
+        Some(index) => {
+            let directory = components[..index].iter().collect::<PathBuf>();
+            let mode = if index + 1 < components.len() {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            };
+            (directory, mode)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Write, thread};
+
+    use glob::MatchOptions;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::internal_events::tests::NoErrors;
+
+    fn changes(notify: &mut Notify<NoErrors>) -> Changes {
+        thread::sleep(Duration::from_millis(100));
+        notify.changes().expect("directory is watched")
+    }
+
+    #[test]
+    fn watches_directories_of_patterns() {
+        assert_eq!(
+            watched_directory("/var/log/*.log"),
+            ("/var/log".into(), RecursiveMode::NonRecursive)
+        );
+        assert_eq!(
+            watched_directory("/var/log/**/*.log"),
+            ("/var/log".into(), RecursiveMode::Recursive)
+        );
+        assert_eq!(
+            watched_directory("/var/log/pods/*/app/0.log"),
+            ("/var/log/pods".into(), RecursiveMode::Recursive)
+        );
+        assert_eq!(
+            watched_directory("/var/log/syslog"),
+            ("/var/log".into(), RecursiveMode::NonRecursive)
+        );
+    }
+
+    #[test]
+    fn notifies_changes() {
+        let dir = tempdir().unwrap();
+        let include = [dir.path().join("*.log")];
+        let exclude = [dir.path().join("excluded.log")];
+        let glob = Glob::new(&include, &exclude, MatchOptions::default(), NoErrors).unwrap();
+        let mut notify = Notify::new(glob, NoErrors);
+        assert_eq!(changes(&mut notify), Changes::default());
+
+        let path = dir.path().join("app.log");
+        let mut file = fs::File::create(&path).unwrap();
+        fs::File::create(dir.path().join("excluded.log")).unwrap();
+        let created = changes(&mut notify);
+        assert!(created.rediscover);
+        assert!(!created.rescan);
+
+        writeln!(file, "line").unwrap();
+        fs::write(dir.path().join("excluded.log"), "line\n").unwrap();
+        fs::write(dir.path().join("other.txt"), "line\n").unwrap();
+        let written = changes(&mut notify);
+        assert!(!written.rediscover);
+        assert_eq!(written.modified, [path.clone()].into_iter().collect());
+
+        fs::remove_file(&path).unwrap();
+        assert!(changes(&mut notify).rediscover);
+    }
+
+    #[test]
+    fn falls_back_to_polling() {
+        let dir = tempdir().unwrap();
+        let include = [dir.path().join("missing/*.log")];
+        let glob = Glob::new(&include, &[], MatchOptions::default(), NoErrors).unwrap();
+        let mut notify = Notify::new(glob, NoErrors);
+        assert_eq!(notify.changes(), None);
+    }
+}
@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+/// Error type for flow packet parsing
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum FlowParserError {
+    #[error("Unexpected end of packet, {} more bytes needed", needed)]
+    UnexpectedEnd { needed: usize },
+    #[error("Unsupported version {}", version)]
+    UnsupportedVersion { version: u32 },
+    #[error("Invalid length {} of {}", length, what)]
+    InvalidLength { what: &'static str, length: usize },
+    #[error("Invalid {} {}", what, value)]
+    InvalidValue { what: &'static str, value: u32 },
+    #[error("Invalid template {}: {}", template_id, reason)]
+    InvalidTemplate {
+        template_id: u16,
+        reason: &'static str,
+    },
+}
+
+/// Result alias for parsing
+pub type FlowParserResult<T> = Result<T, FlowParserError>;
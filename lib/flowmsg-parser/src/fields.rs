@@ -0,0 +1,350 @@
+//! The information elements of IPFIX, which NetFlow v9 shares for most of its
+//! field types, and the decoding of their values.
+
+use std::{
+    borrow::Cow,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+/// The private enterprise number of the reverse information elements of
+/// biflows, as described by RFC 5103.
+pub const REVERSE_PEN: u32 = 29305;
+
+/// The `paddingOctets` information element, which carries no information.
+pub const PADDING_OCTETS: u16 = 210;
+
+/// The seconds between the NTP epoch and the Unix epoch.
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// The abstract data types of the information elements, as described by
+/// RFC 7011 section 6.1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Unsigned,
+    Signed,
+    Float,
+    Boolean,
+    MacAddress,
+    Ipv4Address,
+    Ipv6Address,
+    String,
+    OctetArray,
+    DateTimeSeconds,
+    DateTimeMilliseconds,
+    DateTimeMicroseconds,
+    DateTimeNanoseconds,
+}
+
+/// A decoded field value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    Boolean(bool),
+    MacAddress([u8; 6]),
+    Ipv4Address(Ipv4Addr),
+    Ipv6Address(Ipv6Addr),
+    String(String),
+    Bytes(Vec<u8>),
+    /// A time since the Unix epoch.
+    DateTime {
+        seconds: u64,
+        nanos: u32,
+    },
+}
+
+/// A named field of a flow record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: Cow<'static, str>,
+    pub value: FieldValue,
+}
+
+impl Field {
+    pub fn new(name: impl Into<Cow<'static, str>>, value: FieldValue) -> Self {
+        Self {
+            name: name.into(),
+            value,
+        }
+    }
+}
+
+/// Looks up the name and type of an IANA information element.
+pub fn information_element(id: u16) -> Option<(&'static str, FieldType)> {
+    use FieldType::*;
+
+    Some(match id {
+        1 => ("octetDeltaCount", Unsigned),
+        2 => ("packetDeltaCount", Unsigned),
+        3 => ("deltaFlowCount", Unsigned),
+        4 => ("protocolIdentifier", Unsigned),
+        5 => ("ipClassOfService", Unsigned),
+        6 => ("tcpControlBits", Unsigned),
+        7 => ("sourceTransportPort", Unsigned),
+        8 => ("sourceIPv4Address", Ipv4Address),
+        9 => ("sourceIPv4PrefixLength", Unsigned),
+        10 => ("ingressInterface", Unsigned),
+        11 => ("destinationTransportPort", Unsigned),
+        12 => ("destinationIPv4Address", Ipv4Address),
+        13 => ("destinationIPv4PrefixLength", Unsigned),
+        14 => ("egressInterface", Unsigned),
+        15 => ("ipNextHopIPv4Address", Ipv4Address),
+        16 => ("bgpSourceAsNumber", Unsigned),
+        17 => ("bgpDestinationAsNumber", Unsigned),
+        18 => ("bgpNextHopIPv4Address", Ipv4Address),
+        19 => ("postMCastPacketDeltaCount", Unsigned),
+        20 => ("postMCastOctetDeltaCount", Unsigned),
+        21 => ("flowEndSysUpTime", Unsigned),
+        22 => ("flowStartSysUpTime", Unsigned),
+        23 => ("postOctetDeltaCount", Unsigned),
+        24 => ("postPacketDeltaCount", Unsigned),
+        25 => ("minimumIpTotalLength", Unsigned),
+        26 => ("maximumIpTotalLength", Unsigned),
+        27 => ("sourceIPv6Address", Ipv6Address),
+        28 => ("destinationIPv6Address", Ipv6Address),
+        29 => ("sourceIPv6PrefixLength", Unsigned),
+        30 => ("destinationIPv6PrefixLength", Unsigned),
+        31 => ("flowLabelIPv6", Unsigned),
+        32 => ("icmpTypeCodeIPv4", Unsigned),
+        33 => ("igmpType", Unsigned),
+        34 => ("samplingInterval", Unsigned),
+        35 => ("samplingAlgorithm", Unsigned),
+        36 => ("flowActiveTimeout", Unsigned),
+        37 => ("flowIdleTimeout", Unsigned),
+        38 => ("engineType", Unsigned),
+        39 => ("engineId", Unsigned),
+        40 => ("exportedOctetTotalCount", Unsigned),
+        41 => ("exportedMessageTotalCount", Unsigned),
+        42 => ("exportedFlowRecordTotalCount", Unsigned),
+        44 => ("sourceIPv4Prefix", Ipv4Address),
+        45 => ("destinationIPv4Prefix", Ipv4Address),
+        46 => ("mplsTopLabelType", Unsigned),
+        47 => ("mplsTopLabelIPv4Address", Ipv4Address),
+        48 => ("samplerId", Unsigned),
+        49 => ("samplerMode", Unsigned),
+        50 => ("samplerRandomInterval", Unsigned),
+        52 => ("minimumTTL", Unsigned),
+        53 => ("maximumTTL", Unsigned),
+        54 => ("fragmentIdentification", Unsigned),
+        55 => ("postIpClassOfService", Unsigned),
+        56 => ("sourceMacAddress", MacAddress),
+        57 => ("postDestinationMacAddress", MacAddress),
+        58 => ("vlanId", Unsigned),
+        59 => ("postVlanId", Unsigned),
+        60 => ("ipVersion", Unsigned),
+        61 => ("flowDirection", Unsigned),
+        62 => ("ipNextHopIPv6Address", Ipv6Address),
+        63 => ("bgpNextHopIPv6Address", Ipv6Address),
+        64 => ("ipv6ExtensionHeaders", Unsigned),
+        70 => ("mplsTopLabelStackSection", OctetArray),
+        80 => ("destinationMacAddress", MacAddress),
+        81 => ("postSourceMacAddress", MacAddress),
+        82 => ("interfaceName", String),
+        83 => ("interfaceDescription", String),
+        85 => ("octetTotalCount", Unsigned),
+        86 => ("packetTotalCount", Unsigned),
+        88 => ("fragmentOffset", Unsigned),
+        89 => ("forwardingStatus", Unsigned),
+        90 => ("mplsVpnRouteDistinguisher", OctetArray),
+        94 => ("applicationDescription", String),
+        95 => ("applicationId", OctetArray),
+        96 => ("applicationName", String),
+        98 => ("postIpDiffServCodePoint", Unsigned),
+        99 => ("multicastReplicationFactor", Unsigned),
+        128 => ("bgpNextAdjacentAsNumber", Unsigned),
+        129 => ("bgpPrevAdjacentAsNumber", Unsigned),
+        130 => ("exporterIPv4Address", Ipv4Address),
+        131 => ("exporterIPv6Address", Ipv6Address),
+        136 => ("flowEndReason", Unsigned),
+        138 => ("observationPointId", Unsigned),
+        139 => ("icmpTypeCodeIPv6", Unsigned),
+        144 => ("exportingProcessId", Unsigned),
+        148 => ("flowId", Unsigned),
+        149 => ("observationDomainId", Unsigned),
+        150 => ("flowStartSeconds", DateTimeSeconds),
+        151 => ("flowEndSeconds", DateTimeSeconds),
+        152 => ("flowStartMilliseconds", DateTimeMilliseconds),
+        153 => ("flowEndMilliseconds", DateTimeMilliseconds),
+        154 => ("flowStartMicroseconds", DateTimeMicroseconds),
+        155 => ("flowEndMicroseconds", DateTimeMicroseconds),
+        156 => ("flowStartNanoseconds", DateTimeNanoseconds),
+        157 => ("flowEndNanoseconds", DateTimeNanoseconds),
+        160 => ("systemInitTimeMilliseconds", DateTimeMilliseconds),
+        161 => ("flowDurationMilliseconds", Unsigned),
+        162 => ("flowDurationMicroseconds", Unsigned),
+        176 => ("icmpTypeIPv4", Unsigned),
+        177 => ("icmpCodeIPv4", Unsigned),
+        178 => ("icmpTypeIPv6", Unsigned),
+        179 => ("icmpCodeIPv6", Unsigned),
+        180 => ("udpSourcePort", Unsigned),
+        181 => ("udpDestinationPort", Unsigned),
+        182 => ("tcpSourcePort", Unsigned),
+        183 => ("tcpDestinationPort", Unsigned),
+        192 => ("ipTTL", Unsigned),
+        195 => ("ipDiffServCodePoint", Unsigned),
+        210 => ("paddingOctets", OctetArray),
+        225 => ("postNATSourceIPv4Address", Ipv4Address),
+        226 => ("postNATDestinationIPv4Address", Ipv4Address),
+        227 => ("postNAPTSourceTransportPort", Unsigned),
+        228 => ("postNAPTDestinationTransportPort", Unsigned),
+        230 => ("natEvent", Unsigned),
+        233 => ("firewallEvent", Unsigned),
+        234 => ("ingressVRFID", Unsigned),
+        235 => ("egressVRFID", Unsigned),
+        239 => ("biflowDirection", Unsigned),
+        243 => ("dot1qVlanId", Unsigned),
+        244 => ("dot1qPriority", Unsigned),
+        281 => ("postNATSourceIPv6Address", Ipv6Address),
+        282 => ("postNATDestinationIPv6Address", Ipv6Address),
+        323 => ("observationTimeMilliseconds", DateTimeMilliseconds),
+        324 => ("observationTimeMicroseconds", DateTimeMicroseconds),
+        325 => ("observationTimeNanoseconds", DateTimeNanoseconds),
+        _ => return None,
+    })
+}
+
+/// Looks up the name and type of a field, falling back to a name made of its
+/// identifiers and to an octet array for unknown fields.
+pub fn field_info(id: u16, enterprise: Option<u32>) -> (Cow<'static, str>, FieldType) {
+    match enterprise {
+        None => match information_element(id) {
+            Some((name, field_type)) => (name.into(), field_type),
+            None => (format!("field_{}", id).into(), FieldType::OctetArray),
+        },
+        Some(REVERSE_PEN) => match information_element(id) {
+            Some((name, field_type)) => {
+                let mut reverse = String::with_capacity(name.len() + 7);
+                reverse.push_str("reverse");
+                let mut chars = name.chars();
+                if let Some(first) = chars.next() {
+                    reverse.push(first.to_ascii_uppercase());
+                }
+                reverse.extend(chars);
+                (reverse.into(), field_type)
+            }
+            None => (
+                format!("reverse_field_{}", id).into(),
+                FieldType::OctetArray,
+            ),
+        },
+        Some(enterprise) => (
+            format!("enterprise_{}_field_{}", enterprise, id).into(),
+            FieldType::OctetArray,
+        ),
+    }
+}
+
+/// Decodes the value of a field, accepting the reduced size encodings of the
+/// numeric types and falling back to the raw bytes for unexpected lengths.
+pub fn decode_value(field_type: FieldType, bytes: &[u8]) -> FieldValue {
+    match (field_type, bytes.len()) {
+        (FieldType::Unsigned, 1..=8) => FieldValue::Unsigned(unsigned(bytes)),
+        (FieldType::Signed, 1..=8) => {
+            let shift = 64 - 8 * bytes.len() as u32;
+            FieldValue::Signed(((unsigned(bytes) << shift) as i64) >> shift)
+        }
+        (FieldType::Float, 4) => FieldValue::Float(f32::from_bits(unsigned(bytes) as u32) as f64),
+        (FieldType::Float, 8) => FieldValue::Float(f64::from_bits(unsigned(bytes))),
+        (FieldType::Boolean, 1) => FieldValue::Boolean(bytes[0] == 1),
+        (FieldType::MacAddress, 6) => {
+            let mut mac = [0; 6];
+            mac.copy_from_slice(bytes);
+            FieldValue::MacAddress(mac)
+        }
+        (FieldType::Ipv4Address, 4) => {
+            FieldValue::Ipv4Address(Ipv4Addr::from(unsigned(bytes) as u32))
+        }
+        (FieldType::Ipv6Address, 16) => {
+            let mut address = [0; 16];
+            address.copy_from_slice(bytes);
+            FieldValue::Ipv6Address(Ipv6Addr::from(address))
+        }
+        (FieldType::String, _) => FieldValue::String(
+            String::from_utf8_lossy(bytes)
+                .trim_end_matches('\0')
+                .to_string(),
+        ),
+        (FieldType::DateTimeSeconds, 4) => FieldValue::DateTime {
+            seconds: unsigned(bytes),
+            nanos: 0,
+        },
+        (FieldType::DateTimeMilliseconds, 8) => {
+            let millis = unsigned(bytes);
+            FieldValue::DateTime {
+                seconds: millis / 1000,
+                nanos: (millis % 1000) as u32 * 1_000_000,
+            }
+        }
+        (FieldType::DateTimeMicroseconds | FieldType::DateTimeNanoseconds, 8) => {
+            let seconds = unsigned(&bytes[..4]).saturating_sub(NTP_UNIX_OFFSET);
+            let mut fraction = unsigned(&bytes[4..]);
+            if field_type == FieldType::DateTimeMicroseconds {
+                // The 11 lower bits are ignored at the microsecond precision.
+                fraction &= !0x7ff;
+            }
+            FieldValue::DateTime {
+                seconds,
+                nanos: ((fraction * 1_000_000_000) >> 32) as u32,
+            }
+        }
+        _ => FieldValue::Bytes(bytes.to_vec()),
+    }
+}
+
+fn unsigned(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |value, byte| (value << 8) | u64::from(*byte))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_reduced_size_numbers() {
+        assert_eq!(
+            decode_value(FieldType::Unsigned, &[0x01, 0x00]),
+            FieldValue::Unsigned(256)
+        );
+        assert_eq!(
+            decode_value(FieldType::Signed, &[0xff, 0xfe]),
+            FieldValue::Signed(-2)
+        );
+        assert_eq!(
+            decode_value(FieldType::Unsigned, &[0; 9]),
+            FieldValue::Bytes(vec![0; 9])
+        );
+    }
+
+    #[test]
+    fn decodes_date_times() {
+        assert_eq!(
+            decode_value(
+                FieldType::DateTimeMilliseconds,
+                &1_500_000_000_250u64.to_be_bytes()
+            ),
+            FieldValue::DateTime {
+                seconds: 1_500_000_000,
+                nanos: 250_000_000
+            }
+        );
+        let ntp = ((1_500_000_000 + NTP_UNIX_OFFSET) << 32) | 0x8000_0000;
+        assert_eq!(
+            decode_value(FieldType::DateTimeNanoseconds, &ntp.to_be_bytes()),
+            FieldValue::DateTime {
+                seconds: 1_500_000_000,
+                nanos: 500_000_000
+            }
+        );
+    }
+
+    #[test]
+    fn names_fields() {
+        assert_eq!(field_info(8, None).0, "sourceIPv4Address");
+        assert_eq!(field_info(1, Some(REVERSE_PEN)).0, "reverseOctetDeltaCount");
+        assert_eq!(field_info(1000, None).0, "field_1000");
+        assert_eq!(field_info(12, Some(9)).0, "enterprise_9_field_12");
+    }
+}
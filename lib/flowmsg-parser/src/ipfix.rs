@@ -0,0 +1,220 @@
+//! IPFIX, as described by RFC 7011.
+
+use std::net::SocketAddr;
+
+use crate::{
+    reader::Reader,
+    template::{
+        decode_data_set, Template, TemplateCache, TemplateField, TemplateKey, TemplatePacket,
+        MIN_DATA_SET_ID,
+    },
+    FlowParserError, FlowParserResult,
+};
+
+const VERSION: u16 = 10;
+const HEADER_LENGTH: usize = 16;
+const TEMPLATE_SET_ID: u16 = 2;
+const OPTIONS_TEMPLATE_SET_ID: u16 = 3;
+const ENTERPRISE_BIT: u16 = 0x8000;
+
+/// The scope fields of IPFIX are regular information elements.
+const fn scope_name(_id: u16) -> Option<&'static str> {
+    None
+}
+
+pub fn parse(
+    templates: &mut TemplateCache,
+    exporter: SocketAddr,
+    data: &[u8],
+) -> FlowParserResult<TemplatePacket> {
+    let mut reader = Reader::new(data);
+    let _version = reader.u16()?;
+    let length = reader.u16()? as usize;
+    if length < HEADER_LENGTH || length > data.len() {
+        return Err(FlowParserError::InvalidLength {
+            what: "IPFIX message",
+            length,
+        });
+    }
+    let export_time = reader.u32()?;
+    let sequence_number = reader.u32()?;
+    let domain_id = reader.u32()?;
+    let mut reader = reader.sub(length - HEADER_LENGTH)?;
+
+    let mut packet = TemplatePacket {
+        version: VERSION,
+        export_time,
+        sys_uptime: None,
+        sequence_number,
+        domain_id,
+        records: Vec::new(),
+        missing_templates: Vec::new(),
+    };
+    let key = |template_id| TemplateKey {
+        exporter,
+        version: VERSION,
+        domain_id,
+        template_id,
+    };
+
+    while !reader.is_empty() {
+        let set_id = reader.u16()?;
+        let length = reader.u16()? as usize;
+        if length < 4 {
+            return Err(FlowParserError::InvalidLength {
+                what: "IPFIX set",
+                length,
+            });
+        }
+        let mut set = reader.sub(length - 4)?;
+        match set_id {
+            TEMPLATE_SET_ID | OPTIONS_TEMPLATE_SET_ID => {
+                let options = set_id == OPTIONS_TEMPLATE_SET_ID;
+                while set.remaining() >= 4 {
+                    let id = set.u16()?;
+                    let count = set.u16()?;
+                    if count == 0 {
+                        // Withdraws the template, or all of them with the ID
+                        // of the set.
+                        if id == set_id {
+                            templates.remove_all(exporter, domain_id, options);
+                        } else {
+                            templates.remove(&key(id));
+                        }
+                        continue;
+                    }
+                    let scope_count = if options { set.u16()? } else { 0 };
+                    let fields = (0..count)
+                        .map(|index| parse_field(&mut set, index < scope_count))
+                        .collect::<FlowParserResult<_>>()?;
+                    templates.insert(key(id), Template::new(id, fields, options)?);
+                }
+            }
+            id if id < MIN_DATA_SET_ID => {}
+            id => decode_data_set(&mut packet, templates, key(id), set, scope_name)?,
+        }
+    }
+
+    Ok(packet)
+}
+
+fn parse_field(reader: &mut Reader<'_>, scope: bool) -> FlowParserResult<TemplateField> {
+    let id = reader.u16()?;
+    let length = reader.u16()?;
+    let enterprise = if id & ENTERPRISE_BIT != 0 {
+        Some(reader.u32()?)
+    } else {
+        None
+    };
+    Ok(TemplateField {
+        id: id & !ENTERPRISE_BIT,
+        enterprise,
+        length,
+        scope,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv6Addr;
+
+    use super::*;
+    use crate::fields::{FieldValue, REVERSE_PEN};
+
+    fn message(sets: &[u8]) -> Vec<u8> {
+        let mut data = vec![0, 10];
+        data.extend_from_slice(&((HEADER_LENGTH + sets.len()) as u16).to_be_bytes());
+        data.extend_from_slice(&1_600_000_000u32.to_be_bytes());
+        data.extend_from_slice(&3u32.to_be_bytes());
+        data.extend_from_slice(&42u32.to_be_bytes());
+        data.extend_from_slice(sets);
+        data
+    }
+
+    fn exporter() -> SocketAddr {
+        "[2001:db8::1]:4739".parse().unwrap()
+    }
+
+    #[test]
+    fn decodes_variable_length_and_enterprise_fields() {
+        let mut sets = Vec::new();
+        // sourceIPv6Address, applicationName with a variable length and the
+        // reverse octetDeltaCount of biflows.
+        sets.extend_from_slice(&[0, 2, 0, 24, 1, 0, 0, 3]);
+        sets.extend_from_slice(&[0, 27, 0, 16, 0, 96, 0xff, 0xff]);
+        sets.extend_from_slice(&[0x80, 1, 0, 4]);
+        sets.extend_from_slice(&REVERSE_PEN.to_be_bytes());
+        sets.extend_from_slice(&[1, 0, 0, 30]);
+        sets.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        sets.extend_from_slice(&[5, b'h', b't', b't', b'p', b's']);
+        sets.extend_from_slice(&1234u32.to_be_bytes());
+
+        let mut templates = TemplateCache::default();
+        let packet = parse(&mut templates, exporter(), &message(&sets)).unwrap();
+        assert_eq!(packet.domain_id, 42);
+        assert_eq!(packet.records.len(), 1);
+        let record = &packet.records[0];
+        assert_eq!(
+            record.get("sourceIPv6Address"),
+            Some(&FieldValue::Ipv6Address(Ipv6Addr::LOCALHOST))
+        );
+        assert_eq!(
+            record.get("applicationName"),
+            Some(&FieldValue::String("https".to_string()))
+        );
+        assert_eq!(
+            record.get("reverseOctetDeltaCount"),
+            Some(&FieldValue::Unsigned(1234))
+        );
+    }
+
+    #[test]
+    fn skips_padding_of_variable_length_records() {
+        // applicationName with a variable length, then two records padded to
+        // a 4 bytes boundary.
+        let mut sets = vec![0, 2, 0, 12, 1, 0, 0, 1, 0, 96, 0xff, 0xff];
+        sets.extend_from_slice(&[1, 0, 0, 16, 5, b'h', b't', b't', b'p', b's']);
+        sets.extend_from_slice(&[3, b's', b's', b'h', 0, 0]);
+
+        let mut templates = TemplateCache::default();
+        let packet = parse(&mut templates, exporter(), &message(&sets)).unwrap();
+        assert_eq!(packet.records.len(), 2);
+        assert_eq!(
+            packet.records[1].get("applicationName"),
+            Some(&FieldValue::String("ssh".to_string()))
+        );
+
+        // The bytes too short for the record they would start are padding too.
+        let sets = [1, 0, 0, 12, 5, b'h', b't', b't', b'p', b's', 4, 0];
+        let packet = parse(&mut templates, exporter(), &message(&sets)).unwrap();
+        assert_eq!(packet.records.len(), 1);
+    }
+
+    #[test]
+    fn withdraws_templates() {
+        let mut templates = TemplateCache::default();
+        let sets = [0, 2, 0, 12, 1, 0, 0, 1, 0, 8, 0, 4];
+        parse(&mut templates, exporter(), &message(&sets)).unwrap();
+        assert_eq!(templates.len(), 1);
+
+        let sets = [0, 2, 0, 8, 1, 0, 0, 0];
+        parse(&mut templates, exporter(), &message(&sets)).unwrap();
+        assert!(templates.is_empty());
+
+        let sets = [0, 2, 0, 12, 1, 1, 0, 1, 0, 8, 0, 4];
+        parse(&mut templates, exporter(), &message(&sets)).unwrap();
+        let sets = [0, 2, 0, 8, 0, 2, 0, 0];
+        parse(&mut templates, exporter(), &message(&sets)).unwrap();
+        assert!(templates.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_lengths() {
+        let mut data = message(&[]);
+        data[3] = 200;
+        assert!(matches!(
+            parse(&mut TemplateCache::default(), exporter(), &data),
+            Err(FlowParserError::InvalidLength { .. })
+        ));
+    }
+}
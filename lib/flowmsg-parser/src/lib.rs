@@ -0,0 +1,76 @@
+#![warn(
+    missing_debug_implementations,
+    rust_2018_idioms,
+    unreachable_pub,
+    non_snake_case,
+    non_upper_case_globals
+)]
+
+//! Parsers of the flow export protocols: NetFlow v5 and v9, IPFIX and sFlow v5.
+
+pub mod error;
+pub mod fields;
+pub mod ipfix;
+pub mod netflow_v5;
+pub mod netflow_v9;
+mod reader;
+pub mod sflow;
+pub mod template;
+
+use std::{net::SocketAddr, time::Duration};
+
+pub use error::{FlowParserError, FlowParserResult};
+use reader::Reader;
+use template::TemplateCache;
+
+/// A decoded flow export packet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlowMessage {
+    NetflowV5(netflow_v5::NetflowV5Packet),
+    NetflowV9(template::TemplatePacket),
+    Ipfix(template::TemplatePacket),
+    Sflow(sflow::SflowDatagram),
+}
+
+/// A parser of the packets of any supported protocol, caching the templates
+/// of NetFlow v9 and IPFIX per exporter.
+#[derive(Debug, Default)]
+pub struct FlowParser {
+    templates: TemplateCache,
+}
+
+impl FlowParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A parser caching at most `max_templates` templates, which expire when
+    /// they aren't received again within `template_timeout`.
+    pub fn with_template_limits(max_templates: usize, template_timeout: Duration) -> Self {
+        Self {
+            templates: TemplateCache::new(max_templates, template_timeout),
+        }
+    }
+
+    /// The cached templates, which are needed to decode the data records of
+    /// NetFlow v9 and IPFIX.
+    pub fn templates(&self) -> &TemplateCache {
+        &self.templates
+    }
+
+    /// Parses a packet received from an exporter, detecting its protocol from
+    /// its version number.
+    pub fn parse(&mut self, exporter: SocketAddr, data: &[u8]) -> FlowParserResult<FlowMessage> {
+        let mut reader = Reader::new(data);
+        // sFlow starts with a 32 bits version, whose upper half is zero.
+        match reader.u16()? {
+            5 => netflow_v5::parse(data).map(FlowMessage::NetflowV5),
+            9 => netflow_v9::parse(&mut self.templates, exporter, data).map(FlowMessage::NetflowV9),
+            10 => ipfix::parse(&mut self.templates, exporter, data).map(FlowMessage::Ipfix),
+            0 => sflow::parse(data).map(FlowMessage::Sflow),
+            version => Err(FlowParserError::UnsupportedVersion {
+                version: version as u32,
+            }),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! NetFlow v5, whose records have a fixed format.
+
+use crate::{
+    fields::{Field, FieldValue},
+    reader::Reader,
+    template::FlowRecord,
+    FlowParserError, FlowParserResult,
+};
+
+const HEADER_LENGTH: usize = 24;
+const RECORD_LENGTH: usize = 48;
+const MAX_RECORDS: usize = 30;
+
+/// A decoded NetFlow v5 packet, whose records use the names of the matching
+/// IPFIX information elements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetflowV5Packet {
+    /// The time since the exporter was started, in milliseconds.
+    pub sys_uptime: u32,
+    pub unix_secs: u32,
+    pub unix_nsecs: u32,
+    pub flow_sequence: u32,
+    pub engine_type: u8,
+    pub engine_id: u8,
+    pub sampling_mode: u8,
+    pub sampling_interval: u16,
+    pub records: Vec<FlowRecord>,
+}
+
+pub fn parse(data: &[u8]) -> FlowParserResult<NetflowV5Packet> {
+    let mut reader = Reader::new(data);
+    let _version = reader.u16()?;
+    let count = reader.u16()? as usize;
+    if count > MAX_RECORDS || data.len() < HEADER_LENGTH + count * RECORD_LENGTH {
+        return Err(FlowParserError::InvalidLength {
+            what: "NetFlow v5 packet",
+            length: data.len(),
+        });
+    }
+    let sys_uptime = reader.u32()?;
+    let unix_secs = reader.u32()?;
+    let unix_nsecs = reader.u32()?;
+    let flow_sequence = reader.u32()?;
+    let engine_type = reader.u8()?;
+    let engine_id = reader.u8()?;
+    let sampling = reader.u16()?;
+
+    let records = (0..count)
+        .map(|_| parse_record(&mut reader))
+        .collect::<FlowParserResult<_>>()?;
+
+    Ok(NetflowV5Packet {
+        sys_uptime,
+        unix_secs,
+        unix_nsecs,
+        flow_sequence,
+        engine_type,
+        engine_id,
+        sampling_mode: (sampling >> 14) as u8,
+        sampling_interval: sampling & 0x3fff,
+        records,
+    })
+}
+
+fn parse_record(reader: &mut Reader<'_>) -> FlowParserResult<FlowRecord> {
+    use FieldValue::{Ipv4Address, Unsigned};
+
+    let mut fields = vec![
+        Field::new("sourceIPv4Address", Ipv4Address(reader.ipv4()?)),
+        Field::new("destinationIPv4Address", Ipv4Address(reader.ipv4()?)),
+        Field::new("ipNextHopIPv4Address", Ipv4Address(reader.ipv4()?)),
+        Field::new("ingressInterface", Unsigned(reader.u16()?.into())),
+        Field::new("egressInterface", Unsigned(reader.u16()?.into())),
+        Field::new("packetDeltaCount", Unsigned(reader.u32()?.into())),
+        Field::new("octetDeltaCount", Unsigned(reader.u32()?.into())),
+        Field::new("flowStartSysUpTime", Unsigned(reader.u32()?.into())),
+        Field::new("flowEndSysUpTime", Unsigned(reader.u32()?.into())),
+        Field::new("sourceTransportPort", Unsigned(reader.u16()?.into())),
+        Field::new("destinationTransportPort", Unsigned(reader.u16()?.into())),
+    ];
+    reader.skip(1)?;
+    fields.extend([
+        Field::new("tcpControlBits", Unsigned(reader.u8()?.into())),
+        Field::new("protocolIdentifier", Unsigned(reader.u8()?.into())),
+        Field::new("ipClassOfService", Unsigned(reader.u8()?.into())),
+        Field::new("bgpSourceAsNumber", Unsigned(reader.u16()?.into())),
+        Field::new("bgpDestinationAsNumber", Unsigned(reader.u16()?.into())),
+        Field::new("sourceIPv4PrefixLength", Unsigned(reader.u8()?.into())),
+        Field::new("destinationIPv4PrefixLength", Unsigned(reader.u8()?.into())),
+    ]);
+    reader.skip(2)?;
+
+    Ok(FlowRecord {
+        template_id: 0,
+        options: false,
+        fields,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    fn record() -> Vec<u8> {
+        let mut record = Vec::new();
+        record.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2, 0, 0, 0, 0]);
+        record.extend_from_slice(&[0, 1, 0, 2]);
+        record.extend_from_slice(&3u32.to_be_bytes());
+        record.extend_from_slice(&180u32.to_be_bytes());
+        record.extend_from_slice(&1000u32.to_be_bytes());
+        record.extend_from_slice(&2000u32.to_be_bytes());
+        record.extend_from_slice(&[0xc3, 0x50, 0x00, 0x50]);
+        record.extend_from_slice(&[0, 0x1b, 6, 0]);
+        record.extend_from_slice(&[0, 0, 0, 0, 24, 24, 0, 0]);
+        record
+    }
+
+    #[test]
+    fn parses_packets() {
+        let mut data = vec![0, 5, 0, 1];
+        data.extend_from_slice(&5000u32.to_be_bytes());
+        data.extend_from_slice(&1_600_000_000u32.to_be_bytes());
+        data.extend_from_slice(&0u32.to_be_bytes());
+        data.extend_from_slice(&42u32.to_be_bytes());
+        data.extend_from_slice(&[0, 1, 0x40, 0x64]);
+        data.extend(record());
+
+        let packet = parse(&data).unwrap();
+        assert_eq!(packet.flow_sequence, 42);
+        assert_eq!(packet.sampling_mode, 1);
+        assert_eq!(packet.sampling_interval, 100);
+        assert_eq!(packet.records.len(), 1);
+
+        let record = &packet.records[0];
+        assert_eq!(
+            record.get("sourceIPv4Address"),
+            Some(&FieldValue::Ipv4Address(Ipv4Addr::new(10, 0, 0, 1)))
+        );
+        assert_eq!(
+            record.get("octetDeltaCount"),
+            Some(&FieldValue::Unsigned(180))
+        );
+        assert_eq!(
+            record.get("sourceTransportPort"),
+            Some(&FieldValue::Unsigned(50000))
+        );
+        assert_eq!(
+            record.get("tcpControlBits"),
+            Some(&FieldValue::Unsigned(0x1b))
+        );
+        assert_eq!(
+            record.get("protocolIdentifier"),
+            Some(&FieldValue::Unsigned(6))
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_packets() {
+        let mut data = vec![0, 5, 0, 2];
+        data.extend_from_slice(&[0; 20]);
+        data.extend(record());
+        assert!(matches!(
+            parse(&data),
+            Err(FlowParserError::InvalidLength { .. })
+        ));
+    }
+}
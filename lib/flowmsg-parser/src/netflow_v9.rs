@@ -0,0 +1,224 @@
+//! NetFlow v9, as described by RFC 3954.
+
+use std::net::SocketAddr;
+
+use crate::{
+    reader::Reader,
+    template::{
+        decode_data_set, Template, TemplateCache, TemplateField, TemplateKey, TemplatePacket,
+        MIN_DATA_SET_ID,
+    },
+    FlowParserError, FlowParserResult,
+};
+
+const VERSION: u16 = 9;
+const TEMPLATE_FLOWSET_ID: u16 = 0;
+const OPTIONS_TEMPLATE_FLOWSET_ID: u16 = 1;
+
+/// The names of the scope field types of the options templates.
+fn scope_name(id: u16) -> Option<&'static str> {
+    Some(match id {
+        1 => "scopeSystem",
+        2 => "scopeInterface",
+        3 => "scopeLineCard",
+        4 => "scopeCache",
+        5 => "scopeTemplate",
+        _ => return None,
+    })
+}
+
+pub fn parse(
+    templates: &mut TemplateCache,
+    exporter: SocketAddr,
+    data: &[u8],
+) -> FlowParserResult<TemplatePacket> {
+    let mut reader = Reader::new(data);
+    let _version = reader.u16()?;
+    let _count = reader.u16()?;
+    let sys_uptime = reader.u32()?;
+    let export_time = reader.u32()?;
+    let sequence_number = reader.u32()?;
+    let domain_id = reader.u32()?;
+
+    let mut packet = TemplatePacket {
+        version: VERSION,
+        export_time,
+        sys_uptime: Some(sys_uptime),
+        sequence_number,
+        domain_id,
+        records: Vec::new(),
+        missing_templates: Vec::new(),
+    };
+    let key = |template_id| TemplateKey {
+        exporter,
+        version: VERSION,
+        domain_id,
+        template_id,
+    };
+
+    // Templates may be followed by the data sets they describe, so they're
+    // processed in order.
+    while !reader.is_empty() {
+        let flowset_id = reader.u16()?;
+        let length = reader.u16()? as usize;
+        if length < 4 {
+            return Err(FlowParserError::InvalidLength {
+                what: "NetFlow v9 flowset",
+                length,
+            });
+        }
+        let mut flowset = reader.sub(length - 4)?;
+        match flowset_id {
+            TEMPLATE_FLOWSET_ID => {
+                while flowset.remaining() >= 4 {
+                    let template = parse_template(&mut flowset)?;
+                    templates.insert(key(template.id), template);
+                }
+            }
+            OPTIONS_TEMPLATE_FLOWSET_ID => {
+                while flowset.remaining() >= 6 {
+                    let template = parse_options_template(&mut flowset)?;
+                    templates.insert(key(template.id), template);
+                }
+            }
+            id if id < MIN_DATA_SET_ID => {}
+            id => decode_data_set(&mut packet, templates, key(id), flowset, scope_name)?,
+        }
+    }
+
+    Ok(packet)
+}
+
+fn parse_template(reader: &mut Reader<'_>) -> FlowParserResult<Template> {
+    let id = reader.u16()?;
+    let count = reader.u16()?;
+    let fields = (0..count)
+        .map(|_| parse_field(reader, false))
+        .collect::<FlowParserResult<_>>()?;
+    Template::new(id, fields, false)
+}
+
+fn parse_options_template(reader: &mut Reader<'_>) -> FlowParserResult<Template> {
+    let id = reader.u16()?;
+    let scope_length = reader.u16()? as usize;
+    let option_length = reader.u16()? as usize;
+    let mut fields = Vec::with_capacity((scope_length + option_length) / 4);
+    for _ in 0..scope_length / 4 {
+        fields.push(parse_field(reader, true)?);
+    }
+    for _ in 0..option_length / 4 {
+        fields.push(parse_field(reader, false)?);
+    }
+    Template::new(id, fields, true)
+}
+
+fn parse_field(reader: &mut Reader<'_>, scope: bool) -> FlowParserResult<TemplateField> {
+    Ok(TemplateField {
+        id: reader.u16()?,
+        enterprise: None,
+        length: reader.u16()?,
+        scope,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+    use crate::fields::FieldValue;
+
+    fn header(out: &mut Vec<u8>) {
+        out.extend_from_slice(&[0, 9, 0, 2]);
+        out.extend_from_slice(&1000u32.to_be_bytes());
+        out.extend_from_slice(&1_600_000_000u32.to_be_bytes());
+        out.extend_from_slice(&7u32.to_be_bytes());
+        out.extend_from_slice(&1u32.to_be_bytes());
+    }
+
+    fn template_flowset(out: &mut Vec<u8>) {
+        out.extend_from_slice(&[0, 0, 0, 16, 1, 0, 0, 2]);
+        out.extend_from_slice(&[0, 8, 0, 4, 0, 2, 0, 4]);
+    }
+
+    fn data_flowset(out: &mut Vec<u8>) {
+        out.extend_from_slice(&[1, 0, 0, 16]);
+        out.extend_from_slice(&[192, 168, 0, 1, 0, 0, 0, 10]);
+        // Padding to a 4 bytes boundary.
+        out.extend_from_slice(&[0, 0, 0, 0]);
+    }
+
+    fn exporter() -> SocketAddr {
+        "192.0.2.1:2055".parse().unwrap()
+    }
+
+    #[test]
+    fn decodes_data_with_cached_templates() {
+        let mut templates = TemplateCache::default();
+        let mut data = Vec::new();
+        header(&mut data);
+        data_flowset(&mut data);
+        let packet = parse(&mut templates, exporter(), &data).unwrap();
+        assert!(packet.records.is_empty());
+        assert_eq!(packet.missing_templates, vec![(256, 12)]);
+
+        let mut data = Vec::new();
+        header(&mut data);
+        template_flowset(&mut data);
+        data_flowset(&mut data);
+        let packet = parse(&mut templates, exporter(), &data).unwrap();
+        assert_eq!(packet.records.len(), 1);
+        assert_eq!(packet.domain_id, 1);
+        assert_eq!(packet.sys_uptime, Some(1000));
+        assert_eq!(
+            packet.records[0].get("sourceIPv4Address"),
+            Some(&FieldValue::Ipv4Address(Ipv4Addr::new(192, 168, 0, 1)))
+        );
+        assert_eq!(
+            packet.records[0].get("packetDeltaCount"),
+            Some(&FieldValue::Unsigned(10))
+        );
+
+        // Templates are cached per exporter.
+        let mut data = Vec::new();
+        header(&mut data);
+        data_flowset(&mut data);
+        let packet = parse(&mut templates, exporter(), &data).unwrap();
+        assert_eq!(packet.records.len(), 1);
+        let other = "192.0.2.2:2055".parse().unwrap();
+        let packet = parse(&mut templates, other, &data).unwrap();
+        assert!(packet.records.is_empty());
+    }
+
+    #[test]
+    fn decodes_options() {
+        let mut templates = TemplateCache::default();
+        let mut data = Vec::new();
+        header(&mut data);
+        data.extend_from_slice(&[0, 1, 0, 20, 1, 1, 0, 4, 0, 4]);
+        data.extend_from_slice(&[0, 2, 0, 2, 0, 34, 0, 4, 0, 0]);
+        data.extend_from_slice(&[1, 1, 0, 12, 0, 3, 0, 0, 0, 100, 0, 0]);
+        let packet = parse(&mut templates, exporter(), &data).unwrap();
+        assert_eq!(packet.records.len(), 1);
+        assert!(packet.records[0].options);
+        assert_eq!(
+            packet.records[0].get("scopeInterface"),
+            Some(&FieldValue::Unsigned(3))
+        );
+        assert_eq!(
+            packet.records[0].get("samplingInterval"),
+            Some(&FieldValue::Unsigned(100))
+        );
+    }
+
+    #[test]
+    fn rejects_reserved_template_ids() {
+        let mut data = Vec::new();
+        header(&mut data);
+        data.extend_from_slice(&[0, 0, 0, 12, 0, 1, 0, 1, 0, 8, 0, 4]);
+        assert!(matches!(
+            parse(&mut TemplateCache::default(), exporter(), &data),
+            Err(FlowParserError::InvalidTemplate { template_id: 1, .. })
+        ));
+    }
+}
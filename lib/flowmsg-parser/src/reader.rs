@@ -0,0 +1,82 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::{FlowParserError, FlowParserResult};
+
+/// A cursor reading the big endian values of a packet.
+#[derive(Debug, Clone)]
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) const fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub(crate) const fn remaining(&self) -> usize {
+        self.data.len()
+    }
+
+    pub(crate) const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The bytes which weren't read yet.
+    pub(crate) const fn rest(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub(crate) fn bytes(&mut self, length: usize) -> FlowParserResult<&'a [u8]> {
+        if length > self.data.len() {
+            return Err(FlowParserError::UnexpectedEnd {
+                needed: length - self.data.len(),
+            });
+        }
+        let (bytes, rest) = self.data.split_at(length);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    /// Splits off a reader over the next `length` bytes.
+    pub(crate) fn sub(&mut self, length: usize) -> FlowParserResult<Reader<'a>> {
+        self.bytes(length).map(Reader::new)
+    }
+
+    pub(crate) fn skip(&mut self, length: usize) -> FlowParserResult<()> {
+        self.bytes(length).map(|_| ())
+    }
+
+    fn array<const N: usize>(&mut self) -> FlowParserResult<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.bytes(N)?);
+        Ok(array)
+    }
+
+    pub(crate) fn u8(&mut self) -> FlowParserResult<u8> {
+        self.array::<1>().map(|bytes| bytes[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> FlowParserResult<u16> {
+        self.array().map(u16::from_be_bytes)
+    }
+
+    pub(crate) fn u32(&mut self) -> FlowParserResult<u32> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    pub(crate) fn u64(&mut self) -> FlowParserResult<u64> {
+        self.array().map(u64::from_be_bytes)
+    }
+
+    pub(crate) fn ipv4(&mut self) -> FlowParserResult<Ipv4Addr> {
+        self.array::<4>().map(Ipv4Addr::from)
+    }
+
+    pub(crate) fn ipv6(&mut self) -> FlowParserResult<Ipv6Addr> {
+        self.array::<16>().map(Ipv6Addr::from)
+    }
+
+    pub(crate) fn mac(&mut self) -> FlowParserResult<[u8; 6]> {
+        self.array()
+    }
+}
@@ -0,0 +1,625 @@
+//! sFlow v5, whose datagrams hold sampled packets and interface counters.
+
+use std::net::IpAddr;
+
+use crate::{reader::Reader, FlowParserError, FlowParserResult};
+
+const VERSION: u32 = 5;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const IP_PROTOCOL_TCP: u8 = 6;
+const IP_PROTOCOL_UDP: u8 = 17;
+/// The `ETHERNET-ISO88023` header protocol of the raw packet headers.
+const HEADER_PROTOCOL_ETHERNET: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SflowDatagram {
+    pub agent_address: IpAddr,
+    pub sub_agent_id: u32,
+    pub sequence_number: u32,
+    /// The time since the agent was started, in milliseconds.
+    pub uptime: u32,
+    pub samples: Vec<Sample>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sample {
+    Flow(FlowSample),
+    Counters(CountersSample),
+    Unknown { enterprise: u32, format: u32 },
+}
+
+/// The data source of a sample, like `ifIndex` 3 with the type 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceId {
+    pub source_type: u32,
+    pub index: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowSample {
+    pub sequence_number: u32,
+    pub source_id: SourceId,
+    pub sampling_rate: u32,
+    pub sample_pool: u32,
+    pub drops: u32,
+    pub input: u32,
+    pub output: u32,
+    pub records: Vec<FlowRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlowRecord {
+    RawPacketHeader(RawPacketHeader),
+    SampledEthernet {
+        length: u32,
+        source_mac: [u8; 6],
+        destination_mac: [u8; 6],
+        ethernet_type: u32,
+    },
+    SampledIpv4(SampledIp),
+    SampledIpv6(SampledIp),
+    ExtendedSwitch {
+        source_vlan: u32,
+        source_priority: u32,
+        destination_vlan: u32,
+        destination_priority: u32,
+    },
+    ExtendedRouter {
+        next_hop: IpAddr,
+        source_mask_length: u32,
+        destination_mask_length: u32,
+    },
+    Unknown {
+        enterprise: u32,
+        format: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawPacketHeader {
+    pub header_protocol: u32,
+    pub frame_length: u32,
+    pub stripped: u32,
+    pub header: Vec<u8>,
+    /// The fields decoded from Ethernet headers.
+    pub decoded: Option<PacketHeader>,
+}
+
+/// The fields of the link, network and transport layers of a sampled packet.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PacketHeader {
+    pub source_mac: Option<[u8; 6]>,
+    pub destination_mac: Option<[u8; 6]>,
+    pub ethernet_type: Option<u16>,
+    pub vlan_id: Option<u16>,
+    pub source_ip: Option<IpAddr>,
+    pub destination_ip: Option<IpAddr>,
+    pub ip_protocol: Option<u8>,
+    pub ip_tos: Option<u8>,
+    pub ip_ttl: Option<u8>,
+    pub source_port: Option<u16>,
+    pub destination_port: Option<u16>,
+    pub tcp_flags: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampledIp {
+    pub length: u32,
+    pub protocol: u32,
+    pub source_ip: IpAddr,
+    pub destination_ip: IpAddr,
+    pub source_port: u32,
+    pub destination_port: u32,
+    pub tcp_flags: u32,
+    /// The type of service of IPv4, or the priority of IPv6.
+    pub tos: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CountersSample {
+    pub sequence_number: u32,
+    pub source_id: SourceId,
+    pub records: Vec<CounterRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CounterRecord {
+    GenericInterface(InterfaceCounters),
+    Ethernet(EthernetCounters),
+    Processor(ProcessorCounters),
+    Unknown { enterprise: u32, format: u32 },
+}
+
+/// The counters of the `IF-MIB`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InterfaceCounters {
+    pub if_index: u32,
+    pub if_type: u32,
+    pub if_speed: u64,
+    pub if_direction: u32,
+    pub if_status: u32,
+    pub if_in_octets: u64,
+    pub if_in_ucast_pkts: u32,
+    pub if_in_multicast_pkts: u32,
+    pub if_in_broadcast_pkts: u32,
+    pub if_in_discards: u32,
+    pub if_in_errors: u32,
+    pub if_in_unknown_protos: u32,
+    pub if_out_octets: u64,
+    pub if_out_ucast_pkts: u32,
+    pub if_out_multicast_pkts: u32,
+    pub if_out_broadcast_pkts: u32,
+    pub if_out_discards: u32,
+    pub if_out_errors: u32,
+    pub if_promiscuous_mode: u32,
+}
+
+/// The counters of the `EtherLike-MIB`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EthernetCounters {
+    pub alignment_errors: u32,
+    pub fcs_errors: u32,
+    pub single_collision_frames: u32,
+    pub multiple_collision_frames: u32,
+    pub sqe_test_errors: u32,
+    pub deferred_transmissions: u32,
+    pub late_collisions: u32,
+    pub excessive_collisions: u32,
+    pub internal_mac_transmit_errors: u32,
+    pub carrier_sense_errors: u32,
+    pub frame_too_longs: u32,
+    pub internal_mac_receive_errors: u32,
+    pub symbol_errors: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessorCounters {
+    /// The CPU usages over 5 seconds, 1 minute and 5 minutes, in hundredths
+    /// of percents.
+    pub cpu_5s: u32,
+    pub cpu_1m: u32,
+    pub cpu_5m: u32,
+    pub total_memory: u64,
+    pub free_memory: u64,
+}
+
+pub fn parse(data: &[u8]) -> FlowParserResult<SflowDatagram> {
+    let mut reader = Reader::new(data);
+    let version = reader.u32()?;
+    if version != VERSION {
+        return Err(FlowParserError::UnsupportedVersion { version });
+    }
+    let agent_address = address(&mut reader)?;
+    let sub_agent_id = reader.u32()?;
+    let sequence_number = reader.u32()?;
+    let uptime = reader.u32()?;
+    let count = reader.u32()?;
+
+    let mut samples = Vec::new();
+    for _ in 0..count {
+        let (enterprise, format, mut sample) = structure(&mut reader)?;
+        samples.push(match (enterprise, format) {
+            (0, 1) => Sample::Flow(flow_sample(&mut sample, false)?),
+            (0, 2) => Sample::Counters(counters_sample(&mut sample, false)?),
+            (0, 3) => Sample::Flow(flow_sample(&mut sample, true)?),
+            (0, 4) => Sample::Counters(counters_sample(&mut sample, true)?),
+            _ => Sample::Unknown { enterprise, format },
+        });
+    }
+
+    Ok(SflowDatagram {
+        agent_address,
+        sub_agent_id,
+        sequence_number,
+        uptime,
+        samples,
+    })
+}
+
+/// Reads the format and the data of a sample or a record.
+fn structure<'a>(reader: &mut Reader<'a>) -> FlowParserResult<(u32, u32, Reader<'a>)> {
+    let data_format = reader.u32()?;
+    let length = reader.u32()? as usize;
+    Ok((data_format >> 12, data_format & 0xfff, reader.sub(length)?))
+}
+
+fn address(reader: &mut Reader<'_>) -> FlowParserResult<IpAddr> {
+    match reader.u32()? {
+        1 => reader.ipv4().map(IpAddr::V4),
+        2 => reader.ipv6().map(IpAddr::V6),
+        address_type => Err(FlowParserError::InvalidValue {
+            what: "sFlow address type",
+            value: address_type,
+        }),
+    }
+}
+
+fn source_id(reader: &mut Reader<'_>, expanded: bool) -> FlowParserResult<SourceId> {
+    if expanded {
+        Ok(SourceId {
+            source_type: reader.u32()?,
+            index: reader.u32()?,
+        })
+    } else {
+        let value = reader.u32()?;
+        Ok(SourceId {
+            source_type: value >> 24,
+            index: value & 0x00ff_ffff,
+        })
+    }
+}
+
+fn interface(reader: &mut Reader<'_>, expanded: bool) -> FlowParserResult<u32> {
+    if expanded {
+        let _format = reader.u32()?;
+        reader.u32()
+    } else {
+        reader.u32().map(|value| value & 0x3fff_ffff)
+    }
+}
+
+fn flow_sample(reader: &mut Reader<'_>, expanded: bool) -> FlowParserResult<FlowSample> {
+    let sequence_number = reader.u32()?;
+    let source_id = source_id(reader, expanded)?;
+    let sampling_rate = reader.u32()?;
+    let sample_pool = reader.u32()?;
+    let drops = reader.u32()?;
+    let input = interface(reader, expanded)?;
+    let output = interface(reader, expanded)?;
+    let count = reader.u32()?;
+
+    let mut records = Vec::new();
+    for _ in 0..count {
+        let (enterprise, format, mut record) = structure(reader)?;
+        records.push(match (enterprise, format) {
+            (0, 1) => raw_packet_header(&mut record)?,
+            (0, 2) => {
+                let length = record.u32()?;
+                let source_mac = record.mac()?;
+                record.skip(2)?;
+                let destination_mac = record.mac()?;
+                record.skip(2)?;
+                FlowRecord::SampledEthernet {
+                    length,
+                    source_mac,
+                    destination_mac,
+                    ethernet_type: record.u32()?,
+                }
+            }
+            (0, 3) => FlowRecord::SampledIpv4(sampled_ip(&mut record, false)?),
+            (0, 4) => FlowRecord::SampledIpv6(sampled_ip(&mut record, true)?),
+            (0, 1001) => FlowRecord::ExtendedSwitch {
+                source_vlan: record.u32()?,
+                source_priority: record.u32()?,
+                destination_vlan: record.u32()?,
+                destination_priority: record.u32()?,
+            },
+            (0, 1002) => FlowRecord::ExtendedRouter {
+                next_hop: address(&mut record)?,
+                source_mask_length: record.u32()?,
+                destination_mask_length: record.u32()?,
+            },
+            _ => FlowRecord::Unknown { enterprise, format },
+        });
+    }
+
+    Ok(FlowSample {
+        sequence_number,
+        source_id,
+        sampling_rate,
+        sample_pool,
+        drops,
+        input,
+        output,
+        records,
+    })
+}
+
+fn raw_packet_header(reader: &mut Reader<'_>) -> FlowParserResult<FlowRecord> {
+    let header_protocol = reader.u32()?;
+    let frame_length = reader.u32()?;
+    let stripped = reader.u32()?;
+    let length = reader.u32()? as usize;
+    let header = reader.bytes(length)?.to_vec();
+    let decoded = (header_protocol == HEADER_PROTOCOL_ETHERNET).then(|| ethernet(&header));
+    Ok(FlowRecord::RawPacketHeader(RawPacketHeader {
+        header_protocol,
+        frame_length,
+        stripped,
+        header,
+        decoded,
+    }))
+}
+
+fn sampled_ip(reader: &mut Reader<'_>, ipv6: bool) -> FlowParserResult<SampledIp> {
+    let length = reader.u32()?;
+    let protocol = reader.u32()?;
+    let (source_ip, destination_ip) = if ipv6 {
+        (IpAddr::V6(reader.ipv6()?), IpAddr::V6(reader.ipv6()?))
+    } else {
+        (IpAddr::V4(reader.ipv4()?), IpAddr::V4(reader.ipv4()?))
+    };
+    Ok(SampledIp {
+        length,
+        protocol,
+        source_ip,
+        destination_ip,
+        source_port: reader.u32()?,
+        destination_port: reader.u32()?,
+        tcp_flags: reader.u32()?,
+        tos: reader.u32()?,
+    })
+}
+
+/// Decodes as much as possible of a sampled Ethernet frame, whose header is
+/// usually truncated.
+fn ethernet(data: &[u8]) -> PacketHeader {
+    let mut header = PacketHeader::default();
+    let mut reader = Reader::new(data);
+    let _ = decode_ethernet(&mut reader, &mut header);
+    header
+}
+
+fn decode_ethernet(reader: &mut Reader<'_>, header: &mut PacketHeader) -> FlowParserResult<()> {
+    header.destination_mac = Some(reader.mac()?);
+    header.source_mac = Some(reader.mac()?);
+    let mut ethernet_type = reader.u16()?;
+    if ethernet_type == ETHERTYPE_VLAN {
+        header.vlan_id = Some(reader.u16()? & 0x0fff);
+        ethernet_type = reader.u16()?;
+    }
+    header.ethernet_type = Some(ethernet_type);
+
+    let protocol = match ethernet_type {
+        ETHERTYPE_IPV4 => {
+            let version_length = reader.u8()?;
+            let header_length = (version_length & 0x0f) as usize * 4;
+            header.ip_tos = Some(reader.u8()?);
+            reader.skip(6)?;
+            header.ip_ttl = Some(reader.u8()?);
+            let protocol = reader.u8()?;
+            header.ip_protocol = Some(protocol);
+            reader.skip(2)?;
+            header.source_ip = Some(IpAddr::V4(reader.ipv4()?));
+            header.destination_ip = Some(IpAddr::V4(reader.ipv4()?));
+            reader.skip(header_length.saturating_sub(20))?;
+            protocol
+        }
+        ETHERTYPE_IPV6 => {
+            let first = reader.u32()?;
+            header.ip_tos = Some((first >> 20) as u8);
+            reader.skip(2)?;
+            let protocol = reader.u8()?;
+            header.ip_protocol = Some(protocol);
+            header.ip_ttl = Some(reader.u8()?);
+            header.source_ip = Some(IpAddr::V6(reader.ipv6()?));
+            header.destination_ip = Some(IpAddr::V6(reader.ipv6()?));
+            protocol
+        }
+        _ => return Ok(()),
+    };
+
+    if protocol == IP_PROTOCOL_TCP || protocol == IP_PROTOCOL_UDP {
+        header.source_port = Some(reader.u16()?);
+        header.destination_port = Some(reader.u16()?);
+    }
+    if protocol == IP_PROTOCOL_TCP {
+        reader.skip(9)?;
+        header.tcp_flags = Some(reader.u8()?);
+    }
+    Ok(())
+}
+
+fn counters_sample(reader: &mut Reader<'_>, expanded: bool) -> FlowParserResult<CountersSample> {
+    let sequence_number = reader.u32()?;
+    let source_id = source_id(reader, expanded)?;
+    let count = reader.u32()?;
+
+    let mut records = Vec::new();
+    for _ in 0..count {
+        let (enterprise, format, mut record) = structure(reader)?;
+        let record = &mut record;
+        records.push(match (enterprise, format) {
+            (0, 1) => CounterRecord::GenericInterface(InterfaceCounters {
+                if_index: record.u32()?,
+                if_type: record.u32()?,
+                if_speed: record.u64()?,
+                if_direction: record.u32()?,
+                if_status: record.u32()?,
+                if_in_octets: record.u64()?,
+                if_in_ucast_pkts: record.u32()?,
+                if_in_multicast_pkts: record.u32()?,
+                if_in_broadcast_pkts: record.u32()?,
+                if_in_discards: record.u32()?,
+                if_in_errors: record.u32()?,
+                if_in_unknown_protos: record.u32()?,
+                if_out_octets: record.u64()?,
+                if_out_ucast_pkts: record.u32()?,
+                if_out_multicast_pkts: record.u32()?,
+                if_out_broadcast_pkts: record.u32()?,
+                if_out_discards: record.u32()?,
+                if_out_errors: record.u32()?,
+                if_promiscuous_mode: record.u32()?,
+            }),
+            (0, 2) => CounterRecord::Ethernet(EthernetCounters {
+                alignment_errors: record.u32()?,
+                fcs_errors: record.u32()?,
+                single_collision_frames: record.u32()?,
+                multiple_collision_frames: record.u32()?,
+                sqe_test_errors: record.u32()?,
+                deferred_transmissions: record.u32()?,
+                late_collisions: record.u32()?,
+                excessive_collisions: record.u32()?,
+                internal_mac_transmit_errors: record.u32()?,
+                carrier_sense_errors: record.u32()?,
+                frame_too_longs: record.u32()?,
+                internal_mac_receive_errors: record.u32()?,
+                symbol_errors: record.u32()?,
+            }),
+            (0, 1001) => CounterRecord::Processor(ProcessorCounters {
+                cpu_5s: record.u32()?,
+                cpu_1m: record.u32()?,
+                cpu_5m: record.u32()?,
+                total_memory: record.u64()?,
+                free_memory: record.u64()?,
+            }),
+            _ => CounterRecord::Unknown { enterprise, format },
+        });
+    }
+
+    Ok(CountersSample {
+        sequence_number,
+        source_id,
+        records,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    fn push(out: &mut Vec<u8>, values: &[u32]) {
+        for value in values {
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+
+    fn structure(out: &mut Vec<u8>, format: u32, data: &[u8]) {
+        push(out, &[format, data.len() as u32]);
+        out.extend_from_slice(data);
+    }
+
+    fn tcp_frame() -> Vec<u8> {
+        let mut frame = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 0x81, 0, 0, 10, 8, 0];
+        frame.extend_from_slice(&[0x45, 0x10, 0, 40, 0, 0, 0, 0, 64, 6, 0, 0]);
+        frame.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2]);
+        frame.extend_from_slice(&[0x1f, 0x90, 0xc3, 0x50, 0, 0, 0, 0, 0, 0, 0, 0, 0x50, 0x12]);
+        frame
+    }
+
+    fn datagram() -> Vec<u8> {
+        let mut flow = Vec::new();
+        push(&mut flow, &[7, 3, 100, 1000, 0, 3, 4, 2]);
+        let mut header = Vec::new();
+        let frame = tcp_frame();
+        push(&mut header, &[1, 1514, 4, frame.len() as u32]);
+        header.extend_from_slice(&frame);
+        // Headers are padded to 4 bytes boundaries.
+        while header.len() % 4 != 0 {
+            header.push(0);
+        }
+        structure(&mut flow, 1, &header);
+        let mut switch = Vec::new();
+        push(&mut switch, &[10, 0, 20, 0]);
+        structure(&mut flow, 1001, &switch);
+
+        let mut counters = Vec::new();
+        push(&mut counters, &[8, 3, 1]);
+        let mut interface = Vec::new();
+        push(&mut interface, &[3, 6, 0, 1_000_000_000, 1, 3, 0, 5000]);
+        push(
+            &mut interface,
+            &[10, 0, 0, 0, 1, 0, 0, 7000, 20, 0, 0, 0, 0, 0],
+        );
+        structure(&mut counters, 1, &interface);
+
+        let mut data = Vec::new();
+        push(&mut data, &[5, 1, 0xc0_00_02_01, 0, 99, 123_456, 3]);
+        structure(&mut data, 1, &flow);
+        structure(&mut data, 2, &counters);
+        structure(&mut data, (9 << 12) | 1, &[]);
+        data
+    }
+
+    #[test]
+    fn parses_datagrams() {
+        let datagram = parse(&datagram()).unwrap();
+        assert_eq!(
+            datagram.agent_address,
+            IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))
+        );
+        assert_eq!(datagram.sequence_number, 99);
+        assert_eq!(datagram.samples.len(), 3);
+
+        let flow = match &datagram.samples[0] {
+            Sample::Flow(flow) => flow,
+            sample => panic!("unexpected sample {:?}", sample),
+        };
+        assert_eq!(
+            flow.source_id,
+            SourceId {
+                source_type: 0,
+                index: 3
+            }
+        );
+        assert_eq!(flow.sampling_rate, 100);
+        assert_eq!((flow.input, flow.output), (3, 4));
+        let header = match &flow.records[0] {
+            FlowRecord::RawPacketHeader(header) => header.decoded.clone().unwrap(),
+            record => panic!("unexpected record {:?}", record),
+        };
+        assert_eq!(header.vlan_id, Some(10));
+        assert_eq!(header.source_mac, Some([6, 7, 8, 9, 10, 11]));
+        assert_eq!(
+            header.destination_ip,
+            Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)))
+        );
+        assert_eq!(header.ip_protocol, Some(6));
+        assert_eq!(header.ip_tos, Some(0x10));
+        assert_eq!(header.source_port, Some(8080));
+        assert_eq!(header.destination_port, Some(50000));
+        assert_eq!(header.tcp_flags, Some(0x12));
+        assert_eq!(
+            flow.records[1],
+            FlowRecord::ExtendedSwitch {
+                source_vlan: 10,
+                source_priority: 0,
+                destination_vlan: 20,
+                destination_priority: 0
+            }
+        );
+
+        match &datagram.samples[1] {
+            Sample::Counters(counters) => match &counters.records[0] {
+                CounterRecord::GenericInterface(interface) => {
+                    assert_eq!(interface.if_index, 3);
+                    assert_eq!(interface.if_speed, 1_000_000_000);
+                    assert_eq!(interface.if_in_octets, 5000);
+                    assert_eq!(interface.if_in_errors, 1);
+                    assert_eq!(interface.if_out_octets, 7000);
+                }
+                record => panic!("unexpected record {:?}", record),
+            },
+            sample => panic!("unexpected sample {:?}", sample),
+        }
+        assert_eq!(
+            datagram.samples[2],
+            Sample::Unknown {
+                enterprise: 9,
+                format: 1
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_truncated_packet_headers() {
+        let frame = &tcp_frame()[..30];
+        let header = ethernet(frame);
+        assert_eq!(header.ethernet_type, Some(ETHERTYPE_IPV4));
+        assert_eq!(header.ip_ttl, Some(64));
+        assert_eq!(header.source_ip, None);
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        assert_eq!(
+            parse(&[0, 0, 0, 4]),
+            Err(FlowParserError::UnsupportedVersion { version: 4 })
+        );
+    }
+}
@@ -0,0 +1,357 @@
+//! The templates describing the data records of NetFlow v9 and IPFIX, cached
+//! per exporter until they're updated, withdrawn or expire.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    fields::{decode_value, field_info, Field, FieldType, FieldValue, PADDING_OCTETS},
+    reader::Reader,
+    FlowParserError, FlowParserResult,
+};
+
+/// The length of the variable length fields of IPFIX in templates.
+pub const VARIABLE_LENGTH: u16 = 65535;
+
+/// The smallest template ID of the data sets.
+pub const MIN_DATA_SET_ID: u16 = 256;
+
+/// The default number of cached templates.
+pub const DEFAULT_MAX_TEMPLATES: usize = 10_000;
+
+/// The default lifetime of the templates which aren't received again. Exporters
+/// resend their templates periodically over UDP, so they expire on collectors
+/// (RFC 7011, section 8.4).
+pub const DEFAULT_TEMPLATE_TIMEOUT: Duration = Duration::from_secs(1800);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateField {
+    pub id: u16,
+    pub enterprise: Option<u32>,
+    pub length: u16,
+    /// Whether this is a scope field of an options template.
+    pub scope: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    pub id: u16,
+    pub fields: Vec<TemplateField>,
+    /// Whether this is an options template, whose records describe the
+    /// exporter itself rather than flows.
+    pub options: bool,
+}
+
+impl Template {
+    pub(crate) fn new(
+        id: u16,
+        fields: Vec<TemplateField>,
+        options: bool,
+    ) -> FlowParserResult<Self> {
+        let template = Self {
+            id,
+            fields,
+            options,
+        };
+        if id < MIN_DATA_SET_ID {
+            return Err(FlowParserError::InvalidTemplate {
+                template_id: id,
+                reason: "reserved template ID",
+            });
+        }
+        if template.min_record_length() == 0 {
+            return Err(FlowParserError::InvalidTemplate {
+                template_id: id,
+                reason: "empty records",
+            });
+        }
+        Ok(template)
+    }
+
+    fn has_variable_length(&self) -> bool {
+        self.fields
+            .iter()
+            .any(|field| field.length == VARIABLE_LENGTH)
+    }
+
+    /// The length of the shortest record, the rest of a set being padding.
+    pub fn min_record_length(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|field| match field.length {
+                VARIABLE_LENGTH => 1,
+                length => length as usize,
+            })
+            .sum()
+    }
+
+    fn decode_record(
+        &self,
+        reader: &mut Reader<'_>,
+        scope_name: fn(u16) -> Option<&'static str>,
+    ) -> FlowParserResult<FlowRecord> {
+        let mut fields = Vec::with_capacity(self.fields.len());
+        for field in &self.fields {
+            let length = match field.length {
+                VARIABLE_LENGTH => match reader.u8()? {
+                    255 => reader.u16()? as usize,
+                    length => length as usize,
+                },
+                length => length as usize,
+            };
+            let bytes = reader.bytes(length)?;
+            if field.id == PADDING_OCTETS && field.enterprise.is_none() {
+                continue;
+            }
+
+            let scope = if field.scope {
+                scope_name(field.id)
+            } else {
+                None
+            };
+            let (name, field_type) = match scope {
+                Some(name) => (Cow::Borrowed(name), FieldType::Unsigned),
+                None => field_info(field.id, field.enterprise),
+            };
+            fields.push(Field {
+                name,
+                value: decode_value(field_type, bytes),
+            });
+        }
+        Ok(FlowRecord {
+            template_id: self.id,
+            options: self.options,
+            fields,
+        })
+    }
+}
+
+/// Identifies the templates of an exporter, whose IDs are only unique within
+/// an observation domain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TemplateKey {
+    pub exporter: SocketAddr,
+    pub version: u16,
+    pub domain_id: u32,
+    pub template_id: u16,
+}
+
+/// The templates received from the exporters, along with when they were last
+/// received. Templates expire when they aren't received again within the
+/// timeout, and the least recently received ones are evicted past the maximum
+/// number of templates.
+#[derive(Debug)]
+pub struct TemplateCache {
+    templates: HashMap<TemplateKey, (Template, Instant)>,
+    max_templates: usize,
+    timeout: Duration,
+}
+
+impl Default for TemplateCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_TEMPLATES, DEFAULT_TEMPLATE_TIMEOUT)
+    }
+}
+
+impl TemplateCache {
+    pub fn new(max_templates: usize, timeout: Duration) -> Self {
+        Self {
+            templates: HashMap::new(),
+            max_templates,
+            timeout,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    pub fn get(&self, key: &TemplateKey) -> Option<&Template> {
+        self.get_at(key, Instant::now())
+    }
+
+    fn get_at(&self, key: &TemplateKey, now: Instant) -> Option<&Template> {
+        self.templates
+            .get(key)
+            .filter(|(_, received)| !self.expired(*received, now))
+            .map(|(template, _)| template)
+    }
+
+    fn expired(&self, received: Instant, now: Instant) -> bool {
+        now.saturating_duration_since(received) >= self.timeout
+    }
+
+    pub(crate) fn insert(&mut self, key: TemplateKey, template: Template) {
+        self.insert_at(key, template, Instant::now());
+    }
+
+    fn insert_at(&mut self, key: TemplateKey, template: Template, now: Instant) {
+        if self.max_templates == 0 {
+            return;
+        }
+        if !self.templates.contains_key(&key) && self.templates.len() >= self.max_templates {
+            let timeout = self.timeout;
+            self.templates
+                .retain(|_, (_, received)| now.saturating_duration_since(*received) < timeout);
+            if self.templates.len() >= self.max_templates {
+                let oldest = self
+                    .templates
+                    .iter()
+                    .min_by_key(|(_, (_, received))| *received)
+                    .map(|(key, _)| *key);
+                if let Some(oldest) = oldest {
+                    self.templates.remove(&oldest);
+                }
+            }
+        }
+        self.templates.insert(key, (template, now));
+    }
+
+    pub(crate) fn remove(&mut self, key: &TemplateKey) {
+        self.templates.remove(key);
+    }
+
+    /// Removes all the templates of an observation domain, of either the data
+    /// or the options records.
+    pub(crate) fn remove_all(&mut self, exporter: SocketAddr, domain_id: u32, options: bool) {
+        self.templates.retain(|key, (template, _)| {
+            key.exporter != exporter || key.domain_id != domain_id || template.options != options
+        });
+    }
+}
+
+/// A decoded NetFlow v9 or IPFIX packet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplatePacket {
+    pub version: u16,
+    /// The time of the export, in seconds since the Unix epoch.
+    pub export_time: u32,
+    /// The time since the exporter was started in milliseconds, only sent by
+    /// NetFlow v9.
+    pub sys_uptime: Option<u32>,
+    pub sequence_number: u32,
+    /// The observation domain ID of IPFIX, or the source ID of NetFlow v9.
+    pub domain_id: u32,
+    pub records: Vec<FlowRecord>,
+    /// The IDs of the data sets which couldn't be decoded because their
+    /// template wasn't received yet, along with the length of their records.
+    pub missing_templates: Vec<(u16, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowRecord {
+    pub template_id: u16,
+    /// Whether this record was described by an options template.
+    pub options: bool,
+    pub fields: Vec<Field>,
+}
+
+impl FlowRecord {
+    pub fn get(&self, name: &str) -> Option<&FieldValue> {
+        self.fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| &field.value)
+    }
+}
+
+/// Decodes the records of a data set, stopping at the padding which doesn't
+/// fit a whole record.
+///
+/// The padding is shorter than the shortest record (RFC 7011, section 3.3.1),
+/// but records with variable length fields are longer, and up to three zeros
+/// aligning the set would read as records of empty fields.
+pub(crate) fn decode_data_set(
+    packet: &mut TemplatePacket,
+    templates: &TemplateCache,
+    key: TemplateKey,
+    mut set: Reader<'_>,
+    scope_name: fn(u16) -> Option<&'static str>,
+) -> FlowParserResult<()> {
+    let template = match templates.get(&key) {
+        Some(template) => template,
+        None => {
+            packet
+                .missing_templates
+                .push((key.template_id, set.remaining()));
+            return Ok(());
+        }
+    };
+    let min_length = template.min_record_length();
+    let variable_length = template.has_variable_length();
+    while set.remaining() >= min_length {
+        if variable_length && set.remaining() < 4 && set.rest().iter().all(|&byte| byte == 0) {
+            break;
+        }
+        match template.decode_record(&mut set, scope_name) {
+            Ok(record) => packet.records.push(record),
+            Err(FlowParserError::UnexpectedEnd { .. }) if variable_length => break,
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(template_id: u16) -> TemplateKey {
+        TemplateKey {
+            exporter: "192.0.2.1:2055".parse().unwrap(),
+            version: 10,
+            domain_id: 1,
+            template_id,
+        }
+    }
+
+    fn template(id: u16) -> Template {
+        let field = TemplateField {
+            id: 1,
+            enterprise: None,
+            length: 4,
+            scope: false,
+        };
+        Template::new(id, vec![field], false).unwrap()
+    }
+
+    #[test]
+    fn expires_templates() {
+        let mut templates = TemplateCache::new(10, Duration::from_secs(60));
+        let now = Instant::now();
+        templates.insert_at(key(256), template(256), now);
+        assert!(templates.get_at(&key(256), now).is_some());
+        let later = now + Duration::from_secs(60);
+        assert!(templates.get_at(&key(256), later).is_none());
+
+        // Receiving the template again refreshes it.
+        templates.insert_at(key(256), template(256), later);
+        assert!(templates.get_at(&key(256), later).is_some());
+    }
+
+    #[test]
+    fn evicts_oldest_templates() {
+        let mut templates = TemplateCache::new(2, Duration::from_secs(60));
+        let now = Instant::now();
+        templates.insert_at(key(256), template(256), now);
+        templates.insert_at(key(257), template(257), now + Duration::from_secs(1));
+        templates.insert_at(key(258), template(258), now + Duration::from_secs(2));
+        assert_eq!(templates.len(), 2);
+        assert!(templates.get_at(&key(256), now).is_none());
+        assert!(templates.get_at(&key(257), now).is_some());
+        assert!(templates.get_at(&key(258), now).is_some());
+
+        // Updating a template doesn't evict another one.
+        templates.insert_at(key(257), template(257), now + Duration::from_secs(3));
+        assert_eq!(templates.len(), 2);
+        assert!(templates.get_at(&key(258), now).is_some());
+    }
+}
@@ -167,6 +167,16 @@ impl component_sent_events_throughputs_subscription::ComponentSentEventsThroughp
 )]
 pub struct ComponentErrorsTotalsSubscription;
 
+/// ComponentConsumerLagsSubscription contains the number of messages components are
+/// behind the partitions they consume (metrics ending in `_consumer_lag`).
+#[derive(GraphQLQuery, Debug, Copy, Clone)]
+#[graphql(
+    schema_path = "graphql/schema.json",
+    query_path = "graphql/subscriptions/component_consumer_lags.graphql",
+    response_derives = "Debug"
+)]
+pub struct ComponentConsumerLagsSubscription;
+
 /// Extension methods for metrics subscriptions
 pub trait MetricsSubscriptionExt {
     /// Executes an uptime metrics subscription.
@@ -242,6 +252,12 @@ pub trait MetricsSubscriptionExt {
         &self,
         interval: i64,
     ) -> crate::BoxedSubscription<ComponentErrorsTotalsSubscription>;
+
+    /// Executes a component consumer lags subscription.
+    fn component_consumer_lags_subscription(
+        &self,
+        interval: i64,
+    ) -> crate::BoxedSubscription<ComponentConsumerLagsSubscription>;
 }
 
 impl MetricsSubscriptionExt for crate::SubscriptionClient {
@@ -394,4 +410,16 @@ impl MetricsSubscriptionExt for crate::SubscriptionClient {
 
         self.start::<ComponentErrorsTotalsSubscription>(&request_body)
     }
+
+    /// Executes a component consumer lags subscription.
+    fn component_consumer_lags_subscription(
+        &self,
+        interval: i64,
+    ) -> BoxedSubscription<ComponentConsumerLagsSubscription> {
+        let request_body = ComponentConsumerLagsSubscription::build_query(
+            component_consumer_lags_subscription::Variables { interval },
+        );
+
+        self.start::<ComponentConsumerLagsSubscription>(&request_body)
+    }
 }
@@ -1,6 +1,13 @@
 #![deny(missing_docs)]
 
-use std::{cmp, future::Future, mem, pin::Pin, sync::Arc, task::Poll};
+use std::{
+    cmp,
+    future::Future,
+    mem,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::Poll,
+};
 
 use atomig::{Atom, Atomic, Ordering};
 use futures::future::FutureExt;
@@ -65,6 +72,14 @@ impl EventFinalizers {
         }
     }
 
+    /// Mark all finalizers in this set as rejected, recording the
+    /// details of the rejection in the batches tracking their events.
+    pub fn reject(&self, rejection: &Rejection) {
+        for finalizer in &self.0 {
+            finalizer.reject(rejection);
+        }
+    }
+
     /// Update all sources for this finalizer with the current
     /// status. This *drops* the finalizer array elements so they may
     /// immediately signal the source batch.
@@ -94,6 +109,9 @@ impl Finalizable for EventFinalizers {
 pub struct EventFinalizer {
     status: Atomic<EventStatus>,
     batch: Arc<BatchNotifier>,
+    /// The position of the event in its batch, only meaningful if the
+    /// batch tracks the outcome of each of its events.
+    index: u32,
 }
 
 impl ByteSizeOf for EventFinalizer {
@@ -106,7 +124,23 @@ impl EventFinalizer {
     /// Create a new event in a batch.
     pub fn new(batch: Arc<BatchNotifier>) -> Self {
         let status = Atomic::new(EventStatus::Dropped);
-        Self { status, batch }
+        Self {
+            status,
+            batch,
+            index: 0,
+        }
+    }
+
+    /// Create a new event at the given position in a batch tracking the
+    /// outcome of each of its events, as created by
+    /// [`BatchNotifier::new_tracking_events`].
+    pub fn new_tracked(batch: Arc<BatchNotifier>, index: u32) -> Self {
+        let status = Atomic::new(EventStatus::Dropped);
+        Self {
+            status,
+            batch,
+            index,
+        }
     }
 
     /// Update this finalizer's status in place with the given `EventStatus`.
@@ -119,6 +153,13 @@ impl EventFinalizer {
             .unwrap_or_else(|_| unreachable!());
     }
 
+    /// Mark this event as rejected, recording the details of the
+    /// rejection if its batch tracks the outcome of each event.
+    pub fn reject(&self, rejection: &Rejection) {
+        self.update_status(EventStatus::Rejected);
+        self.batch.record_rejection(self.index, rejection);
+    }
+
     /// Update the batch for this event with this finalizer's
     /// status, and mark this event as no longer requiring update.
     #[allow(clippy::missing_panics_doc)] // Panic is unreachable
@@ -130,6 +171,7 @@ impl EventFinalizer {
             })
             .unwrap_or_else(|_| unreachable!());
         self.batch.update_status(status);
+        self.batch.record_status(self.index, status);
     }
 }
 
@@ -176,6 +218,8 @@ impl BatchStatusReceiver {
 #[derive(Debug)]
 pub struct BatchNotifier {
     status: Atomic<BatchStatus>,
+    /// The outcome of each event of the batch, if tracked.
+    outcomes: Option<Arc<EventOutcomes>>,
     notifier: Option<oneshot::Sender<BatchStatus>>,
 }
 
@@ -183,9 +227,27 @@ impl BatchNotifier {
     /// Create a new `BatchNotifier` along with the receiver used to
     /// await its finalization status.
     pub fn new_with_receiver() -> (Arc<Self>, BatchStatusReceiver) {
+        Self::new_with_outcomes(None)
+    }
+
+    /// Create a new `BatchNotifier` tracking the outcome of each of the
+    /// given number of events, along with the receiver used to await its
+    /// finalization status and the outcomes of the events, complete once
+    /// the receiver resolves. The finalizers of the events are created
+    /// with [`EventFinalizer::new_tracked`].
+    pub fn new_tracking_events(
+        count: usize,
+    ) -> (Arc<Self>, BatchStatusReceiver, Arc<EventOutcomes>) {
+        let outcomes = Arc::new(EventOutcomes::new(count));
+        let (batch, receiver) = Self::new_with_outcomes(Some(Arc::clone(&outcomes)));
+        (batch, receiver, outcomes)
+    }
+
+    fn new_with_outcomes(outcomes: Option<Arc<EventOutcomes>>) -> (Arc<Self>, BatchStatusReceiver) {
         let (sender, receiver) = oneshot::channel();
         let notifier = Self {
             status: Atomic::new(BatchStatus::Delivered),
+            outcomes,
             notifier: Some(sender),
         };
         (Arc::new(notifier), BatchStatusReceiver(receiver))
@@ -231,6 +293,23 @@ impl BatchNotifier {
         }
     }
 
+    /// Record the status of the event at the given position in this
+    /// batch, if it tracks the outcome of each of its events.
+    fn record_status(&self, index: u32, status: EventStatus) {
+        if let Some(outcomes) = &self.outcomes {
+            outcomes.record_status(index, status);
+        }
+    }
+
+    /// Record the details of the rejection of the event at the given
+    /// position in this batch, if it tracks the outcome of each of its
+    /// events.
+    fn record_rejection(&self, index: u32, rejection: &Rejection) {
+        if let Some(outcomes) = &self.outcomes {
+            outcomes.record_rejection(index, rejection);
+        }
+    }
+
     /// Send this notifier's status up to the source.
     fn send_status(&mut self) {
         if let Some(notifier) = self.notifier.take() {
@@ -280,6 +359,66 @@ impl BatchStatus {
     }
 }
 
+/// The outcome of an event of a batch tracking the outcome of each of its
+/// events.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EventOutcome {
+    /// The status of the event.
+    pub status: EventStatus,
+    /// The details of the rejection of the event, if it was rejected.
+    pub rejection: Option<Rejection>,
+}
+
+/// The outcome of each event of a batch tracking them, filled in as the
+/// events are finalized.
+#[derive(Debug)]
+pub struct EventOutcomes(Mutex<Vec<EventOutcome>>);
+
+impl EventOutcomes {
+    fn new(count: usize) -> Self {
+        Self(Mutex::new(vec![EventOutcome::default(); count]))
+    }
+
+    /// Take the outcome of each event, in the order of the events in their
+    /// batch.
+    pub fn take(&self) -> Vec<EventOutcome> {
+        mem::take(&mut *self.lock())
+    }
+
+    fn record_status(&self, index: u32, status: EventStatus) {
+        // A finalizer updates its batch more than once, only the first time
+        // with the status of its event, and copies of an event share its
+        // position in the batch. Copies dropped without being finalized
+        // leave the outcome as is.
+        if !matches!(status, EventStatus::Dropped | EventStatus::Recorded) {
+            if let Some(outcome) = self.lock().get_mut(index as usize) {
+                outcome.status = outcome.status.update(status);
+            }
+        }
+    }
+
+    /// Record the details of the rejection of an event, keeping the first
+    /// one of each event.
+    fn record_rejection(&self, index: u32, rejection: &Rejection) {
+        if let Some(outcome) = self.lock().get_mut(index as usize) {
+            outcome.rejection.get_or_insert_with(|| rejection.clone());
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<EventOutcome>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The details of a permanent rejection of an event by a sink.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rejection {
+    /// The reason the event was rejected.
+    pub reason: String,
+    /// The status code of the response rejecting the event, if any.
+    pub status: Option<u16>,
+}
+
 /// The status of an individual event.
 #[derive(Atom, Copy, Clone, Debug, Derivative, Deserialize, Eq, PartialEq, Serialize)]
 #[derivative(Default)]
@@ -460,6 +599,70 @@ mod tests {
         assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
     }
 
+    #[tokio::test]
+    async fn records_outcome_of_each_event() {
+        let (batch, receiver, outcomes) = BatchNotifier::new_tracking_events(3);
+        let finalizers = (0..3)
+            .map(|index| {
+                EventFinalizers::new(EventFinalizer::new_tracked(Arc::clone(&batch), index))
+            })
+            .collect::<Vec<_>>();
+        drop(batch);
+
+        let bad_request = Rejection {
+            reason: "400 Bad Request".into(),
+            status: Some(400),
+        };
+        let too_large = Rejection {
+            reason: "413 Payload Too Large".into(),
+            status: Some(413),
+        };
+        finalizers[0].reject(&bad_request);
+        finalizers[0].reject(&too_large);
+        finalizers[1].reject(&too_large);
+        finalizers[2].update_status(EventStatus::Delivered);
+        drop(finalizers);
+
+        assert_eq!(receiver.await, BatchStatus::Rejected);
+        assert_eq!(
+            outcomes.take(),
+            vec![
+                EventOutcome {
+                    status: EventStatus::Rejected,
+                    rejection: Some(bad_request),
+                },
+                EventOutcome {
+                    status: EventStatus::Rejected,
+                    rejection: Some(too_large),
+                },
+                EventOutcome {
+                    status: EventStatus::Delivered,
+                    rejection: None,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn merges_outcomes_of_copies() {
+        let (batch, receiver, outcomes) = BatchNotifier::new_tracking_events(1);
+        let copy1 = EventFinalizers::new(EventFinalizer::new_tracked(Arc::clone(&batch), 0));
+        let copy2 = EventFinalizers::new(EventFinalizer::new_tracked(batch, 0));
+        copy1.update_status(EventStatus::Delivered);
+        copy2.update_status(EventStatus::Errored);
+        drop(copy1);
+        drop(copy2);
+
+        assert_eq!(receiver.await, BatchStatus::Errored);
+        assert_eq!(
+            outcomes.take(),
+            vec![EventOutcome {
+                status: EventStatus::Errored,
+                rejection: None,
+            }]
+        );
+    }
+
     fn make_finalizer() -> (EventFinalizers, BatchStatusReceiver) {
         let (batch, receiver) = BatchNotifier::new_with_receiver();
         let finalizer = EventFinalizers::new(EventFinalizer::new(batch));
@@ -14,8 +14,8 @@ use crate::ByteSizeOf;
 pub use ::value::Value;
 pub use array::{into_event_stream, EventArray, EventContainer, LogArray, MetricArray, TraceArray};
 pub use finalization::{
-    BatchNotifier, BatchStatus, BatchStatusReceiver, EventFinalizer, EventFinalizers, EventStatus,
-    Finalizable,
+    BatchNotifier, BatchStatus, BatchStatusReceiver, EventFinalizer, EventFinalizers, EventOutcome,
+    EventOutcomes, EventStatus, Finalizable, Rejection,
 };
 pub use log_event::LogEvent;
 pub use metadata::{EventMetadata, WithMetadata};
@@ -131,6 +131,27 @@ impl Controller {
         CONTROLLER.get().ok_or(Error::NotInitialized)
     }
 
+    /// Remove the series of the metric named `name` with all the given tags, such as the gauges
+    /// of a resource that is no longer tracked.
+    pub fn remove_series(&self, name: &str, tags: &[(&str, &str)]) {
+        self.recorder.with_registry(|registry| {
+            let mut series = Vec::new();
+            registry.visit(|kind, (key, _)| {
+                let matches = key.name().to_string() == name
+                    && tags.iter().all(|(tag, value)| {
+                        key.labels()
+                            .any(|label| label.key() == *tag && label.value() == *value)
+                    });
+                if matches {
+                    series.push((kind, key.clone()));
+                }
+            });
+            for (kind, key) in series {
+                registry.delete(kind, &key);
+            }
+        });
+    }
+
     /// Take a snapshot of all gathered metrics and expose them as metric
     /// [`Event`](crate::event::Event)s.
     pub fn capture_metrics(&self) -> Vec<Metric> {
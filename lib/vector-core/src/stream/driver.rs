@@ -14,7 +14,7 @@ use vector_buffers::{Ackable, Acker};
 
 use super::FuturesUnorderedChunked;
 use crate::{
-    event::{EventStatus, Finalizable},
+    event::{EventStatus, Finalizable, Rejection},
     internal_event::{emit, EventsSent},
 };
 
@@ -137,6 +137,11 @@ impl AcknowledgementTracker {
 pub trait DriverResponse {
     fn event_status(&self) -> EventStatus;
     fn events_sent(&self) -> EventsSent;
+
+    /// The details of the rejection of the request, if the service rejected it.
+    fn rejection(&self) -> Option<Rejection> {
+        None
+    }
 }
 
 /// Drives the interaction between a stream of items and a service which processes them
@@ -172,7 +177,7 @@ where
     St: Stream,
     St::Item: Ackable + Finalizable,
     Svc: Service<St::Item>,
-    Svc::Error: fmt::Debug + 'static,
+    Svc::Error: fmt::Debug + Into<crate::Error> + 'static,
     Svc::Future: Send + 'static,
     Svc::Response: DriverResponse,
 {
@@ -281,12 +286,16 @@ where
                             .map(move |result: Result<Svc::Response, Svc::Error>| {
                                 match result {
                                     Err(error) => {
-                                        error!(message = "Service call failed.", ?error, request_id);
-                                        finalizers.update_status(EventStatus::Rejected);
+                                        let error: crate::Error = error.into();
+                                        error!(message = "Service call failed.", %error, request_id);
+                                        finalizers.update_status(EventStatus::Errored);
                                     },
                                     Ok(response) => {
                                         trace!(message = "Service call succeeded.", request_id);
-                                        finalizers.update_status(response.event_status());
+                                        match response.rejection() {
+                                            Some(rejection) => finalizers.reject(&rejection),
+                                            None => finalizers.update_status(response.event_status()),
+                                        }
                                         if response.event_status() == EventStatus::Delivered {
                                             emit(response.events_sent());
                                         }
@@ -318,6 +327,7 @@ where
 mod tests {
     use std::{
         collections::VecDeque,
+        convert::Infallible,
         future::Future,
         iter::repeat_with,
         num::NonZeroUsize,
@@ -430,7 +440,7 @@ mod tests {
 
     impl Service<DelayRequest> for DelayService {
         type Response = DelayResponse;
-        type Error = ();
+        type Error = Infallible;
         type Future =
             Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + Sync>>;
 
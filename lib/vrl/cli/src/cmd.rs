@@ -4,19 +4,27 @@ use std::{
     io::{self, Read},
     iter::IntoIterator,
     path::PathBuf,
+    sync::Arc,
 };
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use vector_common::TimeZone;
-use vrl::{diagnostic::Formatter, state, Program, Runtime, Target, Value, VrlRuntime};
+use vrl::{diagnostic::Formatter, state, Jit, Program, Runtime, Target, Value, VrlRuntime};
 
 #[cfg(feature = "repl")]
 use super::repl;
-use super::Error;
+use super::{fmt, test, Error};
 
 #[derive(Parser, Debug)]
-#[clap(name = "VRL", about = "Vector Remap Language CLI")]
+#[clap(
+    name = "VRL",
+    about = "Vector Remap Language CLI",
+    args_conflicts_with_subcommands = true
+)]
 pub struct Opts {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// The VRL program to execute. The program ".foo = true", for example, sets the event object's
     /// `foo` field to `true`.
     #[clap(name = "PROGRAM")]
@@ -44,14 +52,18 @@ pub struct Opts {
     runtime: VrlRuntime,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Test VRL programs against fixtures of input events and their expected output events.
+    Test(test::Opts),
+
+    /// Format VRL programs, and the programs of the `remap` transforms in configuration files.
+    Fmt(fmt::Opts),
+}
+
 impl Opts {
     fn timezone(&self) -> Result<TimeZone, Error> {
-        if let Some(ref tz) = self.timezone {
-            TimeZone::parse(tz)
-                .ok_or_else(|| Error::Parse(format!("unable to parse timezone: {}", tz)))
-        } else {
-            Ok(TimeZone::default())
-        }
+        parse_timezone(self.timezone.as_deref())
     }
 
     fn read_program(&self) -> Result<String, Error> {
@@ -85,6 +97,12 @@ impl Opts {
 }
 
 pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    match &opts.command {
+        Some(Command::Test(opts)) => return test::cmd(opts),
+        Some(Command::Fmt(opts)) => return fmt::cmd(opts),
+        None => {}
+    }
+
     match run(opts) {
         Ok(_) => exitcode::OK,
         Err(err) => {
@@ -159,7 +177,15 @@ fn repl(_objects: Vec<Value>, _timezone: &TimeZone, _vrl_runtime: VrlRuntime) ->
     Err(Error::ReplFeature)
 }
 
-fn execute(
+pub(crate) fn parse_timezone(tz: Option<&str>) -> Result<TimeZone, Error> {
+    match tz {
+        Some(tz) => TimeZone::parse(tz)
+            .ok_or_else(|| Error::Parse(format!("unable to parse timezone: {}", tz))),
+        None => Ok(TimeZone::default()),
+    }
+}
+
+pub(crate) fn execute(
     object: &mut impl Target,
     program: &Program,
     timezone: &TimeZone,
@@ -171,18 +197,27 @@ fn execute(
         VrlRuntime::Vm => {
             let vm = runtime
                 .compile(functions, program, Default::default())
-                .unwrap();
+                .map_err(Error::Vm)?;
             runtime
                 .run_vm(&vm, object, timezone)
                 .map_err(Error::Runtime)
         }
+        VrlRuntime::Jit => {
+            let vm = runtime
+                .compile(functions, program, Default::default())
+                .map_err(Error::Vm)?;
+            let jit = Jit::new(Arc::new(vm)).map_err(Error::Jit)?;
+            runtime
+                .run_jit(&jit, object, timezone)
+                .map_err(Error::Runtime)
+        }
         VrlRuntime::Ast => runtime
             .resolve(object, program, timezone)
             .map_err(Error::Runtime),
     }
 }
 
-fn serde_to_vrl(value: serde_json::Value) -> Value {
+pub(crate) fn serde_to_vrl(value: serde_json::Value) -> Value {
     use serde_json::Value as JsonValue;
 
     match value {
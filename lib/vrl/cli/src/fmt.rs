@@ -0,0 +1,755 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use vrl::diagnostic::{DiagnosticError, Formatter};
+
+use super::Error;
+
+/// Format VRL programs, and the programs of the `remap` transforms in TOML and
+/// YAML configuration files.
+///
+/// The program is read from stdin and written formatted to stdout if no files
+/// are given.
+#[derive(Parser, Debug)]
+pub struct Opts {
+    /// The VRL programs (`.vrl`) and configuration files (`.toml`, `.yaml` or `.yml`) to
+    /// format in place.
+    #[clap(name = "PATH", parse(from_os_str))]
+    paths: Vec<PathBuf>,
+
+    /// Don't write the formatted files, but list the files that aren't formatted and exit with
+    /// an error if there are any.
+    #[clap(short, long)]
+    check: bool,
+}
+
+/// The format of a file holding VRL programs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Vrl,
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    /// The format of a file, from its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "vrl" => Some(Self::Vrl),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+}
+
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    match run(opts) {
+        Ok(0) => exitcode::OK,
+        Ok(_) => exitcode::DATAERR,
+        Err(err) => {
+            #[allow(clippy::print_stderr)]
+            {
+                eprintln!("{}", err);
+            }
+            exitcode::SOFTWARE
+        }
+    }
+}
+
+/// Format the files, returning the number of files that aren't formatted
+/// when checking them.
+#[allow(clippy::print_stdout)]
+fn run(opts: &Opts) -> Result<usize, Error> {
+    if opts.paths.is_empty() {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source)?;
+
+        let formatted = format(&source, Format::Vrl)?;
+        if opts.check {
+            return Ok(usize::from(formatted != source));
+        }
+
+        print!("{}", formatted);
+        return Ok(0);
+    }
+
+    let mut unformatted = 0;
+    for path in &opts.paths {
+        let format = Format::from_path(path).ok_or_else(|| {
+            Error::Format(format!(
+                "{}: unknown file type, expected a .vrl, .toml, .yaml or .yml file",
+                path.display()
+            ))
+        })?;
+
+        let source = fs::read_to_string(path)?;
+        let formatted = match self::format(&source, format) {
+            Ok(formatted) => formatted,
+            Err(Error::Parse(diagnostics)) => {
+                return Err(Error::Parse(format!(
+                    "{}:\n{}",
+                    path.display(),
+                    diagnostics
+                )))
+            }
+            Err(err) => return Err(Error::Format(format!("{}: {}", path.display(), err))),
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if opts.check {
+            println!("{}", path.display());
+            unformatted += 1;
+        } else {
+            fs::write(path, formatted)?;
+        }
+    }
+
+    Ok(unformatted)
+}
+
+/// Format a VRL program, or the programs of the `remap` transforms in a
+/// configuration file.
+///
+/// Only the `source` of transforms defined in their own table, such as
+/// `[transforms.my_remap]` in TOML, or as a literal block scalar (`|`) in YAML,
+/// is formatted. The rest of the configuration, including inline tables, flow
+/// mappings and aliases, is left as it is.
+pub fn format(source: &str, format: Format) -> Result<String, Error> {
+    match format {
+        Format::Vrl => format_program(source),
+        Format::Toml => format_toml(source),
+        Format::Yaml => format_yaml(source),
+    }
+}
+
+fn format_program(source: &str) -> Result<String, Error> {
+    vrl::format::format(source).map_err(|err| match err {
+        vrl::format::Error::Parse(err) => {
+            let diagnostics = vec![Box::new(err) as Box<dyn DiagnosticError>];
+            Error::Parse(Formatter::new(source, diagnostics).colored().to_string())
+        }
+        err => Error::Format(err.to_string()),
+    })
+}
+
+/// Format the program of a `remap` transform, unless it's already formatted.
+fn format_remap_source(source: &str) -> Result<Option<String>, Error> {
+    let formatted = format_program(source)?;
+
+    Ok(Some(formatted).filter(|formatted| formatted != source))
+}
+
+fn format_toml(source: &str) -> Result<String, Error> {
+    let config: toml::Value =
+        toml::from_str(source).map_err(|err| Error::Format(format!("invalid TOML: {}", err)))?;
+
+    let is_remap = |table: &[String]| match table {
+        [transforms, id] if transforms == "transforms" => {
+            config
+                .get("transforms")
+                .and_then(|transforms| transforms.get(id))
+                .and_then(|transform| transform.get("type"))
+                .and_then(toml::Value::as_str)
+                == Some("remap")
+        }
+        _ => false,
+    };
+
+    let mut output = String::with_capacity(source.len());
+    let mut table = vec![];
+    let mut position = 0;
+
+    while position < source.len() {
+        let line_end = source[position..]
+            .find('\n')
+            .map_or(source.len(), |i| position + i + 1);
+        let line = &source[position..line_end];
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("[[") {
+            table.clear();
+        } else if trimmed.starts_with('[') {
+            table = toml_table(trimmed);
+        } else if let Some((key, value_start)) = toml_key(line) {
+            // Strings are skipped as a whole, as multiline strings could hold
+            // lines looking like tables or keys.
+            let start = position + value_start;
+            if let Some(end) = toml_string_end(source, start) {
+                let literal = &source[start..end];
+                let formatted = if key == "source" && is_remap(&table) {
+                    toml_string(literal)
+                        .map(|program| format_remap_source(&program))
+                        .transpose()?
+                        .flatten()
+                        .and_then(|formatted| encode_toml_string(literal, &formatted))
+                } else {
+                    None
+                };
+
+                output.push_str(&source[position..start]);
+                output.push_str(formatted.as_deref().unwrap_or(literal));
+                position = end;
+                continue;
+            }
+        }
+
+        output.push_str(line);
+        position = line_end;
+    }
+
+    Ok(output)
+}
+
+/// The path of the table of a header, such as `["transforms", "foo"]` for
+/// `[transforms.foo]`.
+fn toml_table(header: &str) -> Vec<String> {
+    let mut path = vec![];
+    let mut value = match toml::from_str::<toml::Value>(header) {
+        Ok(value) => value,
+        Err(_) => return path,
+    };
+
+    while let toml::Value::Table(table) = value {
+        match table.into_iter().next() {
+            Some((key, inner)) => {
+                path.push(key);
+                value = inner;
+            }
+            None => break,
+        }
+    }
+
+    path
+}
+
+/// The key of a line, and the offset of its value.
+fn toml_key(line: &str) -> Option<(&str, usize)> {
+    let trimmed = line.trim_start();
+    if !trimmed.starts_with(|c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '"' | '\'')) {
+        return None;
+    }
+
+    let equals = trimmed.find('=')?;
+    let key = trimmed[..equals]
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'');
+    let value = &trimmed[equals + 1..];
+    let value_start = line.len() - value.trim_start().len();
+
+    Some((key, value_start))
+}
+
+/// The end of the string starting at the offset, if there's one.
+fn toml_string_end(source: &str, start: usize) -> Option<usize> {
+    let rest = &source[start..];
+
+    let end = if let Some(string) = rest.strip_prefix("'''") {
+        string.find("'''").map(|i| i + 6)
+    } else if rest.starts_with("\"\"\"") {
+        basic_string_end(rest, "\"\"\"")
+    } else if let Some(string) = rest.strip_prefix('\'') {
+        string
+            .find(['\'', '\n'])
+            .filter(|i| string[*i..].starts_with('\''))
+            .map(|i| i + 2)
+    } else if rest.starts_with('"') {
+        basic_string_end(rest, "\"")
+    } else {
+        None
+    };
+
+    end.map(|end| start + end)
+}
+
+fn basic_string_end(rest: &str, delimiter: &str) -> Option<usize> {
+    let mut chars = rest.char_indices().skip(delimiter.len());
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '\n' if delimiter == "\"" => return None,
+            _ if rest[i..].starts_with(delimiter) => return Some(i + delimiter.len()),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// The value of a string literal.
+fn toml_string(literal: &str) -> Option<String> {
+    let value: toml::Value = toml::from_str(&format!("value = {}", literal)).ok()?;
+
+    value.get("value")?.as_str().map(ToOwned::to_owned)
+}
+
+/// Write a formatted program as a string literal of the same kind as the
+/// original one, keeping the indentation of multiline strings, unless the
+/// program can't be written as such a string.
+fn encode_toml_string(literal: &str, program: &str) -> Option<String> {
+    for delimiter in ["'''", "\"\"\""] {
+        if let Some(raw) = literal
+            .strip_prefix(delimiter)
+            .and_then(|literal| literal.strip_suffix(delimiter))
+        {
+            let mut lines = raw.split('\n').skip(1).collect::<Vec<_>>();
+
+            // The indentation of the closing delimiter, if it's on its own line.
+            let closing_indent = match lines.last() {
+                Some(last) if last.trim().is_empty() => lines.pop(),
+                _ => None,
+            };
+            let indent = lines
+                .iter()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| &line[..line.len() - line.trim_start().len()])
+                .min_by_key(|indent| indent.len())
+                .unwrap_or_default();
+
+            let mut body = String::from("\n");
+            for line in program.lines() {
+                if !line.is_empty() {
+                    body.push_str(indent);
+                    body.push_str(line);
+                }
+                body.push('\n');
+            }
+            match closing_indent {
+                Some(closing_indent) => body.push_str(closing_indent),
+                None if !body.ends_with("\"\n") => {
+                    body.pop();
+                }
+                None => {}
+            }
+
+            let body = match delimiter {
+                "'''" if body.contains("'''") => return None,
+                "'''" => body,
+                _ => body.replace('\\', "\\\\").replace("\"\"\"", "\"\"\\\""),
+            };
+
+            return Some(format!("{}{}{}", delimiter, body, delimiter));
+        }
+    }
+
+    let program = program.trim_end_matches('\n');
+    if literal.starts_with('\'') {
+        return (!program.contains(['\'', '\n'])).then(|| format!("'{}'", program));
+    }
+
+    let escaped = program
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    Some(format!("\"{}\"", escaped))
+}
+
+fn format_yaml(source: &str) -> Result<String, Error> {
+    let config: serde_yaml::Value = serde_yaml::from_str(source)
+        .map_err(|err| Error::Format(format!("invalid YAML: {}", err)))?;
+
+    let remap_source = |path: &[&str]| match path {
+        ["transforms", id, "source"] => {
+            let transform = config.get("transforms")?.get(id)?;
+            match transform.get("type")?.as_str()? {
+                "remap" => transform.get("source")?.as_str(),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+
+    let lines = source.split_inclusive('\n').collect::<Vec<_>>();
+    let mut output = String::with_capacity(source.len());
+    let mut keys: Vec<(usize, &str)> = vec![];
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        output.push_str(line);
+        i += 1;
+
+        let (indent, key, value) = match yaml_key(line) {
+            Some(key) => key,
+            None => continue,
+        };
+        keys.retain(|(key_indent, _)| *key_indent < indent);
+        keys.push((indent, key));
+
+        // The indicator of a block can follow an anchor, such as `&program |`.
+        let value = match value.strip_prefix('&') {
+            Some(anchored) => anchored
+                .split_once(' ')
+                .map_or("", |(_, value)| value.trim_start()),
+            None => value,
+        };
+        if !value.starts_with(['|', '>']) {
+            continue;
+        }
+
+        // The block holds the lines indented more than its key, and the blank
+        // lines between them. Blank lines after it are left as they are.
+        let start = i;
+        let mut end = i;
+        while i < lines.len() && (lines[i].trim().is_empty() || indentation(lines[i]) > indent) {
+            i += 1;
+            if !lines[i - 1].trim().is_empty() {
+                end = i;
+            }
+        }
+        i = end;
+
+        // Only literal blocks keep the line breaks of the program.
+        let path = keys.iter().map(|(_, key)| *key).collect::<Vec<_>>();
+        let formatted = match remap_source(&path) {
+            Some(program) if matches!(value, "|" | "|-" | "|+") => format_remap_source(program)?,
+            _ => None,
+        };
+
+        match formatted {
+            Some(formatted) => {
+                let block_indent = &lines[start][..indentation(lines[start])];
+                for line in formatted.lines() {
+                    if !line.is_empty() {
+                        output.push_str(block_indent);
+                        output.push_str(line);
+                    }
+                    output.push('\n');
+                }
+            }
+            None => output.push_str(&lines[start..end].concat()),
+        }
+    }
+
+    Ok(output)
+}
+
+/// The indentation, key and value of a line holding a key of a mapping.
+fn yaml_key(line: &str) -> Option<(usize, &str, &str)> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() || trimmed.starts_with(['#', '-']) {
+        return None;
+    }
+
+    let (key, value) = match trimmed.find(": ") {
+        Some(i) => (&trimmed[..i], trimmed[i + 2..].trim()),
+        None => (trimmed.strip_suffix(':')?, ""),
+    };
+    let key = key.trim().trim_matches(|c| c == '"' || c == '\'');
+
+    Some((indentation(line), key, value))
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn toml() {
+        let source = indoc! {r#"
+            [transforms.remap]
+            type = "remap"
+            inputs = ["in"]
+            source = '''
+              .a=1
+              if .b {
+              .c = 2
+              }
+            '''
+
+            [transforms.escaped]
+            type = "remap"
+            inputs = ["in"]
+            source = """.a=match(.b,r'\\d')"""
+
+            [transforms.single]
+            type = "remap"
+            inputs = ["in"]
+            source = ".a=1"
+
+            [transforms.not_remap]
+            type = "filter"
+            source = ".a=1"
+        "#};
+
+        let formatted = indoc! {r#"
+            [transforms.remap]
+            type = "remap"
+            inputs = ["in"]
+            source = '''
+              .a = 1
+              if .b {
+                  .c = 2
+              }
+            '''
+
+            [transforms.escaped]
+            type = "remap"
+            inputs = ["in"]
+            source = """
+            .a = match(.b, r'\\d')"""
+
+            [transforms.single]
+            type = "remap"
+            inputs = ["in"]
+            source = ".a = 1"
+
+            [transforms.not_remap]
+            type = "filter"
+            source = ".a=1"
+        "#};
+
+        assert_eq!(format(source, Format::Toml).unwrap(), formatted);
+        assert_eq!(format(formatted, Format::Toml).unwrap(), formatted);
+    }
+
+    #[test]
+    fn yaml() {
+        let source = indoc! {r#"
+            transforms:
+              remap:
+                type: remap
+                inputs: [in]
+                source: |
+                  .a=1
+                  if .b {
+                  .c = 2
+                  }
+
+              other:
+                type: filter
+                source: |
+                  .a=1
+        "#};
+
+        let formatted = indoc! {r#"
+            transforms:
+              remap:
+                type: remap
+                inputs: [in]
+                source: |
+                  .a = 1
+                  if .b {
+                      .c = 2
+                  }
+
+              other:
+                type: filter
+                source: |
+                  .a=1
+        "#};
+
+        assert_eq!(format(source, Format::Yaml).unwrap(), formatted);
+        assert_eq!(format(formatted, Format::Yaml).unwrap(), formatted);
+    }
+
+    #[test]
+    fn toml_multiline_strings() {
+        // Lines of multiline strings looking like tables or keys are skipped.
+        let source = indoc! {r#"
+            [transforms.other]
+            type = "filter"
+            condition = """
+            [transforms.remap]
+            source = ".a=1"
+            """
+
+            [transforms.remap]
+            type = "remap"
+            description = '''
+            source = ".a=1"
+            '''
+            source = """
+              .a=1 # source = ".b=2"
+              .b = "[transforms.other]"
+            """
+        "#};
+
+        let formatted = indoc! {r#"
+            [transforms.other]
+            type = "filter"
+            condition = """
+            [transforms.remap]
+            source = ".a=1"
+            """
+
+            [transforms.remap]
+            type = "remap"
+            description = '''
+            source = ".a=1"
+            '''
+            source = """
+              .a = 1 # source = ".b=2"
+              .b = "[transforms.other]"
+            """
+        "#};
+
+        assert_eq!(format(source, Format::Toml).unwrap(), formatted);
+        assert_eq!(format(formatted, Format::Toml).unwrap(), formatted);
+    }
+
+    #[test]
+    fn toml_inline_tables() {
+        // Transforms not defined in their own table are left as they are.
+        let source = indoc! {r#"
+            [transforms]
+            inline = { type = "remap", inputs = ["in"], source = ".a=1" }
+            dotted.type = "remap"
+            dotted.source = ".a=1"
+
+            [transforms.remap]
+            type = "remap"
+            inputs = [
+              "in",
+            ]
+            source = ".a=1" # comment
+            options = { source = ".a=1" }
+        "#};
+
+        let formatted = indoc! {r#"
+            [transforms]
+            inline = { type = "remap", inputs = ["in"], source = ".a=1" }
+            dotted.type = "remap"
+            dotted.source = ".a=1"
+
+            [transforms.remap]
+            type = "remap"
+            inputs = [
+              "in",
+            ]
+            source = ".a = 1" # comment
+            options = { source = ".a=1" }
+        "#};
+
+        assert_eq!(format(source, Format::Toml).unwrap(), formatted);
+    }
+
+    #[test]
+    fn yaml_anchors() {
+        let source = indoc! {r#"
+            transforms:
+              remap:
+                type: remap
+                source: &program |
+                  .a=1
+              copy:
+                type: remap
+                source: *program
+        "#};
+
+        let formatted = indoc! {r#"
+            transforms:
+              remap:
+                type: remap
+                source: &program |
+                  .a = 1
+              copy:
+                type: remap
+                source: *program
+        "#};
+
+        assert_eq!(format(source, Format::Yaml).unwrap(), formatted);
+        assert_eq!(format(formatted, Format::Yaml).unwrap(), formatted);
+    }
+
+    #[test]
+    fn yaml_block_scalars() {
+        // Only literal block scalars without an indentation indicator are
+        // formatted, the lines of other blocks are skipped.
+        let source = indoc! {r#"
+            transforms:
+              strip:
+                type: remap
+                source: |-
+                  .a=1
+              keep:
+                type: remap
+                source: |+
+                  .a=1
+
+              indicator:
+                type: remap
+                source: |2
+                    .a=1
+              folded:
+                type: remap
+                source: >
+                  .a=1
+              quoted:
+                type: remap
+                description: "multiline
+                  source: |
+                  .a=1"
+                source: ".a=1"
+              flow: { type: remap, source: ".a=1" }
+              other:
+                type: filter
+                condition: |
+                  transforms:
+                    remap:
+                      source: |
+                        .a=1
+        "#};
+
+        let formatted = indoc! {r#"
+            transforms:
+              strip:
+                type: remap
+                source: |-
+                  .a = 1
+              keep:
+                type: remap
+                source: |+
+                  .a = 1
+
+              indicator:
+                type: remap
+                source: |2
+                    .a=1
+              folded:
+                type: remap
+                source: >
+                  .a=1
+              quoted:
+                type: remap
+                description: "multiline
+                  source: |
+                  .a=1"
+                source: ".a=1"
+              flow: { type: remap, source: ".a=1" }
+              other:
+                type: filter
+                condition: |
+                  transforms:
+                    remap:
+                      source: |
+                        .a=1
+        "#};
+
+        assert_eq!(format(source, Format::Yaml).unwrap(), formatted);
+        assert_eq!(format(formatted, Format::Yaml).unwrap(), formatted);
+    }
+
+    #[test]
+    fn invalid_program() {
+        let source = "[transforms.remap]\ntype = \"remap\"\nsource = \".a = \"\n";
+
+        assert!(matches!(format(source, Format::Toml), Err(Error::Parse(_))));
+    }
+}
@@ -0,0 +1,35 @@
+use chrono::SecondsFormat;
+use vrl::{prelude::VrlValueConvert, Value};
+
+/// Convert a VRL value to JSON, so that it can be compared to the expected
+/// JSON result of a test.
+pub fn vrl_value_to_json_value(value: Value) -> serde_json::Value {
+    use serde_json::Value::*;
+
+    match value {
+        v @ Value::Bytes(_) => String(v.try_bytes_utf8_lossy().unwrap().into_owned()),
+        Value::Integer(v) => v.into(),
+        Value::Float(v) => v.into_inner().into(),
+        Value::Boolean(v) => v.into(),
+        Value::Object(v) => v
+            .into_iter()
+            .map(|(k, v)| (k, vrl_value_to_json_value(v)))
+            .collect::<serde_json::Value>(),
+        Value::Array(v) => v
+            .into_iter()
+            .map(vrl_value_to_json_value)
+            .collect::<serde_json::Value>(),
+        Value::Timestamp(v) => v.to_rfc3339_opts(SecondsFormat::AutoSi, true).into(),
+        Value::Regex(v) => v.to_string().into(),
+        Value::Null => Null,
+    }
+}
+
+/// The coloured line diff between the pretty-printed expected and actual
+/// JSON values.
+pub fn json_diff(want: &serde_json::Value, got: &serde_json::Value) -> String {
+    let want = serde_json::to_string_pretty(want).unwrap();
+    let got = serde_json::to_string_pretty(got).unwrap();
+
+    prettydiff::diff_lines(&want, &got).to_string()
+}
@@ -6,8 +6,11 @@
 #![deny(unused_comparisons)]
 
 pub mod cmd;
+pub mod fmt;
+pub mod json;
 #[cfg(feature = "repl")]
 mod repl;
+pub mod test;
 
 pub use cmd::{cmd, Opts};
 
@@ -28,4 +31,16 @@ pub enum Error {
 
     #[error("repl feature disabled, program input required")]
     ReplFeature,
+
+    #[error("test error: {}", .0)]
+    Test(String),
+
+    #[error("format error: {}", .0)]
+    Format(String),
+
+    #[error("vm error: {}", .0)]
+    Vm(String),
+
+    #[error("jit error: {}", .0)]
+    Jit(String),
 }
@@ -1,4 +1,7 @@
-use std::borrow::Cow::{self, Borrowed, Owned};
+use std::{
+    borrow::Cow::{self, Borrowed, Owned},
+    sync::Arc,
+};
 
 use indoc::indoc;
 use once_cell::sync::Lazy;
@@ -13,7 +16,7 @@ use rustyline::{
     Context, Editor, Helper,
 };
 use vector_common::TimeZone;
-use vrl::{diagnostic::Formatter, state, value, Runtime, Target, Value, VrlRuntime};
+use vrl::{diagnostic::Formatter, state, value, Jit, Runtime, Target, Value, VrlRuntime};
 
 // Create a list of all possible error values for potential docs lookup
 static ERRORS: Lazy<Vec<String>> = Lazy::new(|| {
@@ -168,6 +171,13 @@ fn execute(
                 .run_vm(&vm, object, timezone)
                 .map_err(|err| err.to_string())
         }
+        VrlRuntime::Jit => {
+            let vm = runtime.compile(stdlib::all(), &program, Default::default())?;
+            let jit = Jit::new(Arc::new(vm))?;
+            runtime
+                .run_jit(&jit, object, timezone)
+                .map_err(|err| err.to_string())
+        }
         VrlRuntime::Ast => runtime
             .resolve(object, &program, timezone)
             .map_err(|err| err.to_string()),
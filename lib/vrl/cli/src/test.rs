@@ -0,0 +1,332 @@
+#![allow(clippy::print_stdout)] // test output
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use ansi_term::Colour;
+use clap::Parser;
+use vector_common::TimeZone;
+use vrl::{
+    coverage::{Branch, Coverage},
+    diagnostic::{Formatter, Span},
+    state, Program, Runtime, VrlRuntime,
+};
+
+use super::{
+    cmd::{execute, parse_timezone, serde_to_vrl},
+    json::{json_diff, vrl_value_to_json_value},
+    Error,
+};
+
+const INPUT_SUFFIX: &str = ".input.json";
+const EXPECTED_SUFFIX: &str = ".expected.json";
+
+/// Test VRL programs against fixtures of input events and their expected
+/// output events.
+///
+/// The fixtures of a program such as `remap.vrl` are stored in the `remap`
+/// directory next to it, with each input event in a `<name>.input.json` file,
+/// and the event expected once the program ran in `<name>.expected.json`.
+#[derive(Parser, Debug)]
+pub struct Opts {
+    /// The VRL programs to test, or the directories searched for programs with fixtures.
+    #[clap(name = "PATH", default_value = ".", parse(from_os_str))]
+    paths: Vec<PathBuf>,
+
+    /// Write the output events to the expected output files, instead of comparing them.
+    #[clap(short, long)]
+    update: bool,
+
+    /// Don't print the difference between the expected and the output events.
+    #[clap(short, long)]
+    no_diff: bool,
+
+    /// The timezone used to parse dates.
+    #[clap(short = 'z', long)]
+    timezone: Option<String>,
+
+    /// Should we use the VM to evaluate the VRL. Coverage is only reported by the AST runtime.
+    #[clap(short, long = "runtime", default_value_t)]
+    runtime: VrlRuntime,
+}
+
+/// A VRL program and the directory holding its fixtures.
+struct Suite {
+    program: PathBuf,
+    fixtures: PathBuf,
+}
+
+impl Suite {
+    fn new(program: PathBuf) -> Option<Self> {
+        let fixtures = program.with_extension("");
+
+        fixtures.is_dir().then(|| Self { program, fixtures })
+    }
+
+    /// The names of the fixtures, in order.
+    fn fixtures(&self) -> Result<Vec<String>, Error> {
+        let mut names = vec![];
+        for entry in fs::read_dir(&self.fixtures)? {
+            let file_name = entry?.file_name();
+            if let Some(name) = file_name.to_string_lossy().strip_suffix(INPUT_SUFFIX) {
+                names.push(name.to_owned());
+            }
+        }
+
+        names.sort();
+        Ok(names)
+    }
+}
+
+pub fn cmd(opts: &Opts) -> exitcode::ExitCode {
+    match run(opts) {
+        Ok(0) => exitcode::OK,
+        Ok(_) => exitcode::DATAERR,
+        Err(err) => {
+            #[allow(clippy::print_stderr)]
+            {
+                eprintln!("{}", err);
+            }
+            exitcode::SOFTWARE
+        }
+    }
+}
+
+/// Run the tests, returning the number of failures.
+fn run(opts: &Opts) -> Result<usize, Error> {
+    let timezone = parse_timezone(opts.timezone.as_deref())?;
+
+    let mut suites = vec![];
+    for path in &opts.paths {
+        if path.is_dir() {
+            let mut programs = vec![];
+            find_programs(path, &mut programs)?;
+            suites.extend(programs.into_iter().filter_map(Suite::new));
+        } else {
+            let suite = Suite::new(path.clone()).ok_or_else(|| {
+                Error::Test(format!(
+                    "no fixtures found for {}, expected a {} directory",
+                    path.display(),
+                    path.with_extension("").display()
+                ))
+            })?;
+            suites.push(suite);
+        }
+    }
+
+    if suites.is_empty() {
+        return Err(Error::Test(
+            "no VRL programs with fixtures found".to_owned(),
+        ));
+    }
+
+    suites.sort_by(|a, b| a.program.cmp(&b.program));
+    suites.dedup_by(|a, b| a.program == b.program);
+
+    let mut failed_count = 0;
+    for suite in &suites {
+        failed_count += test_suite(suite, opts, &timezone)?;
+    }
+
+    print_result(failed_count);
+
+    Ok(failed_count)
+}
+
+fn find_programs(dir: &Path, programs: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            find_programs(&path, programs)?;
+        } else if path
+            .extension()
+            .map_or(false, |extension| extension == "vrl")
+        {
+            programs.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the fixtures of a program, returning the number of failures.
+fn test_suite(suite: &Suite, opts: &Opts, timezone: &TimeZone) -> Result<usize, Error> {
+    println!(
+        "{}",
+        Colour::Fixed(3)
+            .bold()
+            .paint(suite.program.display().to_string())
+    );
+
+    let source = fs::read_to_string(&suite.program)?;
+
+    let mut state = state::Compiler::new();
+    state.enable_coverage();
+    if let Some(dir) = suite.program.parent() {
+        state.set_import_paths(vec![dir.to_owned()]);
+    }
+
+    let program = match vrl::compile_with_state(&source, &stdlib::all(), &mut state) {
+        Ok(program) => program,
+        Err(diagnostics) => {
+            println!("  {}", Colour::Red.bold().paint("FAILED (compilation)"));
+            println!("{}", Formatter::new(&source, diagnostics).colored());
+            return Ok(1);
+        }
+    };
+
+    let mut failed_count = 0;
+    for name in suite.fixtures()? {
+        if !test_fixture(suite, &name, &program, opts, timezone)? {
+            failed_count += 1;
+        }
+    }
+
+    match state.coverage() {
+        Some(coverage) if opts.runtime == VrlRuntime::Ast => print_coverage(&source, coverage),
+        _ => {}
+    }
+
+    Ok(failed_count)
+}
+
+/// Run a fixture of a program, returning whether it succeeded.
+fn test_fixture(
+    suite: &Suite,
+    name: &str,
+    program: &Program,
+    opts: &Opts,
+    timezone: &TimeZone,
+) -> Result<bool, Error> {
+    let dots = 60usize.saturating_sub(name.len());
+    print!("  {}{}", name, Colour::Fixed(240).paint(".".repeat(dots)));
+
+    let input = suite.fixtures.join(format!("{}{}", name, INPUT_SUFFIX));
+    let expected = suite.fixtures.join(format!("{}{}", name, EXPECTED_SUFFIX));
+
+    let mut object = serde_to_vrl(read_json(&input)?);
+    let runtime = Runtime::new(state::Runtime::default());
+    if let Err(err) = execute(
+        &mut object,
+        program,
+        timezone,
+        runtime,
+        stdlib::all(),
+        opts.runtime,
+    ) {
+        println!("{} (runtime)", Colour::Red.bold().paint("FAILED"));
+        println!("    {}", err);
+        return Ok(false);
+    }
+
+    let got = vrl_value_to_json_value(object);
+
+    if opts.update {
+        let mut output = serde_json::to_string_pretty(&got)?;
+        output.push('\n');
+        fs::write(&expected, output)?;
+
+        println!("{}", Colour::Cyan.bold().paint("UPDATED"));
+        return Ok(true);
+    }
+
+    if !expected.exists() {
+        println!(
+            "{} (missing expected output)",
+            Colour::Red.bold().paint("FAILED")
+        );
+        return Ok(false);
+    }
+
+    let want = read_json(&expected)?;
+    if got == want {
+        println!("{}", Colour::Green.bold().paint("OK"));
+        return Ok(true);
+    }
+
+    println!("{} (expectation)", Colour::Red.bold().paint("FAILED"));
+    if !opts.no_diff {
+        println!("  {}", json_diff(&want, &got));
+    }
+
+    Ok(false)
+}
+
+fn read_json(path: &Path) -> Result<serde_json::Value, Error> {
+    let json = fs::read_to_string(path)?;
+
+    serde_json::from_str(&json)
+        .map_err(|err| Error::Test(format!("unable to parse {}: {}", path.display(), err)))
+}
+
+fn print_coverage(source: &str, coverage: &Coverage) {
+    let statements = coverage.statements();
+    let branches = coverage.branches();
+
+    println!(
+        "  coverage: {}/{} statements, {}/{} branches",
+        statements.iter().filter(|(_, hits)| *hits > 0).count(),
+        statements.len(),
+        branches.iter().filter(|(_, hits)| *hits > 0).count(),
+        branches.len(),
+    );
+
+    for (span, _) in statements.iter().filter(|(_, hits)| *hits == 0) {
+        let line = format!("{} statement not executed", location(source, *span));
+        println!(
+            "    {}: {}",
+            Colour::Yellow.paint(line),
+            snippet(source, *span)
+        );
+    }
+
+    for (branch, _) in branches.iter().filter(|(_, hits)| *hits == 0) {
+        let span = branch.span();
+        let line = match branch {
+            Branch::Block(_) => format!("{} branch not taken", location(source, span)),
+            Branch::MissingElse(_) => {
+                format!("{} missing else branch not taken", location(source, span))
+            }
+        };
+        println!(
+            "    {}: {}",
+            Colour::Yellow.paint(line),
+            snippet(source, span)
+        );
+    }
+}
+
+fn location(source: &str, span: Span) -> String {
+    let line = source[..span.start()].matches('\n').count() + 1;
+
+    format!("line {}", line)
+}
+
+/// The first line of the code at the span.
+fn snippet(source: &str, span: Span) -> &str {
+    source[span.range()]
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+}
+
+fn print_result(failed_count: usize) {
+    println!();
+
+    if failed_count > 0 {
+        println!(
+            "  Overall result: {}\n\n    Number failed: {}\n",
+            Colour::Red.bold().paint("FAILED"),
+            failed_count
+        );
+    } else {
+        println!(
+            "  Overall result: {}\n",
+            Colour::Green.bold().paint("SUCCESS")
+        );
+    }
+}
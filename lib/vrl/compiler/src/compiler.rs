@@ -1,11 +1,20 @@
-use std::convert::TryFrom;
+use std::{collections::HashSet, convert::TryFrom};
 
 use chrono::{TimeZone, Utc};
-use diagnostic::DiagnosticError;
+use diagnostic::{DiagnosticError, SourceFile};
 use ordered_float::NotNan;
 use parser::ast::{self, AssignmentOp, Node};
 
-use crate::{expression::*, Function, Program, State, Value};
+use crate::{
+    coverage::{Counter, Coverage},
+    expression::*,
+    function::{
+        closure::FunctionClosure,
+        user::{self, Imported, UserFunction},
+    },
+    parser::Ident,
+    Function, Program, Span, State, TypeDef, Value,
+};
 
 pub(crate) type Errors = Vec<Box<dyn DiagnosticError>>;
 
@@ -15,6 +24,25 @@ pub(crate) struct Compiler<'a> {
     errors: Errors,
     fallible: bool,
     abortable: bool,
+
+    /// The user-defined functions whose bodies are being compiled, used to
+    /// reject recursive calls.
+    calls: Vec<Ident>,
+
+    /// The user-defined functions whose bodies failed to compile, which are
+    /// only reported once.
+    invalid_functions: HashSet<Ident>,
+
+    /// The user-defined functions whose bodies were compiled for a call.
+    compiled_functions: HashSet<Ident>,
+
+    /// Whether the body of an uncalled function is being compiled, whose
+    /// parameters are of any type.
+    uncalled_body: bool,
+
+    /// Whether the body of an imported function is being compiled, whose
+    /// coverage and types aren't recorded, as it's part of another file.
+    imported_body: bool,
 }
 
 impl<'a> Compiler<'a> {
@@ -25,6 +53,11 @@ impl<'a> Compiler<'a> {
             errors: vec![],
             fallible: false,
             abortable: false,
+            calls: vec![],
+            invalid_functions: HashSet::default(),
+            compiled_functions: HashSet::default(),
+            uncalled_body: false,
+            imported_body: false,
         }
     }
 
@@ -35,6 +68,8 @@ impl<'a> Compiler<'a> {
             .map(|expr| Box::new(expr) as _)
             .collect();
 
+        self.compile_uncalled_functions();
+
         if !self.errors.is_empty() {
             return Err(self.errors);
         }
@@ -66,7 +101,15 @@ impl<'a> Compiler<'a> {
                             self.errors.push(Box::new(err));
                         }
 
-                        Some(expr)
+                        Some(self.probe_statement(span, expr))
+                    }
+                    FunctionDefinition(node) => {
+                        self.compile_function_definition(node, None);
+                        None
+                    }
+                    Import(node) => {
+                        self.compile_import(node, None);
+                        None
                     }
                     Error(err) => {
                         self.handle_parser_error(err);
@@ -77,6 +120,99 @@ impl<'a> Compiler<'a> {
             .collect()
     }
 
+    /// Store a user-defined function, to be compiled when called.
+    ///
+    /// Functions that are never called are compiled once the whole program
+    /// is, see [`Compiler::compile_uncalled_functions`].
+    ///
+    /// The file is the imported file the function is defined in, if any.
+    fn compile_function_definition(
+        &mut self,
+        node: Node<ast::FunctionDefinition>,
+        file: Option<&SourceFile>,
+    ) {
+        let definition = node.into_inner();
+        let (ident_span, ident) = definition.ident.clone().take();
+
+        let builtin = self.fns.iter().any(|f| f.identifier() == ident.as_ref());
+        if builtin || self.state.function(&ident).is_some() {
+            let err = user::Error::Redefinition {
+                ident_span,
+                ident,
+                builtin,
+            };
+            self.push_error(Box::new(err), file);
+            return;
+        }
+
+        let function = UserFunction::new(definition, file.cloned());
+        self.state.insert_function(ident, function);
+    }
+
+    /// Import the function definitions of another VRL file.
+    ///
+    /// The file is the imported file the import is part of, if any. Files
+    /// imported before are skipped, which also prevents import cycles.
+    fn compile_import(&mut self, node: Node<ast::Import>, file: Option<&SourceFile>) {
+        let path = node.into_inner().path;
+        let path_span = path.span();
+
+        let imported =
+            user::resolve_import(self.state.import_paths(), path.inner()).and_then(|resolved| {
+                if !self.state.insert_import(resolved.clone()) {
+                    return Ok(None);
+                }
+
+                std::fs::read_to_string(&resolved)
+                    .map(|source| Some(SourceFile::new(resolved.display(), source)))
+                    .map_err(|err| format!("unable to read file: {}", err))
+            });
+
+        let imported = match imported {
+            Ok(Some(imported)) => imported,
+            Ok(None) => return,
+            Err(reason) => {
+                let err = user::Error::InvalidImport { path_span, reason };
+                self.push_error(Box::new(err), file);
+                return;
+            }
+        };
+
+        let ast = match parser::parse(imported.source()) {
+            Ok(ast) => ast,
+            Err(err) => {
+                self.push_error(Box::new(err), Some(&imported));
+                return;
+            }
+        };
+
+        for node in ast {
+            let expr_span = node.span();
+
+            match node.into_inner() {
+                ast::RootExpr::FunctionDefinition(node) => {
+                    self.compile_function_definition(node, Some(&imported))
+                }
+                ast::RootExpr::Import(node) => self.compile_import(node, Some(&imported)),
+                ast::RootExpr::Expr(_) => {
+                    let err = user::Error::UnexpectedImportedExpression { expr_span };
+                    self.push_error(Box::new(err), Some(&imported));
+                }
+                ast::RootExpr::Error(err) => self.push_error(Box::new(err), Some(&imported)),
+            }
+        }
+    }
+
+    /// Push an error, pointing into the given imported file, if any.
+    fn push_error(&mut self, error: Box<dyn DiagnosticError>, file: Option<&SourceFile>) {
+        let error = match file {
+            Some(file) => Imported::wrap(file, error),
+            None => error,
+        };
+
+        self.errors.push(error);
+    }
+
     fn compile_exprs(&mut self, nodes: impl IntoIterator<Item = Node<ast::Expr>>) -> Vec<Expr> {
         nodes
             .into_iter()
@@ -87,18 +223,29 @@ impl<'a> Compiler<'a> {
     fn compile_expr(&mut self, node: Node<ast::Expr>) -> Expr {
         use ast::Expr::*;
 
-        match node.into_inner() {
+        let span = node.span();
+        let expr = match node.into_inner() {
             Literal(node) => self.compile_literal(node).into(),
             Container(node) => self.compile_container(node).into(),
-            IfStatement(node) => self.compile_if_statement(node).into(),
+            IfStatement(node) => self.compile_if_statement(node),
             Op(node) => self.compile_op(node).into(),
             Assignment(node) => self.compile_assignment(node).into(),
             Query(node) => self.compile_query(node).into(),
+            FunctionCall(node) if self.is_user_function_call(&node) => {
+                self.compile_user_function_call(node).into()
+            }
             FunctionCall(node) => self.compile_function_call(node).into(),
             Variable(node) => self.compile_variable(node).into(),
             Unary(node) => self.compile_unary(node).into(),
             Abort(node) => self.compile_abort(node).into(),
+        };
+
+        if !self.imported_body && self.state.types().is_some() {
+            let type_def = expr.type_def(self.state);
+            self.state.record_type(span, type_def);
         }
+
+        expr
     }
 
     fn compile_literal(&mut self, node: Node<ast::Literal>) -> Literal {
@@ -137,7 +284,15 @@ impl<'a> Compiler<'a> {
     }
 
     fn compile_block(&mut self, node: Node<ast::Block>) -> Block {
-        let exprs = self.compile_exprs(node.into_inner().into_iter());
+        let exprs = node
+            .into_inner()
+            .into_iter()
+            .map(|node| {
+                let span = node.span();
+                let expr = self.compile_expr(node);
+                self.probe_statement(span, expr)
+            })
+            .collect();
 
         Block::new(exprs)
     }
@@ -160,7 +315,8 @@ impl<'a> Compiler<'a> {
         Object::new(exprs)
     }
 
-    fn compile_if_statement(&mut self, node: Node<ast::IfStatement>) -> IfStatement {
+    fn compile_if_statement(&mut self, node: Node<ast::IfStatement>) -> Expr {
+        let span = node.span();
         let ast::IfStatement {
             predicate,
             consequent,
@@ -171,17 +327,63 @@ impl<'a> Compiler<'a> {
             Ok(v) => v,
             Err(err) => {
                 self.errors.push(Box::new(err));
-                return IfStatement::noop();
+                return IfStatement::noop().into();
             }
         };
 
+        let consequent_span = consequent.span();
         let consequent = self.compile_block(consequent);
-        let alternative = alternative.map(|block| self.compile_block(block));
+        let alternative = alternative.map(|block| (block.span(), self.compile_block(block)));
+
+        let coverage = match self.coverage() {
+            Some(coverage) => coverage,
+            None => {
+                return IfStatement {
+                    predicate,
+                    consequent,
+                    alternative: alternative.map(|(_, block)| block),
+                }
+                .into()
+            }
+        };
 
-        IfStatement {
-            predicate,
-            consequent,
-            alternative,
+        let counter = coverage.block(consequent_span);
+        let consequent = probe_block(consequent, counter.clone());
+
+        match alternative {
+            Some((span, block)) => IfStatement {
+                predicate,
+                consequent,
+                alternative: Some(probe_block(block, coverage.block(span))),
+            }
+            .into(),
+            None => {
+                let statement = IfStatement {
+                    predicate,
+                    consequent,
+                    alternative: None,
+                };
+
+                Probe::new(statement.into(), coverage.missing_else(span, counter)).into()
+            }
+        }
+    }
+
+    /// The coverage of the program, unless disabled, or the body of an
+    /// imported function is being compiled.
+    fn coverage(&mut self) -> Option<&mut Coverage> {
+        if self.imported_body {
+            return None;
+        }
+
+        self.state.coverage_mut()
+    }
+
+    /// Record the coverage of a statement, if enabled.
+    fn probe_statement(&mut self, span: Span, expr: Expr) -> Expr {
+        match self.coverage() {
+            Some(coverage) => Probe::new(expr, coverage.statement(span)).into(),
+            None => expr,
         }
     }
 
@@ -240,6 +442,16 @@ impl<'a> Compiler<'a> {
 
         self.state.snapshot();
         let assignment = node.into_inner();
+        let variables = match &assignment {
+            Single { target, .. } => vec![target.inner()],
+            Infallible { ok, err, .. } => vec![ok.inner(), err.inner()],
+        }
+        .into_iter()
+        .filter_map(|target| match target {
+            ast::AssignmentTarget::Internal(ident, _) => Some(ident.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
 
         let node = match assignment {
             Single { target, op, expr } => {
@@ -290,6 +502,19 @@ impl<'a> Compiler<'a> {
 
         Assignment::new(node, self.state).unwrap_or_else(|err| {
             self.state.rollback();
+
+            // The assignment may be valid once the function is called, so
+            // the variables are still defined for the rest of the body.
+            if self.uncalled_body && user::depends_on_parameter_types(err.code()) {
+                for ident in variables {
+                    let details = assignment::Details {
+                        type_def: TypeDef::any(),
+                        value: None,
+                    };
+                    self.state.insert_variable(ident, details);
+                }
+            }
+
             self.errors.push(Box::new(err));
             Assignment::noop()
         })
@@ -318,8 +543,15 @@ impl<'a> Compiler<'a> {
                 Target::Container(container)
             }
             FunctionCall(call) => {
-                let call = self.compile_function_call(Node::new(span, call));
-                Target::FunctionCall(call)
+                let node = Node::new(span, call);
+
+                if self.is_user_function_call(&node) {
+                    let call = self.compile_user_function_call(node);
+                    Target::UserFunctionCall(call)
+                } else {
+                    let call = self.compile_function_call(node);
+                    Target::FunctionCall(call)
+                }
             }
         }
     }
@@ -330,6 +562,7 @@ impl<'a> Compiler<'a> {
             ident,
             abort_on_error,
             arguments,
+            closure,
         } = node.into_inner();
 
         let arguments = arguments
@@ -341,20 +574,343 @@ impl<'a> Compiler<'a> {
             self.fallible = true;
         }
 
-        FunctionCall::new(
+        let (closure_variables, closure_block) = match closure {
+            Some(closure) => {
+                let (span, ast::FunctionClosure { variables, block }) = closure.take();
+                (Some(Node::new(span, variables)), Some((span, block)))
+            }
+            None => (None, None),
+        };
+
+        let builder = match function_call::Builder::new(
             call_span,
             ident,
             abort_on_error,
             arguments,
             self.fns,
             self.state,
+            closure_variables,
+        ) {
+            Ok(builder) => builder,
+            Err(err) => {
+                self.errors.push(Box::new(err));
+                return FunctionCall::noop();
+            }
+        };
+
+        let closure = closure_block.map(|(span, block)| {
+            let closure = self.compile_function_closure(builder.closure_variables(), block);
+            Node::new(span, closure)
+        });
+
+        builder.compile(self.state, closure).unwrap_or_else(|err| {
+            self.errors.push(Box::new(err));
+            FunctionCall::noop()
+        })
+    }
+
+    /// Compile the block of a closure, with the closure variables in scope.
+    ///
+    /// Variables defined within the closure go out of scope at the end of the
+    /// block. Since the closure can run any number of times, including none,
+    /// changes to the types of variables and of the target made within the
+    /// closure are merged with their types from before the closure.
+    fn compile_function_closure(
+        &mut self,
+        variables: Vec<(Ident, TypeDef)>,
+        block: Node<ast::Block>,
+    ) -> FunctionClosure {
+        let outer = self
+            .state
+            .variable_idents()
+            .map(|ident| {
+                let details = self.state.variable(ident).cloned();
+                (ident.clone(), details.expect("variable exists"))
+            })
+            .collect::<Vec<_>>();
+        let target = self.state.target().cloned();
+
+        for (ident, type_def) in &variables {
+            let details = assignment::Details {
+                type_def: type_def.clone(),
+                value: None,
+            };
+            self.state.insert_variable(ident.clone(), details);
+        }
+
+        let block = self.compile_block(block);
+        let type_def = block.type_def(self.state);
+
+        let variables = variables
+            .into_iter()
+            .map(|(ident, _)| ident)
+            .collect::<Vec<_>>();
+
+        let scoped = variables.iter().collect::<HashSet<_>>();
+        let known = outer.iter().map(|(ident, _)| ident).collect::<HashSet<_>>();
+        let locals = self
+            .state
+            .variable_idents()
+            .filter(|ident| !scoped.contains(ident) && !known.contains(ident))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for ident in locals.iter().chain(&variables) {
+            self.state.remove_variable(ident);
+        }
+
+        for (ident, before) in outer {
+            let details = match self.state.variable(&ident) {
+                Some(after) if !scoped.contains(&ident) => merge_details(before, after.clone()),
+                _ => before,
+            };
+            self.state.insert_variable(ident, details);
+        }
+
+        if let (Some(before), Some(after)) = (target, self.state.target().cloned()) {
+            self.state.update_target(merge_details(before, after));
+        }
+
+        FunctionClosure::new(variables, locals, block, type_def)
+    }
+
+    fn is_user_function_call(&self, node: &Node<ast::FunctionCall>) -> bool {
+        self.state.function(node.inner().ident.inner()).is_some()
+    }
+
+    fn compile_user_function_call(&mut self, node: Node<ast::FunctionCall>) -> UserFunctionCall {
+        use user_function_call::{Arguments, Error};
+
+        let call_span = node.span();
+        let ast::FunctionCall {
+            ident,
+            abort_on_error,
+            arguments,
+            closure,
+        } = node.into_inner();
+        let ident_span = ident.span();
+        let name = ident.inner().clone();
+
+        let function = self
+            .state
+            .function(&name)
+            .cloned()
+            .expect("function exists");
+
+        let arguments = arguments
+            .into_iter()
+            .map(|node| Node::new(node.span(), self.compile_function_argument(node)))
+            .collect();
+
+        if abort_on_error {
+            self.fallible = true;
+        }
+
+        if let Some(closure) = closure {
+            self.errors.push(Box::new(Error::UnexpectedClosure {
+                call_span,
+                closure_span: closure.span(),
+            }));
+            return UserFunctionCall::noop(name);
+        }
+
+        if self.calls.contains(&name) {
+            self.errors.push(Box::new(Error::Recursion {
+                ident_span,
+                ident: name.clone(),
+            }));
+            return UserFunctionCall::noop(name);
+        }
+
+        let parameters = function
+            .definition()
+            .parameters
+            .iter()
+            .map(|parameter| parameter.inner().clone())
+            .collect::<Vec<_>>();
+
+        let arguments =
+            match Arguments::bind(call_span, ident_span, &parameters, arguments, self.state) {
+                Ok(arguments) => arguments,
+                Err(err) => {
+                    self.errors.push(Box::new(err));
+                    return UserFunctionCall::noop(name);
+                }
+            };
+
+        if self.invalid_functions.contains(&name) {
+            return UserFunctionCall::noop(name);
+        }
+
+        let (locals, block, type_def) =
+            match self.compile_user_function_body(&name, &function, &arguments) {
+                Some(body) => body,
+                None => return UserFunctionCall::noop(name),
+            };
+
+        UserFunctionCall::new(
+            call_span,
+            ident,
+            abort_on_error,
+            arguments,
+            locals,
+            block,
+            type_def,
         )
         .unwrap_or_else(|err| {
             self.errors.push(Box::new(err));
-            FunctionCall::noop()
+            UserFunctionCall::noop(name)
         })
     }
 
+    /// Compile the body of a user-defined function for a single call, with the
+    /// parameters of the function typed by the arguments of the call.
+    ///
+    /// The body only has access to the parameters of the function and to the
+    /// target, so the variables of the caller are out of scope while it is
+    /// compiled. Variables defined within the body go out of scope at the end
+    /// of the body.
+    ///
+    /// Returns `None` if the body failed to compile, in which case the errors
+    /// are reported, pointing into the file the function is defined in.
+    fn compile_user_function_body(
+        &mut self,
+        ident: &Ident,
+        function: &UserFunction,
+        arguments: &user_function_call::Arguments,
+    ) -> Option<(Vec<Ident>, Block, TypeDef)> {
+        let outer = self
+            .state
+            .variable_idents()
+            .cloned()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|ident| {
+                let details = self.state.remove_variable(&ident);
+                (ident, details.expect("variable exists"))
+            })
+            .collect::<Vec<_>>();
+
+        for (parameter, type_def, value) in &arguments.parameters {
+            let details = assignment::Details {
+                type_def: type_def.clone(),
+                value: value.clone(),
+            };
+            self.state.insert_variable(parameter.clone(), details);
+        }
+
+        let errors = self.errors.len();
+
+        let imported_body = self.imported_body;
+        self.imported_body |= function.file().is_some();
+        self.calls.push(ident.clone());
+        let block = self.compile_block(function.definition().block.clone());
+        let type_def = block.type_def(self.state);
+        self.calls.pop();
+        self.imported_body = imported_body;
+
+        let parameters = arguments
+            .parameters
+            .iter()
+            .map(|(parameter, _, _)| parameter)
+            .collect::<HashSet<_>>();
+        let variables = self.state.variable_idents().cloned().collect::<Vec<_>>();
+        let locals = variables
+            .iter()
+            .filter(|ident| !parameters.contains(ident))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for ident in &variables {
+            self.state.remove_variable(ident);
+        }
+
+        for (ident, details) in outer {
+            self.state.insert_variable(ident, details);
+        }
+
+        if self.errors.len() == errors {
+            self.compiled_functions.insert(ident.clone());
+            return Some((locals, block, type_def));
+        }
+
+        if let Some(file) = function.file() {
+            let errors = self
+                .errors
+                .split_off(errors)
+                .into_iter()
+                .map(|error| Imported::wrap(file, error))
+                .collect::<Vec<_>>();
+
+            self.errors.extend(errors);
+        }
+
+        self.invalid_functions.insert(ident.clone());
+        None
+    }
+
+    /// Compile the bodies of the user-defined functions that are never
+    /// called, including the imported ones, with parameters of any type.
+    ///
+    /// This reports the errors in these functions, such as calls to undefined
+    /// functions, before they are used. Errors that depend on the types of the
+    /// parameters are only reported once the function is called with
+    /// arguments of these types.
+    fn compile_uncalled_functions(&mut self) {
+        use user_function_call::Arguments;
+
+        let mut idents = self
+            .state
+            .function_idents()
+            .filter(|ident| !self.compiled_functions.contains(ident))
+            .filter(|ident| !self.invalid_functions.contains(ident))
+            .cloned()
+            .collect::<Vec<_>>();
+        idents.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+
+        // The functions aren't part of the program, unless called.
+        let fallible = self.fallible;
+        let abortable = self.abortable;
+        self.uncalled_body = true;
+
+        for ident in idents {
+            // Compiled while compiling the body of another uncalled function.
+            if self.compiled_functions.contains(&ident) || self.invalid_functions.contains(&ident) {
+                continue;
+            }
+
+            let function = self
+                .state
+                .function(&ident)
+                .cloned()
+                .expect("function exists");
+            let arguments = Arguments {
+                parameters: function
+                    .definition()
+                    .parameters
+                    .iter()
+                    .map(|parameter| (parameter.inner().clone(), TypeDef::any(), None))
+                    .collect(),
+                arguments: vec![],
+            };
+
+            let errors = self.errors.len();
+            self.compile_user_function_body(&ident, &function, &arguments);
+
+            let errors = self
+                .errors
+                .split_off(errors)
+                .into_iter()
+                .filter(|error| !user::depends_on_parameter_types(error.code()))
+                .collect::<Vec<_>>();
+            self.errors.extend(errors);
+        }
+
+        self.uncalled_body = false;
+        self.fallible = fallible;
+        self.abortable = abortable;
+    }
+
     fn compile_function_argument(&mut self, node: Node<ast::FunctionArgument>) -> FunctionArgument {
         let ast::FunctionArgument { ident, expr } = node.into_inner();
         let expr = Node::new(expr.span(), self.compile_expr(expr));
@@ -408,3 +964,25 @@ impl<'a> Compiler<'a> {
         self.errors.push(Box::new(error))
     }
 }
+
+/// Merge the details of a variable from before and after a closure that might
+/// have changed it.
+fn merge_details(before: assignment::Details, after: assignment::Details) -> assignment::Details {
+    if before.type_def == after.type_def && before.value == after.value {
+        return before;
+    }
+
+    assignment::Details {
+        type_def: before.type_def.merge_deep(after.type_def),
+        value: None,
+    }
+}
+
+/// Record the coverage of a branch, by counting the times the block is
+/// resolved.
+fn probe_block(block: Block, counter: Counter) -> Block {
+    let mut exprs = block.into_inner();
+    exprs.insert(0, Probe::new(Noop.into(), counter).into());
+
+    Block::new(exprs)
+}
@@ -0,0 +1,195 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::Span;
+
+/// The statements and branches of a program executed at runtime.
+///
+/// Coverage is only recorded for programs compiled with coverage enabled (see
+/// [`Compiler::enable_coverage`](crate::state::Compiler::enable_coverage)),
+/// and resolved by the AST runtime.
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+    statements: Vec<(Span, Counter)>,
+    branches: Vec<(Branch, Hits)>,
+}
+
+/// A branch of an `if` statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Branch {
+    /// The consequent or alternative block at the span.
+    Block(Span),
+
+    /// The missing `else` block of the `if` statement at the span, which is
+    /// taken each time the statement is executed without taking its
+    /// consequent.
+    MissingElse(Span),
+}
+
+impl Branch {
+    pub fn span(self) -> Span {
+        match self {
+            Branch::Block(span) | Branch::MissingElse(span) => span,
+        }
+    }
+}
+
+impl Ord for Branch {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let key = |branch: &Self| (branch.span(), matches!(branch, Branch::MissingElse(_)));
+
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for Branch {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Hits {
+    Counter(Counter),
+    Difference(Counter, Counter),
+}
+
+impl Coverage {
+    pub(crate) fn statement(&mut self, span: Span) -> Counter {
+        let counter = Counter::default();
+        self.statements.push((span, counter.clone()));
+        counter
+    }
+
+    pub(crate) fn block(&mut self, span: Span) -> Counter {
+        let counter = Counter::default();
+        self.branches
+            .push((Branch::Block(span), Hits::Counter(counter.clone())));
+        counter
+    }
+
+    /// Add the missing `else` block of an `if` statement, given the counter
+    /// of its consequent, returning the counter of the statement itself.
+    pub(crate) fn missing_else(&mut self, span: Span, consequent: Counter) -> Counter {
+        let statement = Counter::default();
+        let hits = Hits::Difference(statement.clone(), consequent);
+
+        self.branches.push((Branch::MissingElse(span), hits));
+        statement
+    }
+
+    /// The number of times each statement was executed, ordered by their
+    /// position in the source.
+    ///
+    /// Statements compiled more than once, such as the statements of a
+    /// function called from several places, are merged.
+    pub fn statements(&self) -> Vec<(Span, usize)> {
+        merge(
+            self.statements
+                .iter()
+                .map(|(span, counter)| (*span, counter.get())),
+        )
+    }
+
+    /// The number of times each branch was taken, ordered by their position
+    /// in the source.
+    pub fn branches(&self) -> Vec<(Branch, usize)> {
+        merge(self.branches.iter().map(|(branch, hits)| {
+            let hits = match hits {
+                Hits::Counter(counter) => counter.get(),
+                Hits::Difference(total, taken) => total.get().saturating_sub(taken.get()),
+            };
+
+            (*branch, hits)
+        }))
+    }
+}
+
+fn merge<T: Ord>(points: impl Iterator<Item = (T, usize)>) -> Vec<(T, usize)> {
+    let mut merged = BTreeMap::new();
+    for (point, hits) in points {
+        *merged.entry(point).or_insert(0) += hits;
+    }
+
+    merged.into_iter().collect()
+}
+
+/// The number of times a statement or branch was executed, shared by all
+/// clones of the program.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Counter(Arc<AtomicUsize>);
+
+impl Counter {
+    pub(crate) fn increment(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use vector_common::TimeZone;
+
+    use super::*;
+    use crate::{state, Context, Expression, Value};
+
+    fn span(source: &str, code: &str) -> Span {
+        let start = source.find(code).expect("code in source");
+        Span::new(start, start + code.len())
+    }
+
+    #[test]
+    fn statements_and_branches() {
+        let source = indoc::indoc! {"
+            .a = 1
+            if .b == true {
+                .c = 2
+            }
+        "};
+
+        let mut state = state::Compiler::new();
+        state.enable_coverage();
+        let ast = parser::parse(source).unwrap();
+        let program = crate::compile_with_state(ast, &[], &mut state).unwrap();
+
+        for b in [true, false, false] {
+            let mut target = Value::Object(BTreeMap::from([("b".to_owned(), b.into())]));
+            let mut runtime = state::Runtime::default();
+            let timezone = TimeZone::default();
+            let mut ctx = Context::new(&mut target, &mut runtime, &timezone);
+
+            for expr in program.iter() {
+                expr.resolve(&mut ctx).unwrap();
+            }
+        }
+
+        let coverage = state.coverage().unwrap();
+        let if_statement = span(source, "if .b == true {\n    .c = 2\n}");
+
+        assert_eq!(
+            coverage.statements(),
+            vec![
+                (span(source, ".a = 1"), 3),
+                (if_statement, 3),
+                (span(source, ".c = 2"), 1),
+            ]
+        );
+        assert_eq!(
+            coverage.branches(),
+            vec![
+                (Branch::MissingElse(if_statement), 2),
+                (Branch::Block(span(source, "{\n    .c = 2\n}")), 1),
+            ]
+        );
+    }
+}
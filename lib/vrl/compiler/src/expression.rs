@@ -17,6 +17,7 @@ mod noop;
 mod not;
 mod object;
 mod op;
+mod probe;
 mod unary;
 mod variable;
 
@@ -26,6 +27,7 @@ pub(crate) mod function_call;
 pub(crate) mod literal;
 pub(crate) mod predicate;
 pub(crate) mod query;
+pub(crate) mod user_function_call;
 
 pub use abort::Abort;
 pub use array::Array;
@@ -43,8 +45,10 @@ pub use not::Not;
 pub use object::Object;
 pub use op::Op;
 pub use predicate::Predicate;
+pub use probe::Probe;
 pub use query::{Query, Target};
 pub use unary::Unary;
+pub use user_function_call::UserFunctionCall;
 pub use variable::Variable;
 
 pub trait Expression: Send + Sync + fmt::Debug + DynClone {
@@ -103,10 +107,12 @@ pub enum Expr {
     Assignment(Assignment),
     Query(Query),
     FunctionCall(FunctionCall),
+    UserFunctionCall(UserFunctionCall),
     Variable(Variable),
     Noop(Noop),
     Unary(Unary),
     Abort(Abort),
+    Probe(Probe),
 }
 
 impl Expr {
@@ -127,10 +133,12 @@ impl Expr {
             Assignment(..) => "assignment",
             Query(..) => "query",
             FunctionCall(..) => "function call",
+            UserFunctionCall(..) => "user-defined function call",
             Variable(..) => "variable call",
             Noop(..) => "noop",
             Unary(..) => "unary operation",
             Abort(..) => "abort operation",
+            Probe(v) => v.expr().as_str(),
         }
     }
 
@@ -192,10 +200,12 @@ impl Expression for Expr {
             Assignment(v) => v.resolve(ctx),
             Query(v) => v.resolve(ctx),
             FunctionCall(v) => v.resolve(ctx),
+            UserFunctionCall(v) => v.resolve(ctx),
             Variable(v) => v.resolve(ctx),
             Noop(v) => v.resolve(ctx),
             Unary(v) => v.resolve(ctx),
             Abort(v) => v.resolve(ctx),
+            Probe(v) => v.resolve(ctx),
         }
     }
 
@@ -210,10 +220,12 @@ impl Expression for Expr {
             Assignment(v) => Expression::as_value(v),
             Query(v) => Expression::as_value(v),
             FunctionCall(v) => Expression::as_value(v),
+            UserFunctionCall(v) => Expression::as_value(v),
             Variable(v) => Expression::as_value(v),
             Noop(v) => Expression::as_value(v),
             Unary(v) => Expression::as_value(v),
             Abort(v) => Expression::as_value(v),
+            Probe(v) => Expression::as_value(v),
         }
     }
 
@@ -228,10 +240,12 @@ impl Expression for Expr {
             Assignment(v) => v.type_def(state),
             Query(v) => v.type_def(state),
             FunctionCall(v) => v.type_def(state),
+            UserFunctionCall(v) => v.type_def(state),
             Variable(v) => v.type_def(state),
             Noop(v) => v.type_def(state),
             Unary(v) => v.type_def(state),
             Abort(v) => v.type_def(state),
+            Probe(v) => v.type_def(state),
         }
     }
 
@@ -251,10 +265,12 @@ impl Expression for Expr {
             Assignment(v) => v.compile_to_vm(vm, state),
             Query(v) => v.compile_to_vm(vm, state),
             FunctionCall(v) => v.compile_to_vm(vm, state),
+            UserFunctionCall(v) => v.compile_to_vm(vm, state),
             Variable(v) => v.compile_to_vm(vm, state),
             Noop(v) => v.compile_to_vm(vm, state),
             Unary(v) => v.compile_to_vm(vm, state),
             Abort(v) => v.compile_to_vm(vm, state),
+            Probe(v) => v.compile_to_vm(vm, state),
         }
     }
 }
@@ -271,10 +287,12 @@ impl fmt::Display for Expr {
             Assignment(v) => v.fmt(f),
             Query(v) => v.fmt(f),
             FunctionCall(v) => v.fmt(f),
+            UserFunctionCall(v) => v.fmt(f),
             Variable(v) => v.fmt(f),
             Noop(v) => v.fmt(f),
             Unary(v) => v.fmt(f),
             Abort(v) => v.fmt(f),
+            Probe(v) => v.fmt(f),
         }
     }
 }
@@ -323,6 +341,12 @@ impl From<FunctionCall> for Expr {
     }
 }
 
+impl From<UserFunctionCall> for Expr {
+    fn from(user_function_call: UserFunctionCall) -> Self {
+        Expr::UserFunctionCall(user_function_call)
+    }
+}
+
 impl From<Variable> for Expr {
     fn from(variable: Variable) -> Self {
         Expr::Variable(variable)
@@ -347,6 +371,12 @@ impl From<Abort> for Expr {
     }
 }
 
+impl From<Probe> for Expr {
+    fn from(probe: Probe) -> Self {
+        Expr::Probe(probe)
+    }
+}
+
 impl From<Value> for Expr {
     fn from(value: Value) -> Self {
         use Value::*;
@@ -4,7 +4,7 @@ use diagnostic::{DiagnosticError, Label, Note};
 use lookup::LookupBuf;
 
 use crate::{
-    expression::{Expr, Literal, Resolved},
+    expression::{Expr, ExpressionError, Literal, Resolved},
     parser::{
         ast::{self, Ident},
         Node,
@@ -388,6 +388,9 @@ where
                     err.insert(Value::Null, ctx);
                     value
                 }
+                // An abort statement aborts the whole program, and can't be
+                // assigned.
+                Err(error @ ExpressionError::Abort { .. }) => return Err(error),
                 Err(error) => {
                     ok.insert(default.clone(), ctx);
                     let value = Value::from(error.to_string());
@@ -5,7 +5,10 @@ use diagnostic::{DiagnosticError, Label, Note, Urls};
 
 use crate::{
     expression::{levenstein, ExpressionError, FunctionArgument, Noop},
-    function::{ArgumentList, FunctionCompileContext, Parameter},
+    function::{
+        closure::{self, FunctionClosure},
+        ArgumentList, FunctionCompileContext, Parameter,
+    },
     parser::{Ident, Node},
     value::Kind,
     vm::OpCode,
@@ -30,16 +33,45 @@ pub struct FunctionCall {
     // Used by the VM to identify this function when called.
     function_id: usize,
     arguments: Arc<Vec<Node<FunctionArgument>>>,
+
+    // The closure passed to the function, compiled into the VM alongside the
+    // call.
+    closure: Option<FunctionClosure>,
 }
 
-impl FunctionCall {
-    pub fn new(
+/// Validates a function call, before its closure, if any, is compiled.
+///
+/// The closure variables are only known once the function and the kinds of
+/// its arguments are known, so the compiler validates the call first, then
+/// compiles the closure with its variables in scope, and finally compiles the
+/// function call itself.
+pub(crate) struct Builder<'a> {
+    abort_on_error: bool,
+    arguments: Vec<Node<FunctionArgument>>,
+    call_span: Span,
+    ident_span: Span,
+    function_id: usize,
+    function: &'a dyn Function,
+    list: ArgumentList,
+    maybe_fallible_arguments: bool,
+    closure: Option<ClosureSignature>,
+}
+
+/// The variables and expected output of the closure passed to a function call.
+struct ClosureSignature {
+    variables: Vec<(Ident, Kind)>,
+    output: closure::Output,
+}
+
+impl<'a> Builder<'a> {
+    pub(crate) fn new(
         call_span: Span,
         ident: Node<Ident>,
         abort_on_error: bool,
         arguments: Vec<Node<FunctionArgument>>,
-        funcs: &[Box<dyn Function>],
+        funcs: &'a [Box<dyn Function>],
         state: &mut State,
+        closure_variables: Option<Node<Vec<Node<Ident>>>>,
     ) -> Result<Self, Error> {
         let (ident_span, ident) = ident.take();
 
@@ -165,6 +197,87 @@ impl FunctionCall {
                 })
             })?;
 
+        let closure = match (function.closure(), closure_variables) {
+            (None, None) => None,
+            (None, Some(variables)) => {
+                return Err(Error::UnexpectedClosure {
+                    call_span,
+                    closure_span: variables.span(),
+                })
+            }
+            (Some(_), None) => return Err(Error::MissingClosure { call_span }),
+            (Some(definition), Some(variables)) => Some(closure_signature(
+                definition, variables, ident_span, &list, state,
+            )?),
+        };
+
+        Ok(Self {
+            abort_on_error,
+            arguments,
+            call_span,
+            ident_span,
+            function_id,
+            function: function.as_ref(),
+            list,
+            maybe_fallible_arguments,
+            closure,
+        })
+    }
+
+    /// The variables of the closure passed to the function, with their type
+    /// definitions.
+    pub(crate) fn closure_variables(&self) -> Vec<(Ident, TypeDef)> {
+        self.closure
+            .iter()
+            .flat_map(|closure| &closure.variables)
+            .map(|(ident, kind)| (ident.clone(), TypeDef::from(kind.clone()).infallible()))
+            .collect()
+    }
+
+    pub(crate) fn compile(
+        self,
+        state: &mut State,
+        closure: Option<Node<FunctionClosure>>,
+    ) -> Result<FunctionCall, Error> {
+        let Self {
+            abort_on_error,
+            arguments,
+            call_span,
+            ident_span,
+            function_id,
+            function,
+            mut list,
+            maybe_fallible_arguments,
+            closure: signature,
+        } = self;
+
+        let closure = match (signature, closure) {
+            (Some(signature), Some(closure)) => {
+                let (block_span, closure) = closure.take();
+                let type_def = closure.type_def();
+
+                // Closures need to be infallible, for the same reasons as
+                // arguments.
+                if type_def.is_fallible() {
+                    return Err(Error::FallibleClosure { block_span });
+                }
+
+                if let closure::Output::Kind(expected) = signature.output {
+                    if !expected.is_superset(type_def.kind()) {
+                        return Err(Error::ReturnTypeMismatch {
+                            block_span,
+                            found: type_def.kind().clone(),
+                            expected,
+                        });
+                    }
+                }
+
+                list.set_closure(closure.clone());
+                Some(closure)
+            }
+            _ => None,
+        };
+
         // We take the external context, and pass it to the function compile context, this allows
         // functions mutable access to external state, but keeps the internal compiler state behind
         // an immutable reference, to ensure compiler state correctness.
@@ -196,7 +309,7 @@ impl FunctionCall {
             error: err.to_string(),
         })?;
 
-        Ok(Self {
+        Ok(FunctionCall {
             abort_on_error,
             expr,
             maybe_fallible_arguments,
@@ -204,7 +317,95 @@ impl FunctionCall {
             ident: function.identifier(),
             function_id,
             arguments: Arc::new(arguments),
+            closure,
+        })
+    }
+}
+
+/// Resolve the kinds of the closure variables and of its expected output,
+/// from the closure inputs applying to the kinds of the call arguments.
+fn closure_signature(
+    definition: closure::Definition,
+    variables: Node<Vec<Node<Ident>>>,
+    ident_span: Span,
+    list: &ArgumentList,
+    state: &State,
+) -> Result<ClosureSignature, Error> {
+    let (closure_arguments_span, variables) = variables.take();
+
+    let inputs = definition
+        .inputs
+        .into_iter()
+        .filter_map(|input| {
+            let kind = list
+                .get(input.parameter_keyword)?
+                .type_def(state)
+                .kind()
+                .clone();
+
+            input.kind.intersects(&kind).then(|| (input, kind))
         })
+        .collect::<Vec<_>>();
+
+    if let Some((input, _)) = inputs
+        .iter()
+        .find(|(input, _)| input.variables.len() != variables.len())
+    {
+        return Err(Error::ClosureArityMismatch {
+            ident_span,
+            closure_arguments_span,
+            expected: input.variables.len(),
+            supplied: variables.len(),
+        });
+    }
+
+    let variables = variables
+        .into_iter()
+        .enumerate()
+        .map(|(position, ident)| {
+            let kind = inputs
+                .iter()
+                .map(|(input, kind)| input.variable_kind(position, kind))
+                .reduce(|lhs, rhs| lhs | rhs)
+                .unwrap_or_else(Kind::any);
+
+            (ident.into_inner(), kind)
+        })
+        .collect();
+
+    let output = inputs
+        .into_iter()
+        .map(|(input, _)| input.output)
+        .reduce(|lhs, rhs| match (lhs, rhs) {
+            (closure::Output::Kind(lhs), closure::Output::Kind(rhs)) => {
+                closure::Output::Kind(lhs | rhs)
+            }
+            _ => closure::Output::Any,
+        })
+        .unwrap_or(closure::Output::Any);
+
+    Ok(ClosureSignature { variables, output })
+}
+
+impl FunctionCall {
+    pub fn new(
+        call_span: Span,
+        ident: Node<Ident>,
+        abort_on_error: bool,
+        arguments: Vec<Node<FunctionArgument>>,
+        funcs: &[Box<dyn Function>],
+        state: &mut State,
+    ) -> Result<Self, Error> {
+        Builder::new(
+            call_span,
+            ident,
+            abort_on_error,
+            arguments,
+            funcs,
+            state,
+            None,
+        )?
+        .compile(state, None)
     }
 
     /// Takes the arguments passed and resolves them into the order they are defined
@@ -269,6 +470,7 @@ impl FunctionCall {
             ident: "noop",
             arguments: Arc::new(Vec::new()),
             function_id: 0,
+            closure: None,
         }
     }
 
@@ -290,9 +492,9 @@ impl FunctionCall {
 impl Expression for FunctionCall {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         self.expr.resolve(ctx).map_err(|err| match err {
-            ExpressionError::Abort { .. } => {
-                panic!("abort errors must only be defined by `abort` statement")
-            }
+            // An abort statement within a closure, or within a user-defined
+            // function called by an argument, aborts the whole program.
+            ExpressionError::Abort { .. } => err,
             ExpressionError::Error {
                 message,
                 mut labels,
@@ -396,24 +598,43 @@ impl Expression for FunctionCall {
             None => return Err(format!("Function {} not found.", self.function_id)),
         };
 
-        // We take the external context, and pass it to the function compile context, this allows
-        // functions mutable access to external state, but keeps the internal compiler state behind
-        // an immutable reference, to ensure compiler state correctness.
-        let external_context = state.swap_external_context(AnyMap::new());
-
-        let mut compile_ctx =
-            FunctionCompileContext::new(self.span).with_external_context(external_context);
+        // The closure body is written inline, and jumped over until the function
+        // runs it.
+        let closure = match &self.closure {
+            Some(closure) => {
+                let jump = vm.emit_jump(OpCode::Jump);
+                let start = vm.instructions().len();
+                closure.block().compile_to_vm(vm, state)?;
+                vm.write_opcode(OpCode::Return);
+                vm.patch_jump(jump);
+
+                Some(vm.add_closure(
+                    start,
+                    closure.variables().to_vec(),
+                    closure.locals().to_vec(),
+                ))
+            }
+            None => None,
+        };
 
         for (keyword, argument) in &args {
             let fun = vm.function(self.function_id).unwrap();
             let argument = argument.as_ref().map(|argument| argument.inner());
 
+            // We take the external context, and pass it to the function compile context, this
+            // allows functions mutable access to external state, but keeps the internal compiler
+            // state behind an immutable reference, to ensure compiler state correctness. It is
+            // re-inserted before compiling the argument, as nested function calls need it too.
+            let external_context = state.swap_external_context(AnyMap::new());
+            let mut compile_ctx =
+                FunctionCompileContext::new(self.span).with_external_context(external_context);
+
             // Call `compile_argument` for functions that need to perform any compile time processing
             // on the argument.
-            match fun
-                .compile_argument(&args, &mut compile_ctx, keyword, argument)
-                .map_err(|err| err.to_string())?
-            {
+            let compiled = fun.compile_argument(&args, &mut compile_ctx, keyword, argument);
+            let _ = state.swap_external_context(compile_ctx.into_external_context());
+
+            match compiled.map_err(|err| err.to_string())? {
                 Some(stat) => {
                     // The function has compiled this argument as a static.
                     let stat = vm.add_static(stat);
@@ -436,8 +657,11 @@ impl Expression for FunctionCall {
             }
         }
 
-        // Re-insert the external context into the compiler state.
-        let _ = state.swap_external_context(compile_ctx.into_external_context());
+        // Move the closure into place to be passed to the function.
+        if let Some(closure) = closure {
+            vm.write_opcode(OpCode::MoveClosure);
+            vm.write_primitive(closure);
+        }
 
         // Call the function with the given id.
         vm.write_opcode(OpCode::Call);
@@ -466,7 +690,13 @@ impl fmt::Display for FunctionCall {
             }
         }
 
-        f.write_str(")")
+        f.write_str(")")?;
+
+        if let Some(closure) = &self.closure {
+            write!(f, " -> {}", closure)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -551,6 +781,30 @@ pub enum Error {
 
     #[error("error updating state {}", error)]
     UpdateState { call_span: Span, error: String },
+
+    #[error("unexpected closure")]
+    UnexpectedClosure { call_span: Span, closure_span: Span },
+
+    #[error("missing closure")]
+    MissingClosure { call_span: Span },
+
+    #[error("invalid closure arguments")]
+    ClosureArityMismatch {
+        ident_span: Span,
+        closure_arguments_span: Span,
+        expected: usize,
+        supplied: usize,
+    },
+
+    #[error("fallible closure")]
+    FallibleClosure { block_span: Span },
+
+    #[error("return type mismatch")]
+    ReturnTypeMismatch {
+        block_span: Span,
+        found: Kind,
+        expected: Kind,
+    },
 }
 
 impl DiagnosticError for Error {
@@ -567,6 +821,11 @@ impl DiagnosticError for Error {
             InvalidArgumentKind { .. } => 110,
             FallibleArgument { .. } => 630,
             UpdateState { .. } => 640,
+            UnexpectedClosure { .. } => 109,
+            MissingClosure { .. } => 120,
+            ClosureArityMismatch { .. } => 121,
+            ReturnTypeMismatch { .. } => 122,
+            FallibleClosure { .. } => 632,
         }
     }
 
@@ -720,6 +979,65 @@ impl DiagnosticError for Error {
                 format!("an error occurred updating the compiler state: {}", error),
                 call_span,
             )],
+
+            UnexpectedClosure {
+                call_span,
+                closure_span,
+            } => vec![
+                Label::primary("unexpected closure", closure_span),
+                Label::context("this function does not accept a closure", call_span),
+            ],
+
+            MissingClosure { call_span } => {
+                vec![Label::primary("this function expects a closure", call_span)]
+            }
+
+            ClosureArityMismatch {
+                ident_span,
+                closure_arguments_span,
+                expected,
+                supplied,
+            } => {
+                let variables = |count: usize| if count == 1 { "variable" } else { "variables" };
+
+                vec![
+                    Label::primary(
+                        format!("this closure defines {} {}", supplied, variables(*supplied)),
+                        closure_arguments_span,
+                    ),
+                    Label::context(
+                        format!(
+                            "this function expects {} closure {}",
+                            expected,
+                            variables(*expected)
+                        ),
+                        ident_span,
+                    ),
+                ]
+            }
+
+            FallibleClosure { block_span } => vec![
+                Label::primary("this closure can fail", block_span),
+                Label::context(
+                    "handle the errors within the closure before passing it in",
+                    block_span,
+                ),
+            ],
+
+            ReturnTypeMismatch {
+                block_span,
+                found,
+                expected,
+            } => vec![
+                Label::primary(format!("this closure resolves to {}", found), block_span),
+                Label::context(
+                    format!(
+                        "but the function expects the closure to return {}",
+                        expected
+                    ),
+                    block_span,
+                ),
+            ],
         }
     }
 
@@ -731,7 +1049,16 @@ impl DiagnosticError for Error {
                 "function arguments".to_owned(),
                 Urls::expression_docs_url("#arguments"),
             )],
-            AbortInfallible { .. } | FallibleArgument { .. } => vec![Note::SeeErrorDocs],
+            AbortInfallible { .. } | FallibleArgument { .. } | FallibleClosure { .. } => {
+                vec![Note::SeeErrorDocs]
+            }
+            UnexpectedClosure { .. }
+            | MissingClosure { .. }
+            | ClosureArityMismatch { .. }
+            | ReturnTypeMismatch { .. } => vec![Note::SeeDocs(
+                "function closures".to_owned(),
+                Urls::expression_docs_url("#closure"),
+            )],
             InvalidArgumentKind {
                 function_ident,
                 abort_on_error,
@@ -93,6 +93,12 @@ impl Expression for Op {
         use Value::*;
 
         let lhs = self.lhs.resolve(ctx);
+
+        // An abort statement aborts the whole program, and can't be coalesced.
+        if matches!(lhs, Result::Err(expression::ExpressionError::Abort { .. })) {
+            return lhs;
+        }
+
         let mut rhs = || self.rhs.resolve(ctx);
 
         match self.opcode {
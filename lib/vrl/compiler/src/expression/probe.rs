@@ -0,0 +1,64 @@
+use std::fmt;
+
+use crate::{
+    coverage::Counter,
+    expression::{Expr, Resolved},
+    Context, Expression, State, TypeDef, Value,
+};
+
+/// An expression counting the number of times it's resolved, used to record
+/// the coverage of a program.
+#[derive(Debug, Clone)]
+pub struct Probe {
+    expr: Box<Expr>,
+    counter: Counter,
+}
+
+impl Probe {
+    pub(crate) fn new(expr: Expr, counter: Counter) -> Self {
+        Self {
+            expr: Box::new(expr),
+            counter,
+        }
+    }
+
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+}
+
+impl Expression for Probe {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        self.counter.increment();
+        self.expr.resolve(ctx)
+    }
+
+    fn as_value(&self) -> Option<Value> {
+        self.expr.as_value()
+    }
+
+    fn type_def(&self, state: &State) -> TypeDef {
+        self.expr.type_def(state)
+    }
+
+    fn compile_to_vm(
+        &self,
+        vm: &mut crate::vm::Vm,
+        state: &mut crate::state::Compiler,
+    ) -> Result<(), String> {
+        // The VM doesn't record coverage.
+        self.expr.compile_to_vm(vm, state)
+    }
+}
+
+impl PartialEq for Probe {
+    fn eq(&self, other: &Self) -> bool {
+        self.expr == other.expr
+    }
+}
+
+impl fmt::Display for Probe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.expr.fmt(f)
+    }
+}
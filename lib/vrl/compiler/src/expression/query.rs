@@ -4,7 +4,7 @@ use lookup::LookupBuf;
 use value::{kind::remove, Kind};
 
 use crate::{
-    expression::{assignment, Container, FunctionCall, Resolved, Variable},
+    expression::{assignment, Container, FunctionCall, Resolved, UserFunctionCall, Variable},
     parser::ast::Ident,
     vm::{self, OpCode},
     Context, Expression, State, TypeDef, Value,
@@ -46,6 +46,7 @@ impl Query {
     pub fn expression_target(&self) -> Option<&dyn Expression> {
         match &self.target {
             Target::FunctionCall(expr) => Some(expr),
+            Target::UserFunctionCall(expr) => Some(expr),
             Target::Container(expr) => Some(expr),
             _ => None,
         }
@@ -87,6 +88,7 @@ impl Expression for Query {
             }
             Internal(variable) => variable.resolve(ctx)?,
             FunctionCall(call) => call.resolve(ctx)?,
+            UserFunctionCall(call) => call.resolve(ctx)?,
             Container(container) => container.resolve(ctx)?,
         };
 
@@ -126,6 +128,7 @@ impl Expression for Query {
 
             Internal(variable) => variable.type_def(state).at_path(&self.path.to_lookup()),
             FunctionCall(call) => call.type_def(state).at_path(&self.path.to_lookup()),
+            UserFunctionCall(call) => call.type_def(state).at_path(&self.path.to_lookup()),
             Container(container) => container.type_def(state).at_path(&self.path.to_lookup()),
         }
     }
@@ -153,6 +156,14 @@ impl Expression for Query {
                 vm.write_opcode(OpCode::GetPath);
                 vm::Variable::Stack(self.path.clone())
             }
+            Target::UserFunctionCall(call) => {
+                // Write the code to call the function.
+                call.compile_to_vm(vm, state)?;
+
+                // Then retrieve the given path from the returned value that has been pushed on the stack
+                vm.write_opcode(OpCode::GetPath);
+                vm::Variable::Stack(self.path.clone())
+            }
             Target::Container(container) => {
                 // Write the code to create the container onto the stack.
                 container.compile_to_vm(vm, state)?;
@@ -187,6 +198,7 @@ pub enum Target {
     Internal(Variable),
     External,
     FunctionCall(FunctionCall),
+    UserFunctionCall(UserFunctionCall),
     Container(Container),
 }
 
@@ -198,6 +210,7 @@ impl fmt::Display for Target {
             Internal(v) => v.fmt(f),
             External => write!(f, "."),
             FunctionCall(v) => v.fmt(f),
+            UserFunctionCall(v) => v.fmt(f),
             Container(v) => v.fmt(f),
         }
     }
@@ -211,6 +224,7 @@ impl fmt::Debug for Target {
             Internal(v) => write!(f, "Internal({:?})", v),
             External => f.write_str("External"),
             FunctionCall(v) => v.fmt(f),
+            UserFunctionCall(v) => v.fmt(f),
             Container(v) => v.fmt(f),
         }
     }
@@ -0,0 +1,436 @@
+use std::fmt;
+
+use diagnostic::{DiagnosticError, Label, Note, Urls};
+
+use crate::{
+    expression::{Block, Expr, ExpressionError, FunctionArgument},
+    function::closure::with_variables,
+    parser::{Ident, Node},
+    vm::OpCode,
+    Context, Expression, Resolved, Span, State, TypeDef, Value,
+};
+
+/// A call to a [user-defined function](crate::function::user).
+///
+/// The body of the function is compiled for each call, with the parameters
+/// of the function typed by the arguments of the call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserFunctionCall {
+    ident: Ident,
+    abort_on_error: bool,
+
+    // used for enhancing runtime error messages.
+    span: Span,
+
+    // The arguments, in the order of the parameters they are bound to.
+    arguments: Vec<Expr>,
+    parameters: Vec<Ident>,
+
+    // The variables declared within the body of the function, removed once
+    // the function returns.
+    locals: Vec<Ident>,
+    block: Block,
+    block_type_def: TypeDef,
+}
+
+/// The arguments of a call to a user-defined function, bound to the
+/// parameters of the function, before its body is compiled.
+pub(crate) struct Arguments {
+    pub(crate) parameters: Vec<(Ident, TypeDef, Option<Value>)>,
+    pub(crate) arguments: Vec<Expr>,
+}
+
+impl Arguments {
+    /// Bind the arguments of a call to the parameters of the function.
+    ///
+    /// Positional arguments are bound in order, skipping parameters bound
+    /// by keyword, the same way they are for built-in functions.
+    pub(crate) fn bind(
+        call_span: Span,
+        ident_span: Span,
+        parameters: &[Ident],
+        arguments: Vec<Node<FunctionArgument>>,
+        state: &State,
+    ) -> Result<Self, Error> {
+        if arguments.len() > parameters.len() {
+            let arguments_span = {
+                let start = arguments.first().unwrap().span().start();
+                let end = arguments.last().unwrap().span().end();
+
+                Span::new(start, end)
+            };
+
+            return Err(Error::WrongNumberOfArgs {
+                arguments_span,
+                max: parameters.len(),
+            });
+        }
+
+        let mut index = 0;
+        let mut bound = vec![None; parameters.len()];
+
+        for node in arguments {
+            let argument = node.into_inner();
+
+            let position = match argument.keyword() {
+                None => {
+                    index += 1;
+                    Some(index - 1)
+                }
+                Some(keyword) => parameters
+                    .iter()
+                    .position(|parameter| parameter.as_ref() == keyword)
+                    .map(|position| {
+                        if position == index {
+                            index += 1;
+                        }
+
+                        position
+                    }),
+            }
+            .ok_or_else(|| Error::UnknownKeyword {
+                keyword_span: argument.keyword_span().expect("exists"),
+                ident_span,
+                keywords: parameters.to_vec(),
+            })?;
+
+            if argument.expr().type_def(state).is_fallible() {
+                return Err(Error::FallibleArgument {
+                    expr_span: argument.span(),
+                });
+            }
+
+            bound[position] = Some(argument);
+        }
+
+        let mut bound_parameters = Vec::with_capacity(parameters.len());
+        let mut bound_arguments = Vec::with_capacity(parameters.len());
+
+        for (position, (parameter, argument)) in parameters.iter().zip(bound).enumerate() {
+            let argument = argument.ok_or_else(|| Error::MissingArgument {
+                call_span,
+                keyword: parameter.clone(),
+                position,
+            })?;
+
+            let type_def = argument.expr().type_def(state).infallible();
+            let value = argument.expr().as_value();
+
+            bound_parameters.push((parameter.clone(), type_def, value));
+            bound_arguments.push(argument.into_inner());
+        }
+
+        Ok(Self {
+            parameters: bound_parameters,
+            arguments: bound_arguments,
+        })
+    }
+}
+
+impl UserFunctionCall {
+    pub(crate) fn new(
+        call_span: Span,
+        ident: Node<Ident>,
+        abort_on_error: bool,
+        arguments: Arguments,
+        locals: Vec<Ident>,
+        block: Block,
+        block_type_def: TypeDef,
+    ) -> Result<Self, Error> {
+        let (ident_span, ident) = ident.take();
+
+        // Asking for an infallible function to abort on error makes no sense.
+        if abort_on_error && !block_type_def.is_fallible() {
+            return Err(Error::AbortInfallible {
+                ident_span,
+                abort_span: Span::new(ident_span.end(), ident_span.end() + 1),
+            });
+        }
+
+        Ok(Self {
+            ident,
+            abort_on_error,
+            span: call_span,
+            arguments: arguments.arguments,
+            parameters: arguments
+                .parameters
+                .into_iter()
+                .map(|(parameter, _, _)| parameter)
+                .collect(),
+            locals,
+            block,
+            block_type_def,
+        })
+    }
+
+    pub fn noop(ident: Ident) -> Self {
+        Self {
+            ident,
+            abort_on_error: false,
+            span: Span::default(),
+            arguments: vec![],
+            parameters: vec![],
+            locals: vec![],
+            block: Block::new(vec![]),
+            block_type_def: TypeDef::null().infallible(),
+        }
+    }
+}
+
+impl Expression for UserFunctionCall {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let values = self
+            .arguments
+            .iter()
+            .map(|argument| argument.resolve(ctx))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        with_variables(ctx, &self.parameters, &self.locals, values, |ctx| {
+            self.block.resolve(ctx)
+        })
+        .map_err(|err| call_error(self.ident.as_ref(), self.span, err))
+    }
+
+    fn type_def(&self, _: &State) -> TypeDef {
+        let mut type_def = self.block_type_def.clone();
+
+        if self.abort_on_error {
+            type_def = type_def.infallible();
+        }
+
+        type_def
+    }
+
+    fn compile_to_vm(
+        &self,
+        vm: &mut crate::vm::Vm,
+        state: &mut crate::state::Compiler,
+    ) -> Result<(), String> {
+        // The function body is written inline, and jumped over until the
+        // function is called.
+        let jump = vm.emit_jump(OpCode::Jump);
+        let start = vm.instructions().len();
+        self.block.compile_to_vm(vm, state)?;
+        vm.write_opcode(OpCode::Return);
+        vm.patch_jump(jump);
+
+        let function = vm.add_user_function(
+            self.ident.clone(),
+            start,
+            self.parameters.clone(),
+            self.locals.clone(),
+        );
+
+        // Push the arguments onto the stack, in the order of the parameters.
+        for argument in &self.arguments {
+            argument.compile_to_vm(vm, state)?;
+        }
+
+        vm.write_opcode(OpCode::CallUserFunction);
+        vm.write_primitive(function);
+
+        // We need to write the spans for error reporting.
+        vm.write_primitive(self.span.start());
+        vm.write_primitive(self.span.end());
+
+        Ok(())
+    }
+}
+
+/// Annotate an error returned by a user-defined function with the call it
+/// originates from, the same way errors of built-in functions are.
+///
+/// An abort statement within the function aborts the whole program.
+pub(crate) fn call_error(ident: &str, span: Span, err: ExpressionError) -> ExpressionError {
+    match err {
+        ExpressionError::Abort { .. } => err,
+        ExpressionError::Error {
+            message,
+            mut labels,
+            notes,
+        } => {
+            labels.push(Label::primary(message.clone(), span));
+
+            ExpressionError::Error {
+                message: format!(
+                    r#"function call error for "{}" at ({}:{}): {}"#,
+                    ident,
+                    span.start(),
+                    span.end(),
+                    message
+                ),
+                labels,
+                notes,
+            }
+        }
+    }
+}
+
+impl fmt::Display for UserFunctionCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.ident.fmt(f)?;
+
+        if self.abort_on_error {
+            f.write_str("!")?;
+        }
+
+        f.write_str("(")?;
+
+        let mut iter = self.arguments.iter().peekable();
+        while let Some(argument) = iter.next() {
+            argument.fmt(f)?;
+
+            if iter.peek().is_some() {
+                f.write_str(", ")?;
+            }
+        }
+
+        f.write_str(")")
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum Error {
+    #[error("wrong number of function arguments")]
+    WrongNumberOfArgs { arguments_span: Span, max: usize },
+
+    #[error("unknown function argument keyword")]
+    UnknownKeyword {
+        keyword_span: Span,
+        ident_span: Span,
+        keywords: Vec<Ident>,
+    },
+
+    #[error("missing function argument")]
+    MissingArgument {
+        call_span: Span,
+        keyword: Ident,
+        position: usize,
+    },
+
+    #[error("can't abort infallible function")]
+    AbortInfallible { ident_span: Span, abort_span: Span },
+
+    #[error("fallible argument")]
+    FallibleArgument { expr_span: Span },
+
+    #[error("unexpected closure")]
+    UnexpectedClosure { call_span: Span, closure_span: Span },
+
+    #[error("recursive function call")]
+    Recursion { ident_span: Span, ident: Ident },
+}
+
+impl DiagnosticError for Error {
+    fn code(&self) -> usize {
+        use Error::*;
+
+        match self {
+            WrongNumberOfArgs { .. } => 106,
+            MissingArgument { .. } => 107,
+            UnknownKeyword { .. } => 108,
+            UnexpectedClosure { .. } => 109,
+            Recursion { .. } => 123,
+            AbortInfallible { .. } => 620,
+            FallibleArgument { .. } => 630,
+        }
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        use Error::*;
+
+        match self {
+            WrongNumberOfArgs {
+                arguments_span,
+                max,
+            } => {
+                let arg = if *max == 1 { "argument" } else { "arguments" };
+
+                vec![
+                    Label::primary("too many function arguments", arguments_span),
+                    Label::context(
+                        format!("this function takes a maximum of {} {}", max, arg),
+                        arguments_span,
+                    ),
+                ]
+            }
+
+            UnknownKeyword {
+                keyword_span,
+                ident_span,
+                keywords,
+            } => vec![
+                Label::primary("unknown keyword", keyword_span),
+                Label::context(
+                    format!(
+                        "this function accepts the following keywords: {}",
+                        keywords
+                            .iter()
+                            .map(|k| format!(r#""{}""#, k))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    ident_span,
+                ),
+            ],
+
+            MissingArgument {
+                call_span,
+                keyword,
+                position,
+            } => vec![Label::primary(
+                format!(
+                    r#"required argument missing: "{}" (position {})"#,
+                    keyword, position
+                ),
+                call_span,
+            )],
+
+            AbortInfallible {
+                ident_span,
+                abort_span,
+            } => vec![
+                Label::primary("this function can't fail", ident_span),
+                Label::context("remove this abort-instruction", abort_span),
+            ],
+
+            FallibleArgument { expr_span } => vec![
+                Label::primary("this expression can fail", expr_span),
+                Label::context(
+                    "handle the error before passing it in as an argument",
+                    expr_span,
+                ),
+            ],
+
+            UnexpectedClosure {
+                call_span,
+                closure_span,
+            } => vec![
+                Label::primary("unexpected closure", closure_span),
+                Label::context("this function does not accept a closure", call_span),
+            ],
+
+            Recursion { ident_span, ident } => vec![
+                Label::primary(
+                    format!(r#"the function "{}" is already being called"#, ident),
+                    ident_span,
+                ),
+                Label::context("functions can't call themselves", ident_span),
+            ],
+        }
+    }
+
+    fn notes(&self) -> Vec<Note> {
+        use Error::*;
+
+        match self {
+            AbortInfallible { .. } | FallibleArgument { .. } => vec![Note::SeeErrorDocs],
+            _ => vec![Note::SeeDocs(
+                "user-defined functions".to_owned(),
+                Urls::expression_docs_url("#function-definition"),
+            )],
+        }
+    }
+}
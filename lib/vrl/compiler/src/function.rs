@@ -17,6 +17,11 @@ use crate::{
     Context, ExpressionError, Span, Value,
 };
 
+pub mod closure;
+pub mod user;
+
+use closure::FunctionClosure;
+
 pub type Compiled = Result<Box<dyn Expression>, Box<dyn DiagnosticError>>;
 pub type CompiledArgument =
     Result<Option<Box<dyn std::any::Any + Send + Sync>>, Box<dyn DiagnosticError>>;
@@ -65,6 +70,15 @@ pub trait Function: Send + Sync + fmt::Debug {
         &[]
     }
 
+    /// The closure this function accepts, if any.
+    ///
+    /// A function accepting a closure has to be called with one, and receives
+    /// it through [`ArgumentList::required_closure`] at compile-time, or
+    /// [`VmArgumentList::required_closure`] when called by the VM.
+    fn closure(&self) -> Option<closure::Definition> {
+        None
+    }
+
     /// Implement this function if you need to manipulate and store any function parameters
     /// at compile time.
     fn compile_argument(
@@ -208,7 +222,12 @@ impl Parameter {
 // -----------------------------------------------------------------------------
 
 #[derive(Debug, Default, Clone)]
-pub struct ArgumentList(HashMap<&'static str, Expr>);
+pub struct ArgumentList {
+    arguments: HashMap<&'static str, Expr>,
+
+    /// The closure passed to the function call, if any.
+    closure: Option<FunctionClosure>,
+}
 
 impl ArgumentList {
     pub fn optional(&mut self, keyword: &'static str) -> Option<Box<dyn Expression>> {
@@ -366,16 +385,33 @@ impl ArgumentList {
         Ok(required(self.optional_array(keyword)?))
     }
 
+    pub fn optional_closure(&mut self) -> Option<FunctionClosure> {
+        self.closure.take()
+    }
+
+    pub fn required_closure(&mut self) -> Result<FunctionClosure, Error> {
+        self.optional_closure()
+            .ok_or(Error::ExpectedFunctionClosure)
+    }
+
     pub(crate) fn keywords(&self) -> Vec<&'static str> {
-        self.0.keys().copied().collect::<Vec<_>>()
+        self.arguments.keys().copied().collect::<Vec<_>>()
     }
 
     pub(crate) fn insert(&mut self, k: &'static str, v: Expr) {
-        self.0.insert(k, v);
+        self.arguments.insert(k, v);
+    }
+
+    pub(crate) fn get(&self, keyword: &'static str) -> Option<&Expr> {
+        self.arguments.get(keyword)
+    }
+
+    pub(crate) fn set_closure(&mut self, closure: FunctionClosure) {
+        self.closure = Some(closure);
     }
 
     fn optional_expr(&mut self, keyword: &'static str) -> Option<Expr> {
-        self.0.remove(keyword)
+        self.arguments.remove(keyword)
     }
 
     fn required_expr(&mut self, keyword: &'static str) -> Expr {
@@ -389,11 +425,13 @@ fn required<T>(argument: Option<T>) -> T {
 
 impl From<HashMap<&'static str, Value>> for ArgumentList {
     fn from(map: HashMap<&'static str, Value>) -> Self {
-        Self(
-            map.into_iter()
+        Self {
+            arguments: map
+                .into_iter()
                 .map(|(k, v)| (k, v.into()))
                 .collect::<HashMap<_, _>>(),
-        )
+            closure: None,
+        }
     }
 }
 
@@ -411,13 +449,16 @@ impl From<Vec<Node<FunctionArgument>>> for ArgumentList {
             })
             .collect::<HashMap<_, _>>();
 
-        Self(arguments)
+        Self {
+            arguments,
+            closure: None,
+        }
     }
 }
 
 impl From<ArgumentList> for Vec<(&'static str, Option<FunctionArgument>)> {
     fn from(args: ArgumentList) -> Self {
-        args.0
+        args.arguments
             .iter()
             .map(|(key, expr)| {
                 (
@@ -459,6 +500,9 @@ pub enum Error {
         value: Value,
         error: &'static str,
     },
+
+    #[error("missing function closure")]
+    ExpectedFunctionClosure,
 }
 
 impl diagnostic::DiagnosticError for Error {
@@ -470,6 +514,7 @@ impl diagnostic::DiagnosticError for Error {
             InvalidEnumVariant { .. } => 401,
             ExpectedStaticExpression { .. } => 402,
             InvalidArgument { .. } => 403,
+            ExpectedFunctionClosure => 404,
         }
     }
 
@@ -533,6 +578,10 @@ impl diagnostic::DiagnosticError for Error {
                 Label::context(format!("received: {}", value), Span::default()),
                 Label::context(format!("error: {}", error), Span::default()),
             ],
+
+            ExpectedFunctionClosure => {
+                vec![Label::primary("expected function closure", Span::default())]
+            }
         }
     }
 
@@ -0,0 +1,262 @@
+//! Closures passed to function calls.
+//!
+//! A function accepting a closure describes it using a [`Definition`], which
+//! the compiler uses to type-check the closure variables and its return value:
+//!
+//! ```text
+//! for_each(.tags) -> |key, value| { .seen = push(.seen, key) }
+//! ```
+//!
+//! VRL has no loop constructs, and closures can only be called by the
+//! function they are passed to. The functions iterate over a copy of a
+//! finite collection, so a program using closures still runs a bounded number
+//! of expressions.
+
+use std::fmt;
+
+use crate::{
+    expression::{Block, Resolved},
+    parser::Ident,
+    value::{kind::Collection, Kind},
+    vm::Vm,
+    Context, Expression, TypeDef, Value,
+};
+
+/// The definition of a closure a function accepts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+    /// A list of possible inputs for the closure.
+    ///
+    /// The input applying to a given call is chosen by matching the kind of
+    /// the target argument against [`Input::kind`]. If multiple inputs match,
+    /// the closure variables get the union of their kinds.
+    pub inputs: Vec<Input>,
+}
+
+/// One possible input for a closure.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Input {
+    /// The keyword of the function parameter the closure iterates over.
+    pub parameter_keyword: &'static str,
+
+    /// The kind of the target argument this input applies to.
+    pub kind: Kind,
+
+    /// The variables the closure receives, in order.
+    pub variables: Vec<Variable>,
+
+    /// The value the closure is expected to return.
+    pub output: Output,
+}
+
+/// A variable passed to a closure.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variable {
+    /// The kind of the variable.
+    pub kind: VariableKind,
+}
+
+/// The kind of a closure variable.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariableKind {
+    /// The variable always has the given kind.
+    Exact(Kind),
+
+    /// The variable holds the key of an element of the target argument, i.e.
+    /// a string for objects and an integer for arrays.
+    TargetInnerKey,
+
+    /// The variable holds the value of an element of the target argument.
+    TargetInnerValue,
+}
+
+/// The value a closure is expected to return.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Output {
+    /// The closure can return any value.
+    Any,
+
+    /// The closure has to return a value of the given kind.
+    Kind(Kind),
+}
+
+impl Input {
+    /// Get the kind of the closure variable at the given position, given the
+    /// kind of the target argument.
+    pub(crate) fn variable_kind(&self, position: usize, target: &Kind) -> Kind {
+        let object = target.as_object().filter(|_| self.kind.contains_object());
+        let array = target.as_array().filter(|_| self.kind.contains_array());
+
+        match self.variables.get(position).map(|v| &v.kind) {
+            Some(VariableKind::Exact(kind)) => kind.clone(),
+            Some(VariableKind::TargetInnerKey) => {
+                let mut kind = Kind::empty();
+                if object.is_some() {
+                    kind.add_bytes();
+                }
+                if array.is_some() {
+                    kind.add_integer();
+                }
+                kind
+            }
+            Some(VariableKind::TargetInnerValue) => {
+                let mut kind = Kind::empty();
+                if let Some(object) = object {
+                    kind = kind | inner_kind(object.clone());
+                }
+                if let Some(array) = array {
+                    kind = kind | inner_kind(array.clone());
+                }
+                kind
+            }
+            None => Kind::any(),
+        }
+    }
+}
+
+/// Get the kind of any element of the collection.
+///
+/// An empty collection never runs the closure, so it has no meaningful
+/// element kind, and any kind is assumed.
+fn inner_kind<T: Ord + Clone>(mut collection: Collection<T>) -> Kind {
+    collection.anonymize();
+    collection
+        .unknown()
+        .map_or_else(Kind::any, |unknown| unknown.to_kind().into_owned())
+}
+
+/// A compiled closure, passed to the function it belongs to through the
+/// [`ArgumentList`](super::ArgumentList).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionClosure {
+    variables: Vec<Ident>,
+    locals: Vec<Ident>,
+    block: Block,
+    block_type_def: TypeDef,
+}
+
+impl FunctionClosure {
+    pub(crate) fn new(
+        variables: Vec<Ident>,
+        locals: Vec<Ident>,
+        block: Block,
+        block_type_def: TypeDef,
+    ) -> Self {
+        Self {
+            variables,
+            locals,
+            block,
+            block_type_def,
+        }
+    }
+
+    /// The names of the closure variables.
+    pub fn variables(&self) -> &[Ident] {
+        &self.variables
+    }
+
+    /// The type definition of the value returned by the closure.
+    ///
+    /// This is computed when the closure is compiled, as the closure
+    /// variables are only in scope within the closure.
+    pub fn type_def(&self) -> &TypeDef {
+        &self.block_type_def
+    }
+
+    pub(crate) fn block(&self) -> &Block {
+        &self.block
+    }
+
+    pub(crate) fn locals(&self) -> &[Ident] {
+        &self.locals
+    }
+
+    /// Run the closure once, with the given values bound to its variables.
+    pub fn run(&self, ctx: &mut Context, values: impl IntoIterator<Item = Value>) -> Resolved {
+        with_variables(ctx, &self.variables, &self.locals, values, |ctx| {
+            self.block.resolve(ctx)
+        })
+    }
+}
+
+impl fmt::Display for FunctionClosure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("|")?;
+
+        let mut iter = self.variables.iter().peekable();
+        while let Some(variable) = iter.next() {
+            variable.fmt(f)?;
+
+            if iter.peek().is_some() {
+                f.write_str(", ")?;
+            }
+        }
+
+        write!(f, "| {}", self.block)
+    }
+}
+
+/// A closure compiled into the instructions of the [`Vm`], passed to the
+/// function it belongs to through the [`VmArgumentList`](crate::vm::VmArgumentList).
+#[derive(Clone, Copy)]
+pub struct VmFunctionClosure<'a> {
+    vm: &'a Vm,
+    closure: &'a crate::vm::VmClosure,
+}
+
+impl<'a> VmFunctionClosure<'a> {
+    pub(crate) fn new(vm: &'a Vm, closure: &'a crate::vm::VmClosure) -> Self {
+        Self { vm, closure }
+    }
+
+    /// Run the closure once, with the given values bound to its variables.
+    pub fn run(&self, ctx: &mut Context, values: impl IntoIterator<Item = Value>) -> Resolved {
+        let closure = self.closure;
+
+        with_variables(ctx, &closure.variables, &closure.locals, values, |ctx| {
+            self.vm.interpret_from(ctx, closure.start)
+        })
+    }
+}
+
+/// Bind the values to the closure variables for the duration of `f`.
+///
+/// Variables declared within the closure are removed afterwards, and any
+/// variable shadowed by a closure variable or a variable declared within the
+/// closure gets its previous value back.
+///
+/// This is also used to run the body of user-defined functions, with the
+/// function parameters as the closure variables.
+pub(crate) fn with_variables<F>(
+    ctx: &mut Context,
+    variables: &[Ident],
+    locals: &[Ident],
+    values: impl IntoIterator<Item = Value>,
+    f: F,
+) -> Resolved
+where
+    F: FnOnce(&mut Context) -> Resolved,
+{
+    let shadowed = variables
+        .iter()
+        .chain(locals)
+        .map(|ident| ctx.state().variable(ident).cloned())
+        .collect::<Vec<_>>();
+
+    for (ident, value) in variables.iter().zip(values) {
+        ctx.state_mut().insert_variable(ident.clone(), value);
+    }
+
+    let resolved = f(ctx);
+
+    for (ident, value) in variables.iter().chain(locals).zip(shadowed) {
+        match value {
+            Some(value) => ctx.state_mut().insert_variable(ident.clone(), value),
+            None => {
+                ctx.state_mut().remove_variable(ident);
+            }
+        }
+    }
+
+    resolved
+}
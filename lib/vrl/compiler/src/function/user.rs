@@ -0,0 +1,238 @@
+//! User-defined functions.
+//!
+//! Programs can define functions at their root, and import the functions
+//! defined in other VRL files, found in the import paths configured on the
+//! compiler [`state`](crate::state::Compiler):
+//!
+//! ```text
+//! import "http.vrl"
+//!
+//! fn normalize_status(status) {
+//!     int(status) ?? 0
+//! }
+//!
+//! .status = normalize_status(.status)
+//! ```
+//!
+//! The body of a function is compiled for each call, with its parameters
+//! typed by the arguments of that call, so the types of the arguments and of
+//! the returned value are checked across the call boundary. The bodies of the
+//! functions that are never called are compiled with parameters of any type,
+//! so that their errors are still reported.
+//!
+//! Functions can't call themselves, directly or indirectly, so a program using
+//! them still runs a bounded number of expressions.
+
+use std::{
+    fmt,
+    path::{Component, Path, PathBuf},
+};
+
+use diagnostic::{DiagnosticError, Label, Note, SourceFile, Urls};
+
+use crate::{
+    parser::{ast, Ident},
+    Span,
+};
+
+/// A function defined in a program, or in a file imported by it.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct UserFunction {
+    definition: ast::FunctionDefinition,
+
+    /// The imported file the function is defined in, if any.
+    file: Option<SourceFile>,
+}
+
+impl UserFunction {
+    pub(crate) fn new(definition: ast::FunctionDefinition, file: Option<SourceFile>) -> Self {
+        Self { definition, file }
+    }
+
+    pub(crate) fn definition(&self) -> &ast::FunctionDefinition {
+        &self.definition
+    }
+
+    pub(crate) fn file(&self) -> Option<&SourceFile> {
+        self.file.as_ref()
+    }
+}
+
+/// Find the file to import in the import paths.
+///
+/// Imports are relative to one of the import paths, and can't point outside
+/// of them.
+pub(crate) fn resolve_import(import_paths: &[PathBuf], path: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(path);
+
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(r#"imports must be relative paths, without any ".." components"#.to_owned());
+    }
+
+    if import_paths.is_empty() {
+        return Err("no import paths are configured".to_owned());
+    }
+
+    import_paths
+        .iter()
+        .map(|directory| directory.join(relative))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            format!(
+                "file not found in the import paths: {}",
+                import_paths
+                    .iter()
+                    .map(|path| format!(r#""{}""#, path.display()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })
+}
+
+/// Whether the error with the given code depends on the types of the
+/// parameters, which are only known when the function is called.
+pub(crate) const fn depends_on_parameter_types(code: usize) -> bool {
+    // Non-boolean predicate, unhandled fallible assignment, fallible predicate
+    // and fallible argument of a user-defined function.
+    matches!(code, 102 | 103 | 111 | 630)
+}
+
+// -----------------------------------------------------------------------------
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("function redefinition")]
+    Redefinition {
+        ident_span: Span,
+        ident: Ident,
+        builtin: bool,
+    },
+
+    #[error("invalid import")]
+    InvalidImport { path_span: Span, reason: String },
+
+    #[error("unexpected expression in imported file")]
+    UnexpectedImportedExpression { expr_span: Span },
+}
+
+impl DiagnosticError for Error {
+    fn code(&self) -> usize {
+        use Error::*;
+
+        match self {
+            Redefinition { .. } => 124,
+            InvalidImport { .. } => 125,
+            UnexpectedImportedExpression { .. } => 126,
+        }
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        use Error::*;
+
+        match self {
+            Redefinition {
+                ident_span,
+                ident,
+                builtin,
+            } => {
+                let message = if *builtin {
+                    format!(r#"the built-in function "{}" already exists"#, ident)
+                } else {
+                    format!(r#"the function "{}" is already defined"#, ident)
+                };
+
+                vec![
+                    Label::primary(message, ident_span),
+                    Label::context("rename this function", ident_span),
+                ]
+            }
+
+            InvalidImport { path_span, reason } => vec![
+                Label::primary("this file can't be imported", path_span),
+                Label::context(reason, path_span),
+            ],
+
+            UnexpectedImportedExpression { expr_span } => vec![
+                Label::primary("unexpected expression", expr_span),
+                Label::context(
+                    "imported files can only contain function definitions and imports",
+                    expr_span,
+                ),
+            ],
+        }
+    }
+
+    fn notes(&self) -> Vec<Note> {
+        vec![Note::SeeDocs(
+            "user-defined functions".to_owned(),
+            Urls::expression_docs_url("#function-definition"),
+        )]
+    }
+}
+
+/// An error pointing into an imported file.
+pub(crate) struct Imported {
+    file: SourceFile,
+    error: Box<dyn DiagnosticError>,
+}
+
+impl Imported {
+    /// Attribute the error to the given file, unless it already points into
+    /// another imported file.
+    pub(crate) fn wrap(
+        file: &SourceFile,
+        error: Box<dyn DiagnosticError>,
+    ) -> Box<dyn DiagnosticError> {
+        if error.file().is_some() {
+            return error;
+        }
+
+        Box::new(Self {
+            file: file.clone(),
+            error,
+        })
+    }
+}
+
+impl fmt::Display for Imported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl fmt::Debug for Imported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Imported({:?}, {:?})", self.file.name(), self.error)
+    }
+}
+
+impl std::error::Error for Imported {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+impl DiagnosticError for Imported {
+    fn code(&self) -> usize {
+        self.error.code()
+    }
+
+    fn message(&self) -> String {
+        self.error.message()
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        self.error.labels()
+    }
+
+    fn notes(&self) -> Vec<Note> {
+        self.error.notes()
+    }
+
+    fn file(&self) -> Option<SourceFile> {
+        Some(self.file.clone())
+    }
+}
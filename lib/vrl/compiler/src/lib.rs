@@ -10,6 +10,7 @@ mod context;
 mod program;
 mod test_util;
 
+pub mod coverage;
 pub mod expression;
 pub mod function;
 pub mod state;
@@ -38,6 +39,7 @@ pub type Result = std::result::Result<Program, compiler::Errors>;
 pub enum VrlRuntime {
     Ast,
     Vm,
+    Jit,
 }
 
 impl Default for VrlRuntime {
@@ -53,7 +55,8 @@ impl FromStr for VrlRuntime {
         match s {
             "ast" => Ok(Self::Ast),
             "vm" => Ok(Self::Vm),
-            _ => Err("runtime must be ast or vm."),
+            "jit" => Ok(Self::Jit),
+            _ => Err("runtime must be ast, vm or jit."),
         }
     }
 }
@@ -66,6 +69,7 @@ impl Display for VrlRuntime {
             match self {
                 VrlRuntime::Ast => "ast",
                 VrlRuntime::Vm => "vm",
+                VrlRuntime::Jit => "jit",
             }
         )
     }
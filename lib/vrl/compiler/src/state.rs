@@ -1,9 +1,15 @@
 use anymap::AnyMap;
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 use value::Kind;
 
-use crate::{expression::assignment, parser::ast::Ident, Value};
+use crate::{
+    coverage::Coverage, expression::assignment, function::user::UserFunction, parser::ast::Ident,
+    Span, TypeDef, Value,
+};
 
 /// The state held by the compiler.
 ///
@@ -21,9 +27,24 @@ pub struct Compiler {
     /// Stored internal variable type definitions.
     variables: HashMap<Ident, assignment::Details>,
 
+    /// User-defined functions, either defined in the program or imported.
+    functions: HashMap<Ident, UserFunction>,
+
+    /// The directories searched for imported VRL files.
+    import_paths: Vec<PathBuf>,
+
+    /// The files already imported, which are skipped when imported again.
+    imported: HashSet<PathBuf>,
+
     /// Context passed between the client program and a VRL function.
     external_context: AnyMap,
 
+    /// The coverage of the compiled program, if enabled.
+    coverage: Option<Coverage>,
+
+    /// The type definitions of the compiled expressions, if recorded.
+    types: Option<Vec<(Span, TypeDef)>>,
+
     /// On request, the compiler can store its state in this field, which can
     /// later be used to revert the compiler state to the previously stored
     /// state.
@@ -43,6 +64,11 @@ impl Default for Compiler {
             external_context: AnyMap::new(),
             target: None,
             variables: HashMap::default(),
+            functions: HashMap::default(),
+            import_paths: vec![],
+            imported: HashSet::default(),
+            coverage: None,
+            types: None,
             snapshot: None,
         }
     }
@@ -83,6 +109,39 @@ impl Compiler {
         self.variables.insert(ident, details);
     }
 
+    pub(crate) fn remove_variable(&mut self, ident: &Ident) -> Option<assignment::Details> {
+        self.variables.remove(ident)
+    }
+
+    /// Set the directories searched for files imported by the program.
+    ///
+    /// Without any import paths, programs can't import files.
+    pub fn set_import_paths(&mut self, paths: Vec<PathBuf>) {
+        self.import_paths = paths;
+    }
+
+    pub(crate) fn import_paths(&self) -> &[PathBuf] {
+        &self.import_paths
+    }
+
+    /// Mark the file at the given path as imported, returning `false` if it
+    /// was imported before.
+    pub(crate) fn insert_import(&mut self, path: PathBuf) -> bool {
+        self.imported.insert(path)
+    }
+
+    pub(crate) fn function(&self, ident: &Ident) -> Option<&UserFunction> {
+        self.functions.get(ident)
+    }
+
+    pub(crate) fn function_idents(&self) -> impl Iterator<Item = &Ident> {
+        self.functions.keys()
+    }
+
+    pub(crate) fn insert_function(&mut self, ident: Ident, function: UserFunction) {
+        self.functions.insert(ident, function);
+    }
+
     pub(crate) fn target(&self) -> Option<&assignment::Details> {
         self.target.as_ref()
     }
@@ -101,7 +160,12 @@ impl Compiler {
         let snapshot = Self {
             target,
             variables,
+            functions: self.functions.clone(),
+            import_paths: self.import_paths.clone(),
+            imported: self.imported.clone(),
             external_context: AnyMap::new(),
+            coverage: None,
+            types: None,
             snapshot: None,
         };
 
@@ -112,8 +176,45 @@ impl Compiler {
     pub(crate) fn rollback(&mut self) {
         if let Some(snapshot) = self.snapshot.take() {
             let external_context = self.swap_external_context(AnyMap::new());
+            let coverage = self.coverage.take();
+            let types = self.types.take();
             *self = *snapshot;
             self.external_context = external_context;
+            self.coverage = coverage;
+            self.types = types;
+        }
+    }
+
+    /// Record which statements and branches of the compiled program are
+    /// executed at runtime.
+    pub fn enable_coverage(&mut self) {
+        self.coverage = Some(Coverage::default());
+    }
+
+    /// The coverage of the compiled program, if enabled.
+    pub fn coverage(&self) -> Option<&Coverage> {
+        self.coverage.as_ref()
+    }
+
+    pub(crate) fn coverage_mut(&mut self) -> Option<&mut Coverage> {
+        self.coverage.as_mut()
+    }
+
+    /// Record the type definition of each expression of the compiled
+    /// program, as used by editors to show the type of an expression.
+    pub fn record_types(&mut self) {
+        self.types = Some(vec![]);
+    }
+
+    /// The span and type definition of each compiled expression, in the
+    /// order they were compiled, if recorded.
+    pub fn types(&self) -> Option<&[(Span, TypeDef)]> {
+        self.types.as_deref()
+    }
+
+    pub(crate) fn record_type(&mut self, span: Span, type_def: TypeDef) {
+        if let Some(types) = self.types.as_mut() {
+            types.push((span, type_def));
         }
     }
 
@@ -169,4 +270,8 @@ impl Runtime {
     pub(crate) fn insert_variable(&mut self, ident: Ident, value: Value) {
         self.variables.insert(ident, value);
     }
+
+    pub(crate) fn remove_variable(&mut self, ident: &Ident) -> Option<Value> {
+        self.variables.remove(ident)
+    }
 }
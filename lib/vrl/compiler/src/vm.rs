@@ -37,13 +37,20 @@
 //! calculated during compilation. The index of the paramter is passed to the
 //! function during runtime, allowing it to downcast the data to the correct
 //! type and use as necessary.
+//!
+//! # JIT
+//! With the `jit` feature, `Jit` compiles the instructions to native code,
+//! which runs each operation with the same implementation as the
+//! interpreter.
 
 mod argument_list;
+mod jit;
 mod machine;
 mod state;
 mod variable;
 
 pub use argument_list::{compile_arguments, function_compile_arguments, VmArgumentList};
+pub use jit::Jit;
 pub use machine::OpCode;
-pub use machine::Vm;
+pub use machine::{Vm, VmClosure, VmUserFunction};
 pub use variable::Variable;
@@ -2,7 +2,7 @@ use diagnostic::DiagnosticError;
 
 use crate::{
     expression::{Expr, FunctionArgument},
-    function::FunctionCompileContext,
+    function::{closure::VmFunctionClosure, FunctionCompileContext},
     value::Kind,
     ExpressionError, Function, Parameter, Value,
 };
@@ -42,11 +42,28 @@ impl<'a> VmArgument<'a> {
 pub struct VmArgumentList<'a> {
     args: &'static [Parameter],
     values: Vec<Option<VmArgument<'a>>>,
+    closure: Option<VmFunctionClosure<'a>>,
 }
 
 impl<'a> VmArgumentList<'a> {
     pub fn new(args: &'static [Parameter], values: Vec<Option<VmArgument<'a>>>) -> Self {
-        Self { args, values }
+        Self {
+            args,
+            values,
+            closure: None,
+        }
+    }
+
+    /// Sets the closure passed to the function.
+    pub fn with_closure(mut self, closure: Option<VmFunctionClosure<'a>>) -> Self {
+        self.closure = closure;
+        self
+    }
+
+    /// Returns the closure passed to the function.
+    /// Note that this can only be called once since the closure is removed from the list.
+    pub fn required_closure(&mut self) -> VmFunctionClosure<'a> {
+        self.closure.take().expect("closure is required")
     }
 
     fn argument_pos(&self, name: &str) -> usize {
@@ -188,5 +205,6 @@ where
     VmArgumentList {
         args: params,
         values,
+        closure: None,
     }
 }
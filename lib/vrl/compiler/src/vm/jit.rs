@@ -0,0 +1,73 @@
+//! Ahead-of-time compilation of the VM to native code.
+//!
+//! The instructions of a [`Vm`] are translated with Cranelift into a native
+//! function, removing the dispatch over the `OpCode` of each instruction and
+//! turning jumps into native branches. Each operation is run by a call to a
+//! helper sharing its implementation with the interpreter, and the bodies of
+//! closures and user-defined functions are still run by interpreting the VM.
+//! Function calls run the VM implementation of the function, so every function
+//! is supported.
+//!
+//! Native compilation requires the `jit` feature. Without it, [`Jit::new`]
+//! fails, so that callers can run the whole program on the VM instead.
+
+#[cfg(feature = "jit")]
+mod native;
+
+use std::sync::Arc;
+
+use super::Vm;
+use crate::{Context, ExpressionError, Value};
+
+/// A [`Vm`] compiled to native code.
+pub struct Jit {
+    vm: Arc<Vm>,
+
+    #[cfg(feature = "jit")]
+    code: native::Code,
+}
+
+impl Jit {
+    /// Compile the instructions of the VM to native code.
+    ///
+    /// Returns an error if native compilation isn't available for this build
+    /// or target.
+    #[cfg(feature = "jit")]
+    pub fn new(vm: Arc<Vm>) -> Result<Self, String> {
+        let code = native::Code::compile(&vm)?;
+
+        Ok(Self { vm, code })
+    }
+
+    /// Compile the instructions of the VM to native code.
+    ///
+    /// Returns an error if native compilation isn't available for this build
+    /// or target.
+    #[cfg(not(feature = "jit"))]
+    pub fn new(_vm: Arc<Vm>) -> Result<Self, String> {
+        Err("VRL was built without the `jit` feature".to_owned())
+    }
+
+    /// The VM the native code was compiled from.
+    pub fn vm(&self) -> &Arc<Vm> {
+        &self.vm
+    }
+
+    /// Run the native code, with the same result as interpreting the VM.
+    #[cfg(feature = "jit")]
+    pub fn run(&self, ctx: &mut Context<'_>) -> Result<Value, ExpressionError> {
+        self.code.run(&self.vm, ctx)
+    }
+
+    /// Run the native code, with the same result as interpreting the VM.
+    #[cfg(not(feature = "jit"))]
+    pub fn run(&self, ctx: &mut Context<'_>) -> Result<Value, ExpressionError> {
+        self.vm.interpret(ctx)
+    }
+}
+
+impl std::fmt::Debug for Jit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Jit").field("vm", &self.vm).finish()
+    }
+}
@@ -0,0 +1,578 @@
+use std::{
+    any::Any,
+    ffi::c_void,
+    mem::ManuallyDrop,
+    panic::{self, AssertUnwindSafe},
+};
+
+use cranelift_codegen::ir::{self, condcodes::IntCC, types, AbiParam, InstBuilder};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, Linkage, Module};
+
+use crate::{
+    value::VrlValueArithmetic,
+    vm::{
+        argument_list::VmArgument,
+        machine::{
+            abort, binary_op, compare, create_array, create_object, not, return_value, should_jump,
+            Instruction,
+        },
+        state::VmState,
+        OpCode, Vm,
+    },
+    Context, ExpressionError, Value,
+};
+
+/// The helper ran the operation, the program continues with the next one.
+const CONTINUE: i64 = 0;
+
+/// The helper ran a jump operation, and the jump is taken.
+const JUMP: i64 = 1;
+
+/// The program ended, with its result or error stored in the environment.
+const EXIT: i64 = 2;
+
+/// A helper running an operation on the environment, given the primitives
+/// following the `OpCode`.
+type Helper = extern "C" fn(*mut c_void, usize, usize, usize) -> i64;
+
+/// The function compiled from the instructions of a VM, taking a pointer to
+/// the [`Env`] of the run.
+type Function = extern "C" fn(*mut c_void);
+
+/// The mutable state of a run of the native code, passed to each helper.
+struct Env<'a, 'b> {
+    vm: &'a Vm,
+    ctx: &'a mut Context<'b>,
+    state: VmState<'a>,
+
+    /// The result of the program, set when the program ends.
+    result: Option<Result<Value, ExpressionError>>,
+
+    /// A panic caught in a helper, as panics can't unwind through the native
+    /// code. It is resumed once the native code returns.
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+pub(super) struct Code {
+    /// The module owning the memory of the native code.
+    module: ManuallyDrop<JITModule>,
+    function: Function,
+}
+
+// SAFETY: the native code is immutable once compiled and only reads the
+// environment passed to it, so it can be shared and sent between threads. The
+// module is only used to free the native code when dropped.
+unsafe impl Send for Code {}
+unsafe impl Sync for Code {}
+
+impl Drop for Code {
+    fn drop(&mut self) {
+        // SAFETY: the native code can't be called anymore once dropped.
+        unsafe { ManuallyDrop::take(&mut self.module).free_memory() }
+    }
+}
+
+impl Code {
+    /// Compile the instructions of the VM into a native function, with one
+    /// block per `OpCode` calling the helper running the operation, followed by
+    /// a branch to the block of the next operation, to the target of a jump, or
+    /// to the exit.
+    pub(super) fn compile(vm: &Vm) -> Result<Self, String> {
+        let mut builder =
+            JITBuilder::new(default_libcall_names()).map_err(|err| err.to_string())?;
+        for (_, name, helper) in HELPERS {
+            builder.symbol(*name, *helper as *const u8);
+        }
+
+        let mut module = JITModule::new(builder);
+        let pointer = module.target_config().pointer_type();
+
+        let mut helper_signature = module.make_signature();
+        helper_signature.params.extend([AbiParam::new(pointer); 4]);
+        helper_signature.returns.push(AbiParam::new(types::I64));
+
+        let helpers = HELPERS
+            .iter()
+            .map(|(opcode, name, _)| {
+                module
+                    .declare_function(name, Linkage::Import, &helper_signature)
+                    .map(|id| (*opcode, id))
+                    .map_err(|err| err.to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut ctx = module.make_context();
+        ctx.func.signature.params.push(AbiParam::new(pointer));
+
+        let mut builder_context = FunctionBuilderContext::new();
+        let mut builder = FunctionBuilder::new(&mut ctx.func, &mut builder_context);
+
+        let helpers = helpers
+            .into_iter()
+            .map(|(opcode, id)| (opcode, module.declare_func_in_func(id, builder.func)))
+            .collect::<Vec<_>>();
+
+        let instructions = vm.instructions();
+        let blocks = instructions
+            .iter()
+            .map(|instruction| match instruction {
+                Instruction::OpCode(_) => Some(builder.create_block()),
+                Instruction::Primitive(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let block_at = |index: usize| {
+            blocks
+                .get(index)
+                .copied()
+                .flatten()
+                .ok_or_else(|| format!("Expecting opcode at {}", index))
+        };
+
+        let entry = builder.create_block();
+        let exit = builder.create_block();
+
+        builder.append_block_params_for_function_params(entry);
+        builder.switch_to_block(entry);
+        let env = builder.block_params(entry)[0];
+        builder.ins().jump(block_at(0)?, &[]);
+
+        let mut index = 0;
+        while index < instructions.len() {
+            let opcode = match instructions[index] {
+                Instruction::OpCode(opcode) => opcode,
+                Instruction::Primitive(_) => return Err(format!("Expecting opcode at {}", index)),
+            };
+
+            let start = index + 1;
+            let primitives = instructions
+                .get(start..start + operands(opcode))
+                .ok_or_else(|| format!("Expecting primitive at {}", instructions.len()))?
+                .iter()
+                .enumerate()
+                .map(|(offset, instruction)| match instruction {
+                    Instruction::Primitive(primitive) => Ok(*primitive),
+                    Instruction::OpCode(_) => {
+                        Err(format!("Expecting primitive at {}", start + offset))
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let next = start + primitives.len();
+
+            builder.switch_to_block(block_at(index)?);
+
+            if opcode == OpCode::Jump {
+                builder.ins().jump(block_at(next + primitives[0])?, &[]);
+                index = next;
+                continue;
+            }
+
+            let helper = helpers
+                .iter()
+                .find(|(helper_opcode, _)| *helper_opcode == opcode)
+                .map(|(_, helper)| *helper)
+                .ok_or_else(|| format!("{:?} can't be compiled", opcode))?;
+
+            let mut args = vec![env];
+            for i in 0..3 {
+                let primitive = primitives.get(i).copied().unwrap_or_default();
+                args.push(builder.ins().iconst(pointer, primitive as i64));
+            }
+
+            let call = builder.ins().call(helper, &args);
+            let code = builder.inst_results(call)[0];
+
+            match opcode {
+                OpCode::Return | OpCode::Abort => {
+                    builder.ins().jump(exit, &[]);
+                }
+                OpCode::JumpIfFalse
+                | OpCode::JumpIfTrue
+                | OpCode::JumpIfTruthy
+                | OpCode::JumpAndSwapIfFalsey
+                | OpCode::JumpIfNotErr
+                | OpCode::JumpIfErr
+                | OpCode::EndStatement => {
+                    let exited = builder.ins().icmp_imm(IntCC::Equal, code, EXIT);
+                    let taken = builder.create_block();
+                    branch(&mut builder, exited, exit, taken);
+
+                    builder.switch_to_block(taken);
+                    branch(
+                        &mut builder,
+                        code,
+                        block_at(next + primitives[0])?,
+                        block_at(next)?,
+                    );
+                }
+                _ => branch(&mut builder, code, exit, block_at(next)?),
+            }
+
+            index = next;
+        }
+
+        builder.switch_to_block(exit);
+        builder.ins().return_(&[]);
+
+        builder.seal_all_blocks();
+        builder.finalize();
+
+        let id = module
+            .declare_anonymous_function(&ctx.func.signature)
+            .map_err(|err| err.to_string())?;
+        module
+            .define_function(id, &mut ctx)
+            .map_err(|err| err.to_string())?;
+        module.clear_context(&mut ctx);
+        module.finalize_definitions();
+
+        // SAFETY: the function was compiled with the signature of `Function`.
+        let function = unsafe {
+            std::mem::transmute::<*const u8, Function>(module.get_finalized_function(id))
+        };
+
+        Ok(Self {
+            module: ManuallyDrop::new(module),
+            function,
+        })
+    }
+
+    pub(super) fn run(&self, vm: &Vm, ctx: &mut Context<'_>) -> Result<Value, ExpressionError> {
+        let mut env = Env {
+            vm,
+            ctx,
+            state: VmState::new(vm),
+            result: None,
+            panic: None,
+        };
+
+        (self.function)((&mut env as *mut Env).cast());
+
+        if let Some(payload) = env.panic {
+            panic::resume_unwind(payload);
+        }
+
+        env.result
+            .unwrap_or_else(|| Err("program ended without a result".into()))
+    }
+}
+
+/// Branches to `then` if the condition is non-zero, or to `otherwise`.
+fn branch(
+    builder: &mut FunctionBuilder,
+    condition: ir::Value,
+    then: ir::Block,
+    otherwise: ir::Block,
+) {
+    builder.ins().brnz(condition, then, &[]);
+    builder.ins().jump(otherwise, &[]);
+}
+
+/// The number of primitives following the `OpCode`.
+fn operands(opcode: OpCode) -> usize {
+    match opcode {
+        OpCode::SetPathInfallible | OpCode::Call | OpCode::CallUserFunction => 3,
+        OpCode::Abort => 2,
+        OpCode::Constant
+        | OpCode::JumpIfFalse
+        | OpCode::JumpIfTrue
+        | OpCode::JumpIfTruthy
+        | OpCode::JumpAndSwapIfFalsey
+        | OpCode::JumpIfNotErr
+        | OpCode::JumpIfErr
+        | OpCode::Jump
+        | OpCode::EndStatement
+        | OpCode::SetPath
+        | OpCode::GetPath
+        | OpCode::CreateArray
+        | OpCode::CreateObject
+        | OpCode::MoveStaticParameter
+        | OpCode::MoveClosure => 1,
+        OpCode::Return
+        | OpCode::Add
+        | OpCode::Subtract
+        | OpCode::Multiply
+        | OpCode::Divide
+        | OpCode::Rem
+        | OpCode::Merge
+        | OpCode::And
+        | OpCode::Not
+        | OpCode::Greater
+        | OpCode::GreaterEqual
+        | OpCode::Less
+        | OpCode::LessEqual
+        | OpCode::NotEqual
+        | OpCode::Equal
+        | OpCode::Pop
+        | OpCode::ClearError
+        | OpCode::EmptyParameter
+        | OpCode::MoveParameter => 0,
+    }
+}
+
+/// Runs the operation of a helper on the environment, storing errors ending
+/// the program, and catching panics.
+fn guard<F>(env: *mut c_void, operation: F) -> i64
+where
+    F: FnOnce(&mut Env) -> Result<i64, ExpressionError>,
+{
+    // SAFETY: the native code only calls helpers with the environment given
+    // to the function by `Code::run`.
+    let env = unsafe { &mut *env.cast::<Env>() };
+
+    match panic::catch_unwind(AssertUnwindSafe(|| operation(env))) {
+        Ok(Ok(code)) => code,
+        Ok(Err(err)) => {
+            env.result = Some(Err(err));
+            EXIT
+        }
+        Err(payload) => {
+            env.panic = Some(payload);
+            EXIT
+        }
+    }
+}
+
+/// The helper of each `OpCode`, except `Jump`, which is a native branch.
+const HELPERS: &[(OpCode, &str, Helper)] = &[
+    (OpCode::Abort, "vrl_abort", abort_helper),
+    (OpCode::Return, "vrl_return", return_helper),
+    (OpCode::Constant, "vrl_constant", constant),
+    (OpCode::Add, "vrl_add", add),
+    (OpCode::Subtract, "vrl_subtract", subtract),
+    (OpCode::Multiply, "vrl_multiply", multiply),
+    (OpCode::Divide, "vrl_divide", divide),
+    (OpCode::Rem, "vrl_rem", rem),
+    (OpCode::Merge, "vrl_merge", merge),
+    (OpCode::And, "vrl_and", and),
+    (OpCode::Not, "vrl_not", not_helper),
+    (OpCode::Greater, "vrl_greater", greater),
+    (OpCode::GreaterEqual, "vrl_greater_equal", greater_equal),
+    (OpCode::Less, "vrl_less", less),
+    (OpCode::LessEqual, "vrl_less_equal", less_equal),
+    (OpCode::NotEqual, "vrl_not_equal", not_equal),
+    (OpCode::Equal, "vrl_equal", equal),
+    (OpCode::Pop, "vrl_pop", pop),
+    (OpCode::ClearError, "vrl_clear_error", clear_error),
+    (OpCode::JumpIfFalse, "vrl_jump_if_false", jump_if_false),
+    (OpCode::JumpIfTrue, "vrl_jump_if_true", jump_if_true),
+    (OpCode::JumpIfTruthy, "vrl_jump_if_truthy", jump_if_truthy),
+    (
+        OpCode::JumpAndSwapIfFalsey,
+        "vrl_jump_and_swap_if_falsey",
+        jump_and_swap_if_falsey,
+    ),
+    (OpCode::JumpIfNotErr, "vrl_jump_if_not_err", jump_if_not_err),
+    (OpCode::JumpIfErr, "vrl_jump_if_err", jump_if_err),
+    (OpCode::EndStatement, "vrl_end_statement", end_statement),
+    (OpCode::SetPath, "vrl_set_path", set_path),
+    (
+        OpCode::SetPathInfallible,
+        "vrl_set_path_infallible",
+        set_path_infallible,
+    ),
+    (OpCode::GetPath, "vrl_get_path", get_path),
+    (OpCode::Call, "vrl_call", call),
+    (OpCode::CreateArray, "vrl_create_array", create_array_helper),
+    (
+        OpCode::CreateObject,
+        "vrl_create_object",
+        create_object_helper,
+    ),
+    (
+        OpCode::EmptyParameter,
+        "vrl_empty_parameter",
+        empty_parameter,
+    ),
+    (OpCode::MoveParameter, "vrl_move_parameter", move_parameter),
+    (
+        OpCode::MoveStaticParameter,
+        "vrl_move_static_parameter",
+        move_static_parameter,
+    ),
+    (OpCode::MoveClosure, "vrl_move_closure", move_closure),
+    (
+        OpCode::CallUserFunction,
+        "vrl_call_user_function",
+        call_user_function,
+    ),
+];
+
+extern "C" fn abort_helper(env: *mut c_void, start: usize, end: usize, _: usize) -> i64 {
+    guard(env, |env| {
+        env.result = Some(Err(abort(&mut env.state, start, end)));
+        Ok(EXIT)
+    })
+}
+
+extern "C" fn return_helper(env: *mut c_void, _: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| {
+        env.result = Some(return_value(&mut env.state));
+        Ok(EXIT)
+    })
+}
+
+extern "C" fn constant(env: *mut c_void, idx: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| {
+        env.state.stack.push(env.vm.values()[idx].clone());
+        Ok(CONTINUE)
+    })
+}
+
+/// Defines the helpers of binary operations.
+macro_rules! binary_helpers {
+    ($($helper:ident => $fun:path,)*) => {
+        $(
+            extern "C" fn $helper(env: *mut c_void, _: usize, _: usize, _: usize) -> i64 {
+                guard(env, |env| binary_op(&mut env.state, $fun).map(|_| CONTINUE))
+            }
+        )*
+    };
+}
+
+binary_helpers! {
+    add => Value::try_add,
+    subtract => Value::try_sub,
+    multiply => Value::try_mul,
+    divide => Value::try_div,
+    rem => Value::try_rem,
+    merge => Value::try_merge,
+    and => Value::try_and,
+    greater => Value::try_gt,
+    greater_equal => Value::try_ge,
+    less => Value::try_lt,
+    less_equal => Value::try_le,
+}
+
+extern "C" fn not_helper(env: *mut c_void, _: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| not(&mut env.state).map(|_| CONTINUE))
+}
+
+extern "C" fn not_equal(env: *mut c_void, _: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| compare(&mut env.state, false).map(|_| CONTINUE))
+}
+
+extern "C" fn equal(env: *mut c_void, _: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| compare(&mut env.state, true).map(|_| CONTINUE))
+}
+
+extern "C" fn pop(env: *mut c_void, _: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| env.state.pop_stack().map(|_| CONTINUE))
+}
+
+extern "C" fn clear_error(env: *mut c_void, _: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| {
+        env.state.error = None;
+        Ok(CONTINUE)
+    })
+}
+
+/// Defines the helpers of jumps, returning whether the jump is taken.
+macro_rules! jump_helpers {
+    ($($helper:ident => $opcode:expr,)*) => {
+        $(
+            extern "C" fn $helper(env: *mut c_void, _: usize, _: usize, _: usize) -> i64 {
+                guard(env, |env| {
+                    should_jump(&mut env.state, $opcode).map(|jump| if jump { JUMP } else { CONTINUE })
+                })
+            }
+        )*
+    };
+}
+
+jump_helpers! {
+    jump_if_false => OpCode::JumpIfFalse,
+    jump_if_true => OpCode::JumpIfTrue,
+    jump_if_truthy => OpCode::JumpIfTruthy,
+    jump_and_swap_if_falsey => OpCode::JumpAndSwapIfFalsey,
+    jump_if_not_err => OpCode::JumpIfNotErr,
+    jump_if_err => OpCode::JumpIfErr,
+    end_statement => OpCode::EndStatement,
+}
+
+extern "C" fn set_path(env: *mut c_void, variable: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| {
+        env.vm
+            .set_path(env.ctx, &mut env.state, variable)
+            .map(|_| CONTINUE)
+    })
+}
+
+extern "C" fn set_path_infallible(
+    env: *mut c_void,
+    variable: usize,
+    error: usize,
+    default: usize,
+) -> i64 {
+    guard(env, |env| {
+        env.vm
+            .set_path_infallible(env.ctx, &mut env.state, variable, error, default)
+            .map(|_| CONTINUE)
+    })
+}
+
+extern "C" fn get_path(env: *mut c_void, variable: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| {
+        env.vm
+            .get_path(env.ctx, &mut env.state, variable)
+            .map(|_| CONTINUE)
+    })
+}
+
+extern "C" fn call(env: *mut c_void, function_id: usize, start: usize, end: usize) -> i64 {
+    guard(env, |env| {
+        env.vm
+            .call(env.ctx, &mut env.state, function_id, start, end)
+            .map(|_| CONTINUE)
+    })
+}
+
+extern "C" fn create_array_helper(env: *mut c_void, count: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| {
+        create_array(&mut env.state, count).map(|_| CONTINUE)
+    })
+}
+
+extern "C" fn create_object_helper(env: *mut c_void, count: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| {
+        create_object(&mut env.state, count).map(|_| CONTINUE)
+    })
+}
+
+extern "C" fn empty_parameter(env: *mut c_void, _: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| {
+        env.state.parameter_stack.push(None);
+        Ok(CONTINUE)
+    })
+}
+
+extern "C" fn move_parameter(env: *mut c_void, _: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| {
+        let value = env.state.stack.pop().map(VmArgument::Value);
+        env.state.parameter_stack.push(value);
+        Ok(CONTINUE)
+    })
+}
+
+extern "C" fn move_static_parameter(env: *mut c_void, idx: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| {
+        env.vm.move_static_parameter(&mut env.state, idx);
+        Ok(CONTINUE)
+    })
+}
+
+extern "C" fn move_closure(env: *mut c_void, idx: usize, _: usize, _: usize) -> i64 {
+    guard(env, |env| {
+        env.vm.move_closure(&mut env.state, idx);
+        Ok(CONTINUE)
+    })
+}
+
+extern "C" fn call_user_function(env: *mut c_void, idx: usize, start: usize, end: usize) -> i64 {
+    guard(env, |env| {
+        env.vm
+            .call_user_function(env.ctx, &mut env.state, idx, start, end)
+            .map(|_| CONTINUE)
+    })
+}
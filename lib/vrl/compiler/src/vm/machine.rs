@@ -1,6 +1,12 @@
 use super::{state::VmState, Variable, VmArgumentList};
 use crate::value::{VrlValueArithmetic, VrlValueConvert};
-use crate::{vm::argument_list::VmArgument, Context, ExpressionError, Function, Value};
+use crate::{
+    expression::user_function_call::call_error,
+    function::closure::{with_variables, VmFunctionClosure},
+    parser::Ident,
+    vm::argument_list::VmArgument,
+    Context, ExpressionError, Function, Value,
+};
 use diagnostic::Span;
 use std::{collections::BTreeMap, ops::Deref};
 
@@ -131,6 +137,14 @@ pub enum OpCode {
     /// at compile time. (Used, for example, to precompile and store regexes at compile time.)
     MoveStaticParameter,
 
+    /// Moves the closure indicated by the ensuing primitive into the closure slot, to be passed to the
+    /// next function called.
+    MoveClosure,
+
+    /// Calls the user-defined function indicated by the ensuing primitive. Its arguments are popped
+    /// from the stack, and the value it returns is pushed back onto the stack.
+    CallUserFunction,
+
     /// After each statement (with the exception of the last one) within a block we need to pop the
     /// stack, and if we are in an error state jump to the end of the block.
     EndStatement,
@@ -152,6 +166,29 @@ pub enum Instruction {
     Primitive(usize),
 }
 
+/// A closure compiled into the instructions of the VM.
+///
+/// The closure body is written inline, directly before the call to the
+/// function it is passed to, and is jumped over during normal execution. The
+/// function runs it by interpreting the VM from `start` until the `Return`
+/// ending the body.
+#[derive(Debug, Clone)]
+pub struct VmClosure {
+    pub(crate) start: usize,
+    pub(crate) variables: Vec<Ident>,
+    pub(crate) locals: Vec<Ident>,
+}
+
+/// A user-defined function compiled into the instructions of the VM.
+///
+/// Like closures, the function body is written inline, directly before the
+/// call to the function, and is jumped over during normal execution.
+#[derive(Debug, Clone)]
+pub struct VmUserFunction {
+    pub(crate) ident: Ident,
+    pub(crate) body: VmClosure,
+}
+
 #[derive(Debug, Default)]
 pub struct Vm {
     fns: Vec<Box<dyn Function>>,
@@ -159,6 +196,8 @@ pub struct Vm {
     values: Vec<Value>,
     targets: Vec<Variable>,
     static_params: Vec<Box<dyn std::any::Any + Send + Sync>>,
+    closures: Vec<VmClosure>,
+    user_functions: Vec<VmUserFunction>,
 }
 
 impl Vm {
@@ -230,6 +269,40 @@ impl Vm {
         self.static_params.len() - 1
     }
 
+    /// Adds a closure to the list and returns the position of this in the list.
+    pub fn add_closure(
+        &mut self,
+        start: usize,
+        variables: Vec<Ident>,
+        locals: Vec<Ident>,
+    ) -> usize {
+        self.closures.push(VmClosure {
+            start,
+            variables,
+            locals,
+        });
+        self.closures.len() - 1
+    }
+
+    /// Adds a user-defined function to the list and returns the position of this in the list.
+    pub fn add_user_function(
+        &mut self,
+        ident: Ident,
+        start: usize,
+        parameters: Vec<Ident>,
+        locals: Vec<Ident>,
+    ) -> usize {
+        self.user_functions.push(VmUserFunction {
+            ident,
+            body: VmClosure {
+                start,
+                variables: parameters,
+                locals,
+            },
+        });
+        self.user_functions.len() - 1
+    }
+
     /// For debugging purposes, returns a list of strings representing the instructions and primitives.
     pub fn disassemble(&self) -> Vec<String> {
         self.instructions
@@ -266,8 +339,19 @@ impl Vm {
     /// The VM is stack based. When the `Return` `OpCode` is encountered the top item on the stack is popped and returned.
     /// It is expected that the final instruction is a `Return`.
     pub fn interpret<'a>(&self, ctx: &mut Context<'a>) -> Result<Value, ExpressionError> {
+        self.interpret_from(ctx, 0)
+    }
+
+    /// Interpret the VM, starting at the given instruction until a `Return` `OpCode` is encountered.
+    /// This is used to run the body of closures.
+    pub(crate) fn interpret_from<'a>(
+        &self,
+        ctx: &mut Context<'a>,
+        instruction_pointer: usize,
+    ) -> Result<Value, ExpressionError> {
         // Any mutable state during the run is stored here.
         let mut state: VmState = VmState::new(self);
+        state.instruction_pointer = instruction_pointer;
 
         loop {
             let next = state.next_opcode()?;
@@ -277,30 +361,17 @@ impl Vm {
                     // Aborts the process.
                     let start = state.next_primitive()?;
                     let end = state.next_primitive()?;
-                    let message = match state.pop_stack()? {
-                        Value::Null => None,
-                        value => Some(value.try_bytes_utf8_lossy()?.to_string()),
-                    };
-                    return Err(ExpressionError::Abort {
-                        span: Span::new(start, end),
-                        message,
-                    });
+                    return Err(abort(&mut state, start, end));
                 }
                 OpCode::Return => {
                     // Ends the process and returns the top item from the stack - or `Null` if the stack is empty.
-                    return match state.error {
-                        None => Ok(state.stack.pop().unwrap_or(Value::Null)),
-                        Some(err) => Err(err),
-                    };
+                    return return_value(&mut state);
                 }
                 OpCode::Constant => {
                     let value = state.read_constant()?;
                     state.stack.push(value);
                 }
-                OpCode::Not => match state.pop_stack()? {
-                    Value::Boolean(value) => state.stack.push(Value::Boolean(!value)),
-                    _ => return Err("Negating non boolean".into()),
-                },
+                OpCode::Not => not(&mut state)?,
                 OpCode::Add => binary_op(&mut state, Value::try_add)?,
                 OpCode::Subtract => binary_op(&mut state, Value::try_sub)?,
                 OpCode::Multiply => binary_op(&mut state, Value::try_mul)?,
@@ -312,24 +383,8 @@ impl Vm {
                 OpCode::GreaterEqual => binary_op(&mut state, Value::try_ge)?,
                 OpCode::Less => binary_op(&mut state, Value::try_lt)?,
                 OpCode::LessEqual => binary_op(&mut state, Value::try_le)?,
-                OpCode::NotEqual => {
-                    if state.error.is_none() {
-                        let rhs = state.pop_stack()?;
-                        let lhs = state.pop_stack()?;
-                        state.stack.push((!lhs.eq_lossy(&rhs)).into());
-                    } else {
-                        state.pop_stack()?;
-                    }
-                }
-                OpCode::Equal => {
-                    if state.error.is_none() {
-                        let rhs = state.pop_stack()?;
-                        let lhs = state.pop_stack()?;
-                        state.stack.push(lhs.eq_lossy(&rhs).into());
-                    } else {
-                        state.pop_stack()?;
-                    }
-                }
+                OpCode::NotEqual => compare(&mut state, false)?,
+                OpCode::Equal => compare(&mut state, true)?,
                 OpCode::Pop => {
                     // Removes the top item from the stack.
                     let _ = state.pop_stack()?;
@@ -338,217 +393,47 @@ impl Vm {
                     // Resets the state of the error.
                     state.error = None;
                 }
-                OpCode::JumpIfFalse => {
-                    // If the value at the top of the stack is false, jump by the given amount.
-                    let jump = state.next_primitive()?;
-                    if !is_true(state.peek_stack()?)? {
-                        state.instruction_pointer += jump;
-                    }
-                }
-                OpCode::JumpIfTrue => {
-                    // If the value at the top of the stack is true, jump by the given amount.
-                    let jump = state.next_primitive()?;
-                    if is_true(state.peek_stack()?)? {
-                        state.instruction_pointer += jump;
-                    }
-                }
-                OpCode::JumpIfTruthy => {
-                    // If the value at the top of the stack is true, jump by the given amount.
-                    // Used by OR operations.
-                    let jump = state.next_primitive()?;
-                    if is_truthy(state.peek_stack()?) {
-                        state.instruction_pointer += jump;
-                    }
-                }
-                OpCode::JumpAndSwapIfFalsey => {
-                    // If the value at the top of the stack is true, jump by the given amount.
-                    // Used by AND operations.
-                    let jump = state.next_primitive()?;
-                    if !is_truthy(state.peek_stack()?) {
-                        state.pop_stack()?;
-                        state.push_stack(Value::Boolean(false));
-                        state.instruction_pointer += jump;
-                    }
-                }
-                OpCode::JumpIfNotErr => {
-                    // If the current state is not in error, jump by the given amount.
-                    let jump = state.next_primitive()?;
-                    if state.error.is_none() {
-                        state.instruction_pointer += jump;
-                    }
-                }
-                OpCode::JumpIfErr => {
-                    // If the current state is in error, jump by the given amount.
+                OpCode::JumpIfFalse
+                | OpCode::JumpIfTrue
+                | OpCode::JumpIfTruthy
+                | OpCode::JumpAndSwapIfFalsey
+                | OpCode::JumpIfNotErr
+                | OpCode::JumpIfErr
+                | OpCode::EndStatement
+                | OpCode::Jump => {
+                    // Moves the instruction pointer by the given amount if the jump is taken.
                     let jump = state.next_primitive()?;
-                    if state.error.is_some() {
+                    if should_jump(&mut state, next)? {
                         state.instruction_pointer += jump;
                     }
                 }
-                OpCode::EndStatement => {
-                    let jump = state.next_primitive()?;
-                    if state.error.is_some() {
-                        state.instruction_pointer += jump;
-                    } else {
-                        state.pop_stack()?;
-                    }
-                }
-
-                OpCode::Jump => {
-                    // Moves the instruction pointer by the amount specified.
-                    let jump = state.next_primitive()?;
-                    state.instruction_pointer += jump;
-                }
                 OpCode::SetPath => {
-                    // Sets the path specified by the target to the value at the top of the stack.
-                    // The value is then pushed back onto the stack since the assignment expression
-                    // also returns this value.
-                    // (Allows statements such as `a = b = 32`.)
                     let variable = state.next_primitive()?;
-                    let variable = &self.targets[variable];
-                    let value = state.pop_stack()?;
-
-                    set_variable(ctx, variable, value.clone())?;
-                    state.push_stack(value);
+                    self.set_path(ctx, &mut state, variable)?;
                 }
                 OpCode::SetPathInfallible => {
-                    // Sets the path for an infallible assignment statement ie.
-                    // `thing, err = fallible_call()`
                     let variable = state.next_primitive()?;
-                    let variable = &self.targets[variable];
-
                     let error = state.next_primitive()?;
-                    let error = &self.targets[error];
-
                     let default = state.next_primitive()?;
-                    let default = &self.values[default];
-
-                    // Note, after assignment the value is pushed back onto the stack since it is possible for
-                    // the value to be further used afterwards. This means the value is cloned when the variable is set.
-                    // A potential future enhancement would be for the compiler to determine if this value is used and
-                    // pass that as a hint to this OpCode so we only clone and fill up the stack when needed.
-                    match state.error.take() {
-                        Some(err) => {
-                            let err = Value::from(err.to_string());
-                            set_variable(ctx, variable, default.clone())?;
-                            set_variable(ctx, error, err.clone())?;
-                            state.push_stack(err);
-                        }
-                        None => {
-                            let value = state.pop_stack()?;
-                            set_variable(ctx, variable, value.clone())?;
-                            set_variable(ctx, error, Value::Null)?;
-                            state.push_stack(value);
-                        }
-                    }
+                    self.set_path_infallible(ctx, &mut state, variable, error, default)?;
                 }
                 OpCode::GetPath => {
-                    // Retrieves a value using the given path and pushes this onto the stack.
                     let variable = state.next_primitive()?;
-                    let variable = &self.targets[variable];
-
-                    match &variable {
-                        Variable::External(path) => {
-                            let value = ctx.target().target_get(path)?.unwrap_or(Value::Null);
-                            state.stack.push(value);
-                        }
-                        Variable::Internal(ident, path) => {
-                            let value = match ctx.state().variable(ident) {
-                                Some(value) => match path {
-                                    Some(path) => {
-                                        value.get_by_path(path).cloned().unwrap_or(Value::Null)
-                                    }
-                                    None => value.clone(),
-                                },
-                                None => Value::Null,
-                            };
-
-                            state.stack.push(value);
-                        }
-                        Variable::None => state.stack.push(Value::Null),
-                        Variable::Stack(path) => {
-                            if state.error.is_none() {
-                                let value = state.pop_stack()?;
-                                let value = value.get_by_path(path).cloned().unwrap_or(Value::Null);
-                                state.stack.push(value);
-                            }
-                        }
-                    }
+                    self.get_path(ctx, &mut state, variable)?;
                 }
                 OpCode::CreateArray => {
-                    // Creates an array from the values on the stack.
-                    // The next primitive on the stack is the number of fields in the array
-                    // followed by the values to be added to the array.
                     let count = state.next_primitive()?;
-                    let mut arr = Vec::new();
-
-                    for _ in 0..count {
-                        arr.push(state.pop_stack()?);
-                    }
-
-                    state.stack.push(Value::Array(arr));
+                    create_array(&mut state, count)?;
                 }
                 OpCode::CreateObject => {
-                    // Creates an object from the values on the stack.
-                    // The next primitive on the stack is the number of fields in the object
-                    // followed by key, value pairs.
                     let count = state.next_primitive()?;
-                    let mut object = BTreeMap::new();
-
-                    for _ in 0..count {
-                        let value = state.pop_stack()?;
-                        let key = state.pop_stack()?;
-                        let key = String::from_utf8_lossy(&key.try_bytes().unwrap()).to_string();
-
-                        object.insert(key, value);
-                    }
-
-                    state.stack.push(Value::Object(object));
+                    create_object(&mut state, count)?;
                 }
                 OpCode::Call => {
-                    // Calls a function in the stdlib.
                     let function_id = state.next_primitive()?;
                     let span_start = state.next_primitive()?;
                     let span_end = state.next_primitive()?;
-                    let parameters = &self.fns[function_id].parameters();
-
-                    let len = state.parameter_stack().len();
-                    let args = state
-                        .parameter_stack_mut()
-                        .drain(len - parameters.len()..)
-                        .collect();
-
-                    let mut argumentlist = VmArgumentList::new(parameters, args);
-                    let function = &self.fns[function_id];
-
-                    let result = argumentlist
-                        .check_arguments()
-                        .and_then(|_| function.call_by_vm(ctx, &mut argumentlist));
-
-                    match result {
-                        Ok(result) => state.stack.push(result),
-                        Err(err) => match err {
-                            ExpressionError::Abort { .. } => {
-                                panic!("abort errors must only be defined by `abort` statement")
-                            }
-                            ExpressionError::Error {
-                                message,
-                                labels,
-                                notes,
-                            } => {
-                                state.error = Some(ExpressionError::Error {
-                                    message: format!(
-                                        r#"function call error for "{}" at ({}:{}): {}"#,
-                                        function.identifier(),
-                                        span_start,
-                                        span_end,
-                                        message
-                                    ),
-                                    labels,
-                                    notes,
-                                });
-                            }
-                        },
-                    }
+                    self.call(ctx, &mut state, function_id, span_start, span_end)?;
                 }
                 OpCode::EmptyParameter => {
                     // Moves an empty, optional parameter onto the parameter stack.
@@ -561,21 +446,350 @@ impl Vm {
                         .push(state.stack.pop().map(VmArgument::Value))
                 }
                 OpCode::MoveStaticParameter => {
-                    // Moves a static parameter onto the parameter stack.
-                    // A static parameter will have been created by the function`s `compile_argument` method
-                    // during compile time.
                     let idx = state.next_primitive()?;
-                    state
-                        .parameter_stack
-                        .push(Some(VmArgument::Any(&self.static_params[idx])));
+                    self.move_static_parameter(&mut state, idx);
+                }
+                OpCode::MoveClosure => {
+                    let idx = state.next_primitive()?;
+                    self.move_closure(&mut state, idx);
+                }
+                OpCode::CallUserFunction => {
+                    let idx = state.next_primitive()?;
+                    let span_start = state.next_primitive()?;
+                    let span_end = state.next_primitive()?;
+                    self.call_user_function(ctx, &mut state, idx, span_start, span_end)?;
                 }
             }
         }
     }
+
+    // The operations below take the primitives following their `OpCode`, and
+    // are shared by the interpreter and the native code compiled by the JIT.
+
+    /// Sets the path specified by the target to the value at the top of the stack.
+    /// The value is then pushed back onto the stack since the assignment expression
+    /// also returns this value.
+    /// (Allows statements such as `a = b = 32`.)
+    pub(super) fn set_path(
+        &self,
+        ctx: &mut Context<'_>,
+        state: &mut VmState,
+        variable: usize,
+    ) -> Result<(), ExpressionError> {
+        let variable = &self.targets[variable];
+        let value = state.pop_stack()?;
+
+        set_variable(ctx, variable, value.clone())?;
+        state.push_stack(value);
+
+        Ok(())
+    }
+
+    /// Sets the path for an infallible assignment statement ie.
+    /// `thing, err = fallible_call()`
+    pub(super) fn set_path_infallible(
+        &self,
+        ctx: &mut Context<'_>,
+        state: &mut VmState,
+        variable: usize,
+        error: usize,
+        default: usize,
+    ) -> Result<(), ExpressionError> {
+        let variable = &self.targets[variable];
+        let error = &self.targets[error];
+        let default = &self.values[default];
+
+        // Note, after assignment the value is pushed back onto the stack since it is possible for
+        // the value to be further used afterwards. This means the value is cloned when the variable is set.
+        // A potential future enhancement would be for the compiler to determine if this value is used and
+        // pass that as a hint to this OpCode so we only clone and fill up the stack when needed.
+        match state.error.take() {
+            Some(err) => {
+                let err = Value::from(err.to_string());
+                set_variable(ctx, variable, default.clone())?;
+                set_variable(ctx, error, err.clone())?;
+                state.push_stack(err);
+            }
+            None => {
+                let value = state.pop_stack()?;
+                set_variable(ctx, variable, value.clone())?;
+                set_variable(ctx, error, Value::Null)?;
+                state.push_stack(value);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Retrieves a value using the given path and pushes this onto the stack.
+    pub(super) fn get_path(
+        &self,
+        ctx: &mut Context<'_>,
+        state: &mut VmState,
+        variable: usize,
+    ) -> Result<(), ExpressionError> {
+        match &self.targets[variable] {
+            Variable::External(path) => {
+                let value = ctx.target().target_get(path)?.unwrap_or(Value::Null);
+                state.stack.push(value);
+            }
+            Variable::Internal(ident, path) => {
+                let value = match ctx.state().variable(ident) {
+                    Some(value) => match path {
+                        Some(path) => value.get_by_path(path).cloned().unwrap_or(Value::Null),
+                        None => value.clone(),
+                    },
+                    None => Value::Null,
+                };
+
+                state.stack.push(value);
+            }
+            Variable::None => state.stack.push(Value::Null),
+            Variable::Stack(path) => {
+                if state.error.is_none() {
+                    let value = state.pop_stack()?;
+                    let value = value.get_by_path(path).cloned().unwrap_or(Value::Null);
+                    state.stack.push(value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Calls a function in the stdlib.
+    pub(super) fn call(
+        &self,
+        ctx: &mut Context<'_>,
+        state: &mut VmState,
+        function_id: usize,
+        span_start: usize,
+        span_end: usize,
+    ) -> Result<(), ExpressionError> {
+        let parameters = &self.fns[function_id].parameters();
+
+        let len = state.parameter_stack().len();
+        let args = state
+            .parameter_stack_mut()
+            .drain(len - parameters.len()..)
+            .collect();
+
+        let mut argumentlist =
+            VmArgumentList::new(parameters, args).with_closure(state.closure.take());
+        let function = &self.fns[function_id];
+
+        let result = argumentlist
+            .check_arguments()
+            .and_then(|_| function.call_by_vm(ctx, &mut argumentlist));
+
+        match result {
+            Ok(result) => state.stack.push(result),
+            Err(err) => match err {
+                // An abort statement within a closure aborts the whole program.
+                ExpressionError::Abort { .. } if function.closure().is_some() => return Err(err),
+                ExpressionError::Abort { .. } => {
+                    panic!("abort errors must only be defined by `abort` statement")
+                }
+                ExpressionError::Error {
+                    message,
+                    labels,
+                    notes,
+                } => {
+                    state.error = Some(ExpressionError::Error {
+                        message: format!(
+                            r#"function call error for "{}" at ({}:{}): {}"#,
+                            function.identifier(),
+                            span_start,
+                            span_end,
+                            message
+                        ),
+                        labels,
+                        notes,
+                    });
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Moves a static parameter onto the parameter stack.
+    /// A static parameter will have been created by the function`s `compile_argument` method
+    /// during compile time.
+    pub(super) fn move_static_parameter<'a>(&'a self, state: &mut VmState<'a>, idx: usize) {
+        state
+            .parameter_stack
+            .push(Some(VmArgument::Any(&self.static_params[idx])));
+    }
+
+    /// Moves a closure into the closure slot, to be passed to the function called
+    /// next.
+    pub(super) fn move_closure<'a>(&'a self, state: &mut VmState<'a>, idx: usize) {
+        state.closure = Some(VmFunctionClosure::new(self, &self.closures[idx]));
+    }
+
+    /// Calls a user-defined function, binding the arguments at the top of the
+    /// stack to its parameters for the duration of the call.
+    pub(super) fn call_user_function(
+        &self,
+        ctx: &mut Context<'_>,
+        state: &mut VmState,
+        idx: usize,
+        span_start: usize,
+        span_end: usize,
+    ) -> Result<(), ExpressionError> {
+        let function = &self.user_functions[idx];
+        let body = &function.body;
+
+        let len = state.stack.len();
+        let args = state
+            .stack
+            .drain(len - body.variables.len()..)
+            .collect::<Vec<_>>();
+
+        let result = with_variables(ctx, &body.variables, &body.locals, args, |ctx| {
+            self.interpret_from(ctx, body.start)
+        });
+
+        match result {
+            Ok(result) => state.stack.push(result),
+            Err(err) => {
+                let span = Span::new(span_start, span_end);
+                match call_error(function.ident.as_ref(), span, err) {
+                    err @ ExpressionError::Abort { .. } => return Err(err),
+                    err => state.error = Some(err),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The error aborting the process, with the message at the top of the stack.
+pub(super) fn abort(state: &mut VmState, start: usize, end: usize) -> ExpressionError {
+    let message = match state.pop_stack() {
+        Ok(Value::Null) => None,
+        Ok(value) => match value.try_bytes_utf8_lossy() {
+            Ok(message) => Some(message.to_string()),
+            Err(err) => return err.into(),
+        },
+        Err(err) => return err,
+    };
+
+    ExpressionError::Abort {
+        span: Span::new(start, end),
+        message,
+    }
+}
+
+/// The top item from the stack - or `Null` if the stack is empty, unless the
+/// process is in error.
+pub(super) fn return_value(state: &mut VmState) -> Result<Value, ExpressionError> {
+    match state.error.take() {
+        None => Ok(state.stack.pop().unwrap_or(Value::Null)),
+        Some(err) => Err(err),
+    }
+}
+
+/// Pops the boolean at the top of the stack and pushes its negation.
+pub(super) fn not(state: &mut VmState) -> Result<(), ExpressionError> {
+    match state.pop_stack()? {
+        Value::Boolean(value) => state.stack.push(Value::Boolean(!value)),
+        _ => return Err("Negating non boolean".into()),
+    }
+
+    Ok(())
+}
+
+/// Pops the top two elements from the stack, pushing whether they are equal,
+/// or not equal if `equal` is false.
+pub(super) fn compare(state: &mut VmState, equal: bool) -> Result<(), ExpressionError> {
+    if state.error.is_none() {
+        let rhs = state.pop_stack()?;
+        let lhs = state.pop_stack()?;
+        state.stack.push((lhs.eq_lossy(&rhs) == equal).into());
+    } else {
+        state.pop_stack()?;
+    }
+
+    Ok(())
+}
+
+/// Whether the given jump `OpCode` moves the instruction pointer.
+pub(super) fn should_jump(state: &mut VmState, opcode: OpCode) -> Result<bool, ExpressionError> {
+    let jump = match opcode {
+        // If the value at the top of the stack is false, jump by the given amount.
+        OpCode::JumpIfFalse => !is_true(state.peek_stack()?)?,
+        // If the value at the top of the stack is true, jump by the given amount.
+        OpCode::JumpIfTrue => is_true(state.peek_stack()?)?,
+        // If the value at the top of the stack is true, jump by the given amount.
+        // Used by OR operations.
+        OpCode::JumpIfTruthy => is_truthy(state.peek_stack()?),
+        // If the value at the top of the stack is true, jump by the given amount.
+        // Used by AND operations.
+        OpCode::JumpAndSwapIfFalsey => {
+            let jump = !is_truthy(state.peek_stack()?);
+            if jump {
+                state.pop_stack()?;
+                state.push_stack(Value::Boolean(false));
+            }
+            jump
+        }
+        // If the current state is not in error, jump by the given amount.
+        OpCode::JumpIfNotErr => state.error.is_none(),
+        // If the current state is in error, jump by the given amount.
+        OpCode::JumpIfErr => state.error.is_some(),
+        OpCode::EndStatement => {
+            let jump = state.error.is_some();
+            if !jump {
+                state.pop_stack()?;
+            }
+            jump
+        }
+        OpCode::Jump => true,
+        opcode => return Err(format!("{:?} is not a jump", opcode).into()),
+    };
+
+    Ok(jump)
+}
+
+/// Creates an array from the values on the stack.
+/// The given count is the number of fields in the array, which are popped
+/// from the stack.
+pub(super) fn create_array(state: &mut VmState, count: usize) -> Result<(), ExpressionError> {
+    let mut arr = Vec::new();
+
+    for _ in 0..count {
+        arr.push(state.pop_stack()?);
+    }
+
+    state.stack.push(Value::Array(arr));
+
+    Ok(())
+}
+
+/// Creates an object from the values on the stack.
+/// The given count is the number of fields in the object, popped from the
+/// stack as key, value pairs.
+pub(super) fn create_object(state: &mut VmState, count: usize) -> Result<(), ExpressionError> {
+    let mut object = BTreeMap::new();
+
+    for _ in 0..count {
+        let value = state.pop_stack()?;
+        let key = state.pop_stack()?;
+        let key = String::from_utf8_lossy(&key.try_bytes().unwrap()).to_string();
+
+        object.insert(key, value);
+    }
+
+    state.stack.push(Value::Object(object));
+
+    Ok(())
 }
 
 /// Op that applies a function to the top two elements on the stack.
-fn binary_op<F, E>(state: &mut VmState, fun: F) -> Result<(), ExpressionError>
+pub(super) fn binary_op<F, E>(state: &mut VmState, fun: F) -> Result<(), ExpressionError>
 where
     E: Into<ExpressionError>,
     F: Fn(Value, Value) -> Result<Value, E>,
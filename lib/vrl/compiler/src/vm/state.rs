@@ -1,5 +1,5 @@
 use super::{argument_list::VmArgument, machine::Instruction, OpCode, Vm};
-use crate::{ExpressionError, Value};
+use crate::{function::closure::VmFunctionClosure, ExpressionError, Value};
 
 /// `VmState` contains the mutable state used to run the Vm.
 pub(crate) struct VmState<'a> {
@@ -10,6 +10,8 @@ pub(crate) struct VmState<'a> {
     pub(super) stack: Vec<Value>,
     /// A stack of values passed to functions when called.
     pub(super) parameter_stack: Vec<Option<VmArgument<'a>>>,
+    /// The closure passed to the function called next.
+    pub(super) closure: Option<VmFunctionClosure<'a>>,
     /// Errors generated by the last expression are stored here.
     pub(super) error: Option<ExpressionError>,
 }
@@ -21,6 +23,7 @@ impl<'a> VmState<'a> {
             instruction_pointer: 0,
             stack: Vec::new(),
             parameter_stack: Vec::new(),
+            closure: None,
             error: None,
         }
     }
@@ -2,7 +2,7 @@ use std::ops::{Deref, DerefMut};
 
 use codespan_reporting::diagnostic;
 
-use crate::{DiagnosticError, Label, Note, Severity, Span};
+use crate::{DiagnosticError, Label, Note, Severity, SourceFile, Span};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Diagnostic {
@@ -11,6 +11,7 @@ pub struct Diagnostic {
     message: String,
     labels: Vec<Label>,
     notes: Vec<Note>,
+    file: Option<SourceFile>,
 }
 
 impl Diagnostic {
@@ -35,6 +36,7 @@ impl Diagnostic {
             message: message.to_string(),
             labels,
             notes,
+            file: None,
         }
     }
 
@@ -56,10 +58,21 @@ impl Diagnostic {
         self
     }
 
+    /// Point the labels of the diagnostic into the given source file,
+    /// instead of the compiled program.
+    pub fn with_file(mut self, file: SourceFile) -> Self {
+        self.file = Some(file);
+        self
+    }
+
     pub fn severity(&self) -> Severity {
         self.severity
     }
 
+    pub fn code(&self) -> usize {
+        self.code
+    }
+
     pub fn message(&self) -> &str {
         &self.message
     }
@@ -72,6 +85,10 @@ impl Diagnostic {
         &self.labels
     }
 
+    pub fn file(&self) -> Option<&SourceFile> {
+        self.file.as_ref()
+    }
+
     /// Returns `true` if the diagnostic represents either an
     /// [error](Severity::Error) or [bug](Severity::Bug).
     #[inline]
@@ -113,6 +130,7 @@ impl From<Box<dyn DiagnosticError>> for Diagnostic {
             message: error.message(),
             labels: error.labels(),
             notes: error.notes(),
+            file: error.file(),
         }
     }
 }
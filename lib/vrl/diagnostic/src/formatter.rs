@@ -35,7 +35,7 @@ impl<'a> fmt::Display for Formatter<'a> {
         use codespan_reporting::{files::SimpleFile, term};
         use termcolor::Buffer;
 
-        let file = SimpleFile::new("", self.source);
+        let program = SimpleFile::new("", self.source);
         let config = term::Config::default();
         let mut buffer = if self.color {
             Buffer::ansi()
@@ -46,7 +46,16 @@ impl<'a> fmt::Display for Formatter<'a> {
         f.write_str("\n")?;
 
         for diagnostic in self.diagnostics.iter() {
-            term::emit(&mut buffer, &config, &file, &diagnostic.to_owned().into())
+            let imported;
+            let file = match diagnostic.file() {
+                Some(file) => {
+                    imported = SimpleFile::new(file.name(), file.source());
+                    &imported
+                }
+                None => &program,
+            };
+
+            term::emit(&mut buffer, &config, file, &diagnostic.to_owned().into())
                 .map_err(|_| fmt::Error)?;
         }
 
@@ -3,6 +3,7 @@ mod formatter;
 mod label;
 mod note;
 mod severity;
+mod source_file;
 mod span;
 
 pub use diagnostic::{Diagnostic, DiagnosticList};
@@ -10,6 +11,7 @@ pub use formatter::Formatter;
 pub use label::Label;
 pub use note::Note;
 pub use severity::Severity;
+pub use source_file::SourceFile;
 pub use span::{span, Span};
 
 const VRL_DOCS_ROOT_URL: &str = "https://vrl.dev";
@@ -41,6 +43,13 @@ pub trait DiagnosticError: std::error::Error {
     fn notes(&self) -> Vec<Note> {
         vec![]
     }
+
+    /// The source file the labels point into.
+    ///
+    /// Defaults to `None`, meaning the labels point into the compiled program.
+    fn file(&self) -> Option<SourceFile> {
+        None
+    }
 }
 
 pub struct Urls;
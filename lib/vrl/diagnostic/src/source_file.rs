@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+/// A source file other than the compiled program, such as an imported VRL
+/// module, that a diagnostic points into.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceFile {
+    name: String,
+    source: Arc<str>,
+}
+
+impl SourceFile {
+    pub fn new(name: impl ToString, source: impl Into<Arc<str>>) -> Self {
+        Self {
+            name: name.to_string(),
+            source: source.into(),
+        }
+    }
+
+    /// The name of the file, shown as the location of the diagnostic.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
@@ -0,0 +1,421 @@
+use std::path::PathBuf;
+
+use lsp_types::{
+    CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity, Documentation, Hover,
+    HoverContents, MarkupContent, MarkupKind, NumberOrString, Position, Range, TextEdit,
+};
+use vrl::{
+    diagnostic::{self, Severity, Span, Urls},
+    state, Function, TypeDef,
+};
+
+use crate::symbols::Symbols;
+
+/// A VRL program opened in the editor, analysed each time it changes.
+pub(crate) struct Document {
+    source: String,
+
+    /// The byte offset at which each line starts.
+    line_starts: Vec<usize>,
+
+    diagnostics: Vec<diagnostic::Diagnostic>,
+
+    /// The type definition of each compiled expression.
+    types: Vec<(Span, TypeDef)>,
+
+    /// The symbols of the program, unless it can't be parsed.
+    symbols: Option<Symbols>,
+}
+
+impl Document {
+    /// Compile the program, importing files from the given directories.
+    pub(crate) fn new(
+        source: String,
+        functions: &[Box<dyn Function>],
+        import_paths: Vec<PathBuf>,
+    ) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        let mut state = state::Compiler::new();
+        state.record_types();
+        state.set_import_paths(import_paths);
+
+        let diagnostics = match vrl::compile_with_state(&source, functions, &mut state) {
+            Ok(_) => vec![],
+            Err(errors) => errors.into_iter().map(Into::into).collect(),
+        };
+        let types = state.types().map(<[_]>::to_vec).unwrap_or_default();
+        let symbols = parser::parse(&source).ok().map(Symbols::new);
+
+        Self {
+            source,
+            line_starts,
+            diagnostics,
+            types,
+            symbols,
+        }
+    }
+
+    pub(crate) fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let label = diagnostic
+                    .labels()
+                    .iter()
+                    .find(|label| label.primary)
+                    .or_else(|| diagnostic.labels().first());
+
+                let mut message = diagnostic.message().to_owned();
+                let range = match (diagnostic.file(), label) {
+                    // Errors in imported files are reported at the start of the program.
+                    (Some(file), _) => {
+                        message = format!("{}: {}", file.name(), message);
+                        Range::default()
+                    }
+                    (None, Some(label)) => {
+                        if !label.message.is_empty() && label.message != message {
+                            message = format!("{}\n{}", message, label.message);
+                        }
+                        self.range(label.span)
+                    }
+                    (None, None) => Range::default(),
+                };
+
+                let severity = match diagnostic.severity() {
+                    Severity::Bug | Severity::Error => DiagnosticSeverity::ERROR,
+                    Severity::Warning => DiagnosticSeverity::WARNING,
+                    Severity::Note => DiagnosticSeverity::INFORMATION,
+                };
+
+                Diagnostic {
+                    range,
+                    severity: Some(severity),
+                    code: Some(NumberOrString::String(format!("E{:03}", diagnostic.code()))),
+                    source: Some("vrl".to_owned()),
+                    message,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// The documentation of the function called at the position, or the type
+    /// of the innermost expression at the position.
+    pub(crate) fn hover(
+        &self,
+        position: Position,
+        functions: &[Box<dyn Function>],
+    ) -> Option<Hover> {
+        let offset = self.offset(position);
+
+        let call = self
+            .symbols
+            .as_ref()
+            .and_then(|symbols| symbols.call_at(offset))
+            .and_then(|(ident, span)| {
+                let function = functions
+                    .iter()
+                    .find(|f| f.identifier() == ident.as_ref())?;
+                Some((function_docs(function.as_ref()), *span))
+            });
+
+        let (value, span) = match call {
+            Some(call) => call,
+            None => {
+                let (span, type_def) = self
+                    .types
+                    .iter()
+                    .filter(|(span, _)| span.start() <= offset && offset < span.end())
+                    .min_by_key(|(span, _)| span.end() - span.start())?;
+
+                let fallible = if type_def.is_fallible() {
+                    " (fallible)"
+                } else {
+                    ""
+                };
+                let value = format!("```text\n{}{}\n```", type_def.kind(), fallible);
+
+                (value, *span)
+            }
+        };
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(self.range(span)),
+        })
+    }
+
+    /// The parameters of the function whose arguments are being written at
+    /// the position, or the functions and variables otherwise.
+    pub(crate) fn completion(
+        &self,
+        position: Position,
+        functions: &[Box<dyn Function>],
+    ) -> Vec<CompletionItem> {
+        let offset = self.offset(position);
+
+        if let Some(function) = self
+            .called_function(offset)
+            .and_then(|ident| functions.iter().find(|f| f.identifier() == ident))
+        {
+            return function
+                .parameters()
+                .iter()
+                .map(|parameter| CompletionItem {
+                    label: parameter.keyword.to_owned(),
+                    kind: Some(CompletionItemKind::FIELD),
+                    detail: Some(parameter.kind().to_string()),
+                    insert_text: Some(format!("{}: ", parameter.keyword)),
+                    ..Default::default()
+                })
+                .collect();
+        }
+
+        let variables = self
+            .symbols
+            .iter()
+            .flat_map(Symbols::variables)
+            .map(|variable| CompletionItem {
+                label: variable.to_owned(),
+                kind: Some(CompletionItemKind::VARIABLE),
+                ..Default::default()
+            });
+
+        functions
+            .iter()
+            .map(|function| CompletionItem {
+                label: function.identifier().to_owned(),
+                kind: Some(CompletionItemKind::FUNCTION),
+                detail: Some(signature(function.as_ref())),
+                documentation: Some(Documentation::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: function_docs(function.as_ref()),
+                })),
+                ..Default::default()
+            })
+            .chain(variables)
+            .collect()
+    }
+
+    /// The range of the definition of the variable or function at the position.
+    pub(crate) fn definition(&self, position: Position) -> Option<Range> {
+        let offset = self.offset(position);
+
+        self.symbols
+            .as_ref()?
+            .definition_at(offset)
+            .map(|span| self.range(span))
+    }
+
+    /// The edit replacing the program with its formatted source, unless it
+    /// can't be parsed or is already formatted.
+    pub(crate) fn formatting(&self) -> Option<Vec<TextEdit>> {
+        let formatted = parser::format::format(&self.source).ok()?;
+        if formatted == self.source {
+            return Some(vec![]);
+        }
+
+        let range = Range::new(Position::default(), self.position(self.source.len()));
+        Some(vec![TextEdit::new(range, formatted)])
+    }
+
+    /// The identifier of the function whose argument list contains the
+    /// offset, found by looking back for an unclosed parenthesis, as the
+    /// program usually doesn't parse while arguments are being written.
+    fn called_function(&self, offset: usize) -> Option<&str> {
+        let before = self.source.get(..offset)?;
+
+        let mut depth = 0usize;
+        let open = before.char_indices().rev().find_map(|(i, c)| {
+            match c {
+                ')' | ']' | '}' => depth += 1,
+                '(' | '[' | '{' if depth > 0 => depth -= 1,
+                '(' => return Some(Some(i)),
+                '[' | '{' | '\n' => return Some(None),
+                _ => {}
+            }
+            None
+        })??;
+
+        let ident = before[..open].trim_end_matches('!');
+        let start = ident
+            .rfind(|c: char| !c.is_alphanumeric() && c != '_')
+            .map_or(0, |i| i + 1);
+
+        Some(&ident[start..]).filter(|ident| !ident.is_empty())
+    }
+
+    /// The byte offset of an editor position, which counts UTF-16 code units.
+    fn offset(&self, position: Position) -> usize {
+        let start = match self.line_starts.get(position.line as usize) {
+            Some(start) => *start,
+            None => return self.source.len(),
+        };
+
+        let mut units = 0;
+        for (i, c) in self.source[start..].char_indices() {
+            if units >= position.character as usize || c == '\n' {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+
+        self.source.len()
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        let line = self
+            .line_starts
+            .partition_point(|start| *start <= offset)
+            .saturating_sub(1);
+        let start = self.line_starts[line];
+        let character = self.source[start..offset]
+            .chars()
+            .map(char::len_utf16)
+            .sum::<usize>();
+
+        Position::new(line as u32, character as u32)
+    }
+
+    fn range(&self, span: Span) -> Range {
+        let end = span.end().min(self.source.len());
+        let start = span.start().min(end);
+
+        Range::new(self.position(start), self.position(end))
+    }
+}
+
+/// The signature of a function, such as `round(value: integer or float, [precision: integer])`.
+fn signature(function: &dyn Function) -> String {
+    let parameters = function
+        .parameters()
+        .iter()
+        .map(|parameter| {
+            let parameter_signature = format!("{}: {}", parameter.keyword, parameter.kind());
+            if parameter.required {
+                parameter_signature
+            } else {
+                format!("[{}]", parameter_signature)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    format!("{}({})", function.identifier(), parameters.join(", "))
+}
+
+fn function_docs(function: &dyn Function) -> String {
+    let mut docs = format!("```text\n{}\n```\n", signature(function));
+
+    if function.summary() != "TODO" {
+        docs.push('\n');
+        docs.push_str(function.summary());
+        docs.push('\n');
+    }
+
+    docs.push('\n');
+    docs.push_str(&Urls::func_docs(function.identifier()));
+
+    docs
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn document(source: &str) -> Document {
+        Document::new(source.to_owned(), &stdlib::all(), vec![])
+    }
+
+    fn hover_text(hover: Hover) -> String {
+        match hover.contents {
+            HoverContents::Markup(content) => content.value,
+            contents => panic!("{:?}", contents),
+        }
+    }
+
+    #[test]
+    fn diagnostics() {
+        let document = document("foo = 1\n.bar = baz\n");
+        let diagnostics = document.diagnostics();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(1, 7), Position::new(1, 10))
+        );
+        assert_eq!(
+            diagnostics[0].code,
+            Some(NumberOrString::String("E701".to_owned()))
+        );
+    }
+
+    #[test]
+    fn hover_type() {
+        let document = document(indoc! {r#"
+            foo = "bar"
+            .baz = upcase(foo)
+        "#});
+
+        let hover = document
+            .hover(Position::new(1, 15), &stdlib::all())
+            .unwrap();
+        assert_eq!(hover_text(hover), "```text\nstring\n```");
+
+        let hover = document.hover(Position::new(1, 8), &stdlib::all()).unwrap();
+        assert!(hover_text(hover).starts_with("```text\nupcase(value: string)\n```"));
+    }
+
+    #[test]
+    fn definition() {
+        let document = document(indoc! {r#"
+            foo = 1
+            foo = foo + 1
+            .bar = foo
+        "#});
+
+        assert_eq!(
+            document.definition(Position::new(2, 8)),
+            Some(Range::new(Position::new(1, 0), Position::new(1, 3)))
+        );
+        assert_eq!(document.definition(Position::new(2, 1)), None);
+    }
+
+    #[test]
+    fn completion() {
+        let functions = stdlib::all();
+
+        let labels = |items: Vec<CompletionItem>| {
+            items.into_iter().map(|item| item.label).collect::<Vec<_>>()
+        };
+
+        let parameters = document("foo = 1\n.bar = replace(.message, ")
+            .completion(Position::new(1, 24), &functions);
+        assert_eq!(
+            labels(parameters),
+            vec!["value", "pattern", "with", "count"]
+        );
+
+        let items = labels(document("foo = 1\n").completion(Position::new(1, 0), &functions));
+        assert!(items.contains(&"replace".to_owned()));
+        assert!(items.contains(&"foo".to_owned()));
+    }
+
+    #[test]
+    fn formatting() {
+        let edits = document("if true {\n.foo=1\n}").formatting().unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].new_text, "if true {\n    .foo = 1\n}\n");
+        assert_eq!(edits[0].range.end, Position::new(2, 1));
+
+        assert_eq!(document(".foo = 1\n").formatting(), Some(vec![]));
+        assert_eq!(document(".foo =\n").formatting(), None);
+    }
+}
@@ -0,0 +1,34 @@
+#![deny(clippy::all)]
+#![deny(unreachable_pub)]
+#![deny(unused_allocation)]
+#![deny(unused_extern_crates)]
+#![deny(unused_assignments)]
+#![deny(unused_comparisons)]
+
+//! A language server for VRL, providing editors with the diagnostics of a
+//! program, the type of its expressions, the completion of function names and
+//! parameters, the definition of its variables and its formatting.
+
+mod document;
+mod server;
+mod symbols;
+
+pub use server::run;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("io error: {}", .0)]
+    Io(#[from] std::io::Error),
+
+    #[error("protocol error: {}", .0)]
+    Protocol(#[from] lsp_server::ProtocolError),
+
+    #[error("json error: {}", .0)]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid request: {}", .0)]
+    Request(String),
+
+    #[error("client disconnected")]
+    Disconnected,
+}
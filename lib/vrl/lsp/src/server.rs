@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as LspNotification, PublishDiagnostics,
+    },
+    request::{Completion, Formatting, GotoDefinition, HoverRequest, Request as LspRequest},
+    CompletionOptions, CompletionResponse, GotoDefinitionResponse, HoverProviderCapability,
+    InitializeParams, Location, OneOf, PublishDiagnosticsParams, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use vrl::Function;
+
+use crate::{document::Document, Error};
+
+/// Run the language server over stdin and stdout, until the client shuts it
+/// down.
+pub fn run() -> Result<(), Error> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["(".to_owned(), ",".to_owned()]),
+            ..Default::default()
+        }),
+        definition_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+
+    let params = connection.initialize(serde_json::to_value(capabilities)?)?;
+    let _params: InitializeParams = serde_json::from_value(params)?;
+
+    // The connection is dropped once the server stops, which stops the IO threads.
+    Server::new(connection).run()?;
+    io_threads.join()?;
+
+    Ok(())
+}
+
+struct Server {
+    connection: Connection,
+    functions: Vec<Box<dyn Function>>,
+    documents: HashMap<Url, Document>,
+}
+
+impl Server {
+    fn new(connection: Connection) -> Self {
+        Self {
+            connection,
+            functions: stdlib::all(),
+            documents: HashMap::default(),
+        }
+    }
+
+    fn run(mut self) -> Result<(), Error> {
+        let receiver = self.connection.receiver.clone();
+
+        for message in &receiver {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    self.handle_request(request)?;
+                }
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_request(&mut self, request: Request) -> Result<(), Error> {
+        let id = request.id.clone();
+
+        let result = match request.method.as_str() {
+            HoverRequest::METHOD => {
+                let params = extract::<HoverRequest>(request)?.text_document_position_params;
+                let document = self.documents.get(&params.text_document.uri);
+
+                serde_json::to_value(
+                    document.and_then(|document| document.hover(params.position, &self.functions)),
+                )?
+            }
+            Completion::METHOD => {
+                let params = extract::<Completion>(request)?.text_document_position;
+                let items = self
+                    .documents
+                    .get(&params.text_document.uri)
+                    .map(|document| document.completion(params.position, &self.functions))
+                    .unwrap_or_default();
+
+                serde_json::to_value(CompletionResponse::Array(items))?
+            }
+            GotoDefinition::METHOD => {
+                let params = extract::<GotoDefinition>(request)?.text_document_position_params;
+                let uri = params.text_document.uri;
+                let location = self
+                    .documents
+                    .get(&uri)
+                    .and_then(|document| document.definition(params.position))
+                    .map(|range| GotoDefinitionResponse::Scalar(Location::new(uri, range)));
+
+                serde_json::to_value(location)?
+            }
+            Formatting::METHOD => {
+                let params = extract::<Formatting>(request)?;
+                let edits = self
+                    .documents
+                    .get(&params.text_document.uri)
+                    .and_then(Document::formatting);
+
+                serde_json::to_value(edits)?
+            }
+            _ => {
+                let response = Response::new_err(
+                    id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    format!("unsupported method: {}", request.method),
+                );
+                return self.send(response.into());
+            }
+        };
+
+        self.send(Response::new_ok(id, result).into())
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<(), Error> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = extract_notification::<DidOpenTextDocument>(notification)?;
+                let document = params.text_document;
+                self.update(document.uri, document.text)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = extract_notification::<DidChangeTextDocument>(notification)?;
+
+                // The whole document is synced, so the last change holds its text.
+                match params.content_changes.into_iter().last() {
+                    Some(change) => self.update(params.text_document.uri, change.text),
+                    None => Ok(()),
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = extract_notification::<DidCloseTextDocument>(notification)?;
+                self.documents.remove(&params.text_document.uri);
+                self.publish_diagnostics(params.text_document.uri, vec![])
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Analyse the new text of a document, and publish its diagnostics.
+    fn update(&mut self, uri: Url, text: String) -> Result<(), Error> {
+        // Files are imported from the directory of the program.
+        let import_paths = uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| path.parent().map(ToOwned::to_owned))
+            .into_iter()
+            .collect();
+
+        let document = Document::new(text, &self.functions, import_paths);
+        let diagnostics = document.diagnostics();
+        self.documents.insert(uri.clone(), document);
+
+        self.publish_diagnostics(uri, diagnostics)
+    }
+
+    fn publish_diagnostics(
+        &self,
+        uri: Url,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+    ) -> Result<(), Error> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_owned(), params);
+
+        self.send(notification.into())
+    }
+
+    fn send(&self, message: Message) -> Result<(), Error> {
+        self.connection
+            .sender
+            .send(message)
+            .map_err(|_| Error::Disconnected)
+    }
+}
+
+fn extract<R: LspRequest>(request: Request) -> Result<R::Params, Error> {
+    request
+        .extract::<R::Params>(R::METHOD)
+        .map(|(_, params): (RequestId, _)| params)
+        .map_err(|err| Error::Request(format!("{:?}", err)))
+}
+
+fn extract_notification<N: LspNotification>(
+    notification: Notification,
+) -> Result<N::Params, Error> {
+    notification
+        .extract::<N::Params>(N::METHOD)
+        .map_err(|err| Error::Request(format!("{:?}", err)))
+}
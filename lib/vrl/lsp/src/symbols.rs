@@ -0,0 +1,218 @@
+use parser::ast::{
+    Assignment, AssignmentTarget, Container, Expr, FunctionCall, Ident, Node, Predicate, Program,
+    QueryTarget, RootExpr, Unary,
+};
+use vrl::diagnostic::Span;
+
+/// The variables and functions defined and used by a program, in the order
+/// they appear in the source.
+#[derive(Debug, Default)]
+pub(crate) struct Symbols {
+    /// The assignments of variables, and the parameters of closures and
+    /// user-defined functions.
+    definitions: Vec<(Ident, Span)>,
+
+    /// The variables read by the program.
+    references: Vec<(Ident, Span)>,
+
+    /// The user-defined functions, with the span of their identifier.
+    functions: Vec<(Ident, Span)>,
+
+    /// The function calls, with the span of the called identifier.
+    calls: Vec<(Ident, Span)>,
+}
+
+impl Symbols {
+    pub(crate) fn new(program: Program) -> Self {
+        let mut symbols = Self::default();
+
+        for node in program {
+            match node.into_inner() {
+                RootExpr::Expr(expr) => symbols.expr(expr),
+                RootExpr::FunctionDefinition(node) => {
+                    let definition = node.into_inner();
+                    let (span, ident) = definition.ident.take();
+                    symbols.functions.push((ident, span));
+                    symbols.idents(definition.parameters);
+                    symbols.exprs(definition.block.into_inner().into_inner());
+                }
+                RootExpr::Import(_) | RootExpr::Error(_) => {}
+            }
+        }
+
+        symbols
+    }
+
+    /// The variable names defined by the program, without duplicates.
+    pub(crate) fn variables(&self) -> Vec<&str> {
+        let mut variables = self
+            .definitions
+            .iter()
+            .map(|(ident, _)| ident.as_ref())
+            .collect::<Vec<_>>();
+
+        variables.sort();
+        variables.dedup();
+        variables
+    }
+
+    /// The function call whose identifier is at the offset.
+    pub(crate) fn call_at(&self, offset: usize) -> Option<&(Ident, Span)> {
+        self.calls.iter().find(|(_, span)| contains(*span, offset))
+    }
+
+    /// The definition of the variable or function at the offset.
+    ///
+    /// Variables resolve to the last definition before they're used, or the
+    /// first one when they're used before any definition, such as in a
+    /// function body.
+    pub(crate) fn definition_at(&self, offset: usize) -> Option<Span> {
+        if let Some((ident, _)) = self.call_at(offset) {
+            return self
+                .functions
+                .iter()
+                .find(|(function, _)| function == ident)
+                .map(|(_, span)| *span);
+        }
+
+        let (ident, span) = self
+            .references
+            .iter()
+            .chain(&self.definitions)
+            .find(|(_, span)| contains(*span, offset))?;
+
+        let mut definitions = self
+            .definitions
+            .iter()
+            .filter(|(definition, _)| definition == ident);
+
+        definitions
+            .clone()
+            .filter(|(_, definition)| definition.start() <= span.start())
+            .last()
+            .or_else(|| definitions.next())
+            .map(|(_, span)| *span)
+    }
+
+    fn exprs(&mut self, nodes: impl IntoIterator<Item = Node<Expr>>) {
+        for node in nodes {
+            self.expr(node);
+        }
+    }
+
+    fn expr(&mut self, node: Node<Expr>) {
+        match node.into_inner() {
+            Expr::Literal(_) => {}
+            Expr::Container(node) => self.container(node.into_inner()),
+            Expr::IfStatement(node) => {
+                let statement = node.into_inner();
+                match statement.predicate.into_inner() {
+                    Predicate::One(expr) => self.expr(*expr),
+                    Predicate::Many(exprs) => self.exprs(exprs),
+                }
+                self.exprs(statement.consequent.into_inner().into_inner());
+                if let Some(alternative) = statement.alternative {
+                    self.exprs(alternative.into_inner().into_inner());
+                }
+            }
+            Expr::Op(node) => {
+                let op = node.into_inner();
+                self.expr(*op.0);
+                self.expr(*op.2);
+            }
+            Expr::Assignment(node) => match node.into_inner() {
+                Assignment::Single { target, expr, .. } => {
+                    self.expr(*expr);
+                    self.assignment_target(target);
+                }
+                Assignment::Infallible { ok, err, expr, .. } => {
+                    self.expr(*expr);
+                    self.assignment_target(ok);
+                    self.assignment_target(err);
+                }
+            },
+            Expr::Query(node) => {
+                let query = node.into_inner();
+                let target_span = query.target.span();
+
+                match query.target.into_inner() {
+                    QueryTarget::Internal(ident) => self.references.push((ident, target_span)),
+                    QueryTarget::External => {}
+                    QueryTarget::FunctionCall(call) => self.function_call(call),
+                    QueryTarget::Container(container) => self.container(container),
+                }
+            }
+            Expr::FunctionCall(node) => self.function_call(node.into_inner()),
+            Expr::Variable(node) => {
+                let (span, ident) = node.take();
+                self.references.push((ident, span));
+            }
+            Expr::Unary(node) => match node.into_inner() {
+                Unary::Not(node) => {
+                    let (_, expr) = node.into_inner().take();
+                    self.expr(*expr);
+                }
+            },
+            Expr::Abort(node) => {
+                if let Some(message) = node.into_inner().message {
+                    self.expr(*message);
+                }
+            }
+        }
+    }
+
+    fn container(&mut self, container: Container) {
+        match container {
+            Container::Group(node) => self.expr(node.into_inner().into_inner()),
+            Container::Block(node) => self.exprs(node.into_inner().into_inner()),
+            Container::Array(node) => self.exprs(node.into_inner()),
+            Container::Object(node) => {
+                for (_, expr) in node.into_inner() {
+                    self.expr(expr);
+                }
+            }
+        }
+    }
+
+    fn function_call(&mut self, call: FunctionCall) {
+        let (span, ident) = call.ident.take();
+        self.calls.push((ident, span));
+
+        for argument in call.arguments {
+            self.expr(argument.into_inner().expr);
+        }
+
+        if let Some(closure) = call.closure {
+            let closure = closure.into_inner();
+            self.idents(closure.variables);
+            self.exprs(closure.block.into_inner().into_inner());
+        }
+    }
+
+    fn assignment_target(&mut self, node: Node<AssignmentTarget>) {
+        let span = node.span();
+
+        match node.into_inner() {
+            AssignmentTarget::Internal(ident, _) => self.definitions.push((ident, span)),
+            AssignmentTarget::Query(query) => {
+                if let QueryTarget::Internal(ident) = query.target.into_inner() {
+                    self.references.push((ident, span));
+                }
+            }
+            AssignmentTarget::Noop | AssignmentTarget::External(_) => {}
+        }
+    }
+
+    fn idents(&mut self, idents: Vec<Node<Ident>>) {
+        for node in idents {
+            let (span, ident) = node.take();
+            self.definitions.push((ident, span));
+        }
+    }
+}
+
+/// Whether the offset is within the span, including its end, which is where
+/// the cursor is when it's placed right after an identifier.
+fn contains(span: Span, offset: usize) -> bool {
+    span.start() <= offset && offset <= span.end()
+}
@@ -238,6 +238,7 @@ impl<'a> ArbitraryDepth<'a> for FunctionCall {
             ident,
             abort_on_error,
             arguments,
+            closure: None,
         })
     }
 }
@@ -0,0 +1,384 @@
+//! Format the source of a VRL program.
+//!
+//! The formatter re-emits the tokens of the program with canonical spacing and
+//! indentation, while keeping its comments, the line breaks chosen by the
+//! author (at most one blank line in a row), and the text of each token, so
+//! that strings, numbers and paths are never rewritten.
+
+use crate::{
+    ast::RootExpr,
+    lex::{Lexer, Token},
+};
+
+const INDENT: &str = "    ";
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Parse(#[from] crate::Error),
+
+    #[error("formatting changed the meaning of the program, please report")]
+    Changed,
+}
+
+/// Format the source of a program.
+///
+/// The program has to parse, and the formatted program is parsed again to
+/// make sure formatting didn't change its meaning.
+pub fn format(source: &str) -> Result<String, Error> {
+    let program = crate::parse(source)?;
+
+    // The parser recovers from errors in root expressions, to report all of them.
+    if let Some(err) = program.iter().find_map(|expr| match expr.inner() {
+        RootExpr::Error(err) => Some(err.clone()),
+        _ => None,
+    }) {
+        return Err(err.into());
+    }
+
+    let mut formatter = Formatter::new(source);
+    for token in Lexer::new(source) {
+        let (start, token, end) = token?;
+        formatter.token(start, token, end);
+    }
+    let formatted = formatter.finish();
+
+    // Spans differ after formatting, but they aren't part of the debug output
+    // of the program.
+    match crate::parse(&formatted) {
+        Ok(formatted_program) if format!("{:?}", formatted_program) == format!("{:?}", program) => {
+            Ok(formatted)
+        }
+        _ => Err(Error::Changed),
+    }
+}
+
+/// The pipes around the parameters of a closure, such as `-> |key, value|`.
+#[derive(Clone, Copy, PartialEq)]
+enum Closure {
+    None,
+    Arrow,
+    Parameters,
+}
+
+struct Formatter<'a> {
+    source: &'a str,
+    output: String,
+
+    /// The end of the previous token in the source.
+    last_end: usize,
+
+    /// The previous token written, and whether it was the opening pipe of the
+    /// parameters of a closure.
+    previous: Option<(Token<&'a str>, bool)>,
+
+    /// The number of line breaks since the previous token was written.
+    newlines: usize,
+
+    /// The number of unclosed brackets, braces and parentheses.
+    depth: usize,
+
+    /// Whether the current line continues an expression from the line before.
+    continuation: bool,
+
+    closure: Closure,
+
+    /// The depth of each path coalescing group, such as `.(a | b)`, in which
+    /// the original spacing is kept.
+    coalesce: Vec<usize>,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            output: String::with_capacity(source.len()),
+            last_end: 0,
+            previous: None,
+            newlines: 0,
+            depth: 0,
+            continuation: false,
+            closure: Closure::None,
+            coalesce: vec![],
+        }
+    }
+
+    fn token(&mut self, start: usize, token: Token<&'a str>, end: usize) {
+        match token {
+            // Query markers don't represent any text of the source.
+            Token::LQuery | Token::RQuery => return,
+            _ => {}
+        }
+
+        self.comment(start);
+        let adjacent = start == self.last_end;
+        self.last_end = end;
+
+        if token == Token::Newline {
+            self.newlines += 1;
+            return;
+        }
+
+        let closer = matches!(token, Token::RBrace | Token::RBracket | Token::RParen);
+        if closer {
+            self.depth = self.depth.saturating_sub(1);
+        }
+
+        let opening_pipe = token == Token::Operator("|") && self.closure == Closure::Arrow;
+        let closing_pipe = token == Token::Operator("|") && self.closure == Closure::Parameters;
+
+        if self.line_break(closer) {
+            self.indent();
+        } else if self.space_before(&token, adjacent, closing_pipe) {
+            self.output.push(' ');
+        }
+
+        self.output.push_str(&self.source[start..end]);
+
+        if token == Token::LParen && matches!(self.previous, Some((Token::Dot, _))) && adjacent {
+            self.coalesce.push(self.depth);
+        } else if token == Token::RParen && self.coalesce.last() == Some(&self.depth) {
+            self.coalesce.pop();
+        }
+
+        if matches!(token, Token::LBrace | Token::LBracket | Token::LParen) {
+            self.depth += 1;
+        }
+
+        self.closure = match token {
+            Token::Arrow => Closure::Arrow,
+            _ if opening_pipe => Closure::Parameters,
+            _ if closing_pipe => Closure::None,
+            _ => self.closure,
+        };
+        self.previous = Some((token, opening_pipe));
+    }
+
+    /// Write the comment in the source between the previous token and the
+    /// given offset, if any.
+    fn comment(&mut self, offset: usize) {
+        let gap = &self.source[self.last_end..offset];
+        let comment = match gap.find('#') {
+            Some(start) => gap[start..].trim_end(),
+            None => return,
+        };
+
+        if self.line_break(false) || self.output.is_empty() {
+            self.indent();
+        } else {
+            self.output.push(' ');
+        }
+        self.output.push_str(comment);
+    }
+
+    /// Start a new line if the source had a line break before the next token
+    /// or comment, keeping a single blank line if the source had any, except
+    /// at the start and end of a block.
+    fn line_break(&mut self, closer: bool) -> bool {
+        let newlines = std::mem::take(&mut self.newlines);
+        if newlines == 0 || self.output.is_empty() {
+            return false;
+        }
+
+        let opener = matches!(
+            self.previous,
+            Some((Token::LBrace | Token::LBracket | Token::LParen, _))
+        ) && self.output.ends_with(&['{', '[', '('][..]);
+
+        self.output.push('\n');
+        if newlines > 1 && !opener && !closer {
+            self.output.push('\n');
+        }
+
+        // Binary operators and assignments can be followed by a line break.
+        self.continuation = matches!(
+            self.previous,
+            Some((Token::Operator(_) | Token::Equals | Token::MergeEquals, _))
+        );
+
+        true
+    }
+
+    fn indent(&mut self) {
+        let depth = self.depth + usize::from(self.continuation);
+        self.output.push_str(&INDENT.repeat(depth));
+    }
+
+    fn space_before(&self, token: &Token<&'a str>, adjacent: bool, closing_pipe: bool) -> bool {
+        let (previous, opening_pipe) = match &self.previous {
+            Some((previous, opening_pipe)) => (previous, *opening_pipe),
+            None => return false,
+        };
+
+        // Paths are whitespace sensitive within coalescing groups.
+        if !self.coalesce.is_empty() {
+            return !adjacent;
+        }
+
+        match (previous, token) {
+            _ if opening_pipe || closing_pipe => false,
+            (_, Token::Comma | Token::SemiColon | Token::Colon) => false,
+            (_, Token::RParen | Token::RBracket) => false,
+            (Token::LParen | Token::LBracket, _) => false,
+            (Token::LBrace, Token::RBrace) => false,
+            (Token::FunctionCall(_), Token::LParen | Token::Bang) => false,
+            (Token::Bang, Token::LParen) => false,
+            // Negation is only followed by a space if it would otherwise be
+            // part of the next operator.
+            (Token::Bang, token) => matches!(token, Token::Operator(_)),
+            (Token::Dot, _) => !adjacent,
+            (previous, Token::Dot | Token::LBracket) if adjacent => !ends_operand(previous),
+            _ => true,
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.comment(self.source.len());
+
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+
+        self.output
+    }
+}
+
+/// Whether a token can end the target or a segment of a path, such as `foo`
+/// in `foo.bar`. Reserved identifiers and keywords are valid path fields.
+fn ends_operand(token: &Token<&str>) -> bool {
+    matches!(
+        token,
+        Token::Identifier(_)
+            | Token::PathField(_)
+            | Token::ReservedIdentifier(_)
+            | Token::StringLiteral(_)
+            | Token::IntegerLiteral(_)
+            | Token::RParen
+            | Token::RBracket
+            | Token::RBrace
+            | Token::Dot
+            | Token::If
+            | Token::Else
+            | Token::Null
+            | Token::False
+            | Token::True
+            | Token::Abort
+            | Token::Fn
+            | Token::Import
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+
+    fn assert_formatted(source: &str, expected: &str) {
+        let formatted = format(source).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(format(&formatted).unwrap(), formatted, "not idempotent");
+    }
+
+    #[test]
+    fn spacing() {
+        assert_formatted(".foo=1+2", ".foo = 1 + 2\n");
+        assert_formatted(
+            ".foo  |=  { \"a\" : 1 ,\"b\":[ 1,2 ] }",
+            ".foo |= { \"a\": 1, \"b\": [1, 2] }\n",
+        );
+        assert_formatted(".a = { }", ".a = {}\n");
+        assert_formatted(".a = ! .b", ".a = !.b\n");
+        assert_formatted(".a = !!exists(.b)", ".a = !!exists(.b)\n");
+        assert_formatted(
+            ".a,err = parse_json! ( .b , timezone:\"UTC\" )",
+            ".a, err = parse_json!(.b, timezone: \"UTC\")\n",
+        );
+        assert_formatted(".a = .b.c[0].\"d e\".if", ".a = .b.c[0].\"d e\".if\n");
+        assert_formatted("foo = [1][0]", "foo = [1][0]\n");
+        assert_formatted(".a = .(b|c)", ".a = .(b|c)\n");
+        assert_formatted("x = 1;.a = x", "x = 1; .a = x\n");
+    }
+
+    #[test]
+    fn closure() {
+        assert_formatted(
+            ". = map_values(.)->| value |{upcase!(value)}",
+            ". = map_values(.) -> |value| { upcase!(value) }\n",
+        );
+        assert_formatted(
+            "for_each(.)   -> |k,v| {\n.a = k\n}",
+            "for_each(.) -> |k, v| {\n    .a = k\n}\n",
+        );
+    }
+
+    #[test]
+    fn indentation() {
+        let source =
+            "\n\n  .a = 1\n\n\n\nif .b {\n\n.c = [\n   1,\n2\n ]\n\n  } else {\n.d = 1 +\n2\n}\n\n";
+        let expected = ".a = 1\n\nif .b {\n    .c = [\n        1,\n        2\n    ]\n} else {\n    .d = 1 +\n        2\n}\n";
+
+        assert_formatted(source, expected);
+    }
+
+    #[test]
+    fn comments() {
+        let source = "# leading\n.a = 1 # trailing\n  \nif true {\n  # inside\n.b = 2\n    # before closing\n}\n# end";
+        let expected = "# leading\n.a = 1 # trailing\n\nif true {\n    # inside\n    .b = 2\n    # before closing\n}\n# end\n";
+
+        assert_formatted(source, expected);
+        assert_formatted("# only a comment   ", "# only a comment\n");
+    }
+
+    #[test]
+    fn strings_are_untouched() {
+        assert_formatted(
+            ".a = \"  #  {\"\n.b = s'  x  '\n.c = r'\\d  +'",
+            ".a = \"  #  {\"\n.b = s'  x  '\n.c = r'\\d  +'\n",
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        assert!(matches!(format(".a = "), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn test_suite() {
+        let tests = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/tests");
+        let mut dirs = vec![tests];
+        let mut formatted_programs = 0;
+
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                if path.extension().and_then(|ext| ext.to_str()) != Some("vrl") {
+                    continue;
+                }
+
+                // Test programs start with comments holding their expected
+                // result, which don't have to parse.
+                let source = fs::read_to_string(&path).unwrap();
+                let formatted = match format(&source) {
+                    Ok(formatted) => formatted,
+                    Err(Error::Parse(_)) => continue,
+                    Err(err) => panic!("{}: {}", path.display(), err),
+                };
+
+                assert_eq!(
+                    format(&formatted).unwrap(),
+                    formatted,
+                    "{}: not idempotent",
+                    path.display()
+                );
+                formatted_programs += 1;
+            }
+        }
+
+        assert!(formatted_programs > 100);
+    }
+}
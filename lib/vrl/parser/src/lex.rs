@@ -245,6 +245,8 @@ pub enum Token<S> {
     False,
     True,
     Abort,
+    Fn,
+    Import,
 
     // tokens
     Colon,
@@ -265,6 +267,7 @@ pub enum Token<S> {
     MergeEquals,
     Bang,
     Question,
+    Arrow,
 
     /// The {L,R}Query token is an "instruction" token. It does not represent
     /// any character in the source, instead it represents the start or end of a
@@ -330,6 +333,8 @@ impl<S> Token<S> {
             Null => Null,
             True => True,
             Abort => Abort,
+            Fn => Fn,
+            Import => Import,
 
             // tokens
             Colon => Colon,
@@ -350,6 +355,7 @@ impl<S> Token<S> {
             MergeEquals => MergeEquals,
             Bang => Bang,
             Question => Question,
+            Arrow => Arrow,
 
             LQuery => LQuery,
             RQuery => RQuery,
@@ -384,6 +390,8 @@ where
             Null => "Null",
             True => "True",
             Abort => "Abort",
+            Fn => "Fn",
+            Import => "Import",
 
             // tokens
             Colon => "Colon",
@@ -404,6 +412,7 @@ where
             MergeEquals => "MergeEquals",
             Bang => "Bang",
             Question => "Question",
+            Arrow => "Arrow",
 
             LQuery => "LQuery",
             RQuery => "RQuery",
@@ -425,6 +434,8 @@ impl<'input> Token<&'input str> {
             "false" => False,
             "null" => Null,
             "abort" => Abort,
+            "fn" => Fn,
+            "import" => Import,
 
             // reserved identifiers
             "array" | "bool" | "boolean" | "break" | "continue" | "do" | "emit" | "float"
@@ -524,6 +535,11 @@ impl<'input> Iterator for Lexer<'input> {
                         Some(Ok(self.token(start, Bang)))
                     }
 
+                    '-' if self.test_peek(|ch| ch == '>') => {
+                        self.bump();
+                        Some(Ok(self.token(start, Arrow)))
+                    }
+
                     '#' => {
                         self.take_until(start, |ch| ch == '\n');
                         continue;
@@ -1405,6 +1421,62 @@ mod test {
         );
     }
 
+    #[test]
+    fn function_closures() {
+        test(
+            data(r#"foo(.) -> |k, v| { k }"#),
+            vec![
+                (r#"~~~                   "#, FunctionCall("foo")),
+                (r#"   ~                  "#, LParen),
+                (r#"    ~                 "#, LQuery),
+                (r#"    ~                 "#, Dot),
+                (r#"    ~                 "#, RQuery),
+                (r#"     ~                "#, RParen),
+                (r#"       ~~             "#, Arrow),
+                (r#"          ~           "#, Operator("|")),
+                (r#"           ~          "#, Identifier("k")),
+                (r#"            ~         "#, Comma),
+                (r#"              ~       "#, Identifier("v")),
+                (r#"               ~      "#, Operator("|")),
+                (r#"                 ~    "#, LBrace),
+                (r#"                   ~  "#, Identifier("k")),
+                (r#"                     ~"#, RBrace),
+            ],
+        );
+    }
+
+    #[test]
+    fn function_definitions() {
+        test(
+            data(r#"fn foo(a) { a }"#),
+            vec![
+                (r#"~~             "#, Fn),
+                (r#"   ~~~         "#, FunctionCall("foo")),
+                (r#"      ~        "#, LParen),
+                (r#"       ~       "#, Identifier("a")),
+                (r#"        ~      "#, RParen),
+                (r#"          ~    "#, LBrace),
+                (r#"            ~  "#, Identifier("a")),
+                (r#"              ~"#, RBrace),
+            ],
+        );
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn imports() {
+        use StringLiteral as S;
+        use Token::StringLiteral as L;
+
+        test(
+            data(r#"import "foo.vrl""#),
+            vec![
+                (r#"~~~~~~          "#, Import),
+                (r#"       ~~~~~~~~~"#, L(S::Escaped("foo.vrl"))),
+            ],
+        );
+    }
+
     #[test]
     fn function_calls() {
         test(
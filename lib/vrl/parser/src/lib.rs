@@ -10,6 +10,7 @@ mod arbitrary;
 #[cfg(feature = "fuzz")]
 mod arbitrary_depth;
 pub mod ast;
+pub mod format;
 mod lex;
 
 pub use ast::{Literal, Program};
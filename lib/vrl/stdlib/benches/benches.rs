@@ -16,16 +16,28 @@ criterion_group!(
               ceil,
               compact,
               contains,
+              decode_base32,
               decode_base64,
+              decode_gzip,
+              decode_hex,
               decode_percent,
+              decode_snappy,
+              decode_zlib,
+              decode_zstd,
+              decrypt,
               // TODO: Cannot pass a Path to bench_function
               //del,
               downcase,
+              encode_base32,
               encode_base64,
+              encode_gzip,
+              encode_hex,
               encode_key_value,
               encode_json,
               encode_logfmt,
               encode_percent,
+              // TODO: value is dynamic so we cannot assert equality
+              //encrypt,
               ends_with,
               // TODO: Cannot pass a Path to bench_function
               //exists
@@ -39,6 +51,7 @@ criterion_group!(
               get,
               get_env_var,
               get_hostname,
+              hmac,
               includes,
               int,
               ip_aton,
@@ -76,6 +89,7 @@ criterion_group!(
               parse_aws_alb_log,
               parse_aws_cloudwatch_log_subscription_message,
               parse_aws_vpc_flow_log,
+              parse_cef,
               parse_common_log,
               parse_csv,
               parse_duration,
@@ -84,6 +98,7 @@ criterion_group!(
               parse_groks,
               parse_key_value,
               parse_klog,
+              parse_leef,
               parse_int,
               parse_json,
               parse_nginx_log,
@@ -91,18 +106,22 @@ criterion_group!(
               parse_regex,
               parse_regex_all,
               parse_ruby_hash,
+              parse_suricata_eve,
               parse_syslog,
               parse_timestamp,
               parse_tokens,
               parse_url,
               parse_user_agent,
+              parse_windows_event_xml,
               parse_xml,
+              parse_zeek,
               push,
               redact,
               remove,
               replace,
               reverse_dns,
               round,
+              seahash,
               set,
               sha1,
               sha2,
@@ -132,7 +151,8 @@ criterion_group!(
               //unnest
               // TODO: value is dynamic so we cannot assert equality
               //uuidv4,
-              upcase
+              upcase,
+              xxhash
 );
 criterion_main!(benches);
 
@@ -222,6 +242,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    decode_base32 => vrl_stdlib::DecodeBase32;
+
+    literal {
+        args: func_args![value: "ONXW2ZJLHVZXI4TJNZTS65TBNR2WK==="],
+        want: Ok("some+=string/value"),
+    }
+}
+
 bench_function! {
     decode_base64 => vrl_stdlib::DecodeBase64;
 
@@ -231,6 +260,24 @@ bench_function! {
     }
 }
 
+bench_function! {
+    decode_gzip => vrl_stdlib::DecodeGzip;
+
+    literal {
+        args: func_args![value: Bytes::from_static(b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\xff\xab\xcc\x2f\x55\xc8\x48\x2c\x4b\x55\x28\x2e\x4d\x4e\x4e\x2d\x2e\x4e\x2b\xcd\xc9\xa9\x54\x48\x49\x4d\xce\x4f\x49\x4d\x51\xc8\x4d\x05\x00\xfb\x04\x60\xda\x20\x00\x00\x00")],
+        want: Ok("you have successfully decoded me"),
+    }
+}
+
+bench_function! {
+    decode_hex => vrl_stdlib::DecodeHex;
+
+    literal {
+        args: func_args![value: "736f6d652b3d737472696e672f76616c7565"],
+        want: Ok("some+=string/value"),
+    }
+}
+
 bench_function! {
     decode_percent => vrl_stdlib::DecodePercent;
 
@@ -240,6 +287,47 @@ bench_function! {
     }
 }
 
+bench_function! {
+    decode_snappy => vrl_stdlib::DecodeSnappy;
+
+    literal {
+        args: func_args![value: Bytes::from_static(b"\x20\x7cyou have successfully decoded me")],
+        want: Ok("you have successfully decoded me"),
+    }
+}
+
+bench_function! {
+    decode_zlib => vrl_stdlib::DecodeZlib;
+
+    literal {
+        args: func_args![value: Bytes::from_static(b"\x78\x9c\xab\xcc\x2f\x55\xc8\x48\x2c\x4b\x55\x28\x2e\x4d\x4e\x4e\x2d\x2e\x4e\x2b\xcd\xc9\xa9\x54\x48\x49\x4d\xce\x4f\x49\x4d\x51\xc8\x4d\x05\x00\xcc\xb2\x0c\x41")],
+        want: Ok("you have successfully decoded me"),
+    }
+}
+
+bench_function! {
+    decode_zstd => vrl_stdlib::DecodeZstd;
+
+    literal {
+        args: func_args![value: Bytes::from_static(b"\x28\xb5\x2f\xfd\x20\x20\x01\x01\x00you have successfully decoded me")],
+        want: Ok("you have successfully decoded me"),
+    }
+}
+
+bench_function! {
+    decrypt => vrl_stdlib::Decrypt;
+
+    aes_256_gcm {
+        args: func_args![
+            ciphertext: Bytes::from_static(b"\xef\x35\xa6\xc5\xa1\x0f\x3a\xfa\x72\x1b\x24\x02\xa7\x47\xd0\xa3\x5e\x99\x0b"),
+            algorithm: "AES-256-GCM",
+            key: "0123456789abcdef0123456789abcdef",
+            iv: "123456789012",
+        ],
+        want: Ok("foo"),
+    }
+}
+
 bench_function! {
     downcase => vrl_stdlib::Downcase;
 
@@ -249,6 +337,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    encode_base32 => vrl_stdlib::EncodeBase32;
+
+    literal {
+        args: func_args![value: "some+=string/value"],
+        want: Ok("ONXW2ZJLHVZXI4TJNZTS65TBNR2WK==="),
+    }
+}
+
 bench_function! {
     encode_base64 => vrl_stdlib::EncodeBase64;
 
@@ -258,6 +355,24 @@ bench_function! {
     }
 }
 
+bench_function! {
+    encode_gzip => vrl_stdlib::EncodeGzip;
+
+    literal {
+        args: func_args![value: "please encode me"],
+        want: Ok(Bytes::from_static(b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x00\xff\x2b\xc8\x49\x4d\x2c\x4e\x55\x48\xcd\x4b\xce\x4f\x49\x55\xc8\x4d\x05\x00\x8e\x11\xe2\xf7\x10\x00\x00\x00")),
+    }
+}
+
+bench_function! {
+    encode_hex => vrl_stdlib::EncodeHex;
+
+    literal {
+        args: func_args![value: "some+=string/value"],
+        want: Ok("736f6d652b3d737472696e672f76616c7565"),
+    }
+}
+
 bench_function! {
     encode_key_value => vrl_stdlib::EncodeKeyValue;
 
@@ -496,6 +611,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    hmac => vrl_stdlib::Hmac;
+
+    default {
+        args: func_args![value: "foo", key: "secret"],
+        want: Ok("773ba44693c7553d6ee20f61ea5d2757a9a4f4a44d2841ae4e95b52e4cd62db4")
+    }
+}
+
 bench_function! {
     includes => vrl_stdlib::Includes;
 
@@ -1274,6 +1398,29 @@ bench_function! {
     }
 }
 
+bench_function! {
+    parse_cef => vrl_stdlib::ParseCef;
+
+    literal {
+        args: func_args![
+            value: "CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232 msg=Detected a threat. No action needed",
+        ],
+        want: Ok(btreemap! {
+            "cefVersion" => "0",
+            "deviceVendor" => "Security",
+            "deviceProduct" => "threatmanager",
+            "deviceVersion" => "1.0",
+            "deviceEventClassId" => "100",
+            "name" => "worm successfully stopped",
+            "severity" => "10",
+            "src" => "10.0.0.1",
+            "dst" => "2.1.2.2",
+            "spt" => "1232",
+            "msg" => "Detected a threat. No action needed",
+        }),
+    }
+}
+
 bench_function! {
     parse_common_log => vrl_stdlib::ParseCommonLog;
 
@@ -1446,6 +1593,26 @@ bench_function! {
     }
 }
 
+bench_function! {
+    parse_leef => vrl_stdlib::ParseLeef;
+
+    literal {
+        args: func_args![
+            value: "LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5",
+        ],
+        want: Ok(btreemap! {
+            "leefVersion" => "1.0",
+            "vendor" => "Microsoft",
+            "productName" => "MSExchange",
+            "productVersion" => "4.0 SP1",
+            "eventId" => "15345",
+            "src" => "192.0.2.0",
+            "dst" => "172.50.123.1",
+            "sev" => "5",
+        }),
+    }
+}
+
 bench_function! {
     parse_nginx_log => vrl_stdlib::ParseNginxLog;
 
@@ -1587,6 +1754,34 @@ bench_function! {
     }
 }
 
+bench_function! {
+    parse_suricata_eve => vrl_stdlib::ParseSuricataEve;
+
+    alert {
+        args: func_args![
+            value: r#"{"timestamp":"2009-11-24T21:27:09.534255+0100","event_type":"alert","src_ip":"192.168.2.7","src_port":1041,"dest_ip":"x.x.250.50","dest_port":80,"proto":"TCP","alert":{"action":"allowed","gid":1,"signature_id":2001999,"rev":9,"signature":"ET MALWARE BTGrab.com Spyware Downloading Ads","category":"A Network Trojan was detected","severity":1}}"#,
+        ],
+        want: Ok(value!({
+            timestamp: (Utc.ymd(2009, 11, 24).and_hms_micro(20, 27, 9, 534255)),
+            event_type: "alert",
+            src_ip: "192.168.2.7",
+            src_port: 1041,
+            dest_ip: "x.x.250.50",
+            dest_port: 80,
+            proto: "TCP",
+            alert: {
+                action: "allowed",
+                gid: 1,
+                signature_id: 2001999,
+                rev: 9,
+                signature: "ET MALWARE BTGrab.com Spyware Downloading Ads",
+                category: "A Network Trojan was detected",
+                severity: 1,
+            },
+        })),
+    }
+}
+
 bench_function! {
     parse_syslog => vrl_stdlib::ParseSyslog;
 
@@ -1720,6 +1915,21 @@ bench_function! {
     }
 }
 
+bench_function! {
+    parse_windows_event_xml => vrl_stdlib::ParseWindowsEventXml;
+
+    security_event {
+        args: func_args![value: r#"<Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event"><System><Provider Name="Microsoft-Windows-Security-Auditing"/><EventID>4625</EventID><EventRecordID>42</EventRecordID><Channel>Security</Channel></System><EventData><Data Name="TargetUserName">jane</Data></EventData></Event>"#],
+        want: Ok(value!({
+            "provider_name": "Microsoft-Windows-Security-Auditing",
+            "event_id": 4625,
+            "record_id": 42,
+            "channel": "Security",
+            "event_data": { "TargetUserName": "jane" },
+        }))
+    }
+}
+
 bench_function! {
     parse_xml => vrl_stdlib::ParseXml;
 
@@ -1866,6 +2076,27 @@ bench_function! {
     }
 }
 
+bench_function! {
+    parse_zeek => vrl_stdlib::ParseZeek;
+
+    typed {
+        args: func_args![
+            value: "1320279566.452687\tCkVxvQ1NqdKqBvCBx2\t10.0.0.1\t80\ttcp\t-\tT\t(empty)",
+            fields: vec!["ts", "uid", "id.orig_h", "id.orig_p", "proto", "service", "local_orig", "tunnel_parents"],
+            types: vec!["time", "string", "addr", "port", "enum", "string", "bool", "set[string]"],
+        ],
+        want: Ok(btreemap! {
+            "ts" => Value::Timestamp(Utc.timestamp(1320279566, 452687000)),
+            "uid" => "CkVxvQ1NqdKqBvCBx2",
+            "id.orig_h" => "10.0.0.1",
+            "id.orig_p" => 80,
+            "proto" => "tcp",
+            "local_orig" => true,
+            "tunnel_parents" => Value::Array(vec![]),
+        }),
+    }
+}
+
 bench_function! {
     push => vrl_stdlib::Push;
 
@@ -1959,6 +2190,15 @@ bench_function! {
     }
 }
 
+bench_function! {
+    seahash => vrl_stdlib::Seahash;
+
+    literal {
+        args: func_args![value: "foo"],
+        want: Ok(4413582353838009230_i64)
+    }
+}
+
 bench_function! {
     sha1 => vrl_stdlib::Sha1;
 
@@ -2365,3 +2605,12 @@ bench_function! {
         want: Ok("FOO")
     }
 }
+
+bench_function! {
+    xxhash => vrl_stdlib::Xxhash;
+
+    default {
+        args: func_args![value: "foo"],
+        want: Ok(3792637401_i64)
+    }
+}
@@ -0,0 +1,109 @@
+use data_encoding::BASE32_NOPAD;
+use vrl::prelude::*;
+
+fn decode_base32(value: Value) -> Resolved {
+    let value = value.try_bytes()?;
+
+    // Accept values both with and without padding.
+    let end = value
+        .iter()
+        .rposition(|byte| *byte != b'=')
+        .map_or(0, |position| position + 1);
+
+    BASE32_NOPAD
+        .decode(&value[..end])
+        .map(|value| Bytes::from(value).into())
+        .map_err(|_| "unable to decode value from base32".into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeBase32;
+
+impl Function for DecodeBase32 {
+    fn identifier(&self) -> &'static str {
+        "decode_base32"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(DecodeBase32Fn { value }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_base32!("PFXXKIDIMF3GKIDTOVRWGZLTONTHK3DMPEQGIZLDN5SGKZBANVSQ====")"#,
+            result: Ok("you have successfully decoded me"),
+        }]
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+
+        decode_base32(value)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeBase32Fn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for DecodeBase32Fn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        decode_base32(value)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    test_function![
+        decode_base32 => DecodeBase32;
+
+        with_padding {
+            args: func_args![value: value!("ONXW2ZJAON2HE2LOM4QHMYLMOVSQ====")],
+            want: Ok(value!("some string value")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        without_padding {
+            args: func_args![value: value!("ONXW2ZJAON2HE2LOM4QHMYLMOVSQ")],
+            want: Ok(value!("some string value")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        empty {
+            args: func_args![value: value!("")],
+            want: Ok(value!("")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid {
+            args: func_args![value: value!("ONXW2ZJAON2HE2LOM4QHMYLMOVS1")],
+            want: Err("unable to decode value from base32"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
@@ -0,0 +1,136 @@
+use flate2::read::MultiGzDecoder;
+use vrl::prelude::*;
+
+use crate::util::{max_decompressed_size, read_decompressed};
+
+fn decode_gzip(value: Value, max_size: Option<Value>) -> Resolved {
+    let value = value.try_bytes()?;
+    let max_size = max_decompressed_size(max_size)?;
+
+    read_decompressed(MultiGzDecoder::new(&value[..]), max_size).map(Value::from)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeGzip;
+
+impl Function for DecodeGzip {
+    fn identifier(&self) -> &'static str {
+        "decode_gzip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "max_size",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let max_size = arguments.optional("max_size");
+
+        Ok(Box::new(DecodeGzipFn { value, max_size }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_gzip!(decode_base64!("H4sIAAAAAAAC/6vML1XISCxLVSguTU5OLS5OK83JqVRISU3OT0lNUchNBQD7BGDaIAAAAA=="))"#,
+            result: Ok("you have successfully decoded me"),
+        }]
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let max_size = args.optional("max_size");
+
+        decode_gzip(value, max_size)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeGzipFn {
+    value: Box<dyn Expression>,
+    max_size: Option<Box<dyn Expression>>,
+}
+
+impl Expression for DecodeGzipFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let max_size = self
+            .max_size
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+
+        decode_gzip(value, max_size)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // Always fallible due to the possibility of decompression errors that VRL can't detect
+        // in advance.
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    fn encode(text: &str) -> Bytes {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap().into()
+    }
+
+    test_function![
+        decode_gzip => DecodeGzip;
+
+        right_gzip {
+            args: func_args![value: value!(encode("some string value"))],
+            want: Ok(value!("some string value")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        empty_gzip {
+            args: func_args![value: value!(encode(""))],
+            want: Ok(value!("")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        wrong_gzip {
+            args: func_args![value: value!("some string value")],
+            want: Err("unable to decompress value: invalid gzip header"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        max_size {
+            args: func_args![value: value!(encode("some string value")), max_size: 4],
+            want: Err("decompressed value exceeds max_size of 4 bytes"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_max_size {
+            args: func_args![value: value!(encode("some string value")), max_size: 0],
+            want: Err("max_size must be a positive integer"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
@@ -0,0 +1,101 @@
+use vrl::prelude::*;
+
+fn decode_hex(value: Value) -> Resolved {
+    let value = value.try_bytes()?;
+
+    hex::decode(value)
+        .map(|value| Bytes::from(value).into())
+        .map_err(|_| "unable to decode value from hex".into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeHex;
+
+impl Function for DecodeHex {
+    fn identifier(&self) -> &'static str {
+        "decode_hex"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(DecodeHexFn { value }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_hex!("796f752068617665207375636365737366756c6c79206465636f646564206d65")"#,
+            result: Ok("you have successfully decoded me"),
+        }]
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+
+        decode_hex(value)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeHexFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for DecodeHexFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        decode_hex(value)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    test_function![
+        decode_hex => DecodeHex;
+
+        lowercase {
+            args: func_args![value: value!("736f6d6520737472696e672076616c7565")],
+            want: Ok(value!("some string value")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        uppercase {
+            args: func_args![value: value!("736F6D6520737472696E672076616C7565")],
+            want: Ok(value!("some string value")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        odd_length {
+            args: func_args![value: value!("736")],
+            want: Err("unable to decode value from hex"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_character {
+            args: func_args![value: value!("zz")],
+            want: Err("unable to decode value from hex"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
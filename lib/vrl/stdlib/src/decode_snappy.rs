@@ -0,0 +1,143 @@
+use vrl::prelude::*;
+
+use crate::util::{decompressed_size_error, max_decompressed_size};
+
+fn decode_snappy(value: Value, max_size: Option<Value>) -> Resolved {
+    let value = value.try_bytes()?;
+    let max_size = max_decompressed_size(max_size)?;
+
+    // The size of the decompressed value is part of the snappy header, so it can be checked
+    // before decompressing anything.
+    let size = snap::raw::decompress_len(&value)
+        .map_err(|err| format!("unable to decompress value: {}", err))?;
+    if size > max_size {
+        return Err(decompressed_size_error(max_size));
+    }
+
+    snap::raw::Decoder::new()
+        .decompress_vec(&value)
+        .map(|decompressed| Bytes::from(decompressed).into())
+        .map_err(|err| format!("unable to decompress value: {}", err).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeSnappy;
+
+impl Function for DecodeSnappy {
+    fn identifier(&self) -> &'static str {
+        "decode_snappy"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "max_size",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let max_size = arguments.optional("max_size");
+
+        Ok(Box::new(DecodeSnappyFn { value, max_size }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_snappy!(decode_base64!("IHx5b3UgaGF2ZSBzdWNjZXNzZnVsbHkgZGVjb2RlZCBtZQ=="))"#,
+            result: Ok("you have successfully decoded me"),
+        }]
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let max_size = args.optional("max_size");
+
+        decode_snappy(value, max_size)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeSnappyFn {
+    value: Box<dyn Expression>,
+    max_size: Option<Box<dyn Expression>>,
+}
+
+impl Expression for DecodeSnappyFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let max_size = self
+            .max_size
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+
+        decode_snappy(value, max_size)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // Always fallible due to the possibility of decompression errors that VRL can't detect
+        // in advance.
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn encode(text: &str) -> Bytes {
+        snap::raw::Encoder::new()
+            .compress_vec(text.as_bytes())
+            .unwrap()
+            .into()
+    }
+
+    test_function![
+        decode_snappy => DecodeSnappy;
+
+        right_snappy {
+            args: func_args![value: value!(encode("some string value"))],
+            want: Ok(value!("some string value")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        empty_snappy {
+            args: func_args![value: value!(encode(""))],
+            want: Ok(value!("")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        wrong_snappy {
+            args: func_args![value: value!("some string value")],
+            want: Err("unable to decompress value: snappy: corrupt input (expected valid offset but got offset 1931502957; dst position: 0)"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        max_size {
+            args: func_args![value: value!(encode("some string value")), max_size: 4],
+            want: Err("decompressed value exceeds max_size of 4 bytes"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_max_size {
+            args: func_args![value: value!(encode("some string value")), max_size: 0],
+            want: Err("max_size must be a positive integer"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
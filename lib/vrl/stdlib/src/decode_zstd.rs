@@ -0,0 +1,132 @@
+use vrl::prelude::*;
+
+use crate::util::{max_decompressed_size, read_decompressed};
+
+fn decode_zstd(value: Value, max_size: Option<Value>) -> Resolved {
+    let value = value.try_bytes()?;
+    let max_size = max_decompressed_size(max_size)?;
+
+    let decoder = zstd::stream::read::Decoder::new(&value[..])
+        .map_err(|err| format!("unable to decompress value: {}", err))?;
+
+    read_decompressed(decoder, max_size).map(Value::from)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeZstd;
+
+impl Function for DecodeZstd {
+    fn identifier(&self) -> &'static str {
+        "decode_zstd"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "max_size",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let max_size = arguments.optional("max_size");
+
+        Ok(Box::new(DecodeZstdFn { value, max_size }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_zstd!(decode_base64!("KLUv/SAgAQEAeW91IGhhdmUgc3VjY2Vzc2Z1bGx5IGRlY29kZWQgbWU="))"#,
+            result: Ok("you have successfully decoded me"),
+        }]
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let max_size = args.optional("max_size");
+
+        decode_zstd(value, max_size)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeZstdFn {
+    value: Box<dyn Expression>,
+    max_size: Option<Box<dyn Expression>>,
+}
+
+impl Expression for DecodeZstdFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let max_size = self
+            .max_size
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+
+        decode_zstd(value, max_size)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // Always fallible due to the possibility of decompression errors that VRL can't detect
+        // in advance.
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn encode(text: &str) -> Bytes {
+        zstd::stream::encode_all(text.as_bytes(), 0).unwrap().into()
+    }
+
+    test_function![
+        decode_zstd => DecodeZstd;
+
+        right_zstd {
+            args: func_args![value: value!(encode("some string value"))],
+            want: Ok(value!("some string value")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        empty_zstd {
+            args: func_args![value: value!(encode(""))],
+            want: Ok(value!("")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        wrong_zstd {
+            args: func_args![value: value!("some string value")],
+            want: Err("unable to decompress value: Unknown frame descriptor"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        max_size {
+            args: func_args![value: value!(encode("some string value")), max_size: 4],
+            want: Err("decompressed value exceeds max_size of 4 bytes"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_max_size {
+            args: func_args![value: value!(encode("some string value")), max_size: 0],
+            want: Err("max_size must be a positive integer"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
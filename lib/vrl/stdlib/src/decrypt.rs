@@ -0,0 +1,227 @@
+use aes_gcm::Aes256Gcm;
+use chacha20poly1305::{
+    aead::{generic_array::typenum::Unsigned, Aead, NewAead, Nonce},
+    ChaCha20Poly1305,
+};
+use vrl::prelude::*;
+
+fn decrypt(ciphertext: Value, algorithm: &Bytes, key: Value, iv: Value) -> Resolved {
+    let ciphertext = ciphertext.try_bytes()?;
+    let key = key.try_bytes()?;
+    let iv = iv.try_bytes()?;
+    let plaintext = match algorithm.as_ref() {
+        b"AES-256-GCM" => open::<Aes256Gcm>(&key, &iv, &ciphertext)?,
+        b"CHACHA20-POLY1305" => open::<ChaCha20Poly1305>(&key, &iv, &ciphertext)?,
+        _ => unreachable!("enum invariant"),
+    };
+
+    Ok(Bytes::from(plaintext).into())
+}
+
+fn open<C: NewAead + Aead>(key: &[u8], iv: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let cipher = C::new_from_slice(key)
+        .map_err(|_| format!("key must be {} bytes long", C::KeySize::USIZE))?;
+
+    if iv.len() != C::NonceSize::USIZE {
+        return Err(format!("iv must be {} bytes long", C::NonceSize::USIZE).into());
+    }
+
+    // Authentication failures are deliberately not told apart from other
+    // decryption errors.
+    cipher
+        .decrypt(Nonce::<C>::from_slice(iv), ciphertext)
+        .map_err(|_| "unable to decrypt value".into())
+}
+
+fn algorithms() -> Vec<Value> {
+    vec![value!("AES-256-GCM"), value!("CHACHA20-POLY1305")]
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Decrypt;
+
+impl Function for Decrypt {
+    fn identifier(&self) -> &'static str {
+        "decrypt"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "ciphertext",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "iv",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "AES-256-GCM",
+            source: r#"decrypt!(decode_base64!("7zWmxaEPOvpyGyQCp0fQo16ZCw=="), "AES-256-GCM", get_secret("encryption_key"), "123456789012")"#,
+            result: Ok("foo"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let ciphertext = arguments.required("ciphertext");
+        let algorithm = arguments
+            .required_enum("algorithm", &algorithms())?
+            .try_bytes()
+            .expect("algorithm not bytes");
+        let key = arguments.required("key");
+        let iv = arguments.required("iv");
+
+        Ok(Box::new(DecryptFn {
+            ciphertext,
+            algorithm,
+            key,
+            iv,
+        }))
+    }
+
+    fn compile_argument(
+        &self,
+        _args: &[(&'static str, Option<FunctionArgument>)],
+        _ctx: &mut FunctionCompileContext,
+        name: &str,
+        expr: Option<&expression::Expr>,
+    ) -> CompiledArgument {
+        match (name, expr) {
+            ("algorithm", Some(expr)) => {
+                let algorithm = expr
+                    .as_enum("algorithm", algorithms())?
+                    .try_bytes()
+                    .expect("algorithm not bytes");
+
+                Ok(Some(Box::new(algorithm) as _))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let ciphertext = args.required("ciphertext");
+        let algorithm = args
+            .required_any("algorithm")
+            .downcast_ref::<Bytes>()
+            .unwrap();
+        let key = args.required("key");
+        let iv = args.required("iv");
+
+        decrypt(ciphertext, algorithm, key, iv)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DecryptFn {
+    ciphertext: Box<dyn Expression>,
+    algorithm: Bytes,
+    key: Box<dyn Expression>,
+    iv: Box<dyn Expression>,
+}
+
+impl Expression for DecryptFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let ciphertext = self.ciphertext.resolve(ctx)?;
+        let key = self.key.resolve(ctx)?;
+        let iv = self.iv.resolve(ctx)?;
+
+        decrypt(ciphertext, &self.algorithm, key, iv)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors, the ciphertexts of "foo" with a key that must never be
+    // used outside of tests.
+    const AES_256_GCM: &[u8] =
+        b"\xef\x35\xa6\xc5\xa1\x0f\x3a\xfa\x72\x1b\x24\x02\xa7\x47\xd0\xa3\x5e\x99\x0b";
+    const CHACHA20_POLY1305: &[u8] =
+        b"\x28\xb3\x46\xde\xd5\xbd\x21\x48\x98\x71\xe5\x5b\xfe\xf3\x2c\x9f\xb1\x4d\x2c";
+
+    test_function![
+        decrypt => Decrypt;
+
+        aes_256_gcm {
+            args: func_args![
+                ciphertext: Bytes::from_static(AES_256_GCM),
+                algorithm: "AES-256-GCM",
+                key: "0123456789abcdef0123456789abcdef",
+                iv: "123456789012",
+            ],
+            want: Ok("foo"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        chacha20_poly1305 {
+            args: func_args![
+                ciphertext: Bytes::from_static(CHACHA20_POLY1305),
+                algorithm: "CHACHA20-POLY1305",
+                key: "0123456789abcdef0123456789abcdef",
+                iv: "123456789012",
+            ],
+            want: Ok("foo"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        wrong_key {
+            args: func_args![
+                ciphertext: Bytes::from_static(AES_256_GCM),
+                algorithm: "AES-256-GCM",
+                key: "fedcba9876543210fedcba9876543210",
+                iv: "123456789012",
+            ],
+            want: Err("unable to decrypt value"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        wrong_algorithm {
+            args: func_args![
+                ciphertext: Bytes::from_static(AES_256_GCM),
+                algorithm: "CHACHA20-POLY1305",
+                key: "0123456789abcdef0123456789abcdef",
+                iv: "123456789012",
+            ],
+            want: Err("unable to decrypt value"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        short_iv {
+            args: func_args![
+                ciphertext: Bytes::from_static(AES_256_GCM),
+                algorithm: "AES-256-GCM",
+                key: "0123456789abcdef0123456789abcdef",
+                iv: "1234",
+            ],
+            want: Err("iv must be 12 bytes long"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
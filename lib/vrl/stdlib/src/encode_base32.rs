@@ -0,0 +1,116 @@
+use data_encoding::{BASE32, BASE32_NOPAD};
+use vrl::prelude::*;
+
+fn encode_base32(value: Value, padding: Option<Value>) -> Resolved {
+    let value = value.try_bytes()?;
+    let padding = padding
+        .map(|v| v.try_boolean())
+        .transpose()?
+        .unwrap_or(true);
+    let encoding = if padding { &BASE32 } else { &BASE32_NOPAD };
+
+    Ok(encoding.encode(&value).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeBase32;
+
+impl Function for EncodeBase32 {
+    fn identifier(&self) -> &'static str {
+        "encode_base32"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "padding",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let padding = arguments.optional("padding");
+
+        Ok(Box::new(EncodeBase32Fn { value, padding }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"encode_base32("please encode me")"#,
+            result: Ok("OBWGKYLTMUQGK3TDN5SGKIDNMU======"),
+        }]
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let padding = args.optional("padding");
+
+        encode_base32(value, padding)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeBase32Fn {
+    value: Box<dyn Expression>,
+    padding: Option<Box<dyn Expression>>,
+}
+
+impl Expression for EncodeBase32Fn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let padding = self.padding.as_ref().map(|p| p.resolve(ctx)).transpose()?;
+
+        encode_base32(value, padding)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::bytes().infallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    test_function![
+        encode_base32 => EncodeBase32;
+
+        with_defaults {
+            args: func_args![value: value!("some string value")],
+            want: Ok(value!("ONXW2ZJAON2HE2LOM4QHMYLMOVSQ====")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        with_padding {
+            args: func_args![value: value!("some string value"), padding: true],
+            want: Ok(value!("ONXW2ZJAON2HE2LOM4QHMYLMOVSQ====")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        no_padding {
+            args: func_args![value: value!("some string value"), padding: false],
+            want: Ok(value!("ONXW2ZJAON2HE2LOM4QHMYLMOVSQ")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        empty {
+            args: func_args![value: value!("")],
+            want: Ok(value!("")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+    ];
+}
@@ -0,0 +1,153 @@
+use std::io::Read;
+
+use flate2::{read::GzEncoder, Compression};
+use vrl::prelude::*;
+
+const MAX_COMPRESSION_LEVEL: u32 = 9;
+
+fn encode_gzip(value: Value, compression_level: Option<Value>) -> Resolved {
+    let value = value.try_bytes()?;
+    let level = match compression_level {
+        Some(level) => {
+            let level = level.try_integer()?;
+
+            u32::try_from(level)
+                .ok()
+                .filter(|level| *level <= MAX_COMPRESSION_LEVEL)
+                .map(Compression::new)
+                .ok_or_else(|| {
+                    format!(
+                        "compression_level must be between 0 and {}",
+                        MAX_COMPRESSION_LEVEL
+                    )
+                })?
+        }
+        None => Compression::default(),
+    };
+
+    let mut buf = Vec::new();
+    GzEncoder::new(&value[..], level)
+        .read_to_end(&mut buf)
+        .expect("gzip compression failed, please report");
+
+    Ok(Value::Bytes(buf.into()))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeGzip;
+
+impl Function for EncodeGzip {
+    fn identifier(&self) -> &'static str {
+        "encode_gzip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "compression_level",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let compression_level = arguments.optional("compression_level");
+
+        Ok(Box::new(EncodeGzipFn {
+            value,
+            compression_level,
+        }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"decode_gzip!(encode_gzip("please encode me"))"#,
+            result: Ok("please encode me"),
+        }]
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let compression_level = args.optional("compression_level");
+
+        encode_gzip(value, compression_level)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeGzipFn {
+    value: Box<dyn Expression>,
+    compression_level: Option<Box<dyn Expression>>,
+}
+
+impl Expression for EncodeGzipFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let compression_level = self
+            .compression_level
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+
+        encode_gzip(value, compression_level)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::bytes().with_fallibility(self.compression_level.is_some())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use flate2::read::MultiGzDecoder;
+
+    use super::*;
+
+    fn decode(value: Value) -> Value {
+        let value = value.try_bytes().unwrap();
+        let mut buf = Vec::new();
+        MultiGzDecoder::new(&value[..])
+            .read_to_end(&mut buf)
+            .unwrap();
+
+        Bytes::from(buf).into()
+    }
+
+    #[test]
+    fn round_trip() {
+        for level in [None, Some(0), Some(1), Some(9)] {
+            let encoded = encode_gzip(value!("some string value"), level.map(Value::from)).unwrap();
+
+            assert_eq!(decode(encoded), value!("some string value"));
+        }
+    }
+
+    test_function![
+        encode_gzip => EncodeGzip;
+
+        invalid_compression_level {
+            args: func_args![value: value!("some string value"), compression_level: 10],
+            want: Err("compression_level must be between 0 and 9"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        negative_compression_level {
+            args: func_args![value: value!("some string value"), compression_level: -1],
+            want: Err("compression_level must be between 0 and 9"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
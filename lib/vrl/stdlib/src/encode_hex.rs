@@ -0,0 +1,93 @@
+use vrl::prelude::*;
+
+fn encode_hex(value: Value) -> Resolved {
+    let value = value.try_bytes()?;
+
+    Ok(hex::encode(value).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeHex;
+
+impl Function for EncodeHex {
+    fn identifier(&self) -> &'static str {
+        "encode_hex"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(EncodeHexFn { value }))
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "demo string",
+            source: r#"encode_hex("please encode me")"#,
+            result: Ok("706c6561736520656e636f6465206d65"),
+        }]
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+
+        encode_hex(value)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeHexFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for EncodeHexFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        encode_hex(value)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::bytes().infallible()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    test_function![
+        encode_hex => EncodeHex;
+
+        string {
+            args: func_args![value: value!("some string value")],
+            want: Ok(value!("736f6d6520737472696e672076616c7565")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        binary {
+            args: func_args![value: value!(Bytes::from_static(&[0x00, 0x7f, 0xff]))],
+            want: Ok(value!("007fff")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        empty {
+            args: func_args![value: value!("")],
+            want: Ok(value!("")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+    ];
+}
@@ -0,0 +1,197 @@
+use aes_gcm::Aes256Gcm;
+use chacha20poly1305::{
+    aead::{generic_array::typenum::Unsigned, Aead, NewAead, Nonce},
+    ChaCha20Poly1305,
+};
+use vrl::prelude::*;
+
+/// The length of the random IV generated for each encrypted value, which is
+/// the nonce length of all supported algorithms.
+const IV_LENGTH: usize = 12;
+
+fn encrypt(plaintext: Value, algorithm: &Bytes, key: Value) -> Resolved {
+    let plaintext = plaintext.try_bytes()?;
+    let key = key.try_bytes()?;
+    let iv = rand::random::<[u8; IV_LENGTH]>();
+    let ciphertext = match algorithm.as_ref() {
+        b"AES-256-GCM" => seal::<Aes256Gcm>(&key, &iv, &plaintext)?,
+        b"CHACHA20-POLY1305" => seal::<ChaCha20Poly1305>(&key, &iv, &plaintext)?,
+        _ => unreachable!("enum invariant"),
+    };
+
+    Ok(BTreeMap::from([
+        (
+            "ciphertext".to_owned(),
+            Value::from(Bytes::from(ciphertext)),
+        ),
+        ("iv".to_owned(), Value::from(Bytes::copy_from_slice(&iv))),
+    ])
+    .into())
+}
+
+fn seal<C: NewAead + Aead>(key: &[u8], iv: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let cipher = C::new_from_slice(key)
+        .map_err(|_| format!("key must be {} bytes long", C::KeySize::USIZE))?;
+
+    cipher
+        .encrypt(Nonce::<C>::from_slice(iv), plaintext)
+        .map_err(|_| "unable to encrypt value".into())
+}
+
+fn algorithms() -> Vec<Value> {
+    vec![value!("AES-256-GCM"), value!("CHACHA20-POLY1305")]
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Encrypt;
+
+impl Function for Encrypt {
+    fn identifier(&self) -> &'static str {
+        "encrypt"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "plaintext",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "round trip",
+            source: r#"
+                key = get_secret("encryption_key")
+                encrypted = encrypt!("foo", "AES-256-GCM", key)
+                decrypt!(encrypted.ciphertext, "AES-256-GCM", key, encrypted.iv)
+            "#,
+            result: Ok("foo"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let plaintext = arguments.required("plaintext");
+        let algorithm = arguments
+            .required_enum("algorithm", &algorithms())?
+            .try_bytes()
+            .expect("algorithm not bytes");
+        let key = arguments.required("key");
+
+        Ok(Box::new(EncryptFn {
+            plaintext,
+            algorithm,
+            key,
+        }))
+    }
+
+    fn compile_argument(
+        &self,
+        _args: &[(&'static str, Option<FunctionArgument>)],
+        _ctx: &mut FunctionCompileContext,
+        name: &str,
+        expr: Option<&expression::Expr>,
+    ) -> CompiledArgument {
+        match (name, expr) {
+            ("algorithm", Some(expr)) => {
+                let algorithm = expr
+                    .as_enum("algorithm", algorithms())?
+                    .try_bytes()
+                    .expect("algorithm not bytes");
+
+                Ok(Some(Box::new(algorithm) as _))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let plaintext = args.required("plaintext");
+        let algorithm = args
+            .required_any("algorithm")
+            .downcast_ref::<Bytes>()
+            .unwrap();
+        let key = args.required("key");
+
+        encrypt(plaintext, algorithm, key)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncryptFn {
+    plaintext: Box<dyn Expression>,
+    algorithm: Bytes,
+    key: Box<dyn Expression>,
+}
+
+impl Expression for EncryptFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let plaintext = self.plaintext.resolve(ctx)?;
+        let key = self.key.resolve(ctx)?;
+
+        encrypt(plaintext, &self.algorithm, key)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        // Fallible, as the key can't be checked until the program runs.
+        TypeDef::object(inner_kind()).fallible()
+    }
+}
+
+fn inner_kind() -> BTreeMap<Field, Kind> {
+    BTreeMap::from([
+        ("ciphertext".into(), Kind::bytes()),
+        ("iv".into(), Kind::bytes()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors, the keys must never be used outside of tests.
+    test_function![
+        encrypt => Encrypt;
+
+        short_key {
+            args: func_args![plaintext: "foo", algorithm: "AES-256-GCM", key: "secret"],
+            want: Err("key must be 32 bytes long"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        long_key {
+            args: func_args![plaintext: "foo", algorithm: "CHACHA20-POLY1305", key: "0123456789abcdef0123456789abcdef0"],
+            want: Err("key must be 32 bytes long"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+    ];
+
+    #[test]
+    fn random_iv() {
+        let algorithm = Bytes::from("AES-256-GCM");
+        // Test vector, the key must never be used outside of tests.
+        let key = value!("0123456789abcdef0123456789abcdef");
+
+        let first = encrypt(value!("foo"), &algorithm, key.clone()).unwrap();
+        let second = encrypt(value!("foo"), &algorithm, key).unwrap();
+
+        assert_ne!(first, second);
+    }
+}
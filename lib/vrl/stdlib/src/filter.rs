@@ -0,0 +1,153 @@
+use vrl::prelude::*;
+
+fn filter<T>(value: Value, runner: &mut T) -> Resolved
+where
+    T: FnMut(Value, Value) -> Resolved,
+{
+    match value {
+        Value::Object(object) => {
+            let mut filtered = BTreeMap::new();
+            for (key, value) in object {
+                if runner(key.clone().into(), value.clone())?.try_boolean()? {
+                    filtered.insert(key, value);
+                }
+            }
+
+            Ok(Value::Object(filtered))
+        }
+        Value::Array(array) => {
+            let mut filtered = Vec::with_capacity(array.len());
+            for (index, value) in array.into_iter().enumerate() {
+                if runner((index as i64).into(), value.clone())?.try_boolean()? {
+                    filtered.push(value);
+                }
+            }
+
+            Ok(Value::Array(filtered))
+        }
+        value => Err(value::Error::Expected {
+            got: value.kind(),
+            expected: Kind::array(Collection::any()) | Kind::object(Collection::any()),
+        }
+        .into()),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Filter;
+
+impl Function for Filter {
+    fn identifier(&self) -> &'static str {
+        "filter"
+    }
+
+    fn summary(&self) -> &'static str {
+        "filter the elements of an object or array using a closure"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {"
+            Keeps the elements of an object or array for which the closure returns `true`. The
+            closure receives the key and value of each field of an object, or the index and value
+            of each element of an array.
+        "}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::OBJECT | kind::ARRAY,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "drop null fields",
+                source: r#"filter({ "a": 1, "b": null }) -> |_key, value| { !is_null(value) }"#,
+                result: Ok(r#"{ "a": 1 }"#),
+            },
+            Example {
+                title: "array",
+                source: r#"filter([1, 2, 3, 4]) -> |_index, value| { value > 2 }"#,
+                result: Ok("[3, 4]"),
+            },
+        ]
+    }
+
+    fn closure(&self) -> Option<closure::Definition> {
+        use closure::{Definition, Input, Output, Variable, VariableKind};
+
+        let input = |kind| Input {
+            parameter_keyword: "value",
+            kind,
+            variables: vec![
+                Variable {
+                    kind: VariableKind::TargetInnerKey,
+                },
+                Variable {
+                    kind: VariableKind::TargetInnerValue,
+                },
+            ],
+            output: Output::Kind(Kind::boolean()),
+        };
+
+        Some(Definition {
+            inputs: vec![
+                input(Kind::object(Collection::any())),
+                input(Kind::array(Collection::any())),
+            ],
+        })
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let closure = arguments.required_closure()?;
+
+        Ok(Box::new(FilterFn { value, closure }))
+    }
+
+    fn call_by_vm(&self, ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let closure = args.required_closure();
+
+        filter(value, &mut |key, value| closure.run(ctx, [key, value]))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FilterFn {
+    value: Box<dyn Expression>,
+    closure: closure::FunctionClosure,
+}
+
+impl Expression for FilterFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        filter(value, &mut |key, value| self.closure.run(ctx, [key, value]))
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        // Any element might be removed, so the known elements become unknown.
+        let value = self.value.type_def(state);
+
+        let mut kind = Kind::empty();
+        if let Some(mut object) = value.as_object().cloned() {
+            object.anonymize();
+            kind.add_object(object);
+        }
+        if let Some(mut array) = value.as_array().cloned() {
+            array.anonymize();
+            kind.add_array(array);
+        }
+
+        TypeDef::from(kind).infallible()
+    }
+}
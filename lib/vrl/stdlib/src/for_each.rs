@@ -0,0 +1,131 @@
+use vrl::prelude::*;
+
+fn for_each<T>(value: Value, runner: &mut T) -> Resolved
+where
+    T: FnMut(Value, Value) -> Resolved,
+{
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                runner(key.into(), value)?;
+            }
+        }
+        Value::Array(array) => {
+            for (index, value) in array.into_iter().enumerate() {
+                runner((index as i64).into(), value)?;
+            }
+        }
+        value => {
+            return Err(value::Error::Expected {
+                got: value.kind(),
+                expected: Kind::array(Collection::any()) | Kind::object(Collection::any()),
+            }
+            .into())
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ForEach;
+
+impl Function for ForEach {
+    fn identifier(&self) -> &'static str {
+        "for_each"
+    }
+
+    fn summary(&self) -> &'static str {
+        "run a closure for each element of an object or array"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {"
+            Runs the closure for each key and value of an object, or each index and value of an
+            array. The result of the closure is discarded, use assignments within the closure to
+            collect results.
+        "}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::OBJECT | kind::ARRAY,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "sum values",
+            source: indoc! {r#"
+                sum = 0
+                for_each([1, 2, 3]) -> |_index, value| { sum = sum + value }
+                sum
+            "#},
+            result: Ok("6"),
+        }]
+    }
+
+    fn closure(&self) -> Option<closure::Definition> {
+        use closure::{Definition, Input, Output, Variable, VariableKind};
+
+        let input = |kind| Input {
+            parameter_keyword: "value",
+            kind,
+            variables: vec![
+                Variable {
+                    kind: VariableKind::TargetInnerKey,
+                },
+                Variable {
+                    kind: VariableKind::TargetInnerValue,
+                },
+            ],
+            output: Output::Any,
+        };
+
+        Some(Definition {
+            inputs: vec![
+                input(Kind::object(Collection::any())),
+                input(Kind::array(Collection::any())),
+            ],
+        })
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let closure = arguments.required_closure()?;
+
+        Ok(Box::new(ForEachFn { value, closure }))
+    }
+
+    fn call_by_vm(&self, ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let closure = args.required_closure();
+
+        for_each(value, &mut |key, value| closure.run(ctx, [key, value]))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ForEachFn {
+    value: Box<dyn Expression>,
+    closure: closure::FunctionClosure,
+}
+
+impl Expression for ForEachFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        for_each(value, &mut |key, value| self.closure.run(ctx, [key, value]))
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::null().infallible()
+    }
+}
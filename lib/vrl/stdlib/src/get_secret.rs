@@ -0,0 +1,188 @@
+use std::{collections::BTreeMap, fmt, iter::FromIterator};
+
+use vrl::prelude::*;
+
+/// The secrets a program can read with `get_secret`, set as external context
+/// of the compiler.
+#[derive(Clone, Default)]
+pub struct Secrets(BTreeMap<String, Bytes>);
+
+impl Secrets {
+    pub fn insert(&mut self, name: impl Into<String>, secret: impl Into<Bytes>) {
+        self.0.insert(name.into(), secret.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Bytes> {
+        self.0.get(name)
+    }
+}
+
+impl<K: Into<String>, V: Into<Bytes>> FromIterator<(K, V)> for Secrets {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(name, secret)| (name.into(), secret.into()))
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Debug for Secrets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+fn get_secret(
+    secrets: Option<&Secrets>,
+    name: Value,
+) -> std::result::Result<Bytes, vrl::function::Error> {
+    let secret = match &name {
+        Value::Bytes(bytes) => {
+            secrets.and_then(|secrets| secrets.get(&String::from_utf8_lossy(bytes)))
+        }
+        _ => None,
+    };
+
+    secret
+        .cloned()
+        .ok_or(vrl::function::Error::InvalidArgument {
+            keyword: "name",
+            value: name,
+            error: "unknown secret",
+        })
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GetSecret;
+
+impl Function for GetSecret {
+    fn identifier(&self) -> &'static str {
+        "get_secret"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "name",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "hmac key",
+            source: r#"get_secret("hmac_key") != """#,
+            result: Ok("true"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let name = arguments.required_literal("name")?.to_value();
+        let value = get_secret(ctx.get_external_context::<Secrets>(), name)?;
+
+        Ok(Box::new(GetSecretFn { value }))
+    }
+
+    fn compile_argument(
+        &self,
+        _args: &[(&'static str, Option<FunctionArgument>)],
+        ctx: &mut FunctionCompileContext,
+        name: &str,
+        expr: Option<&expression::Expr>,
+    ) -> CompiledArgument {
+        match (name, expr) {
+            ("name", Some(expr)) => {
+                let name = expr.as_literal("name")?;
+                let value = get_secret(ctx.get_external_context::<Secrets>(), name)?;
+
+                Ok(Some(Box::new(value) as _))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args
+            .required_any("name")
+            .downcast_ref::<Bytes>()
+            .expect("secret not bytes");
+
+        Ok(value.clone().into())
+    }
+}
+
+#[derive(Clone)]
+struct GetSecretFn {
+    value: Bytes,
+}
+
+impl fmt::Debug for GetSecretFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GetSecretFn")
+            .field("value", &"<redacted>")
+            .finish()
+    }
+}
+
+impl Expression for GetSecretFn {
+    fn resolve(&self, _: &mut Context) -> Resolved {
+        Ok(self.value.clone().into())
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::bytes().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::TimeZone;
+    use vrl::{state, Runtime};
+
+    use super::*;
+
+    fn resolve(source: &str, secrets: Secrets) -> std::result::Result<Value, String> {
+        let mut state = state::Compiler::new();
+        state.set_external_context(secrets);
+
+        let functions: Vec<Box<dyn vrl::Function>> = vec![Box::new(GetSecret)];
+        let program = vrl::compile_with_state(source, &functions, &mut state)
+            .map_err(|diagnostics| diagnostics[0].message())?;
+
+        Runtime::new(state::Runtime::default())
+            .resolve(&mut value!({}), &program, &TimeZone::default())
+            .map_err(|error| error.to_string())
+    }
+
+    #[test]
+    fn reads_secret() {
+        let secrets = Secrets::from_iter([("key", "secret")]);
+
+        assert_eq!(
+            resolve(r#"get_secret("key")"#, secrets),
+            Ok(value!("secret"))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_secret() {
+        let secrets = Secrets::from_iter([("key", "secret")]);
+
+        assert!(resolve(r#"get_secret("other")"#, secrets)
+            .unwrap_err()
+            .ends_with("invalid argument"));
+    }
+
+    #[test]
+    fn redacts_secrets() {
+        let secrets = Secrets::from_iter([("key", "secret")]);
+
+        assert_eq!(format!("{:?}", secrets), r#"{"key"}"#);
+    }
+}
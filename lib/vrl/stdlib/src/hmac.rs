@@ -0,0 +1,193 @@
+use ::sha1::Sha1;
+use hmac_lib::{digest::KeyInit, Mac};
+use sha_2::{Sha224, Sha256, Sha384, Sha512};
+use vrl::prelude::*;
+
+fn hmac(value: Value, key: Value, algorithm: &Bytes) -> Resolved {
+    let value = value.try_bytes()?;
+    let key = key.try_bytes()?;
+    let hash = match algorithm.as_ref() {
+        b"SHA-1" => encode::<hmac_lib::Hmac<Sha1>>(&key, &value),
+        b"SHA-224" => encode::<hmac_lib::Hmac<Sha224>>(&key, &value),
+        b"SHA-256" => encode::<hmac_lib::Hmac<Sha256>>(&key, &value),
+        b"SHA-384" => encode::<hmac_lib::Hmac<Sha384>>(&key, &value),
+        b"SHA-512" => encode::<hmac_lib::Hmac<Sha512>>(&key, &value),
+        _ => unreachable!("enum invariant"),
+    };
+    Ok(hash.into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Hmac;
+
+fn algorithms() -> Vec<Value> {
+    vec![
+        value!("SHA-1"),
+        value!("SHA-224"),
+        value!("SHA-256"),
+        value!("SHA-384"),
+        value!("SHA-512"),
+    ]
+}
+
+impl Function for Hmac {
+    fn identifier(&self) -> &'static str {
+        "hmac"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "algorithm",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "default algorithm",
+                source: r#"hmac("foo", get_secret("hmac_key"))"#,
+                result: Ok("773ba44693c7553d6ee20f61ea5d2757a9a4f4a44d2841ae4e95b52e4cd62db4"),
+            },
+            Example {
+                title: "custom algorithm",
+                source: r#"hmac("foo", get_secret("hmac_key"), "SHA-1")"#,
+                result: Ok("9baed91be7f58b57c824b60da7cb262b2ecafbd2"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let key = arguments.required("key");
+        let algorithm = arguments
+            .optional_enum("algorithm", &algorithms())?
+            .unwrap_or_else(|| value!("SHA-256"))
+            .try_bytes()
+            .expect("algorithm not bytes");
+
+        Ok(Box::new(HmacFn {
+            value,
+            key,
+            algorithm,
+        }))
+    }
+
+    fn compile_argument(
+        &self,
+        _args: &[(&'static str, Option<FunctionArgument>)],
+        _ctx: &mut FunctionCompileContext,
+        name: &str,
+        expr: Option<&expression::Expr>,
+    ) -> CompiledArgument {
+        match (name, expr) {
+            ("algorithm", Some(expr)) => {
+                let algorithm = expr
+                    .as_enum("algorithm", algorithms())?
+                    .try_bytes()
+                    .expect("algorithm not bytes");
+
+                Ok(Some(Box::new(algorithm) as _))
+            }
+            ("algorithm", None) => Ok(Some(Box::new(Bytes::from("SHA-256")) as _)),
+            _ => Ok(None),
+        }
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let key = args.required("key");
+        let algorithm = args
+            .required_any("algorithm")
+            .downcast_ref::<Bytes>()
+            .unwrap();
+
+        hmac(value, key, algorithm)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HmacFn {
+    value: Box<dyn Expression>,
+    key: Box<dyn Expression>,
+    algorithm: Bytes,
+}
+
+impl Expression for HmacFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let key = self.key.resolve(ctx)?;
+        let algorithm = &self.algorithm;
+
+        hmac(value, key, algorithm)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::bytes().infallible()
+    }
+}
+
+#[inline]
+fn encode<M: Mac + KeyInit>(key: &[u8], value: &[u8]) -> String {
+    let mut mac = <M as KeyInit>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(value);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors, the key must never be used outside of tests.
+    test_function![
+        hmac => Hmac;
+
+        hmac {
+             args: func_args![value: "foo", key: "secret"],
+             want: Ok("773ba44693c7553d6ee20f61ea5d2757a9a4f4a44d2841ae4e95b52e4cd62db4"),
+             tdef: TypeDef::bytes().infallible(),
+        }
+
+        hmac_sha1 {
+            args: func_args![value: "foo", key: "secret", algorithm: "SHA-1"],
+            want: Ok("9baed91be7f58b57c824b60da7cb262b2ecafbd2"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        hmac_sha224 {
+            args: func_args![value: "foo", key: "secret", algorithm: "SHA-224"],
+            want: Ok("21f62f59e04ee0d50b3546230207af9d2bf36ce2075eaa2dc50c0b37"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        hmac_sha384 {
+            args: func_args![value: "foo", key: "secret", algorithm: "SHA-384"],
+            want: Ok("0edb7068ecbf4de2c47b8819fd534333379f208f989c51018d03ee1155e4c0740a418ec220d4260eabcb2d090b16de6e"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        hmac_sha512 {
+            args: func_args![value: "foo", key: "secret", algorithm: "SHA-512"],
+            want: Ok("82df7103de8d82de45e01c45fe642b5d13c6c2b47decafebc009431c665c6fa5f3d1af4e978ea1bde91426622073ebeac61a3461efd467e0971c788bc8ebdbbe"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+    ];
+}
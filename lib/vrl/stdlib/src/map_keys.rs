@@ -0,0 +1,191 @@
+use vrl::prelude::*;
+
+fn map_keys<T>(value: Value, recursive: bool, runner: &mut T) -> Resolved
+where
+    T: FnMut(Value) -> Resolved,
+{
+    match value {
+        Value::Object(object) => map_object_keys(object, recursive, runner).map(Value::Object),
+        value => Err(value::Error::Expected {
+            got: value.kind(),
+            expected: Kind::object(Collection::any()),
+        }
+        .into()),
+    }
+}
+
+fn map_object_keys<T>(
+    object: BTreeMap<String, Value>,
+    recursive: bool,
+    runner: &mut T,
+) -> Result<BTreeMap<String, Value>>
+where
+    T: FnMut(Value) -> Resolved,
+{
+    object
+        .into_iter()
+        .map(|(key, value)| {
+            let key = runner(key.into())?.try_bytes_utf8_lossy()?.into_owned();
+            let value = if recursive {
+                map_nested_keys(value, runner)?
+            } else {
+                value
+            };
+
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// Map the keys of the objects nested within the value, including objects
+/// within arrays.
+fn map_nested_keys<T>(value: Value, runner: &mut T) -> Resolved
+where
+    T: FnMut(Value) -> Resolved,
+{
+    match value {
+        Value::Object(object) => map_object_keys(object, true, runner).map(Value::Object),
+        Value::Array(array) => array
+            .into_iter()
+            .map(|value| map_nested_keys(value, runner))
+            .collect::<Result<Vec<_>>>()
+            .map(Value::Array),
+        value => Ok(value),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MapKeys;
+
+impl Function for MapKeys {
+    fn identifier(&self) -> &'static str {
+        "map_keys"
+    }
+
+    fn summary(&self) -> &'static str {
+        "map the keys of an object using a closure"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {"
+            Maps the keys of an object, renaming each key to the string returned by the closure.
+
+            When `recursive` is enabled, the keys of the objects nested within the object,
+            including objects within arrays, are mapped too.
+
+            If the closure returns the same key for multiple fields, the field that comes last in
+            key order is kept.
+        "}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::OBJECT,
+                required: true,
+            },
+            Parameter {
+                keyword: "recursive",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "upcase keys",
+                source: r#"map_keys({ "a": 1, "b": 2 }) -> |key| { upcase(key) }"#,
+                result: Ok(r#"{ "A": 1, "B": 2 }"#),
+            },
+            Example {
+                title: "recursive",
+                source: r#"map_keys({ "a": { "b": [{ "c": 1 }] } }, recursive: true) -> |key| { upcase(key) }"#,
+                result: Ok(r#"{ "A": { "B": [{ "C": 1 }] } }"#),
+            },
+        ]
+    }
+
+    fn closure(&self) -> Option<closure::Definition> {
+        use closure::{Definition, Input, Output, Variable, VariableKind};
+
+        Some(Definition {
+            inputs: vec![Input {
+                parameter_keyword: "value",
+                kind: Kind::object(Collection::any()),
+                variables: vec![Variable {
+                    kind: VariableKind::Exact(Kind::bytes()),
+                }],
+                output: Output::Kind(Kind::bytes()),
+            }],
+        })
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let recursive = arguments.optional("recursive");
+        let closure = arguments.required_closure()?;
+
+        Ok(Box::new(MapKeysFn {
+            value,
+            recursive,
+            closure,
+        }))
+    }
+
+    fn call_by_vm(&self, ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let recursive = match args.optional("recursive") {
+            Some(recursive) => recursive.try_boolean()?,
+            None => false,
+        };
+        let closure = args.required_closure();
+
+        map_keys(value, recursive, &mut |key| closure.run(ctx, [key]))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MapKeysFn {
+    value: Box<dyn Expression>,
+    recursive: Option<Box<dyn Expression>>,
+    closure: closure::FunctionClosure,
+}
+
+impl Expression for MapKeysFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let recursive = match &self.recursive {
+            Some(expr) => expr.resolve(ctx)?.try_boolean()?,
+            None => false,
+        };
+
+        map_keys(value, recursive, &mut |key| self.closure.run(ctx, [key]))
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        // The keys are only known at runtime, so the kinds of the known fields
+        // apply to any field.
+        let mut object = self
+            .value
+            .type_def(state)
+            .as_object()
+            .cloned()
+            .unwrap_or_else(Collection::any);
+
+        if self.recursive.is_some() {
+            object = Collection::any();
+        } else {
+            object.anonymize();
+        }
+
+        TypeDef::object(object).infallible()
+    }
+}
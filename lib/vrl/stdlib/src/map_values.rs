@@ -0,0 +1,179 @@
+use vrl::prelude::*;
+
+fn map_values<T>(value: Value, recursive: bool, runner: &mut T) -> Resolved
+where
+    T: FnMut(Value) -> Resolved,
+{
+    match value {
+        Value::Object(object) => object
+            .into_iter()
+            .map(|(key, value)| Ok((key, map_value(value, recursive, runner)?)))
+            .collect::<Result<BTreeMap<_, _>>>()
+            .map(Value::Object),
+        Value::Array(array) => array
+            .into_iter()
+            .map(|value| map_value(value, recursive, runner))
+            .collect::<Result<Vec<_>>>()
+            .map(Value::Array),
+        value => Err(value::Error::Expected {
+            got: value.kind(),
+            expected: Kind::array(Collection::any()) | Kind::object(Collection::any()),
+        }
+        .into()),
+    }
+}
+
+/// Map a single value, mapping the values nested within it first when
+/// recursive.
+fn map_value<T>(value: Value, recursive: bool, runner: &mut T) -> Resolved
+where
+    T: FnMut(Value) -> Resolved,
+{
+    let value = match value {
+        value @ (Value::Object(_) | Value::Array(_)) if recursive => {
+            map_values(value, recursive, runner)?
+        }
+        value => value,
+    };
+
+    runner(value)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MapValues;
+
+impl Function for MapValues {
+    fn identifier(&self) -> &'static str {
+        "map_values"
+    }
+
+    fn summary(&self) -> &'static str {
+        "map the values of an object or array using a closure"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {"
+            Maps the values of an object or array, replacing each value with the result of the
+            closure.
+
+            When `recursive` is enabled, the values nested within objects and arrays are mapped
+            first, and the closure is then called with the mapped object or array.
+        "}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::OBJECT | kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "recursive",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "object",
+                source: r#"map_values({ "a": 1, "b": 2 }) -> |value| { (int(value) ?? 0) * 10 }"#,
+                result: Ok(r#"{ "a": 10, "b": 20 }"#),
+            },
+            Example {
+                title: "recursive",
+                source: r#"map_values({ "a": "foo", "b": { "c": "bar" } }, recursive: true) -> |value| { upcase(value) ?? value }"#,
+                result: Ok(r#"{ "a": "FOO", "b": { "c": "BAR" } }"#),
+            },
+        ]
+    }
+
+    fn closure(&self) -> Option<closure::Definition> {
+        use closure::{Definition, Input, Output, Variable, VariableKind};
+
+        // Nested values are passed to the closure when recursive, so the
+        // closure value can be of any kind.
+        let input = |kind| Input {
+            parameter_keyword: "value",
+            kind,
+            variables: vec![Variable {
+                kind: VariableKind::Exact(Kind::any()),
+            }],
+            output: Output::Any,
+        };
+
+        Some(Definition {
+            inputs: vec![
+                input(Kind::object(Collection::any())),
+                input(Kind::array(Collection::any())),
+            ],
+        })
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let recursive = arguments.optional("recursive");
+        let closure = arguments.required_closure()?;
+
+        Ok(Box::new(MapValuesFn {
+            value,
+            recursive,
+            closure,
+        }))
+    }
+
+    fn call_by_vm(&self, ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let recursive = match args.optional("recursive") {
+            Some(recursive) => recursive.try_boolean()?,
+            None => false,
+        };
+        let closure = args.required_closure();
+
+        map_values(value, recursive, &mut |value| closure.run(ctx, [value]))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MapValuesFn {
+    value: Box<dyn Expression>,
+    recursive: Option<Box<dyn Expression>>,
+    closure: closure::FunctionClosure,
+}
+
+impl Expression for MapValuesFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let recursive = match &self.recursive {
+            Some(expr) => expr.resolve(ctx)?.try_boolean()?,
+            None => false,
+        };
+
+        map_values(value, recursive, &mut |value| {
+            self.closure.run(ctx, [value])
+        })
+    }
+
+    fn type_def(&self, state: &state::Compiler) -> TypeDef {
+        let value = self.value.type_def(state);
+        let output = self.closure.type_def().kind().clone();
+
+        let mut kind = Kind::empty();
+        if value.contains_object() {
+            kind.add_object(Collection::from_unknown(output.clone()));
+        }
+        if value.contains_array() {
+            kind.add_array(Collection::from_unknown(output));
+        }
+
+        TypeDef::from(kind).infallible()
+    }
+}
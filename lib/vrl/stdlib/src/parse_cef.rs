@@ -0,0 +1,454 @@
+use std::collections::BTreeMap;
+
+use vrl::prelude::*;
+
+use crate::util::split_pipe_header;
+
+/// The names of the fields of the CEF header, after the `CEF:` prefix.
+const HEADER_FIELDS: [&str; 7] = [
+    "cefVersion",
+    "deviceVendor",
+    "deviceProduct",
+    "deviceVersion",
+    "deviceEventClassId",
+    "name",
+    "severity",
+];
+
+fn parse_cef(value: Value, translate_custom_fields: bool) -> Resolved {
+    let bytes = value.try_bytes()?;
+    let message = String::from_utf8_lossy(&bytes);
+
+    // CEF messages are usually sent with a syslog prefix, which is skipped.
+    let start = message
+        .find("CEF:")
+        .ok_or("unable to parse CEF message: missing CEF header")?;
+    let (mut header, extension) = parse_header(&message[start + 4..])
+        .ok_or("unable to parse CEF message: expected 7 header fields")?;
+
+    let mut log: BTreeMap<String, Value> = HEADER_FIELDS
+        .iter()
+        .map(|field| (*field).to_owned())
+        .zip(header.drain(..).map(Value::from))
+        .collect();
+
+    for (key, value) in parse_extension(extension)? {
+        // Extension fields named after a header field are prefixed, so the
+        // header field is kept.
+        let key = if HEADER_FIELDS.contains(&key.as_str()) {
+            format!("ext_{}", key)
+        } else {
+            key
+        };
+
+        log.insert(key, value.into());
+    }
+
+    if translate_custom_fields {
+        translate_custom_field_labels(&mut log);
+    }
+
+    Ok(log.into())
+}
+
+fn parse_header(input: &str) -> Option<(Vec<String>, &str)> {
+    split_pipe_header(input, HEADER_FIELDS.len()).or_else(|| {
+        // Messages without an extension don't always end the header with a
+        // pipe.
+        let (mut header, severity) = split_pipe_header(input, HEADER_FIELDS.len() - 1)?;
+        header.push(severity.trim_end().to_owned());
+
+        Some((header, ""))
+    })
+}
+
+/// Parse the space-separated `key=value` pairs of the extension.
+///
+/// Values can contain unescaped spaces, so a value ends where the next key
+/// starts.
+fn parse_extension(input: &str) -> Result<Vec<(String, String)>> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(vec![]);
+    }
+
+    // The start of each key, and the position of the `=` ending it.
+    let mut keys = vec![];
+    let mut escaped = false;
+    for (index, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '=' => {
+                let start = input[..index]
+                    .rfind(|c: char| !is_key_char(c))
+                    .map_or(0, |position| position + 1);
+
+                if start < index && (start == 0 || input.as_bytes()[start - 1] == b' ') {
+                    keys.push((start, index));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !matches!(keys.first(), Some((0, _))) {
+        return Err(r#"unable to parse CEF extension: expected "key=value" pairs"#.into());
+    }
+
+    Ok(keys
+        .iter()
+        .enumerate()
+        .map(|(position, &(start, equals))| {
+            let end = keys
+                .get(position + 1)
+                .map_or(input.len(), |&(next, _)| next);
+            let key = input[start..equals].to_owned();
+            let value = unescape_value(input[equals + 1..end].trim_end_matches(' '));
+
+            (key, value)
+        })
+        .collect())
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.'
+}
+
+fn unescape_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c @ ('=' | '\\')) => unescaped.push(c),
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Replace the custom fields of the extension, such as `cs1`, with fields
+/// named after their labels, such as `cs1Label`.
+///
+/// Fields whose label names an existing field, such as a header field, are
+/// kept as is.
+fn translate_custom_field_labels(log: &mut BTreeMap<String, Value>) {
+    let labels = log
+        .keys()
+        .filter(|key| !HEADER_FIELDS.contains(&key.as_str()))
+        .filter_map(|key| {
+            let field = key.strip_suffix("Label")?;
+            log.contains_key(field)
+                .then(|| (key.clone(), field.to_owned()))
+        })
+        .collect::<Vec<_>>();
+
+    for (label, field) in labels {
+        let name = match log.get(&label) {
+            Some(Value::Bytes(name)) => String::from_utf8_lossy(name).into_owned(),
+            _ => continue,
+        };
+        if log.contains_key(&name) {
+            continue;
+        }
+
+        log.remove(&label);
+        if let Some(value) = log.remove(&field) {
+            log.insert(name, value);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseCef;
+
+impl Function for ParseCef {
+    fn identifier(&self) -> &'static str {
+        "parse_cef"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "translate_custom_fields",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "valid",
+                source: r#"parse_cef!("CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232")"#,
+                result: Ok(indoc! { r#"{
+                    "cefVersion": "0",
+                    "deviceEventClassId": "100",
+                    "deviceProduct": "threatmanager",
+                    "deviceVendor": "Security",
+                    "deviceVersion": "1.0",
+                    "dst": "2.1.2.2",
+                    "name": "worm successfully stopped",
+                    "severity": "10",
+                    "spt": "1232",
+                    "src": "10.0.0.1"
+                }"#}),
+            },
+            Example {
+                title: "translate custom fields",
+                source: r#"parse_cef!("CEF:0|Dev|firewall|2.2|1|Connection denied|5|cs1Label=zone cs1=dmz", translate_custom_fields: true)"#,
+                result: Ok(indoc! { r#"{
+                    "cefVersion": "0",
+                    "deviceEventClassId": "1",
+                    "deviceProduct": "firewall",
+                    "deviceVendor": "Dev",
+                    "deviceVersion": "2.2",
+                    "name": "Connection denied",
+                    "severity": "5",
+                    "zone": "dmz"
+                }"#}),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let translate_custom_fields = arguments.optional("translate_custom_fields");
+
+        Ok(Box::new(ParseCefFn {
+            value,
+            translate_custom_fields,
+        }))
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let translate_custom_fields = match args.optional("translate_custom_fields") {
+            Some(value) => value.try_boolean()?,
+            None => false,
+        };
+
+        parse_cef(value, translate_custom_fields)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseCefFn {
+    value: Box<dyn Expression>,
+    translate_custom_fields: Option<Box<dyn Expression>>,
+}
+
+impl Expression for ParseCefFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let translate_custom_fields = match &self.translate_custom_fields {
+            Some(expr) => expr.resolve(ctx)?.try_boolean()?,
+            None => false,
+        };
+
+        parse_cef(value, translate_custom_fields)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::object(inner_kind()).fallible()
+    }
+}
+
+fn inner_kind() -> Collection<Field> {
+    let mut collection = Collection::from(
+        HEADER_FIELDS
+            .iter()
+            .map(|field| ((*field).into(), Kind::bytes()))
+            .collect::<BTreeMap<_, _>>(),
+    );
+    collection.set_unknown(Kind::bytes());
+    collection
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::btreemap;
+
+    use super::*;
+
+    test_function![
+        parse_cef => ParseCef;
+
+        header_only {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|"],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "Security",
+                "deviceProduct" => "threatmanager",
+                "deviceVersion" => "1.0",
+                "deviceEventClassId" => "100",
+                "name" => "worm successfully stopped",
+                "severity" => "10",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        header_without_trailing_pipe {
+            args: func_args![value: "CEF:1|Security|threatmanager|1.0|100|worm successfully stopped|High"],
+            want: Ok(btreemap! {
+                "cefVersion" => "1",
+                "deviceVendor" => "Security",
+                "deviceProduct" => "threatmanager",
+                "deviceVersion" => "1.0",
+                "deviceEventClassId" => "100",
+                "name" => "worm successfully stopped",
+                "severity" => "High",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        extension {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 msg=Detected a threat. No action needed. spt=1232"],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "Security",
+                "deviceProduct" => "threatmanager",
+                "deviceVersion" => "1.0",
+                "deviceEventClassId" => "100",
+                "name" => "worm successfully stopped",
+                "severity" => "10",
+                "src" => "10.0.0.1",
+                "dst" => "2.1.2.2",
+                "msg" => "Detected a threat. No action needed.",
+                "spt" => "1232",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        escapes {
+            args: func_args![value: r#"CEF:0|Vendor\|Inc|prod\\uct|1.0|100|name|10|msg=a\=b c\\d\nnext path=C:\Windows"#],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "Vendor|Inc",
+                "deviceProduct" => r#"prod\uct"#,
+                "deviceVersion" => "1.0",
+                "deviceEventClassId" => "100",
+                "name" => "name",
+                "severity" => "10",
+                "msg" => "a=b c\\d\nnext",
+                "path" => r#"C:\Windows"#,
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        syslog_prefix {
+            args: func_args![value: "<134>Sep 19 08:26:10 host CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1"],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "Security",
+                "deviceProduct" => "threatmanager",
+                "deviceVersion" => "1.0",
+                "deviceEventClassId" => "100",
+                "name" => "worm successfully stopped",
+                "severity" => "10",
+                "src" => "10.0.0.1",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        translate_custom_fields {
+            args: func_args![
+                value: "CEF:0|Dev|firewall|2.2|1|Connection denied|5|cs1Label=zone cs1=dmz cn1Label=rule cn1=42 cs2=unlabelled",
+                translate_custom_fields: true,
+            ],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "Dev",
+                "deviceProduct" => "firewall",
+                "deviceVersion" => "2.2",
+                "deviceEventClassId" => "1",
+                "name" => "Connection denied",
+                "severity" => "5",
+                "zone" => "dmz",
+                "rule" => "42",
+                "cs2" => "unlabelled",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        translate_custom_fields_keeps_existing_fields {
+            args: func_args![
+                value: "CEF:0|Dev|firewall|2.2|1|Connection denied|5|cs1Label=severity cs1=low cs2Label=src cs2=dmz src=10.0.0.1",
+                translate_custom_fields: true,
+            ],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "Dev",
+                "deviceProduct" => "firewall",
+                "deviceVersion" => "2.2",
+                "deviceEventClassId" => "1",
+                "name" => "Connection denied",
+                "severity" => "5",
+                "cs1Label" => "severity",
+                "cs1" => "low",
+                "cs2Label" => "src",
+                "cs2" => "dmz",
+                "src" => "10.0.0.1",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        extension_header_conflict {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 name=spoofed"],
+            want: Ok(btreemap! {
+                "cefVersion" => "0",
+                "deviceVendor" => "Security",
+                "deviceProduct" => "threatmanager",
+                "deviceVersion" => "1.0",
+                "deviceEventClassId" => "100",
+                "name" => "worm successfully stopped",
+                "severity" => "10",
+                "src" => "10.0.0.1",
+                "ext_name" => "spoofed",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        missing_header {
+            args: func_args![value: "Sep 19 08:26:10 host hello world"],
+            want: Err("unable to parse CEF message: missing CEF header"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        incomplete_header {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0"],
+            want: Err("unable to parse CEF message: expected 7 header fields"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        invalid_extension {
+            args: func_args![value: "CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|no pairs here"],
+            want: Err(r#"unable to parse CEF extension: expected "key=value" pairs"#),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+    ];
+}
@@ -0,0 +1,285 @@
+use std::collections::BTreeMap;
+
+use vrl::prelude::*;
+
+use crate::util::split_pipe_header;
+
+/// The names of the fields of the LEEF header, after the `LEEF:` prefix.
+const HEADER_FIELDS: [&str; 5] = [
+    "leefVersion",
+    "vendor",
+    "productName",
+    "productVersion",
+    "eventId",
+];
+
+fn parse_leef(value: Value) -> Resolved {
+    let bytes = value.try_bytes()?;
+    let message = String::from_utf8_lossy(&bytes);
+
+    // LEEF messages are usually sent with a syslog prefix, which is skipped.
+    let start = message
+        .find("LEEF:")
+        .ok_or("unable to parse LEEF message: missing LEEF header")?;
+    let (header, mut attributes) = split_pipe_header(&message[start + 5..], HEADER_FIELDS.len())
+        .ok_or("unable to parse LEEF message: expected 5 header fields")?;
+
+    // LEEF 2.0 adds an optional header field holding the attribute delimiter,
+    // which is a tab otherwise.
+    let mut delimiter = '\t';
+    if header[0].starts_with('2') {
+        if let Some((field, rest)) = attributes
+            .split_once('|')
+            .filter(|(field, _)| field.len() <= 6 && !field.contains('='))
+        {
+            delimiter = parse_delimiter(field)?;
+            attributes = rest;
+        }
+    }
+
+    let mut log: BTreeMap<String, Value> = HEADER_FIELDS
+        .iter()
+        .map(|field| (*field).to_owned())
+        .zip(header.into_iter().map(Value::from))
+        .collect();
+
+    for attribute in attributes
+        .trim_end_matches(|c| c == '\r' || c == '\n')
+        .split(delimiter)
+        .filter(|attribute| !attribute.is_empty())
+    {
+        let (key, value) = attribute.split_once('=').ok_or_else(|| {
+            format!(
+                r#"unable to parse LEEF attribute "{}": expected "key=value""#,
+                attribute
+            )
+        })?;
+
+        log.insert(key.to_owned(), value.into());
+    }
+
+    Ok(log.into())
+}
+
+/// Parse the delimiter of a LEEF 2.0 header, which is either a single
+/// character, or its hexadecimal code prefixed with `x` or `0x`.
+fn parse_delimiter(field: &str) -> std::result::Result<char, String> {
+    let mut chars = field.chars();
+
+    match (chars.next(), chars.next()) {
+        (None, _) => Ok('\t'),
+        (Some(c), None) => Ok(c),
+        _ => field
+            .strip_prefix("0x")
+            .or_else(|| field.strip_prefix("0X"))
+            .or_else(|| field.strip_prefix('x'))
+            .or_else(|| field.strip_prefix('X'))
+            .and_then(|code| u32::from_str_radix(code, 16).ok())
+            .and_then(char::from_u32)
+            .ok_or_else(|| format!(r#"unable to parse LEEF delimiter "{}""#, field)),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseLeef;
+
+impl Function for ParseLeef {
+    fn identifier(&self) -> &'static str {
+        "parse_leef"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "LEEF 1.0",
+                source: r#"parse_leef!("LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5")"#,
+                result: Ok(indoc! { r#"{
+                    "dst": "172.50.123.1",
+                    "eventId": "15345",
+                    "leefVersion": "1.0",
+                    "productName": "MSExchange",
+                    "productVersion": "4.0 SP1",
+                    "sev": "5",
+                    "src": "192.0.2.0",
+                    "vendor": "Microsoft"
+                }"#}),
+            },
+            Example {
+                title: "LEEF 2.0",
+                source: r#"parse_leef!("LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^sev=5")"#,
+                result: Ok(indoc! { r#"{
+                    "dst": "10.0.0.5",
+                    "eventId": "41",
+                    "leefVersion": "2.0",
+                    "productName": "StealthWatch",
+                    "productVersion": "1.0",
+                    "sev": "5",
+                    "src": "10.0.1.8",
+                    "vendor": "Lancope"
+                }"#}),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(ParseLeefFn { value }))
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+
+        parse_leef(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseLeefFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for ParseLeefFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        parse_leef(value)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::object(inner_kind()).fallible()
+    }
+}
+
+fn inner_kind() -> Collection<Field> {
+    let mut collection = Collection::from(
+        HEADER_FIELDS
+            .iter()
+            .map(|field| ((*field).into(), Kind::bytes()))
+            .collect::<BTreeMap<_, _>>(),
+    );
+    collection.set_unknown(Kind::bytes());
+    collection
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::btreemap;
+
+    use super::*;
+
+    test_function![
+        parse_leef => ParseLeef;
+
+        leef_1 {
+            args: func_args![value: "LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5\tcat=anomaly\tmsg=the nature of the anomaly\n"],
+            want: Ok(btreemap! {
+                "leefVersion" => "1.0",
+                "vendor" => "Microsoft",
+                "productName" => "MSExchange",
+                "productVersion" => "4.0 SP1",
+                "eventId" => "15345",
+                "src" => "192.0.2.0",
+                "dst" => "172.50.123.1",
+                "sev" => "5",
+                "cat" => "anomaly",
+                "msg" => "the nature of the anomaly",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        leef_2_delimiter {
+            args: func_args![value: "LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^url=https://example.com/?a=b"],
+            want: Ok(btreemap! {
+                "leefVersion" => "2.0",
+                "vendor" => "Lancope",
+                "productName" => "StealthWatch",
+                "productVersion" => "1.0",
+                "eventId" => "41",
+                "src" => "10.0.1.8",
+                "dst" => "10.0.0.5",
+                "url" => "https://example.com/?a=b",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        leef_2_hex_delimiter {
+            args: func_args![value: "LEEF:2.0|Lancope|StealthWatch|1.0|41|0x5E|src=10.0.1.8^dst=10.0.0.5"],
+            want: Ok(btreemap! {
+                "leefVersion" => "2.0",
+                "vendor" => "Lancope",
+                "productName" => "StealthWatch",
+                "productVersion" => "1.0",
+                "eventId" => "41",
+                "src" => "10.0.1.8",
+                "dst" => "10.0.0.5",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        leef_2_default_delimiter {
+            args: func_args![value: "LEEF:2.0|Lancope|StealthWatch|1.0|41|src=10.0.1.8\tdst=10.0.0.5"],
+            want: Ok(btreemap! {
+                "leefVersion" => "2.0",
+                "vendor" => "Lancope",
+                "productName" => "StealthWatch",
+                "productVersion" => "1.0",
+                "eventId" => "41",
+                "src" => "10.0.1.8",
+                "dst" => "10.0.0.5",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        syslog_prefix {
+            args: func_args![value: "<13>Jan 18 11:07:53 192.168.1.1 LEEF:1.0|QRadar|QRM|1.0|NEW_PORT_DISCOVERED|src=172.5.6.67\tdst=172.50.123.1"],
+            want: Ok(btreemap! {
+                "leefVersion" => "1.0",
+                "vendor" => "QRadar",
+                "productName" => "QRM",
+                "productVersion" => "1.0",
+                "eventId" => "NEW_PORT_DISCOVERED",
+                "src" => "172.5.6.67",
+                "dst" => "172.50.123.1",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        missing_header {
+            args: func_args![value: "Jan 18 11:07:53 host hello world"],
+            want: Err("unable to parse LEEF message: missing LEEF header"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        incomplete_header {
+            args: func_args![value: "LEEF:1.0|QRadar|QRM"],
+            want: Err("unable to parse LEEF message: expected 5 header fields"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        invalid_delimiter {
+            args: func_args![value: "LEEF:2.0|Lancope|StealthWatch|1.0|41|xZZ|src=10.0.1.8"],
+            want: Err(r#"unable to parse LEEF delimiter "xZZ""#),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        invalid_attribute {
+            args: func_args![value: "LEEF:1.0|QRadar|QRM|1.0|42|src=172.5.6.67\tgarbage"],
+            want: Err(r#"unable to parse LEEF attribute "garbage": expected "key=value""#),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+    ];
+}
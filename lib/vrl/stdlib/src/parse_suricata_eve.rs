@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use vrl::prelude::*;
+
+/// The format of the timestamps in EVE events, such as
+/// `2009-11-24T21:27:09.534255+0100`.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f%z";
+
+const INTEGER_FIELDS: [&str; 5] = ["flow_id", "pcap_cnt", "tx_id", "src_port", "dest_port"];
+const STRING_FIELDS: [&str; 5] = ["src_ip", "dest_ip", "proto", "app_proto", "in_iface"];
+const ALERT_INTEGER_FIELDS: [&str; 4] = ["gid", "signature_id", "rev", "severity"];
+const ALERT_STRING_FIELDS: [&str; 3] = ["action", "signature", "category"];
+const FLOW_INTEGER_FIELDS: [&str; 4] = [
+    "pkts_toserver",
+    "pkts_toclient",
+    "bytes_toserver",
+    "bytes_toclient",
+];
+const FLOW_TIMESTAMP_FIELDS: [&str; 2] = ["start", "end"];
+
+fn parse_suricata_eve(value: Value) -> Resolved {
+    let bytes = value.try_bytes()?;
+    let mut event = serde_json::from_slice::<'_, BTreeMap<String, Value>>(&bytes)
+        .map_err(|e| format!("unable to parse Suricata EVE event: {}", e))?;
+
+    match event.get("event_type") {
+        Some(Value::Bytes(_)) => {}
+        Some(_) => return Err(invalid_field("", "event_type", "a string")),
+        None => return Err("unable to parse Suricata EVE event: missing event_type".into()),
+    }
+
+    match event.get_mut("timestamp") {
+        Some(timestamp) => convert_timestamp(timestamp, "", "timestamp")?,
+        None => return Err("unable to parse Suricata EVE event: missing timestamp".into()),
+    }
+
+    check_fields(&event, "", &INTEGER_FIELDS, "an integer", is_integer)?;
+    check_fields(&event, "", &STRING_FIELDS, "a string", is_string)?;
+
+    match event.get_mut("alert") {
+        Some(Value::Object(alert)) => {
+            check_fields(
+                alert,
+                "alert.",
+                &ALERT_INTEGER_FIELDS,
+                "an integer",
+                is_integer,
+            )?;
+            check_fields(alert, "alert.", &ALERT_STRING_FIELDS, "a string", is_string)?;
+        }
+        Some(_) => return Err(invalid_field("", "alert", "an object")),
+        None => {}
+    }
+
+    match event.get_mut("flow") {
+        Some(Value::Object(flow)) => {
+            check_fields(
+                flow,
+                "flow.",
+                &FLOW_INTEGER_FIELDS,
+                "an integer",
+                is_integer,
+            )?;
+            for field in &FLOW_TIMESTAMP_FIELDS {
+                if let Some(timestamp) = flow.get_mut(*field) {
+                    convert_timestamp(timestamp, "flow.", field)?;
+                }
+            }
+        }
+        Some(_) => return Err(invalid_field("", "flow", "an object")),
+        None => {}
+    }
+
+    Ok(event.into())
+}
+
+fn convert_timestamp(value: &mut Value, prefix: &str, field: &str) -> Result<()> {
+    let timestamp = match value {
+        Value::Bytes(bytes) => {
+            DateTime::parse_from_str(&String::from_utf8_lossy(bytes), TIMESTAMP_FORMAT).ok()
+        }
+        _ => None,
+    }
+    .ok_or_else(|| invalid_field(prefix, field, "a timestamp"))?;
+
+    *value = Value::Timestamp(timestamp.with_timezone(&Utc));
+
+    Ok(())
+}
+
+fn check_fields(
+    object: &BTreeMap<String, Value>,
+    prefix: &str,
+    fields: &[&str],
+    expected: &str,
+    matches: fn(&Value) -> bool,
+) -> Result<()> {
+    for field in fields {
+        match object.get(*field) {
+            Some(value) if !matches(value) => return Err(invalid_field(prefix, field, expected)),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn is_integer(value: &Value) -> bool {
+    matches!(value, Value::Integer(_))
+}
+
+fn is_string(value: &Value) -> bool {
+    matches!(value, Value::Bytes(_))
+}
+
+fn invalid_field(prefix: &str, field: &str, expected: &str) -> ExpressionError {
+    format!(
+        r#"unable to parse Suricata EVE event: field "{}{}" must be {}"#,
+        prefix, field, expected
+    )
+    .into()
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseSuricataEve;
+
+impl Function for ParseSuricataEve {
+    fn identifier(&self) -> &'static str {
+        "parse_suricata_eve"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "alert event",
+            source: r#"parse_suricata_eve!(s'{"timestamp":"2009-11-24T21:27:09.534255+0100","event_type":"alert","src_ip":"192.168.2.7","src_port":1041,"dest_ip":"x.x.250.50","dest_port":80,"proto":"TCP","alert":{"action":"allowed","gid":1,"signature_id":2001999,"rev":9,"signature":"ET MALWARE BTGrab.com Spyware Downloading Ads","category":"A Network Trojan was detected","severity":1}}')"#,
+            result: Ok(indoc! { r#"{
+                "alert": {
+                    "action": "allowed",
+                    "category": "A Network Trojan was detected",
+                    "gid": 1,
+                    "rev": 9,
+                    "severity": 1,
+                    "signature": "ET MALWARE BTGrab.com Spyware Downloading Ads",
+                    "signature_id": 2001999
+                },
+                "dest_ip": "x.x.250.50",
+                "dest_port": 80,
+                "event_type": "alert",
+                "proto": "TCP",
+                "src_ip": "192.168.2.7",
+                "src_port": 1041,
+                "timestamp": "2009-11-24T20:27:09.534255Z"
+            }"#}),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(ParseSuricataEveFn { value }))
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+
+        parse_suricata_eve(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseSuricataEveFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for ParseSuricataEveFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        parse_suricata_eve(value)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::object(inner_kind()).fallible()
+    }
+}
+
+/// The kind of the fields that aren't typed by the parser.
+fn json_kind() -> Kind {
+    Kind::null()
+        | Kind::bytes()
+        | Kind::integer()
+        | Kind::float()
+        | Kind::boolean()
+        | Kind::array(Collection::any())
+        | Kind::object(Collection::any())
+}
+
+fn object_kind<'a>(fields: impl IntoIterator<Item = (&'a str, Kind)>) -> Collection<Field> {
+    let mut collection = Collection::from(
+        fields
+            .into_iter()
+            .map(|(field, kind)| (field.into(), kind))
+            .collect::<BTreeMap<_, _>>(),
+    );
+    collection.set_unknown(json_kind());
+    collection
+}
+
+fn inner_kind() -> Collection<Field> {
+    let alert = object_kind(
+        ALERT_INTEGER_FIELDS
+            .iter()
+            .map(|field| (*field, Kind::integer().or_null()))
+            .chain(
+                ALERT_STRING_FIELDS
+                    .iter()
+                    .map(|field| (*field, Kind::bytes().or_null())),
+            ),
+    );
+
+    let flow = object_kind(
+        FLOW_INTEGER_FIELDS
+            .iter()
+            .map(|field| (*field, Kind::integer().or_null()))
+            .chain(
+                FLOW_TIMESTAMP_FIELDS
+                    .iter()
+                    .map(|field| (*field, Kind::timestamp().or_null())),
+            ),
+    );
+
+    object_kind(
+        INTEGER_FIELDS
+            .iter()
+            .map(|field| (*field, Kind::integer().or_null()))
+            .chain(
+                STRING_FIELDS
+                    .iter()
+                    .map(|field| (*field, Kind::bytes().or_null())),
+            )
+            .chain(vec![
+                ("timestamp", Kind::timestamp()),
+                ("event_type", Kind::bytes()),
+                ("alert", Kind::object(alert).or_null()),
+                ("flow", Kind::object(flow).or_null()),
+            ]),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    test_function![
+        parse_suricata_eve => ParseSuricataEve;
+
+        alert {
+            args: func_args![value: r#"{"timestamp":"2009-11-24T21:27:09.534255+0100","event_type":"alert","src_ip":"192.168.2.7","src_port":1041,"dest_ip":"x.x.250.50","dest_port":80,"proto":"TCP","alert":{"action":"allowed","gid":1,"signature_id":2001999,"rev":9,"signature":"ET MALWARE BTGrab.com Spyware Downloading Ads","category":"A Network Trojan was detected","severity":1}}"#],
+            want: Ok(value!({
+                timestamp: (Utc.ymd(2009, 11, 24).and_hms_micro(20, 27, 9, 534255)),
+                event_type: "alert",
+                src_ip: "192.168.2.7",
+                src_port: 1041,
+                dest_ip: "x.x.250.50",
+                dest_port: 80,
+                proto: "TCP",
+                alert: {
+                    action: "allowed",
+                    gid: 1,
+                    signature_id: 2001999,
+                    rev: 9,
+                    signature: "ET MALWARE BTGrab.com Spyware Downloading Ads",
+                    category: "A Network Trojan was detected",
+                    severity: 1,
+                },
+            })),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        flow {
+            args: func_args![value: r#"{"timestamp":"2021-02-02T18:27:13.021213+0000","flow_id":1176428925893431,"event_type":"flow","proto":"UDP","flow":{"pkts_toserver":1,"pkts_toclient":1,"bytes_toserver":76,"bytes_toclient":92,"start":"2021-02-02T18:26:42.995741+0000","end":"2021-02-02T18:26:43.000186+0000","state":"established"}}"#],
+            want: Ok(value!({
+                timestamp: (Utc.ymd(2021, 2, 2).and_hms_micro(18, 27, 13, 21213)),
+                flow_id: 1176428925893431,
+                event_type: "flow",
+                proto: "UDP",
+                flow: {
+                    pkts_toserver: 1,
+                    pkts_toclient: 1,
+                    bytes_toserver: 76,
+                    bytes_toclient: 92,
+                    start: (Utc.ymd(2021, 2, 2).and_hms_micro(18, 26, 42, 995741)),
+                    end: (Utc.ymd(2021, 2, 2).and_hms_micro(18, 26, 43, 186)),
+                    state: "established",
+                },
+            })),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        invalid_json {
+            args: func_args![value: r#"{"timestamp":"#],
+            want: Err("unable to parse Suricata EVE event: EOF while parsing a value at line 1 column 13"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        missing_event_type {
+            args: func_args![value: r#"{"timestamp":"2021-02-02T18:27:13.021213+0000"}"#],
+            want: Err("unable to parse Suricata EVE event: missing event_type"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        invalid_timestamp {
+            args: func_args![value: r#"{"timestamp":"yesterday","event_type":"dns"}"#],
+            want: Err(r#"unable to parse Suricata EVE event: field "timestamp" must be a timestamp"#),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        invalid_field_type {
+            args: func_args![value: r#"{"timestamp":"2021-02-02T18:27:13.021213+0000","event_type":"alert","alert":{"signature_id":"2001999"}}"#],
+            want: Err(r#"unable to parse Suricata EVE event: field "alert.signature_id" must be an integer"#),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+    ];
+}
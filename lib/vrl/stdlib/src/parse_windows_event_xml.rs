@@ -0,0 +1,400 @@
+use std::collections::{btree_map::Entry, BTreeMap};
+
+use chrono::{DateTime, Utc};
+use roxmltree::{Document, Node};
+use vrl::prelude::*;
+
+/// The elements of `System` holding integers, and the fields they are returned
+/// as.
+const INTEGER_ELEMENTS: [(&str, &str); 6] = [
+    ("EventID", "event_id"),
+    ("Version", "version"),
+    ("Level", "level"),
+    ("Task", "task"),
+    ("Opcode", "opcode"),
+    ("EventRecordID", "record_id"),
+];
+
+/// The elements of `System` holding strings, and the fields they are returned
+/// as.
+const STRING_ELEMENTS: [(&str, &str); 3] = [
+    ("Keywords", "keywords"),
+    ("Channel", "channel"),
+    ("Computer", "computer_name"),
+];
+
+/// The attributes of the elements of `System` holding integers, and the fields
+/// they are returned as.
+const INTEGER_ATTRIBUTES: [(&str, &str, &str); 2] = [
+    ("Execution", "ProcessID", "process_id"),
+    ("Execution", "ThreadID", "thread_id"),
+];
+
+/// The attributes of the elements of `System` holding strings, and the fields
+/// they are returned as.
+const STRING_ATTRIBUTES: [(&str, &str, &str); 4] = [
+    ("Provider", "Name", "provider_name"),
+    ("Provider", "Guid", "provider_guid"),
+    ("Correlation", "ActivityID", "activity_id"),
+    ("Security", "UserID", "user_id"),
+];
+
+fn parse_windows_event_xml(value: Value) -> Resolved {
+    let xml = value.try_bytes_utf8_lossy()?;
+    let document =
+        Document::parse(&xml).map_err(|e| format!("unable to parse Windows event: {}", e))?;
+
+    let event = document.root_element();
+    if event.tag_name().name() != "Event" {
+        return Err(r#"unable to parse Windows event: expected an "Event" root element"#.into());
+    }
+    let system = child(event, "System")
+        .ok_or(r#"unable to parse Windows event: missing "System" element"#)?;
+
+    let mut log = BTreeMap::new();
+
+    for (element, field) in &INTEGER_ELEMENTS {
+        if let Some(node) = child(system, element) {
+            log.insert((*field).to_owned(), parse_integer(element, text(node))?);
+        }
+    }
+
+    if !log.contains_key("event_id") {
+        return Err(r#"unable to parse Windows event: missing "EventID" element"#.into());
+    }
+
+    for (element, field) in &STRING_ELEMENTS {
+        if let Some(node) = child(system, element) {
+            log.insert((*field).to_owned(), text(node).into());
+        }
+    }
+
+    for (element, attribute, field) in &INTEGER_ATTRIBUTES {
+        if let Some(value) = child(system, element).and_then(|node| node.attribute(*attribute)) {
+            log.insert((*field).to_owned(), parse_integer(attribute, value)?);
+        }
+    }
+
+    for (element, attribute, field) in &STRING_ATTRIBUTES {
+        if let Some(value) = child(system, element).and_then(|node| node.attribute(*attribute)) {
+            log.insert((*field).to_owned(), value.into());
+        }
+    }
+
+    if let Some(time) = child(system, "TimeCreated").and_then(|node| node.attribute("SystemTime")) {
+        let timestamp = DateTime::parse_from_rfc3339(time)
+            .map_err(|_| r#"unable to parse Windows event: invalid "SystemTime" timestamp"#)?;
+        log.insert(
+            "time_created".to_owned(),
+            Value::Timestamp(timestamp.with_timezone(&Utc)),
+        );
+    }
+
+    if let Some(node) = child(event, "EventData") {
+        log.insert("event_data".to_owned(), event_data(node));
+    }
+
+    if let Some(node) = child(event, "UserData") {
+        log.insert("user_data".to_owned(), element_value(node));
+    }
+
+    Ok(log.into())
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.is_element() && child.tag_name().name() == name)
+}
+
+fn text<'a>(node: Node<'a, '_>) -> &'a str {
+    node.text().unwrap_or_default().trim()
+}
+
+fn parse_integer(name: &str, value: &str) -> Resolved {
+    value.parse::<i64>().map(Value::from).map_err(|_| {
+        format!(
+            r#"unable to parse Windows event: "{}" must be an integer"#,
+            name
+        )
+        .into()
+    })
+}
+
+/// The `Data` elements of the event data, by name.
+///
+/// Unnamed elements are named after their position, starting from `param1`,
+/// the way they are referred to by the messages of the events. The `Binary`
+/// element is returned as the `binary` field.
+fn event_data(node: Node) -> Value {
+    let mut data = BTreeMap::new();
+    let mut position = 0;
+
+    for element in node.children().filter(Node::is_element) {
+        let value = Value::from(element.text().unwrap_or_default());
+
+        match element.tag_name().name() {
+            "Data" => {
+                position += 1;
+                let name = element
+                    .attribute("Name")
+                    .map_or_else(|| format!("param{}", position), ToOwned::to_owned);
+                data.insert(name, value);
+            }
+            "Binary" => {
+                data.insert("binary".to_owned(), value);
+            }
+            _ => {}
+        }
+    }
+
+    data.into()
+}
+
+/// The children of the element, by name, or its text if it has none.
+///
+/// Children sharing a name are returned as an array.
+fn element_value(node: Node) -> Value {
+    let mut children = node.children().filter(Node::is_element).peekable();
+    if children.peek().is_none() {
+        return node.text().unwrap_or_default().into();
+    }
+
+    let mut object = BTreeMap::<String, Value>::new();
+    for child in children {
+        let value = element_value(child);
+
+        match object.entry(child.tag_name().name().to_owned()) {
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            }
+            Entry::Occupied(mut entry) => match entry.get_mut() {
+                Value::Array(values) => values.push(value),
+                existing => *existing = Value::Array(vec![existing.clone(), value]),
+            },
+        }
+    }
+
+    object.into()
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseWindowsEventXml;
+
+impl Function for ParseWindowsEventXml {
+    fn identifier(&self) -> &'static str {
+        "parse_windows_event_xml"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "valid",
+            source: r#"parse_windows_event_xml!(s'<Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event"><System><Provider Name="Microsoft-Windows-Security-Auditing"/><EventID>4625</EventID><EventRecordID>42</EventRecordID><Channel>Security</Channel><Computer>dc01.example.com</Computer></System><EventData><Data Name="TargetUserName">jane</Data><Data Name="IpAddress">10.0.0.1</Data></EventData></Event>')"#,
+            result: Ok(indoc! { r#"{
+                "channel": "Security",
+                "computer_name": "dc01.example.com",
+                "event_data": {
+                    "IpAddress": "10.0.0.1",
+                    "TargetUserName": "jane"
+                },
+                "event_id": 4625,
+                "provider_name": "Microsoft-Windows-Security-Auditing",
+                "record_id": 42
+            }"#}),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(ParseWindowsEventXmlFn { value }))
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+
+        parse_windows_event_xml(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseWindowsEventXmlFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for ParseWindowsEventXmlFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        parse_windows_event_xml(value)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::object(inner_kind()).fallible()
+    }
+}
+
+fn inner_kind() -> Collection<Field> {
+    let fields = INTEGER_ELEMENTS
+        .iter()
+        .map(|(_, field)| (*field, Kind::integer().or_null()))
+        .chain(
+            STRING_ELEMENTS
+                .iter()
+                .map(|(_, field)| (*field, Kind::bytes().or_null())),
+        )
+        .chain(
+            INTEGER_ATTRIBUTES
+                .iter()
+                .map(|(_, _, field)| (*field, Kind::integer().or_null())),
+        )
+        .chain(
+            STRING_ATTRIBUTES
+                .iter()
+                .map(|(_, _, field)| (*field, Kind::bytes().or_null())),
+        )
+        .chain(vec![
+            ("event_id", Kind::integer()),
+            ("time_created", Kind::timestamp().or_null()),
+            (
+                "event_data",
+                Kind::object(Collection::from_unknown(Kind::bytes())).or_null(),
+            ),
+            ("user_data", Kind::object(Collection::any()).or_null()),
+        ])
+        .map(|(field, kind)| (field.into(), kind))
+        .collect::<BTreeMap<_, _>>();
+
+    Collection::from(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    test_function![
+        parse_windows_event_xml => ParseWindowsEventXml;
+
+        security_event {
+            args: func_args![value: r#"<Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event">
+                <System>
+                    <Provider Name="Microsoft-Windows-Security-Auditing" Guid="{54849625-5478-4994-a5ba-3e3b0328c30d}"/>
+                    <EventID>4624</EventID>
+                    <Version>2</Version>
+                    <Level>0</Level>
+                    <Task>12544</Task>
+                    <Opcode>0</Opcode>
+                    <Keywords>0x8020000000000000</Keywords>
+                    <TimeCreated SystemTime="2022-03-01T12:00:00.000000000Z"/>
+                    <EventRecordID>123</EventRecordID>
+                    <Correlation ActivityID="{a1b2c3d4-0000-0000-0000-000000000000}"/>
+                    <Execution ProcessID="4" ThreadID="5"/>
+                    <Channel>Security</Channel>
+                    <Computer>dc01.example.com</Computer>
+                    <Security/>
+                </System>
+                <EventData>
+                    <Data Name="SubjectUserSid">S-1-5-18</Data>
+                    <Data Name="TargetUserName">jane</Data>
+                    <Data Name="IpAddress">-</Data>
+                </EventData>
+            </Event>"#],
+            want: Ok(value!({
+                provider_name: "Microsoft-Windows-Security-Auditing",
+                provider_guid: "{54849625-5478-4994-a5ba-3e3b0328c30d}",
+                event_id: 4624,
+                version: 2,
+                level: 0,
+                task: 12544,
+                opcode: 0,
+                keywords: "0x8020000000000000",
+                time_created: (Utc.ymd(2022, 3, 1).and_hms(12, 0, 0)),
+                record_id: 123,
+                activity_id: "{a1b2c3d4-0000-0000-0000-000000000000}",
+                process_id: 4,
+                thread_id: 5,
+                channel: "Security",
+                computer_name: "dc01.example.com",
+                event_data: {
+                    SubjectUserSid: "S-1-5-18",
+                    TargetUserName: "jane",
+                    IpAddress: "-",
+                },
+            })),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        unnamed_event_data {
+            args: func_args![value: r#"<Event><System><EventID Qualifiers="16384">7036</EventID><Channel>System</Channel></System><EventData><Data>Windows Update</Data><Data>running</Data><Binary>770075006100</Binary></EventData></Event>"#],
+            want: Ok(value!({
+                event_id: 7036,
+                channel: "System",
+                event_data: {
+                    param1: "Windows Update",
+                    param2: "running",
+                    binary: "770075006100",
+                },
+            })),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        user_data {
+            args: func_args![value: r#"<Event><System><EventID>1102</EventID></System><UserData><LogFileCleared><SubjectUserSid>S-1-5-21</SubjectUserSid><SubjectUserName>admin</SubjectUserName><Privilege>SeSecurityPrivilege</Privilege><Privilege>SeBackupPrivilege</Privilege></LogFileCleared></UserData></Event>"#],
+            want: Ok(value!({
+                event_id: 1102,
+                user_data: {
+                    LogFileCleared: {
+                        SubjectUserSid: "S-1-5-21",
+                        SubjectUserName: "admin",
+                        Privilege: ["SeSecurityPrivilege", "SeBackupPrivilege"],
+                    },
+                },
+            })),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        invalid_xml {
+            args: func_args![value: "<Event><System></Event>"],
+            want: Err("unable to parse Windows event: expected 'System' tag, not 'Event' at 1:16"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        unexpected_root {
+            args: func_args![value: "<Log><System><EventID>1</EventID></System></Log>"],
+            want: Err(r#"unable to parse Windows event: expected an "Event" root element"#),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        missing_event_id {
+            args: func_args![value: "<Event><System><Channel>Security</Channel></System></Event>"],
+            want: Err(r#"unable to parse Windows event: missing "EventID" element"#),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        invalid_integer {
+            args: func_args![value: "<Event><System><EventID>4624</EventID><Execution ProcessID=\"abc\"/></System></Event>"],
+            want: Err(r#"unable to parse Windows event: "ProcessID" must be an integer"#),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        invalid_timestamp {
+            args: func_args![value: r#"<Event><System><EventID>4624</EventID><TimeCreated SystemTime="yesterday"/></System></Event>"#],
+            want: Err(r#"unable to parse Windows event: invalid "SystemTime" timestamp"#),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+    ];
+}
@@ -0,0 +1,358 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, TimeZone, Utc};
+use vrl::prelude::*;
+
+/// The value of a field that isn't set.
+const UNSET_FIELD: &str = "-";
+
+/// The value of an empty string or container.
+const EMPTY_FIELD: &str = "(empty)";
+
+fn parse_zeek(value: Value, fields: Value, types: Option<Value>) -> Resolved {
+    let bytes = value.try_bytes()?;
+    let line = String::from_utf8_lossy(&bytes);
+    let line = line.trim_end_matches(|c| c == '\r' || c == '\n');
+
+    if line.starts_with('#') {
+        return Err("unable to parse Zeek log: unexpected header line".into());
+    }
+
+    let fields = resolve_strings(fields)?;
+    let types = types.map(resolve_strings).transpose()?;
+    if let Some(types) = &types {
+        if types.len() != fields.len() {
+            return Err(
+                "unable to parse Zeek log: fields and types must have the same length".into(),
+            );
+        }
+    }
+
+    let values = line.split('\t').collect::<Vec<_>>();
+    if values.len() != fields.len() {
+        return Err(format!(
+            "unable to parse Zeek log: expected {} fields, found {}",
+            fields.len(),
+            values.len()
+        )
+        .into());
+    }
+
+    let mut log = BTreeMap::new();
+    for (index, (field, value)) in fields.into_iter().zip(values).enumerate() {
+        if value == UNSET_FIELD {
+            continue;
+        }
+
+        let value = match &types {
+            Some(types) => convert(value, &types[index]).ok_or_else(|| {
+                format!(
+                    r#"unable to parse Zeek field "{}" as "{}""#,
+                    field, types[index]
+                )
+            })?,
+            None => convert_string(value),
+        };
+
+        log.insert(field, value);
+    }
+
+    Ok(log.into())
+}
+
+fn resolve_strings(value: Value) -> Result<Vec<String>> {
+    value
+        .try_array()?
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            value
+                .try_bytes_utf8_lossy()
+                .map(|value| value.into_owned())
+                .map_err(|error| {
+                    format!("invalid field value type at index {}: {}", index, error).into()
+                })
+        })
+        .collect()
+}
+
+/// Convert a field to the VRL value matching its Zeek type, or `None` if the
+/// field doesn't hold a valid value of that type.
+fn convert(value: &str, kind: &str) -> Option<Value> {
+    if let Some(element) = container_element(kind) {
+        if value == EMPTY_FIELD {
+            return Some(Value::Array(vec![]));
+        }
+
+        return value
+            .split(',')
+            .map(|value| convert(value, element))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array);
+    }
+
+    match kind {
+        "bool" => match value {
+            "T" => Some(true.into()),
+            "F" => Some(false.into()),
+            _ => None,
+        },
+        "count" | "int" | "port" => value.parse::<i64>().ok().map(Into::into),
+        "double" | "interval" => value
+            .parse::<f64>()
+            .ok()
+            .and_then(|value| NotNan::new(value).ok())
+            .map(Into::into),
+        "time" => parse_time(value).map(Into::into),
+        _ => Some(convert_string(value)),
+    }
+}
+
+/// The element type of a `set[..]` or `vector[..]` type.
+fn container_element(kind: &str) -> Option<&str> {
+    kind.strip_prefix("set[")
+        .or_else(|| kind.strip_prefix("vector["))
+        .and_then(|element| element.strip_suffix(']'))
+}
+
+fn convert_string(value: &str) -> Value {
+    if value == EMPTY_FIELD {
+        return "".into();
+    }
+
+    unescape(value).into()
+}
+
+/// Unescape the `\xHH` sequences Zeek uses for non-printable bytes and
+/// separators.
+fn unescape(value: &str) -> Bytes {
+    let bytes = value.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'\\' && bytes.get(index + 1) == Some(&b'x') {
+            if let Some(byte) = value
+                .get(index + 2..index + 4)
+                .and_then(|code| u8::from_str_radix(code, 16).ok())
+            {
+                unescaped.push(byte);
+                index += 4;
+                continue;
+            }
+        }
+
+        unescaped.push(bytes[index]);
+        index += 1;
+    }
+
+    unescaped.into()
+}
+
+/// Parse a time, in seconds since the epoch with a fractional part.
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    let (seconds, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > 9 || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    let seconds = seconds.parse::<i64>().ok()?;
+    let nanoseconds = format!("{:0<9}", fraction).parse::<u32>().ok()?;
+
+    Utc.timestamp_opt(seconds, nanoseconds).single()
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseZeek;
+
+impl Function for ParseZeek {
+    fn identifier(&self) -> &'static str {
+        "parse_zeek"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "fields",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "types",
+                kind: kind::ARRAY,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "untyped fields",
+                source: r#"parse_zeek!("1320279566.452687\tCkVxvQ1NqdKqBvCBx2\t10.0.0.1\t-", ["ts", "uid", "id.orig_h", "service"])"#,
+                result: Ok(indoc! { r#"{
+                    "id.orig_h": "10.0.0.1",
+                    "ts": "1320279566.452687",
+                    "uid": "CkVxvQ1NqdKqBvCBx2"
+                }"#}),
+            },
+            Example {
+                title: "typed fields",
+                source: r#"parse_zeek!("1320279566.452687\tCkVxvQ1NqdKqBvCBx2\t10.0.0.1\t80\tT\t(empty)", ["ts", "uid", "id.orig_h", "id.orig_p", "local_orig", "tunnel_parents"], ["time", "string", "addr", "port", "bool", "set[string]"])"#,
+                result: Ok(indoc! { r#"{
+                    "id.orig_h": "10.0.0.1",
+                    "id.orig_p": 80,
+                    "local_orig": true,
+                    "ts": "2011-11-03T00:19:26.452687Z",
+                    "tunnel_parents": [],
+                    "uid": "CkVxvQ1NqdKqBvCBx2"
+                }"#}),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let fields = arguments.required("fields");
+        let types = arguments.optional("types");
+
+        Ok(Box::new(ParseZeekFn {
+            value,
+            fields,
+            types,
+        }))
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let fields = args.required("fields");
+        let types = args.optional("types");
+
+        parse_zeek(value, fields, types)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseZeekFn {
+    value: Box<dyn Expression>,
+    fields: Box<dyn Expression>,
+    types: Option<Box<dyn Expression>>,
+}
+
+impl Expression for ParseZeekFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let fields = self.fields.resolve(ctx)?;
+        let types = self
+            .types
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+
+        parse_zeek(value, fields, types)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::object(inner_kind(self.types.is_some())).fallible()
+    }
+}
+
+fn inner_kind(typed: bool) -> Collection<Field> {
+    let mut collection = Collection::empty();
+
+    if typed {
+        collection.set_unknown(
+            Kind::bytes()
+                .or_integer()
+                .or_float()
+                .or_boolean()
+                .or_timestamp()
+                .or_array(Collection::any()),
+        );
+    } else {
+        collection.set_unknown(Kind::bytes());
+    }
+
+    collection
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_common::btreemap;
+
+    use super::*;
+
+    test_function![
+        parse_zeek => ParseZeek;
+
+        untyped {
+            args: func_args![
+                value: "1320279566.452687\tCkVxvQ1NqdKqBvCBx2\t10.0.0.1\t-\t(empty)\tfoo\\x09bar\n",
+                fields: vec!["ts", "uid", "id.orig_h", "service", "history", "note"],
+            ],
+            want: Ok(btreemap! {
+                "ts" => "1320279566.452687",
+                "uid" => "CkVxvQ1NqdKqBvCBx2",
+                "id.orig_h" => "10.0.0.1",
+                "history" => "",
+                "note" => "foo\tbar",
+            }),
+            tdef: TypeDef::object(inner_kind(false)).fallible(),
+        }
+
+        typed {
+            args: func_args![
+                value: "1320279566.452687\tCkVxvQ1NqdKqBvCBx2\t10.0.0.1\t80\ttcp\t0.000508\t-\tT\t(empty)\tdns,http\t1,2",
+                fields: vec!["ts", "uid", "id.orig_h", "id.orig_p", "proto", "duration", "service", "local_orig", "tunnel_parents", "services", "counts"],
+                types: vec!["time", "string", "addr", "port", "enum", "interval", "string", "bool", "set[string]", "set[string]", "vector[count]"],
+            ],
+            want: Ok(btreemap! {
+                "ts" => Value::Timestamp(Utc.timestamp(1320279566, 452687000)),
+                "uid" => "CkVxvQ1NqdKqBvCBx2",
+                "id.orig_h" => "10.0.0.1",
+                "id.orig_p" => 80,
+                "proto" => "tcp",
+                "duration" => 0.000508,
+                "local_orig" => true,
+                "tunnel_parents" => Value::Array(vec![]),
+                "services" => vec!["dns", "http"],
+                "counts" => vec![1, 2],
+            }),
+            tdef: TypeDef::object(inner_kind(true)).fallible(),
+        }
+
+        header_line {
+            args: func_args![value: "#separator \\x09", fields: vec!["ts"]],
+            want: Err("unable to parse Zeek log: unexpected header line"),
+            tdef: TypeDef::object(inner_kind(false)).fallible(),
+        }
+
+        field_count_mismatch {
+            args: func_args![value: "1320279566.452687\tCkVxvQ1NqdKqBvCBx2", fields: vec!["ts"]],
+            want: Err("unable to parse Zeek log: expected 1 fields, found 2"),
+            tdef: TypeDef::object(inner_kind(false)).fallible(),
+        }
+
+        types_length_mismatch {
+            args: func_args![value: "1320279566.452687", fields: vec!["ts"], types: vec!["time", "string"]],
+            want: Err("unable to parse Zeek log: fields and types must have the same length"),
+            tdef: TypeDef::object(inner_kind(true)).fallible(),
+        }
+
+        invalid_typed_value {
+            args: func_args![value: "yes", fields: vec!["local_orig"], types: vec!["bool"]],
+            want: Err(r#"unable to parse Zeek field "local_orig" as "bool""#),
+            tdef: TypeDef::object(inner_kind(true)).fallible(),
+        }
+    ];
+}
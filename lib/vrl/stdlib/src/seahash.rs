@@ -0,0 +1,88 @@
+use vrl::prelude::*;
+
+fn seahash(value: Value) -> Resolved {
+    let value = value.try_bytes()?;
+
+    // VRL integers are signed, so hashes can be negative.
+    Ok((seahash_lib::hash(&value) as i64).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Seahash;
+
+impl Function for Seahash {
+    fn identifier(&self) -> &'static str {
+        "seahash"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "seahash",
+            source: r#"seahash("foo")"#,
+            result: Ok("4413582353838009230"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Box::new(SeahashFn { value }))
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+
+        seahash(value)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SeahashFn {
+    value: Box<dyn Expression>,
+}
+
+impl Expression for SeahashFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        seahash(value)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::integer().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        seahash => Seahash;
+
+        seahash {
+             args: func_args![value: "foo"],
+             want: Ok(4413582353838009230_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+
+        seahash_foobar {
+             args: func_args![value: "foobar"],
+             want: Ok(5348458858952426560_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+    ];
+}
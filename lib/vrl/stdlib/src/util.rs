@@ -82,6 +82,38 @@ pub(crate) fn is_nullish(value: &vrl::Value) -> bool {
     }
 }
 
+/// Splits the first `count` fields off the pipe-delimited header of a CEF or
+/// LEEF message, unescaping `\|` and `\\` within them, and returns the fields
+/// along with the rest of the message.
+#[cfg(any(feature = "parse_cef", feature = "parse_leef"))]
+pub(crate) fn split_pipe_header(input: &str, count: usize) -> Option<(Vec<String>, &str)> {
+    let mut fields = Vec::with_capacity(count);
+    let mut field = String::new();
+    let mut chars = input.char_indices();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => match chars.clone().next() {
+                Some((_, escaped @ ('|' | '\\'))) => {
+                    chars.next();
+                    field.push(escaped);
+                }
+                _ => field.push(c),
+            },
+            '|' => {
+                fields.push(std::mem::take(&mut field));
+
+                if fields.len() == count {
+                    return Some((fields, &input[index + 1..]));
+                }
+            }
+            c => field.push(c),
+        }
+    }
+
+    None
+}
+
 #[cfg(any(feature = "decode_base64", feature = "encode_base64"))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Base64Charset {
@@ -122,3 +154,71 @@ impl std::str::FromStr for Base64Charset {
         }
     }
 }
+
+/// The default maximum size of a decompressed value, guarding against
+/// decompression bombs.
+#[cfg(any(
+    feature = "decode_gzip",
+    feature = "decode_snappy",
+    feature = "decode_zlib",
+    feature = "decode_zstd"
+))]
+pub(crate) const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 100 * 1024 * 1024;
+
+/// Resolves the `max_size` argument of the decompression functions.
+#[cfg(any(
+    feature = "decode_gzip",
+    feature = "decode_snappy",
+    feature = "decode_zlib",
+    feature = "decode_zstd"
+))]
+pub(crate) fn max_decompressed_size(max_size: Option<vrl::Value>) -> vrl::prelude::Result<usize> {
+    use vrl::prelude::VrlValueConvert;
+
+    match max_size {
+        Some(max_size) => {
+            let max_size = max_size.try_integer()?;
+
+            usize::try_from(max_size)
+                .ok()
+                .filter(|max_size| *max_size > 0)
+                .ok_or_else(|| "max_size must be a positive integer".into())
+        }
+        None => Ok(DEFAULT_MAX_DECOMPRESSED_SIZE),
+    }
+}
+
+/// Reads a decompressed value, failing once it grows past `max_size` bytes.
+#[cfg(any(
+    feature = "decode_gzip",
+    feature = "decode_zlib",
+    feature = "decode_zstd"
+))]
+pub(crate) fn read_decompressed(
+    reader: impl std::io::Read,
+    max_size: usize,
+) -> vrl::prelude::Result<vrl::prelude::Bytes> {
+    use std::io::Read;
+
+    let mut buf = Vec::new();
+    reader
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut buf)
+        .map_err(|err| format!("unable to decompress value: {}", err))?;
+
+    if buf.len() > max_size {
+        return Err(decompressed_size_error(max_size));
+    }
+
+    Ok(buf.into())
+}
+
+#[cfg(any(
+    feature = "decode_gzip",
+    feature = "decode_snappy",
+    feature = "decode_zlib",
+    feature = "decode_zstd"
+))]
+pub(crate) fn decompressed_size_error(max_size: usize) -> vrl::prelude::ExpressionError {
+    format!("decompressed value exceeds max_size of {} bytes", max_size).into()
+}
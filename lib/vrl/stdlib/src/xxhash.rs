@@ -0,0 +1,165 @@
+use std::hash::Hasher;
+
+use twox_hash::{XxHash32, XxHash64};
+use vrl::prelude::*;
+
+fn xxhash(value: Value, variant: &Bytes) -> Resolved {
+    let value = value.try_bytes()?;
+    let hash = match variant.as_ref() {
+        b"XXH32" => hash::<XxHash32>(&value),
+        b"XXH64" => hash::<XxHash64>(&value),
+        _ => unreachable!("enum invariant"),
+    };
+
+    // VRL integers are signed, so 64-bit hashes can be negative.
+    Ok((hash as i64).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Xxhash;
+
+fn variants() -> Vec<Value> {
+    vec![value!("XXH32"), value!("XXH64")]
+}
+
+impl Function for Xxhash {
+    fn identifier(&self) -> &'static str {
+        "xxhash"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "variant",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "default variant",
+                source: r#"xxhash("foo")"#,
+                result: Ok("3792637401"),
+            },
+            Example {
+                title: "custom variant",
+                source: r#"xxhash("foo", "XXH64")"#,
+                result: Ok("3728699739546630719"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::Compiler,
+        _ctx: &mut FunctionCompileContext,
+        mut arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let variant = arguments
+            .optional_enum("variant", &variants())?
+            .unwrap_or_else(|| value!("XXH32"))
+            .try_bytes()
+            .expect("variant not bytes");
+
+        Ok(Box::new(XxhashFn { value, variant }))
+    }
+
+    fn compile_argument(
+        &self,
+        _args: &[(&'static str, Option<FunctionArgument>)],
+        _ctx: &mut FunctionCompileContext,
+        name: &str,
+        expr: Option<&expression::Expr>,
+    ) -> CompiledArgument {
+        match (name, expr) {
+            ("variant", Some(expr)) => {
+                let variant = expr
+                    .as_enum("variant", variants())?
+                    .try_bytes()
+                    .expect("variant not bytes");
+
+                Ok(Some(Box::new(variant) as _))
+            }
+            ("variant", None) => Ok(Some(Box::new(Bytes::from("XXH32")) as _)),
+            _ => Ok(None),
+        }
+    }
+
+    fn call_by_vm(&self, _ctx: &mut Context, args: &mut VmArgumentList) -> Resolved {
+        let value = args.required("value");
+        let variant = args
+            .required_any("variant")
+            .downcast_ref::<Bytes>()
+            .unwrap();
+
+        xxhash(value, variant)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct XxhashFn {
+    value: Box<dyn Expression>,
+    variant: Bytes,
+}
+
+impl Expression for XxhashFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let variant = &self.variant;
+
+        xxhash(value, variant)
+    }
+
+    fn type_def(&self, _: &state::Compiler) -> TypeDef {
+        TypeDef::integer().infallible()
+    }
+}
+
+#[inline]
+fn hash<T: Hasher + Default>(value: &[u8]) -> u64 {
+    let mut hasher = T::default();
+    hasher.write(value);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        xxhash => Xxhash;
+
+        xxhash {
+             args: func_args![value: "foo"],
+             want: Ok(3792637401_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+
+        xxhash_32 {
+            args: func_args![value: "foobar", variant: "XXH32"],
+            want: Ok(3986901679_i64),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        xxhash_64 {
+            args: func_args![value: "foo", variant: "XXH64"],
+            want: Ok(3728699739546630719_i64),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        xxhash_64_negative {
+            args: func_args![value: "foobar", variant: "XXH64"],
+            want: Ok(-6725556575634347271_i64),
+            tdef: TypeDef::integer().infallible(),
+        }
+    ];
+}
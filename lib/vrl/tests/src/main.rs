@@ -3,17 +3,17 @@
 
 mod test_enrichment;
 
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc};
 
 use ansi_term::Colour;
 use chrono::{DateTime, SecondsFormat, Utc};
 use chrono_tz::Tz;
 use clap::Parser;
+use cli::json::{json_diff, vrl_value_to_json_value};
 use glob::glob;
 use vector_common::TimeZone;
-use vrl::prelude::VrlValueConvert;
-use vrl::VrlRuntime;
 use vrl::{diagnostic::Formatter, state, Runtime, Terminate, Value};
+use vrl::{Jit, VrlRuntime};
 use vrl_tests::{docs, Test};
 
 #[cfg(not(target_env = "msvc"))]
@@ -150,6 +150,8 @@ fn main() {
 
         let mut state = vrl::state::Compiler::new();
         state.set_external_context(test_enrichment.clone());
+        state.set_external_context(test_secrets());
+        state.set_import_paths(vec!["modules".into()]);
 
         let program = vrl::compile_with_state(&test.source, &functions, &mut state);
 
@@ -208,11 +210,7 @@ fn main() {
                                 failed_count += 1;
 
                                 if !cmd.no_diff {
-                                    let want = serde_json::to_string_pretty(&want).unwrap();
-                                    let got = serde_json::to_string_pretty(&got).unwrap();
-
-                                    let diff = prettydiff::diff_lines(&want, &got);
-                                    println!("  {}", diff);
+                                    println!("  {}", json_diff(&want, &got));
                                 }
 
                                 failed = true;
@@ -255,10 +253,7 @@ fn main() {
                                     failed_count += 1;
 
                                     if !cmd.no_diff {
-                                        let want = serde_json::to_string_pretty(&want).unwrap();
-                                        let got = serde_json::to_string_pretty(&got).unwrap();
-                                        let diff = prettydiff::diff_lines(&want, &got);
-                                        println!("{}", diff);
+                                        println!("{}", json_diff(&want, &got));
                                     }
 
                                     failed = true;
@@ -342,6 +337,12 @@ fn run_vrl(
             test_enrichment.finish_load();
             runtime.run_vm(&vm, &mut test.object, &timezone)
         }
+        VrlRuntime::Jit => {
+            let vm = runtime.compile(functions, &program, state).unwrap();
+            let jit = Jit::new(Arc::new(vm)).unwrap();
+            test_enrichment.finish_load();
+            runtime.run_jit(&jit, &mut test.object, &timezone)
+        }
         VrlRuntime::Ast => {
             test_enrichment.finish_load();
             runtime.resolve(&mut test.object, &program, &timezone)
@@ -349,6 +350,17 @@ fn run_vrl(
     }
 }
 
+/// The secrets read by the examples of cryptographic functions, these are
+/// test vectors only.
+fn test_secrets() -> stdlib::Secrets {
+    [
+        ("encryption_key", "0123456789abcdef0123456789abcdef"),
+        ("hmac_key", "secret"),
+    ]
+    .into_iter()
+    .collect()
+}
+
 fn compare_partial_diagnostic(got: &str, want: &str) -> bool {
     got.lines()
         .filter(|line| line.trim().starts_with("error[E"))
@@ -376,25 +388,3 @@ fn print_result(failed_count: usize) {
 
     std::process::exit(code)
 }
-
-fn vrl_value_to_json_value(value: Value) -> serde_json::Value {
-    use serde_json::Value::*;
-
-    match value {
-        v @ Value::Bytes(_) => String(v.try_bytes_utf8_lossy().unwrap().into_owned()),
-        Value::Integer(v) => v.into(),
-        Value::Float(v) => v.into_inner().into(),
-        Value::Boolean(v) => v.into(),
-        Value::Object(v) => v
-            .into_iter()
-            .map(|(k, v)| (k, vrl_value_to_json_value(v)))
-            .collect::<serde_json::Value>(),
-        Value::Array(v) => v
-            .into_iter()
-            .map(vrl_value_to_json_value)
-            .collect::<serde_json::Value>(),
-        Value::Timestamp(v) => v.to_rfc3339_opts(SecondsFormat::AutoSi, true).into(),
-        Value::Regex(v) => v.to_string().into(),
-        Value::Null => Null,
-    }
-}
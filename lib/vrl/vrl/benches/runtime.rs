@@ -63,6 +63,29 @@ fn benchmark_kind_display(c: &mut Criterion) {
             )
         });
 
+        #[cfg(feature = "jit")]
+        {
+            let jit = vrl::Jit::new(std::sync::Arc::new(
+                runtime
+                    .compile(vrl_stdlib::all(), &program, Default::default())
+                    .unwrap(),
+            ))
+            .unwrap();
+
+            group.bench_with_input(BenchmarkId::new("Jit", source.name), &jit, |b, jit| {
+                let state = state::Runtime::default();
+                let mut runtime = Runtime::new(state);
+                b.iter_with_setup(
+                    || Value::Object(BTreeMap::default()),
+                    |mut obj| {
+                        let _ = black_box(runtime.run_jit(jit, &mut obj, &tz));
+                        runtime.clear();
+                        obj
+                    },
+                )
+            });
+        }
+
         group.bench_with_input(BenchmarkId::new("Ast", source.name), &(), |b, _| {
             let state = state::Runtime::default();
             let mut runtime = Runtime::new(state);
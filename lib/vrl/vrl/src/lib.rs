@@ -10,10 +10,12 @@ pub mod prelude;
 mod runtime;
 
 pub use compiler::{
-    function, state, value, vm::Vm, Context, Expression, Function, Program, Target, Value,
-    VrlRuntime,
+    coverage, function, state, value,
+    vm::{Jit, Vm},
+    Context, Expression, Function, Program, Target, TypeDef, Value, VrlRuntime,
 };
 pub use diagnostic;
+pub use parser::format;
 pub use runtime::{Runtime, RuntimeResult, Terminate};
 
 /// Compile a given source into the final [`Program`].
@@ -22,7 +22,7 @@ pub use bytes::Bytes;
 
 // commonly used function types
 pub use compiler::function::{
-    ArgumentList, Compiled, CompiledArgument, Example, FunctionCompileContext, Parameter,
+    closure, ArgumentList, Compiled, CompiledArgument, Example, FunctionCompileContext, Parameter,
 };
 // commonly used macros
 pub use compiler::{
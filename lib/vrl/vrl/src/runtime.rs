@@ -1,5 +1,5 @@
 use compiler::{
-    vm::{OpCode, Vm},
+    vm::{Jit, OpCode, Vm},
     ExpressionError, Function,
 };
 use lookup::LookupBuf;
@@ -142,4 +142,242 @@ impl Runtime {
             err @ ExpressionError::Error { .. } => Terminate::Error(err),
         })
     }
+
+    /// Given the provided [`Target`], runs the native code compiled by the
+    /// [`Jit`] to completion.
+    pub fn run_jit(
+        &mut self,
+        jit: &Jit,
+        target: &mut dyn Target,
+        timezone: &TimeZone,
+    ) -> Result<Value, Terminate> {
+        let mut context = Context::new(target, &mut self.state, timezone);
+        jit.run(&mut context).map_err(|err| match err {
+            ExpressionError::Abort { .. } => Terminate::Abort(err),
+            err @ ExpressionError::Error { .. } => Terminate::Error(err),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        panic::{self, AssertUnwindSafe},
+        sync::Arc,
+    };
+
+    use compiler::{
+        function::{ArgumentList, Compiled, Example, FunctionCompileContext},
+        vm::VmArgumentList,
+        Expression, Resolved, TypeDef,
+    };
+    use indoc::indoc;
+
+    use super::*;
+
+    /// Programs exercising each `OpCode`, run by every runtime.
+    const SOURCES: [&str; 14] = [
+        r#".sum = 1 + 2 * 3 - 4 / 2 % 3"#,
+        r#".compare = [1 < 2, 2 <= 2, 3 > 4, 4 >= 5, "a" == "a", "a" != "b"]"#,
+        r#".logic = [true && false, false || true, !true, null || "default", !false && true]"#,
+        r#".merged = { "a": 1 } | { "b": [1, 2, { "c": .message }] }"#,
+        indoc! {r#"
+            if .number == 3 {
+                .size = "three"
+            } else if .number == 1 {
+                .size = "one"
+            } else {
+                .size = "small"
+            }
+        "#},
+        r#".parsed, .err = parse_json(.message)"#,
+        r#".parsed, .err = parse_json(.json)"#,
+        r#".fallback = parse_json(.message) ?? "invalid""#,
+        r#".message = upcase!(.message); .message"#,
+        r#".matched = parse_regex!(.message, r'^(?P<first>\w+)')"#,
+        r#"parse_json!(.message)"#,
+        r#"abort"#,
+        indoc! {r#"
+            total = 0
+            for_each([1, 2, 3]) -> |_index, value| { total = total + value }
+            .total = total
+            .doubled = map_values({ "a": 1, "b": 2 }) -> |value| { [value] }
+        "#},
+        indoc! {r#"
+            fn double(value) {
+                value * 2
+            }
+
+            fn reject(reason) {
+                abort reason
+            }
+
+            .doubled = double(21)
+            if .number == 3 { reject("too large") }
+        "#},
+    ];
+
+    fn target() -> Value {
+        Value::Object(BTreeMap::from([
+            ("message".to_owned(), "hello world".into()),
+            ("json".to_owned(), r#"{"ok": true}"#.into()),
+            ("number".to_owned(), 3.into()),
+        ]))
+    }
+
+    fn vm(source: &str, functions: Vec<Box<dyn Function>>) -> Vm {
+        let program = crate::compile(source, &functions).unwrap();
+
+        Runtime::new(Default::default())
+            .compile(functions, &program, Default::default())
+            .unwrap()
+    }
+
+    /// The result of a run, with errors compared by their message, as the VM
+    /// doesn't keep the labels of function call errors.
+    fn outcome(result: RuntimeResult, target: Value) -> (Result<Value, String>, Value) {
+        let result = result.map_err(|err| match err {
+            Terminate::Abort(err) => format!("abort: {}", err),
+            Terminate::Error(err) => format!("error: {}", err),
+        });
+
+        (result, target)
+    }
+
+    /// Resolves the program, returning its outcome.
+    fn resolve(source: &str) -> (Result<Value, String>, Value) {
+        let program = crate::compile(source, &vrl_stdlib::all()).unwrap();
+        let mut target = target();
+        let result =
+            Runtime::new(Default::default()).resolve(&mut target, &program, &TimeZone::default());
+
+        outcome(result, target)
+    }
+
+    #[test]
+    fn vm_matches_ast() {
+        for source in SOURCES {
+            let vm = vm(source, vrl_stdlib::all());
+            let mut target = target();
+            let result =
+                Runtime::new(Default::default()).run_vm(&vm, &mut target, &TimeZone::default());
+
+            assert_eq!(outcome(result, target), resolve(source), "{}", source);
+        }
+    }
+
+    #[cfg(feature = "jit")]
+    #[test]
+    fn jit_matches_ast() {
+        for source in SOURCES {
+            let jit = Jit::new(Arc::new(vm(source, vrl_stdlib::all()))).unwrap();
+
+            // The native code is run more than once, as it's shared by the
+            // events of a transform.
+            for _ in 0..2 {
+                let mut target = target();
+                let result = Runtime::new(Default::default()).run_jit(
+                    &jit,
+                    &mut target,
+                    &TimeZone::default(),
+                );
+
+                assert_eq!(outcome(result, target), resolve(source), "{}", source);
+            }
+        }
+    }
+
+    #[cfg(not(feature = "jit"))]
+    #[test]
+    fn jit_requires_feature() {
+        let vm = vm(".foo = 1", vrl_stdlib::all());
+
+        assert_eq!(
+            Jit::new(Arc::new(vm)).unwrap_err(),
+            "VRL was built without the `jit` feature"
+        );
+    }
+
+    /// A function panicking when called, as a function with a bug would.
+    #[derive(Debug, Clone, Copy)]
+    struct Panic;
+
+    impl Function for Panic {
+        fn identifier(&self) -> &'static str {
+            "panic"
+        }
+
+        fn examples(&self) -> &'static [Example] {
+            &[]
+        }
+
+        fn compile(
+            &self,
+            _state: &state::Compiler,
+            _ctx: &mut FunctionCompileContext,
+            _arguments: ArgumentList,
+        ) -> Compiled {
+            Ok(Box::new(PanicFn))
+        }
+
+        fn call_by_vm(&self, _ctx: &mut Context, _args: &mut VmArgumentList) -> Resolved {
+            panic!("function panicked")
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct PanicFn;
+
+    impl Expression for PanicFn {
+        fn resolve(&self, _ctx: &mut Context) -> Resolved {
+            panic!("function panicked")
+        }
+
+        fn type_def(&self, _state: &state::Compiler) -> TypeDef {
+            TypeDef::null()
+        }
+    }
+
+    const PANIC_SOURCE: &str = r#"if .number == 3 { panic() } else { "ok" }"#;
+
+    fn panic_message(payload: Box<dyn std::any::Any + Send>) -> &'static str {
+        *payload.downcast::<&'static str>().unwrap()
+    }
+
+    #[test]
+    fn vm_propagates_panic() {
+        let vm = vm(PANIC_SOURCE, vec![Box::new(Panic)]);
+        let mut target = target();
+
+        let payload = panic::catch_unwind(AssertUnwindSafe(|| {
+            Runtime::new(Default::default()).run_vm(&vm, &mut target, &TimeZone::default())
+        }))
+        .unwrap_err();
+
+        assert_eq!(panic_message(payload), "function panicked");
+    }
+
+    #[cfg(feature = "jit")]
+    #[test]
+    fn jit_propagates_panic() {
+        let jit = Jit::new(Arc::new(vm(PANIC_SOURCE, vec![Box::new(Panic)]))).unwrap();
+        let mut target = target();
+
+        // The panic is caught in the helper, and resumed once the native code
+        // returns.
+        let payload = panic::catch_unwind(AssertUnwindSafe(|| {
+            Runtime::new(Default::default()).run_jit(&jit, &mut target, &TimeZone::default())
+        }))
+        .unwrap_err();
+
+        assert_eq!(panic_message(payload), "function panicked");
+
+        // The native code is still usable after a panic.
+        let mut target = Value::Object(BTreeMap::from([("number".to_owned(), 1.into())]));
+        let result =
+            Runtime::new(Default::default()).run_jit(&jit, &mut target, &TimeZone::default());
+
+        assert_eq!(result, Ok("ok".into()));
+    }
 }
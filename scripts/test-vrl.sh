@@ -12,3 +12,11 @@ set -euo pipefail
 
   cargo run -- --runtime=ast && cargo run -- --runtime=vm
 )
+
+# The `jit` runtime, and the tests of the native code compiled by it, require
+# the `jit` feature.
+(
+  cd "$(dirname "${BASH_SOURCE[0]}")/../lib/vrl/tests"
+
+  cargo run --features jit -- --runtime=jit && cargo test --package vrl --features jit
+)
@@ -0,0 +1,112 @@
+use async_graphql::Object;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    config::ComponentKey,
+    event::{Metric, MetricValue},
+};
+
+pub struct ConsumerLag(Metric);
+
+impl ConsumerLag {
+    pub const fn new(m: Metric) -> Self {
+        Self(m)
+    }
+}
+
+#[Object]
+impl ConsumerLag {
+    /// Metric timestamp
+    pub async fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.0.timestamp()
+    }
+
+    /// Number of messages the consumer is behind the end of the partitions it consumes
+    pub async fn consumer_lag(&self) -> f64 {
+        match self.0.value() {
+            MetricValue::Gauge { value } => *value,
+            _ => 0.00,
+        }
+    }
+}
+
+impl From<Metric> for ConsumerLag {
+    fn from(m: Metric) -> Self {
+        Self(m)
+    }
+}
+
+pub struct ComponentConsumerLag {
+    component_key: ComponentKey,
+    metric: Metric,
+}
+
+impl ComponentConsumerLag {
+    /// Returns a new `ComponentConsumerLag` struct, which is a GraphQL type. The
+    /// component id is hoisted for clear field resolution in the resulting payload
+    pub fn new(metric: Metric) -> Self {
+        let component_key = metric.tag_value("component_id").expect(
+            "Returned a metric without a `component_id`, which shouldn't happen. Please report.",
+        );
+        let component_key = ComponentKey::from(component_key);
+
+        Self {
+            component_key,
+            metric,
+        }
+    }
+}
+
+#[Object]
+impl ComponentConsumerLag {
+    /// Component id
+    async fn component_id(&self) -> &str {
+        self.component_key.id()
+    }
+
+    /// Consumer lag metric
+    async fn metric(&self) -> ConsumerLag {
+        ConsumerLag::new(self.metric.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::Request;
+    use metrics::gauge;
+    use serde_json::json;
+    use tokio_stream::StreamExt;
+
+    use crate::api::schema::build_schema;
+
+    #[tokio::test]
+    async fn sums_lag_of_partitions_by_component() {
+        let _ = crate::metrics::init_test();
+        for (component_id, partition_id, lag) in [("a", "0", 3.0), ("a", "1", 4.0), ("b", "0", 5.0)]
+        {
+            gauge!(
+                "kafka_consumer_lag", lag,
+                "component_id" => component_id,
+                "topic_id" => "topic",
+                "partition_id" => partition_id,
+            );
+        }
+
+        let schema = build_schema().finish();
+        let mut lags = schema.execute_stream(Request::new(
+            "subscription { componentConsumerLags(interval: 10) { componentId metric { consumerLag } } }",
+        ));
+        let response = lags.next().await.unwrap();
+
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({
+                "componentConsumerLags": [
+                    { "componentId": "a", "metric": { "consumerLag": 7.0 } },
+                    { "componentId": "b", "metric": { "consumerLag": 5.0 } },
+                ]
+            })
+        );
+    }
+}
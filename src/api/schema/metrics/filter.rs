@@ -226,6 +226,32 @@ pub fn component_counter_metrics(
     })
 }
 
+/// Returns a stream of `Vec<Metric>`, where the 'gauge' metrics matching `filter_fn` are
+/// summed for each component, such as the lag of the partitions it consumes. Uses a local
+/// cache to return results only when the value of a component changed.
+pub fn component_gauge_metrics(
+    interval: i32,
+    filter_fn: &'static MetricFilterFn,
+) -> impl Stream<Item = Vec<Metric>> {
+    let mut cache = BTreeMap::new();
+
+    component_to_filtered_metrics(interval, filter_fn).map(move |map| {
+        map.into_iter()
+            .filter_map(|(id, metrics)| {
+                let m = sum_metrics_owned(metrics)?;
+                match m.value() {
+                    MetricValue::Gauge { value }
+                        if cache.insert(id, *value).map_or(true, |last| last != *value) =>
+                    {
+                        Some(m)
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    })
+}
+
 /// Returns the throughput of a 'counter' metric, sampled over `interval` milliseconds
 /// and filtered by the provided `filter_fn`.
 pub fn counter_throughput(
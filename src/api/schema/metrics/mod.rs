@@ -1,3 +1,4 @@
+mod consumer_lag;
 mod errors;
 mod events_in;
 mod events_out;
@@ -17,6 +18,7 @@ mod host;
 
 use async_graphql::{Interface, Object, Subscription};
 use chrono::{DateTime, Utc};
+pub use consumer_lag::{ComponentConsumerLag, ConsumerLag};
 pub use errors::{ComponentErrorsTotal, ErrorsTotal};
 pub use events_in::EventsInTotal;
 pub use events_out::EventsOutTotal;
@@ -297,6 +299,16 @@ impl MetricsSubscription {
             .map(|m| m.into_iter().map(ComponentErrorsTotal::new).collect())
     }
 
+    /// Component consumer lag metrics over `interval`, for the components consuming
+    /// partitioned streams.
+    async fn component_consumer_lags(
+        &self,
+        #[graphql(default = 1000, validator(minimum = 10, maximum = 60_000))] interval: i32,
+    ) -> impl Stream<Item = Vec<ComponentConsumerLag>> {
+        component_gauge_metrics(interval, &|m| m.name().ends_with("_consumer_lag"))
+            .map(|m| m.into_iter().map(ComponentConsumerLag::new).collect())
+    }
+
     /// All metrics.
     async fn metrics(
         &self,
@@ -115,6 +115,7 @@ impl AnyCondition {
             AnyCondition::String(s) => VrlConfig {
                 source: s.clone(),
                 runtime: Default::default(),
+                import_paths: Vec::new(),
             }
             .build(enrichment_tables),
             AnyCondition::Map(m) => m.build(enrichment_tables),
@@ -165,7 +166,7 @@ mod tests {
         .unwrap();
 
         assert_eq!(
-            r#"Map(VrlConfig { source: ".nork == true", runtime: Ast })"#,
+            r#"Map(VrlConfig { source: ".nork == true", runtime: Ast, import_paths: [] })"#,
             format!("{:?}", conf.condition)
         )
     }
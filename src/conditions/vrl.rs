@@ -1,8 +1,8 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 use vector_common::TimeZone;
-use vrl::{diagnostic::Formatter, Program, Runtime, Value, Vm, VrlRuntime};
+use vrl::{diagnostic::Formatter, Jit, Program, Runtime, Value, Vm, VrlRuntime};
 
 use crate::{
     conditions::{Condition, ConditionConfig, ConditionDescription, Conditional},
@@ -17,6 +17,10 @@ pub struct VrlConfig {
 
     #[serde(default)]
     pub(crate) runtime: VrlRuntime,
+
+    /// The directories searched for the files imported by the condition.
+    #[serde(default)]
+    pub(crate) import_paths: Vec<PathBuf>,
 }
 
 inventory::submit! {
@@ -52,6 +56,7 @@ impl ConditionConfig for VrlConfig {
 
         let mut state = vrl::state::Compiler::new();
         state.set_external_context(enrichment_tables.clone());
+        state.set_import_paths(self.import_paths.clone());
 
         let program = vrl::compile_with_state(&self.source, &functions, &mut state).map_err(
             |diagnostics| {
@@ -67,6 +72,26 @@ impl ConditionConfig for VrlConfig {
                 Ok(Condition::VrlVm(VrlVm {
                     source: self.source.clone(),
                     vm,
+                    jit: None,
+                }))
+            }
+            VrlRuntime::Jit => {
+                let vm = Arc::new(Runtime::default().compile(functions, &program, state)?);
+                let jit = match Jit::new(Arc::clone(&vm)) {
+                    Ok(jit) => Some(Arc::new(jit)),
+                    Err(error) => {
+                        warn!(
+                            message = "Condition can't be compiled to native code, falling back to the vm runtime.",
+                            %error,
+                        );
+                        None
+                    }
+                };
+
+                Ok(Condition::VrlVm(VrlVm {
+                    source: self.source.clone(),
+                    vm,
+                    jit,
                 }))
             }
             VrlRuntime::Ast => Ok(Condition::Vrl(Vrl {
@@ -162,6 +187,9 @@ impl Conditional for Vrl {
 pub struct VrlVm {
     pub(super) source: String,
     pub(super) vm: Arc<Vm>,
+
+    /// The native code compiled from the VM, when using the `jit` runtime.
+    pub(super) jit: Option<Arc<Jit>>,
 }
 
 impl VrlVm {
@@ -182,7 +210,10 @@ impl VrlVm {
         // TODO: use timezone from remap config
         let timezone = TimeZone::default();
 
-        Runtime::default().run_vm(&self.vm, &mut target, &timezone)
+        match &self.jit {
+            Some(jit) => Runtime::default().run_jit(jit, &mut target, &timezone),
+            None => Runtime::default().run_vm(&self.vm, &mut target, &timezone),
+        }
     }
 }
 
@@ -315,6 +346,7 @@ mod test {
             let config = VrlConfig {
                 source,
                 runtime: Default::default(),
+                import_paths: Vec::new(),
             };
 
             assert_eq!(
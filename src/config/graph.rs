@@ -17,6 +17,7 @@ pub enum Node {
     },
     Sink {
         ty: DataType,
+        outputs: Vec<Output>,
     },
 }
 
@@ -83,6 +84,7 @@ impl Graph {
                 id.clone(),
                 Node::Sink {
                     ty: config.inner.input().data_type(),
+                    outputs: config.outputs(),
                 },
             );
         }
@@ -149,7 +151,7 @@ impl Graph {
         match self.nodes[key] {
             Node::Source { .. } => panic!("no inputs on sources"),
             Node::Transform { in_ty, .. } => in_ty,
-            Node::Sink { ty } => ty,
+            Node::Sink { ty, .. } => ty,
         }
     }
 
@@ -157,16 +159,16 @@ impl Graph {
     ///
     /// # Panics
     ///
-    /// Will panic if the given id is not present in the graph or identifies a sink, which can't
-    /// have inputs.
+    /// Will panic if the given id is not present in the graph.
     fn get_output_type(&self, id: &OutputId) -> DataType {
         match &self.nodes[&id.component] {
-            Node::Source { outputs } | Node::Transform { outputs, .. } => outputs
+            Node::Source { outputs }
+            | Node::Transform { outputs, .. }
+            | Node::Sink { outputs, .. } => outputs
                 .iter()
                 .find(|output| output.port == id.port)
                 .map(|output| output.ty)
                 .expect("output didn't exist"),
-            Node::Sink { .. } => panic!("no outputs on sinks"),
         }
     }
 
@@ -251,14 +253,12 @@ impl Graph {
         self.nodes
             .iter()
             .flat_map(|(key, node)| match node {
-                Node::Sink { .. } => vec![],
-                Node::Source { outputs } | Node::Transform { outputs, .. } => outputs
-                    .iter()
-                    .map(|output| OutputId {
-                        component: key.clone(),
-                        port: output.port.clone(),
-                    })
-                    .collect(),
+                Node::Source { outputs }
+                | Node::Transform { outputs, .. }
+                | Node::Sink { outputs, .. } => outputs.iter().map(move |output| OutputId {
+                    component: key.clone(),
+                    port: output.port.clone(),
+                }),
             })
             .collect()
     }
@@ -336,7 +336,7 @@ impl Graph {
             .into_iter()
             .filter(|path| {
                 if let Some(key) = path.last() {
-                    matches!(self.nodes.get(key), Some(Node::Sink { ty: _ }))
+                    matches!(self.nodes.get(key), Some(Node::Sink { .. }))
                 } else {
                     false
                 }
@@ -396,7 +396,13 @@ mod test {
         fn add_sink(&mut self, id: &str, ty: DataType, inputs: Vec<&str>) {
             let id = ComponentKey::from(id);
             let inputs = clean_inputs(inputs);
-            self.nodes.insert(id.clone(), Node::Sink { ty });
+            self.nodes.insert(
+                id.clone(),
+                Node::Sink {
+                    ty,
+                    outputs: vec![],
+                },
+            );
             for from in inputs {
                 self.edges.push(Edge {
                     from,
@@ -405,6 +411,14 @@ mod test {
             }
         }
 
+        fn add_sink_output(&mut self, id: &str, name: &str, ty: DataType) {
+            let id = id.into();
+            match self.nodes.get_mut(&id) {
+                Some(Node::Sink { outputs, .. }) => outputs.push(Output::from((name, ty))),
+                _ => panic!("invalid sink"),
+            }
+        }
+
         fn test_add_input(&mut self, node: &str, input: &str) -> Result<(), String> {
             let available_inputs = self.input_map().unwrap();
             self.add_input(input, &node.into(), &available_inputs)
@@ -599,6 +613,52 @@ mod test {
         );
     }
 
+    #[test]
+    fn allows_sink_rejected_outputs() {
+        let mut graph = Graph::default();
+        graph.add_source("log_source", DataType::Log);
+        graph.add_sink("http_sink", DataType::Log, vec!["log_source"]);
+        graph.add_sink_output("http_sink", "rejected", DataType::Log);
+        graph.add_sink("plain_sink", DataType::Log, vec!["log_source"]);
+
+        graph.add_sink("rejected_sink", DataType::Log, vec![]);
+        graph.add_sink("bad_sink", DataType::Log, vec![]);
+
+        assert_eq!(
+            Ok(()),
+            graph.test_add_input("rejected_sink", "http_sink.rejected")
+        );
+        assert_eq!(Ok(()), graph.typecheck());
+        graph.check_for_cycles().unwrap();
+
+        // sinks only have the outputs they opted into
+        let expected =
+            "Input \"plain_sink.rejected\" for sink \"bad_sink\" doesn't match any components."
+                .to_string();
+        assert_eq!(
+            Err(expected),
+            graph.test_add_input("bad_sink", "plain_sink.rejected")
+        );
+        let expected =
+            "Input \"http_sink\" for sink \"bad_sink\" doesn't match any components.".to_string();
+        assert_eq!(Err(expected), graph.test_add_input("bad_sink", "http_sink"));
+    }
+
+    #[test]
+    fn detects_cycles_between_sinks() {
+        let mut graph = Graph::default();
+        graph.add_source("in", DataType::Log);
+        graph.add_sink("one", DataType::Log, vec!["in", "two.rejected"]);
+        graph.add_sink_output("one", "rejected", DataType::Log);
+        graph.add_sink("two", DataType::Log, vec!["one.rejected"]);
+        graph.add_sink_output("two", "rejected", DataType::Log);
+
+        assert!(graph
+            .check_for_cycles()
+            .unwrap_err()
+            .starts_with("Cyclic dependency detected in the chain"));
+    }
+
     #[test]
     fn disallows_ambiguous_inputs() {
         let mut graph = Graph::default();
@@ -46,7 +46,9 @@ pub use loading::{
     load, load_builder_from_paths, load_from_paths, load_from_paths_with_provider, load_from_str,
     load_source_from_paths, merge_path_lists, process_paths, CONFIG_PATHS,
 };
-pub use sink::{SinkConfig, SinkContext, SinkDescription, SinkHealthcheckOptions, SinkOuter};
+pub use sink::{
+    SinkConfig, SinkContext, SinkDescription, SinkHealthcheckOptions, SinkOuter, REJECTED_OUTPUT,
+};
 pub use source::{SourceConfig, SourceContext, SourceDescription, SourceOuter};
 pub use transform::{TransformDescription, TransformOuter};
 pub use unit_test::{build_unit_tests, build_unit_tests_main, UnitTestResult};
@@ -2,11 +2,14 @@ use async_trait::async_trait;
 use component::ComponentDescription;
 use serde::{Deserialize, Serialize};
 use vector_buffers::{Acker, BufferConfig, BufferType};
-use vector_core::config::{AcknowledgementsConfig, GlobalOptions, Input};
+use vector_core::config::{AcknowledgementsConfig, GlobalOptions, Input, Output};
 
 use super::{component, ComponentKey, ProxyConfig, Resource};
 use crate::sinks::{self, util::UriSerde};
 
+/// The name of the output receiving the events permanently rejected by a sink.
+pub const REJECTED_OUTPUT: &str = "rejected";
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct SinkOuter<T> {
     #[serde(default = "Default::default")] // https://github.com/serde-rs/serde/issues/1541
@@ -28,6 +31,14 @@ pub struct SinkOuter<T> {
     )]
     proxy: ProxyConfig,
 
+    /// Send the events permanently rejected by the sink to its `rejected` output instead of
+    /// dropping them.
+    #[serde(
+        default,
+        skip_serializing_if = "vector_core::serde::skip_serializing_if_default"
+    )]
+    pub reroute_rejected: bool,
+
     #[serde(flatten)]
     pub inner: Box<dyn SinkConfig>,
 }
@@ -41,6 +52,7 @@ impl<T> SinkOuter<T> {
             healthcheck_uri: None,
             inner,
             proxy: Default::default(),
+            reroute_rejected: false,
         }
     }
 
@@ -79,6 +91,18 @@ impl<T> SinkOuter<T> {
         &self.proxy
     }
 
+    /// The outputs of the sink, which only has a `rejected` output when rerouting rejected events.
+    pub fn outputs(&self) -> Vec<Output> {
+        if self.reroute_rejected {
+            vec![Output::from((
+                REJECTED_OUTPUT,
+                self.inner.input().data_type(),
+            ))]
+        } else {
+            Vec::new()
+        }
+    }
+
     pub(super) fn map_inputs<U>(self, f: impl Fn(&T) -> U) -> SinkOuter<U> {
         let inputs = self.inputs.iter().map(f).collect();
         self.with_inputs(inputs)
@@ -92,6 +116,7 @@ impl<T> SinkOuter<T> {
             healthcheck: self.healthcheck,
             healthcheck_uri: self.healthcheck_uri,
             proxy: self.proxy,
+            reroute_rejected: self.reroute_rejected,
         }
     }
 }
@@ -0,0 +1,70 @@
+use std::fmt::Display;
+
+use super::prelude::{error_stage, error_type};
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct ClickhouseEventEncodingError<'a, E> {
+    pub table: &'a str,
+    pub column: &'a str,
+    pub error: E,
+}
+
+impl<'a, E: Display> InternalEvent for ClickhouseEventEncodingError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to convert event field to the column type; dropping event.",
+            table = %self.table,
+            column = %self.column,
+            error = %self.error,
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+            rate_limit_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct ClickhouseSchemaFetchError<'a, E> {
+    pub table: &'a str,
+    pub error: E,
+    /// The number of events dropped because the schema of their table can't be used.
+    pub dropped: Option<usize>,
+}
+
+impl<'a, E: Display> InternalEvent for ClickhouseSchemaFetchError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to fetch the table schema.",
+            table = %self.table,
+            error = %self.error,
+            dropped = ?self.dropped,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::PROCESSING,
+            rate_limit_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        if let Some(count) = self.dropped {
+            counter!(
+                "component_discarded_events_total", count as u64,
+                "error_type" => error_type::REQUEST_FAILED,
+                "stage" => error_stage::PROCESSING,
+            );
+        }
+    }
+}
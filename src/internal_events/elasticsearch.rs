@@ -39,3 +39,41 @@ impl<'a> InternalEvent for ElasticsearchResponseError<'a> {
         );
     }
 }
+
+#[cfg(feature = "sinks-elasticsearch")]
+#[derive(Debug)]
+pub struct ElasticsearchBulkItemRejected<'a> {
+    pub index: &'a str,
+    pub status: u16,
+    pub rejection_type: &'a str,
+    pub reason: &'a str,
+}
+
+#[cfg(feature = "sinks-elasticsearch")]
+impl<'a> InternalEvent for ElasticsearchBulkItemRejected<'a> {
+    fn emit(self) {
+        let error_code = super::prelude::http_error_code(self.status);
+        error!(
+            message = "Document rejected by Elasticsearch; dropping it.",
+            index = %self.index,
+            rejection_type = %self.rejection_type,
+            reason = %self.reason,
+            error_code = %error_code,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            rate_limit_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => error_code.clone(),
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_code" => error_code,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -2,7 +2,6 @@ use std::time::Duration;
 
 use super::prelude::{error_stage, error_type};
 use metrics::{counter, histogram};
-use tokio::time::error::Elapsed;
 use vector_core::internal_event::InternalEvent;
 
 #[derive(Debug)]
@@ -71,7 +70,6 @@ impl InternalEvent for ExecFailedError<'_> {
 pub struct ExecTimeoutError<'a> {
     pub command: &'a str,
     pub elapsed_seconds: u64,
-    pub error: Elapsed,
 }
 
 impl InternalEvent for ExecTimeoutError<'_> {
@@ -80,7 +78,6 @@ impl InternalEvent for ExecTimeoutError<'_> {
             message = "Timeout during exec.",
             command = %self.command,
             elapsed_seconds = %self.elapsed_seconds,
-            error = %self.error,
             error_type = error_type::TIMED_OUT,
             stage = error_stage::RECEIVING,
         );
@@ -413,6 +413,32 @@ mod source {
         }
     }
 
+    #[derive(Debug)]
+    pub struct PathWatchError<'a> {
+        pub path: &'a Path,
+        pub error: Error,
+    }
+
+    impl<'a> InternalEvent for PathWatchError<'a> {
+        fn emit(self) {
+            error!(
+                message = "Failed to watch path for changes, polling files instead.",
+                error = %self.error,
+                error_code = "watching",
+                error_type = error_type::READER_FAILED,
+                stage = error_stage::RECEIVING,
+                path = %self.path.display(),
+            );
+            counter!(
+                "component_errors_total", 1,
+                "error_code" => "watching",
+                "error_type" => error_type::READER_FAILED,
+                "stage" => error_stage::RECEIVING,
+                "path" => self.path.to_string_lossy().into_owned(),
+            );
+        }
+    }
+
     #[derive(Clone)]
     pub struct FileSourceInternalEventsEmitter;
 
@@ -467,5 +493,9 @@ mod source {
         fn emit_path_globbing_failed(&self, path: &Path, error: &Error) {
             emit!(PathGlobbingError { path, error });
         }
+
+        fn emit_path_watch_failed(&self, path: &Path, error: Error) {
+            emit!(PathWatchError { path, error });
+        }
     }
 }
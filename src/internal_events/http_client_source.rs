@@ -0,0 +1,97 @@
+use std::time::Instant;
+
+use super::prelude::{error_stage, error_type};
+use metrics::{counter, histogram};
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct HttpClientEventsReceived {
+    pub byte_size: usize,
+    pub count: usize,
+    pub url: http::Uri,
+    pub start: Instant,
+}
+
+impl InternalEvent for HttpClientEventsReceived {
+    fn emit(self) {
+        trace!(
+            message = "Events received.",
+            count = %self.count,
+            byte_size = %self.byte_size,
+            url = %self.url,
+        );
+        counter!(
+            "component_received_events_total", self.count as u64,
+            "uri" => self.url.to_string(),
+        );
+        counter!(
+            "component_received_event_bytes_total", self.byte_size as u64,
+            "uri" => self.url.to_string(),
+        );
+        counter!("requests_completed_total", 1);
+        histogram!("request_duration_seconds", self.start.elapsed());
+    }
+}
+
+#[derive(Debug)]
+pub struct HttpClientHttpResponseError {
+    pub code: hyper::StatusCode,
+    pub url: http::Uri,
+}
+
+impl InternalEvent for HttpClientHttpResponseError {
+    fn emit(self) {
+        error!(
+            message = "HTTP error response.",
+            url = %self.url,
+            code = %self.code,
+            error = self.code.canonical_reason().unwrap_or("unknown status code"),
+            error_code = %http_error_code(self.code),
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "url" => self.url.to_string(),
+            "error_code" => http_error_code(self.code),
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+        // deprecated
+        counter!("http_error_response_total", 1);
+    }
+}
+
+fn http_error_code(code: hyper::StatusCode) -> String {
+    format!("http_response_{}", code.as_u16())
+}
+
+#[derive(Debug)]
+pub struct HttpClientHttpError {
+    pub error: crate::Error,
+    pub url: http::Uri,
+}
+
+impl InternalEvent for HttpClientHttpError {
+    fn emit(self) {
+        error!(
+            message = "HTTP request processing error.",
+            url = %self.url,
+            error = ?self.error,
+            error_code = "failed_requesting",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "url" => self.url.to_string(),
+            "error_code" => "failed_requesting",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+        // deprecated
+        counter!("http_request_errors_total", 1);
+    }
+}
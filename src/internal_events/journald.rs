@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use super::prelude::{error_stage, error_type};
 use metrics::counter;
 use vector_core::internal_event::InternalEvent;
@@ -44,3 +46,27 @@ impl InternalEvent for JournaldInvalidRecordError {
         counter!("invalid_record_bytes_total", self.text.len() as u64); // deprecated
     }
 }
+
+#[derive(Debug)]
+pub struct JournaldReadError<'a, E> {
+    pub path: &'a Path,
+    pub error: E,
+}
+
+impl<'a, E: std::fmt::Display> InternalEvent for JournaldReadError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed reading journal file.",
+            path = ?self.path,
+            error = %self.error,
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
@@ -1,7 +1,9 @@
 use super::prelude::{error_stage, error_type};
-use metrics::{counter, gauge};
+use std::time::Duration;
 
-use vector_core::{internal_event::InternalEvent, update_counter};
+use metrics::{counter, gauge, histogram};
+
+use vector_core::{internal_event::InternalEvent, metrics::Controller, update_counter};
 
 #[derive(Debug)]
 pub struct KafkaEventsReceived {
@@ -117,6 +119,165 @@ impl InternalEvent for KafkaStatisticsReceived<'_> {
     }
 }
 
+#[derive(Debug)]
+pub struct KafkaPartitionStatisticsReceived<'a> {
+    pub topic: &'a str,
+    pub partition: i32,
+    pub consumer_lag: i64,
+    pub fetched_messages: u64,
+    pub fetched_bytes: u64,
+}
+
+impl InternalEvent for KafkaPartitionStatisticsReceived<'_> {
+    fn emit(self) {
+        // The lag is only known for the partitions consumed, and `-1` otherwise.
+        if self.consumer_lag >= 0 {
+            gauge!(
+                "kafka_consumer_lag", self.consumer_lag as f64,
+                "topic_id" => self.topic.to_owned(),
+                "partition_id" => self.partition.to_string(),
+            );
+        }
+        if self.fetched_messages > 0 {
+            counter!(
+                "kafka_consumer_fetched_messages_total", self.fetched_messages,
+                "topic_id" => self.topic.to_owned(),
+                "partition_id" => self.partition.to_string(),
+            );
+        }
+        if self.fetched_bytes > 0 {
+            counter!(
+                "kafka_consumer_fetched_messages_bytes_total", self.fetched_bytes,
+                "topic_id" => self.topic.to_owned(),
+                "partition_id" => self.partition.to_string(),
+            );
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaPartitionRevoked<'a> {
+    pub component_id: Option<&'a str>,
+    pub topic: &'a str,
+    pub partition: i32,
+}
+
+impl InternalEvent for KafkaPartitionRevoked<'_> {
+    fn emit(self) {
+        // The consumer the partition is assigned to now reports its lag.
+        if let Ok(controller) = Controller::get() {
+            let partition = self.partition.to_string();
+            let mut tags = vec![
+                ("topic_id", self.topic),
+                ("partition_id", partition.as_str()),
+            ];
+            if let Some(component_id) = self.component_id {
+                tags.push(("component_id", component_id));
+            }
+            controller.remove_series("kafka_consumer_lag", &tags);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaCoordinatorStatisticsReceived {
+    pub rtt: Duration,
+}
+
+impl InternalEvent for KafkaCoordinatorStatisticsReceived {
+    fn emit(self) {
+        histogram!("kafka_consumer_coordinator_rtt_seconds", self.rtt);
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaRebalanced {
+    pub kind: &'static str,
+    pub partitions: usize,
+}
+
+impl InternalEvent for KafkaRebalanced {
+    fn emit(self) {
+        debug!(
+            message = "Consumer group rebalanced.",
+            kind = self.kind,
+            partitions = %self.partitions,
+        );
+        counter!("kafka_consumer_rebalances_total", 1, "kind" => self.kind);
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaRebalanceError<'a, E> {
+    pub error: &'a E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for KafkaRebalanceError<'_, E> {
+    fn emit(self) {
+        error!(
+            message = "Consumer group rebalance failed.",
+            error = %self.error,
+            error_code = "kafka_rebalance",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "kafka_rebalance",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+        counter!("kafka_consumer_rebalances_total", 1, "kind" => "error");
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaStartOffsetsError {
+    pub error: rdkafka::error::KafkaError,
+}
+
+impl InternalEvent for KafkaStartOffsetsError {
+    fn emit(self) {
+        error!(
+            message = "Unable to seek assigned partitions to the offsets at the start timestamp.",
+            error = %self.error,
+            error_code = "kafka_start_offsets",
+            error_type = error_type::READER_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "kafka_start_offsets",
+            "error_type" => error_type::READER_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaOffsetCommitError {
+    pub error: rdkafka::error::KafkaError,
+}
+
+impl InternalEvent for KafkaOffsetCommitError {
+    fn emit(self) {
+        error!(
+            message = "Unable to commit consumer offsets.",
+            error = %self.error,
+            error_code = "kafka_offset_commit",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "kafka_offset_commit",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
+
 pub struct KafkaHeaderExtractionError<'a> {
     pub header_field: &'a str,
 }
@@ -140,3 +301,90 @@ impl InternalEvent for KafkaHeaderExtractionError<'_> {
         counter!("kafka_header_extraction_failures_total", 1);
     }
 }
+
+#[derive(Debug)]
+pub struct KafkaTransactionAborted<'a> {
+    pub error: &'a rdkafka::error::KafkaError,
+    pub count: usize,
+}
+
+impl InternalEvent for KafkaTransactionAborted<'_> {
+    fn emit(self) {
+        warn!(
+            message = "Transaction aborted, retrying.",
+            error = %self.error,
+            count = %self.count,
+            error_code = "transaction_aborted",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "transaction_aborted",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+        counter!("kafka_transactions_aborted_total", 1);
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaTransactionRejected<'a> {
+    pub error: &'a rdkafka::error::KafkaError,
+    pub count: usize,
+}
+
+impl InternalEvent for KafkaTransactionRejected<'_> {
+    fn emit(self) {
+        error!(
+            message = "Transaction failed permanently, rejecting events.",
+            error = %self.error,
+            count = %self.count,
+            error_code = "transaction_rejected",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "transaction_rejected",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+        counter!(
+            "component_discarded_events_total", self.count as u64,
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct KafkaProducerFenced<'a> {
+    pub error: &'a rdkafka::error::KafkaError,
+    pub reinitialize: bool,
+}
+
+impl InternalEvent for KafkaProducerFenced<'_> {
+    fn emit(self) {
+        let message = if self.reinitialize {
+            "Transactional producer failed, creating a new one."
+        } else {
+            "Transactional producer failed, stopping the sink."
+        };
+        error!(
+            message,
+            error = %self.error,
+            error_code = "producer_fenced",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "producer_fenced",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -25,6 +25,8 @@ mod aws_sqs;
 #[cfg(any(feature = "sinks-azure_blob", feature = "sinks-datadog_archives"))]
 pub(crate) mod azure_blob;
 mod batch;
+#[cfg(feature = "sinks-clickhouse")]
+mod clickhouse;
 #[cfg(feature = "transforms-coercer")]
 mod coercer;
 mod common;
@@ -60,6 +62,8 @@ mod geoip;
 mod heartbeat;
 mod http;
 pub mod http_client;
+#[cfg(feature = "sources-http_client")]
+mod http_client_source;
 #[cfg(feature = "sources-internal_logs")]
 mod internal_logs;
 #[cfg(all(unix, feature = "sources-journald"))]
@@ -85,6 +89,8 @@ mod metric_to_log;
 mod mongodb_metrics;
 #[cfg(any(feature = "sources-nats", feature = "sinks-nats"))]
 mod nats;
+#[cfg(feature = "sources-netflow")]
+mod netflow;
 #[cfg(feature = "sources-nginx_metrics")]
 mod nginx_metrics;
 mod open;
@@ -101,6 +107,8 @@ mod open;
     feature = "transforms-tokenizer",
 ))]
 mod parser;
+#[cfg(feature = "sinks-postgres")]
+mod postgres;
 #[cfg(feature = "sources-postgresql_metrics")]
 mod postgresql_metrics;
 mod process;
@@ -119,6 +127,8 @@ mod rename_fields;
 mod sample;
 #[cfg(feature = "sinks-sematext")]
 mod sematext_metrics;
+#[cfg(feature = "sources-snmp_trap")]
+mod snmp_trap;
 mod socket;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 mod splunk_hec;
@@ -137,6 +147,8 @@ mod throttle;
 mod udp;
 mod unix;
 mod vector;
+#[cfg(any(feature = "sources-websocket", feature = "sinks-websocket"))]
+mod websocket;
 
 #[cfg(any(
     feature = "sources-file",
@@ -176,6 +188,8 @@ pub(crate) use self::aws_ecs_metrics::*;
 pub(crate) use self::aws_kinesis_firehose::*;
 #[cfg(any(feature = "sources-aws_s3", feature = "sources-aws_sqs",))]
 pub(crate) use self::aws_sqs::*;
+#[cfg(feature = "sinks-clickhouse")]
+pub(crate) use self::clickhouse::*;
 #[cfg(feature = "transforms-coercer")]
 pub(crate) use self::coercer::*;
 #[cfg(feature = "transforms-concat")]
@@ -218,6 +232,8 @@ pub(crate) use self::geoip::*;
     feature = "sources-aws_ecs_metrics",
 ))]
 pub(crate) use self::http::*;
+#[cfg(feature = "sources-http_client")]
+pub(crate) use self::http_client_source::*;
 #[cfg(feature = "sources-internal_logs")]
 pub(crate) use self::internal_logs::*;
 #[cfg(all(unix, feature = "sources-journald"))]
@@ -242,6 +258,8 @@ pub(crate) use self::lua::*;
 pub(crate) use self::metric_to_log::*;
 #[cfg(any(feature = "sources-nats", feature = "sinks-nats"))]
 pub(crate) use self::nats::*;
+#[cfg(feature = "sources-netflow")]
+pub(crate) use self::netflow::*;
 #[cfg(feature = "sources-nginx_metrics")]
 pub(crate) use self::nginx_metrics::*;
 #[cfg(any(
@@ -257,6 +275,8 @@ pub(crate) use self::nginx_metrics::*;
     feature = "transforms-tokenizer",
 ))]
 pub(crate) use self::parser::*;
+#[cfg(feature = "sinks-postgres")]
+pub(crate) use self::postgres::*;
 #[cfg(feature = "sources-postgresql_metrics")]
 pub(crate) use self::postgresql_metrics::*;
 #[cfg(any(feature = "sources-prometheus", feature = "sinks-prometheus"))]
@@ -277,6 +297,8 @@ pub(crate) use self::rename_fields::*;
 pub(crate) use self::sample::*;
 #[cfg(feature = "sinks-sematext")]
 pub(crate) use self::sematext_metrics::*;
+#[cfg(feature = "sources-snmp_trap")]
+pub(crate) use self::snmp_trap::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 pub(crate) use self::splunk_hec::*;
 #[cfg(feature = "sinks-statsd")]
@@ -306,6 +328,8 @@ pub(crate) use self::throttle::*;
 pub(crate) use self::unix::*;
 #[cfg(feature = "sources-vector")]
 pub(crate) use self::vector::*;
+#[cfg(any(feature = "sources-websocket", feature = "sinks-websocket"))]
+pub(crate) use self::websocket::*;
 #[cfg(windows)]
 pub(crate) use self::windows::*;
 pub(crate) use self::{
@@ -0,0 +1,129 @@
+use std::net::SocketAddr;
+
+use super::prelude::{error_stage, error_type};
+use flowmsg_parser::FlowParserError;
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+enum NetflowSocketErrorType {
+    Bind,
+    Read,
+}
+
+#[derive(Debug)]
+pub struct NetflowSocketError {
+    r#type: NetflowSocketErrorType,
+    pub error: std::io::Error,
+}
+
+impl NetflowSocketError {
+    pub const fn bind(error: std::io::Error) -> Self {
+        Self {
+            r#type: NetflowSocketErrorType::Bind,
+            error,
+        }
+    }
+
+    pub const fn read(error: std::io::Error) -> Self {
+        Self {
+            r#type: NetflowSocketErrorType::Read,
+            error,
+        }
+    }
+}
+
+impl InternalEvent for NetflowSocketError {
+    fn emit(self) {
+        let (message, error_code) = match self.r#type {
+            NetflowSocketErrorType::Bind => (
+                "Failed to bind to UDP listener socket.",
+                "failed_udp_binding",
+            ),
+            NetflowSocketErrorType::Read => ("Failed to read UDP datagram.", "failed_udp_datagram"),
+        };
+        error!(
+            message = %message,
+            error = %self.error,
+            error_code = %error_code,
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => error_code,
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct NetflowParseError {
+    pub error: FlowParserError,
+    pub peer: SocketAddr,
+}
+
+impl NetflowParseError {
+    const fn error_code(&self) -> &'static str {
+        match self.error {
+            FlowParserError::UnexpectedEnd { .. } => "unexpected_end",
+            FlowParserError::UnsupportedVersion { .. } => "unsupported_version",
+            FlowParserError::InvalidLength { .. } => "invalid_length",
+            FlowParserError::InvalidValue { .. } => "invalid_value",
+            FlowParserError::InvalidTemplate { .. } => "invalid_template",
+        }
+    }
+}
+
+impl InternalEvent for NetflowParseError {
+    fn emit(self) {
+        let error_code = self.error_code();
+        error!(
+            message = "Discarding flow packet.",
+            error = %self.error,
+            error_code = %error_code,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+            peer = %self.peer,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => error_code,
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+/// The records of a data set were discarded because its template wasn't
+/// received yet from the exporter.
+#[derive(Debug)]
+pub struct NetflowMissingTemplate {
+    pub template_id: u16,
+    pub byte_size: usize,
+    pub peer: SocketAddr,
+}
+
+impl InternalEvent for NetflowMissingTemplate {
+    fn emit(self) {
+        warn!(
+            message = "Discarding records without a template.",
+            template_id = %self.template_id,
+            byte_size = %self.byte_size,
+            error_code = "missing_template",
+            error_type = error_type::CONDITION_FAILED,
+            stage = error_stage::PROCESSING,
+            peer = %self.peer,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "missing_template",
+            "error_type" => error_type::CONDITION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use std::fmt::Display;
+
+use super::prelude::{error_stage, error_type};
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct PostgresEventsSent {
+    pub count: usize,
+    pub byte_size: usize,
+}
+
+impl InternalEvent for PostgresEventsSent {
+    fn emit(self) {
+        trace!(message = "Events sent.", count = %self.count, byte_size = %self.byte_size);
+        counter!("component_sent_events_total", self.count as u64);
+        counter!("component_sent_event_bytes_total", self.byte_size as u64);
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresEventEncodingError<'a, E> {
+    pub table: &'a str,
+    pub error: E,
+}
+
+impl<'a, E: Display> InternalEvent for PostgresEventEncodingError<'a, E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to convert event field to the column type; rejecting event.",
+            table = %self.table,
+            error = %self.error,
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+            rate_limit_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct PostgresSendError<'a, E> {
+    pub error: E,
+    /// The SQLSTATE code returned by the server, if any.
+    pub code: Option<&'a str>,
+}
+
+impl<'a, E: Display> InternalEvent for PostgresSendError<'a, E> {
+    fn emit(self) {
+        let error_code = self.code.unwrap_or_default().to_string();
+        error!(
+            message = "Failed to write rows.",
+            error = %self.error,
+            error_code = %error_code,
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::SENDING,
+            rate_limit_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => error_code,
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::net::SocketAddr;
+
+use super::prelude::{error_stage, error_type};
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+use crate::sources::snmp_trap::TrapError;
+
+#[derive(Debug)]
+enum SnmpTrapSocketErrorType {
+    Bind,
+    Read,
+}
+
+#[derive(Debug)]
+pub struct SnmpTrapSocketError {
+    r#type: SnmpTrapSocketErrorType,
+    pub error: std::io::Error,
+}
+
+impl SnmpTrapSocketError {
+    pub const fn bind(error: std::io::Error) -> Self {
+        Self {
+            r#type: SnmpTrapSocketErrorType::Bind,
+            error,
+        }
+    }
+
+    pub const fn read(error: std::io::Error) -> Self {
+        Self {
+            r#type: SnmpTrapSocketErrorType::Read,
+            error,
+        }
+    }
+}
+
+impl InternalEvent for SnmpTrapSocketError {
+    fn emit(self) {
+        let (message, error_code) = match self.r#type {
+            SnmpTrapSocketErrorType::Bind => (
+                "Failed to bind to UDP listener socket.",
+                "failed_udp_binding",
+            ),
+            SnmpTrapSocketErrorType::Read => {
+                ("Failed to read UDP datagram.", "failed_udp_datagram")
+            }
+        };
+        error!(
+            message = %message,
+            error = %self.error,
+            error_code = %error_code,
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => error_code,
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SnmpTrapInvalidMessage<'a> {
+    pub error: &'a TrapError,
+    pub peer: SocketAddr,
+}
+
+impl<'a> SnmpTrapInvalidMessage<'a> {
+    const fn error_code(&self) -> &'static str {
+        match self.error {
+            TrapError::InvalidMessage { .. } => "invalid_message",
+            TrapError::UnsupportedVersion { .. } => "unsupported_version",
+            TrapError::UnsupportedPdu { .. } => "unsupported_pdu",
+            TrapError::CommunityNotAllowed { .. } => "community_not_allowed",
+            TrapError::Usm { .. } => "security_check_failed",
+        }
+    }
+
+    const fn error_type(&self) -> &'static str {
+        match self.error {
+            TrapError::CommunityNotAllowed { .. } | TrapError::Usm { .. } => {
+                error_type::CONDITION_FAILED
+            }
+            _ => error_type::PARSER_FAILED,
+        }
+    }
+}
+
+impl<'a> InternalEvent for SnmpTrapInvalidMessage<'a> {
+    fn emit(self) {
+        let error_code = self.error_code();
+        let error_type = self.error_type();
+        error!(
+            message = "Discarding SNMP message.",
+            error = %self.error,
+            error_code = %error_code,
+            error_type = %error_type,
+            stage = error_stage::PROCESSING,
+            peer = %self.peer,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => error_code,
+            "error_type" => error_type,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct SnmpTrapInformResponseError {
+    pub error: std::io::Error,
+    pub peer: SocketAddr,
+}
+
+impl InternalEvent for SnmpTrapInformResponseError {
+    fn emit(self) {
+        error!(
+            message = "Failed to acknowledge SNMP inform.",
+            error = %self.error,
+            error_code = "inform_response",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::SENDING,
+            peer = %self.peer,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "inform_response",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    fmt::{Debug, Display},
+    net::SocketAddr,
+};
+
+use super::prelude::{error_stage, error_type};
+use metrics::counter;
+use vector_core::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct WebSocketConnectionEstablished {
+    pub peer_addr: Option<SocketAddr>,
+}
+
+impl InternalEvent for WebSocketConnectionEstablished {
+    fn emit(self) {
+        if let Some(peer_addr) = self.peer_addr {
+            debug!(message = "Connected.", %peer_addr);
+        } else {
+            debug!(message = "Connected.", peer_addr = "unknown");
+        }
+        counter!("connection_established_total", 1, "mode" => "websocket");
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketConnectionError<E> {
+    pub error: E,
+}
+
+impl<E: std::error::Error> InternalEvent for WebSocketConnectionError<E> {
+    fn emit(self) {
+        error!(
+            message = "Unable to connect.",
+            error = %self.error,
+            error_code = "failed_connecting",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_connecting",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketConnectionShutdown {
+    pub peer_addr: Option<SocketAddr>,
+}
+
+impl InternalEvent for WebSocketConnectionShutdown {
+    fn emit(self) {
+        if let Some(peer_addr) = self.peer_addr {
+            debug!(message = "WebSocket connection closed.", %peer_addr);
+        } else {
+            debug!(
+                message = "WebSocket connection closed.",
+                peer_addr = "unknown"
+            );
+        }
+        counter!("connection_shutdown_total", 1, "mode" => "websocket");
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketReceiveError<E> {
+    pub error: E,
+    pub peer_addr: Option<SocketAddr>,
+}
+
+impl<E: std::error::Error> InternalEvent for WebSocketReceiveError<E> {
+    fn emit(self) {
+        error!(
+            message = "Error receiving WebSocket message.",
+            error = %self.error,
+            error_code = "failed_receiving",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::RECEIVING,
+            peer_addr = ?self.peer_addr,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_receiving",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::RECEIVING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketSendError<E> {
+    pub error: E,
+}
+
+impl<E: std::error::Error> InternalEvent for WebSocketSendError<E> {
+    fn emit(self) {
+        error!(
+            message = "Error sending WebSocket message.",
+            error = %self.error,
+            error_code = "failed_sending",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "failed_sending",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
+
+/// An event was sent `attempts` times without success, and is dropped.
+#[derive(Debug)]
+pub struct WebSocketSendAttemptsExhausted {
+    pub attempts: usize,
+}
+
+impl InternalEvent for WebSocketSendAttemptsExhausted {
+    fn emit(self) {
+        error!(
+            message = "Failed to send event; dropping event.",
+            attempts = %self.attempts,
+            error_code = "send_attempts_exhausted",
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "send_attempts_exhausted",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_code" => "send_attempts_exhausted",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
+
+#[derive(Debug)]
+pub struct WebSocketEventEncodingError<E> {
+    pub error: E,
+}
+
+impl<E: Display> InternalEvent for WebSocketEventEncodingError<E> {
+    fn emit(self) {
+        error!(
+            message = "Failed to encode event; dropping event.",
+            error = %self.error,
+            error_type = error_type::ENCODER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+        counter!(
+            "component_discarded_events_total", 1,
+            "error_type" => error_type::ENCODER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        );
+    }
+}
+
+/// The server didn't answer a ping in time, so the connection is considered
+/// lost.
+#[derive(Debug)]
+pub struct WebSocketPongTimeout;
+
+impl InternalEvent for WebSocketPongTimeout {
+    fn emit(self) {
+        error!(
+            message = "No pong received from the WebSocket server, reconnecting.",
+            error_code = "pong_timeout",
+            error_type = error_type::CONNECTION_FAILED,
+            stage = error_stage::SENDING,
+            internal_log_rate_secs = 10,
+        );
+        counter!(
+            "component_errors_total", 1,
+            "error_code" => "pong_timeout",
+            "error_type" => error_type::CONNECTION_FAILED,
+            "stage" => error_stage::SENDING,
+        );
+    }
+}
@@ -1,10 +1,30 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
 
-use rdkafka::{consumer::ConsumerContext, ClientConfig, ClientContext, Statistics};
+use once_cell::sync::{Lazy, OnceCell};
+use rdkafka::{
+    client::NativeClient,
+    consumer::{Consumer, ConsumerContext, ConsumerGroupMetadata, Rebalance, StreamConsumer},
+    error::{KafkaError as RdKafkaError, KafkaResult},
+    types::{RDKafkaErrorCode, RDKafkaRespErr},
+    ClientConfig, ClientContext, Statistics, TopicPartitionList,
+};
 use serde::{Deserialize, Serialize};
 use snafu::Snafu;
+use tracing::Span;
 
-use crate::{internal_events::KafkaStatisticsReceived, tls::TlsOptions};
+use crate::{
+    internal_events::{
+        KafkaCoordinatorStatisticsReceived, KafkaOffsetCommitError, KafkaPartitionRevoked,
+        KafkaPartitionStatisticsReceived, KafkaRebalanceError, KafkaRebalanced,
+        KafkaStatisticsReceived,
+    },
+    tls::TlsOptions,
+};
 
 #[derive(Debug, Snafu)]
 enum KafkaError {
@@ -107,3 +127,288 @@ impl ClientContext for KafkaStatisticsContext {
 }
 
 impl ConsumerContext for KafkaStatisticsContext {}
+
+type AssignFn = Box<dyn Fn(&mut TopicPartitionList) + Send + Sync>;
+
+/// Client context of the consumers, which also reports the lag and fetches of
+/// the partitions, the rebalances of the group and the offset commits.
+///
+/// The callbacks are called from the threads of librdkafka, so the metrics are
+/// emitted in the span set with [`KafkaConsumerContext::set_span`] to be
+/// attributed to the component.
+#[derive(Default)]
+pub(crate) struct KafkaConsumerContext {
+    span: OnceCell<Span>,
+    /// The component the metrics are emitted for, to remove the series of the
+    /// partitions it no longer consumes.
+    component_id: OnceCell<String>,
+    /// The messages and bytes fetched from each partition, as of the previous
+    /// statistics.
+    fetched: Mutex<HashMap<(String, i32), (u64, u64)>>,
+    /// Called with the partitions assigned by the group before they are
+    /// assigned, to set the offsets they start at.
+    on_assign: OnceCell<AssignFn>,
+}
+
+impl KafkaConsumerContext {
+    pub(crate) fn set_span(&self, span: Span) {
+        let _ = self.span.set(span);
+    }
+
+    pub(crate) fn set_component_id(&self, component_id: &str) {
+        let _ = self.component_id.set(component_id.to_owned());
+    }
+
+    pub(crate) fn set_on_assign(
+        &self,
+        on_assign: impl Fn(&mut TopicPartitionList) + Send + Sync + 'static,
+    ) {
+        let _ = self.on_assign.set(Box::new(on_assign));
+    }
+
+    fn in_span(&self, f: impl FnOnce()) {
+        match self.span.get() {
+            Some(span) => span.in_scope(f),
+            None => f(),
+        }
+    }
+}
+
+impl ClientContext for KafkaConsumerContext {
+    fn stats(&self, statistics: Statistics) {
+        self.in_span(|| {
+            emit!(KafkaStatisticsReceived {
+                statistics: &statistics
+            });
+
+            let mut fetched = self.fetched.lock().expect("poisoned lock");
+            for (topic, topic_statistics) in &statistics.topics {
+                for (partition, partition_statistics) in &topic_statistics.partitions {
+                    // The internal partition `-1` holds the messages of
+                    // unknown partitions.
+                    if *partition < 0 {
+                        continue;
+                    }
+                    let current = (
+                        partition_statistics.rxmsgs as u64,
+                        partition_statistics.rxbytes as u64,
+                    );
+                    let previous = fetched
+                        .insert((topic.clone(), *partition), current)
+                        .unwrap_or_default();
+                    emit!(KafkaPartitionStatisticsReceived {
+                        topic,
+                        partition: *partition,
+                        consumer_lag: partition_statistics.consumer_lag,
+                        fetched_messages: counter_delta(previous.0, current.0),
+                        fetched_bytes: counter_delta(previous.1, current.1),
+                    });
+                }
+            }
+
+            // The offsets are committed to the group coordinator, which
+            // librdkafka reports as a broker of its own.
+            let coordinator_rtt = statistics
+                .brokers
+                .values()
+                .filter(|broker| broker.name.starts_with("GroupCoordinator"))
+                .find_map(|broker| broker.rtt.as_ref())
+                .filter(|rtt| rtt.cnt > 0);
+            if let Some(rtt) = coordinator_rtt {
+                emit!(KafkaCoordinatorStatisticsReceived {
+                    rtt: Duration::from_micros(rtt.avg.max(0) as u64),
+                });
+            }
+        });
+    }
+}
+
+impl ConsumerContext for KafkaConsumerContext {
+    fn rebalance(
+        &self,
+        native_client: &NativeClient,
+        err: RDKafkaRespErr,
+        partitions: &mut TopicPartitionList,
+    ) {
+        if err == RDKafkaRespErr::RD_KAFKA_RESP_ERR__ASSIGN_PARTITIONS {
+            if let Some(on_assign) = self.on_assign.get() {
+                self.in_span(|| on_assign(partitions));
+            }
+        }
+        DefaultRebalance(self).rebalance(native_client, err, partitions);
+    }
+
+    fn post_rebalance(&self, rebalance: &Rebalance) {
+        self.in_span(|| match rebalance {
+            Rebalance::Assign(partitions) => {
+                emit!(KafkaRebalanced {
+                    kind: "assign",
+                    partitions: partitions.count(),
+                });
+            }
+            Rebalance::Revoke(partitions) => {
+                emit!(KafkaRebalanced {
+                    kind: "revoke",
+                    partitions: partitions.count(),
+                });
+                let mut fetched = self.fetched.lock().expect("poisoned lock");
+                for elem in partitions.elements() {
+                    fetched.remove(&(elem.topic().to_owned(), elem.partition()));
+                    emit!(KafkaPartitionRevoked {
+                        component_id: self.component_id.get().map(String::as_str),
+                        topic: elem.topic(),
+                        partition: elem.partition(),
+                    });
+                }
+            }
+            Rebalance::Error(error) => emit!(KafkaRebalanceError { error }),
+        });
+    }
+
+    fn commit_callback(&self, result: KafkaResult<()>, _offsets: &TopicPartitionList) {
+        match result {
+            // Nothing was consumed since the previous commit.
+            Ok(()) | Err(RdKafkaError::ConsumerCommit(RDKafkaErrorCode::NoOffset)) => {}
+            Err(error) => self.in_span(|| emit!(KafkaOffsetCommitError { error })),
+        }
+    }
+}
+
+/// Assigns the partitions with the default strategy of librdkafka, which
+/// starts them at the offsets set in the list, and reports the rebalance to
+/// the context.
+struct DefaultRebalance<'a>(&'a KafkaConsumerContext);
+
+impl ClientContext for DefaultRebalance<'_> {}
+
+impl ConsumerContext for DefaultRebalance<'_> {
+    fn post_rebalance(&self, rebalance: &Rebalance) {
+        self.0.post_rebalance(rebalance);
+    }
+}
+
+/// The increase of a counter of librdkafka, which restarts from zero when a
+/// partition is assigned again.
+fn counter_delta(previous: u64, current: u64) -> u64 {
+    if current >= previous {
+        current - previous
+    } else {
+        current
+    }
+}
+
+/// The consumers of the running `kafka` sources that leave the commit of their
+/// offsets to a transactional `kafka` sink, by consumer group.
+static GROUP_CONSUMERS: Lazy<Mutex<HashMap<String, Weak<StreamConsumer<KafkaConsumerContext>>>>> =
+    Lazy::new(Default::default);
+
+/// Makes the group of the consumer available to the transactional sinks
+/// committing its offsets, for as long as the consumer is alive.
+pub(crate) fn register_group_consumer(
+    group_id: &str,
+    consumer: &Arc<StreamConsumer<KafkaConsumerContext>>,
+) {
+    let mut consumers = GROUP_CONSUMERS.lock().expect("poisoned lock");
+    consumers.retain(|_, consumer| consumer.strong_count() > 0);
+    consumers.insert(group_id.to_owned(), Arc::downgrade(consumer));
+}
+
+/// The metadata of the consumer of a source in its group, which changes every
+/// time the group rebalances. The brokers refuse the offsets sent to a
+/// transaction with the metadata of an older generation of the group.
+pub(crate) fn group_metadata(group_id: &str) -> Option<ConsumerGroupMetadata> {
+    let consumers = GROUP_CONSUMERS.lock().expect("poisoned lock");
+    consumers.get(group_id)?.upgrade()?.group_metadata()
+}
+
+#[cfg(test)]
+mod test {
+    use rdkafka::statistics::{Partition, Topic};
+
+    use super::*;
+    use crate::{
+        event::{Metric, MetricValue},
+        metrics::{self, Controller},
+    };
+
+    fn statistics(consumer_lag: i64, rxmsgs: u64, rxbytes: u64) -> Statistics {
+        let partition = |partition| Partition {
+            partition,
+            consumer_lag,
+            rxmsgs,
+            rxbytes,
+            ..Default::default()
+        };
+        let topic = Topic {
+            topic: "topic".into(),
+            partitions: HashMap::from([(0, partition(0)), (-1, partition(-1))]),
+            ..Default::default()
+        };
+        Statistics {
+            topics: HashMap::from([("topic".into(), topic)]),
+            ..Default::default()
+        }
+    }
+
+    fn series(name: &str) -> Vec<Metric> {
+        Controller::get()
+            .unwrap()
+            .capture_metrics()
+            .into_iter()
+            .filter(|metric| metric.name() == name)
+            .collect()
+    }
+
+    fn value(name: &str) -> f64 {
+        match series(name).as_slice() {
+            [metric] => match metric.value() {
+                MetricValue::Counter { value } | MetricValue::Gauge { value } => *value,
+                value => panic!("unexpected value {:?}", value),
+            },
+            series => panic!("expected a single series, got {:?}", series),
+        }
+    }
+
+    #[test]
+    fn counter_delta_restarts_from_zero() {
+        assert_eq!(counter_delta(0, 5), 5);
+        assert_eq!(counter_delta(5, 8), 3);
+        assert_eq!(counter_delta(8, 8), 0);
+        // The partition was assigned again.
+        assert_eq!(counter_delta(8, 2), 2);
+    }
+
+    #[test]
+    fn reports_partition_statistics() {
+        let _ = metrics::init_test();
+        let context = KafkaConsumerContext::default();
+
+        context.stats(statistics(3, 10, 100));
+        context.stats(statistics(2, 15, 150));
+
+        let lag = series("kafka_consumer_lag");
+        assert_eq!(lag.len(), 1, "the unknown partition is skipped");
+        assert!(lag[0].tag_matches("topic_id", "topic"));
+        assert!(lag[0].tag_matches("partition_id", "0"));
+        assert_eq!(value("kafka_consumer_lag"), 2.0);
+        assert_eq!(value("kafka_consumer_fetched_messages_total"), 15.0);
+        assert_eq!(value("kafka_consumer_fetched_messages_bytes_total"), 150.0);
+    }
+
+    #[test]
+    fn removes_lag_of_revoked_partitions() {
+        let _ = metrics::init_test();
+        let context = KafkaConsumerContext::default();
+        context.stats(statistics(3, 10, 100));
+
+        let mut partitions = TopicPartitionList::new();
+        partitions.add_partition("topic", 0);
+        context.post_rebalance(&Rebalance::Revoke(&partitions));
+        assert!(series("kafka_consumer_lag").is_empty());
+
+        // The counters of librdkafka restart when the partition is assigned again.
+        context.stats(statistics(1, 4, 40));
+        assert_eq!(value("kafka_consumer_lag"), 1.0);
+        assert_eq!(value("kafka_consumer_fetched_messages_total"), 14.0);
+    }
+}
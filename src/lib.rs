@@ -105,6 +105,8 @@ pub(crate) mod utilization;
 pub mod validate;
 #[cfg(windows)]
 pub mod vector_windows;
+#[cfg(any(feature = "sources-websocket", feature = "sinks-websocket"))]
+pub(crate) mod websocket;
 
 pub use source_sender::SourceSender;
 pub use vector_core::{event, metrics, schema, Error, Result};
@@ -0,0 +1,767 @@
+//! Conversion of events to the ClickHouse `RowBinary` and `Native` formats, driven by the
+//! schema of the table they are inserted into.
+
+use std::{
+    borrow::Cow,
+    convert::TryFrom,
+    net::{IpAddr, Ipv4Addr},
+};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use snafu::Snafu;
+
+use super::schema::{ColumnType, TableSchema};
+use crate::event::{LogEvent, Value};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum CoerceError {
+    #[snafu(display("Can't convert {} to {}.", kind, ty))]
+    Incompatible { kind: &'static str, ty: ColumnType },
+    #[snafu(display("Value out of range for {}.", ty))]
+    OutOfRange { ty: ColumnType },
+    #[snafu(display("Invalid {} value {:?}.", ty, value))]
+    Invalid { ty: ColumnType, value: String },
+}
+
+/// A value converted to the type of its column, ready to be written in either format.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+    Null,
+    /// A fixed width value, already in its little endian wire representation.
+    Scalar(Scalar),
+    /// A variable length value, written with its length as prefix.
+    Bytes(Bytes),
+    /// A value of a `FixedString` column, written as is.
+    Raw(Bytes),
+    Array(Vec<Cell>),
+    Map(Vec<(Cell, Cell)>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scalar {
+    buf: [u8; 16],
+    len: u8,
+}
+
+impl Scalar {
+    fn new(bytes: &[u8]) -> Self {
+        let mut buf = [0; 16];
+        buf[..bytes.len()].copy_from_slice(bytes);
+        Self {
+            buf,
+            len: bytes.len() as u8,
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len as usize]
+    }
+}
+
+impl Cell {
+    fn elements(&self) -> &[Cell] {
+        match self {
+            Cell::Array(elements) => elements,
+            _ => &[],
+        }
+    }
+
+    fn entries(&self) -> &[(Cell, Cell)] {
+        match self {
+            Cell::Map(entries) => entries,
+            _ => &[],
+        }
+    }
+
+    /// The value ClickHouse itself uses when a column is omitted.
+    pub fn default_for(ty: &ColumnType) -> Self {
+        match ty {
+            ColumnType::Nullable(_) => Cell::Null,
+            ColumnType::LowCardinality(inner) => Cell::default_for(inner),
+            ColumnType::Array(_) => Cell::Array(Vec::new()),
+            ColumnType::Map(_, _) => Cell::Map(Vec::new()),
+            ColumnType::String => Cell::Bytes(Bytes::new()),
+            ColumnType::FixedString(n) => Cell::Raw(Bytes::from(vec![0; *n])),
+            ColumnType::Enum8(variants) => {
+                let min = variants.iter().map(|(_, value)| *value).min();
+                Cell::Scalar(Scalar::new(&min.unwrap_or_default().to_le_bytes()))
+            }
+            ColumnType::Enum16(variants) => {
+                let min = variants.iter().map(|(_, value)| *value).min();
+                Cell::Scalar(Scalar::new(&min.unwrap_or_default().to_le_bytes()))
+            }
+            ty => Cell::Scalar(Scalar::new(&[0; 16][..scalar_width(ty)])),
+        }
+    }
+}
+
+fn scalar_width(ty: &ColumnType) -> usize {
+    match ty {
+        ColumnType::UInt8 | ColumnType::Int8 | ColumnType::Bool | ColumnType::Enum8(_) => 1,
+        ColumnType::UInt16 | ColumnType::Int16 | ColumnType::Date | ColumnType::Enum16(_) => 2,
+        ColumnType::UInt32
+        | ColumnType::Int32
+        | ColumnType::Float32
+        | ColumnType::Date32
+        | ColumnType::DateTime(_)
+        | ColumnType::Ipv4 => 4,
+        ColumnType::UInt64
+        | ColumnType::Int64
+        | ColumnType::Float64
+        | ColumnType::DateTime64(..) => 8,
+        ColumnType::Decimal { precision, .. } => decimal_width(*precision),
+        ColumnType::Uuid | ColumnType::Ipv6 => 16,
+        _ => 0,
+    }
+}
+
+const fn decimal_width(precision: u32) -> usize {
+    match precision {
+        0..=9 => 4,
+        10..=18 => 8,
+        _ => 16,
+    }
+}
+
+const fn kind_name(value: &Value) -> &'static str {
+    match value {
+        Value::Bytes(_) | Value::Regex(_) => "string",
+        Value::Integer(_) => "integer",
+        Value::Float(_) => "float",
+        Value::Boolean(_) => "boolean",
+        Value::Timestamp(_) => "timestamp",
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::Null => "null",
+    }
+}
+
+fn text(value: &Value) -> Option<Cow<'_, str>> {
+    match value {
+        Value::Bytes(bytes) => Some(String::from_utf8_lossy(bytes)),
+        Value::Regex(regex) => Some(Cow::Borrowed(regex.as_str())),
+        _ => None,
+    }
+}
+
+/// Converts a value to the given column type. Missing and null values of non nullable
+/// columns are replaced by the default value of the column type.
+pub fn coerce(value: Option<&Value>, ty: &ColumnType) -> Result<Cell, CoerceError> {
+    let value = match value {
+        None | Some(Value::Null) => return Ok(Cell::default_for(ty)),
+        Some(value) => value,
+    };
+    let incompatible = || CoerceError::Incompatible {
+        kind: kind_name(value),
+        ty: ty.clone(),
+    };
+
+    match ty {
+        ColumnType::Nullable(inner) | ColumnType::LowCardinality(inner) => {
+            coerce(Some(value), inner)
+        }
+        ColumnType::Array(inner) => match value {
+            Value::Array(values) => values
+                .iter()
+                .map(|value| coerce(Some(value), inner))
+                .collect::<Result<_, _>>()
+                .map(Cell::Array),
+            _ => Err(incompatible()),
+        },
+        ColumnType::Map(key_ty, value_ty) => match value {
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, value)| {
+                    let key = Value::from(key.as_str());
+                    Ok((coerce(Some(&key), key_ty)?, coerce(Some(value), value_ty)?))
+                })
+                .collect::<Result<_, _>>()
+                .map(Cell::Map),
+            _ => Err(incompatible()),
+        },
+        ColumnType::UInt8 => integer::<u8>(value, ty).map(|v| scalar(&v.to_le_bytes())),
+        ColumnType::UInt16 => integer::<u16>(value, ty).map(|v| scalar(&v.to_le_bytes())),
+        ColumnType::UInt32 => integer::<u32>(value, ty).map(|v| scalar(&v.to_le_bytes())),
+        ColumnType::UInt64 => integer::<u64>(value, ty).map(|v| scalar(&v.to_le_bytes())),
+        ColumnType::Int8 => integer::<i8>(value, ty).map(|v| scalar(&v.to_le_bytes())),
+        ColumnType::Int16 => integer::<i16>(value, ty).map(|v| scalar(&v.to_le_bytes())),
+        ColumnType::Int32 => integer::<i32>(value, ty).map(|v| scalar(&v.to_le_bytes())),
+        ColumnType::Int64 => integer::<i64>(value, ty).map(|v| scalar(&v.to_le_bytes())),
+        ColumnType::Float32 => float(value, ty).map(|v| scalar(&(v as f32).to_le_bytes())),
+        ColumnType::Float64 => float(value, ty).map(|v| scalar(&v.to_le_bytes())),
+        ColumnType::Bool => {
+            let flag = match value {
+                Value::Boolean(flag) => *flag,
+                Value::Integer(0) => false,
+                Value::Integer(1) => true,
+                _ => match text(value).as_deref() {
+                    Some("true" | "1") => true,
+                    Some("false" | "0") => false,
+                    Some(other) => return Err(invalid(ty, other)),
+                    None => return Err(incompatible()),
+                },
+            };
+            Ok(scalar(&[flag as u8]))
+        }
+        ColumnType::String => Ok(Cell::Bytes(value.coerce_to_bytes())),
+        ColumnType::FixedString(n) => {
+            let bytes = value.coerce_to_bytes();
+            if bytes.len() > *n {
+                return Err(CoerceError::OutOfRange { ty: ty.clone() });
+            }
+            let mut padded = BytesMut::with_capacity(*n);
+            padded.put_slice(&bytes);
+            padded.put_bytes(0, n - bytes.len());
+            Ok(Cell::Raw(padded.freeze()))
+        }
+        ColumnType::Uuid => {
+            let input = text(value).ok_or_else(incompatible)?;
+            let uuid = uuid::Uuid::parse_str(&input).map_err(|_| invalid(ty, &input))?;
+            let uuid = uuid.as_u128();
+            // ClickHouse stores the two halves of a UUID as little endian integers.
+            let mut buf = [0; 16];
+            buf[..8].copy_from_slice(&((uuid >> 64) as u64).to_le_bytes());
+            buf[8..].copy_from_slice(&(uuid as u64).to_le_bytes());
+            Ok(scalar(&buf))
+        }
+        ColumnType::Date => {
+            let days = timestamp(value, ty)?
+                .timestamp()
+                .div_euclid(SECONDS_PER_DAY);
+            let days = u16::try_from(days).map_err(|_| out_of_range(ty))?;
+            Ok(scalar(&days.to_le_bytes()))
+        }
+        ColumnType::Date32 => {
+            let days = timestamp(value, ty)?
+                .timestamp()
+                .div_euclid(SECONDS_PER_DAY);
+            let days = i32::try_from(days).map_err(|_| out_of_range(ty))?;
+            Ok(scalar(&days.to_le_bytes()))
+        }
+        ColumnType::DateTime(_) => {
+            let seconds = timestamp(value, ty)?.timestamp();
+            let seconds = u32::try_from(seconds).map_err(|_| out_of_range(ty))?;
+            Ok(scalar(&seconds.to_le_bytes()))
+        }
+        ColumnType::DateTime64(precision, _) => {
+            let timestamp = timestamp(value, ty)?;
+            let ticks = i128::from(timestamp.timestamp()) * 10i128.pow(*precision)
+                + i128::from(timestamp.timestamp_subsec_nanos() / 10u32.pow(9 - precision));
+            let ticks = i64::try_from(ticks).map_err(|_| out_of_range(ty))?;
+            Ok(scalar(&ticks.to_le_bytes()))
+        }
+        ColumnType::Decimal { precision, scale } => {
+            let unscaled = decimal(value, *scale, ty)?;
+            if unscaled.unsigned_abs() >= 10u128.pow(*precision) {
+                return Err(out_of_range(ty));
+            }
+            let bytes = unscaled.to_le_bytes();
+            Ok(scalar(&bytes[..decimal_width(*precision)]))
+        }
+        ColumnType::Ipv4 => {
+            let input = text(value).ok_or_else(incompatible)?;
+            let address = input.parse::<Ipv4Addr>().map_err(|_| invalid(ty, &input))?;
+            Ok(scalar(&u32::from(address).to_le_bytes()))
+        }
+        ColumnType::Ipv6 => {
+            let input = text(value).ok_or_else(incompatible)?;
+            let address = match input.parse::<IpAddr>().map_err(|_| invalid(ty, &input))? {
+                IpAddr::V4(address) => address.to_ipv6_mapped(),
+                IpAddr::V6(address) => address,
+            };
+            Ok(scalar(&address.octets()))
+        }
+        ColumnType::Enum8(variants) => {
+            enum_value(value, variants, ty).map(|v| scalar(&v.to_le_bytes()))
+        }
+        ColumnType::Enum16(variants) => {
+            enum_value(value, variants, ty).map(|v| scalar(&v.to_le_bytes()))
+        }
+    }
+}
+
+fn scalar(bytes: &[u8]) -> Cell {
+    Cell::Scalar(Scalar::new(bytes))
+}
+
+fn invalid(ty: &ColumnType, value: &str) -> CoerceError {
+    CoerceError::Invalid {
+        ty: ty.clone(),
+        value: value.to_string(),
+    }
+}
+
+fn out_of_range(ty: &ColumnType) -> CoerceError {
+    CoerceError::OutOfRange { ty: ty.clone() }
+}
+
+fn integer<T: TryFrom<i128>>(value: &Value, ty: &ColumnType) -> Result<T, CoerceError> {
+    let wide = match value {
+        Value::Integer(integer) => i128::from(*integer),
+        Value::Float(float) if float.fract() == 0.0 && float.abs() < 1e38 => {
+            float.into_inner() as i128
+        }
+        Value::Boolean(flag) => i128::from(*flag),
+        Value::Timestamp(timestamp) => i128::from(timestamp.timestamp()),
+        _ => {
+            let input = text(value).ok_or(CoerceError::Incompatible {
+                kind: kind_name(value),
+                ty: ty.clone(),
+            })?;
+            input.trim().parse().map_err(|_| invalid(ty, &input))?
+        }
+    };
+    T::try_from(wide).map_err(|_| out_of_range(ty))
+}
+
+fn float(value: &Value, ty: &ColumnType) -> Result<f64, CoerceError> {
+    match value {
+        Value::Integer(integer) => Ok(*integer as f64),
+        Value::Float(float) => Ok(float.into_inner()),
+        Value::Boolean(flag) => Ok(f64::from(u8::from(*flag))),
+        _ => {
+            let input = text(value).ok_or(CoerceError::Incompatible {
+                kind: kind_name(value),
+                ty: ty.clone(),
+            })?;
+            input.trim().parse().map_err(|_| invalid(ty, &input))
+        }
+    }
+}
+
+/// Reads a point in time. Numbers are seconds since the Unix epoch and strings without a
+/// time zone are in UTC.
+fn timestamp(value: &Value, ty: &ColumnType) -> Result<DateTime<Utc>, CoerceError> {
+    match value {
+        Value::Timestamp(timestamp) => Ok(*timestamp),
+        Value::Integer(seconds) => Utc
+            .timestamp_opt(*seconds, 0)
+            .single()
+            .ok_or_else(|| out_of_range(ty)),
+        Value::Float(seconds) => {
+            let nanos = (seconds.fract() * 1e9).round() as u32;
+            Utc.timestamp_opt(seconds.floor() as i64, nanos)
+                .single()
+                .ok_or_else(|| out_of_range(ty))
+        }
+        _ => {
+            let input = text(value).ok_or(CoerceError::Incompatible {
+                kind: kind_name(value),
+                ty: ty.clone(),
+            })?;
+            let input = input.trim();
+            DateTime::parse_from_rfc3339(input)
+                .map(|timestamp| timestamp.with_timezone(&Utc))
+                .or_else(|_| {
+                    NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S%.f")
+                        .map(|naive| Utc.from_utc_datetime(&naive))
+                })
+                .or_else(|_| {
+                    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                        .map(|date| Utc.from_utc_datetime(&date.and_hms(0, 0, 0)))
+                })
+                .map_err(|_| invalid(ty, input))
+        }
+    }
+}
+
+/// Reads a decimal number as an integer scaled by `10^scale`. Strings are read exactly and
+/// digits beyond the scale are truncated.
+fn decimal(value: &Value, scale: u32, ty: &ColumnType) -> Result<i128, CoerceError> {
+    let factor = 10i128.pow(scale);
+    match value {
+        Value::Integer(integer) => i128::from(*integer)
+            .checked_mul(factor)
+            .ok_or_else(|| out_of_range(ty)),
+        Value::Float(float) => {
+            let scaled = (float.into_inner() * factor as f64).round();
+            if scaled.abs() < 1e38 {
+                Ok(scaled as i128)
+            } else {
+                Err(out_of_range(ty))
+            }
+        }
+        _ => {
+            let input = text(value).ok_or(CoerceError::Incompatible {
+                kind: kind_name(value),
+                ty: ty.clone(),
+            })?;
+            parse_decimal(input.trim(), scale).ok_or_else(|| invalid(ty, &input))
+        }
+    }
+}
+
+fn parse_decimal(input: &str, scale: u32) -> Option<i128> {
+    let (negative, digits) = match input.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, input.strip_prefix('+').unwrap_or(input)),
+    };
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if integer.is_empty() && fraction.is_empty() {
+        return None;
+    }
+
+    let mut unscaled: i128 = 0;
+    let fraction = fraction.chars().chain(std::iter::repeat('0'));
+    for c in integer.chars().chain(fraction.take(scale as usize)) {
+        let digit = c.to_digit(10)?;
+        unscaled = unscaled.checked_mul(10)?.checked_add(i128::from(digit))?;
+    }
+    Some(if negative { -unscaled } else { unscaled })
+}
+
+fn enum_value<T>(value: &Value, variants: &[(String, T)], ty: &ColumnType) -> Result<T, CoerceError>
+where
+    T: Copy + PartialEq + TryFrom<i64>,
+{
+    match value {
+        Value::Integer(integer) => T::try_from(*integer)
+            .ok()
+            .filter(|integer| variants.iter().any(|(_, value)| value == integer))
+            .ok_or_else(|| invalid(ty, &integer.to_string())),
+        _ => {
+            let input = text(value).ok_or(CoerceError::Incompatible {
+                kind: kind_name(value),
+                ty: ty.clone(),
+            })?;
+            variants
+                .iter()
+                .find(|(name, _)| name == &input)
+                .map(|(_, value)| *value)
+                .ok_or_else(|| invalid(ty, &input))
+        }
+    }
+}
+
+/// Converts the fields of an event named after the columns of the schema. Fields without a
+/// matching column are ignored.
+pub fn coerce_row(
+    log: &LogEvent,
+    schema: &TableSchema,
+) -> Result<Vec<Cell>, (String, CoerceError)> {
+    schema
+        .columns
+        .iter()
+        .map(|column| {
+            coerce(log.get(column.name.as_str()), &column.ty)
+                .map_err(|error| (column.name.clone(), error))
+        })
+        .collect()
+}
+
+pub fn put_varuint(buf: &mut BytesMut, mut value: u64) {
+    while value >= 0x80 {
+        buf.put_u8((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.put_u8(value as u8);
+}
+
+fn put_plain(cell: &Cell, buf: &mut BytesMut) {
+    match cell {
+        Cell::Scalar(scalar) => buf.put_slice(scalar.as_bytes()),
+        Cell::Bytes(bytes) => {
+            put_varuint(buf, bytes.len() as u64);
+            buf.put_slice(bytes);
+        }
+        Cell::Raw(bytes) => buf.put_slice(bytes),
+        // Only produced for the types handled by the callers.
+        Cell::Null | Cell::Array(_) | Cell::Map(_) => {}
+    }
+}
+
+/// Writes a value in the `RowBinary` format.
+pub fn write_row_binary(ty: &ColumnType, cell: &Cell, buf: &mut BytesMut) {
+    match ty {
+        ColumnType::Nullable(inner) => match cell {
+            Cell::Null => buf.put_u8(1),
+            cell => {
+                buf.put_u8(0);
+                write_row_binary(inner, cell, buf);
+            }
+        },
+        ColumnType::LowCardinality(inner) => write_row_binary(inner, cell, buf),
+        ColumnType::Array(inner) => {
+            put_varuint(buf, cell.elements().len() as u64);
+            for element in cell.elements() {
+                write_row_binary(inner, element, buf);
+            }
+        }
+        ColumnType::Map(key_ty, value_ty) => {
+            put_varuint(buf, cell.entries().len() as u64);
+            for (key, value) in cell.entries() {
+                write_row_binary(key_ty, key, buf);
+                write_row_binary(value_ty, value, buf);
+            }
+        }
+        _ => put_plain(cell, buf),
+    }
+}
+
+/// Writes the values of a column in the `Native` format, where nested values are stored
+/// as separate columns following their null map or offsets.
+pub fn write_native_column(ty: &ColumnType, cells: &[&Cell], buf: &mut BytesMut) {
+    match ty {
+        ColumnType::Nullable(inner) => {
+            for cell in cells {
+                buf.put_u8(matches!(cell, Cell::Null) as u8);
+            }
+            let default = Cell::default_for(inner);
+            let values = cells
+                .iter()
+                .map(|cell| match cell {
+                    Cell::Null => &default,
+                    cell => cell,
+                })
+                .collect::<Vec<_>>();
+            write_native_column(inner, &values, buf);
+        }
+        ColumnType::LowCardinality(inner) => write_native_column(inner, cells, buf),
+        ColumnType::Array(inner) => {
+            let mut offset = 0u64;
+            for cell in cells {
+                offset += cell.elements().len() as u64;
+                buf.put_u64_le(offset);
+            }
+            let elements = cells
+                .iter()
+                .flat_map(|cell| cell.elements())
+                .collect::<Vec<_>>();
+            write_native_column(inner, &elements, buf);
+        }
+        ColumnType::Map(key_ty, value_ty) => {
+            let mut offset = 0u64;
+            for cell in cells {
+                offset += cell.entries().len() as u64;
+                buf.put_u64_le(offset);
+            }
+            let entries = cells.iter().flat_map(|cell| cell.entries());
+            let keys = entries.clone().map(|(key, _)| key).collect::<Vec<_>>();
+            let values = entries.map(|(_, value)| value).collect::<Vec<_>>();
+            write_native_column(key_ty, &keys, buf);
+            write_native_column(value_ty, &values, buf);
+        }
+        _ => {
+            for cell in cells {
+                put_plain(cell, buf);
+            }
+        }
+    }
+}
+
+/// A column of a block in the `Native` format.
+#[derive(Clone, Debug)]
+pub struct NativeColumn {
+    pub name: String,
+    pub ty: ColumnType,
+    pub data: Bytes,
+}
+
+/// Rows converted to the `Native` format, one buffer per column.
+#[derive(Clone, Debug)]
+pub struct NativeBlock {
+    pub rows: usize,
+    pub columns: Vec<NativeColumn>,
+}
+
+impl NativeBlock {
+    pub fn new(schema: &TableSchema, rows: &[Vec<Cell>]) -> Self {
+        let columns = schema
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let cells = rows.iter().map(|row| &row[index]).collect::<Vec<_>>();
+                let mut data = BytesMut::new();
+                write_native_column(&column.ty, &cells, &mut data);
+                NativeColumn {
+                    name: column.name.clone(),
+                    ty: column.ty.clone(),
+                    data: data.freeze(),
+                }
+            })
+            .collect();
+
+        Self {
+            rows: rows.len(),
+            columns,
+        }
+    }
+
+    pub fn byte_size(&self) -> usize {
+        self.columns.iter().map(|column| column.data.len()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::sinks::clickhouse::schema::Column;
+
+    fn parse(ty: &str) -> ColumnType {
+        ColumnType::parse(ty).unwrap()
+    }
+
+    fn row_binary(ty: &str, value: Value) -> Vec<u8> {
+        let ty = parse(ty);
+        let cell = coerce(Some(&value), &ty).unwrap();
+        let mut buf = BytesMut::new();
+        write_row_binary(&ty, &cell, &mut buf);
+        buf.to_vec()
+    }
+
+    #[test]
+    fn coerces_integers() {
+        assert_eq!(row_binary("UInt16", Value::from(258)), vec![2, 1]);
+        assert_eq!(row_binary("Int8", Value::from("-1")), vec![255]);
+        assert_eq!(row_binary("UInt8", Value::from(true)), vec![1]);
+        assert_eq!(
+            coerce(Some(&Value::from(256)), &ColumnType::UInt8),
+            Err(CoerceError::OutOfRange {
+                ty: ColumnType::UInt8
+            })
+        );
+        assert_eq!(
+            coerce(Some(&Value::from(1.5)), &ColumnType::Int32),
+            Err(CoerceError::Incompatible {
+                kind: "float",
+                ty: ColumnType::Int32
+            })
+        );
+    }
+
+    #[test]
+    fn coerces_timestamps() {
+        let timestamp = Value::from(Utc.ymd(2022, 3, 4).and_hms_milli(5, 6, 7, 890));
+        let seconds = 1_646_370_367u32;
+
+        assert_eq!(
+            row_binary("DateTime('UTC')", timestamp.clone()),
+            seconds.to_le_bytes()
+        );
+        assert_eq!(
+            row_binary("DateTime64(3)", timestamp.clone()),
+            (i64::from(seconds) * 1000 + 890).to_le_bytes()
+        );
+        assert_eq!(row_binary("Date", timestamp), 19055u16.to_le_bytes());
+        assert_eq!(
+            row_binary("DateTime", Value::from("2022-03-04 05:06:07")),
+            seconds.to_le_bytes()
+        );
+        assert_eq!(
+            row_binary("DateTime", Value::from("2022-03-04T06:06:07+01:00")),
+            seconds.to_le_bytes()
+        );
+        assert_eq!(
+            row_binary("DateTime", Value::from(i64::from(seconds))),
+            seconds.to_le_bytes()
+        );
+    }
+
+    #[test]
+    fn coerces_decimals() {
+        assert_eq!(
+            row_binary("Decimal(9, 2)", Value::from("-12.345")),
+            (-1234i32).to_le_bytes()
+        );
+        assert_eq!(
+            row_binary("Decimal64(3)", Value::from(1.5)),
+            1500i64.to_le_bytes()
+        );
+        assert_eq!(
+            row_binary("Decimal(38, 0)", Value::from(7)),
+            7i128.to_le_bytes()
+        );
+        assert_eq!(
+            coerce(Some(&Value::from(1000)), &parse("Decimal(4, 2)")),
+            Err(CoerceError::OutOfRange {
+                ty: parse("Decimal(4, 2)")
+            })
+        );
+    }
+
+    #[test]
+    fn coerces_strings() {
+        assert_eq!(row_binary("String", Value::from("abc")), b"\x03abc");
+        assert_eq!(row_binary("String", Value::from(12)), b"\x0212");
+        assert_eq!(row_binary("FixedString(4)", Value::from("ab")), b"ab\0\0");
+        assert_eq!(
+            row_binary("Enum8('info' = 1, 'error' = 2)", Value::from("error")),
+            vec![2]
+        );
+        assert_eq!(
+            row_binary("UUID", Value::from("61f0c404-5cb3-11e7-907b-a6006ad3dba0")),
+            vec![
+                0xe7, 0x11, 0xb3, 0x5c, 0x04, 0xc4, 0xf0, 0x61, 0xa0, 0xdb, 0xd3, 0x6a, 0x00, 0xa6,
+                0x7b, 0x90
+            ]
+        );
+        assert_eq!(row_binary("IPv4", Value::from("1.2.3.4")), vec![4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn coerces_nested_values() {
+        let value = Value::from(vec![Value::from(1), Value::Null]);
+        assert_eq!(
+            row_binary("Array(Nullable(UInt8))", value),
+            vec![2, 0, 1, 1]
+        );
+
+        let mut map = std::collections::BTreeMap::new();
+        map.insert("a".to_string(), Value::from(1));
+        assert_eq!(
+            row_binary("Map(String, UInt8)", Value::from(map)),
+            b"\x01\x01a\x01"
+        );
+    }
+
+    #[test]
+    fn uses_defaults_for_missing_values() {
+        let ty = parse("Nullable(String)");
+        assert_eq!(coerce(None, &ty), Ok(Cell::Null));
+        assert_eq!(
+            coerce(Some(&Value::Null), &ColumnType::UInt32),
+            Ok(Cell::Scalar(Scalar::new(&[0; 4])))
+        );
+    }
+
+    #[test]
+    fn writes_native_columns() {
+        let schema = TableSchema {
+            columns: vec![
+                Column {
+                    name: "tags".into(),
+                    ty: parse("Array(String)"),
+                },
+                Column {
+                    name: "code".into(),
+                    ty: parse("Nullable(UInt8)"),
+                },
+            ],
+        };
+        let mut first = LogEvent::default();
+        first.insert("tags", vec!["a", "b"]);
+        first.insert("code", 3);
+        let mut second = LogEvent::default();
+        second.insert("tags", vec!["c"]);
+
+        let rows = vec![
+            coerce_row(&first, &schema).unwrap(),
+            coerce_row(&second, &schema).unwrap(),
+        ];
+        let block = NativeBlock::new(&schema, &rows);
+
+        assert_eq!(block.rows, 2);
+        let mut tags = Vec::new();
+        tags.extend_from_slice(&2u64.to_le_bytes());
+        tags.extend_from_slice(&3u64.to_le_bytes());
+        tags.extend_from_slice(b"\x01a\x01b\x01c");
+        assert_eq!(block.columns[0].data, tags);
+        assert_eq!(block.columns[1].data, vec![0, 1, 3, 0]);
+    }
+}
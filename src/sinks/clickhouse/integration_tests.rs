@@ -0,0 +1,735 @@
+use std::{
+    convert::{Infallible, TryInto},
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use futures::future;
+use http::StatusCode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::time::{timeout, Duration};
+use vector_core::event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event, LogEvent};
+use warp::Filter;
+
+use super::config::{ClickhouseConfig, InsertFormat, Protocol};
+use crate::{
+    config::{log_schema, SinkConfig, SinkContext},
+    event,
+    http::Auth,
+    sinks::util::{
+        encoding::{EncodingConfigWithDefault, TimestampFormat},
+        BatchConfig, Compression, TowerRequestConfig,
+    },
+    test_util::{
+        components::{self, HTTP_SINK_TAGS},
+        random_string, trace_init,
+    },
+};
+
+fn clickhouse_address() -> String {
+    std::env::var("CLICKHOUSE_ADDRESS").unwrap_or_else(|_| "http://localhost:8123".into())
+}
+
+fn clickhouse_native_address() -> String {
+    std::env::var("CLICKHOUSE_NATIVE_ADDRESS").unwrap_or_else(|_| "tcp://localhost:9000".into())
+}
+
+#[tokio::test]
+async fn insert_events() {
+    trace_init();
+
+    let table = gen_table();
+    let host = clickhouse_address();
+
+    let mut batch = BatchConfig::default();
+    batch.max_events = Some(1);
+
+    let config = ClickhouseConfig {
+        endpoint: host.parse().unwrap(),
+        table: table.as_str().try_into().unwrap(),
+        compression: Compression::None,
+        batch,
+        request: TowerRequestConfig {
+            retry_attempts: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let client = ClickhouseClient::new(host);
+    client
+        .create_table(
+            &table,
+            "host String, timestamp String, message String, items Array(String)",
+        )
+        .await;
+
+    let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (mut input_event, mut receiver) = make_event();
+    input_event
+        .as_mut_log()
+        .insert("items", vec!["item1", "item2"]);
+
+    components::run_sink_event(sink, input_event.clone(), &HTTP_SINK_TAGS).await;
+
+    let output = client.select_all(&table).await;
+    assert_eq!(1, output.rows);
+
+    let expected = serde_json::to_value(input_event.into_log()).unwrap();
+    assert_eq!(expected, output.data[0]);
+
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+}
+
+#[tokio::test]
+async fn skip_unknown_fields() {
+    trace_init();
+
+    let table = gen_table();
+    let host = clickhouse_address();
+
+    let mut batch = BatchConfig::default();
+    batch.max_events = Some(1);
+
+    let config = ClickhouseConfig {
+        endpoint: host.parse().unwrap(),
+        table: table.as_str().try_into().unwrap(),
+        skip_unknown_fields: true,
+        compression: Compression::None,
+        batch,
+        request: TowerRequestConfig {
+            retry_attempts: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let client = ClickhouseClient::new(host);
+    client
+        .create_table(&table, "host String, timestamp String, message String")
+        .await;
+
+    let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (mut input_event, mut receiver) = make_event();
+    input_event.as_mut_log().insert("unknown", "mysteries");
+
+    components::run_sink_event(sink, input_event.clone(), &HTTP_SINK_TAGS).await;
+
+    let output = client.select_all(&table).await;
+    assert_eq!(1, output.rows);
+
+    input_event.as_mut_log().remove("unknown");
+    let expected = serde_json::to_value(input_event.into_log()).unwrap();
+    assert_eq!(expected, output.data[0]);
+
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+}
+
+#[tokio::test]
+async fn insert_events_unix_timestamps() {
+    trace_init();
+
+    let table = gen_table();
+    let host = clickhouse_address();
+    let encoding = EncodingConfigWithDefault {
+        timestamp_format: Some(TimestampFormat::Unix),
+        ..Default::default()
+    };
+
+    let mut batch = BatchConfig::default();
+    batch.max_events = Some(1);
+
+    let config = ClickhouseConfig {
+        endpoint: host.parse().unwrap(),
+        table: table.as_str().try_into().unwrap(),
+        compression: Compression::None,
+        encoding,
+        batch,
+        request: TowerRequestConfig {
+            retry_attempts: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let client = ClickhouseClient::new(host);
+    client
+        .create_table(
+            &table,
+            "host String, timestamp DateTime('UTC'), message String",
+        )
+        .await;
+
+    let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (mut input_event, _receiver) = make_event();
+
+    components::run_sink_event(sink, input_event.clone(), &HTTP_SINK_TAGS).await;
+
+    let output = client.select_all(&table).await;
+    assert_eq!(1, output.rows);
+
+    let exp_event = input_event.as_mut_log();
+    exp_event.insert(
+        log_schema().timestamp_key(),
+        format!(
+            "{}",
+            exp_event
+                .get(log_schema().timestamp_key())
+                .unwrap()
+                .as_timestamp()
+                .unwrap()
+                .format("%Y-%m-%d %H:%M:%S")
+        ),
+    );
+
+    let expected = serde_json::to_value(exp_event).unwrap();
+    assert_eq!(expected, output.data[0]);
+}
+
+#[tokio::test]
+async fn insert_events_unix_timestamps_toml_config() {
+    trace_init();
+
+    let table = gen_table();
+    let host = clickhouse_address();
+
+    let config: ClickhouseConfig = toml::from_str(&format!(
+        r#"
+host = "{}"
+table = "{}"
+compression = "none"
+[request]
+retry_attempts = 1
+[batch]
+max_events = 1
+[encoding]
+timestamp_format = "unix""#,
+        host, table
+    ))
+    .unwrap();
+
+    let client = ClickhouseClient::new(host);
+    client
+        .create_table(
+            &table,
+            "host String, timestamp DateTime('UTC'), message String",
+        )
+        .await;
+
+    let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (mut input_event, _receiver) = make_event();
+
+    components::run_sink_event(sink, input_event.clone(), &HTTP_SINK_TAGS).await;
+
+    let output = client.select_all(&table).await;
+    assert_eq!(1, output.rows);
+
+    let exp_event = input_event.as_mut_log();
+    exp_event.insert(
+        log_schema().timestamp_key(),
+        format!(
+            "{}",
+            exp_event
+                .get(log_schema().timestamp_key())
+                .unwrap()
+                .as_timestamp()
+                .unwrap()
+                .format("%Y-%m-%d %H:%M:%S")
+        ),
+    );
+
+    let expected = serde_json::to_value(exp_event).unwrap();
+    assert_eq!(expected, output.data[0]);
+}
+
+#[tokio::test]
+async fn no_retry_on_incorrect_data() {
+    trace_init();
+
+    let table = gen_table();
+    let host = clickhouse_address();
+
+    let mut batch = BatchConfig::default();
+    batch.max_events = Some(1);
+
+    let config = ClickhouseConfig {
+        endpoint: host.parse().unwrap(),
+        table: table.as_str().try_into().unwrap(),
+        compression: Compression::None,
+        batch,
+        ..Default::default()
+    };
+
+    let client = ClickhouseClient::new(host);
+    // the event contains a message field, but its being omitted to
+    // fail the request.
+    client
+        .create_table(&table, "host String, timestamp String")
+        .await;
+
+    let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (input_event, mut receiver) = make_event();
+
+    // Retries should go on forever, so if we are retrying incorrectly
+    // this timeout should trigger.
+    timeout(Duration::from_secs(5), sink.run_events(vec![input_event]))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
+
+#[tokio::test]
+async fn no_retry_on_incorrect_data_warp() {
+    trace_init();
+
+    let visited = Arc::new(AtomicBool::new(false));
+    let routes = warp::any().and_then(move || {
+        assert!(!visited.load(Ordering::SeqCst), "Should not retry request.");
+        visited.store(true, Ordering::SeqCst);
+
+        future::ok::<_, Infallible>(warp::reply::with_status(
+            "Code: 117",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ))
+    });
+    let server = warp::serve(routes).bind("0.0.0.0:8124".parse::<SocketAddr>().unwrap());
+    tokio::spawn(server);
+
+    let host = String::from("http://localhost:8124");
+
+    let mut batch = BatchConfig::default();
+    batch.max_events = Some(1);
+
+    let config = ClickhouseConfig {
+        endpoint: host.parse().unwrap(),
+        table: gen_table().as_str().try_into().unwrap(),
+        batch,
+        ..Default::default()
+    };
+    let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (input_event, mut receiver) = make_event();
+
+    // Retries should go on forever, so if we are retrying incorrectly
+    // this timeout should trigger.
+    timeout(Duration::from_secs(5), sink.run_events(vec![input_event]))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Errored));
+}
+
+#[tokio::test]
+async fn rejects_batch_when_schema_is_unavailable() {
+    trace_init();
+
+    let routes = warp::any()
+        .map(|| warp::reply::with_status("unavailable", StatusCode::SERVICE_UNAVAILABLE));
+    let server = warp::serve(routes).bind("0.0.0.0:8125".parse::<SocketAddr>().unwrap());
+    tokio::spawn(server);
+
+    let mut batch = BatchConfig::default();
+    batch.max_events = Some(1);
+
+    let config = ClickhouseConfig {
+        endpoint: "http://localhost:8125".parse().unwrap(),
+        table: gen_table().as_str().try_into().unwrap(),
+        format: InsertFormat::RowBinary,
+        batch,
+        ..Default::default()
+    };
+    let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (input_event, mut receiver) = make_event();
+
+    // Fetching the schema gives up after a few attempts instead of blocking the sink.
+    timeout(Duration::from_secs(30), sink.run_events(vec![input_event]))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
+
+const TYPED_SCHEMA: &str = "host String, timestamp DateTime64(3, 'UTC'), message String, \
+    amount Decimal(10, 2), tags Array(LowCardinality(String)), code Nullable(UInt16), \
+    level Enum8('info' = 1, 'error' = 2)";
+
+fn make_typed_event() -> (Event, BatchStatusReceiver) {
+    let (mut event, receiver) = make_event();
+    let log = event.as_mut_log();
+    log.insert("amount", "12.34");
+    log.insert("tags", vec!["a", "b"]);
+    log.insert("level", "error");
+    log.insert("unknown", "ignored");
+    (event, receiver)
+}
+
+fn expected_typed_row(event: &Event) -> Value {
+    let log = event.as_log();
+    json!({
+        "host": "example.com",
+        "timestamp": log
+            .get(log_schema().timestamp_key())
+            .unwrap()
+            .as_timestamp()
+            .unwrap()
+            .format("%Y-%m-%d %H:%M:%S%.3f")
+            .to_string(),
+        "message": "raw log line",
+        "amount": 12.34,
+        "tags": ["a", "b"],
+        "code": null,
+        "level": "error",
+    })
+}
+
+#[tokio::test]
+async fn insert_typed_events_row_binary() {
+    trace_init();
+
+    let table = gen_table();
+    let host = clickhouse_address();
+
+    let mut batch = BatchConfig::default();
+    batch.max_events = Some(1);
+
+    let config = ClickhouseConfig {
+        endpoint: host.parse().unwrap(),
+        table: table.as_str().try_into().unwrap(),
+        format: InsertFormat::RowBinary,
+        compression: Compression::gzip_default(),
+        batch,
+        request: TowerRequestConfig {
+            retry_attempts: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let client = ClickhouseClient::new(host);
+    client.create_table(&table, TYPED_SCHEMA).await;
+
+    let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (input_event, mut receiver) = make_typed_event();
+
+    components::run_sink_event(sink, input_event.clone(), &HTTP_SINK_TAGS).await;
+
+    let output = client.select_all(&table).await;
+    assert_eq!(1, output.rows);
+    assert_eq!(expected_typed_row(&input_event), output.data[0]);
+
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+}
+
+#[tokio::test]
+async fn insert_typed_events_native() {
+    trace_init();
+
+    let table = gen_table();
+    let client = ClickhouseClient::new(clickhouse_address());
+    client.create_table(&table, TYPED_SCHEMA).await;
+
+    let mut batch = BatchConfig::default();
+    batch.max_events = Some(1);
+
+    let config = ClickhouseConfig {
+        endpoint: clickhouse_native_address().parse().unwrap(),
+        table: table.as_str().try_into().unwrap(),
+        protocol: Protocol::Native,
+        auth: Some(Auth::Basic {
+            user: "default".into(),
+            password: "".into(),
+        }),
+        batch,
+        request: TowerRequestConfig {
+            retry_attempts: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let (sink, healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+    healthcheck.await.unwrap();
+
+    let (input_event, mut receiver) = make_typed_event();
+    sink.run_events(vec![input_event.clone()]).await.unwrap();
+
+    let output = client.select_all(&table).await;
+    assert_eq!(1, output.rows);
+    assert_eq!(expected_typed_row(&input_event), output.data[0]);
+
+    assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+}
+
+#[tokio::test]
+async fn native_round_trips_each_column_type() {
+    trace_init();
+
+    let mut map = std::collections::BTreeMap::new();
+    map.insert("a".to_string(), 1.into());
+    let cases: Vec<(&str, event::Value, Value)> = vec![
+        ("UInt8", 255.into(), json!(255)),
+        ("UInt16", 65535.into(), json!(65535)),
+        ("UInt32", 4_294_967_295i64.into(), json!(4_294_967_295u32)),
+        ("UInt64", 5.into(), json!("5")),
+        ("Int8", (-128).into(), json!(-128)),
+        ("Int16", (-32768).into(), json!(-32768)),
+        ("Int32", (-5).into(), json!(-5)),
+        ("Int64", (-5).into(), json!("-5")),
+        ("Float32", 1.5.into(), json!(1.5)),
+        ("Float64", (-2.25).into(), json!(-2.25)),
+        ("Bool", true.into(), json!(true)),
+        ("String", "abc".into(), json!("abc")),
+        ("FixedString(4)", "ab".into(), json!("ab\0\0")),
+        (
+            "UUID",
+            "61f0c404-5cb3-11e7-907b-a6006ad3dba0".into(),
+            json!("61f0c404-5cb3-11e7-907b-a6006ad3dba0"),
+        ),
+        ("Date", "2022-03-04 05:06:07".into(), json!("2022-03-04")),
+        ("Date32", "2022-03-04 05:06:07".into(), json!("2022-03-04")),
+        (
+            "DateTime('UTC')",
+            "2022-03-04 05:06:07".into(),
+            json!("2022-03-04 05:06:07"),
+        ),
+        (
+            "DateTime64(6, 'UTC')",
+            "2022-03-04T05:06:07.123456Z".into(),
+            json!("2022-03-04 05:06:07.123456"),
+        ),
+        ("Decimal(9, 2)", "-12.34".into(), json!(-12.34)),
+        ("Decimal(18, 4)", "1.5".into(), json!(1.5)),
+        ("Decimal(38, 2)", "7.25".into(), json!(7.25)),
+        ("IPv4", "1.2.3.4".into(), json!("1.2.3.4")),
+        ("IPv6", "2001:db8::1".into(), json!("2001:db8::1")),
+        (
+            "Enum8('info' = 1, 'error' = 2)",
+            "error".into(),
+            json!("error"),
+        ),
+        (
+            "Enum16('low' = -300, 'high' = 300)",
+            "high".into(),
+            json!("high"),
+        ),
+        ("Nullable(UInt16)", event::Value::Null, json!(null)),
+        ("Nullable(String)", "abc".into(), json!("abc")),
+        ("LowCardinality(String)", "abc".into(), json!("abc")),
+        (
+            "Array(Nullable(Int32))",
+            vec![event::Value::from(1), event::Value::Null].into(),
+            json!([1, null]),
+        ),
+        ("Map(String, UInt8)", map.into(), json!({"a": 1})),
+    ];
+
+    let client = ClickhouseClient::new(clickhouse_address());
+    for (ty, input, expected) in cases {
+        let table = gen_table();
+        client
+            .create_table(
+                &table,
+                &format!("host String, timestamp DateTime64(3, 'UTC'), value {}", ty),
+            )
+            .await;
+
+        let config = ClickhouseConfig {
+            endpoint: clickhouse_native_address().parse().unwrap(),
+            table: table.as_str().try_into().unwrap(),
+            protocol: Protocol::Native,
+            auth: Some(Auth::Basic {
+                user: "default".into(),
+                password: "".into(),
+            }),
+            ..Default::default()
+        };
+        let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let (mut event, mut receiver) = make_event();
+        event.as_mut_log().insert("value", input);
+        sink.run_events(vec![event]).await.unwrap();
+
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered), "{}", ty);
+        let output = client.select_all(&table).await;
+        assert_eq!(1, output.rows, "{}", ty);
+        assert_eq!(output.data[0]["value"], expected, "{}", ty);
+    }
+}
+
+#[tokio::test]
+async fn rejects_events_not_matching_schema() {
+    trace_init();
+
+    let table = gen_table();
+    let host = clickhouse_address();
+
+    let config = ClickhouseConfig {
+        endpoint: host.parse().unwrap(),
+        table: table.as_str().try_into().unwrap(),
+        format: InsertFormat::RowBinary,
+        compression: Compression::None,
+        ..Default::default()
+    };
+
+    let client = ClickhouseClient::new(host);
+    client
+        .create_table(&table, "host String, timestamp DateTime, code UInt8")
+        .await;
+
+    let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let (mut valid, mut valid_receiver) = make_event();
+    valid.as_mut_log().insert("code", 200);
+    let (mut invalid, mut invalid_receiver) = make_event();
+    invalid.as_mut_log().insert("code", "not a number");
+
+    sink.run_events(vec![valid, invalid]).await.unwrap();
+
+    let output = client.select_all(&table).await;
+    assert_eq!(1, output.rows);
+    assert_eq!(output.data[0]["code"], json!(200));
+
+    assert_eq!(valid_receiver.try_recv(), Ok(BatchStatus::Delivered));
+    assert_eq!(invalid_receiver.try_recv(), Ok(BatchStatus::Rejected));
+}
+
+#[tokio::test]
+async fn templated_table() {
+    trace_init();
+
+    let prefix = gen_table();
+    let host = clickhouse_address();
+
+    let config = ClickhouseConfig {
+        endpoint: host.parse().unwrap(),
+        table: format!("{}_{{{{ service }}}}", prefix)
+            .as_str()
+            .try_into()
+            .unwrap(),
+        compression: Compression::None,
+        ..Default::default()
+    };
+
+    let client = ClickhouseClient::new(host);
+    for service in ["api", "web"] {
+        client
+            .create_table(
+                &format!("{}_{}", prefix, service),
+                "host String, timestamp String, message String, service String",
+            )
+            .await;
+    }
+
+    let (sink, _hc) = config.build(SinkContext::new_test()).await.unwrap();
+
+    let events = ["api", "web", "api"]
+        .iter()
+        .map(|service| {
+            let (mut event, _receiver) = make_event();
+            event.as_mut_log().insert("service", *service);
+            event
+        })
+        .collect::<Vec<_>>();
+    sink.run_events(events).await.unwrap();
+
+    assert_eq!(client.select_all(&format!("{}_api", prefix)).await.rows, 2);
+    assert_eq!(client.select_all(&format!("{}_web", prefix)).await.rows, 1);
+}
+
+fn make_event() -> (Event, BatchStatusReceiver) {
+    let (batch, receiver) = BatchNotifier::new_with_receiver();
+    let mut event = LogEvent::from("raw log line").with_batch_notifier(&batch);
+    event.insert("host", "example.com");
+    (event.into(), receiver)
+}
+
+struct ClickhouseClient {
+    host: String,
+    client: reqwest::Client,
+}
+
+impl ClickhouseClient {
+    fn new(host: String) -> Self {
+        ClickhouseClient {
+            host,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn create_table(&self, table: &str, schema: &str) {
+        let response = self
+            .client
+            .post(&self.host)
+            //
+            .body(format!(
+                "CREATE TABLE {}
+                 ({})
+                 ENGINE = MergeTree()
+                 ORDER BY (host, timestamp);",
+                table, schema
+            ))
+            .send()
+            .await
+            .unwrap();
+
+        if !response.status().is_success() {
+            panic!("create table failed: {}", response.text().await.unwrap())
+        }
+    }
+
+    async fn select_all(&self, table: &str) -> QueryResponse {
+        let response = self
+            .client
+            .post(&self.host)
+            .body(format!("SELECT * FROM {} FORMAT JSON", table))
+            .send()
+            .await
+            .unwrap();
+
+        if !response.status().is_success() {
+            panic!("select all failed: {}", response.text().await.unwrap())
+        } else {
+            let text = response.text().await.unwrap();
+            match serde_json::from_str(&text) {
+                Ok(value) => value,
+                Err(_) => panic!("json failed: {:?}", text),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)] // deserialize all fields
+struct QueryResponse {
+    data: Vec<Value>,
+    meta: Vec<Value>,
+    rows: usize,
+    statistics: Stats,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)] // deserialize all fields
+struct Stats {
+    bytes_read: usize,
+    elapsed: f64,
+    rows_read: usize,
+}
+
+fn gen_table() -> String {
+    format!("test_{}", random_string(10).to_lowercase())
+}
@@ -0,0 +1,25 @@
+//! ClickHouse sink
+//!
+//! Inserts events over HTTP, either as `JSONEachRow` or as `RowBinary`, or over the native
+//! TCP protocol as column blocks.
+//!
+//! Events are partitioned by their rendered database and table. Except for `JSONEachRow`,
+//! where ClickHouse converts the fields itself, the schema of each table is fetched and
+//! cached so that fields are converted to the column types before being sent.
+mod config;
+mod encoder;
+#[cfg(feature = "clickhouse-integration-tests")]
+#[cfg(test)]
+mod integration_tests;
+mod native;
+mod schema;
+mod service;
+mod sink;
+
+pub use config::ClickhouseConfig;
+
+use crate::config::SinkDescription;
+
+inventory::submit! {
+    SinkDescription::new::<ClickhouseConfig>("clickhouse")
+}
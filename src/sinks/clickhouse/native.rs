@@ -0,0 +1,536 @@
+//! A minimal client for the ClickHouse native TCP protocol, implementing what the sink
+//! needs: inserting blocks, describing tables and pinging the server.
+//!
+//! The client announces an older protocol revision than current servers use, which makes
+//! the server convert `LowCardinality` columns to their plain representation and keeps the
+//! packets free of the fields added since.
+//!
+//! <https://clickhouse.com/docs/en/native-protocol/basics>
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use bytes::{BufMut, BytesMut};
+use snafu::{ResultExt, Snafu};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, BufStream},
+    net::TcpStream,
+};
+
+use super::{
+    encoder::{put_varuint, NativeBlock},
+    schema::{ColumnType, TableKey},
+};
+use crate::{
+    dns,
+    tls::{MaybeTlsSettings, MaybeTlsStream, TlsError},
+};
+
+const CLIENT_NAME: &str = "Vector";
+const CLIENT_VERSION_MAJOR: u64 = 1;
+const CLIENT_VERSION_MINOR: u64 = 0;
+const CLIENT_VERSION_PATCH: u64 = 0;
+const CLIENT_REVISION: u64 = 54401;
+
+const REVISION_WITH_SERVER_TIMEZONE: u64 = 54058;
+const REVISION_WITH_SERVER_DISPLAY_NAME: u64 = 54372;
+const REVISION_WITH_VERSION_PATCH: u64 = 54401;
+
+/// Upper bound on the length of strings read from the server.
+const MAX_STRING_LENGTH: u64 = 1 << 30;
+
+mod client_packet {
+    pub const HELLO: u64 = 0;
+    pub const QUERY: u64 = 1;
+    pub const DATA: u64 = 2;
+    pub const PING: u64 = 4;
+}
+
+mod server_packet {
+    pub const HELLO: u64 = 0;
+    pub const DATA: u64 = 1;
+    pub const EXCEPTION: u64 = 2;
+    pub const PROGRESS: u64 = 3;
+    pub const PONG: u64 = 4;
+    pub const END_OF_STREAM: u64 = 5;
+    pub const PROFILE_INFO: u64 = 6;
+    pub const TOTALS: u64 = 7;
+    pub const EXTREMES: u64 = 8;
+}
+
+const QUERY_KIND_INITIAL: u8 = 1;
+const INTERFACE_TCP: u8 = 1;
+const STAGE_COMPLETE: u64 = 2;
+const COMPRESSION_DISABLED: u64 = 0;
+
+#[derive(Debug, Snafu)]
+pub enum NativeError {
+    #[snafu(display("Unable to resolve {}: {}", host, source))]
+    Resolve { host: String, source: dns::DnsError },
+    #[snafu(display("No addresses found for {}", host))]
+    NoAddresses { host: String },
+    #[snafu(display("Unable to connect: {}", source))]
+    Connect { source: TlsError },
+    #[snafu(display("Connection error: {}", source))]
+    Io { source: std::io::Error },
+    #[snafu(display("Code: {}. {}: {}", code, name, message))]
+    Server {
+        code: i32,
+        name: String,
+        message: String,
+    },
+    #[snafu(display("Unexpected packet {} from server", packet))]
+    UnexpectedPacket { packet: u64 },
+    #[snafu(display("Unsupported column type {:?} in result", ty))]
+    UnsupportedResult { ty: String },
+    #[snafu(display("Server expects columns {:?} but the block has {:?}", expected, actual))]
+    HeaderMismatch {
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+}
+
+impl NativeError {
+    /// The ClickHouse error code, if the server refused the query.
+    pub const fn code(&self) -> Option<i32> {
+        match self {
+            Self::Server { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NativeOptions {
+    pub host: String,
+    pub port: u16,
+    pub tls: MaybeTlsSettings,
+    pub user: String,
+    pub password: String,
+}
+
+/// A pool of connections to the server, opened on demand.
+#[derive(Clone, Debug)]
+pub struct NativeClient {
+    options: Arc<NativeOptions>,
+    idle: Arc<Mutex<Vec<NativeConnection>>>,
+}
+
+impl NativeClient {
+    pub fn new(options: NativeOptions) -> Self {
+        Self {
+            options: Arc::new(options),
+            idle: Arc::default(),
+        }
+    }
+
+    /// Takes an idle connection or opens a new one. Connections are only returned to the pool
+    /// with `release` after a successful exchange, as the state of the stream is unknown after
+    /// an error.
+    pub async fn acquire(&self) -> Result<NativeConnection, NativeError> {
+        let idle = self.idle.lock().expect("poisoned lock").pop();
+        match idle {
+            Some(connection) => Ok(connection),
+            None => NativeConnection::connect(&self.options).await,
+        }
+    }
+
+    pub fn release(&self, connection: NativeConnection) {
+        self.idle.lock().expect("poisoned lock").push(connection);
+    }
+
+    pub fn endpoint(&self) -> String {
+        format!("{}:{}", self.options.host, self.options.port)
+    }
+}
+
+/// A block read from the server. Only `String` columns can be read, which is enough for
+/// the results of `DESCRIBE TABLE`.
+struct ResultBlock {
+    columns: Vec<(String, String)>,
+    /// The values of each column.
+    values: Vec<Vec<String>>,
+}
+
+#[derive(Debug)]
+pub struct NativeConnection {
+    stream: BufStream<MaybeTlsStream<TcpStream>>,
+    revision: u64,
+}
+
+impl NativeConnection {
+    async fn connect(options: &NativeOptions) -> Result<Self, NativeError> {
+        let ip = dns::Resolver
+            .lookup_ip(options.host.clone())
+            .await
+            .context(ResolveSnafu {
+                host: options.host.clone(),
+            })?
+            .next()
+            .ok_or_else(|| NativeError::NoAddresses {
+                host: options.host.clone(),
+            })?;
+        let stream = options
+            .tls
+            .connect(&options.host, &SocketAddr::new(ip, options.port))
+            .await
+            .context(ConnectSnafu)?;
+
+        let mut connection = Self {
+            stream: BufStream::new(stream),
+            revision: CLIENT_REVISION,
+        };
+        connection.handshake(options).await?;
+        Ok(connection)
+    }
+
+    async fn handshake(&mut self, options: &NativeOptions) -> Result<(), NativeError> {
+        let mut buf = BytesMut::new();
+        put_varuint(&mut buf, client_packet::HELLO);
+        put_string(&mut buf, CLIENT_NAME);
+        put_varuint(&mut buf, CLIENT_VERSION_MAJOR);
+        put_varuint(&mut buf, CLIENT_VERSION_MINOR);
+        put_varuint(&mut buf, CLIENT_REVISION);
+        // Queries use fully qualified table names, so the default database is kept.
+        put_string(&mut buf, "");
+        put_string(&mut buf, &options.user);
+        put_string(&mut buf, &options.password);
+        self.send(&buf).await?;
+
+        match self.read_varuint().await? {
+            server_packet::HELLO => {
+                let _name = self.read_string().await?;
+                let _major = self.read_varuint().await?;
+                let _minor = self.read_varuint().await?;
+                let revision = self.read_varuint().await?;
+                self.revision = revision.min(CLIENT_REVISION);
+                if self.revision >= REVISION_WITH_SERVER_TIMEZONE {
+                    let _timezone = self.read_string().await?;
+                }
+                if self.revision >= REVISION_WITH_SERVER_DISPLAY_NAME {
+                    let _display_name = self.read_string().await?;
+                }
+                if self.revision >= REVISION_WITH_VERSION_PATCH {
+                    let _patch = self.read_varuint().await?;
+                }
+                Ok(())
+            }
+            server_packet::EXCEPTION => Err(self.read_exception().await?),
+            packet => Err(NativeError::UnexpectedPacket { packet }),
+        }
+    }
+
+    pub async fn ping(&mut self) -> Result<(), NativeError> {
+        let mut buf = BytesMut::new();
+        put_varuint(&mut buf, client_packet::PING);
+        self.send(&buf).await?;
+
+        loop {
+            match self.read_varuint().await? {
+                server_packet::PONG => return Ok(()),
+                server_packet::PROGRESS => self.read_progress().await?,
+                server_packet::EXCEPTION => return Err(self.read_exception().await?),
+                packet => return Err(NativeError::UnexpectedPacket { packet }),
+            }
+        }
+    }
+
+    /// Runs a query whose result only has `String` columns, returning its rows.
+    pub async fn query(&mut self, query: &str) -> Result<Vec<Vec<String>>, NativeError> {
+        self.send_query(query).await?;
+
+        let mut rows = Vec::new();
+        loop {
+            match self.read_varuint().await? {
+                server_packet::DATA | server_packet::TOTALS | server_packet::EXTREMES => {
+                    let block = self.read_data().await?;
+                    let row_count = block.values.first().map_or(0, Vec::len);
+                    rows.extend((0..row_count).map(|row| {
+                        block
+                            .values
+                            .iter()
+                            .map(|column| column[row].clone())
+                            .collect()
+                    }));
+                }
+                server_packet::PROGRESS => self.read_progress().await?,
+                server_packet::PROFILE_INFO => self.read_profile_info().await?,
+                server_packet::EXCEPTION => return Err(self.read_exception().await?),
+                server_packet::END_OF_STREAM => return Ok(rows),
+                packet => return Err(NativeError::UnexpectedPacket { packet }),
+            }
+        }
+    }
+
+    /// Inserts a block into the table. The server first answers the query with the columns it
+    /// expects, which must match the columns of the block.
+    pub async fn insert(
+        &mut self,
+        table: &TableKey,
+        block: &NativeBlock,
+    ) -> Result<(), NativeError> {
+        let columns = block
+            .columns
+            .iter()
+            .map(|column| super::schema::quote_identifier(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let query = format!(
+            "INSERT INTO {} ({}) VALUES",
+            table.qualified_name(),
+            columns
+        );
+        self.send_query(&query).await?;
+
+        let header = loop {
+            match self.read_varuint().await? {
+                server_packet::DATA => break self.read_data().await?,
+                server_packet::PROGRESS => self.read_progress().await?,
+                server_packet::EXCEPTION => return Err(self.read_exception().await?),
+                packet => return Err(NativeError::UnexpectedPacket { packet }),
+            }
+        };
+
+        let matches = header.columns.len() == block.columns.len()
+            && header
+                .columns
+                .iter()
+                .zip(&block.columns)
+                .all(|((name, ty), column)| {
+                    name == &column.name
+                        && ColumnType::parse(ty)
+                            .map(|ty| {
+                                ty.without_low_cardinality() == column.ty.without_low_cardinality()
+                            })
+                            .unwrap_or(false)
+                });
+        if !matches {
+            return Err(NativeError::HeaderMismatch {
+                expected: header
+                    .columns
+                    .iter()
+                    .map(|(name, ty)| format!("{} {}", name, ty))
+                    .collect(),
+                actual: block
+                    .columns
+                    .iter()
+                    .map(|column| format!("{} {}", column.name, column.ty))
+                    .collect(),
+            });
+        }
+
+        let mut buf = BytesMut::new();
+        put_data_header(&mut buf, header.columns.len(), block.rows);
+        for ((name, ty), column) in header.columns.iter().zip(&block.columns) {
+            put_string(&mut buf, name);
+            // The type sent by the server is used as is, as the server compares them textually.
+            put_string(&mut buf, ty);
+            buf.put_slice(&column.data);
+        }
+        // An empty block marks the end of the data.
+        put_data_header(&mut buf, 0, 0);
+        self.send(&buf).await?;
+
+        loop {
+            match self.read_varuint().await? {
+                server_packet::PROGRESS => self.read_progress().await?,
+                server_packet::PROFILE_INFO => self.read_profile_info().await?,
+                server_packet::EXCEPTION => return Err(self.read_exception().await?),
+                server_packet::END_OF_STREAM => return Ok(()),
+                packet => return Err(NativeError::UnexpectedPacket { packet }),
+            }
+        }
+    }
+
+    async fn send_query(&mut self, query: &str) -> Result<(), NativeError> {
+        let mut buf = BytesMut::new();
+        put_varuint(&mut buf, client_packet::QUERY);
+        // Query ID, generated by the server when empty.
+        put_string(&mut buf, "");
+
+        // Client info.
+        buf.put_u8(QUERY_KIND_INITIAL);
+        put_string(&mut buf, ""); // initial user
+        put_string(&mut buf, ""); // initial query ID
+        put_string(&mut buf, "0.0.0.0:0"); // initial address
+        buf.put_u8(INTERFACE_TCP);
+        put_string(&mut buf, ""); // OS user
+        put_string(&mut buf, &crate::get_hostname().unwrap_or_default());
+        put_string(&mut buf, CLIENT_NAME);
+        put_varuint(&mut buf, CLIENT_VERSION_MAJOR);
+        put_varuint(&mut buf, CLIENT_VERSION_MINOR);
+        put_varuint(&mut buf, CLIENT_REVISION);
+        put_string(&mut buf, ""); // quota key
+        if self.revision >= REVISION_WITH_VERSION_PATCH {
+            put_varuint(&mut buf, CLIENT_VERSION_PATCH);
+        }
+
+        // No settings, the list is terminated by an empty name.
+        put_string(&mut buf, "");
+        put_varuint(&mut buf, STAGE_COMPLETE);
+        put_varuint(&mut buf, COMPRESSION_DISABLED);
+        put_string(&mut buf, query);
+
+        // An empty block marks the end of the external tables.
+        put_data_header(&mut buf, 0, 0);
+        self.send(&buf).await
+    }
+
+    async fn send(&mut self, buf: &[u8]) -> Result<(), NativeError> {
+        self.stream.write_all(buf).await.context(IoSnafu)?;
+        self.stream.flush().await.context(IoSnafu)
+    }
+
+    async fn read_data(&mut self) -> Result<ResultBlock, NativeError> {
+        let _table_name = self.read_string().await?;
+
+        // Block info, a list of fields terminated by a zero field number.
+        loop {
+            match self.read_varuint().await? {
+                0 => break,
+                1 => {
+                    let _is_overflows = self.stream.read_u8().await.context(IoSnafu)?;
+                }
+                2 => {
+                    let _bucket_num = self.stream.read_i32_le().await.context(IoSnafu)?;
+                }
+                field => return Err(NativeError::UnexpectedPacket { packet: field }),
+            }
+        }
+
+        let column_count = self.read_varuint().await?;
+        let row_count = self.read_varuint().await?;
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        for _ in 0..column_count {
+            let name = self.read_string().await?;
+            let ty = self.read_string().await?;
+            // Empty blocks carry no data, whatever the type of their columns.
+            if row_count > 0 {
+                if ty != "String" {
+                    return Err(NativeError::UnsupportedResult { ty });
+                }
+                let mut column = Vec::new();
+                for _ in 0..row_count {
+                    column.push(self.read_string().await?);
+                }
+                values.push(column);
+            }
+            columns.push((name, ty));
+        }
+
+        Ok(ResultBlock { columns, values })
+    }
+
+    async fn read_exception(&mut self) -> Result<NativeError, NativeError> {
+        let code = self.stream.read_i32_le().await.context(IoSnafu)?;
+        let name = self.read_string().await?;
+        let message = self.read_string().await?;
+        let _stack_trace = self.read_string().await?;
+
+        // Nested exceptions are causes of the first one, which already describes them.
+        while self.stream.read_u8().await.context(IoSnafu)? != 0 {
+            let _code = self.stream.read_i32_le().await.context(IoSnafu)?;
+            for _ in 0..3 {
+                let _ = self.read_string().await?;
+            }
+        }
+
+        Ok(NativeError::Server {
+            code,
+            name,
+            message,
+        })
+    }
+
+    async fn read_progress(&mut self) -> Result<(), NativeError> {
+        let _rows = self.read_varuint().await?;
+        let _bytes = self.read_varuint().await?;
+        let _total_rows = self.read_varuint().await?;
+        Ok(())
+    }
+
+    async fn read_profile_info(&mut self) -> Result<(), NativeError> {
+        let _rows = self.read_varuint().await?;
+        let _blocks = self.read_varuint().await?;
+        let _bytes = self.read_varuint().await?;
+        let _applied_limit = self.stream.read_u8().await.context(IoSnafu)?;
+        let _rows_before_limit = self.read_varuint().await?;
+        let _calculated_rows_before_limit = self.stream.read_u8().await.context(IoSnafu)?;
+        Ok(())
+    }
+
+    async fn read_varuint(&mut self) -> Result<u64, NativeError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.stream.read_u8().await.context(IoSnafu)?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(NativeError::Io {
+            source: std::io::Error::new(std::io::ErrorKind::InvalidData, "varint too long"),
+        })
+    }
+
+    async fn read_string(&mut self) -> Result<String, NativeError> {
+        let len = self.read_varuint().await?;
+        if len > MAX_STRING_LENGTH {
+            return Err(NativeError::Io {
+                source: std::io::Error::new(std::io::ErrorKind::InvalidData, "string too long"),
+            });
+        }
+        let mut buf = vec![0; len as usize];
+        self.stream.read_exact(&mut buf).await.context(IoSnafu)?;
+        Ok(String::from_utf8_lossy(&buf).into_owned())
+    }
+}
+
+fn put_string(buf: &mut BytesMut, value: &str) {
+    put_varuint(buf, value.len() as u64);
+    buf.put_slice(value.as_bytes());
+}
+
+fn put_data_header(buf: &mut BytesMut, columns: usize, rows: usize) {
+    put_varuint(buf, client_packet::DATA);
+    // Temporary table name.
+    put_string(buf, "");
+    // Block info: not an overflow block, no bucket.
+    put_varuint(buf, 1);
+    buf.put_u8(0);
+    put_varuint(buf, 2);
+    buf.put_i32_le(-1);
+    put_varuint(buf, 0);
+    put_varuint(buf, columns as u64);
+    put_varuint(buf, rows as u64);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_varuint() {
+        let mut buf = BytesMut::new();
+        put_varuint(&mut buf, 1);
+        put_varuint(&mut buf, 300);
+        put_varuint(&mut buf, u64::MAX);
+        assert_eq!(
+            &buf[..],
+            &[1, 0xac, 0x02, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01][..]
+        );
+    }
+
+    #[test]
+    fn encodes_empty_data_block() {
+        let mut buf = BytesMut::new();
+        put_data_header(&mut buf, 0, 0);
+        assert_eq!(
+            &buf[..],
+            &[2, 0, 1, 0, 2, 0xff, 0xff, 0xff, 0xff, 0, 0, 0][..]
+        );
+    }
+}
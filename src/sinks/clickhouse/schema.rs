@@ -0,0 +1,483 @@
+//! Table schemas used to convert event fields to the ClickHouse column types.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use snafu::Snafu;
+
+/// How long a fetched schema is used before being fetched again, so that columns added
+/// to a table are eventually picked up.
+const SCHEMA_TTL: Duration = Duration::from_secs(60);
+
+/// Upper bound on the number of cached schemas, as templated tables can resolve to an
+/// unbounded number of tables.
+const MAX_CACHED_SCHEMAS: usize = 1024;
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum SchemaError {
+    #[snafu(display("Invalid column type {:?}.", ty))]
+    InvalidType { ty: String },
+    #[snafu(display("Unsupported column type {:?}.", ty))]
+    UnsupportedType { ty: String },
+    #[snafu(display("Table {} has no insertable columns.", table))]
+    NoColumns { table: String },
+}
+
+/// The subset of the ClickHouse data types events can be converted to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnType {
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Bool,
+    String,
+    FixedString(usize),
+    Uuid,
+    Date,
+    Date32,
+    DateTime(Option<String>),
+    DateTime64(u32, Option<String>),
+    Decimal { precision: u32, scale: u32 },
+    Ipv4,
+    Ipv6,
+    Enum8(Vec<(String, i8)>),
+    Enum16(Vec<(String, i16)>),
+    Nullable(Box<ColumnType>),
+    Array(Box<ColumnType>),
+    Map(Box<ColumnType>, Box<ColumnType>),
+    LowCardinality(Box<ColumnType>),
+}
+
+impl ColumnType {
+    pub fn parse(ty: &str) -> Result<Self, SchemaError> {
+        let ty = ty.trim();
+        let invalid = || SchemaError::InvalidType { ty: ty.into() };
+        let (name, args) = match ty.find('(') {
+            Some(start) if ty.ends_with(')') => {
+                (&ty[..start], split_args(&ty[start + 1..ty.len() - 1]))
+            }
+            Some(_) => return Err(invalid()),
+            None => (ty, Vec::new()),
+        };
+
+        let parsed = match (name, args.as_slice()) {
+            ("UInt8", []) => Self::UInt8,
+            ("UInt16", []) => Self::UInt16,
+            ("UInt32", []) => Self::UInt32,
+            ("UInt64", []) => Self::UInt64,
+            ("Int8", []) => Self::Int8,
+            ("Int16", []) => Self::Int16,
+            ("Int32", []) => Self::Int32,
+            ("Int64", []) => Self::Int64,
+            ("Float32", []) => Self::Float32,
+            ("Float64", []) => Self::Float64,
+            ("Bool", []) => Self::Bool,
+            ("String", []) => Self::String,
+            ("FixedString", [n]) => Self::FixedString(n.parse().map_err(|_| invalid())?),
+            ("UUID", []) => Self::Uuid,
+            ("Date", []) => Self::Date,
+            ("Date32", []) => Self::Date32,
+            ("DateTime", []) => Self::DateTime(None),
+            ("DateTime", [tz]) => Self::DateTime(Some(unquote(tz).ok_or_else(invalid)?)),
+            ("DateTime64", [precision]) => {
+                Self::DateTime64(parse_precision(precision, 9).ok_or_else(invalid)?, None)
+            }
+            ("DateTime64", [precision, tz]) => Self::DateTime64(
+                parse_precision(precision, 9).ok_or_else(invalid)?,
+                Some(unquote(tz).ok_or_else(invalid)?),
+            ),
+            ("Decimal", [precision, scale]) => {
+                let precision = parse_precision(precision, 76).ok_or_else(invalid)?;
+                let scale = parse_precision(scale, precision).ok_or_else(invalid)?;
+                Self::Decimal { precision, scale }
+            }
+            ("Decimal32", [scale]) => Self::decimal(9, scale).ok_or_else(invalid)?,
+            ("Decimal64", [scale]) => Self::decimal(18, scale).ok_or_else(invalid)?,
+            ("Decimal128", [scale]) => Self::decimal(38, scale).ok_or_else(invalid)?,
+            ("Decimal256", [scale]) => Self::decimal(76, scale).ok_or_else(invalid)?,
+            ("IPv4", []) => Self::Ipv4,
+            ("IPv6", []) => Self::Ipv6,
+            ("Enum8", variants) => Self::Enum8(parse_enum(variants).ok_or_else(invalid)?),
+            ("Enum16", variants) => Self::Enum16(parse_enum(variants).ok_or_else(invalid)?),
+            ("Nullable", [inner]) => Self::Nullable(Box::new(Self::parse(inner)?)),
+            ("Array", [inner]) => Self::Array(Box::new(Self::parse(inner)?)),
+            ("Map", [key, value]) => {
+                Self::Map(Box::new(Self::parse(key)?), Box::new(Self::parse(value)?))
+            }
+            ("LowCardinality", [inner]) => Self::LowCardinality(Box::new(Self::parse(inner)?)),
+            _ => return Err(SchemaError::UnsupportedType { ty: ty.into() }),
+        };
+
+        // 256-bit decimals don't fit the integer types used for encoding.
+        match parsed {
+            Self::Decimal { precision, .. } if precision > 38 => {
+                Err(SchemaError::UnsupportedType { ty: ty.into() })
+            }
+            parsed => Ok(parsed),
+        }
+    }
+
+    fn decimal(precision: u32, scale: &str) -> Option<Self> {
+        let scale = parse_precision(scale, precision)?;
+        Some(Self::Decimal { precision, scale })
+    }
+
+    /// Removes `LowCardinality` wrappers, which only change how a column is stored.
+    pub fn without_low_cardinality(&self) -> Self {
+        match self {
+            Self::LowCardinality(inner) => inner.without_low_cardinality(),
+            Self::Nullable(inner) => Self::Nullable(Box::new(inner.without_low_cardinality())),
+            Self::Array(inner) => Self::Array(Box::new(inner.without_low_cardinality())),
+            Self::Map(key, value) => Self::Map(
+                Box::new(key.without_low_cardinality()),
+                Box::new(value.without_low_cardinality()),
+            ),
+            ty => ty.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UInt8 => write!(f, "UInt8"),
+            Self::UInt16 => write!(f, "UInt16"),
+            Self::UInt32 => write!(f, "UInt32"),
+            Self::UInt64 => write!(f, "UInt64"),
+            Self::Int8 => write!(f, "Int8"),
+            Self::Int16 => write!(f, "Int16"),
+            Self::Int32 => write!(f, "Int32"),
+            Self::Int64 => write!(f, "Int64"),
+            Self::Float32 => write!(f, "Float32"),
+            Self::Float64 => write!(f, "Float64"),
+            Self::Bool => write!(f, "Bool"),
+            Self::String => write!(f, "String"),
+            Self::FixedString(n) => write!(f, "FixedString({})", n),
+            Self::Uuid => write!(f, "UUID"),
+            Self::Date => write!(f, "Date"),
+            Self::Date32 => write!(f, "Date32"),
+            Self::DateTime(None) => write!(f, "DateTime"),
+            Self::DateTime(Some(tz)) => write!(f, "DateTime('{}')", tz),
+            Self::DateTime64(precision, None) => write!(f, "DateTime64({})", precision),
+            Self::DateTime64(precision, Some(tz)) => {
+                write!(f, "DateTime64({}, '{}')", precision, tz)
+            }
+            Self::Decimal { precision, scale } => write!(f, "Decimal({}, {})", precision, scale),
+            Self::Ipv4 => write!(f, "IPv4"),
+            Self::Ipv6 => write!(f, "IPv6"),
+            Self::Enum8(variants) => write_enum(f, "Enum8", variants),
+            Self::Enum16(variants) => write_enum(f, "Enum16", variants),
+            Self::Nullable(inner) => write!(f, "Nullable({})", inner),
+            Self::Array(inner) => write!(f, "Array({})", inner),
+            Self::Map(key, value) => write!(f, "Map({}, {})", key, value),
+            Self::LowCardinality(inner) => write!(f, "LowCardinality({})", inner),
+        }
+    }
+}
+
+fn write_enum<T: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    variants: &[(String, T)],
+) -> fmt::Result {
+    write!(f, "{}(", name)?;
+    for (i, (variant, value)) in variants.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "'{}' = {}", variant.replace('\'', "\\'"), value)?;
+    }
+    write!(f, ")")
+}
+
+/// Splits the arguments of a parametric type on the commas that aren't nested in
+/// parentheses or quotes.
+fn split_args(args: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in args.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '\'' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth = depth.saturating_sub(1),
+            ',' if !quoted && depth == 0 => {
+                parts.push(args[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !args[start..].trim().is_empty() {
+        parts.push(args[start..].trim());
+    }
+    parts
+}
+
+fn unquote(s: &str) -> Option<String> {
+    let s = s.trim();
+    let inner = s.strip_prefix('\'')?.strip_suffix('\'')?;
+    Some(inner.replace("\\'", "'").replace("\\\\", "\\"))
+}
+
+fn parse_precision(s: &str, max: u32) -> Option<u32> {
+    s.trim().parse().ok().filter(|precision| *precision <= max)
+}
+
+fn parse_enum<T: std::str::FromStr>(variants: &[&str]) -> Option<Vec<(String, T)>> {
+    variants
+        .iter()
+        .map(|variant| {
+            let (name, value) = variant.rsplit_once('=')?;
+            Some((unquote(name)?, value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub ty: ColumnType,
+}
+
+/// The insertable columns of a table, in table order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableSchema {
+    pub columns: Vec<Column>,
+}
+
+impl TableSchema {
+    /// Builds the schema from the `name`, `type` and `default_type` columns returned by
+    /// `DESCRIBE TABLE`. Materialized, alias and ephemeral columns can't be inserted into and
+    /// are skipped.
+    pub fn from_description<'a>(
+        table: &str,
+        rows: impl IntoIterator<Item = (&'a str, &'a str, &'a str)>,
+    ) -> Result<Self, SchemaError> {
+        let columns = rows
+            .into_iter()
+            .filter(|(_, _, default_type)| {
+                !matches!(*default_type, "MATERIALIZED" | "ALIAS" | "EPHEMERAL")
+            })
+            .map(|(name, ty, _)| {
+                Ok(Column {
+                    name: name.to_string(),
+                    ty: ColumnType::parse(ty)?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if columns.is_empty() {
+            return Err(SchemaError::NoColumns {
+                table: table.to_string(),
+            });
+        }
+        Ok(Self { columns })
+    }
+
+    pub fn column_names(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|column| column.name.as_str())
+    }
+}
+
+/// The database and table events are inserted into.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct TableKey {
+    pub database: String,
+    pub table: String,
+}
+
+impl TableKey {
+    /// The fully qualified, quoted name of the table.
+    pub fn qualified_name(&self) -> String {
+        format!(
+            "{}.{}",
+            quote_identifier(&self.database),
+            quote_identifier(&self.table)
+        )
+    }
+}
+
+impl fmt::Display for TableKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.database, self.table)
+    }
+}
+
+pub fn quote_identifier(identifier: &str) -> String {
+    format!(
+        "\"{}\"",
+        identifier.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+/// Schemas fetched per table, shared between the encoding and the sending of events so
+/// that a failed insert can force the schema to be fetched again.
+#[derive(Clone, Debug, Default)]
+pub struct SchemaCache {
+    schemas: Arc<Mutex<HashMap<TableKey, (Instant, Arc<TableSchema>)>>>,
+}
+
+impl SchemaCache {
+    pub fn get(&self, key: &TableKey) -> Option<Arc<TableSchema>> {
+        let schemas = self.schemas.lock().expect("poisoned lock");
+        schemas
+            .get(key)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < SCHEMA_TTL)
+            .map(|(_, schema)| Arc::clone(schema))
+    }
+
+    pub fn insert(&self, key: TableKey, schema: Arc<TableSchema>) {
+        let mut schemas = self.schemas.lock().expect("poisoned lock");
+        if schemas.len() >= MAX_CACHED_SCHEMAS && !schemas.contains_key(&key) {
+            schemas.retain(|_, (fetched_at, _)| fetched_at.elapsed() < SCHEMA_TTL);
+            if schemas.len() >= MAX_CACHED_SCHEMAS {
+                let oldest = schemas
+                    .iter()
+                    .min_by_key(|(_, (fetched_at, _))| *fetched_at)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    schemas.remove(&oldest);
+                }
+            }
+        }
+        schemas.insert(key, (Instant::now(), schema));
+    }
+
+    pub fn invalidate(&self, key: &TableKey) {
+        let mut schemas = self.schemas.lock().expect("poisoned lock");
+        schemas.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_types() {
+        let cases = [
+            ("UInt8", ColumnType::UInt8),
+            ("String", ColumnType::String),
+            ("FixedString(16)", ColumnType::FixedString(16)),
+            ("DateTime", ColumnType::DateTime(None)),
+            (
+                "DateTime('Europe/Paris')",
+                ColumnType::DateTime(Some("Europe/Paris".into())),
+            ),
+            (
+                "DateTime64(3, 'UTC')",
+                ColumnType::DateTime64(3, Some("UTC".into())),
+            ),
+            (
+                "Decimal(10, 2)",
+                ColumnType::Decimal {
+                    precision: 10,
+                    scale: 2,
+                },
+            ),
+            (
+                "Decimal64(4)",
+                ColumnType::Decimal {
+                    precision: 18,
+                    scale: 4,
+                },
+            ),
+            (
+                "Enum8('a' = 1, 'b,c' = -2)",
+                ColumnType::Enum8(vec![("a".into(), 1), ("b,c".into(), -2)]),
+            ),
+            (
+                "Map(LowCardinality(String), Array(Nullable(Int64)))",
+                ColumnType::Map(
+                    Box::new(ColumnType::LowCardinality(Box::new(ColumnType::String))),
+                    Box::new(ColumnType::Array(Box::new(ColumnType::Nullable(Box::new(
+                        ColumnType::Int64,
+                    ))))),
+                ),
+            ),
+        ];
+
+        for (input, expected) in cases {
+            let parsed = ColumnType::parse(input).unwrap();
+            assert_eq!(parsed, expected, "{}", input);
+            assert_eq!(ColumnType::parse(&parsed.to_string()).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_types() {
+        for input in ["Tuple(String, UInt8)", "Decimal256(2)", "Object('json')"] {
+            assert_eq!(
+                ColumnType::parse(input),
+                Err(SchemaError::UnsupportedType { ty: input.into() })
+            );
+        }
+        assert_eq!(
+            ColumnType::parse("FixedString(x)"),
+            Err(SchemaError::InvalidType {
+                ty: "FixedString(x)".into()
+            })
+        );
+    }
+
+    #[test]
+    fn skips_non_insertable_columns() {
+        let schema = TableSchema::from_description(
+            "logs",
+            vec![
+                ("message", "String", ""),
+                ("length", "UInt64", "MATERIALIZED"),
+                ("host", "LowCardinality(String)", "DEFAULT"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            schema.column_names().collect::<Vec<_>>(),
+            vec!["message", "host"]
+        );
+    }
+
+    #[test]
+    fn bounds_schema_cache() {
+        let cache = SchemaCache::default();
+        let schema = Arc::new(
+            TableSchema::from_description("logs", vec![("message", "String", "")]).unwrap(),
+        );
+        let key = |n: usize| TableKey {
+            database: "db".into(),
+            table: format!("logs_{}", n),
+        };
+
+        for n in 0..=MAX_CACHED_SCHEMAS {
+            cache.insert(key(n), Arc::clone(&schema));
+        }
+
+        assert_eq!(cache.schemas.lock().unwrap().len(), MAX_CACHED_SCHEMAS);
+        assert!(cache.get(&key(MAX_CACHED_SCHEMAS)).is_some());
+    }
+
+    #[test]
+    fn strips_low_cardinality() {
+        let ty = ColumnType::parse("Array(LowCardinality(Nullable(String)))").unwrap();
+        assert_eq!(
+            ty.without_low_cardinality(),
+            ColumnType::parse("Array(Nullable(String))").unwrap()
+        );
+    }
+}
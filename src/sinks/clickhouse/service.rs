@@ -0,0 +1,552 @@
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use http::{Request, StatusCode, Uri};
+use hyper::Body;
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+use tower::Service;
+use tracing_futures::Instrument;
+use vector_core::{
+    buffers::Ackable,
+    event::{EventFinalizers, EventStatus, Finalizable, Rejection},
+    internal_event::EventsSent,
+    stream::DriverResponse,
+};
+
+use super::{
+    encoder::NativeBlock,
+    native::{NativeClient, NativeError},
+    schema::{quote_identifier, SchemaCache, SchemaError, TableKey, TableSchema},
+};
+use crate::{
+    http::{Auth, HttpClient, HttpError},
+    internal_events::EndpointBytesSent,
+    sinks::{
+        util::{
+            http::HttpRetryLogic,
+            retries::{RetryAction, RetryLogic},
+            uri::protocol_endpoint,
+            Compression,
+        },
+        UriParseSnafu,
+    },
+};
+
+/// Error codes of refused inserts that would be refused again if retried.
+///
+/// <https://github.com/ClickHouse/ClickHouse/blob/master/src/Common/ErrorCodes.cpp>
+const NON_RETRIABLE_CODES: &[i32] = &[
+    16,  // NO_SUCH_COLUMN_IN_TABLE
+    27,  // CANNOT_PARSE_INPUT_ASSERTION_FAILED
+    53,  // TYPE_MISMATCH
+    60,  // UNKNOWN_TABLE
+    62,  // SYNTAX_ERROR
+    81,  // UNKNOWN_DATABASE
+    117, // INCORRECT_DATA
+    192, // UNKNOWN_USER
+    193, // WRONG_PASSWORD
+    497, // ACCESS_DENIED
+    516, // AUTHENTICATION_FAILED
+];
+
+#[derive(Debug, Snafu)]
+pub enum ClickhouseError {
+    #[snafu(display("HTTP request failed: {}", source))]
+    Http { source: HttpError },
+    #[snafu(display("Server responded with {}: {}", status, body))]
+    HttpStatus { status: StatusCode, body: String },
+    #[snafu(display("Invalid response: {}", source))]
+    InvalidResponse { source: serde_json::Error },
+    #[snafu(display("{}", source))]
+    Native { source: NativeError },
+    #[snafu(display("Unusable schema for table {}: {}", table, source))]
+    Schema {
+        table: TableKey,
+        source: SchemaError,
+    },
+}
+
+impl ClickhouseError {
+    /// Whether the same query could succeed if sent again later.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Self::Http { source } => HttpRetryLogic.is_retriable_error(source),
+            Self::HttpStatus { status, .. } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            Self::Native { source } => match source {
+                NativeError::Server { code, .. } => !NON_RETRIABLE_CODES.contains(code),
+                NativeError::HeaderMismatch { .. } | NativeError::UnsupportedResult { .. } => false,
+                _ => true,
+            },
+            Self::InvalidResponse { .. } | Self::Schema { .. } => false,
+        }
+    }
+}
+
+/// The way events are sent to ClickHouse.
+#[derive(Clone, Debug)]
+pub enum ClickhouseTransport {
+    Http {
+        client: HttpClient,
+        endpoint: Uri,
+        auth: Option<Auth>,
+    },
+    Native(NativeClient),
+}
+
+#[derive(Deserialize)]
+struct DescribedColumn {
+    name: String,
+    #[serde(rename = "type")]
+    ty: String,
+    default_type: String,
+}
+
+impl ClickhouseTransport {
+    pub async fn healthcheck(self) -> crate::Result<()> {
+        match self {
+            Self::Http {
+                client,
+                endpoint,
+                auth,
+            } => {
+                let uri = set_uri_query(&endpoint, "SELECT 1", false)?;
+                let mut request = Request::get(uri).body(Body::empty()).unwrap();
+                if let Some(auth) = &auth {
+                    auth.apply(&mut request);
+                }
+
+                let response = client.send(request).await?;
+                match response.status() {
+                    StatusCode::OK => Ok(()),
+                    status => {
+                        Err(crate::sinks::HealthcheckError::UnexpectedStatus { status }.into())
+                    }
+                }
+            }
+            Self::Native(client) => {
+                let mut connection = client.acquire().await?;
+                connection.ping().await?;
+                client.release(connection);
+                Ok(())
+            }
+        }
+    }
+
+    /// Fetches the insertable columns of the table.
+    pub async fn describe(&self, table: &TableKey) -> Result<TableSchema, ClickhouseError> {
+        let query = format!("DESCRIBE TABLE {}", table.qualified_name());
+        let schema = match self {
+            Self::Http {
+                client,
+                endpoint,
+                auth,
+            } => {
+                let query = format!("{} FORMAT JSONEachRow", query);
+                let uri =
+                    set_uri_query(endpoint, &query, false).expect("Endpoint was already validated");
+                let mut request = Request::get(uri).body(Body::empty()).unwrap();
+                if let Some(auth) = auth {
+                    auth.apply(&mut request);
+                }
+
+                let response = client.send(request).await.context(HttpSnafu)?;
+                let status = response.status();
+                let body = hyper::body::to_bytes(response.into_body())
+                    .await
+                    .map_err(|error| ClickhouseError::Http {
+                        source: HttpError::CallRequest { source: error },
+                    })?;
+                if !status.is_success() {
+                    return Err(ClickhouseError::HttpStatus {
+                        status,
+                        body: String::from_utf8_lossy(&body).into_owned(),
+                    });
+                }
+
+                let columns = serde_json::Deserializer::from_slice(&body)
+                    .into_iter::<DescribedColumn>()
+                    .collect::<Result<Vec<_>, _>>()
+                    .context(InvalidResponseSnafu)?;
+                TableSchema::from_description(
+                    &table.table,
+                    columns.iter().map(|column| {
+                        (
+                            column.name.as_str(),
+                            column.ty.as_str(),
+                            column.default_type.as_str(),
+                        )
+                    }),
+                )
+            }
+            Self::Native(client) => {
+                let mut connection = client.acquire().await.context(NativeSnafu)?;
+                let rows = connection.query(&query).await.context(NativeSnafu)?;
+                client.release(connection);
+
+                TableSchema::from_description(
+                    &table.table,
+                    rows.iter()
+                        .filter(|row| row.len() >= 3)
+                        .map(|row| (row[0].as_str(), row[1].as_str(), row[2].as_str())),
+                )
+            }
+        };
+
+        schema.context(SchemaSnafu {
+            table: table.clone(),
+        })
+    }
+}
+
+/// The formats events are inserted with.
+#[derive(Clone, Debug)]
+pub enum ClickhousePayload {
+    JsonEachRow(Bytes),
+    RowBinary { columns: Vec<String>, body: Bytes },
+    Native(NativeBlock),
+}
+
+#[derive(Clone, Debug)]
+pub struct ClickhouseRequest {
+    pub table: TableKey,
+    pub payload: ClickhousePayload,
+    pub compression: Compression,
+    pub skip_unknown_fields: bool,
+    pub finalizers: EventFinalizers,
+    /// The number of events the request was built from, including the dropped ones.
+    pub batch_size: usize,
+    /// The number of events in the payload.
+    pub events_count: usize,
+    pub events_byte_size: usize,
+}
+
+impl Ackable for ClickhouseRequest {
+    fn ack_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+impl Finalizable for ClickhouseRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+#[derive(Debug)]
+pub struct ClickhouseResponse {
+    /// The response to inserts made over HTTP, checked by the retry logic.
+    http_response: Option<http::Response<Bytes>>,
+    events_count: usize,
+    events_byte_size: usize,
+}
+
+impl DriverResponse for ClickhouseResponse {
+    fn event_status(&self) -> EventStatus {
+        match &self.http_response {
+            Some(response) if !response.status().is_success() => EventStatus::Rejected,
+            _ => EventStatus::Delivered,
+        }
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+
+    fn rejection(&self) -> Option<Rejection> {
+        // ClickHouse explains why it refused the insert in the body of the response.
+        match &self.http_response {
+            Some(response) if !response.status().is_success() => Some(Rejection {
+                reason: format!(
+                    "{}: {}",
+                    response.status(),
+                    String::from_utf8_lossy(response.body()).trim()
+                ),
+                status: Some(response.status().as_u16()),
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ClickhouseService {
+    transport: ClickhouseTransport,
+    schemas: SchemaCache,
+}
+
+impl ClickhouseService {
+    pub const fn new(transport: ClickhouseTransport, schemas: SchemaCache) -> Self {
+        Self { transport, schemas }
+    }
+}
+
+impl Service<ClickhouseRequest> for ClickhouseService {
+    type Response = ClickhouseResponse;
+    type Error = ClickhouseError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: ClickhouseRequest) -> Self::Future {
+        let transport = self.transport.clone();
+        let schemas = self.schemas.clone();
+
+        Box::pin(async move {
+            let uses_schema = !matches!(request.payload, ClickhousePayload::JsonEachRow(_));
+            let result = send(transport, &request).in_current_span().await;
+
+            // The table may have been altered, make sure the next batches use its current schema.
+            let failed = match &result {
+                Ok(Some(response)) => !response.status().is_success(),
+                Ok(None) => false,
+                Err(_) => true,
+            };
+            if uses_schema && failed {
+                schemas.invalidate(&request.table);
+            }
+
+            result.map(|http_response| ClickhouseResponse {
+                http_response,
+                events_count: request.events_count,
+                events_byte_size: request.events_byte_size,
+            })
+        })
+    }
+}
+
+async fn send(
+    transport: ClickhouseTransport,
+    request: &ClickhouseRequest,
+) -> Result<Option<http::Response<Bytes>>, ClickhouseError> {
+    match transport {
+        ClickhouseTransport::Http {
+            client,
+            endpoint,
+            auth,
+        } => {
+            let (query, body, content_type) = match &request.payload {
+                ClickhousePayload::JsonEachRow(body) => (
+                    insert_query(&request.table, None, "JSONEachRow"),
+                    body.clone(),
+                    "application/x-ndjson",
+                ),
+                ClickhousePayload::RowBinary { columns, body } => (
+                    insert_query(&request.table, Some(columns), "RowBinary"),
+                    body.clone(),
+                    "application/octet-stream",
+                ),
+                ClickhousePayload::Native(_) => unreachable!("native blocks are sent over TCP"),
+            };
+            let uri = set_uri_query(&endpoint, &query, request.skip_unknown_fields)
+                .expect("Endpoint was already validated");
+
+            let mut builder = Request::post(&uri).header("Content-Type", content_type);
+            if let Some(ce) = request.compression.content_encoding() {
+                builder = builder.header("Content-Encoding", ce);
+            }
+            let byte_size = body.len();
+            let mut http_request = builder.body(Body::from(body)).unwrap();
+            if let Some(auth) = &auth {
+                auth.apply(&mut http_request);
+            }
+
+            let response = client.send(http_request).await.context(HttpSnafu)?;
+            if response.status().is_success() {
+                let (protocol, endpoint) = protocol_endpoint(uri);
+                emit!(EndpointBytesSent {
+                    byte_size,
+                    protocol: &protocol,
+                    endpoint: &endpoint,
+                });
+            }
+            let (parts, body) = response.into_parts();
+            let body =
+                hyper::body::to_bytes(body)
+                    .await
+                    .map_err(|error| ClickhouseError::Http {
+                        source: HttpError::CallRequest { source: error },
+                    })?;
+            Ok(Some(http::Response::from_parts(parts, body)))
+        }
+        ClickhouseTransport::Native(client) => {
+            let block = match &request.payload {
+                ClickhousePayload::Native(block) => block,
+                _ => unreachable!("native connections only send native blocks"),
+            };
+            let mut connection = client.acquire().await.context(NativeSnafu)?;
+            connection
+                .insert(&request.table, block)
+                .await
+                .context(NativeSnafu)?;
+            client.release(connection);
+            emit!(EndpointBytesSent {
+                byte_size: block.byte_size(),
+                protocol: "tcp",
+                endpoint: &client.endpoint(),
+            });
+            Ok(None)
+        }
+    }
+}
+
+fn insert_query(table: &TableKey, columns: Option<&[String]>, format: &str) -> String {
+    match columns {
+        Some(columns) => {
+            let columns = columns
+                .iter()
+                .map(|column| quote_identifier(column))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "INSERT INTO {} ({}) FORMAT {}",
+                table.qualified_name(),
+                columns,
+                format
+            )
+        }
+        None => format!("INSERT INTO {} FORMAT {}", table.qualified_name(), format),
+    }
+}
+
+pub(super) fn set_uri_query(uri: &Uri, query: &str, skip_unknown: bool) -> crate::Result<Uri> {
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("query", query)
+        .finish();
+
+    let mut uri = uri.to_string();
+    if !uri.ends_with('/') {
+        uri.push('/');
+    }
+    uri.push_str("?input_format_import_nested_json=1&");
+    if skip_unknown {
+        uri.push_str("input_format_skip_unknown_fields=1&");
+    }
+    uri.push_str(query.as_str());
+
+    uri.parse::<Uri>()
+        .context(UriParseSnafu)
+        .map_err(Into::into)
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ClickhouseRetryLogic {
+    inner: HttpRetryLogic,
+}
+
+impl RetryLogic for ClickhouseRetryLogic {
+    type Error = ClickhouseError;
+    type Response = ClickhouseResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        error.is_retriable()
+    }
+
+    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
+        let response = match &response.http_response {
+            Some(response) => response,
+            None => return RetryAction::Successful,
+        };
+
+        match response.status() {
+            StatusCode::INTERNAL_SERVER_ERROR => {
+                let body = response.body();
+
+                // Currently, clickhouse returns 500's incorrect data and type mismatch errors.
+                // This attempts to check if the body starts with `Code: {code_num}` and to not
+                // retry those errors.
+                //
+                // Reference: https://github.com/vectordotdev/vector/pull/693#issuecomment-517332654
+                // Error code definitions: https://github.com/ClickHouse/ClickHouse/blob/master/dbms/src/Common/ErrorCodes.cpp
+                //
+                // Fix already merged: https://github.com/ClickHouse/ClickHouse/pull/6271
+                if body.starts_with(b"Code: 117") {
+                    RetryAction::DontRetry("incorrect data".into())
+                } else if body.starts_with(b"Code: 53") {
+                    RetryAction::DontRetry("type mismatch".into())
+                } else {
+                    RetryAction::Retry(String::from_utf8_lossy(body).to_string().into())
+                }
+            }
+            _ => self.inner.should_retry_response(response),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(database: &str, table: &str) -> TableKey {
+        TableKey {
+            database: database.into(),
+            table: table.into(),
+        }
+    }
+
+    #[test]
+    fn encode_valid() {
+        let uri = set_uri_query(
+            &"http://localhost:80".parse().unwrap(),
+            &insert_query(&table("my_database", "my_table"), None, "JSONEachRow"),
+            false,
+        )
+        .unwrap();
+        assert_eq!(uri.to_string(), "http://localhost:80/?input_format_import_nested_json=1&query=INSERT+INTO+%22my_database%22.%22my_table%22+FORMAT+JSONEachRow");
+
+        let uri = set_uri_query(
+            &"http://localhost:80".parse().unwrap(),
+            &insert_query(&table("my_database", "my_\"table\""), None, "JSONEachRow"),
+            false,
+        )
+        .unwrap();
+        assert_eq!(uri.to_string(), "http://localhost:80/?input_format_import_nested_json=1&query=INSERT+INTO+%22my_database%22.%22my_%5C%22table%5C%22%22+FORMAT+JSONEachRow");
+    }
+
+    #[test]
+    fn encode_invalid() {
+        set_uri_query(
+            &"localhost:80".parse().unwrap(),
+            &insert_query(&table("my_database", "my_table"), None, "JSONEachRow"),
+            false,
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn insert_query_with_columns() {
+        let columns = vec!["message".to_string(), "host".to_string()];
+        assert_eq!(
+            insert_query(&table("db", "logs"), Some(&columns), "RowBinary"),
+            r#"INSERT INTO "db"."logs" ("message", "host") FORMAT RowBinary"#
+        );
+    }
+
+    #[test]
+    fn retries_only_transient_native_errors() {
+        let server_error = |code| ClickhouseError::Native {
+            source: NativeError::Server {
+                code,
+                name: "DB::Exception".into(),
+                message: String::new(),
+            },
+        };
+        assert!(!server_error(53).is_retriable());
+        assert!(server_error(241).is_retriable());
+        assert!(ClickhouseError::Native {
+            source: NativeError::Io {
+                source: std::io::ErrorKind::ConnectionReset.into()
+            }
+        }
+        .is_retriable());
+    }
+}
@@ -0,0 +1,309 @@
+use std::{io::Write, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use bytes::{BufMut, BytesMut};
+use futures::{future, stream::BoxStream, StreamExt};
+use tokio::time::sleep;
+use tower::Service;
+use vector_core::{
+    buffers::Acker,
+    event::{EventFinalizers, Finalizable, Rejection},
+    partition::Partitioner,
+    sink::StreamSink,
+    stream::{BatcherSettings, DriverResponse},
+    ByteSizeOf,
+};
+
+use super::{
+    config::Encoding,
+    encoder::{coerce_row, write_row_binary, NativeBlock},
+    schema::{SchemaCache, TableKey, TableSchema},
+    service::{ClickhouseError, ClickhousePayload, ClickhouseRequest, ClickhouseTransport},
+};
+use crate::{
+    event::Event,
+    internal_events::{
+        ClickhouseEventEncodingError, ClickhouseSchemaFetchError, TemplateRenderingError,
+    },
+    sinks::util::{
+        builder::SinkBuilderExt,
+        encoding::{EncodingConfigWithDefault, EncodingConfiguration},
+        retries::ExponentialBackoff,
+        Compression, Compressor,
+    },
+    template::Template,
+};
+
+/// The number of times the schema of a table is fetched before rejecting the events inserted
+/// into it.
+const SCHEMA_FETCH_ATTEMPTS: usize = 5;
+
+/// Partitions events by the database and table they are inserted into.
+pub struct TablePartitioner {
+    database: Template,
+    table: Template,
+}
+
+impl TablePartitioner {
+    pub const fn new(database: Template, table: Template) -> Self {
+        Self { database, table }
+    }
+
+    fn render(&self, template: &Template, event: &Event, field: &'static str) -> Option<String> {
+        template
+            .render_string(event)
+            .map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some(field),
+                    drop_event: true,
+                });
+            })
+            .ok()
+    }
+}
+
+impl Partitioner for TablePartitioner {
+    type Item = Event;
+    type Key = Option<TableKey>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        Some(TableKey {
+            database: self.render(&self.database, item, "database")?,
+            table: self.render(&self.table, item, "table")?,
+        })
+    }
+}
+
+/// The format of the requests built from the events.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RequestFormat {
+    JsonEachRow,
+    RowBinary,
+    Native,
+}
+
+pub struct ClickhouseRequestBuilder {
+    pub format: RequestFormat,
+    pub encoding: EncodingConfigWithDefault<Encoding>,
+    pub compression: Compression,
+    pub skip_unknown_fields: bool,
+    pub transport: ClickhouseTransport,
+    pub schemas: SchemaCache,
+    pub acker: Acker,
+}
+
+impl ClickhouseRequestBuilder {
+    async fn build(&self, table: TableKey, mut events: Vec<Event>) -> Option<ClickhouseRequest> {
+        let batch_size = events.len();
+
+        if self.format == RequestFormat::JsonEachRow {
+            let finalizers = events.take_finalizers();
+            let events_byte_size = events.size_of();
+            let mut body = BytesMut::new();
+            for mut event in events {
+                self.encoding.apply_rules(&mut event);
+                let log = event.into_log();
+                body.extend_from_slice(
+                    &crate::serde::json::to_bytes(&log).expect("Events should be valid json!"),
+                );
+                body.put_u8(b'\n');
+            }
+
+            return Some(self.request(
+                table,
+                ClickhousePayload::JsonEachRow(self.compress(&body).into()),
+                finalizers,
+                batch_size,
+                batch_size,
+                events_byte_size,
+            ));
+        }
+
+        let schema = match self.schema(&table).await {
+            Ok(schema) => schema,
+            Err(error) => {
+                let rejection = Rejection {
+                    reason: format!("Failed to fetch the schema of the table: {}", error),
+                    status: None,
+                };
+                emit!(ClickhouseSchemaFetchError {
+                    table: &table.to_string(),
+                    error,
+                    dropped: Some(batch_size),
+                });
+                events.take_finalizers().reject(&rejection);
+                self.acker.ack(batch_size);
+                return None;
+            }
+        };
+
+        let table_name = table.to_string();
+        let mut rows = Vec::with_capacity(batch_size);
+        let mut finalizers = EventFinalizers::default();
+        let mut events_byte_size = 0;
+        for mut event in events {
+            self.encoding.apply_rules(&mut event);
+            let byte_size = event.size_of();
+            let mut log = event.into_log();
+            let event_finalizers = log.metadata_mut().take_finalizers();
+            match coerce_row(&log, &schema) {
+                Ok(row) => {
+                    rows.push(row);
+                    finalizers.merge(event_finalizers);
+                    events_byte_size += byte_size;
+                }
+                Err((column, error)) => {
+                    let rejection = Rejection {
+                        reason: format!("Invalid value for column {:?}: {}", column, error),
+                        status: None,
+                    };
+                    emit!(ClickhouseEventEncodingError {
+                        table: &table_name,
+                        column: &column,
+                        error,
+                    });
+                    event_finalizers.reject(&rejection);
+                }
+            }
+        }
+
+        if rows.is_empty() {
+            self.acker.ack(batch_size);
+            return None;
+        }
+
+        let payload = match self.format {
+            RequestFormat::Native => ClickhousePayload::Native(NativeBlock::new(&schema, &rows)),
+            _ => {
+                let mut body = BytesMut::new();
+                for row in &rows {
+                    for (column, cell) in schema.columns.iter().zip(row) {
+                        write_row_binary(&column.ty, cell, &mut body);
+                    }
+                }
+                ClickhousePayload::RowBinary {
+                    columns: schema.column_names().map(Into::into).collect(),
+                    body: self.compress(&body).into(),
+                }
+            }
+        };
+
+        Some(self.request(
+            table,
+            payload,
+            finalizers,
+            batch_size,
+            rows.len(),
+            events_byte_size,
+        ))
+    }
+
+    fn request(
+        &self,
+        table: TableKey,
+        payload: ClickhousePayload,
+        finalizers: EventFinalizers,
+        batch_size: usize,
+        events_count: usize,
+        events_byte_size: usize,
+    ) -> ClickhouseRequest {
+        ClickhouseRequest {
+            table,
+            payload,
+            compression: self.compression,
+            skip_unknown_fields: self.skip_unknown_fields,
+            finalizers,
+            batch_size,
+            events_count,
+            events_byte_size,
+        }
+    }
+
+    fn compress(&self, body: &[u8]) -> BytesMut {
+        let mut compressor = Compressor::from(self.compression);
+        compressor
+            .write_all(body)
+            .expect("Compressing in memory can't fail");
+        compressor
+            .finish()
+            .expect("Compressing in memory can't fail")
+    }
+
+    /// Gets the schema of the table, fetching it if it isn't cached. Fetching is retried with
+    /// a backoff up to `SCHEMA_FETCH_ATTEMPTS` times, unless the server refuses the query.
+    async fn schema(&self, table: &TableKey) -> Result<Arc<TableSchema>, ClickhouseError> {
+        if let Some(schema) = self.schemas.get(table) {
+            return Ok(schema);
+        }
+
+        let mut backoff = ExponentialBackoff::from_millis(2)
+            .factor(250)
+            .max_delay(Duration::from_secs(60));
+        let mut attempts = 1;
+        loop {
+            match self.transport.describe(table).await {
+                Ok(schema) => {
+                    let schema = Arc::new(schema);
+                    self.schemas.insert(table.clone(), Arc::clone(&schema));
+                    return Ok(schema);
+                }
+                Err(error) if error.is_retriable() && attempts < SCHEMA_FETCH_ATTEMPTS => {
+                    emit!(ClickhouseSchemaFetchError {
+                        table: &table.to_string(),
+                        error,
+                        dropped: None,
+                    });
+                    sleep(backoff.next().unwrap()).await;
+                    attempts += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+pub struct ClickhouseSink<S> {
+    pub service: S,
+    pub batch_settings: BatcherSettings,
+    pub partitioner: TablePartitioner,
+    pub request_builder: ClickhouseRequestBuilder,
+}
+
+impl<S> ClickhouseSink<S>
+where
+    S: Service<ClickhouseRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: std::fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let acker = self.request_builder.acker.clone();
+        let request_builder = Arc::new(self.request_builder);
+
+        let sink = input
+            .batched_partitioned(self.partitioner, self.batch_settings)
+            .filter_map(|(key, batch)| async move { key.map(move |key| (key, batch)) })
+            .then(move |(table, events)| {
+                let request_builder = Arc::clone(&request_builder);
+                async move { request_builder.build(table, events).await }
+            })
+            .filter_map(future::ready)
+            .into_driver(self.service, acker);
+
+        sink.run().await
+    }
+}
+
+#[async_trait]
+impl<S> StreamSink<Event> for ClickhouseSink<S>
+where
+    S: Service<ClickhouseRequest> + Send + 'static,
+    S::Future: Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: std::fmt::Debug + Into<crate::Error> + Send,
+{
+    async fn run(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
@@ -11,7 +11,7 @@ use tower::{Service, ServiceExt};
 use vector_core::{internal_event::EventsSent, stream::DriverResponse};
 
 use crate::{
-    event::EventStatus,
+    event::{EventStatus, Rejection},
     http::HttpClient,
     sinks::{
         datadog::events::request_builder::DatadogEventsRequest,
@@ -37,6 +37,13 @@ impl DriverResponse for DatadogEventsResponse {
             output: None,
         }
     }
+
+    fn rejection(&self) -> Option<Rejection> {
+        (self.event_status == EventStatus::Rejected).then(|| Rejection {
+            reason: self.http_status.to_string(),
+            status: Some(self.http_status.as_u16()),
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -11,7 +11,7 @@ use snafu::ResultExt;
 use tower::Service;
 use vector_core::{
     buffers::Ackable,
-    event::{EventFinalizers, EventStatus, Finalizable},
+    event::{EventFinalizers, EventStatus, Finalizable, Rejection},
     internal_event::EventsSent,
     stream::DriverResponse,
 };
@@ -132,6 +132,13 @@ impl DriverResponse for DatadogMetricsResponse {
             output: None,
         }
     }
+
+    fn rejection(&self) -> Option<Rejection> {
+        self.status_code.is_client_error().then(|| Rejection {
+            reason: self.status_code.to_string(),
+            status: Some(self.status_code.as_u16()),
+        })
+    }
 }
 
 #[derive(Clone)]
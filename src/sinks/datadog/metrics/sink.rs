@@ -57,7 +57,7 @@ pub(crate) struct DatadogMetricsSink<S> {
 impl<S> DatadogMetricsSink<S>
 where
     S: Service<DatadogMetricsRequest> + Send,
-    S::Error: fmt::Debug + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send + 'static,
     S::Future: Send + 'static,
     S::Response: DriverResponse,
 {
@@ -123,7 +123,7 @@ where
 impl<S> StreamSink<Event> for DatadogMetricsSink<S>
 where
     S: Service<DatadogMetricsRequest> + Send,
-    S::Error: fmt::Debug + Send + 'static,
+    S::Error: fmt::Debug + Into<crate::Error> + Send + 'static,
     S::Future: Send + 'static,
     S::Response: DriverResponse,
 {
@@ -2,11 +2,11 @@ use bytes::Bytes;
 use vector_core::ByteSizeOf;
 
 use crate::{
-    event::{EventFinalizers, Finalizable},
+    event::Finalizable,
     sinks::{
         elasticsearch::{
             encoder::{ElasticsearchEncoder, ProcessedEvent},
-            service::ElasticsearchRequest,
+            service::{Document, ElasticsearchRequest, PendingDocuments},
         },
         util::{encoding::EncodingConfigFixed, Compression, RequestBuilder},
     },
@@ -18,9 +18,7 @@ pub struct ElasticsearchRequestBuilder {
 }
 
 pub struct Metadata {
-    finalizers: EventFinalizers,
-    batch_size: usize,
-    events_byte_size: usize,
+    documents: Vec<Document>,
 }
 
 impl RequestBuilder<Vec<ProcessedEvent>> for ElasticsearchRequestBuilder {
@@ -40,26 +38,22 @@ impl RequestBuilder<Vec<ProcessedEvent>> for ElasticsearchRequestBuilder {
     }
 
     fn split_input(&self, mut events: Vec<ProcessedEvent>) -> (Self::Metadata, Self::Events) {
-        let events_byte_size = events
-            .iter()
-            .map(|x| x.log.size_of())
-            .reduce(|a, b| a + b)
-            .unwrap_or(0);
-
-        let metadata = Metadata {
-            finalizers: events.take_finalizers(),
-            batch_size: events.len(),
-            events_byte_size,
-        };
-        (metadata, events)
+        let documents = events
+            .iter_mut()
+            .map(|event| Document {
+                byte_size: event.log.size_of(),
+                finalizers: event.take_finalizers(),
+            })
+            .collect();
+
+        (Metadata { documents }, events)
     }
 
     fn build_request(&self, metadata: Self::Metadata, payload: Bytes) -> Self::Request {
         ElasticsearchRequest {
             payload,
-            finalizers: metadata.finalizers,
-            batch_size: metadata.batch_size,
-            events_byte_size: metadata.events_byte_size,
+            batch_size: metadata.documents.len(),
+            pending: PendingDocuments::new(metadata.documents),
         }
     }
 }
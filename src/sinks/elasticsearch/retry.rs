@@ -2,6 +2,7 @@ use http::StatusCode;
 use serde::Deserialize;
 
 use crate::{
+    event::Rejection,
     http::HttpError,
     sinks::{
         elasticsearch::service::ElasticsearchResponse,
@@ -14,6 +15,12 @@ struct EsResultResponse {
     items: Vec<EsResultItem>,
 }
 
+impl EsResultResponse {
+    fn parse(body: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice::<EsResultResponse>(body)
+    }
+}
+
 #[derive(Deserialize, Debug)]
 enum EsResultItem {
     #[serde(rename = "index")]
@@ -34,14 +41,74 @@ impl EsResultItem {
 
 #[derive(Deserialize, Debug)]
 struct EsIndexResult {
+    #[serde(rename = "_index", default)]
+    index: String,
+    #[serde(default)]
+    status: u16,
     error: Option<EsErrorDetails>,
 }
 
 #[derive(Deserialize, Debug)]
-struct EsErrorDetails {
-    reason: String,
+pub struct EsErrorDetails {
+    pub reason: String,
     #[serde(rename = "type")]
-    err_type: String,
+    pub err_type: String,
+}
+
+/// A document of a bulk request that Elasticsearch refused to index.
+#[derive(Debug)]
+pub struct RejectedItem {
+    /// Position of the document in the bulk request.
+    pub position: usize,
+    pub index: String,
+    pub status: u16,
+    pub error: EsErrorDetails,
+}
+
+impl RejectedItem {
+    /// The rejection recorded on the event of the document.
+    pub fn rejection(&self) -> Rejection {
+        Rejection {
+            reason: format!("{}: {}", self.error.err_type, self.error.reason),
+            status: Some(self.status),
+        }
+    }
+}
+
+/// The per-document result of a bulk request that reported errors.
+#[derive(Debug, Default)]
+pub struct BulkOutcome {
+    /// Positions of the documents that failed with a transient error and can be resent.
+    pub retriable: Vec<usize>,
+    /// Documents that will fail again if resent, such as mapping conflicts.
+    pub rejected: Vec<RejectedItem>,
+}
+
+impl BulkOutcome {
+    /// Inspects each item of a bulk response body.
+    ///
+    /// Items rejected with `429 Too Many Requests` or a server error are retriable, other
+    /// errors are permanent.
+    pub fn from_response_body(body: &[u8]) -> Result<Self, serde_json::Error> {
+        let response = EsResultResponse::parse(body)?;
+        let mut outcome = BulkOutcome::default();
+        for (position, item) in response.items.into_iter().enumerate() {
+            let result = item.result();
+            if let Some(error) = result.error {
+                if result.status == 429 || result.status >= 500 {
+                    outcome.retriable.push(position);
+                } else {
+                    outcome.rejected.push(RejectedItem {
+                        position,
+                        index: result.index,
+                        status: result.status,
+                        error,
+                    });
+                }
+            }
+        }
+        Ok(outcome)
+    }
 }
 
 #[derive(Clone)]
@@ -78,7 +145,15 @@ impl RetryLogic for ElasticsearchRetryLogic {
             _ if status.is_success() => {
                 let body = String::from_utf8_lossy(response.http_response.body());
 
-                if body.contains("\"errors\":true") {
+                if response.retriable_items > 0 {
+                    RetryAction::Retry(
+                        format!(
+                            "{} of {} documents failed with a retriable error",
+                            response.retriable_items, response.batch_size
+                        )
+                        .into(),
+                    )
+                } else if body.contains("\"errors\":true") {
                     RetryAction::DontRetry(get_error_reason(&body).into())
                 } else {
                     RetryAction::Successful
@@ -90,7 +165,7 @@ impl RetryLogic for ElasticsearchRetryLogic {
 }
 
 fn get_error_reason(body: &str) -> String {
-    match serde_json::from_str::<EsResultResponse>(body) {
+    match EsResultResponse::parse(body.as_bytes()) {
         Err(json_error) => format!(
             "some messages failed, could not parse response, error: {}",
             json_error
@@ -124,12 +199,55 @@ mod tests {
                 http_response: response,
                 event_status: EventStatus::Rejected,
                 batch_size: 1,
-                events_byte_size: 1,
+                retriable_items: 0,
+                delivered_count: 0,
+                delivered_byte_size: 0,
             }),
             RetryAction::DontRetry(_)
         ));
     }
 
+    #[test]
+    fn retries_partially_failed_response() {
+        let json = r#"{"took":3,"errors":true,"items":[{"index":{"_index":"test","status":201}},{"index":{"_index":"test","status":429,"error":{"type":"es_rejected_execution_exception","reason":"rejected execution"}}}]}"#;
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .body(Bytes::from(json))
+            .unwrap();
+        let logic = ElasticsearchRetryLogic;
+        assert!(matches!(
+            logic.should_retry_response(&ElasticsearchResponse {
+                http_response: response,
+                event_status: EventStatus::Errored,
+                batch_size: 2,
+                retriable_items: 1,
+                delivered_count: 1,
+                delivered_byte_size: 1,
+            }),
+            RetryAction::Retry(_)
+        ));
+    }
+
+    #[test]
+    fn inspects_bulk_items() {
+        let json = r#"{"took":3,"errors":true,"items":[
+            {"index":{"_index":"test","status":201}},
+            {"create":{"_index":"test","status":400,"error":{"type":"mapper_parsing_exception","reason":"failed to parse field [host]"}}},
+            {"index":{"_index":"test","status":429,"error":{"type":"es_rejected_execution_exception","reason":"rejected execution"}}},
+            {"index":{"_index":"test","status":503,"error":{"type":"unavailable_shards_exception","reason":"primary shard is not active"}}}
+        ]}"#;
+        let outcome = BulkOutcome::from_response_body(json.as_bytes()).unwrap();
+
+        assert_eq!(outcome.retriable, vec![2, 3]);
+        assert_eq!(outcome.rejected.len(), 1);
+        let rejected = &outcome.rejected[0];
+        assert_eq!(rejected.position, 1);
+        assert_eq!(rejected.index, "test");
+        assert_eq!(rejected.status, 400);
+        assert_eq!(rejected.error.err_type, "mapper_parsing_exception");
+        assert_eq!(rejected.error.reason, "failed to parse field [host]");
+    }
+
     #[test]
     fn get_index_error_reason() {
         let json = "{\"took\":185,\"errors\":true,\"items\":[{\"index\":{\"_index\":\"test-hgw28jv10u\",\"_type\":\"log_lines\",\"_id\":\"3GhQLXEBE62DvOOUKdFH\",\"status\":400,\"error\":{\"type\":\"illegal_argument_exception\",\"reason\":\"mapper [message] of different type, current_type [long], merged_type [text]\"}}}]}";
@@ -2,41 +2,148 @@ use aws_types::credentials::SharedCredentialsProvider;
 use aws_types::region::Region;
 use std::{
     collections::HashMap,
-    sync::Arc,
+    io::{self, Read, Write},
+    mem,
+    sync::{Arc, Mutex, MutexGuard},
     task::{Context, Poll},
 };
 
 use bytes::Bytes;
+use flate2::read::MultiGzDecoder;
 use futures::future::BoxFuture;
-use http::{Response, Uri};
+use http::{Response, StatusCode, Uri};
 use hyper::{service::Service, Body, Request};
 use tower::ServiceExt;
 use vector_core::{
     buffers::Ackable, internal_event::EventsSent, stream::DriverResponse, ByteSizeOf,
 };
 
-use crate::sinks::elasticsearch::sign_request;
+use crate::sinks::elasticsearch::{retry::BulkOutcome, sign_request};
 use crate::{
-    event::{EventFinalizers, EventStatus, Finalizable},
+    event::{EventFinalizers, EventStatus, Finalizable, Rejection},
     http::{Auth, HttpClient},
-    internal_events::ElasticsearchResponseError,
+    internal_events::{ElasticsearchBulkItemRejected, ElasticsearchResponseError},
     sinks::util::{
         http::{HttpBatchService, RequestConfig},
-        Compression, ElementCount,
+        Compression, Compressor, ElementCount,
     },
 };
 
 #[derive(Clone)]
 pub struct ElasticsearchRequest {
     pub payload: Bytes,
-    pub finalizers: EventFinalizers,
     pub batch_size: usize,
-    pub events_byte_size: usize,
+    pub pending: PendingDocuments,
+}
+
+/// A document of a bulk request.
+pub struct Document {
+    pub finalizers: EventFinalizers,
+    pub byte_size: usize,
+}
+
+/// Tracks the outcome of each document of a bulk request.
+///
+/// The retry layer resends clones of the original request, so this state is shared between
+/// them. When only some documents of a bulk request fail with a retriable error, the payload
+/// is narrowed down to those documents so that the retry doesn't index the others twice.
+///
+/// Each document is finalized as soon as Elasticsearch accepts or rejects it. The documents
+/// still pending once the request is given up on, when the retries are exhausted, are marked
+/// as errored.
+#[derive(Clone, Default)]
+pub struct PendingDocuments(Arc<Mutex<PendingState>>);
+
+#[derive(Default)]
+struct PendingState {
+    /// The payload holding only the pending documents, once narrowed down.
+    payload: Option<Bytes>,
+    /// The documents of the request, in the order of the original payload.
+    documents: Vec<Document>,
+    /// Positions in `documents` of the documents still to be sent, in payload order.
+    pending: Vec<usize>,
+    delivered_count: usize,
+    delivered_byte_size: usize,
+}
+
+impl PendingDocuments {
+    pub fn new(documents: Vec<Document>) -> Self {
+        let pending = (0..documents.len()).collect();
+        Self(Arc::new(Mutex::new(PendingState {
+            payload: None,
+            documents,
+            pending,
+            delivered_count: 0,
+            delivered_byte_size: 0,
+        })))
+    }
+
+    fn payload(&self) -> Option<Bytes> {
+        self.lock().payload.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PendingState> {
+        self.0.lock().expect("poisoned lock")
+    }
+}
+
+impl PendingState {
+    fn deliver(&mut self, document: usize) {
+        let document = &self.documents[document];
+        document.finalizers.update_status(EventStatus::Delivered);
+        self.delivered_count += 1;
+        self.delivered_byte_size += document.byte_size;
+    }
+
+    fn deliver_all(&mut self) {
+        for document in mem::take(&mut self.pending) {
+            self.deliver(document);
+        }
+        self.payload = None;
+    }
+
+    fn reject_all(&mut self, rejection: &Rejection) {
+        for document in mem::take(&mut self.pending) {
+            self.documents[document].finalizers.reject(rejection);
+        }
+        self.payload = None;
+    }
+
+    /// Finalizes the pending documents the bulk response reports as indexed or rejected, and
+    /// keeps the retriable ones pending along with the payload to resend them.
+    fn update(&mut self, outcome: &BulkOutcome, payload: Option<Bytes>) {
+        let mut retriable = outcome.retriable.iter().peekable();
+        let mut rejected = outcome.rejected.iter().peekable();
+        let mut still_pending = Vec::with_capacity(outcome.retriable.len());
+        for (position, document) in mem::take(&mut self.pending).into_iter().enumerate() {
+            if retriable.next_if(|&&p| p == position).is_some() {
+                still_pending.push(document);
+            } else if let Some(item) = rejected.next_if(|item| item.position == position) {
+                self.documents[document]
+                    .finalizers
+                    .reject(&item.rejection());
+            } else {
+                self.deliver(document);
+            }
+        }
+        self.pending = still_pending;
+        self.payload = payload;
+    }
+}
+
+impl Drop for PendingState {
+    fn drop(&mut self) {
+        for &document in &self.pending {
+            self.documents[document]
+                .finalizers
+                .update_status(EventStatus::Errored);
+        }
+    }
 }
 
 impl ByteSizeOf for ElasticsearchRequest {
     fn allocated_bytes(&self) -> usize {
-        self.payload.allocated_bytes() + self.finalizers.allocated_bytes()
+        self.payload.allocated_bytes()
     }
 }
 
@@ -54,7 +161,8 @@ impl Ackable for ElasticsearchRequest {
 
 impl Finalizable for ElasticsearchRequest {
     fn take_finalizers(&mut self) -> EventFinalizers {
-        std::mem::take(&mut self.finalizers)
+        // Each document is finalized with its own outcome by `PendingDocuments`.
+        EventFinalizers::default()
     }
 }
 
@@ -64,6 +172,7 @@ pub struct ElasticsearchService {
         BoxFuture<'static, Result<http::Request<Bytes>, crate::Error>>,
         ElasticsearchRequest,
     >,
+    compression: Compression,
 }
 
 impl ElasticsearchService {
@@ -71,6 +180,7 @@ impl ElasticsearchService {
         http_client: HttpClient<Body>,
         http_request_builder: HttpRequestBuilder,
     ) -> ElasticsearchService {
+        let compression = http_request_builder.compression;
         let http_request_builder = Arc::new(http_request_builder);
         let batch_service = HttpBatchService::new(http_client, move |req| {
             let request_builder = Arc::clone(&http_request_builder);
@@ -78,7 +188,10 @@ impl ElasticsearchService {
                 Box::pin(async move { request_builder.build_request(req).await });
             future
         });
-        ElasticsearchService { batch_service }
+        ElasticsearchService {
+            batch_service,
+            compression,
+        }
     }
 }
 
@@ -129,7 +242,11 @@ pub struct ElasticsearchResponse {
     pub http_response: Response<Bytes>,
     pub event_status: EventStatus,
     pub batch_size: usize,
-    pub events_byte_size: usize,
+    /// Number of documents that failed with a retriable error and are pending a retry.
+    pub retriable_items: usize,
+    /// Number of documents indexed by this request and its previous attempts.
+    pub delivered_count: usize,
+    pub delivered_byte_size: usize,
 }
 
 impl DriverResponse for ElasticsearchResponse {
@@ -139,8 +256,8 @@ impl DriverResponse for ElasticsearchResponse {
 
     fn events_sent(&self) -> EventsSent {
         EventsSent {
-            count: self.batch_size,
-            byte_size: self.events_byte_size,
+            count: self.delivered_count,
+            byte_size: self.delivered_byte_size,
             output: None,
         }
     }
@@ -155,48 +272,360 @@ impl Service<ElasticsearchRequest> for ElasticsearchService {
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, req: ElasticsearchRequest) -> Self::Future {
+    fn call(&mut self, mut req: ElasticsearchRequest) -> Self::Future {
         let mut http_service = self.batch_service.clone();
+        let compression = self.compression;
         Box::pin(async move {
             http_service.ready().await?;
             let batch_size = req.batch_size;
-            let events_byte_size = req.events_byte_size;
+            let pending = req.pending.clone();
+            if let Some(payload) = pending.payload() {
+                req.payload = payload;
+            }
+            let payload = req.payload.clone();
             let http_response = http_service.call(req).await?;
-            let event_status = get_event_status(&http_response);
+            let (event_status, retriable_items) =
+                get_event_status(&http_response, &payload, compression, &pending);
+            let (delivered_count, delivered_byte_size) = {
+                let state = pending.lock();
+                (state.delivered_count, state.delivered_byte_size)
+            };
             Ok(ElasticsearchResponse {
                 event_status,
                 http_response,
                 batch_size,
-                events_byte_size,
+                retriable_items,
+                delivered_count,
+                delivered_byte_size,
             })
         })
     }
 }
 
-fn get_event_status(response: &Response<Bytes>) -> EventStatus {
+fn get_event_status(
+    response: &Response<Bytes>,
+    payload: &Bytes,
+    compression: Compression,
+    pending: &PendingDocuments,
+) -> (EventStatus, usize) {
     let status = response.status();
     if status.is_success() {
         let body = String::from_utf8_lossy(response.body());
         if body.contains("\"errors\":true") {
             emit!(ElasticsearchResponseError::new(
-                "Response containerd errors.",
+                "Response contained errors.",
                 response
             ));
-            EventStatus::Rejected
+            inspect_bulk_items(response.body(), payload, compression, pending)
         } else {
-            EventStatus::Delivered
+            pending.lock().deliver_all();
+            (EventStatus::Delivered, 0)
         }
-    } else if status.is_server_error() {
+    } else if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
         emit!(ElasticsearchResponseError::new(
             "Response wasn't successful.",
             response,
         ));
-        EventStatus::Errored
+        (EventStatus::Errored, 0)
     } else {
         emit!(ElasticsearchResponseError::new(
             "Response failed.",
             response,
         ));
-        EventStatus::Rejected
+        pending.lock().reject_all(&Rejection {
+            reason: format!(
+                "{}: {}",
+                status,
+                String::from_utf8_lossy(response.body()).trim()
+            ),
+            status: Some(status.as_u16()),
+        });
+        (EventStatus::Rejected, 0)
+    }
+}
+
+/// Finalizes the documents Elasticsearch indexed or rejected and narrows the pending payload
+/// down to the documents that can be retried.
+fn inspect_bulk_items(
+    body: &[u8],
+    payload: &Bytes,
+    compression: Compression,
+    pending: &PendingDocuments,
+) -> (EventStatus, usize) {
+    let outcome = match BulkOutcome::from_response_body(body) {
+        Ok(outcome) => outcome,
+        Err(error) => {
+            error!(message = "Failed to parse bulk response.", %error);
+            pending.lock().reject_all(&Rejection {
+                reason: format!("Failed to parse bulk response: {}", error),
+                status: None,
+            });
+            return (EventStatus::Rejected, 0);
+        }
+    };
+
+    for item in &outcome.rejected {
+        emit!(ElasticsearchBulkItemRejected {
+            index: &item.index,
+            status: item.status,
+            rejection_type: &item.error.err_type,
+            reason: &item.error.reason,
+        });
+    }
+
+    let mut state = pending.lock();
+    if outcome.retriable.is_empty() {
+        state.update(&outcome, None);
+    } else {
+        match select_documents(payload, &outcome.retriable, compression) {
+            Ok(retry_payload) => state.update(&outcome, Some(retry_payload)),
+            Err(error) => {
+                error!(message = "Failed to build retry payload.", %error);
+                state.update(&outcome, None);
+                state.reject_all(&Rejection {
+                    reason: format!("Failed to build retry payload: {}", error),
+                    status: None,
+                });
+            }
+        }
+    }
+
+    let retriable = state.pending.len();
+    if retriable > 0 {
+        (EventStatus::Errored, retriable)
+    } else if state.delivered_count == 0 {
+        (EventStatus::Rejected, 0)
+    } else {
+        (EventStatus::Delivered, 0)
+    }
+}
+
+/// Builds a bulk payload holding only the documents at the given positions of `payload`.
+///
+/// Each document of a bulk request is made of an action line followed by a source line.
+fn select_documents(
+    payload: &Bytes,
+    positions: &[usize],
+    compression: Compression,
+) -> io::Result<Bytes> {
+    let mut decoded = Vec::new();
+    match compression {
+        Compression::None => decoded.extend_from_slice(payload),
+        Compression::Gzip(_) => {
+            MultiGzDecoder::new(&payload[..]).read_to_end(&mut decoded)?;
+        }
+    }
+
+    let mut lines = decoded.split(|b| *b == b'\n');
+    let mut compressor = Compressor::from(compression);
+    let mut current = 0;
+    for &position in positions {
+        let skip = (position - current) * 2;
+        let action = lines.nth(skip);
+        let source = lines.next();
+        match (action, source) {
+            (Some(action), Some(source)) => {
+                compressor.write_all(action)?;
+                compressor.write_all(b"\n")?;
+                compressor.write_all(source)?;
+                compressor.write_all(b"\n")?;
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "bulk response has more items than the request",
+                ))
+            }
+        }
+        current = position + 1;
+    }
+
+    Ok(compressor.finish()?.freeze())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{
+        BatchNotifier, BatchStatus, BatchStatusReceiver, EventFinalizer, EventOutcome,
+        EventOutcomes,
+    };
+
+    const PAYLOAD: &str = "{\"index\":{\"_index\":\"a\"}}\n{\"n\":0}\n\
+        {\"index\":{\"_index\":\"a\"}}\n{\"n\":1}\n\
+        {\"index\":{\"_index\":\"a\"}}\n{\"n\":2}\n";
+
+    fn compress(payload: &str, compression: Compression) -> Bytes {
+        let mut compressor = Compressor::from(compression);
+        compressor.write_all(payload.as_bytes()).unwrap();
+        compressor.finish().unwrap().freeze()
+    }
+
+    fn decompress(payload: &Bytes) -> String {
+        let mut decoded = String::new();
+        MultiGzDecoder::new(&payload[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        decoded
+    }
+
+    #[test]
+    fn selects_documents() {
+        let payload = Bytes::from(PAYLOAD);
+        let selected = select_documents(&payload, &[0, 2], Compression::None).unwrap();
+        assert_eq!(
+            &selected[..],
+            &b"{\"index\":{\"_index\":\"a\"}}\n{\"n\":0}\n{\"index\":{\"_index\":\"a\"}}\n{\"n\":2}\n"[..]
+        );
+    }
+
+    #[test]
+    fn selects_compressed_documents() {
+        let compression = Compression::gzip_default();
+        let payload = compress(PAYLOAD, compression);
+        let selected = select_documents(&payload, &[1], compression).unwrap();
+        assert_eq!(
+            decompress(&selected),
+            "{\"index\":{\"_index\":\"a\"}}\n{\"n\":1}\n"
+        );
+    }
+
+    #[test]
+    fn select_documents_out_of_range() {
+        let payload = Bytes::from(PAYLOAD);
+        select_documents(&payload, &[3], Compression::None).unwrap_err();
+    }
+
+    fn tracked_documents(
+        count: usize,
+    ) -> (PendingDocuments, BatchStatusReceiver, Arc<EventOutcomes>) {
+        let (batch, receiver, outcomes) = BatchNotifier::new_tracking_events(count);
+        let documents = (0..count as u32)
+            .map(|index| Document {
+                finalizers: EventFinalizers::new(EventFinalizer::new_tracked(
+                    Arc::clone(&batch),
+                    index,
+                )),
+                byte_size: 10,
+            })
+            .collect();
+        (PendingDocuments::new(documents), receiver, outcomes)
+    }
+
+    fn outcome(status: EventStatus) -> EventOutcome {
+        EventOutcome {
+            status,
+            rejection: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn finalizes_each_document() {
+        let body = r#"{"took":3,"errors":true,"items":[
+            {"index":{"_index":"a","status":201}},
+            {"index":{"_index":"a","status":400,"error":{"type":"mapper_parsing_exception","reason":"failed to parse"}}},
+            {"index":{"_index":"a","status":201}}
+        ]}"#;
+        let response = Response::builder().body(Bytes::from(body)).unwrap();
+        let (pending, receiver, outcomes) = tracked_documents(3);
+        let payload = Bytes::from(PAYLOAD);
+
+        let (status, retriable) =
+            get_event_status(&response, &payload, Compression::None, &pending);
+        assert_eq!(status, EventStatus::Delivered);
+        assert_eq!(retriable, 0);
+        {
+            let state = pending.lock();
+            assert_eq!(state.delivered_count, 2);
+            assert_eq!(state.delivered_byte_size, 20);
+        }
+        drop(pending);
+
+        assert_eq!(receiver.await, BatchStatus::Rejected);
+        assert_eq!(
+            outcomes.take(),
+            vec![
+                outcome(EventStatus::Delivered),
+                EventOutcome {
+                    status: EventStatus::Rejected,
+                    rejection: Some(Rejection {
+                        reason: "mapper_parsing_exception: failed to parse".into(),
+                        status: Some(400),
+                    }),
+                },
+                outcome(EventStatus::Delivered),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn narrows_pending_documents() {
+        let body = r#"{"took":3,"errors":true,"items":[
+            {"index":{"_index":"a","status":201}},
+            {"index":{"_index":"a","status":400,"error":{"type":"mapper_parsing_exception","reason":"failed to parse"}}},
+            {"index":{"_index":"a","status":429,"error":{"type":"es_rejected_execution_exception","reason":"rejected execution"}}}
+        ]}"#;
+        let (pending, receiver, outcomes) = tracked_documents(3);
+        let payload = Bytes::from(PAYLOAD);
+
+        let (status, retriable) =
+            inspect_bulk_items(body.as_bytes(), &payload, Compression::None, &pending);
+        assert_eq!(status, EventStatus::Errored);
+        assert_eq!(retriable, 1);
+        let payload = pending.payload().unwrap();
+        assert_eq!(
+            payload,
+            Bytes::from("{\"index\":{\"_index\":\"a\"}}\n{\"n\":2}\n")
+        );
+
+        // The retried document now succeeds.
+        let body = r#"{"took":3,"errors":false,"items":[{"index":{"_index":"a","status":201}}]}"#;
+        let response = Response::builder().body(Bytes::from(body)).unwrap();
+        let (status, retriable) =
+            get_event_status(&response, &payload, Compression::None, &pending);
+        assert_eq!(status, EventStatus::Delivered);
+        assert_eq!(retriable, 0);
+        assert!(pending.payload().is_none());
+        drop(pending);
+
+        assert_eq!(receiver.await, BatchStatus::Rejected);
+        let statuses = outcomes
+            .take()
+            .into_iter()
+            .map(|outcome| outcome.status)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            vec![
+                EventStatus::Delivered,
+                EventStatus::Rejected,
+                EventStatus::Delivered
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_documents_left_pending() {
+        let body = r#"{"took":3,"errors":true,"items":[
+            {"index":{"_index":"a","status":201}},
+            {"index":{"_index":"a","status":503,"error":{"type":"unavailable_shards_exception","reason":"primary shard is not active"}}},
+            {"index":{"_index":"a","status":201}}
+        ]}"#;
+        let (pending, receiver, outcomes) = tracked_documents(3);
+        let payload = Bytes::from(PAYLOAD);
+
+        inspect_bulk_items(body.as_bytes(), &payload, Compression::None, &pending);
+        // The retries are exhausted.
+        drop(pending);
+
+        assert_eq!(receiver.await, BatchStatus::Errored);
+        assert_eq!(
+            outcomes.take(),
+            vec![
+                outcome(EventStatus::Delivered),
+                outcome(EventStatus::Errored),
+                outcome(EventStatus::Delivered),
+            ]
+        );
     }
 }
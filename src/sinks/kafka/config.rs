@@ -1,8 +1,9 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, num::NonZeroUsize, time::Duration};
 
 use futures::FutureExt;
 use rdkafka::ClientConfig;
 use serde::{Deserialize, Serialize};
+use vector_core::stream::BatcherSettings;
 
 use crate::{
     config::{AcknowledgementsConfig, DataType, GenerateConfig, Input, SinkConfig, SinkContext},
@@ -47,6 +48,79 @@ pub(crate) struct KafkaSinkConfig {
         skip_serializing_if = "crate::serde::skip_serializing_if_default"
     )]
     pub acknowledgements: AcknowledgementsConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exactly_once: Option<ExactlyOnceConfig>,
+}
+
+/// Produces events in Kafka transactions, so that consumers reading with the `read_committed`
+/// isolation level see each event exactly once.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ExactlyOnceConfig {
+    /// Identifies the producer across restarts. A new producer with the same id fences the
+    /// previous one and aborts its open transaction.
+    pub transactional_id: String,
+    #[serde(default = "default_transaction_timeout_ms")]
+    pub transaction_timeout_ms: u64,
+    /// The maximum number of events produced in a single transaction.
+    #[serde(default = "default_transaction_max_events")]
+    pub max_events: usize,
+    /// The maximum time events are buffered before their transaction is committed.
+    #[serde(default = "default_transaction_timeout_secs")]
+    pub timeout_secs: f64,
+    #[serde(default)]
+    pub on_fenced: FencedBehavior,
+    /// Commits the offsets of the events read by a `kafka` source in the same transaction.
+    pub source_offsets: Option<SourceOffsetsConfig>,
+}
+
+/// What to do when another producer with the same `transactional_id` takes over.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, Serialize, PartialEq)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum FencedBehavior {
+    /// Stops the sink, leaving the newer producer in charge.
+    #[derivative(Default)]
+    Shutdown,
+    /// Creates a new producer, fencing the other one in turn.
+    Reinitialize,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct SourceOffsetsConfig {
+    /// The consumer group of the source, whose offsets are committed.
+    pub group_id: String,
+    #[serde(default = "default_topic_key")]
+    pub topic_key: String,
+    #[serde(default = "default_partition_key")]
+    pub partition_key: String,
+    #[serde(default = "default_offset_key")]
+    pub offset_key: String,
+}
+
+const fn default_transaction_timeout_ms() -> u64 {
+    60000 // default in librdkafka
+}
+
+const fn default_transaction_max_events() -> usize {
+    1000
+}
+
+const fn default_transaction_timeout_secs() -> f64 {
+    1.0
+}
+
+fn default_topic_key() -> String {
+    "topic".into()
+}
+
+fn default_partition_key() -> String {
+    "partition".into()
+}
+
+fn default_offset_key() -> String {
+    "offset".into()
 }
 
 const fn default_socket_timeout_ms() -> u64 {
@@ -82,6 +156,21 @@ impl KafkaSinkConfig {
                     .set("compression.codec", &to_string(self.compression))
                     .set("message.timeout.ms", &self.message_timeout_ms.to_string());
 
+                if let Some(exactly_once) = &self.exactly_once {
+                    // librdkafka refuses message timeouts longer than the transaction timeout.
+                    let message_timeout_ms = self
+                        .message_timeout_ms
+                        .min(exactly_once.transaction_timeout_ms);
+                    client_config
+                        .set("transactional.id", &exactly_once.transactional_id)
+                        .set(
+                            "transaction.timeout.ms",
+                            &exactly_once.transaction_timeout_ms.to_string(),
+                        )
+                        .set("message.timeout.ms", &message_timeout_ms.to_string())
+                        .set("enable.idempotence", "true");
+                }
+
                 if let Some(value) = self.batch.timeout_secs {
                     // Delay in milliseconds to wait for messages in the producer queue to accumulate before
                     // constructing message batches (MessageSets) to transmit to brokers. A higher value
@@ -172,6 +261,7 @@ impl GenerateConfig for KafkaSinkConfig {
             librdkafka_options: Default::default(),
             headers_key: None,
             acknowledgements: Default::default(),
+            exactly_once: None,
         })
         .unwrap()
     }
@@ -199,6 +289,22 @@ impl SinkConfig for KafkaSinkConfig {
     }
 }
 
+impl ExactlyOnceConfig {
+    pub(crate) fn batch_settings(&self) -> crate::Result<BatcherSettings> {
+        if self.max_events == 0 {
+            return Err("`exactly_once.max_events` must be greater than zero.".into());
+        }
+        if self.timeout_secs <= 0.0 {
+            return Err("`exactly_once.timeout_secs` must be greater than zero.".into());
+        }
+        Ok(BatcherSettings::new(
+            Duration::from_secs_f64(self.timeout_secs),
+            NonZeroUsize::new(usize::MAX).unwrap(),
+            NonZeroUsize::new(self.max_events).unwrap(),
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -207,4 +313,32 @@ mod tests {
     fn generate_config() {
         KafkaSinkConfig::generate_config();
     }
+
+    #[test]
+    fn exactly_once_sets_transactional_options() {
+        let config: KafkaSinkConfig = toml::from_str(
+            r#"
+            bootstrap_servers = "localhost:9092"
+            topic = "billing"
+            encoding.codec = "json"
+
+            [exactly_once]
+            transactional_id = "billing-1"
+            source_offsets.group_id = "billing"
+            "#,
+        )
+        .unwrap();
+
+        let exactly_once = config.exactly_once.as_ref().unwrap();
+        assert_eq!(exactly_once.on_fenced, FencedBehavior::Shutdown);
+        assert_eq!(
+            exactly_once.source_offsets.as_ref().unwrap().offset_key,
+            "offset"
+        );
+
+        let client_config = config.to_rdkafka(KafkaRole::Producer).unwrap();
+        assert_eq!(client_config.get("transactional.id"), Some("billing-1"));
+        assert_eq!(client_config.get("transaction.timeout.ms"), Some("60000"));
+        assert_eq!(client_config.get("message.timeout.ms"), Some("60000"));
+    }
 }
@@ -11,9 +11,12 @@ use rdkafka::{
 use snafu::{ResultExt, Snafu};
 use tokio::time::Duration;
 use tower::limit::ConcurrencyLimit;
-use vector_core::{buffers::Acker, config::log_schema};
+use vector_core::{buffers::Acker, config::log_schema, stream::BatcherSettings};
 
-use super::config::{KafkaRole, KafkaSinkConfig};
+use super::{
+    config::{KafkaRole, KafkaSinkConfig, SourceOffsetsConfig},
+    transaction::{TransactionRequest, TransactionService},
+};
 use crate::{
     event::Event,
     kafka::KafkaStatisticsContext,
@@ -42,12 +45,21 @@ pub(super) enum BuildError {
 pub struct KafkaSink {
     encoding: EncodingConfig<StandardEncodings>,
     acker: Acker,
-    service: KafkaService,
+    service: KafkaSinkService,
     topic: Template,
     key_field: Option<String>,
     headers_key: Option<String>,
 }
 
+enum KafkaSinkService {
+    Producer(KafkaService),
+    Transactional {
+        service: TransactionService,
+        batch_settings: BatcherSettings,
+        source_offsets: Option<SourceOffsetsConfig>,
+    },
+}
+
 pub(crate) fn create_producer(
     client_config: ClientConfig,
 ) -> crate::Result<FutureProducer<KafkaStatisticsContext>> {
@@ -60,21 +72,36 @@ pub(crate) fn create_producer(
 impl KafkaSink {
     pub(crate) fn new(config: KafkaSinkConfig, acker: Acker) -> crate::Result<Self> {
         let producer_config = config.to_rdkafka(KafkaRole::Producer)?;
-        let producer = create_producer(producer_config)?;
+        let service = match &config.exactly_once {
+            None => {
+                KafkaSinkService::Producer(KafkaService::new(create_producer(producer_config)?))
+            }
+            Some(exactly_once) => KafkaSinkService::Transactional {
+                service: TransactionService::new(
+                    producer_config,
+                    exactly_once
+                        .source_offsets
+                        .as_ref()
+                        .map(|source_offsets| source_offsets.group_id.clone()),
+                    Duration::from_millis(exactly_once.transaction_timeout_ms),
+                    exactly_once.on_fenced,
+                ),
+                batch_settings: exactly_once.batch_settings()?,
+                source_offsets: exactly_once.source_offsets.clone(),
+            },
+        };
 
         Ok(KafkaSink {
             headers_key: config.headers_key,
             encoding: config.encoding,
             acker,
-            service: KafkaService::new(producer),
+            service,
             topic: Template::try_from(config.topic).context(TopicTemplateSnafu)?,
             key_field: config.key_field,
         })
     }
 
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
-        // rdkafka will internally retry forever, so we need some limit to prevent this from overflowing
-        let service = ConcurrencyLimit::new(self.service, QUEUED_MIN_MESSAGES as usize);
         let request_builder = KafkaRequestBuilder {
             key_field: self.key_field,
             headers_key: self.headers_key,
@@ -82,10 +109,29 @@ impl KafkaSink {
             encoder: self.encoding,
             log_schema: log_schema(),
         };
-        let sink = input
-            .filter_map(|event| future::ready(request_builder.build_request(event)))
-            .into_driver(service, self.acker);
-        sink.run().await
+        match self.service {
+            KafkaSinkService::Producer(service) => {
+                // rdkafka will internally retry forever, so we need some limit to prevent this from overflowing
+                let service = ConcurrencyLimit::new(service, QUEUED_MIN_MESSAGES as usize);
+                let sink = input
+                    .filter_map(|event| future::ready(request_builder.build_request(event)))
+                    .into_driver(service, self.acker);
+                sink.run().await
+            }
+            KafkaSinkService::Transactional {
+                service,
+                batch_settings,
+                source_offsets,
+            } => {
+                let sink = input
+                    .batched(batch_settings.into_byte_size_config())
+                    .map(|events| {
+                        TransactionRequest::new(events, &request_builder, source_offsets.as_ref())
+                    })
+                    .into_driver(service, self.acker);
+                sink.run().await
+            }
+        }
     }
 }
 
@@ -27,7 +27,7 @@ mod integration_test {
         kafka::{KafkaAuthConfig, KafkaCompression, KafkaSaslConfig, KafkaTlsConfig},
         sinks::{
             kafka::{
-                config::{KafkaRole, KafkaSinkConfig},
+                config::{ExactlyOnceConfig, FencedBehavior, KafkaRole, KafkaSinkConfig},
                 sink::KafkaSink,
                 *,
             },
@@ -68,6 +68,7 @@ mod integration_test {
             librdkafka_options: HashMap::new(),
             headers_key: None,
             acknowledgements: Default::default(),
+            exactly_once: None,
         };
         self::sink::healthcheck(config).await.unwrap();
     }
@@ -123,6 +124,7 @@ mod integration_test {
             librdkafka_options,
             headers_key: None,
             acknowledgements: Default::default(),
+            exactly_once: None,
         };
         let (acker, _ack_counter) = Acker::basic();
         config.clone().to_rdkafka(KafkaRole::Consumer)?;
@@ -244,6 +246,82 @@ mod integration_test {
         .await;
     }
 
+    #[tokio::test]
+    async fn kafka_exactly_once() {
+        crate::test_util::trace_init();
+
+        let server = kafka_address(9091);
+        let topic = format!("test-{}", random_string(10));
+        let config = KafkaSinkConfig {
+            bootstrap_servers: server.clone(),
+            topic: topic.clone(),
+            key_field: None,
+            encoding: EncodingConfig::from(StandardEncodings::Text),
+            batch: BatchConfig::default(),
+            compression: KafkaCompression::None,
+            auth: KafkaAuthConfig::default(),
+            socket_timeout_ms: 60000,
+            message_timeout_ms: 300000,
+            librdkafka_options: HashMap::new(),
+            headers_key: None,
+            acknowledgements: Default::default(),
+            exactly_once: Some(ExactlyOnceConfig {
+                transactional_id: random_string(10),
+                transaction_timeout_ms: 60000,
+                max_events: 100,
+                timeout_secs: 1.0,
+                on_fenced: FencedBehavior::Shutdown,
+                source_offsets: None,
+            }),
+        };
+        let (acker, ack_counter) = Acker::basic();
+        let sink = KafkaSink::new(config, acker).unwrap();
+        let sink = VectorSink::from_event_streamsink(sink);
+
+        let num_events = 1000;
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let (input, events) = random_lines_with_stream(100, num_events, Some(batch));
+        components::init_test();
+        sink.run(events).await.unwrap();
+        components::SINK_TESTS.assert(&["protocol"]);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+        assert_eq!(
+            ack_counter.load(std::sync::atomic::Ordering::Relaxed),
+            num_events
+        );
+
+        let mut client_config = rdkafka::ClientConfig::new();
+        client_config.set("bootstrap.servers", server.as_str());
+        client_config.set("group.id", &random_string(10));
+        client_config.set("isolation.level", "read_committed");
+
+        let mut tpl = TopicPartitionList::new();
+        tpl.add_partition(&topic, 0)
+            .set_offset(Offset::Beginning)
+            .unwrap();
+
+        let consumer: BaseConsumer = client_config.create().unwrap();
+        consumer.assign(&tpl).unwrap();
+
+        let mut failures = 0;
+        let mut out = Vec::new();
+        while failures < 100 {
+            match consumer.poll(Duration::from_secs(3)) {
+                Some(Ok(msg)) => {
+                    let s: &str = msg.payload_view().unwrap().unwrap();
+                    out.push(s.to_owned());
+                }
+                None if out.len() >= input.len() => break,
+                _ => {
+                    failures += 1;
+                    thread::sleep(Duration::from_millis(50));
+                }
+            }
+        }
+
+        assert_eq!(out, input);
+    }
+
     async fn kafka_happy_path(
         server: String,
         sasl: Option<KafkaSaslConfig>,
@@ -266,6 +344,7 @@ mod integration_test {
             librdkafka_options: HashMap::new(),
             headers_key: Some(headers_key.clone()),
             acknowledgements: Default::default(),
+            exactly_once: None,
         };
         let topic = format!("{}-{}", topic, chrono::Utc::now().format("%Y%m%d"));
         println!("Topic name generated in test: {:?}", topic);
@@ -0,0 +1,506 @@
+//! Exactly-once delivery: each batch of events is produced in a Kafka transaction, together
+//! with the offsets of the `kafka` source the events were read from, if any. Consumers using
+//! the `read_committed` isolation level never see the records of aborted transactions, so
+//! retrying a whole batch after a failure doesn't duplicate records.
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::future::{join_all, BoxFuture};
+use rdkafka::{
+    consumer::ConsumerGroupMetadata,
+    error::{KafkaError, KafkaResult, RDKafkaErrorCode},
+    producer::{FutureProducer, FutureRecord, Producer},
+    util::Timeout,
+    ClientConfig, Offset, TopicPartitionList,
+};
+use tokio::{sync::Mutex, time::sleep};
+use tower::Service;
+use vector_core::{
+    buffers::Ackable,
+    internal_event::{BytesSent, EventsSent},
+    stream::DriverResponse,
+};
+
+use super::{
+    config::{FencedBehavior, SourceOffsetsConfig},
+    request_builder::KafkaRequestBuilder,
+    service::KafkaRequest,
+};
+use crate::{
+    event::{Event, EventFinalizers, EventStatus, Finalizable, Value},
+    internal_events::{KafkaProducerFenced, KafkaTransactionAborted, KafkaTransactionRejected},
+    kafka::KafkaStatisticsContext,
+    sinks::util::retries::ExponentialBackoff,
+};
+
+/// The events of one transaction.
+pub struct TransactionRequest {
+    pub records: Vec<KafkaRequest>,
+    /// The next offset to consume of each source partition, by topic and partition.
+    pub offsets: BTreeMap<(String, i32), i64>,
+    pub finalizers: EventFinalizers,
+    pub batch_size: usize,
+    pub events_byte_size: usize,
+}
+
+impl TransactionRequest {
+    pub fn new(
+        events: Vec<Event>,
+        request_builder: &KafkaRequestBuilder,
+        source_offsets: Option<&SourceOffsetsConfig>,
+    ) -> Self {
+        let batch_size = events.len();
+        let mut offsets = BTreeMap::new();
+        let mut finalizers = EventFinalizers::default();
+        let mut records = Vec::with_capacity(batch_size);
+        let mut events_byte_size = 0;
+
+        for event in events {
+            if let Some((topic, partition, offset)) =
+                source_offsets.and_then(|config| source_offset(&event, config))
+            {
+                let next = offsets.entry((topic, partition)).or_insert(offset + 1);
+                *next = (*next).max(offset + 1);
+            }
+            if let Some(mut record) = request_builder.build_request(event) {
+                finalizers.merge(record.take_finalizers());
+                events_byte_size += record.event_byte_size;
+                records.push(record);
+            }
+        }
+
+        Self {
+            records,
+            offsets,
+            finalizers,
+            batch_size,
+            events_byte_size,
+        }
+    }
+
+    fn topic_partition_list(&self) -> KafkaResult<TopicPartitionList> {
+        let mut list = TopicPartitionList::with_capacity(self.offsets.len());
+        for ((topic, partition), offset) in &self.offsets {
+            list.add_partition_offset(topic, *partition, Offset::Offset(*offset))?;
+        }
+        Ok(list)
+    }
+}
+
+/// Reads the position of the event in the topic it was consumed from.
+fn source_offset(event: &Event, config: &SourceOffsetsConfig) -> Option<(String, i32, i64)> {
+    let log = event.maybe_as_log()?;
+    let topic = match log.get(config.topic_key.as_str())? {
+        Value::Bytes(topic) => String::from_utf8_lossy(topic).into_owned(),
+        _ => return None,
+    };
+    let partition = match log.get(config.partition_key.as_str())? {
+        Value::Integer(partition) => *partition as i32,
+        _ => return None,
+    };
+    let offset = match log.get(config.offset_key.as_str())? {
+        Value::Integer(offset) => *offset,
+        _ => return None,
+    };
+    Some((topic, partition, offset))
+}
+
+impl Ackable for TransactionRequest {
+    fn ack_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+impl Finalizable for TransactionRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        std::mem::take(&mut self.finalizers)
+    }
+}
+
+pub struct TransactionResponse {
+    events_count: usize,
+    events_byte_size: usize,
+}
+
+impl DriverResponse for TransactionResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> EventsSent {
+        EventsSent {
+            count: self.events_count,
+            byte_size: self.events_byte_size,
+            output: None,
+        }
+    }
+}
+
+/// Runs one transaction at a time, retrying aborted transactions until they are committed, or
+/// fail with an error that retrying can't fix.
+#[derive(Clone)]
+pub struct TransactionService {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    client_config: ClientConfig,
+    /// Created on first use, and again after a fatal error if configured to. Holding the lock
+    /// for the whole transaction keeps transactions from overlapping.
+    producer: Mutex<Option<FutureProducer<KafkaStatisticsContext>>>,
+    /// The consumer group of the `kafka` source whose offsets are committed.
+    group_id: Option<String>,
+    timeout: Duration,
+    on_fenced: FencedBehavior,
+    fenced: AtomicBool,
+}
+
+impl TransactionService {
+    pub(crate) fn new(
+        client_config: ClientConfig,
+        group_id: Option<String>,
+        timeout: Duration,
+        on_fenced: FencedBehavior,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                client_config,
+                producer: Mutex::new(None),
+                group_id,
+                timeout,
+                on_fenced,
+                fenced: AtomicBool::new(false),
+            }),
+        }
+    }
+}
+
+impl Service<TransactionRequest> for TransactionService {
+    type Response = TransactionResponse;
+    type Error = KafkaError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.inner.fenced.load(Ordering::Relaxed) {
+            Poll::Ready(Err(KafkaError::Canceled))
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn call(&mut self, request: TransactionRequest) -> Self::Future {
+        let inner = Arc::clone(&self.inner);
+
+        Box::pin(async move {
+            let mut slot = inner.producer.lock().await;
+            let mut backoff = ExponentialBackoff::from_millis(2)
+                .factor(250)
+                .max_delay(Duration::from_secs(60));
+
+            loop {
+                // Requests queued behind a fenced transaction mustn't fence the new producer.
+                if inner.fenced.load(Ordering::Relaxed) {
+                    return Err(KafkaError::Canceled);
+                }
+
+                let group_metadata = match inner.group_metadata(&request) {
+                    Ok(group_metadata) => group_metadata,
+                    Err(error) => {
+                        emit!(KafkaTransactionAborted {
+                            error: &error,
+                            count: request.records.len(),
+                        });
+                        sleep(backoff.next().unwrap()).await;
+                        continue;
+                    }
+                };
+
+                let result = match slot.as_ref() {
+                    Some(producer) => {
+                        inner
+                            .run_transaction(producer, &request, group_metadata)
+                            .await
+                    }
+                    None => match inner.init_producer().await {
+                        Ok(producer) => {
+                            let producer = slot.insert(producer);
+                            inner
+                                .run_transaction(producer, &request, group_metadata)
+                                .await
+                        }
+                        Err(error) => Err(error),
+                    },
+                };
+
+                let error = match result {
+                    Ok(()) => break,
+                    Err(error) => error,
+                };
+
+                if is_fatal(&error) {
+                    let reinitialize = inner.on_fenced == FencedBehavior::Reinitialize;
+                    emit!(KafkaProducerFenced {
+                        error: &error,
+                        reinitialize,
+                    });
+                    *slot = None;
+                    if !reinitialize {
+                        inner.fenced.store(true, Ordering::Relaxed);
+                        return Err(error);
+                    }
+                    continue;
+                }
+
+                let permanent = is_permanent(&error);
+                if permanent {
+                    emit!(KafkaTransactionRejected {
+                        error: &error,
+                        count: request.records.len(),
+                    });
+                } else {
+                    emit!(KafkaTransactionAborted {
+                        error: &error,
+                        count: request.records.len(),
+                    });
+                }
+                // A producer that can't abort its transaction is replaced, as initializing a
+                // producer aborts the transactions left open by the previous one.
+                if let Some(producer) = slot.as_ref() {
+                    if inner.abort(producer).await.is_err() {
+                        *slot = None;
+                    }
+                }
+                if permanent {
+                    // The driver rejects the events of the batch on error.
+                    return Err(error);
+                }
+                sleep(backoff.next().unwrap()).await;
+            }
+
+            emit!(BytesSent {
+                byte_size: request
+                    .records
+                    .iter()
+                    .map(|record| {
+                        record.body.len() + record.metadata.key.as_ref().map_or(0, |key| key.len())
+                    })
+                    .sum(),
+                protocol: "kafka",
+            });
+            Ok(TransactionResponse {
+                events_count: request.records.len(),
+                events_byte_size: request.events_byte_size,
+            })
+        })
+    }
+}
+
+impl Inner {
+    async fn init_producer(&self) -> KafkaResult<FutureProducer<KafkaStatisticsContext>> {
+        let producer: FutureProducer<_> = self
+            .client_config
+            .create_with_context(KafkaStatisticsContext)?;
+        let timeout = self.timeout;
+        blocking(&producer, move |producer| {
+            producer.init_transactions(timeout)
+        })
+        .await?;
+        Ok(producer)
+    }
+
+    /// The metadata of the source consumer in its group, if the offsets of the request are
+    /// committed with it. It's looked up for each transaction, as it changes every time the
+    /// group rebalances. The source must be running, with `commit_offsets` disabled so that
+    /// the offsets are only committed by the transactions.
+    fn group_metadata(
+        &self,
+        request: &TransactionRequest,
+    ) -> KafkaResult<Option<ConsumerGroupMetadata>> {
+        match &self.group_id {
+            Some(group_id) if !request.offsets.is_empty() => crate::kafka::group_metadata(group_id)
+                .map(Some)
+                .ok_or(KafkaError::MetadataFetch(RDKafkaErrorCode::UnknownGroup)),
+            _ => Ok(None),
+        }
+    }
+
+    async fn run_transaction(
+        &self,
+        producer: &FutureProducer<KafkaStatisticsContext>,
+        request: &TransactionRequest,
+        group_metadata: Option<ConsumerGroupMetadata>,
+    ) -> KafkaResult<()> {
+        blocking(producer, |producer| producer.begin_transaction()).await?;
+
+        let sends = request.records.iter().map(|record| {
+            let mut future_record = FutureRecord::to(&record.metadata.topic).payload(&record.body);
+            if let Some(key) = &record.metadata.key {
+                future_record = future_record.key(&key[..]);
+            }
+            if let Some(timestamp) = record.metadata.timestamp_millis {
+                future_record = future_record.timestamp(timestamp);
+            }
+            if let Some(headers) = &record.metadata.headers {
+                future_record = future_record.headers(headers.clone());
+            }
+            producer.send(future_record, Timeout::Never)
+        });
+        for result in join_all(sends).await {
+            result.map_err(|(error, _record)| error)?;
+        }
+
+        if let Some(group_metadata) = group_metadata {
+            let offsets = request.topic_partition_list()?;
+            let timeout = self.timeout;
+            blocking(producer, move |producer| {
+                producer.send_offsets_to_transaction(&offsets, &group_metadata, timeout)
+            })
+            .await?;
+        }
+
+        self.commit(producer).await
+    }
+
+    /// Commits the transaction, retrying while the outcome of the commit is unknown. Aborting
+    /// instead could drop a transaction that was in fact committed, and duplicate its records.
+    async fn commit(&self, producer: &FutureProducer<KafkaStatisticsContext>) -> KafkaResult<()> {
+        loop {
+            let timeout = self.timeout;
+            match blocking(producer, move |producer| {
+                producer.commit_transaction(timeout)
+            })
+            .await
+            {
+                Err(KafkaError::Transaction(error))
+                    if error.is_retriable() && !error.txn_requires_abort() =>
+                {
+                    debug!(message = "Retrying transaction commit.", %error);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn abort(&self, producer: &FutureProducer<KafkaStatisticsContext>) -> KafkaResult<()> {
+        let timeout = self.timeout;
+        blocking(producer, move |producer| {
+            producer.abort_transaction(timeout)
+        })
+        .await
+    }
+}
+
+fn is_fatal(error: &KafkaError) -> bool {
+    matches!(error, KafkaError::Transaction(error) if error.is_fatal())
+}
+
+/// Whether the error is caused by the records or the configuration, and fails the transaction
+/// again when it's retried.
+fn is_permanent(error: &KafkaError) -> bool {
+    matches!(
+        error.rdkafka_error_code(),
+        Some(
+            RDKafkaErrorCode::InvalidMessage
+                | RDKafkaErrorCode::InvalidMessageSize
+                | RDKafkaErrorCode::MessageSizeTooLarge
+                | RDKafkaErrorCode::MessageBatchTooLarge
+                | RDKafkaErrorCode::InvalidRecord
+                | RDKafkaErrorCode::InvalidTopic
+                | RDKafkaErrorCode::UnknownTopic
+                | RDKafkaErrorCode::InvalidRequiredAcks
+                | RDKafkaErrorCode::UnsupportedForMessageFormat
+                | RDKafkaErrorCode::PolicyViolation
+                | RDKafkaErrorCode::TopicAuthorizationFailed
+                | RDKafkaErrorCode::ClusterAuthorizationFailed
+                | RDKafkaErrorCode::TransactionalIdAuthorizationFailed
+        )
+    )
+}
+
+/// Runs a call of the transactional API, which blocks until the brokers respond.
+async fn blocking<F>(producer: &FutureProducer<KafkaStatisticsContext>, f: F) -> KafkaResult<()>
+where
+    F: FnOnce(&FutureProducer<KafkaStatisticsContext>) -> KafkaResult<()> + Send + 'static,
+{
+    let producer = producer.clone();
+    tokio::task::spawn_blocking(move || f(&producer))
+        .await
+        .expect("Kafka transaction task panicked")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use vector_core::config::log_schema;
+
+    use super::*;
+    use crate::{sinks::util::encoding::StandardEncodings, template::Template};
+
+    fn event(partition: i64, offset: i64) -> Event {
+        let mut event = Event::from("message");
+        let log = event.as_mut_log();
+        log.insert("topic", "billing");
+        log.insert("partition", partition);
+        log.insert("offset", offset);
+        event
+    }
+
+    #[test]
+    fn classifies_errors() {
+        assert!(is_permanent(&KafkaError::MessageProduction(
+            RDKafkaErrorCode::MessageSizeTooLarge
+        )));
+        assert!(is_permanent(&KafkaError::MessageProduction(
+            RDKafkaErrorCode::TopicAuthorizationFailed
+        )));
+        assert!(!is_permanent(&KafkaError::MessageProduction(
+            RDKafkaErrorCode::MessageTimedOut
+        )));
+        assert!(!is_permanent(&KafkaError::MessageProduction(
+            RDKafkaErrorCode::NotEnoughReplicas
+        )));
+        assert!(!is_permanent(&KafkaError::Canceled));
+    }
+
+    #[test]
+    fn commits_next_offset_of_each_partition() {
+        let request_builder = KafkaRequestBuilder {
+            key_field: None,
+            headers_key: None,
+            topic_template: Template::try_from("out").unwrap(),
+            encoder: StandardEncodings::Text.into(),
+            log_schema: log_schema(),
+        };
+        let source_offsets = SourceOffsetsConfig {
+            group_id: "billing".into(),
+            topic_key: "topic".into(),
+            partition_key: "partition".into(),
+            offset_key: "offset".into(),
+        };
+        let events = vec![
+            event(0, 10),
+            event(1, 3),
+            event(0, 12),
+            event(0, 11),
+            Event::from("not from kafka"),
+        ];
+
+        let request = TransactionRequest::new(events, &request_builder, Some(&source_offsets));
+
+        assert_eq!(request.batch_size, 5);
+        assert_eq!(request.records.len(), 5);
+        assert_eq!(
+            request.offsets.into_iter().collect::<Vec<_>>(),
+            vec![(("billing".into(), 0), 13), (("billing".into(), 1), 4)]
+        );
+    }
+}
@@ -29,12 +29,20 @@ pub struct LokiConfig {
 
     #[serde(default = "crate::serde::default_false")]
     pub remove_label_fields: bool,
+    #[serde(default)]
+    pub structured_metadata: HashMap<Template, Template>,
+    #[serde(default = "crate::serde::default_false")]
+    pub remove_structured_metadata_fields: bool,
     #[serde(default = "crate::serde::default_true")]
     pub remove_timestamp: bool,
     #[serde(default)]
     pub compression: Compression,
     #[serde(default)]
     pub out_of_order_action: OutOfOrderAction,
+    /// How long events are held per stream so that late events can be put back in order
+    /// before `out_of_order_action` applies. Disabled when zero.
+    #[serde(default)]
+    pub reorder_window_ms: u64,
 
     pub auth: Option<Auth>,
 
@@ -117,6 +125,12 @@ impl SinkConfig for LokiConfig {
             }
         }
 
+        for key in self.structured_metadata.keys() {
+            if !valid_label_name(key) {
+                return Err(format!("Invalid structured metadata key {:?}", key.get_ref()).into());
+            }
+        }
+
         let client = self.build_client(cx.clone())?;
 
         let config = LokiConfig {
@@ -1,6 +1,9 @@
 use std::{collections::HashMap, io};
 
-use serde::{ser::SerializeSeq, Serialize};
+use serde::{
+    ser::{SerializeMap, SerializeSeq},
+    Serialize,
+};
 use vector_core::{
     event::{EventFinalizers, Finalizable},
     ByteSizeOf,
@@ -53,11 +56,16 @@ impl From<Vec<LokiRecord>> for LokiBatch {
 pub struct LokiEvent {
     pub timestamp: i64,
     pub event: String,
+    pub structured_metadata: Labels,
 }
 
 impl ByteSizeOf for LokiEvent {
     fn allocated_bytes(&self) -> usize {
-        self.timestamp.allocated_bytes() + self.event.allocated_bytes()
+        self.timestamp.allocated_bytes()
+            + self.event.allocated_bytes()
+            + self.structured_metadata.iter().fold(0, |res, item| {
+                res + item.0.allocated_bytes() + item.1.allocated_bytes()
+            })
     }
 }
 
@@ -66,13 +74,37 @@ impl Serialize for LokiEvent {
     where
         S: serde::Serializer,
     {
-        let mut seq = serializer.serialize_seq(Some(2))?;
+        // Structured metadata is sent as an optional third element of the entry.
+        let len = if self.structured_metadata.is_empty() {
+            2
+        } else {
+            3
+        };
+        let mut seq = serializer.serialize_seq(Some(len))?;
         seq.serialize_element(&self.timestamp.to_string())?;
         seq.serialize_element(&self.event)?;
+        if !self.structured_metadata.is_empty() {
+            seq.serialize_element(&StructuredMetadata(&self.structured_metadata))?;
+        }
         seq.end()
     }
 }
 
+struct StructuredMetadata<'a>(&'a Labels);
+
+impl<'a> Serialize for StructuredMetadata<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in self.0 {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct LokiRecord {
     pub partition: PartitionKey,
@@ -127,3 +159,34 @@ impl PartitionKey {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_event() {
+        let event = LokiEvent {
+            timestamp: 1,
+            event: "hello".into(),
+            structured_metadata: vec![],
+        };
+        assert_eq!(serde_json::to_string(&event).unwrap(), r#"["1","hello"]"#);
+    }
+
+    #[test]
+    fn serialize_event_with_structured_metadata() {
+        let event = LokiEvent {
+            timestamp: 1,
+            event: "hello".into(),
+            structured_metadata: vec![
+                ("trace_id".into(), "abc".into()),
+                ("user".into(), "alice".into()),
+            ],
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"["1","hello",{"trace_id":"abc","user":"alice"}]"#
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! Per-stream reordering of records.
+//!
+//! Loki rejects entries older than the latest one received for their stream. Records of a
+//! stream are held for a bounded window and released sorted by timestamp, so that events
+//! arriving slightly late are inserted in order instead of being handled by the
+//! `out_of_order_action`.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{Stream, StreamExt};
+use tokio::time::{sleep_until, Instant, Sleep};
+
+use super::event::{LokiRecord, PartitionKey};
+
+/// Upper bound on the number of records held for a single stream. Once reached the stream is
+/// released early to bound memory usage.
+const MAX_PENDING_RECORDS: usize = 10_000;
+
+/// Upper bound on the number of streams held at once. Once reached the oldest stream is
+/// released early to make room for a new one.
+const MAX_PENDING_STREAMS: usize = 10_000;
+
+struct PendingStream {
+    deadline: Instant,
+    records: Vec<LokiRecord>,
+}
+
+pub(super) struct ReorderBuffer<S> {
+    input: Option<S>,
+    window: Duration,
+    streams: HashMap<PartitionKey, PendingStream>,
+    /// Deadlines of the pending streams. As every stream is held for the same window, these
+    /// are ordered by construction.
+    deadlines: VecDeque<(Instant, PartitionKey)>,
+    timer: Pin<Box<Sleep>>,
+    ready: VecDeque<LokiRecord>,
+}
+
+impl<S> ReorderBuffer<S> {
+    pub(super) fn new(input: S, window: Duration) -> Self {
+        Self {
+            input: Some(input),
+            window,
+            streams: HashMap::new(),
+            deadlines: VecDeque::new(),
+            timer: Box::pin(sleep_until(Instant::now())),
+            ready: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, record: LokiRecord) {
+        if self.streams.len() >= MAX_PENDING_STREAMS
+            && !self.streams.contains_key(&record.partition)
+        {
+            self.release_oldest();
+        }
+
+        let window = self.window;
+        let deadlines = &mut self.deadlines;
+        let stream = self
+            .streams
+            .entry(record.partition.clone())
+            .or_insert_with(|| {
+                let deadline = Instant::now() + window;
+                deadlines.push_back((deadline, record.partition.clone()));
+                PendingStream {
+                    deadline,
+                    records: Vec::new(),
+                }
+            });
+        stream.records.push(record);
+
+        if stream.records.len() >= MAX_PENDING_RECORDS {
+            let partition = stream.records[0].partition.clone();
+            self.release(&partition);
+        }
+    }
+
+    fn release(&mut self, partition: &PartitionKey) {
+        if let Some(mut stream) = self.streams.remove(partition) {
+            // The sort is stable, so records with equal timestamps keep their arrival order.
+            stream.records.sort_by_key(|record| record.event.timestamp);
+            self.ready.extend(stream.records);
+        }
+    }
+
+    fn release_oldest(&mut self) {
+        while let Some((deadline, partition)) = self.deadlines.pop_front() {
+            if self.is_current(&partition, deadline) {
+                self.release(&partition);
+                return;
+            }
+        }
+    }
+
+    /// Whether the deadline is the one of the pending stream, which may have been released
+    /// early and started over since.
+    fn is_current(&self, partition: &PartitionKey, deadline: Instant) -> bool {
+        matches!(self.streams.get(partition), Some(stream) if stream.deadline == deadline)
+    }
+
+    /// Releases the streams whose window elapsed, and registers a wakeup for the next one.
+    fn release_expired(&mut self, cx: &mut Context<'_>) {
+        while let Some((deadline, _)) = self.deadlines.front() {
+            let deadline = *deadline;
+            if deadline > Instant::now() {
+                if self.timer.deadline() != deadline {
+                    self.timer.as_mut().reset(deadline);
+                }
+                if self.timer.as_mut().poll(cx).is_pending() {
+                    return;
+                }
+            }
+
+            let (deadline, partition) = self.deadlines.pop_front().expect("front exists");
+            if self.is_current(&partition, deadline) {
+                self.release(&partition);
+            }
+        }
+    }
+
+    fn release_all(&mut self) {
+        while let Some((_, partition)) = self.deadlines.pop_front() {
+            self.release(&partition);
+        }
+    }
+}
+
+impl<S> Stream for ReorderBuffer<S>
+where
+    S: Stream<Item = LokiRecord> + Unpin,
+{
+    type Item = LokiRecord;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(record) = this.ready.pop_front() {
+                return Poll::Ready(Some(record));
+            }
+
+            // Expired streams are released even while the input keeps producing records.
+            this.release_expired(cx);
+            if !this.ready.is_empty() {
+                continue;
+            }
+
+            match this.input.as_mut() {
+                Some(input) => match input.poll_next_unpin(cx) {
+                    Poll::Ready(Some(record)) => this.push(record),
+                    Poll::Ready(None) => {
+                        this.input = None;
+                        this.release_all();
+                    }
+                    Poll::Pending => return Poll::Pending,
+                },
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{channel::mpsc, SinkExt};
+    use vector_core::event::EventFinalizers;
+
+    use super::*;
+    use crate::sinks::loki::event::LokiEvent;
+
+    fn record(stream: &str, timestamp: i64) -> LokiRecord {
+        let mut labels = vec![("stream".to_string(), stream.to_string())];
+        LokiRecord {
+            partition: PartitionKey::new(None, &mut labels),
+            labels,
+            event: LokiEvent {
+                timestamp,
+                event: format!("{}-{}", stream, timestamp),
+                structured_metadata: vec![],
+            },
+            finalizers: EventFinalizers::default(),
+        }
+    }
+
+    fn lines(records: Vec<LokiRecord>) -> Vec<String> {
+        records
+            .into_iter()
+            .map(|record| record.event.event)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn sorts_each_stream_on_input_end() {
+        let input = futures::stream::iter(vec![
+            record("a", 3),
+            record("b", 2),
+            record("a", 1),
+            record("b", 1),
+            record("a", 2),
+        ]);
+        let output = ReorderBuffer::new(input, Duration::from_secs(60))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(lines(output), vec!["a-1", "a-2", "a-3", "b-1", "b-2"]);
+    }
+
+    #[tokio::test]
+    async fn releases_streams_after_window() {
+        tokio::time::pause();
+
+        let (mut tx, rx) = mpsc::channel(10);
+        let mut buffer = ReorderBuffer::new(rx, Duration::from_secs(1));
+
+        tx.send(record("a", 2)).await.unwrap();
+        tx.send(record("a", 1)).await.unwrap();
+        assert!(futures::poll!(buffer.next()).is_pending());
+
+        tokio::time::advance(Duration::from_millis(500)).await;
+        tx.send(record("b", 1)).await.unwrap();
+        assert!(futures::poll!(buffer.next()).is_pending());
+
+        // Only the first stream is due.
+        tokio::time::advance(Duration::from_millis(600)).await;
+        assert_eq!(buffer.next().await.unwrap().event.event, "a-1");
+        assert_eq!(buffer.next().await.unwrap().event.event, "a-2");
+        assert!(futures::poll!(buffer.next()).is_pending());
+
+        tokio::time::advance(Duration::from_millis(500)).await;
+        assert_eq!(buffer.next().await.unwrap().event.event, "b-1");
+
+        drop(tx);
+        assert!(buffer.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn releases_full_streams_early() {
+        let records = (0..MAX_PENDING_RECORDS as i64)
+            .rev()
+            .map(|timestamp| record("a", timestamp))
+            .collect::<Vec<_>>();
+        let input = futures::stream::iter(records).chain(futures::stream::pending());
+        let mut buffer = ReorderBuffer::new(input, Duration::from_secs(3600));
+
+        let first = tokio::time::timeout(Duration::from_secs(1), buffer.next())
+            .await
+            .expect("stream should be released without waiting for the window")
+            .unwrap();
+        assert_eq!(first.event.timestamp, 0);
+    }
+
+    #[tokio::test]
+    async fn releases_streams_while_input_is_ready() {
+        // With an empty window each stream expires before the next record is read, so the
+        // records of the input are never held back behind it.
+        let input = futures::stream::iter(vec![record("a", 1), record("b", 1), record("a", 2)])
+            .chain(futures::stream::pending());
+        let mut buffer = ReorderBuffer::new(input, Duration::ZERO);
+
+        let mut output = Vec::new();
+        for _ in 0..3 {
+            output.push(buffer.next().await.unwrap());
+        }
+        assert_eq!(lines(output), vec!["a-1", "b-1", "a-2"]);
+    }
+
+    #[tokio::test]
+    async fn releases_oldest_stream_when_full() {
+        let records = (0..=MAX_PENDING_STREAMS)
+            .map(|stream| record(&stream.to_string(), 1))
+            .collect::<Vec<_>>();
+        let input = futures::stream::iter(records).chain(futures::stream::pending());
+        let mut buffer = ReorderBuffer::new(input, Duration::from_secs(3600));
+
+        let first = tokio::time::timeout(Duration::from_secs(1), buffer.next())
+            .await
+            .expect("oldest stream should be released without waiting for the window")
+            .unwrap();
+        assert_eq!(first.event.event, "0-1");
+        assert_eq!(buffer.streams.len(), MAX_PENDING_STREAMS);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, num::NonZeroUsize};
+use std::{collections::HashMap, num::NonZeroUsize, time::Duration};
 
 use bytes::Bytes;
 use futures::{stream::BoxStream, StreamExt};
@@ -15,7 +15,8 @@ use vector_core::{
 
 use super::{
     config::{Encoding, LokiConfig, OutOfOrderAction},
-    event::{LokiBatchEncoder, LokiEvent, LokiRecord, PartitionKey},
+    event::{Labels, LokiBatchEncoder, LokiEvent, LokiRecord, PartitionKey},
+    reorder::ReorderBuffer,
     service::{LokiRequest, LokiService},
 };
 use crate::{
@@ -150,12 +151,14 @@ pub(super) struct EventEncoder {
     encoding: EncodingConfig<Encoding>,
     labels: HashMap<Template, Template>,
     remove_label_fields: bool,
+    structured_metadata: HashMap<Template, Template>,
+    remove_structured_metadata_fields: bool,
     remove_timestamp: bool,
 }
 
 impl EventEncoder {
-    fn build_labels(&self, event: &Event) -> Vec<(String, String)> {
-        self.labels
+    fn render_pairs(templates: &HashMap<Template, Template>, event: &Event) -> Labels {
+        templates
             .iter()
             .filter_map(|(key_template, value_template)| {
                 if let (Ok(key), Ok(value)) = (
@@ -170,13 +173,11 @@ impl EventEncoder {
             .collect()
     }
 
-    fn remove_label_fields(&self, event: &mut Event) {
-        if self.remove_label_fields {
-            for template in self.labels.values() {
-                if let Some(fields) = template.get_fields() {
-                    for field in fields {
-                        event.as_mut_log().remove(field.as_str());
-                    }
+    fn remove_template_fields(templates: &HashMap<Template, Template>, event: &mut Event) {
+        for template in templates.values() {
+            if let Some(fields) = template.get_fields() {
+                for field in fields {
+                    event.as_mut_log().remove(field.as_str());
                 }
             }
         }
@@ -185,8 +186,15 @@ impl EventEncoder {
     pub(super) fn encode_event(&self, mut event: Event) -> LokiRecord {
         let tenant_id = self.key_partitioner.partition(&event);
         let finalizers = event.take_finalizers();
-        let mut labels = self.build_labels(&event);
-        self.remove_label_fields(&mut event);
+        let mut labels = Self::render_pairs(&self.labels, &event);
+        let mut structured_metadata = Self::render_pairs(&self.structured_metadata, &event);
+        structured_metadata.sort();
+        if self.remove_label_fields {
+            Self::remove_template_fields(&self.labels, &mut event);
+        }
+        if self.remove_structured_metadata_fields {
+            Self::remove_template_fields(&self.structured_metadata, &mut event);
+        }
 
         let schema = log_schema();
         let timestamp_key = schema.timestamp_key();
@@ -228,7 +236,11 @@ impl EventEncoder {
 
         LokiRecord {
             labels,
-            event: LokiEvent { timestamp, event },
+            event: LokiEvent {
+                timestamp,
+                event,
+                structured_metadata,
+            },
             partition,
             finalizers,
         }
@@ -315,6 +327,7 @@ pub struct LokiSink {
     pub(super) encoder: EventEncoder,
     batch_settings: BatcherSettings,
     out_of_order_action: OutOfOrderAction,
+    reorder_window: Option<Duration>,
     service: LokiService,
 }
 
@@ -334,10 +347,14 @@ impl LokiSink {
                 encoding: config.encoding,
                 labels: config.labels,
                 remove_label_fields: config.remove_label_fields,
+                structured_metadata: config.structured_metadata,
+                remove_structured_metadata_fields: config.remove_structured_metadata_fields,
                 remove_timestamp: config.remove_timestamp,
             },
             batch_settings: config.batch.into_batcher_settings()?,
             out_of_order_action: config.out_of_order_action,
+            reorder_window: Some(Duration::from_millis(config.reorder_window_ms))
+                .filter(|window| !window.is_zero()),
             service: LokiService::new(client, config.endpoint, config.auth)?,
         })
     }
@@ -350,8 +367,13 @@ impl LokiSink {
         let encoder = self.encoder.clone();
         let mut filter = RecordFilter::new(self.out_of_order_action);
 
-        let sink = input
-            .map(|event| encoder.encode_event(event))
+        let records = input.map(move |event| encoder.encode_event(event));
+        let records = match self.reorder_window {
+            Some(window) => ReorderBuffer::new(records, window).boxed(),
+            None => records.boxed(),
+        };
+
+        let sink = records
             .map(|record| filter.filter_record(record))
             .batched_partitioned(RecordPartitioner::default(), self.batch_settings)
             .filter_map(|(partition, batch)| async {
@@ -424,6 +446,8 @@ mod tests {
             encoding: EncodingConfig::from(Encoding::Json),
             labels: HashMap::default(),
             remove_label_fields: false,
+            structured_metadata: HashMap::default(),
+            remove_structured_metadata_fields: false,
             remove_timestamp: false,
         };
         let mut event = Event::from("hello world");
@@ -454,6 +478,8 @@ mod tests {
             encoding: EncodingConfig::from(Encoding::Json),
             labels,
             remove_label_fields: false,
+            structured_metadata: HashMap::default(),
+            remove_structured_metadata_fields: false,
             remove_timestamp: false,
         };
         let mut event = Event::from("hello world");
@@ -476,6 +502,8 @@ mod tests {
             encoding: EncodingConfig::from(Encoding::Json),
             labels: HashMap::default(),
             remove_label_fields: false,
+            structured_metadata: HashMap::default(),
+            remove_structured_metadata_fields: false,
             remove_timestamp: true,
         };
         let mut event = Event::from("hello world");
@@ -501,6 +529,8 @@ mod tests {
             encoding: EncodingConfig::from(Encoding::Json),
             labels,
             remove_label_fields: true,
+            structured_metadata: HashMap::default(),
+            remove_structured_metadata_fields: false,
             remove_timestamp: false,
         };
         let mut event = Event::from("hello world");
@@ -512,6 +542,41 @@ mod tests {
         assert!(!record.event.event.contains("value"));
     }
 
+    #[test]
+    fn encoder_with_structured_metadata() {
+        let mut structured_metadata = HashMap::default();
+        structured_metadata.insert(
+            Template::try_from("trace_id").unwrap(),
+            Template::try_from("{{ trace_id }}").unwrap(),
+        );
+        structured_metadata.insert(
+            Template::try_from("missing").unwrap(),
+            Template::try_from("{{ missing }}").unwrap(),
+        );
+        let encoder = EventEncoder {
+            key_partitioner: KeyPartitioner::new(None),
+            encoding: EncodingConfig::from(Encoding::Json),
+            labels: HashMap::default(),
+            remove_label_fields: false,
+            structured_metadata,
+            remove_structured_metadata_fields: true,
+            remove_timestamp: false,
+        };
+        let mut event = Event::from("hello world");
+        event.as_mut_log().insert("trace_id", "abc123");
+        let record = encoder.encode_event(event);
+        assert!(!record.event.event.contains("trace_id"));
+        assert_eq!(
+            record.event.structured_metadata,
+            vec![("trace_id".to_string(), "abc123".to_string())]
+        );
+        // Structured metadata doesn't create new streams.
+        assert_eq!(
+            record.labels,
+            vec![("agent".to_string(), "vector".to_string())]
+        );
+    }
+
     #[tokio::test]
     async fn filter_encoder_drop() {
         let encoder = EventEncoder {
@@ -519,6 +584,8 @@ mod tests {
             encoding: EncodingConfig::from(Encoding::Json),
             labels: HashMap::default(),
             remove_label_fields: false,
+            structured_metadata: HashMap::default(),
+            remove_structured_metadata_fields: false,
             remove_timestamp: false,
         };
         let base = chrono::Utc::now();
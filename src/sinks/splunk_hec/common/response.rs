@@ -1,7 +1,12 @@
-use vector_core::{event::EventStatus, internal_event::EventsSent, stream::DriverResponse};
+use vector_core::{
+    event::{EventStatus, Rejection},
+    internal_event::EventsSent,
+    stream::DriverResponse,
+};
 
 pub struct HecResponse {
     pub event_status: EventStatus,
+    pub rejection: Option<Rejection>,
     pub events_count: usize,
     pub events_byte_size: usize,
 }
@@ -24,4 +29,8 @@ impl DriverResponse for HecResponse {
             output: None,
         }
     }
+
+    fn rejection(&self) -> Option<Rejection> {
+        self.rejection.clone()
+    }
 }
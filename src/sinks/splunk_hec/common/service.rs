@@ -118,6 +118,8 @@ where
 
         Box::pin(async move {
             let response = response.await.map_err(Into::into)?;
+            let rejection = (!response.is_successful() && !response.is_transient())
+                .then(|| response.rejection());
             let event_status = if response.is_successful() {
                 if let Some(ack_finalizer_tx) = ack_finalizer_tx {
                     let _ack_slot = ack_slot.expect("poll_ready not called before invoking call");
@@ -159,6 +161,7 @@ where
 
             Ok(HecResponse {
                 event_status,
+                rejection,
                 events_count,
                 events_byte_size,
             })
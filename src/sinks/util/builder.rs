@@ -199,7 +199,7 @@ pub trait SinkBuilderExt: Stream {
         Self: Sized,
         Self::Item: Ackable + Finalizable,
         Svc: Service<Self::Item>,
-        Svc::Error: fmt::Debug + 'static,
+        Svc::Error: fmt::Debug + Into<crate::Error> + 'static,
         Svc::Future: Send + 'static,
         Svc::Response: DriverResponse,
     {
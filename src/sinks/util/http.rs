@@ -25,7 +25,7 @@ use super::{
     TowerRequestConfig, TowerRequestSettings,
 };
 use crate::{
-    event::Event,
+    event::{Event, Rejection},
     http::{HttpClient, HttpError},
     internal_events::EndpointBytesSent,
 };
@@ -426,6 +426,13 @@ impl<T: fmt::Debug> sink::Response for http::Response<T> {
     fn is_transient(&self) -> bool {
         self.status().is_server_error()
     }
+
+    fn rejection(&self) -> Rejection {
+        Rejection {
+            reason: self.status().to_string(),
+            status: Some(self.status().as_u16()),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone)]
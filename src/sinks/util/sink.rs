@@ -60,7 +60,7 @@ use super::{
     service::{Map, ServiceBuilderExt},
     EncodedEvent,
 };
-use crate::event::EventStatus;
+use crate::event::{EventStatus, Rejection};
 
 // === BatchSink ===
 
@@ -451,8 +451,17 @@ where
             .call(items)
             .err_into()
             .map(move |result| {
+                let rejection = match &result {
+                    Ok(response) if !response.is_successful() && !response.is_transient() => {
+                        Some(response.rejection())
+                    }
+                    _ => None,
+                };
                 let status = result_status(result);
-                finalizers.update_status(status);
+                match rejection {
+                    Some(rejection) => finalizers.reject(&rejection),
+                    None => finalizers.update_status(status),
+                }
                 if status == EventStatus::Delivered {
                     emit!(EventsSent {
                         count,
@@ -570,6 +579,14 @@ pub trait Response: fmt::Debug {
     fn is_transient(&self) -> bool {
         true
     }
+
+    /// The details recorded on the events permanently rejected by this response.
+    fn rejection(&self) -> Rejection {
+        Rejection {
+            reason: format!("{:?}", self),
+            status: None,
+        }
+    }
 }
 
 impl Response for () {}
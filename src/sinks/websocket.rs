@@ -0,0 +1,484 @@
+use std::{
+    net::SocketAddr,
+    num::NonZeroU64,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use bytes::BytesMut;
+use futures::{future, stream::BoxStream, FutureExt, SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    time::{interval, Interval},
+};
+use tokio_tungstenite::{tungstenite::Message, WebSocketStream};
+use tokio_util::codec::Encoder as _;
+use vector_core::{
+    buffers::Acker,
+    internal_event::{BytesSent, EventsSent},
+    ByteSizeOf,
+};
+
+use crate::{
+    codecs::{
+        encoding::{FramingConfig, Serializer, SerializerConfig},
+        JsonSerializerConfig, RawMessageSerializerConfig,
+    },
+    config::{
+        AcknowledgementsConfig, GenerateConfig, Input, SinkConfig, SinkContext, SinkDescription,
+    },
+    event::{Event, EventFinalizers, EventStatus, Finalizable},
+    internal_events::{
+        WebSocketConnectionShutdown, WebSocketEventEncodingError, WebSocketPongTimeout,
+        WebSocketReceiveError, WebSocketSendAttemptsExhausted, WebSocketSendError,
+    },
+    sinks::util::{
+        encoding::{EncodingConfig, EncodingConfigAdapter, EncodingConfigMigrator, Transformer},
+        StreamSink,
+    },
+    tls::{MaybeTlsSettings, TlsConfig},
+    websocket::WebSocketConnector,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WebSocketSinkConfig {
+    uri: String,
+    tls: Option<TlsConfig>,
+    encoding: EncodingConfigAdapter<EncodingConfig<Encoding>, Migrator>,
+    /// The interval between the pings keeping the connection alive, disabled
+    /// if unset.
+    ping_interval_secs: Option<NonZeroU64>,
+    /// The time to wait for a pong before reconnecting, the ping interval if
+    /// unset.
+    ping_timeout_secs: Option<NonZeroU64>,
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::skip_serializing_if_default"
+    )]
+    acknowledgements: AcknowledgementsConfig,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Migrator;
+
+impl EncodingConfigMigrator for Migrator {
+    type Codec = Encoding;
+
+    fn migrate(codec: &Self::Codec) -> (Option<FramingConfig>, SerializerConfig) {
+        match codec {
+            Encoding::Text => (None, RawMessageSerializerConfig::new().into()),
+            Encoding::Json => (None, JsonSerializerConfig::new().into()),
+        }
+    }
+}
+
+inventory::submit! {
+    SinkDescription::new::<WebSocketSinkConfig>("websocket")
+}
+
+impl GenerateConfig for WebSocketSinkConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            uri = "ws://127.0.0.1:8080/events"
+            encoding.codec = "json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "websocket")]
+impl SinkConfig for WebSocketSinkConfig {
+    async fn build(
+        &self,
+        cx: SinkContext,
+    ) -> crate::Result<(super::VectorSink, super::Healthcheck)> {
+        let tls = MaybeTlsSettings::from_config(&self.tls, false)?;
+        let connector = WebSocketConnector::new(&self.uri, tls)?;
+        let (_framer, serializer) = self.encoding.clone().encoding();
+        let sink = WebSocketSink {
+            connector: connector.clone(),
+            transformer: self.encoding.transformer(),
+            serializer,
+            ping_interval: self
+                .ping_interval_secs
+                .map(|secs| Duration::from_secs(secs.get())),
+            ping_timeout: self
+                .ping_timeout_secs
+                .or(self.ping_interval_secs)
+                .map(|secs| Duration::from_secs(secs.get())),
+            acker: cx.acker(),
+            pending: None,
+        };
+        let healthcheck = async move { connector.healthcheck().await }.boxed();
+
+        Ok((super::VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn sink_type(&self) -> &'static str {
+        "websocket"
+    }
+
+    fn acknowledgements(&self) -> Option<&AcknowledgementsConfig> {
+        Some(&self.acknowledgements)
+    }
+}
+
+/// How many times an event is sent, reconnecting in between, before it's
+/// dropped.
+const MAX_SEND_ATTEMPTS: usize = 3;
+
+struct WebSocketSink {
+    connector: WebSocketConnector,
+    transformer: Transformer,
+    serializer: Serializer,
+    ping_interval: Option<Duration>,
+    ping_timeout: Option<Duration>,
+    acker: Acker,
+    /// The event which failed to be sent, sent again once reconnected.
+    pending: Option<PendingEvent>,
+}
+
+struct PendingEvent {
+    message: Message,
+    finalizers: EventFinalizers,
+    byte_size: usize,
+    attempts: usize,
+}
+
+impl WebSocketSink {
+    /// Encodes an event as a message, binary for the native codec and text
+    /// otherwise.
+    fn encode_event(&mut self, mut event: Event) -> Option<Message> {
+        self.transformer.transform(&mut event);
+
+        let mut bytes = BytesMut::new();
+        if let Err(error) = self.serializer.encode(event, &mut bytes) {
+            emit!(WebSocketEventEncodingError { error });
+            return None;
+        }
+
+        Some(match self.serializer {
+            Serializer::Native(_) => Message::Binary(bytes.to_vec()),
+            _ => match String::from_utf8(bytes.to_vec()) {
+                Ok(text) => Message::Text(text),
+                Err(error) => Message::Binary(error.into_bytes()),
+            },
+        })
+    }
+
+    /// Whether the server didn't answer the last ping in time.
+    fn pong_timed_out(&self, last_pong: Instant) -> bool {
+        match (self.ping_interval, self.ping_timeout) {
+            (Some(ping_interval), Some(ping_timeout)) => {
+                last_pong.elapsed() > ping_interval + ping_timeout
+            }
+            _ => false,
+        }
+    }
+
+    /// Sends an event, keeping it to be sent again after reconnecting if that
+    /// fails, until it's dropped after `MAX_SEND_ATTEMPTS`.
+    async fn send_event<S>(
+        &mut self,
+        ws_stream: &mut WebSocketStream<S>,
+        mut event: PendingEvent,
+    ) -> Result<(), ()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let message_len = event.message.len();
+        match ws_stream.send(event.message.clone()).await {
+            Ok(()) => {
+                event.finalizers.update_status(EventStatus::Delivered);
+                self.acker.ack(1);
+                emit!(EventsSent {
+                    count: 1,
+                    byte_size: event.byte_size,
+                    output: None,
+                });
+                emit!(BytesSent {
+                    byte_size: message_len,
+                    protocol: "websocket",
+                });
+                Ok(())
+            }
+            Err(error) => {
+                emit!(WebSocketSendError { error });
+                event.attempts += 1;
+                if event.attempts < MAX_SEND_ATTEMPTS {
+                    self.pending = Some(event);
+                } else {
+                    emit!(WebSocketSendAttemptsExhausted {
+                        attempts: event.attempts
+                    });
+                    event.finalizers.update_status(EventStatus::Errored);
+                    self.acker.ack(1);
+                }
+                Err(())
+            }
+        }
+    }
+
+    /// Sends the events until the input is exhausted, returning an error if
+    /// the connection is lost.
+    async fn handle_events<S>(
+        &mut self,
+        ws_stream: &mut WebSocketStream<S>,
+        peer_addr: Option<SocketAddr>,
+        input: &mut BoxStream<'_, Event>,
+    ) -> Result<(), ()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        if let Some(event) = self.pending.take() {
+            self.send_event(ws_stream, event).await?;
+        }
+
+        let mut ping_interval = self.ping_interval.map(interval);
+        let mut last_pong = Instant::now();
+
+        loop {
+            tokio::select! {
+                _ = tick(&mut ping_interval) => {
+                    if self.pong_timed_out(last_pong) {
+                        emit!(WebSocketPongTimeout);
+                        return Err(());
+                    }
+                    if let Err(error) = ws_stream.send(Message::Ping(Vec::new())).await {
+                        emit!(WebSocketSendError { error });
+                        return Err(());
+                    }
+                },
+
+                message = ws_stream.next() => match message {
+                    Some(Ok(Message::Pong(_))) => last_pong = Instant::now(),
+                    Some(Ok(Message::Close(_))) | None => return Err(()),
+                    Some(Ok(_)) => {},
+                    Some(Err(error)) => {
+                        emit!(WebSocketReceiveError { error, peer_addr });
+                        return Err(());
+                    }
+                },
+
+                event = input.next() => {
+                    let mut event = match event {
+                        Some(event) => event,
+                        None => {
+                            let _ = ws_stream.close(None).await;
+                            return Ok(());
+                        }
+                    };
+
+                    let byte_size = event.size_of();
+                    let finalizers = event.take_finalizers();
+                    let message = match self.encode_event(event) {
+                        Some(message) => message,
+                        None => {
+                            finalizers.update_status(EventStatus::Errored);
+                            self.acker.ack(1);
+                            continue;
+                        }
+                    };
+
+                    let event = PendingEvent {
+                        message,
+                        finalizers,
+                        byte_size,
+                        attempts: 0,
+                    };
+                    self.send_event(ws_stream, event).await?;
+                },
+            }
+        }
+    }
+}
+
+/// Waits for the next tick of the interval, forever if there is none.
+async fn tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => future::pending().await,
+    }
+}
+
+#[async_trait]
+impl StreamSink<Event> for WebSocketSink {
+    async fn run(mut self: Box<Self>, mut input: BoxStream<'_, Event>) -> Result<(), ()> {
+        loop {
+            let mut ws_stream = self.connector.connect_backoff().await;
+            let peer_addr = ws_stream.get_ref().peer_addr().ok();
+            match self
+                .handle_events(&mut ws_stream, peer_addr, &mut input)
+                .await
+            {
+                Ok(()) => return Ok(()),
+                Err(()) => emit!(WebSocketConnectionShutdown { peer_addr }),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{net::TcpListener, time::timeout};
+    use tokio_tungstenite::{accept_async, tungstenite::protocol::Role};
+
+    use super::*;
+    use crate::{
+        config::SinkConfig,
+        event::{BatchNotifier, BatchStatus, BatchStatusReceiver},
+        test_util::{next_addr, trace_init},
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WebSocketSinkConfig>();
+    }
+
+    async fn receive_texts(listener: TcpListener) -> Vec<String> {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws_stream = accept_async(stream).await.unwrap();
+        let mut texts = Vec::new();
+        while let Some(Ok(message)) = ws_stream.next().await {
+            if let Message::Text(text) = message {
+                texts.push(text);
+            }
+        }
+        texts
+    }
+
+    #[tokio::test]
+    async fn sends_json_messages() {
+        trace_init();
+
+        let address = next_addr();
+        let listener = TcpListener::bind(address).await.unwrap();
+        let config: WebSocketSinkConfig = toml::from_str(&format!(
+            r#"
+            uri = "ws://{}/"
+            encoding.codec = "json"
+            "#,
+            address
+        ))
+        .unwrap();
+        let (sink, _healthcheck) = config.build(SinkContext::new_test()).await.unwrap();
+
+        let server = tokio::spawn(receive_texts(listener));
+        let events = vec![Event::from("foo"), Event::from("bar")];
+        sink.run(futures::stream::iter(events)).await.unwrap();
+
+        let texts = timeout(Duration::from_secs(5), server)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(texts.len(), 2);
+        let json: serde_json::Value = serde_json::from_str(&texts[0]).unwrap();
+        assert_eq!(json["message"], "foo");
+    }
+
+    fn test_sink() -> WebSocketSink {
+        let config: WebSocketSinkConfig = toml::from_str(
+            r#"
+            uri = "ws://127.0.0.1:9/"
+            encoding.codec = "text"
+            "#,
+        )
+        .unwrap();
+        let tls = MaybeTlsSettings::from_config(&None, false).unwrap();
+        let (_framer, serializer) = config.encoding.clone().encoding();
+        WebSocketSink {
+            connector: WebSocketConnector::new(&config.uri, tls).unwrap(),
+            transformer: config.encoding.transformer(),
+            serializer,
+            ping_interval: None,
+            ping_timeout: None,
+            acker: Acker::passthrough(),
+            pending: None,
+        }
+    }
+
+    fn pending_event() -> (PendingEvent, BatchStatusReceiver) {
+        let (batch, receiver) = BatchNotifier::new_with_receiver();
+        let mut event = Event::from("foo").with_batch_notifier(&batch);
+        let event = PendingEvent {
+            message: Message::Text("foo".into()),
+            finalizers: event.take_finalizers(),
+            byte_size: 3,
+            attempts: 0,
+        };
+        (event, receiver)
+    }
+
+    /// A connection to a server which is already gone, so sending fails.
+    async fn lost_connection() -> WebSocketStream<tokio::io::DuplexStream> {
+        let (client, server) = tokio::io::duplex(1024);
+        drop(server);
+        WebSocketStream::from_raw_socket(client, Role::Client, None).await
+    }
+
+    #[tokio::test]
+    async fn resends_events_after_reconnecting() {
+        let mut sink = test_sink();
+        let (event, mut receiver) = pending_event();
+        let mut ws_stream = lost_connection().await;
+        assert!(sink.send_event(&mut ws_stream, event).await.is_err());
+        assert!(sink.pending.is_some());
+
+        let (client, server) = tokio::io::duplex(1024);
+        let mut ws_stream = WebSocketStream::from_raw_socket(client, Role::Client, None).await;
+        let server = tokio::spawn(async move {
+            let mut ws_stream = WebSocketStream::from_raw_socket(server, Role::Server, None).await;
+            let message = ws_stream.next().await;
+            // Keep the connection open until the sink closes it.
+            (ws_stream, message)
+        });
+        let mut input = futures::stream::empty().boxed();
+        sink.handle_events(&mut ws_stream, None, &mut input)
+            .await
+            .unwrap();
+
+        let (_ws_stream, message) = server.await.unwrap();
+        let message = message.unwrap().unwrap();
+        assert_eq!(message, Message::Text("foo".into()));
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+    }
+
+    #[tokio::test]
+    async fn drops_events_after_failed_attempts() {
+        let mut sink = test_sink();
+        let (mut event, mut receiver) = pending_event();
+        for attempt in 1..=MAX_SEND_ATTEMPTS {
+            let mut ws_stream = lost_connection().await;
+            assert!(sink.send_event(&mut ws_stream, event).await.is_err());
+            match sink.pending.take() {
+                Some(pending) => {
+                    assert_eq!(pending.attempts, attempt);
+                    event = pending;
+                }
+                None => {
+                    assert_eq!(attempt, MAX_SEND_ATTEMPTS);
+                    break;
+                }
+            }
+        }
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Errored));
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 
 use bytes::Bytes;
 use chrono::Utc;
-use futures::{FutureExt, StreamExt};
+use futures::{future, FutureExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use snafu::Snafu;
@@ -24,10 +24,13 @@ use crate::{
     async_read::VecAsyncReadExt,
     codecs::{
         self,
-        decoding::{DecodingConfig, DeserializerConfig, FramingConfig},
+        decoding::{
+            DecodingConfig, Deserializer, DeserializerConfig, Framer, FramingConfig,
+            NewlineDelimitedDecoder,
+        },
     },
     config::{log_schema, DataType, Output, SourceConfig, SourceContext, SourceDescription},
-    event::Event,
+    event::{Event, LogEvent},
     internal_events::{
         ExecCommandExecuted, ExecEventsReceived, ExecFailedError, ExecTimeoutError,
         StreamClosedError,
@@ -38,8 +41,13 @@ use crate::{
     SourceSender,
 };
 
+mod protocols;
 pub mod sized_bytes_codec;
 
+use self::protocols::InfluxDeserializer;
+#[cfg(feature = "sources-prometheus")]
+use self::protocols::{BoundedBytesDecoder, PrometheusDeserializer};
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(default, deny_unknown_fields)]
 pub struct ExecConfig {
@@ -56,6 +64,10 @@ pub struct ExecConfig {
     framing: FramingConfig,
     #[serde(default = "default_decoding")]
     decoding: DeserializerConfig,
+    #[serde(default)]
+    stdout_protocol: StdoutProtocol,
+    #[serde(default)]
+    include_run_events: bool,
 }
 
 // TODO: Would be nice to combine the scheduled and streaming config with the mode enum once
@@ -72,6 +84,9 @@ pub enum Mode {
 pub struct ScheduledConfig {
     #[serde(default = "default_exec_interval_secs")]
     exec_interval_secs: u64,
+    /// The time after which a run is killed, defaulting to the interval.
+    #[serde(default)]
+    timeout_secs: Option<u64>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -83,12 +98,39 @@ pub struct StreamingConfig {
     respawn_interval_secs: u64,
 }
 
+/// How the standard output of the command is decoded.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StdoutProtocol {
+    /// Logs decoded with the configured `framing` and `decoding`.
+    Logs,
+    /// Metrics in the Influx line protocol, one per line.
+    Influx,
+    /// Metrics in the Prometheus text exposition format, read from the whole
+    /// output of each run. Requires the `sources-prometheus` feature.
+    Prometheus,
+}
+
+impl Default for StdoutProtocol {
+    fn default() -> Self {
+        Self::Logs
+    }
+}
+
 #[derive(Debug, PartialEq, Snafu)]
 pub enum ExecConfigError {
     #[snafu(display("A non-empty list for command must be provided"))]
     CommandEmpty,
     #[snafu(display("The maximum buffer size must be greater than zero"))]
     ZeroBuffer,
+    #[snafu(display("The timeout must be greater than zero"))]
+    ZeroTimeout,
+    #[snafu(display("The prometheus stdout protocol requires the scheduled mode"))]
+    PrometheusStreaming,
+    #[snafu(display(
+        "The prometheus stdout protocol requires Vector to be built with the `sources-prometheus` feature"
+    ))]
+    PrometheusUnavailable,
 }
 
 impl Default for ExecConfig {
@@ -97,6 +139,7 @@ impl Default for ExecConfig {
             mode: Mode::Scheduled,
             scheduled: Some(ScheduledConfig {
                 exec_interval_secs: default_exec_interval_secs(),
+                timeout_secs: None,
             }),
             streaming: None,
             command: vec!["echo".to_owned(), "Hello World!".to_owned()],
@@ -105,6 +148,8 @@ impl Default for ExecConfig {
             maximum_buffer_size_bytes: default_maximum_buffer_size(),
             framing: default_framing_stream_based(),
             decoding: default_decoding(),
+            stdout_protocol: StdoutProtocol::default(),
+            include_run_events: false,
         }
     }
 }
@@ -140,6 +185,13 @@ const STDERR: &str = "stderr";
 const STREAM_KEY: &str = "stream";
 const PID_KEY: &str = "pid";
 const COMMAND_KEY: &str = "command";
+const EXIT_CODE_KEY: &str = "exit_code";
+const SIGNAL_KEY: &str = "signal";
+const DURATION_KEY: &str = "duration_seconds";
+const TIMED_OUT_KEY: &str = "timed_out";
+
+/// How long the output of a timed out run is still read after killing it.
+const KILL_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
 
 inventory::submit! {
     SourceDescription::new::<ExecConfig>("exec")
@@ -153,6 +205,24 @@ impl ExecConfig {
             Err(ExecConfigError::CommandEmpty)
         } else if self.maximum_buffer_size_bytes == 0 {
             Err(ExecConfigError::ZeroBuffer)
+        } else if matches!(
+            &self.scheduled,
+            Some(ScheduledConfig {
+                timeout_secs: Some(0),
+                ..
+            })
+        ) {
+            Err(ExecConfigError::ZeroTimeout)
+        } else if self.stdout_protocol == StdoutProtocol::Prometheus
+            && !cfg!(feature = "sources-prometheus")
+        {
+            Err(ExecConfigError::PrometheusUnavailable)
+        } else if self.stdout_protocol == StdoutProtocol::Prometheus
+            && matches!(self.mode, Mode::Streaming)
+        {
+            // The output is only decoded once the command exits, which a
+            // streaming command may never do.
+            Err(ExecConfigError::PrometheusStreaming)
         } else {
             Ok(())
         }
@@ -169,6 +239,13 @@ impl ExecConfig {
         }
     }
 
+    fn timeout_secs_or_default(&self) -> u64 {
+        match &self.scheduled {
+            None => default_exec_interval_secs(),
+            Some(config) => config.timeout_secs.unwrap_or(config.exec_interval_secs),
+        }
+    }
+
     const fn respawn_on_exit_or_default(&self) -> bool {
         match &self.streaming {
             None => default_respawn_on_exit(),
@@ -182,6 +259,29 @@ impl ExecConfig {
             Some(config) => config.respawn_interval_secs,
         }
     }
+
+    /// The decoder of the standard output, the configured one unless a metric
+    /// protocol is used.
+    fn stdout_decoder(&self, decoder: &codecs::Decoder) -> codecs::Decoder {
+        match self.stdout_protocol {
+            StdoutProtocol::Logs => decoder.clone(),
+            StdoutProtocol::Influx => codecs::Decoder::new(
+                Framer::NewlineDelimited(NewlineDelimitedDecoder::new_with_max_length(
+                    self.maximum_buffer_size_bytes,
+                )),
+                Deserializer::Boxed(Box::new(InfluxDeserializer)),
+            ),
+            #[cfg(feature = "sources-prometheus")]
+            StdoutProtocol::Prometheus => codecs::Decoder::new(
+                Framer::Boxed(Box::new(BoundedBytesDecoder::new(
+                    self.maximum_buffer_size_bytes,
+                ))),
+                Deserializer::Boxed(Box::new(PrometheusDeserializer)),
+            ),
+            #[cfg(not(feature = "sources-prometheus"))]
+            StdoutProtocol::Prometheus => unreachable!("rejected by `validate`"),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -194,11 +294,13 @@ impl SourceConfig for ExecConfig {
         match &self.mode {
             Mode::Scheduled => {
                 let exec_interval_secs = self.exec_interval_secs_or_default();
+                let timeout_secs = self.timeout_secs_or_default();
 
                 Ok(Box::pin(run_scheduled(
                     self.clone(),
                     hostname,
                     exec_interval_secs,
+                    timeout_secs,
                     decoder,
                     cx.shutdown,
                     cx.out,
@@ -222,7 +324,11 @@ impl SourceConfig for ExecConfig {
     }
 
     fn outputs(&self) -> Vec<Output> {
-        vec![Output::default(DataType::Log)]
+        // Stderr and run events are always logs.
+        match self.stdout_protocol {
+            StdoutProtocol::Logs => vec![Output::default(DataType::Log)],
+            _ => vec![Output::default(DataType::Log | DataType::Metric)],
+        }
     }
 
     fn source_type(&self) -> &'static str {
@@ -238,45 +344,34 @@ async fn run_scheduled(
     config: ExecConfig,
     hostname: Option<String>,
     exec_interval_secs: u64,
+    timeout_secs: u64,
     decoder: codecs::Decoder,
     shutdown: ShutdownSignal,
     out: SourceSender,
 ) -> Result<(), ()> {
     debug!("Starting scheduled exec runs.");
     let schedule = Duration::from_secs(exec_interval_secs);
+    let timeout = Duration::from_secs(timeout_secs);
 
     let mut interval = IntervalStream::new(time::interval(schedule)).take_until(shutdown.clone());
 
     while interval.next().await.is_some() {
-        // Wait for our task to finish, wrapping it in a timeout
-        let timeout = tokio::time::timeout(
-            schedule,
-            run_command(
-                config.clone(),
-                hostname.clone(),
-                decoder.clone(),
-                shutdown.clone(),
-                out.clone(),
-            ),
+        // Wait for our task to finish, the command is killed on timeout
+        let output = run_command(
+            config.clone(),
+            hostname.clone(),
+            decoder.clone(),
+            Some(timeout),
+            shutdown.clone(),
+            out.clone(),
         )
         .await;
 
-        match timeout {
-            Ok(output) => {
-                if let Err(command_error) = output {
-                    emit!(ExecFailedError {
-                        command: config.command_line().as_str(),
-                        error: command_error,
-                    });
-                }
-            }
-            Err(error) => {
-                emit!(ExecTimeoutError {
-                    command: config.command_line().as_str(),
-                    elapsed_seconds: schedule.as_secs(),
-                    error,
-                });
-            }
+        if let Err(command_error) = output {
+            emit!(ExecFailedError {
+                command: config.command_line().as_str(),
+                error: command_error,
+            });
         }
     }
 
@@ -304,6 +399,7 @@ async fn run_streaming(
                     config.clone(),
                     hostname.clone(),
                     decoder.clone(),
+                    None,
                     shutdown.clone(),
                     out.clone()
                 ) => {
@@ -328,7 +424,7 @@ async fn run_streaming(
             }
         }
     } else {
-        let output = run_command(config.clone(), hostname, decoder, shutdown, out).await;
+        let output = run_command(config.clone(), hostname, decoder, None, shutdown, out).await;
 
         if let Err(command_error) = output {
             emit!(ExecFailedError {
@@ -345,6 +441,7 @@ async fn run_command(
     config: ExecConfig,
     hostname: Option<String>,
     decoder: codecs::Decoder,
+    timeout: Option<Duration>,
     shutdown: ShutdownSignal,
     mut out: SourceSender,
 ) -> Result<Option<ExitStatus>, Error> {
@@ -384,9 +481,43 @@ async fn run_command(
 
     let pid = child.id();
 
-    spawn_reader_thread(stdout_reader, decoder.clone(), STDOUT, sender);
+    spawn_reader_thread(
+        stdout_reader,
+        config.stdout_decoder(&decoder),
+        STDOUT,
+        sender,
+    );
+
+    let mut deadline = timeout.map(|timeout| start + timeout);
+    let mut timed_out = false;
+
+    loop {
+        let received = tokio::select! {
+            received = receiver.recv() => received,
+            _ = sleep_until(deadline) => {
+                if timed_out {
+                    // The output is still held open, likely by a child of the
+                    // killed process.
+                    break;
+                }
+                emit!(ExecTimeoutError {
+                    command: config.command_line().as_str(),
+                    elapsed_seconds: start.elapsed().as_secs(),
+                });
+                timed_out = true;
+                if let Err(error) = child.kill().await {
+                    error!(message = "Unable to kill timed out command.", %error);
+                }
+                // Keep forwarding what the command wrote before being killed.
+                deadline = Some(Instant::now() + KILL_DRAIN_TIMEOUT);
+                continue;
+            }
+        };
+        let ((mut events, _byte_size), stream) = match received {
+            Some(received) => received,
+            None => break,
+        };
 
-    while let Some(((mut events, _byte_size), stream)) = receiver.recv().await {
         let count = events.len();
         emit!(ExecEventsReceived {
             count,
@@ -405,26 +536,68 @@ async fn run_command(
 
     let elapsed = start.elapsed();
 
-    let result = match child.try_wait() {
-        Ok(Some(exit_status)) => {
-            handle_exit_status(&config, exit_status.code(), elapsed);
-            Ok(Some(exit_status))
-        }
-        Ok(None) => {
-            handle_exit_status(&config, None, elapsed);
-            Ok(None)
-        }
+    let exit_status = match child.try_wait() {
+        Ok(exit_status) => exit_status,
         Err(error) => {
             error!(message = "Unable to obtain exit status.", %error);
-
-            handle_exit_status(&config, None, elapsed);
-            Ok(None)
+            None
         }
     };
+    handle_exit_status(
+        &config,
+        exit_status.and_then(|status| status.code()),
+        elapsed,
+    );
+
+    if config.include_run_events {
+        let mut event = run_event(exit_status, elapsed, timed_out);
+        handle_event(&config, &hostname, &None, pid, &mut event);
+        emit!(ExecEventsReceived {
+            count: 1,
+            command: config.command_line().as_str(),
+            byte_size: event.size_of(),
+        });
+        if let Err(error) = out.send_event(event).await {
+            emit!(StreamClosedError { count: 1, error });
+        }
+    }
 
     debug!("Finished command run.");
 
-    result
+    Ok(exit_status)
+}
+
+/// Waits until the deadline, forever if there is none.
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => time::sleep_until(deadline).await,
+        None => future::pending().await,
+    }
+}
+
+/// Builds the event reporting how a run ended.
+fn run_event(exit_status: Option<ExitStatus>, elapsed: Duration, timed_out: bool) -> Event {
+    let mut log = LogEvent::from("Command executed.");
+    if let Some(code) = exit_status.and_then(|status| status.code()) {
+        log.insert_flat(EXIT_CODE_KEY, code as i64);
+    }
+    if let Some(signal) = exit_status.and_then(|status| exit_signal(&status)) {
+        log.insert_flat(SIGNAL_KEY, signal as i64);
+    }
+    log.insert_flat(DURATION_KEY, elapsed.as_secs_f64());
+    log.insert_flat(TIMED_OUT_KEY, timed_out);
+    log.into()
+}
+
+#[cfg(unix)]
+fn exit_signal(exit_status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    exit_status.signal()
+}
+
+#[cfg(not(unix))]
+const fn exit_signal(_exit_status: &ExitStatus) -> Option<i32> {
+    None
 }
 
 fn handle_exit_status(config: &ExecConfig, exit_status: Option<i32>, exec_duration: Duration) {
@@ -606,6 +779,8 @@ mod tests {
             maximum_buffer_size_bytes: default_maximum_buffer_size(),
             framing: default_framing_stream_based(),
             decoding: default_decoding(),
+            stdout_protocol: StdoutProtocol::default(),
+            include_run_events: false,
         };
 
         let command = build_command(&config);
@@ -674,7 +849,7 @@ mod tests {
         // Wait for our task to finish, wrapping it in a timeout
         let timeout = tokio::time::timeout(
             time::Duration::from_secs(5),
-            run_command(config.clone(), hostname, decoder, shutdown, tx),
+            run_command(config.clone(), hostname, decoder, None, shutdown, tx),
         );
 
         let timeout_result = timeout.await;
@@ -700,6 +875,129 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn test_run_command_timeout() {
+        trace_init();
+        let config = ExecConfig {
+            command: vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "echo before; exec sleep 10".to_owned(),
+            ],
+            include_run_events: true,
+            ..Default::default()
+        };
+        let (tx, mut rx) = SourceSender::new_test();
+
+        let exit_status = tokio::time::timeout(
+            time::Duration::from_secs(5),
+            run_command(
+                config,
+                None,
+                Default::default(),
+                Some(Duration::from_millis(100)),
+                ShutdownSignal::noop(),
+                tx,
+            ),
+        )
+        .await
+        .expect("command was not killed")
+        .expect("command error");
+        assert!(exit_status.unwrap().code().is_none());
+
+        let event = rx.next().await.unwrap();
+        assert_eq!(event.as_log()[log_schema().message_key()], "before".into());
+
+        let event = rx.next().await.unwrap();
+        let log = event.as_log();
+        assert_eq!(log[TIMED_OUT_KEY], true.into());
+        assert_eq!(log[SIGNAL_KEY], 9.into());
+        assert!(log.get(EXIT_CODE_KEY).is_none());
+        assert!(log.get(DURATION_KEY).is_some());
+        assert!(log.get(PID_KEY).is_some());
+    }
+
+    #[tokio::test]
+    #[cfg(not(target_os = "windows"))]
+    async fn test_run_command_influx() {
+        trace_init();
+        let config: ExecConfig = toml::from_str(
+            r#"
+            mode = "scheduled"
+            command = ["echo", "cpu,core=0 usage=0.5,idle=3i"]
+            stdout_protocol = "influx"
+            include_run_events = true
+            "#,
+        )
+        .unwrap();
+        let (tx, rx) = SourceSender::new_test();
+
+        run_command(
+            config,
+            None,
+            Default::default(),
+            None,
+            ShutdownSignal::noop(),
+            tx,
+        )
+        .await
+        .unwrap();
+
+        let events = rx.collect::<Vec<_>>().await;
+        assert_eq!(events.len(), 3);
+        let metric = events[0].as_metric();
+        assert_eq!(metric.name(), "cpu_usage");
+        assert_eq!(metric.tags().unwrap()["core"], "0");
+        assert_eq!(events[1].as_metric().name(), "cpu_idle");
+        assert_eq!(events[2].as_log()[EXIT_CODE_KEY], 0.into());
+        assert_eq!(events[2].as_log()[TIMED_OUT_KEY], false.into());
+    }
+
+    #[test]
+    fn test_validate_timeout() {
+        let config: ExecConfig = toml::from_str(
+            r#"
+            mode = "scheduled"
+            command = ["echo"]
+            scheduled.timeout_secs = 0
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.validate(), Err(ExecConfigError::ZeroTimeout));
+    }
+
+    #[test]
+    #[cfg(feature = "sources-prometheus")]
+    fn test_validate_prometheus_streaming() {
+        let config: ExecConfig = toml::from_str(
+            r#"
+            mode = "streaming"
+            command = ["echo"]
+            stdout_protocol = "prometheus"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.validate(), Err(ExecConfigError::PrometheusStreaming));
+    }
+
+    #[test]
+    #[cfg(not(feature = "sources-prometheus"))]
+    fn test_validate_prometheus_unavailable() {
+        let config: ExecConfig = toml::from_str(
+            r#"
+            mode = "scheduled"
+            command = ["echo"]
+            stdout_protocol = "prometheus"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.validate(),
+            Err(ExecConfigError::PrometheusUnavailable)
+        );
+    }
+
     fn standard_scheduled_test_config() -> ExecConfig {
         Default::default()
     }
@@ -718,6 +1016,8 @@ mod tests {
             maximum_buffer_size_bytes: default_maximum_buffer_size(),
             framing: default_framing_stream_based(),
             decoding: default_decoding(),
+            stdout_protocol: StdoutProtocol::default(),
+            include_run_events: false,
         }
     }
 }
@@ -0,0 +1,318 @@
+use bytes::Bytes;
+#[cfg(feature = "sources-prometheus")]
+use bytes::BytesMut;
+use chrono::{TimeZone, Utc};
+use smallvec::SmallVec;
+#[cfg(feature = "sources-prometheus")]
+use tokio_util::codec::LinesCodecError;
+
+#[cfg(feature = "sources-prometheus")]
+use crate::codecs::decoding::BoxedFramingError;
+use crate::{
+    codecs::decoding::format::Deserializer,
+    event::{
+        metric::{Metric, MetricKind, MetricTags, MetricValue},
+        Event,
+    },
+};
+
+/// Deserializer that builds gauges from a line of the Influx line protocol.
+///
+/// Every numeric or boolean field of the line becomes a gauge named after the
+/// measurement and the field, except for a field named `value` which keeps
+/// the name of the measurement. String fields are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct InfluxDeserializer;
+
+impl Deserializer for InfluxDeserializer {
+    fn parse(&self, bytes: Bytes) -> crate::Result<SmallVec<[Event; 1]>> {
+        let line = std::str::from_utf8(&bytes)
+            .map_err(|error| format!("Invalid UTF-8 in Influx line: {}", error))?
+            .trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(SmallVec::new());
+        }
+
+        let metrics = parse_line(line)
+            .map_err(|error| format!("Error parsing Influx line {:?}: {}", line, error))?;
+        Ok(metrics.into_iter().map(Event::Metric).collect())
+    }
+}
+
+/// Deserializer that builds metrics from the Prometheus text exposition
+/// format, expecting the whole output of a run as a single frame.
+#[cfg(feature = "sources-prometheus")]
+#[derive(Debug, Clone, Default)]
+pub struct PrometheusDeserializer;
+
+#[cfg(feature = "sources-prometheus")]
+impl Deserializer for PrometheusDeserializer {
+    fn parse(&self, bytes: Bytes) -> crate::Result<SmallVec<[Event; 1]>> {
+        let text = String::from_utf8_lossy(&bytes);
+        let events = crate::sources::prometheus::parser::parse_text(&text)
+            .map_err(|error| format!("Error parsing Prometheus text: {}", error))?;
+        Ok(events.into_iter().collect())
+    }
+}
+
+/// Framer passing the whole output of a run through as a single frame, like
+/// `BytesDecoder`, unless it's longer than `max_length` bytes, in which case
+/// the output is discarded.
+#[cfg(feature = "sources-prometheus")]
+#[derive(Debug, Clone)]
+pub struct BoundedBytesDecoder {
+    max_length: usize,
+    discarding: bool,
+}
+
+#[cfg(feature = "sources-prometheus")]
+impl BoundedBytesDecoder {
+    pub const fn new(max_length: usize) -> Self {
+        Self {
+            max_length,
+            discarding: false,
+        }
+    }
+}
+
+#[cfg(feature = "sources-prometheus")]
+impl tokio_util::codec::Decoder for BoundedBytesDecoder {
+    type Item = Bytes;
+    type Error = BoxedFramingError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if self.discarding || src.len() > self.max_length {
+            src.clear();
+            if !self.discarding {
+                self.discarding = true;
+                return Err(LinesCodecError::MaxLineLengthExceeded.into());
+            }
+        }
+        Ok(None)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.decode(src)?;
+        if self.discarding || src.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(src.split().freeze()))
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Result<Vec<Metric>, String> {
+    let (series, rest) = split_once_unescaped(line, ' ', false);
+    let rest = rest.ok_or("missing field set")?;
+    let (fields, timestamp) = split_once_unescaped(rest, ' ', true);
+
+    let mut series = split_unescaped(series, ',', false).into_iter();
+    let measurement = unescape(series.next().unwrap_or_default());
+    if measurement.is_empty() {
+        return Err("missing measurement".into());
+    }
+
+    let mut tags = MetricTags::new();
+    for tag in series {
+        match split_once_unescaped(tag, '=', false) {
+            (key, Some(value)) => tags.insert(unescape(key), unescape(value)),
+            (key, None) => return Err(format!("missing value for tag {:?}", key)),
+        };
+    }
+
+    let timestamp = match timestamp.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(timestamp) => {
+            let nanos = timestamp
+                .parse::<i64>()
+                .map_err(|_| format!("invalid timestamp {:?}", timestamp))?;
+            Utc.timestamp_nanos(nanos)
+        }
+        None => Utc::now(),
+    };
+
+    let mut metrics = Vec::new();
+    for field in split_unescaped(fields, ',', true) {
+        let (key, value) = match split_once_unescaped(field, '=', true) {
+            (key, Some(value)) => (unescape(key), value),
+            (key, None) => return Err(format!("missing value for field {:?}", key)),
+        };
+        let value = match parse_field_value(value) {
+            Some(value) => value,
+            None if value.starts_with('"') => continue,
+            None => return Err(format!("invalid value for field {:?}", key)),
+        };
+
+        let name = if key == "value" {
+            measurement.clone()
+        } else {
+            format!("{}_{}", measurement, key)
+        };
+        metrics.push(
+            Metric::new(name, MetricKind::Absolute, MetricValue::Gauge { value })
+                .with_tags((!tags.is_empty()).then(|| tags.clone()))
+                .with_timestamp(Some(timestamp)),
+        );
+    }
+
+    if metrics.is_empty() {
+        Err("no numeric fields".into())
+    } else {
+        Ok(metrics)
+    }
+}
+
+/// Parses a float, integer, unsigned or boolean field value.
+fn parse_field_value(value: &str) -> Option<f64> {
+    match value {
+        "t" | "T" | "true" | "True" | "TRUE" => Some(1.0),
+        "f" | "F" | "false" | "False" | "FALSE" => Some(0.0),
+        _ if value.ends_with('i') => value[..value.len() - 1]
+            .parse::<i64>()
+            .ok()
+            .map(|v| v as f64),
+        _ if value.ends_with('u') => value[..value.len() - 1]
+            .parse::<u64>()
+            .ok()
+            .map(|v| v as f64),
+        _ => value.parse().ok(),
+    }
+}
+
+/// Splits at the first separator that is neither escaped nor, if `quoted`,
+/// inside a double quoted string.
+fn split_once_unescaped(s: &str, separator: char, quoted: bool) -> (&str, Option<&str>) {
+    let mut escaped = false;
+    let mut in_quotes = false;
+    for (index, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if quoted && c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == separator && !in_quotes {
+            return (&s[..index], Some(&s[index + c.len_utf8()..]));
+        }
+    }
+    (s, None)
+}
+
+fn split_unescaped(mut s: &str, separator: char, quoted: bool) -> Vec<&str> {
+    let mut parts = Vec::new();
+    loop {
+        match split_once_unescaped(s, separator, quoted) {
+            (part, Some(rest)) => {
+                parts.push(part);
+                s = rest;
+            }
+            (part, None) => {
+                parts.push(part);
+                return parts;
+            }
+        }
+    }
+}
+
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Vec<Metric> {
+        InfluxDeserializer
+            .parse(Bytes::copy_from_slice(line.as_bytes()))
+            .unwrap()
+            .into_iter()
+            .map(Event::into_metric)
+            .collect()
+    }
+
+    #[test]
+    fn parses_fields_as_gauges() {
+        let metrics = parse(
+            r#"disk,host=a\ b,path=/var used=12i,free=3.5,ok=true,label="x y" 1542182950000000011"#,
+        );
+
+        assert_eq!(metrics.len(), 3);
+        assert_eq!(metrics[0].name(), "disk_used");
+        assert_eq!(metrics[0].value(), &MetricValue::Gauge { value: 12.0 });
+        assert_eq!(metrics[1].name(), "disk_free");
+        assert_eq!(metrics[1].value(), &MetricValue::Gauge { value: 3.5 });
+        assert_eq!(metrics[2].name(), "disk_ok");
+        assert_eq!(metrics[2].value(), &MetricValue::Gauge { value: 1.0 });
+
+        let tags = metrics[0].tags().unwrap();
+        assert_eq!(tags["host"], "a b");
+        assert_eq!(tags["path"], "/var");
+        assert_eq!(
+            metrics[0].timestamp(),
+            Some(Utc.timestamp_nanos(1542182950000000011))
+        );
+    }
+
+    #[test]
+    fn keeps_measurement_name_for_value_field() {
+        let metrics = parse("queue_depth value=4");
+
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].name(), "queue_depth");
+        assert!(metrics[0].tags().is_none());
+        assert!(metrics[0].timestamp().is_some());
+    }
+
+    #[test]
+    fn skips_comments_and_empty_lines() {
+        assert!(parse("# a comment").is_empty());
+        assert!(parse("   ").is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "sources-prometheus")]
+    fn bounds_prometheus_output() {
+        use tokio_util::codec::Decoder;
+
+        let mut decoder = BoundedBytesDecoder::new(8);
+        let mut output = BytesMut::from("up 1\n");
+        assert!(decoder.decode(&mut output).unwrap().is_none());
+        assert_eq!(
+            decoder.decode_eof(&mut output).unwrap(),
+            Some(Bytes::from("up 1\n"))
+        );
+
+        let mut decoder = BoundedBytesDecoder::new(8);
+        let mut output = BytesMut::from("up 1\nup 2\n");
+        assert!(decoder.decode(&mut output).is_err());
+        output.extend_from_slice(b"up 3\n");
+        assert!(decoder.decode(&mut output).unwrap().is_none());
+        assert!(decoder.decode_eof(&mut output).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        for line in &[
+            "cpu",
+            "cpu value=abc",
+            "cpu,host value=1",
+            "cpu label=\"x\"",
+        ] {
+            assert!(
+                InfluxDeserializer
+                    .parse(Bytes::copy_from_slice(line.as_bytes()))
+                    .is_err(),
+                "{}",
+                line
+            );
+        }
+    }
+}
@@ -3,7 +3,11 @@ use std::{convert::TryInto, path::PathBuf, time::Duration};
 use bytes::Bytes;
 use chrono::Utc;
 use file_source::{
-    paths_provider::glob::{Glob, MatchOptions},
+    paths_provider::{
+        glob::{Glob, MatchOptions},
+        notify::Notify,
+        PathsProvider,
+    },
     Checkpointer, FileFingerprint, FileServer, FingerprintStrategy, Fingerprinter, Line, ReadFrom,
 };
 use futures::{
@@ -81,6 +85,7 @@ pub struct FileConfig {
     pub data_dir: Option<PathBuf>,
     #[serde(alias = "glob_minimum_cooldown")]
     pub glob_minimum_cooldown_ms: u64,
+    pub discovery: DiscoveryConfig,
     // Deprecated name
     #[serde(alias = "fingerprinting")]
     fingerprint: FingerprintConfig,
@@ -113,6 +118,22 @@ pub enum FingerprintConfig {
     DevInode,
 }
 
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoveryConfig {
+    /// List the paths matching the patterns and poll the files periodically.
+    Glob,
+    /// Watch the directories of the patterns for changes, falling back to
+    /// `Glob` where they can't be watched.
+    Notify,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self::Glob
+    }
+}
+
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum ReadFromConfig {
@@ -189,6 +210,7 @@ impl Default for FileConfig {
             host_key: None,
             data_dir: None,
             glob_minimum_cooldown_ms: 1000, // millis
+            discovery: DiscoveryConfig::Glob,
             message_start_indicator: None,
             multi_line_timeout: 1000, // millis
             multiline: None,
@@ -275,13 +297,18 @@ pub fn file_source(
         config.read_from,
     );
 
-    let paths_provider = Glob::new(
+    let glob = Glob::new(
         &config.include,
         &config.exclude,
         MatchOptions::default(),
         FileSourceInternalEventsEmitter,
     )
     .expect("invalid glob patterns");
+    let paths_provider: Box<dyn PathsProvider<IntoIter = Vec<PathBuf>> + Send> =
+        match config.discovery {
+            DiscoveryConfig::Glob => Box::new(glob),
+            DiscoveryConfig::Notify => Box::new(Notify::new(glob, FileSourceInternalEventsEmitter)),
+        };
 
     let encoding_charset = config.encoding.clone().map(|e| e.charset);
 
@@ -613,6 +640,14 @@ mod tests {
         )
         .unwrap();
         assert_eq!(config.read_from, Some(ReadFromConfig::End));
+
+        let config: FileConfig = toml::from_str(
+            r#"
+        discovery = "notify"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.discovery, DiscoveryConfig::Notify);
     }
 
     #[test]
@@ -704,6 +739,42 @@ mod tests {
         assert_eq!(goodbye_i, n);
     }
 
+    #[tokio::test]
+    async fn file_notify_discovery() {
+        let dir = tempdir().unwrap();
+        let config = file::FileConfig {
+            include: vec![dir.path().join("*.log")],
+            discovery: DiscoveryConfig::Notify,
+            // Files are found through notifications, not by globbing periodically.
+            glob_minimum_cooldown_ms: 0,
+            ..test_default_file_config(&dir)
+        };
+
+        let path = dir.path().join("file.log");
+        let archive_path = dir.path().join("file.log.1");
+
+        let received = run_file_source(&config, false, NoAcks, async {
+            let mut file = File::create(&path).unwrap();
+            sleep_500_millis().await;
+
+            writeln!(&mut file, "before rotation").unwrap();
+            sleep_500_millis().await;
+
+            fs::rename(&path, &archive_path).unwrap();
+            writeln!(&mut file, "after rename").unwrap();
+            let mut file = File::create(&path).unwrap();
+            writeln!(&mut file, "after rotation").unwrap();
+            sleep_500_millis().await;
+        })
+        .await;
+
+        let received = extract_messages_string(received);
+        assert_eq!(
+            received,
+            vec!["before rotation", "after rename", "after rotation"]
+        );
+    }
+
     // https://github.com/vectordotdev/vector/issues/8363
     #[tokio::test]
     async fn file_read_empty_lines() {
@@ -0,0 +1,522 @@
+use std::time::Instant;
+
+use bytes::Bytes;
+use futures::{future::join_all, StreamExt};
+use http::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Request, Uri,
+};
+use hyper::{body::to_bytes as body_to_bytes, Body};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt, Snafu};
+use tokio::time;
+use tokio_stream::wrappers::IntervalStream;
+use tokio_util::codec::FramedRead;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    codecs::{
+        self,
+        decoding::{DecodingConfig, DeserializerConfig, FramingConfig},
+    },
+    config::{
+        log_schema, DataType, GenerateConfig, Output, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{Event, LogEvent, Value},
+    http::{Auth, HttpClient},
+    internal_events::{
+        BytesReceived, HttpClientEventsReceived, HttpClientHttpError, HttpClientHttpResponseError,
+        StreamClosedError, TemplateRenderingError,
+    },
+    serde::{default_decoding, default_framing_message_based},
+    template::Template,
+    tls::{TlsOptions, TlsSettings},
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Failed to parse endpoint {:?}: {}", endpoint, source))]
+    InvalidEndpoint {
+        endpoint: String,
+        source: http::uri::InvalidUri,
+    },
+    #[snafu(display("Invalid header name {:?}: {}", name, source))]
+    InvalidHeaderName {
+        name: String,
+        source: http::header::InvalidHeaderName,
+    },
+    #[snafu(display("Invalid header value {:?}: {}", value, source))]
+    InvalidHeaderValue {
+        value: String,
+        source: http::header::InvalidHeaderValue,
+    },
+    #[snafu(display("Authorization header can not be used with defined auth options"))]
+    AuthorizationHeader,
+    #[snafu(display("`interval_secs` must be greater than zero"))]
+    ZeroInterval,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct HttpClientConfig {
+    endpoints: Vec<String>,
+    #[serde(default = "default_interval_secs")]
+    interval_secs: u64,
+    /// Query parameters added to the endpoints, rendered against the last
+    /// event received from each endpoint.
+    #[serde(default)]
+    query: IndexMap<String, Template>,
+    #[serde(default)]
+    headers: IndexMap<String, String>,
+    /// A field holding an array, each element of which becomes an event.
+    split_field: Option<String>,
+    #[serde(default = "default_framing_message_based")]
+    framing: FramingConfig,
+    #[serde(default = "default_decoding")]
+    decoding: DeserializerConfig,
+    tls: Option<TlsOptions>,
+    auth: Option<Auth>,
+}
+
+const fn default_interval_secs() -> u64 {
+    15
+}
+
+inventory::submit! {
+    SourceDescription::new::<HttpClientConfig>("http_client")
+}
+
+impl GenerateConfig for HttpClientConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            endpoints = ["http://localhost:8080/api/events"]
+            decoding.codec = "json""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "http_client")]
+impl SourceConfig for HttpClientConfig {
+    async fn build(&self, mut cx: SourceContext) -> crate::Result<super::Source> {
+        if self.interval_secs == 0 {
+            return Err(BuildError::ZeroInterval.into());
+        }
+        let duration = time::Duration::from_secs(self.interval_secs);
+
+        let tls = TlsSettings::from_options(&self.tls)?;
+        let http_client = HttpClient::new(tls, &cx.proxy)?;
+        let headers = build_headers(&self.headers, &self.auth)?;
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+
+        let context = PollContext {
+            http_client,
+            headers,
+            auth: self.auth.clone(),
+            query: self.query.clone(),
+            split_field: self.split_field.clone(),
+            decoder,
+            timeout: duration,
+        };
+        let mut endpoints = self
+            .endpoints
+            .iter()
+            .map(|endpoint| {
+                let uri = endpoint
+                    .parse::<Uri>()
+                    .context(InvalidEndpointSnafu { endpoint })?;
+                Ok(Endpoint {
+                    uri,
+                    last_event: None,
+                })
+            })
+            .collect::<Result<Vec<_>, BuildError>>()?;
+
+        let shutdown = cx.shutdown;
+        Ok(Box::pin(async move {
+            let mut interval = IntervalStream::new(time::interval(duration)).take_until(shutdown);
+            while interval.next().await.is_some() {
+                let events = join_all(endpoints.iter_mut().map(|endpoint| endpoint.poll(&context)))
+                    .await
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>();
+
+                let count = events.len();
+                if let Err(error) = cx.out.send_batch(events).await {
+                    emit!(StreamClosedError { error, count });
+                    return Err(());
+                }
+            }
+
+            Ok(())
+        }))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "http_client"
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+fn build_headers(
+    headers: &IndexMap<String, String>,
+    auth: &Option<Auth>,
+) -> Result<HeaderMap, BuildError> {
+    let mut map = HeaderMap::with_capacity(headers.len());
+    for (name, value) in headers {
+        if auth.is_some() && name.eq_ignore_ascii_case("Authorization") {
+            return Err(BuildError::AuthorizationHeader);
+        }
+
+        let name =
+            HeaderName::from_bytes(name.as_bytes()).context(InvalidHeaderNameSnafu { name })?;
+        let value =
+            HeaderValue::from_bytes(value.as_bytes()).context(InvalidHeaderValueSnafu { value })?;
+        map.append(name, value);
+    }
+    Ok(map)
+}
+
+/// The settings shared by the requests to every endpoint.
+struct PollContext {
+    http_client: HttpClient,
+    headers: HeaderMap,
+    auth: Option<Auth>,
+    query: IndexMap<String, Template>,
+    split_field: Option<String>,
+    decoder: codecs::Decoder,
+    /// How long a request, including reading its body, may take.
+    timeout: time::Duration,
+}
+
+struct Endpoint {
+    uri: Uri,
+    /// The last event received, which the query templates are rendered
+    /// against.
+    last_event: Option<Event>,
+}
+
+impl Endpoint {
+    /// Renders the query parameters, skipping the ones that can't be rendered
+    /// before any event was received.
+    fn request_uri(&self, query: &IndexMap<String, Template>) -> Uri {
+        if query.is_empty() {
+            return self.uri.clone();
+        }
+
+        let empty = Event::new_empty_log();
+        let event = self.last_event.as_ref().unwrap_or(&empty);
+        let mut serializer = url::form_urlencoded::Serializer::new(
+            self.uri.query().map(ToOwned::to_owned).unwrap_or_default(),
+        );
+        for (name, template) in query {
+            match template.render_string(event) {
+                Ok(value) => {
+                    serializer.append_pair(name, &value);
+                }
+                Err(error) => {
+                    if self.last_event.is_some() {
+                        emit!(TemplateRenderingError {
+                            error,
+                            field: Some(name.as_str()),
+                            drop_event: false,
+                        });
+                    }
+                }
+            }
+        }
+        let query = serializer.finish();
+
+        let mut parts = self.uri.clone().into_parts();
+        let path = parts
+            .path_and_query
+            .as_ref()
+            .map_or("/", |path_and_query| path_and_query.path());
+        let path_and_query = if query.is_empty() {
+            path.to_owned()
+        } else {
+            format!("{}?{}", path, query)
+        };
+        parts.path_and_query = Some(
+            path_and_query
+                .parse()
+                .expect("url encoded query should be valid"),
+        );
+        Uri::from_parts(parts).expect("parts of a valid uri should be valid")
+    }
+
+    async fn poll(&mut self, context: &PollContext) -> Vec<Event> {
+        let uri = self.request_uri(&context.query);
+        let mut request = Request::get(&uri)
+            .body(Body::empty())
+            .expect("error creating request");
+        request.headers_mut().extend(context.headers.clone());
+        if let Some(auth) = &context.auth {
+            auth.apply(&mut request);
+        }
+
+        let start = Instant::now();
+        let (status, body) =
+            match time::timeout(context.timeout, fetch(&context.http_client, request)).await {
+                Ok(Ok(response)) => response,
+                Ok(Err(error)) => {
+                    emit!(HttpClientHttpError { error, url: uri });
+                    return Vec::new();
+                }
+                Err(error) => {
+                    emit!(HttpClientHttpError {
+                        error: error.into(),
+                        url: uri,
+                    });
+                    return Vec::new();
+                }
+            };
+        if !status.is_success() {
+            emit!(HttpClientHttpResponseError {
+                code: status,
+                url: uri,
+            });
+            return Vec::new();
+        }
+        emit!(BytesReceived {
+            byte_size: body.len(),
+            protocol: "http",
+        });
+
+        let events = decode(&context.decoder, body, context.split_field.as_deref()).await;
+        emit!(HttpClientEventsReceived {
+            byte_size: events.size_of(),
+            count: events.len(),
+            url: uri,
+            start,
+        });
+
+        if let Some(event) = events.last() {
+            self.last_event = Some(event.clone());
+        }
+        events
+    }
+}
+
+async fn fetch(
+    http_client: &HttpClient,
+    request: Request<Body>,
+) -> crate::Result<(http::StatusCode, Bytes)> {
+    let response = http_client.send(request).await?;
+    let (parts, body) = response.into_parts();
+    let body = body_to_bytes(body).await?;
+    Ok((parts.status, body))
+}
+
+async fn decode(decoder: &codecs::Decoder, body: Bytes, split_field: Option<&str>) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut stream = FramedRead::new(body.as_ref(), decoder.clone());
+    while let Some(next) = stream.next().await {
+        match next {
+            Ok((decoded, _byte_size)) => events.extend(decoded),
+            Err(error) => {
+                // Error is logged by `crate::codecs::Decoder`, no further
+                // handling is needed here.
+                if !error.can_continue() {
+                    break;
+                }
+            }
+        }
+    }
+
+    if let Some(split_field) = split_field {
+        events = events
+            .into_iter()
+            .flat_map(|event| split(event, split_field))
+            .collect();
+    }
+
+    for event in &mut events {
+        if let Event::Log(log) = event {
+            log.try_insert(log_schema().source_type_key(), Bytes::from("http_client"));
+        }
+    }
+    events
+}
+
+/// Splits the array in the field into an event per element, keeping the event
+/// as is if the field doesn't hold an array.
+fn split(event: Event, field: &str) -> Vec<Event> {
+    let mut log = match event {
+        Event::Log(log) => log,
+        event => return vec![event],
+    };
+    let values = match log.remove(field) {
+        Some(Value::Array(values)) => values,
+        Some(value) => {
+            log.insert(field, value);
+            return vec![log.into()];
+        }
+        None => return vec![log.into()],
+    };
+
+    let timestamp = log.get(log_schema().timestamp_key()).cloned();
+    values
+        .into_iter()
+        .map(|value| {
+            let mut element = match value {
+                Value::Object(fields) => LogEvent::from(fields),
+                value => {
+                    let mut element = LogEvent::default();
+                    element.insert(log_schema().message_key(), value);
+                    element
+                }
+            };
+            if let Some(timestamp) = &timestamp {
+                element.try_insert(log_schema().timestamp_key(), timestamp.clone());
+            }
+            element.into()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use warp::Filter;
+
+    use super::*;
+    use crate::{
+        test_util::{collect_n, next_addr},
+        SourceSender,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<HttpClientConfig>();
+    }
+
+    #[test]
+    fn rejects_authorization_header_with_auth() {
+        let headers = vec![("authorization".to_owned(), "Bearer foo".to_owned())]
+            .into_iter()
+            .collect();
+        let auth = Some(Auth::Bearer {
+            token: "foo".to_owned(),
+        });
+        assert!(matches!(
+            build_headers(&headers, &auth),
+            Err(BuildError::AuthorizationHeader)
+        ));
+    }
+
+    #[test]
+    fn renders_query_against_last_event() {
+        let query = vec![
+            ("limit".to_owned(), Template::try_from("10").unwrap()),
+            ("after".to_owned(), Template::try_from("{{ id }}").unwrap()),
+        ]
+        .into_iter()
+        .collect();
+        let mut endpoint = Endpoint {
+            uri: "http://localhost/events?sort=asc".parse().unwrap(),
+            last_event: None,
+        };
+        assert_eq!(
+            endpoint.request_uri(&query),
+            "http://localhost/events?sort=asc&limit=10"
+        );
+
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("id", "a b");
+        endpoint.last_event = Some(event);
+        assert_eq!(
+            endpoint.request_uri(&query),
+            "http://localhost/events?sort=asc&limit=10&after=a+b"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_zero_interval() {
+        let config: HttpClientConfig = toml::from_str(
+            r#"
+            endpoints = ["http://localhost/events"]
+            interval_secs = 0
+            "#,
+        )
+        .unwrap();
+        let (tx, _rx) = SourceSender::new_test();
+        assert!(config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn splits_array_field() {
+        let mut event = Event::new_empty_log();
+        event.as_mut_log().insert("data[0].id", 1);
+        event.as_mut_log().insert("data[1]", "foo");
+        let events = split(event, "data");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].as_log()["id"], 1.into());
+        assert_eq!(events[1].as_log()[log_schema().message_key()], "foo".into());
+    }
+
+    #[tokio::test]
+    async fn polls_endpoints() {
+        let address = next_addr();
+        let queries = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&queries);
+        let dummy_endpoint = warp::path!("events")
+            .and(warp::query::<HashMap<String, String>>())
+            .map(move |query: HashMap<String, String>| {
+                seen.lock().unwrap().push(query);
+                r#"{"items": [{"id": 1}, {"id": 2}]}"#
+            });
+        tokio::spawn(warp::serve(dummy_endpoint).run(address));
+
+        let config: HttpClientConfig = toml::from_str(&format!(
+            r#"
+            endpoints = ["http://{}/events"]
+            interval_secs = 1
+            query.after = "{{{{ id }}}}"
+            split_field = "items"
+            decoding.codec = "json"
+            "#,
+            address
+        ))
+        .unwrap();
+
+        let (tx, rx) = SourceSender::new_test();
+        let source = config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+
+        let events = time::timeout(time::Duration::from_secs(5), collect_n(rx, 4))
+            .await
+            .expect("timed out waiting for two polls");
+        assert_eq!(events[0].as_log()["id"], 1.into());
+        assert_eq!(
+            events[0].as_log()[log_schema().source_type_key()],
+            "http_client".into()
+        );
+
+        let queries = queries.lock().unwrap();
+        assert_eq!(queries[0].get("after"), None);
+        assert_eq!(queries[1].get("after").map(String::as_str), Some("2"));
+    }
+}
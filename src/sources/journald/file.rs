@@ -0,0 +1,739 @@
+//! Reading of systemd journal files, without relying on `libsystemd` or `journalctl`.
+//!
+//! Only the parts of the format needed to walk the entries in order are implemented: the
+//! header, the entry arrays, and the entry and data objects. The hash tables are never used,
+//! as matches are applied to the fields of the entries once read.
+//!
+//! <https://systemd.io/JOURNAL_FILE_FORMAT/>
+
+use std::{
+    convert::TryInto,
+    fmt,
+    fs::File,
+    io::{self, Read},
+    os::unix::fs::FileExt,
+    path::{Path, PathBuf},
+};
+
+use bytes::Bytes;
+use snafu::{ResultExt, Snafu};
+
+const SIGNATURE: &[u8; 8] = b"LPKSHHRH";
+
+/// The size of the header up to `tail_entry_monotonic`, the last field used here, which all
+/// versions of the format have.
+const HEADER_SIZE: usize = 208;
+
+const OBJECT_HEADER_SIZE: u64 = 16;
+const ENTRY_HEADER_SIZE: u64 = 64;
+const ENTRY_ARRAY_HEADER_SIZE: u64 = 24;
+const DATA_HEADER_SIZE: u64 = 64;
+const COMPACT_DATA_HEADER_SIZE: u64 = 72;
+
+/// Guards against corrupted object sizes.
+const MAX_OBJECT_SIZE: u64 = 1 << 30;
+
+mod object_type {
+    pub const DATA: u8 = 1;
+    pub const ENTRY: u8 = 3;
+    pub const ENTRY_ARRAY: u8 = 6;
+}
+
+mod object_flag {
+    pub const COMPRESSED_XZ: u8 = 1;
+    pub const COMPRESSED_LZ4: u8 = 2;
+    pub const COMPRESSED_ZSTD: u8 = 4;
+}
+
+mod incompatible_flag {
+    // XZ compression (1) is not supported.
+    pub const COMPRESSED_LZ4: u32 = 2;
+    pub const KEYED_HASH: u32 = 4;
+    pub const COMPRESSED_ZSTD: u32 = 8;
+    pub const COMPACT: u32 = 16;
+
+    pub const SUPPORTED: u32 = COMPRESSED_LZ4 | KEYED_HASH | COMPRESSED_ZSTD | COMPACT;
+}
+
+const STATE_ARCHIVED: u8 = 2;
+
+#[derive(Debug, Snafu)]
+pub enum JournalError {
+    #[snafu(display("Unable to read journal file: {}", source))]
+    Io { source: io::Error },
+    #[snafu(display("Not a journal file"))]
+    InvalidSignature,
+    #[snafu(display("Unsupported journal file features {:#x}", flags))]
+    UnsupportedFeatures { flags: u32 },
+    #[snafu(display("Invalid object at offset {}", offset))]
+    InvalidObject { offset: u64 },
+    #[snafu(display("Unsupported compression of object at offset {}", offset))]
+    UnsupportedCompression { offset: u64 },
+    #[snafu(display("Unable to decompress object at offset {}", offset))]
+    Decompress { offset: u64 },
+}
+
+pub type Id128 = [u8; 16];
+
+#[derive(Clone, Debug)]
+struct Header {
+    incompatible_flags: u32,
+    state: u8,
+    file_id: Id128,
+    seqnum_id: Id128,
+    header_size: u64,
+    arena_size: u64,
+    n_entries: u64,
+    entry_array_offset: u64,
+}
+
+impl Header {
+    fn read(file: &File) -> Result<Self, JournalError> {
+        let mut buf = [0; HEADER_SIZE];
+        file.read_exact_at(&mut buf, 0).context(IoSnafu)?;
+        if &buf[..8] != SIGNATURE {
+            return Err(JournalError::InvalidSignature);
+        }
+
+        let incompatible_flags = le32(&buf, 12);
+        if incompatible_flags & !incompatible_flag::SUPPORTED != 0 {
+            return Err(JournalError::UnsupportedFeatures {
+                flags: incompatible_flags,
+            });
+        }
+
+        Ok(Self {
+            incompatible_flags,
+            state: buf[16],
+            file_id: id128(&buf, 24),
+            seqnum_id: id128(&buf, 72),
+            header_size: le64(&buf, 88),
+            arena_size: le64(&buf, 96),
+            n_entries: le64(&buf, 152),
+            entry_array_offset: le64(&buf, 176),
+        })
+    }
+
+    const fn is_compact(&self) -> bool {
+        self.incompatible_flags & incompatible_flag::COMPACT != 0
+    }
+}
+
+/// An entry of the journal, with its fields in the order they were written.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub seqnum: u64,
+    pub realtime: u64,
+    pub monotonic: u64,
+    pub boot_id: Id128,
+    pub xor_hash: u64,
+    pub fields: Vec<(String, Bytes)>,
+}
+
+/// The entry array being walked, and the index of the next entry in it.
+#[derive(Clone, Copy, Debug, Default)]
+struct Position {
+    array: u64,
+    capacity: u64,
+    index: u64,
+    read: u64,
+}
+
+pub struct JournalFile {
+    path: PathBuf,
+    file: File,
+    header: Header,
+    position: Position,
+}
+
+impl JournalFile {
+    pub fn open(path: &Path) -> Result<Self, JournalError> {
+        let file = File::open(path).context(IoSnafu)?;
+        let header = Header::read(&file)?;
+        Ok(Self {
+            path: path.into(),
+            file,
+            header,
+            position: Position::default(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub const fn file_id(&self) -> &Id128 {
+        &self.header.file_id
+    }
+
+    pub const fn seqnum_id(&self) -> &Id128 {
+        &self.header.seqnum_id
+    }
+
+    /// Archived files are never written to again.
+    pub const fn is_archived(&self) -> bool {
+        self.header.state == STATE_ARCHIVED
+    }
+
+    /// Rereads the header, to see the entries appended since it was last read.
+    pub fn refresh(&mut self) -> Result<(), JournalError> {
+        self.header = Header::read(&self.file)?;
+        Ok(())
+    }
+
+    /// Reads the next entry, or returns `None` if all the entries have been read. With
+    /// `with_fields` unset, only the metadata of the entry is read.
+    pub fn next_entry(&mut self, with_fields: bool) -> Result<Option<Entry>, JournalError> {
+        match self.next_entry_offset()? {
+            Some(offset) => self.read_entry(offset, with_fields).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Skips the entries matching the predicate, stopping before the first one that doesn't.
+    /// Only the metadata of the skipped entries is read.
+    pub fn skip_while(
+        &mut self,
+        mut predicate: impl FnMut(&Entry) -> bool,
+    ) -> Result<(), JournalError> {
+        loop {
+            let position = self.position;
+            match self.next_entry(false)? {
+                Some(entry) if predicate(&entry) => continue,
+                Some(_) => {
+                    self.position = position;
+                    return Ok(());
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+
+    fn next_entry_offset(&mut self) -> Result<Option<u64>, JournalError> {
+        if self.position.read >= self.header.n_entries {
+            return Ok(None);
+        }
+
+        loop {
+            if self.position.array == 0 {
+                let first = self.header.entry_array_offset;
+                if first == 0 {
+                    return Ok(None);
+                }
+                self.position.array = first;
+                self.position.capacity = self.read_entry_array(first)?.1;
+                self.position.index = 0;
+            }
+
+            if self.position.index >= self.position.capacity {
+                // The next array may have been linked since the current one was read.
+                let next = self.read_entry_array(self.position.array)?.0;
+                if next == 0 {
+                    return Ok(None);
+                }
+                self.position.array = next;
+                self.position.capacity = self.read_entry_array(next)?.1;
+                self.position.index = 0;
+                continue;
+            }
+
+            let item_size = self.entry_array_item_size();
+            let item_offset =
+                self.position.array + ENTRY_ARRAY_HEADER_SIZE + self.position.index * item_size;
+            let offset = self.read_uint(item_offset, item_size)?;
+            if offset == 0 {
+                // The rest of the array is yet to be written.
+                return Ok(None);
+            }
+
+            self.position.index += 1;
+            self.position.read += 1;
+            return Ok(Some(offset));
+        }
+    }
+
+    /// Reads the offset of the next entry array and the number of items of an entry array.
+    fn read_entry_array(&self, offset: u64) -> Result<(u64, u64), JournalError> {
+        let size = self.read_object_header(offset, object_type::ENTRY_ARRAY)?.1;
+        if size < ENTRY_ARRAY_HEADER_SIZE {
+            return Err(JournalError::InvalidObject { offset });
+        }
+        let next = self.read_uint(offset + OBJECT_HEADER_SIZE, 8)?;
+        let capacity = (size - ENTRY_ARRAY_HEADER_SIZE) / self.entry_array_item_size();
+        Ok((next, capacity))
+    }
+
+    const fn entry_array_item_size(&self) -> u64 {
+        if self.header.is_compact() {
+            4
+        } else {
+            8
+        }
+    }
+
+    fn read_entry(&self, offset: u64, with_fields: bool) -> Result<Entry, JournalError> {
+        let size = self.read_object_header(offset, object_type::ENTRY)?.1;
+        if size < ENTRY_HEADER_SIZE {
+            return Err(JournalError::InvalidObject { offset });
+        }
+        let buf = self.read_at(offset, if with_fields { size } else { ENTRY_HEADER_SIZE })?;
+
+        let fields = if with_fields {
+            let item_size = if self.header.is_compact() { 4 } else { 16 };
+            buf[ENTRY_HEADER_SIZE as usize..]
+                .chunks_exact(item_size)
+                .map(|item| {
+                    let offset = if self.header.is_compact() {
+                        le32(item, 0) as u64
+                    } else {
+                        le64(item, 0)
+                    };
+                    self.read_field(offset)
+                })
+                .collect::<Result<_, _>>()?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Entry {
+            seqnum: le64(&buf, 16),
+            realtime: le64(&buf, 24),
+            monotonic: le64(&buf, 32),
+            boot_id: id128(&buf, 40),
+            xor_hash: le64(&buf, 56),
+            fields,
+        })
+    }
+
+    fn read_field(&self, offset: u64) -> Result<(String, Bytes), JournalError> {
+        let (flags, size) = self.read_object_header(offset, object_type::DATA)?;
+        let header_size = if self.header.is_compact() {
+            COMPACT_DATA_HEADER_SIZE
+        } else {
+            DATA_HEADER_SIZE
+        };
+        if size < header_size {
+            return Err(JournalError::InvalidObject { offset });
+        }
+        let payload = self.read_at(offset + header_size, size - header_size)?;
+
+        let payload = if flags & object_flag::COMPRESSED_ZSTD != 0 {
+            zstd_decompress(&payload, MAX_OBJECT_SIZE).ok_or(JournalError::Decompress { offset })?
+        } else if flags & object_flag::COMPRESSED_LZ4 != 0 {
+            if payload.len() < 8 {
+                return Err(JournalError::InvalidObject { offset });
+            }
+            let size = le64(&payload, 0).min(MAX_OBJECT_SIZE) as usize;
+            lz4_decompress(&payload[8..], size).ok_or(JournalError::Decompress { offset })?
+        } else if flags & object_flag::COMPRESSED_XZ != 0 {
+            return Err(JournalError::UnsupportedCompression { offset });
+        } else {
+            payload
+        };
+
+        let separator = payload
+            .iter()
+            .position(|&byte| byte == b'=')
+            .ok_or(JournalError::InvalidObject { offset })?;
+        let name = String::from_utf8_lossy(&payload[..separator]).into_owned();
+        let mut value = Bytes::from(payload);
+        Ok((name, value.split_off(separator + 1)))
+    }
+
+    /// Reads the type and size of the object at `offset`, checking it is in the arena.
+    fn read_object_header(&self, offset: u64, expected: u8) -> Result<(u8, u64), JournalError> {
+        let invalid = || JournalError::InvalidObject { offset };
+        let arena_end = self.header.header_size + self.header.arena_size;
+        if offset % 8 != 0 || offset < self.header.header_size || offset >= arena_end {
+            return Err(invalid());
+        }
+
+        let buf = self.read_at(offset, OBJECT_HEADER_SIZE)?;
+        let size = le64(&buf, 8);
+        if buf[0] != expected
+            || size < OBJECT_HEADER_SIZE
+            || size > MAX_OBJECT_SIZE
+            || offset + size > arena_end
+        {
+            return Err(invalid());
+        }
+        Ok((buf[1], size))
+    }
+
+    fn read_uint(&self, offset: u64, size: u64) -> Result<u64, JournalError> {
+        let buf = self.read_at(offset, size)?;
+        Ok(match size {
+            4 => le32(&buf, 0) as u64,
+            _ => le64(&buf, 0),
+        })
+    }
+
+    fn read_at(&self, offset: u64, size: u64) -> Result<Vec<u8>, JournalError> {
+        let mut buf = vec![0; size as usize];
+        self.file.read_exact_at(&mut buf, offset).context(IoSnafu)?;
+        Ok(buf)
+    }
+}
+
+/// Decompresses a zstd frame, giving up once the output exceeds `max_size` instead of
+/// trusting the size the frame announces.
+fn zstd_decompress(input: &[u8], max_size: u64) -> Option<Vec<u8>> {
+    let mut output = Vec::new();
+    zstd::stream::read::Decoder::with_buffer(input)
+        .ok()?
+        .take(max_size + 1)
+        .read_to_end(&mut output)
+        .ok()?;
+    (output.len() as u64 <= max_size).then(|| output)
+}
+
+/// Decompresses an LZ4 block, as written by journald.
+///
+/// <https://github.com/lz4/lz4/blob/dev/doc/lz4_Block_format.md>
+fn lz4_decompress(input: &[u8], size: usize) -> Option<Vec<u8>> {
+    fn length(input: &[u8], i: &mut usize, mut length: usize) -> Option<usize> {
+        if length == 15 {
+            loop {
+                let byte = *input.get(*i)?;
+                *i += 1;
+                length += byte as usize;
+                if byte != 255 {
+                    break;
+                }
+            }
+        }
+        Some(length)
+    }
+
+    let mut output = Vec::with_capacity(size);
+    let mut i = 0;
+    while i < input.len() {
+        let token = input[i];
+        i += 1;
+
+        let literals = length(input, &mut i, (token >> 4) as usize)?;
+        output.extend_from_slice(input.get(i..i + literals)?);
+        i += literals;
+        // The last sequence only has literals.
+        if i == input.len() {
+            break;
+        }
+
+        let offset = u16::from_le_bytes([*input.get(i)?, *input.get(i + 1)?]) as usize;
+        i += 2;
+        if offset == 0 || offset > output.len() {
+            return None;
+        }
+        let matched = length(input, &mut i, (token & 0x0f) as usize)? + 4;
+        if output.len() + matched > size {
+            return None;
+        }
+        // The match may overlap the bytes it produces.
+        let start = output.len() - offset;
+        for index in start..start + matched {
+            output.push(output[index]);
+        }
+    }
+
+    (output.len() == size).then(|| output)
+}
+
+/// The position of an entry in the journal, in the format of `journalctl --show-cursor`, so
+/// that checkpoints can be shared with it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cursor {
+    seqnum_id: Id128,
+    seqnum: u64,
+    boot_id: Id128,
+    monotonic: u64,
+    realtime: u64,
+    xor_hash: u64,
+}
+
+impl Cursor {
+    pub const fn new(seqnum_id: Id128, entry: &Entry) -> Self {
+        Self {
+            seqnum_id,
+            seqnum: entry.seqnum,
+            boot_id: entry.boot_id,
+            monotonic: entry.monotonic,
+            realtime: entry.realtime,
+            xor_hash: entry.xor_hash,
+        }
+    }
+
+    pub fn parse(cursor: &str) -> Option<Self> {
+        let mut parsed = Self {
+            seqnum_id: Id128::default(),
+            seqnum: 0,
+            boot_id: Id128::default(),
+            monotonic: 0,
+            realtime: 0,
+            xor_hash: 0,
+        };
+        for part in cursor.split(';') {
+            let (key, value) = part.split_once('=')?;
+            match key {
+                "s" => parsed.seqnum_id = parse_id128(value)?,
+                "i" => parsed.seqnum = u64::from_str_radix(value, 16).ok()?,
+                "b" => parsed.boot_id = parse_id128(value)?,
+                "m" => parsed.monotonic = u64::from_str_radix(value, 16).ok()?,
+                "t" => parsed.realtime = u64::from_str_radix(value, 16).ok()?,
+                "x" => parsed.xor_hash = u64::from_str_radix(value, 16).ok()?,
+                _ => {}
+            }
+        }
+        Some(parsed)
+    }
+
+    /// Whether the entry was written after the one of the cursor. Sequence numbers are only
+    /// comparable within files sharing a sequence number id, other entries are compared by
+    /// their wallclock time.
+    pub fn is_before(&self, seqnum_id: &Id128, entry: &Entry) -> bool {
+        if *seqnum_id == self.seqnum_id {
+            entry.seqnum > self.seqnum
+        } else {
+            entry.realtime > self.realtime
+        }
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "s={};i={:x};b={};m={:x};t={:x};x={:x}",
+            format_id128(&self.seqnum_id),
+            self.seqnum,
+            format_id128(&self.boot_id),
+            self.monotonic,
+            self.realtime,
+            self.xor_hash
+        )
+    }
+}
+
+pub fn format_id128(id: &Id128) -> String {
+    id.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Parses an id, with or without the dashes of the UUID format.
+pub fn parse_id128(id: &str) -> Option<Id128> {
+    let digits = id.trim().replace('-', "");
+    if digits.len() != 32 {
+        return None;
+    }
+    let mut parsed = Id128::default();
+    for (index, byte) in parsed.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(digits.get(index * 2..index * 2 + 2)?, 16).ok()?;
+    }
+    Some(parsed)
+}
+
+fn le32(buf: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(buf[at..at + 4].try_into().unwrap())
+}
+
+fn le64(buf: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(buf[at..at + 8].try_into().unwrap())
+}
+
+fn id128(buf: &[u8], at: usize) -> Id128 {
+    buf[at..at + 16].try_into().unwrap()
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use std::io::Write;
+
+    use tempfile::NamedTempFile;
+
+    use super::*;
+
+    /// Writes a minimal journal file, with one entry array and uncompressed data objects.
+    pub fn write_journal(
+        seqnum_id: Id128,
+        entries: &[(u64, u64, Vec<(&str, &[u8])>)],
+    ) -> NamedTempFile {
+        let header_size = 240u64;
+        let mut arena = Vec::new();
+        let mut entry_offsets = Vec::new();
+        let object = |arena: &mut Vec<u8>, ty: u8, body: &[u8]| -> u64 {
+            let offset = header_size + arena.len() as u64;
+            let size = OBJECT_HEADER_SIZE + body.len() as u64;
+            arena.push(ty);
+            arena.extend_from_slice(&[0; 7]);
+            arena.extend_from_slice(&size.to_le_bytes());
+            arena.extend_from_slice(body);
+            while arena.len() % 8 != 0 {
+                arena.push(0);
+            }
+            offset
+        };
+
+        for (seqnum, realtime, fields) in entries {
+            let data = fields
+                .iter()
+                .map(|(name, value)| {
+                    let mut body = vec![0; (DATA_HEADER_SIZE - OBJECT_HEADER_SIZE) as usize];
+                    body.extend_from_slice(name.as_bytes());
+                    body.push(b'=');
+                    body.extend_from_slice(value);
+                    object(&mut arena, object_type::DATA, &body)
+                })
+                .collect::<Vec<_>>();
+
+            let mut body = Vec::new();
+            body.extend_from_slice(&seqnum.to_le_bytes());
+            body.extend_from_slice(&realtime.to_le_bytes());
+            body.extend_from_slice(&realtime.to_le_bytes());
+            body.extend_from_slice(&[7; 16]);
+            body.extend_from_slice(&0u64.to_le_bytes());
+            for offset in data {
+                body.extend_from_slice(&offset.to_le_bytes());
+                body.extend_from_slice(&0u64.to_le_bytes());
+            }
+            entry_offsets.push(object(&mut arena, object_type::ENTRY, &body));
+        }
+
+        let mut body = 0u64.to_le_bytes().to_vec();
+        for offset in &entry_offsets {
+            body.extend_from_slice(&offset.to_le_bytes());
+        }
+        // Room for entries yet to be written.
+        body.extend_from_slice(&[0; 16]);
+        let array = object(&mut arena, object_type::ENTRY_ARRAY, &body);
+
+        let mut header = vec![0; header_size as usize];
+        header[..8].copy_from_slice(SIGNATURE);
+        header[24..40].copy_from_slice(&[1; 16]);
+        header[72..88].copy_from_slice(&seqnum_id);
+        header[88..96].copy_from_slice(&header_size.to_le_bytes());
+        header[96..104].copy_from_slice(&(arena.len() as u64).to_le_bytes());
+        header[152..160].copy_from_slice(&(entries.len() as u64).to_le_bytes());
+        header[176..184].copy_from_slice(&array.to_le_bytes());
+
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&header).unwrap();
+        file.write_all(&arena).unwrap();
+        file
+    }
+
+    #[test]
+    fn reads_entries() {
+        let file = write_journal(
+            [2; 16],
+            &[
+                (
+                    1,
+                    1000,
+                    vec![("MESSAGE", &b"first"[..]), ("PRIORITY", &b"6"[..])],
+                ),
+                (2, 2000, vec![("MESSAGE", &b"\xc2\xbfbinary\xff"[..])]),
+            ],
+        );
+        let mut journal = JournalFile::open(file.path()).unwrap();
+
+        let first = journal.next_entry(true).unwrap().unwrap();
+        assert_eq!(first.seqnum, 1);
+        assert_eq!(first.realtime, 1000);
+        assert_eq!(
+            first.fields,
+            vec![
+                ("MESSAGE".into(), Bytes::from("first")),
+                ("PRIORITY".into(), Bytes::from("6"))
+            ]
+        );
+
+        let second = journal.next_entry(false).unwrap().unwrap();
+        assert_eq!(second.seqnum, 2);
+        assert!(second.fields.is_empty());
+
+        assert_eq!(journal.next_entry(true).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&[0; HEADER_SIZE]).unwrap();
+        assert!(matches!(
+            JournalFile::open(file.path()),
+            Err(JournalError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn rejects_xz_compressed_files() {
+        let mut header = [0; HEADER_SIZE];
+        header[..8].copy_from_slice(SIGNATURE);
+        // The `COMPRESSED_XZ` incompatible flag.
+        header[12] = 1;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(&header).unwrap();
+        assert!(matches!(
+            JournalFile::open(file.path()),
+            Err(JournalError::UnsupportedFeatures { flags: 1 })
+        ));
+    }
+
+    #[test]
+    fn decompresses_lz4() {
+        // "abcabcabcabc": the literals "abc" followed by a match of 9 bytes at offset 3.
+        let block = [0x35, b'a', b'b', b'c', 3, 0];
+        assert_eq!(
+            lz4_decompress(&block, 12).unwrap(),
+            b"abcabcabcabc".to_vec()
+        );
+        assert_eq!(lz4_decompress(&block, 11), None);
+        assert_eq!(lz4_decompress(&[0x10, b'a', 2, 0], 5), None);
+    }
+
+    #[test]
+    fn decompresses_zstd() {
+        let frame = zstd::encode_all(&[b'a'; 1000][..], 0).unwrap();
+        assert_eq!(zstd_decompress(&frame, 1000).unwrap(), vec![b'a'; 1000]);
+        assert_eq!(zstd_decompress(&frame, 999), None);
+        assert_eq!(zstd_decompress(b"not zstd", 1000), None);
+    }
+
+    #[test]
+    fn formats_and_parses_cursors() {
+        let entry = Entry {
+            seqnum: 0x1a,
+            realtime: 0x5d8e7e0f1d2c1,
+            monotonic: 0x2f,
+            boot_id: [0xab; 16],
+            xor_hash: 0xdead,
+            fields: Vec::new(),
+        };
+        let cursor = Cursor::new([0x01; 16], &entry);
+        let text = cursor.to_string();
+        assert_eq!(
+            text,
+            "s=01010101010101010101010101010101;i=1a;b=abababababababababababababababab;m=2f;t=5d8e7e0f1d2c1;x=dead"
+        );
+        assert_eq!(Cursor::parse(&text), Some(cursor.clone()));
+        assert_eq!(Cursor::parse("not a cursor"), None);
+
+        let later = Entry {
+            seqnum: 0x1b,
+            realtime: 0,
+            ..entry.clone()
+        };
+        assert!(cursor.is_before(&[0x01; 16], &later));
+        assert!(!cursor.is_before(&[0x02; 16], &later));
+        assert!(!cursor.is_before(&[0x01; 16], &entry));
+    }
+
+    #[test]
+    fn parses_ids() {
+        assert_eq!(
+            parse_id128("0a1b2c3d-4e5f-6071-8293-a4b5c6d7e8f9\n"),
+            Some([
+                0x0a, 0x1b, 0x2c, 0x3d, 0x4e, 0x5f, 0x60, 0x71, 0x82, 0x93, 0xa4, 0xb5, 0xc6, 0xd7,
+                0xe8, 0xf9
+            ])
+        );
+        assert_eq!(parse_id128("0a1b"), None);
+    }
+}
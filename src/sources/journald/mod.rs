@@ -29,6 +29,7 @@ use tokio::{
 use tokio_util::codec::FramedRead;
 use vector_core::ByteSizeOf;
 
+use self::reader::{start_reader, ReaderConfig};
 use crate::{
     codecs::{decoding::BoxedFramingError, CharacterDelimitedDecoder},
     config::{
@@ -42,6 +43,9 @@ use crate::{
     SourceSender,
 };
 
+mod file;
+mod reader;
+
 const DEFAULT_BATCH_SIZE: usize = 16;
 const BATCH_TIMEOUT: Duration = Duration::from_millis(10);
 
@@ -74,6 +78,8 @@ enum BuildError {
         value,
     ))]
     DuplicatedMatches { field: String, value: String },
+    #[snafu(display("`journalctl_path` cannot be used with the `native` reader"))]
+    JournalctlPathWithNativeReader,
 }
 
 #[derive(Deserialize, Serialize, Debug, Default)]
@@ -88,6 +94,7 @@ pub struct JournaldConfig {
     pub exclude_matches: HashMap<String, HashSet<String>>,
     pub data_dir: Option<PathBuf>,
     pub batch_size: Option<usize>,
+    pub reader: Option<JournalReader>,
     pub journalctl_path: Option<PathBuf>,
     pub journal_directory: Option<PathBuf>,
     #[serde(default, deserialize_with = "bool_or_struct")]
@@ -97,7 +104,28 @@ pub struct JournaldConfig {
     remap_priority: bool,
 }
 
+/// How the journal is read.
+#[derive(Clone, Copy, Debug, Derivative, Deserialize, PartialEq, Serialize)]
+#[derivative(Default)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalReader {
+    /// Read the journal files directly.
+    Native,
+    /// Read the output of a `journalctl` process.
+    #[derivative(Default)]
+    Journalctl,
+}
+
 impl JournaldConfig {
+    fn reader(&self) -> crate::Result<JournalReader> {
+        match (self.reader, &self.journalctl_path) {
+            (Some(JournalReader::Native), Some(_)) => {
+                Err(BuildError::JournalctlPathWithNativeReader.into())
+            }
+            (reader, _) => Ok(reader.unwrap_or_default()),
+        }
+    }
+
     fn merged_include_matches(&self) -> crate::Result<Matches> {
         let include_units = match (!self.units.is_empty(), !self.include_units.is_empty()) {
             (true, true) => return Err(BuildError::BothUnitsAndIncludeUnits.into()),
@@ -131,7 +159,7 @@ inventory::submit! {
 
 impl_generate_config_from_default!(JournaldConfig);
 
-type Record = HashMap<String, String>;
+type Record = HashMap<String, Bytes>;
 type Matches = HashMap<String, HashSet<String>>;
 
 #[async_trait::async_trait]
@@ -166,27 +194,38 @@ impl SourceConfig for JournaldConfig {
         let mut checkpoint_path = data_dir;
         checkpoint_path.push(CHECKPOINT_FILENAME);
 
-        let journalctl_path = self
-            .journalctl_path
-            .clone()
-            .unwrap_or_else(|| JOURNALCTL.clone());
-
         let batch_size = self.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
         let current_boot_only = self.current_boot_only.unwrap_or(true);
         let since_now = self.since_now.unwrap_or(false);
         let journal_dir = self.journal_directory.clone();
         let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
 
-        let start: StartJournalctlFn = Box::new(move |cursor| {
-            let mut command = create_command(
-                &journalctl_path,
-                journal_dir.as_ref(),
-                current_boot_only,
-                since_now,
-                cursor,
-            );
-            start_journalctl(&mut command)
-        });
+        let start: StartJournalFn = match self.reader()? {
+            JournalReader::Native => {
+                let config = ReaderConfig {
+                    journal_directory: journal_dir,
+                    current_boot_only,
+                    since_now,
+                };
+                Box::new(move |cursor| start_reader(&config, cursor))
+            }
+            JournalReader::Journalctl => {
+                let journalctl_path = self
+                    .journalctl_path
+                    .clone()
+                    .unwrap_or_else(|| JOURNALCTL.clone());
+                Box::new(move |cursor| {
+                    let mut command = create_command(
+                        &journalctl_path,
+                        journal_dir.as_ref(),
+                        current_boot_only,
+                        since_now,
+                        cursor,
+                    );
+                    start_journalctl(&mut command)
+                })
+            }
+        };
 
         Ok(Box::pin(
             JournaldSource {
@@ -229,7 +268,7 @@ impl JournaldSource {
     async fn run_shutdown(
         self,
         shutdown: ShutdownSignal,
-        start_journalctl: StartJournalctlFn,
+        start_journal: StartJournalFn,
     ) -> Result<(), ()> {
         let mut checkpointer = StatefulCheckpointer::new(self.checkpoint_path.clone())
             .await
@@ -242,7 +281,7 @@ impl JournaldSource {
             })?;
 
         let mut on_stop = None;
-        let run = Box::pin(self.run(&mut checkpointer, &mut on_stop, start_journalctl));
+        let run = Box::pin(self.run(&mut checkpointer, &mut on_stop, start_journal));
         future::select(run, shutdown).await;
 
         if let Some(stop) = on_stop {
@@ -257,12 +296,12 @@ impl JournaldSource {
     async fn run<'a>(
         mut self,
         checkpointer: &'a mut StatefulCheckpointer,
-        on_stop: &'a mut Option<StopJournalctlFn>,
-        start_journalctl: StartJournalctlFn,
+        on_stop: &'a mut Option<StopJournalFn>,
+        start_journal: StartJournalFn,
     ) {
         loop {
-            info!("Starting journalctl.");
-            match start_journalctl(&checkpointer.cursor) {
+            info!("Starting to read the journal.");
+            match start_journal(&checkpointer.cursor) {
                 Ok((stream, stop)) => {
                     *on_stop = Some(stop);
                     let should_restart = self.run_stream(stream, checkpointer).await;
@@ -274,21 +313,21 @@ impl JournaldSource {
                     }
                 }
                 Err(error) => {
-                    error!(message = "Error starting journalctl process.", %error);
+                    error!(message = "Error starting to read the journal.", %error);
                 }
             };
 
-            // journalctl process should never stop,
+            // Reading the journal should never stop,
             // so it is an error if we reach here.
             sleep(BACKOFF_DURATION).await;
         }
     }
 
-    /// Process `journalctl` output until some error occurs.
-    /// Return `true` if should restart `journalctl`.
+    /// Process journal entries until some error occurs.
+    /// Return `true` if should restart reading the journal.
     async fn run_stream<'a>(
         &'a mut self,
-        mut stream: BoxStream<'static, Result<Bytes, BoxedFramingError>>,
+        mut stream: BoxStream<'static, Result<JournalEntry, BoxedFramingError>>,
         checkpointer: &'a mut StatefulCheckpointer,
     ) -> bool {
         let batch_size = self.batch_size;
@@ -341,10 +380,10 @@ impl<'a> Batch<'a> {
         }
     }
 
-    fn handle_next(&mut self, result: Option<Result<Bytes, BoxedFramingError>>) -> bool {
+    fn handle_next(&mut self, result: Option<Result<JournalEntry, BoxedFramingError>>) -> bool {
         match result {
             None => {
-                warn!("Journalctl process stopped.");
+                warn!("Journal reader stopped.");
                 self.exiting = Some(true);
                 false
             }
@@ -355,29 +394,36 @@ impl<'a> Batch<'a> {
                 );
                 false
             }
-            Some(Ok(bytes)) => {
-                match decode_record(&bytes, self.source.remap_priority) {
-                    Ok(mut record) => {
-                        if let Some(tmp) = record.remove(&*CURSOR) {
-                            self.checkpointer.set(tmp);
+            Some(Ok(entry)) => {
+                let (mut record, size) = match entry {
+                    JournalEntry::Json(bytes) => match decode_record(&bytes) {
+                        Ok(record) => (record, bytes.len()),
+                        Err(error) => {
+                            emit!(JournaldInvalidRecordError {
+                                error,
+                                text: String::from_utf8_lossy(&bytes).into_owned()
+                            });
+                            return true;
                         }
+                    },
+                    JournalEntry::Fields { record, size } => (record, size),
+                };
 
-                        if !filter_matches(
-                            &record,
-                            &self.source.include_matches,
-                            &self.source.exclude_matches,
-                        ) {
-                            self.record_size += bytes.len();
-                            let event = create_event(record, &self.batch);
-                            self.events.push(event);
-                        }
-                    }
-                    Err(error) => {
-                        emit!(JournaldInvalidRecordError {
-                            error,
-                            text: String::from_utf8_lossy(&bytes).into_owned()
-                        });
-                    }
+                if self.source.remap_priority {
+                    record.get_mut("PRIORITY").map(remap_priority);
+                }
+                if let Some(tmp) = record.remove(&*CURSOR) {
+                    self.checkpointer.set(String::from_utf8_lossy(&tmp));
+                }
+
+                if !filter_matches(
+                    &record,
+                    &self.source.include_matches,
+                    &self.source.exclude_matches,
+                ) {
+                    self.record_size += size;
+                    let event = create_event(record, &self.batch);
+                    self.events.push(event);
                 }
                 true
             }
@@ -410,7 +456,7 @@ impl<'a> Batch<'a> {
                     }
                     Err(error) => {
                         error!(message = "Could not send journald log.", %error);
-                        // `out` channel is closed, don't restart journalctl.
+                        // `out` channel is closed, don't restart reading the journal.
                         self.exiting = Some(false);
                     }
                 }
@@ -424,28 +470,35 @@ impl<'a> Batch<'a> {
     }
 }
 
-/// A function that starts journalctl process.
-/// Return a stream of output split by '\n', and a `StopJournalctlFn`.
+/// An entry of the journal, either as a line of `journalctl --output=json` or as the
+/// fields read from the journal files along with their size.
+enum JournalEntry {
+    Json(Bytes),
+    Fields { record: Record, size: usize },
+}
+
+/// A function that starts reading the journal.
+/// Return a stream of journal entries, and a `StopJournalFn`.
 ///
-/// Code uses `start_journalctl` below,
+/// Code uses `start_reader` or `start_journalctl` below,
 /// but we need this type to implement fake journald source in testing.
-type StartJournalctlFn = Box<
+type StartJournalFn = Box<
     dyn Fn(
             &Option<String>, // cursor
         ) -> crate::Result<(
-            BoxStream<'static, Result<Bytes, BoxedFramingError>>,
-            StopJournalctlFn,
+            BoxStream<'static, Result<JournalEntry, BoxedFramingError>>,
+            StopJournalFn,
         )> + Send
         + Sync,
 >;
 
-type StopJournalctlFn = Box<dyn FnOnce() + Send>;
+type StopJournalFn = Box<dyn FnOnce() + Send>;
 
 fn start_journalctl(
     command: &mut Command,
 ) -> crate::Result<(
-    BoxStream<'static, Result<Bytes, BoxedFramingError>>,
-    StopJournalctlFn,
+    BoxStream<'static, Result<JournalEntry, BoxedFramingError>>,
+    StopJournalFn,
 )> {
     let mut child = command.spawn().context(JournalctlSpawnSnafu)?;
 
@@ -453,6 +506,7 @@ fn start_journalctl(
         child.stdout.take().unwrap(),
         CharacterDelimitedDecoder::new(b'\n'),
     )
+    .map(|line| line.map(JournalEntry::Json))
     .boxed();
 
     let pid = Pid::from_raw(child.id().unwrap() as _);
@@ -536,32 +590,35 @@ fn fixup_unit(unit: &str) -> String {
     }
 }
 
-fn decode_record(line: &[u8], remap: bool) -> Result<Record, JsonError> {
-    let mut record = serde_json::from_str::<JsonValue>(&String::from_utf8_lossy(line))?;
-    // journalctl will output non-ASCII values using an array
-    // of integers. Look for those values and re-parse them.
-    if let Some(record) = record.as_object_mut() {
-        for (_, value) in record.iter_mut().filter(|(_, v)| v.is_array()) {
-            *value = decode_array(value.as_array().expect("already validated"));
-        }
-    }
-    if remap {
-        record.get_mut("PRIORITY").map(remap_priority);
+fn decode_record(line: &[u8]) -> Result<Record, JsonError> {
+    let record =
+        serde_json::from_str::<HashMap<String, JsonValue>>(&String::from_utf8_lossy(line))?;
+    record
+        .into_iter()
+        .map(|(name, value)| decode_value(value).map(|value| (name, value)))
+        .collect()
+}
+
+fn decode_value(value: JsonValue) -> Result<Bytes, JsonError> {
+    match value {
+        JsonValue::String(text) => Ok(text.into()),
+        // journalctl will output non-ASCII values using an array
+        // of integers. Look for those values and keep their bytes.
+        JsonValue::Array(array) => Ok(decode_array(&array)),
+        value => serde_json::from_value::<String>(value).map(Into::into),
     }
-    serde_json::from_value(record)
 }
 
-fn decode_array(array: &[JsonValue]) -> JsonValue {
+fn decode_array(array: &[JsonValue]) -> Bytes {
     decode_array_as_bytes(array).unwrap_or_else(|| {
         let ser = serde_json::to_string(array).expect("already deserialized");
-        JsonValue::String(ser)
+        ser.into()
     })
 }
 
-fn decode_array_as_bytes(array: &[JsonValue]) -> Option<JsonValue> {
-    // From the array of values, turn all the numbers into bytes, and
-    // then the bytes into a string, but return None if any value in the
-    // array was not a valid byte.
+fn decode_array_as_bytes(array: &[JsonValue]) -> Option<Bytes> {
+    // From the array of values, turn all the numbers into bytes, but
+    // return None if any value in the array was not a valid byte.
     array
         .iter()
         .map(|item| {
@@ -571,11 +628,14 @@ fn decode_array_as_bytes(array: &[JsonValue]) -> Option<JsonValue> {
             })
         })
         .collect::<Option<Vec<u8>>>()
-        .map(|array| String::from_utf8_lossy(&array).into())
+        .map(Into::into)
 }
 
-fn remap_priority(priority: &mut JsonValue) {
-    if let Some(num) = priority.as_str().and_then(|s| usize::from_str(s).ok()) {
+fn remap_priority(priority: &mut Bytes) {
+    let num = std::str::from_utf8(priority)
+        .ok()
+        .and_then(|priority| usize::from_str(priority).ok());
+    if let Some(num) = num {
         let text = match num {
             0 => "EMERG",
             1 => "ALERT",
@@ -587,7 +647,7 @@ fn remap_priority(priority: &mut JsonValue) {
             7 => "DEBUG",
             _ => "UNKNOWN",
         };
-        *priority = JsonValue::String(text.into());
+        *priority = text.into();
     }
 }
 
@@ -601,10 +661,11 @@ fn filter_matches(record: &Record, includes: &Matches, excludes: &Matches) -> bo
 }
 
 fn contains_match(record: &Record, matches: &Matches) -> bool {
-    let f = move |(field, value)| {
+    let f = move |(field, value): (&String, &Bytes)| {
         matches
             .get(field)
-            .map(|x| x.contains(value))
+            .zip(std::str::from_utf8(value).ok())
+            .map(|(x, value)| x.contains(value))
             .unwrap_or(false)
     };
     record.iter().any(f)
@@ -774,13 +835,13 @@ mod tests {
     }
 
     impl FakeJournal {
-        fn next(&mut self) -> Option<Result<Bytes, BoxedFramingError>> {
+        fn next(&mut self) -> Option<Result<JournalEntry, BoxedFramingError>> {
             let mut line = String::new();
             match self.reader.read_line(&mut line) {
                 Ok(0) => None,
                 Ok(_) => {
                     line.pop();
-                    Some(Ok(Bytes::from(line)))
+                    Some(Ok(JournalEntry::Json(Bytes::from(line))))
                 }
                 Err(err) => Some(Err(err.into())),
             }
@@ -788,7 +849,7 @@ mod tests {
     }
 
     impl Stream for FakeJournal {
-        type Item = Result<Bytes, BoxedFramingError>;
+        type Item = Result<JournalEntry, BoxedFramingError>;
 
         fn poll_next(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Option<Self::Item>> {
             Poll::Ready(Pin::into_inner(self).next())
@@ -799,8 +860,8 @@ mod tests {
         fn new(
             checkpoint: &Option<String>,
         ) -> (
-            BoxStream<'static, Result<Bytes, BoxedFramingError>>,
-            StopJournalctlFn,
+            BoxStream<'static, Result<JournalEntry, BoxedFramingError>>,
+            StopJournalFn,
         ) {
             let cursor = Cursor::new(FAKE_JOURNAL);
             let reader = BufReader::new(cursor);
@@ -1008,6 +1069,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn keeps_binary_values() {
+        let record = decode_record(br#"{"MESSAGE":[255,98,105,110],"PRIORITY":"6"}"#).unwrap();
+        assert_eq!(record["MESSAGE"], &b"\xffbin"[..]);
+        assert_eq!(record["PRIORITY"], "6");
+    }
+
     #[tokio::test]
     async fn handles_missing_timestamp() {
         let received = run_with_units(&["stdout"], &[], None).await;
@@ -1066,13 +1134,13 @@ mod tests {
         assert!(!filter_matches(&zero, &empty, &excludes));
         assert!(filter_matches(&zero, &includes, &excludes));
         let mut one = HashMap::new();
-        one.insert(String::from(SYSTEMD_UNIT), String::from("one"));
+        one.insert(String::from(SYSTEMD_UNIT), Bytes::from("one"));
         assert!(!filter_matches(&one, &empty, &empty));
         assert!(!filter_matches(&one, &includes, &empty));
         assert!(!filter_matches(&one, &empty, &excludes));
         assert!(!filter_matches(&one, &includes, &excludes));
         let mut two = HashMap::new();
-        two.insert(String::from(SYSTEMD_UNIT), String::from("bar"));
+        two.insert(String::from(SYSTEMD_UNIT), Bytes::from("bar"));
         assert!(!filter_matches(&two, &empty, &empty));
         assert!(filter_matches(&two, &includes, &empty));
         assert!(filter_matches(&two, &empty, &excludes));
@@ -1123,6 +1191,25 @@ mod tests {
         assert_eq!(units, &hashset(&["DEBUG"]));
     }
 
+    #[test]
+    fn selects_reader() {
+        let config = JournaldConfig::default();
+        assert_eq!(config.reader().unwrap(), JournalReader::Journalctl);
+
+        let config = JournaldConfig {
+            reader: Some(JournalReader::Native),
+            ..Default::default()
+        };
+        assert_eq!(config.reader().unwrap(), JournalReader::Native);
+
+        let config = JournaldConfig {
+            reader: Some(JournalReader::Native),
+            journalctl_path: Some("/usr/local/bin/journalctl".into()),
+            ..Default::default()
+        };
+        assert!(config.reader().is_err());
+    }
+
     #[test]
     fn find_duplicate_match_works_correctly() {
         let include_matches = create_matches(vec![("_TRANSPORT", "kernel")]);
@@ -0,0 +1,518 @@
+//! Follows the journal files of a set of directories, the way `journalctl --follow` does:
+//! entries of all the files are read in the order they were written, and files created by
+//! rotation are picked up as they appear.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    os::unix::fs::MetadataExt,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use async_stream::stream;
+use bytes::Bytes;
+use futures::{stream::BoxStream, StreamExt};
+use notify::{raw_watcher, RawEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use super::{
+    file::{format_id128, parse_id128, Cursor, Entry, Id128, JournalFile},
+    JournalEntry, Record, StopJournalFn, CURSOR, RECEIVED_TIMESTAMP,
+};
+use crate::{codecs::decoding::BoxedFramingError, internal_events::JournaldReadError};
+
+/// How often the journal is read when its directories can't be watched.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// How long to wait for changes to the journal before reading it anyway, to notice that the
+/// source stopped and to pick up directories created since.
+const WAIT_TIMEOUT: Duration = Duration::from_secs(1);
+const CHANNEL_SIZE: usize = 1024;
+
+const DEFAULT_JOURNAL_DIRECTORIES: [&str; 2] = ["/var/log/journal", "/run/log/journal"];
+const MACHINE_ID_PATH: &str = "/etc/machine-id";
+const BOOT_ID_PATH: &str = "/proc/sys/kernel/random/boot_id";
+
+const MONOTONIC_TIMESTAMP: &str = "__MONOTONIC_TIMESTAMP";
+const BOOT_ID: &str = "_BOOT_ID";
+
+#[derive(Clone, Debug)]
+pub(super) struct ReaderConfig {
+    pub(super) journal_directory: Option<PathBuf>,
+    pub(super) current_boot_only: bool,
+    pub(super) since_now: bool,
+}
+
+/// Starts reading the journal after the given cursor, on a blocking thread.
+pub(super) fn start_reader(
+    config: &ReaderConfig,
+    cursor: &Option<String>,
+) -> crate::Result<(
+    BoxStream<'static, Result<JournalEntry, BoxedFramingError>>,
+    StopJournalFn,
+)> {
+    let start = match cursor
+        .as_deref()
+        .map(|cursor| (cursor, Cursor::parse(cursor)))
+    {
+        Some((_, Some(cursor))) => Start::After(cursor),
+        Some((cursor, None)) => {
+            warn!(message = "Invalid journal cursor, reading from the beginning.", %cursor);
+            Start::Beginning
+        }
+        None if config.since_now => Start::Now,
+        None => Start::Beginning,
+    };
+    let boot_id = if config.current_boot_only {
+        Some(current_boot_id()?)
+    } else {
+        None
+    };
+
+    let mut reader = Reader {
+        directories: directories(config.journal_directory.as_ref()),
+        boot_id,
+        start: Some(start),
+        files: Vec::new(),
+        failed: HashSet::new(),
+    };
+
+    let (sender, mut receiver) = mpsc::channel(CHANNEL_SIZE);
+    let stopped = Arc::new(AtomicBool::new(false));
+    let stop = {
+        let stopped = Arc::clone(&stopped);
+        Box::new(move || stopped.store(true, Ordering::Relaxed))
+    };
+    tokio::task::spawn_blocking(move || reader.run(&sender, &stopped));
+
+    let stream = stream! {
+        while let Some(entry) = receiver.recv().await {
+            yield Ok(entry);
+        }
+    }
+    .boxed();
+    Ok((stream, stop))
+}
+
+enum Start {
+    Beginning,
+    Now,
+    After(Cursor),
+}
+
+struct TrackedFile {
+    journal: JournalFile,
+    inode: u64,
+    /// The next entry of the file, once read.
+    head: Option<Entry>,
+    /// Set once the file has no more entries to read until the next poll.
+    exhausted: bool,
+}
+
+struct Reader {
+    directories: Vec<PathBuf>,
+    boot_id: Option<Id128>,
+    /// Where to start reading the files found on startup. Files found later were created
+    /// since, and are read from their first entry.
+    start: Option<Start>,
+    files: Vec<TrackedFile>,
+    /// Files that couldn't be opened, which aren't tried again.
+    failed: HashSet<u64>,
+}
+
+impl Reader {
+    fn run(&mut self, sender: &mpsc::Sender<JournalEntry>, stopped: &AtomicBool) {
+        let watcher = watch(&self.directories);
+        while !stopped.load(Ordering::Relaxed) {
+            if !self.poll(sender) {
+                break;
+            }
+            match &watcher {
+                Some((_, events)) => wait_for_changes(events),
+                None => thread::sleep(POLL_INTERVAL),
+            }
+        }
+    }
+
+    /// Sends the entries written since the last poll. Returns `false` if the source is gone.
+    fn poll(&mut self, sender: &mpsc::Sender<JournalEntry>) -> bool {
+        self.scan();
+        self.start = None;
+
+        for file in &mut self.files {
+            file.exhausted = false;
+            if !file.journal.is_archived() {
+                if let Err(error) = file.journal.refresh() {
+                    emit!(JournaldReadError {
+                        path: file.journal.path(),
+                        error,
+                    });
+                    file.exhausted = true;
+                }
+            }
+        }
+
+        loop {
+            for file in &mut self.files {
+                if file.head.is_none() && !file.exhausted {
+                    match file.journal.next_entry(true) {
+                        Ok(Some(entry)) => file.head = Some(entry),
+                        Ok(None) => file.exhausted = true,
+                        Err(error) => {
+                            emit!(JournaldReadError {
+                                path: file.journal.path(),
+                                error,
+                            });
+                            file.exhausted = true;
+                        }
+                    }
+                }
+            }
+
+            let next = self
+                .files
+                .iter_mut()
+                .filter(|file| file.head.is_some())
+                .min_by_key(|file| {
+                    let head = file.head.as_ref().unwrap();
+                    (head.realtime, head.seqnum)
+                });
+            let (seqnum_id, entry) = match next {
+                Some(file) => (*file.journal.seqnum_id(), file.head.take().unwrap()),
+                None => return true,
+            };
+
+            if self
+                .boot_id
+                .map_or(true, |boot_id| boot_id == entry.boot_id)
+            {
+                let (record, size) = entry_record(&seqnum_id, entry);
+                if sender
+                    .blocking_send(JournalEntry::Fields { record, size })
+                    .is_err()
+                {
+                    return false;
+                }
+            }
+        }
+    }
+
+    /// Opens the journal files not opened yet, and closes the ones that were removed.
+    fn scan(&mut self) {
+        let mut seen = HashSet::new();
+        for path in self.journal_paths() {
+            let inode = match fs::metadata(&path) {
+                Ok(metadata) => metadata.ino(),
+                Err(_) => continue,
+            };
+            seen.insert(inode);
+            if self.failed.contains(&inode) || self.files.iter().any(|file| file.inode == inode) {
+                continue;
+            }
+
+            let mut journal = match JournalFile::open(&path) {
+                Ok(journal) => journal,
+                Err(error) => {
+                    emit!(JournaldReadError { path: &path, error });
+                    self.failed.insert(inode);
+                    continue;
+                }
+            };
+            // A renamed file is found again under its new name.
+            if self
+                .files
+                .iter()
+                .any(|file| file.journal.file_id() == journal.file_id())
+            {
+                continue;
+            }
+
+            let seqnum_id = *journal.seqnum_id();
+            let positioned = match &self.start {
+                None | Some(Start::Beginning) => Ok(()),
+                Some(Start::Now) => journal.skip_while(|_| true),
+                Some(Start::After(cursor)) => {
+                    journal.skip_while(|entry| !cursor.is_before(&seqnum_id, entry))
+                }
+            };
+            if let Err(error) = positioned {
+                emit!(JournaldReadError { path: &path, error });
+                self.failed.insert(inode);
+                continue;
+            }
+
+            debug!(message = "Reading journal file.", ?path);
+            self.files.push(TrackedFile {
+                journal,
+                inode,
+                head: None,
+                exhausted: false,
+            });
+        }
+
+        self.files.retain(|file| seen.contains(&file.inode));
+        self.failed.retain(|inode| seen.contains(inode));
+    }
+
+    /// The journal files of the directories and of their subdirectories, which hold the
+    /// files of each machine.
+    fn journal_paths(&self) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        for directory in &self.directories {
+            for entry in read_dir(directory) {
+                if entry.is_dir() {
+                    paths.extend(read_dir(&entry).filter(|path| is_journal(path)));
+                } else if is_journal(&entry) {
+                    paths.push(entry);
+                }
+            }
+        }
+        paths
+    }
+}
+
+/// Watches the journal directories for changes, the way `sd_journal_wait` does. journald
+/// truncates a file to its size after writing to it, so that the writes made through its
+/// memory mapping are notified too. Returns `None` if none of the directories can be watched.
+fn watch(directories: &[PathBuf]) -> Option<(RecommendedWatcher, Receiver<RawEvent>)> {
+    let (sender, receiver) = channel();
+    let mut watcher = match raw_watcher(sender) {
+        Ok(watcher) => watcher,
+        Err(error) => {
+            warn!(message = "Unable to watch the journal, polling it instead.", %error);
+            return None;
+        }
+    };
+
+    let mut watched = false;
+    for directory in directories {
+        match watcher.watch(directory, RecursiveMode::Recursive) {
+            Ok(()) => watched = true,
+            Err(error) => {
+                debug!(message = "Unable to watch journal directory.", ?directory, %error)
+            }
+        }
+    }
+    if !watched {
+        warn!(
+            message = "Unable to watch the journal directories, polling them instead.",
+            ?directories
+        );
+    }
+    watched.then(|| (watcher, receiver))
+}
+
+fn wait_for_changes(events: &Receiver<RawEvent>) {
+    match events.recv_timeout(WAIT_TIMEOUT) {
+        // Changes come in bursts, which are all read at once.
+        Ok(_) => while events.try_recv().is_ok() {},
+        Err(RecvTimeoutError::Timeout) => {}
+        Err(RecvTimeoutError::Disconnected) => thread::sleep(POLL_INTERVAL),
+    }
+}
+
+fn read_dir(directory: &PathBuf) -> impl Iterator<Item = PathBuf> {
+    fs::read_dir(directory)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+}
+
+/// Active and archived files end with `.journal`, files that weren't closed properly are
+/// renamed to end with `.journal~`.
+fn is_journal(path: &PathBuf) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map_or(false, |name| {
+            name.ends_with(".journal") || name.ends_with(".journal~")
+        })
+}
+
+/// The directories holding the journal of the local machine, unless one is configured.
+fn directories(journal_directory: Option<&PathBuf>) -> Vec<PathBuf> {
+    if let Some(directory) = journal_directory {
+        return vec![directory.clone()];
+    }
+
+    let machine_id = fs::read_to_string(MACHINE_ID_PATH)
+        .ok()
+        .and_then(|id| parse_id128(&id));
+    DEFAULT_JOURNAL_DIRECTORIES
+        .iter()
+        .map(|directory| match &machine_id {
+            Some(machine_id) => PathBuf::from(directory).join(format_id128(machine_id)),
+            None => PathBuf::from(directory),
+        })
+        .collect()
+}
+
+fn current_boot_id() -> io::Result<Id128> {
+    let boot_id = fs::read_to_string(BOOT_ID_PATH)?;
+    parse_id128(&boot_id).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid boot id {:?}", boot_id.trim()),
+        )
+    })
+}
+
+/// Converts an entry to the record `journalctl` would output for it. Values are kept as
+/// raw bytes, and fields with several values are output as a JSON array where the values
+/// that are not valid UTF-8 are arrays of bytes.
+fn entry_record(seqnum_id: &Id128, entry: Entry) -> (Record, usize) {
+    let cursor = Cursor::new(*seqnum_id, &entry);
+    let mut size = 0;
+    let mut fields = BTreeMap::<String, Vec<Bytes>>::new();
+    for (name, value) in entry.fields {
+        size += name.len() + value.len() + 1;
+        fields.entry(name).or_default().push(value);
+    }
+
+    let mut record = fields
+        .into_iter()
+        .map(|(name, mut values)| {
+            let value = if values.len() == 1 {
+                values.pop().unwrap()
+            } else {
+                let values = values
+                    .into_iter()
+                    .map(|value| match String::from_utf8(value.to_vec()) {
+                        Ok(text) => serde_json::Value::from(text),
+                        Err(error) => serde_json::Value::from(error.into_bytes()),
+                    })
+                    .collect::<Vec<_>>();
+                serde_json::to_string(&values)
+                    .expect("Values are valid JSON")
+                    .into()
+            };
+            (name, value)
+        })
+        .collect::<Record>();
+
+    record.insert(CURSOR.into(), cursor.to_string().into());
+    record.insert(RECEIVED_TIMESTAMP.into(), entry.realtime.to_string().into());
+    record.insert(
+        MONOTONIC_TIMESTAMP.into(),
+        entry.monotonic.to_string().into(),
+    );
+    record.insert(BOOT_ID.into(), format_id128(&entry.boot_id).into());
+    (record, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use tempfile::tempdir;
+
+    use super::{super::file::tests::write_journal, *};
+
+    fn reader(directory: PathBuf, start: Start) -> Reader {
+        Reader {
+            directories: vec![directory],
+            boot_id: None,
+            start: Some(start),
+            files: Vec::new(),
+            failed: HashSet::new(),
+        }
+    }
+
+    fn messages(reader: &mut Reader) -> Vec<(String, String)> {
+        let (sender, mut receiver) = mpsc::channel(16);
+        assert!(reader.poll(&sender));
+        drop(sender);
+
+        let mut messages = Vec::new();
+        while let Some(JournalEntry::Fields { record, .. }) = receiver.blocking_recv() {
+            messages.push((
+                String::from_utf8_lossy(&record[CURSOR]).into_owned(),
+                String::from_utf8_lossy(&record["MESSAGE"]).into_owned(),
+            ));
+        }
+        messages
+    }
+
+    fn write_journals(directory: &std::path::Path) {
+        let system = write_journal(
+            [1; 16],
+            &[
+                (1, 100, vec![("MESSAGE", &b"one"[..])]),
+                (2, 300, vec![("MESSAGE", &b"three"[..])]),
+            ],
+        );
+        let user = write_journal([2; 16], &[(1, 200, vec![("MESSAGE", &b"two"[..])])]);
+        fs::copy(system.path(), directory.join("system.journal")).unwrap();
+        fs::copy(user.path(), directory.join("user-1000.journal")).unwrap();
+        File::create(directory.join("notes.txt")).unwrap();
+    }
+
+    #[test]
+    fn interleaves_files() {
+        let directory = tempdir().unwrap();
+        write_journals(directory.path());
+
+        let mut reader = reader(directory.path().into(), Start::Beginning);
+        let received = messages(&mut reader)
+            .into_iter()
+            .map(|(_, message)| message)
+            .collect::<Vec<_>>();
+        assert_eq!(received, vec!["one", "two", "three"]);
+        assert!(messages(&mut reader).is_empty());
+    }
+
+    #[test]
+    fn starts_after_cursor() {
+        let directory = tempdir().unwrap();
+        write_journals(directory.path());
+
+        let mut reader = reader(directory.path().into(), Start::Beginning);
+        let (cursor, _) = messages(&mut reader).remove(1);
+
+        let start = Start::After(Cursor::parse(&cursor).unwrap());
+        let mut reader = reader(directory.path().into(), start);
+        let received = messages(&mut reader)
+            .into_iter()
+            .map(|(_, message)| message)
+            .collect::<Vec<_>>();
+        assert_eq!(received, vec!["three"]);
+    }
+
+    #[test]
+    fn starts_now() {
+        let directory = tempdir().unwrap();
+        write_journals(directory.path());
+
+        let mut reader = reader(directory.path().into(), Start::Now);
+        assert!(messages(&mut reader).is_empty());
+    }
+
+    #[test]
+    fn converts_entries_to_records() {
+        let entry = Entry {
+            seqnum: 1,
+            realtime: 1578529839140001,
+            monotonic: 5,
+            boot_id: [0xab; 16],
+            xor_hash: 0,
+            fields: vec![
+                ("MESSAGE".into(), Bytes::from(&b"\xffbinary"[..])),
+                ("SYSLOG_FACILITY".into(), Bytes::from("DHCP4")),
+                ("SYSLOG_FACILITY".into(), Bytes::from("DHCP6")),
+            ],
+        };
+
+        let (record, size) = entry_record(&[1; 16], entry);
+        assert_eq!(size, 50);
+        assert_eq!(record["MESSAGE"], &b"\xffbinary"[..]);
+        assert_eq!(record["SYSLOG_FACILITY"], r#"["DHCP4","DHCP6"]"#);
+        assert_eq!(record[RECEIVED_TIMESTAMP], "1578529839140001");
+        assert_eq!(record[BOOT_ID], "abababababababababababababababab");
+        assert!(record[CURSOR].starts_with(b"s=01010101"));
+    }
+}
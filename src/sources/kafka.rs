@@ -2,19 +2,23 @@ use std::{
     collections::{BTreeMap, HashMap},
     io::Cursor,
     sync::Arc,
+    time::Duration,
 };
 
 use bytes::Bytes;
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, TimeZone, Utc};
 use futures::{FutureExt, Stream, StreamExt};
 use rdkafka::{
     config::ClientConfig,
     consumer::{Consumer, StreamConsumer},
     message::{BorrowedMessage, Headers, Message},
+    Offset, TopicPartitionList,
 };
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use tokio_util::codec::FramedRead;
+use tracing::Span;
 use vector_core::ByteSizeOf;
 
 use super::util::finalizer::OrderedFinalizer;
@@ -30,9 +34,9 @@ use crate::{
     event::{BatchNotifier, Event, Value},
     internal_events::{
         BytesReceived, KafkaEventsReceived, KafkaOffsetUpdateError, KafkaReadError,
-        StreamClosedError,
+        KafkaStartOffsetsError, StreamClosedError,
     },
-    kafka::{KafkaAuthConfig, KafkaStatisticsContext},
+    kafka::{register_group_consumer, KafkaAuthConfig, KafkaConsumerContext},
     serde::{bool_or_struct, default_decoding, default_framing_message_based},
     shutdown::ShutdownSignal,
     sources::util::StreamDecodingError,
@@ -46,6 +50,19 @@ enum BuildError {
     KafkaCreateError { source: rdkafka::error::KafkaError },
     #[snafu(display("Could not subscribe to Kafka topics: {}", source))]
     KafkaSubscribeError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Could not assign Kafka partitions: {}", source))]
+    KafkaAssignError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Could not find the offsets at `start_timestamp`: {}", source))]
+    KafkaStartOffsetsError { source: rdkafka::error::KafkaError },
+    #[snafu(display("Exactly one of `topics` and `partitions` must be set"))]
+    TopicsOrPartitions,
+    #[snafu(display("`group_id` is required to subscribe to `topics`"))]
+    MissingGroupId,
+    #[snafu(display("Invalid topic pattern {:?}: {}", pattern, source))]
+    InvalidTopicPattern {
+        pattern: String,
+        source: regex::Error,
+    },
 }
 
 #[derive(Clone, Debug, Derivative, Deserialize, Serialize)]
@@ -53,10 +70,22 @@ enum BuildError {
 #[serde(deny_unknown_fields)]
 pub struct KafkaSourceConfig {
     bootstrap_servers: String,
+    /// Topic names, or regular expressions when starting with `^`.
+    #[serde(default)]
     topics: Vec<String>,
-    group_id: String,
+    /// Explicit partitions to consume, instead of subscribing to `topics`.
+    #[serde(default)]
+    partitions: Vec<PartitionsConfig>,
+    /// Required to subscribe to `topics`. Without it, offsets aren't committed.
+    group_id: Option<String>,
     #[serde(default = "default_auto_offset_reset")]
     auto_offset_reset: String,
+    /// Where partitions without committed offsets start being consumed, instead of
+    /// `auto_offset_reset`.
+    start_timestamp: Option<DateTime<Utc>>,
+    #[serde(default = "default_topic_metadata_refresh_interval_ms")]
+    #[derivative(Default(value = "default_topic_metadata_refresh_interval_ms()"))]
+    topic_metadata_refresh_interval_ms: u64,
     #[serde(default = "default_session_timeout_ms")]
     session_timeout_ms: u64,
     #[serde(default = "default_socket_timeout_ms")]
@@ -65,6 +94,10 @@ pub struct KafkaSourceConfig {
     fetch_wait_max_ms: u64,
     #[serde(default = "default_commit_interval_ms")]
     commit_interval_ms: u64,
+    /// Disabled when a transactional `kafka` sink commits the offsets of the events instead.
+    #[serde(default = "crate::serde::default_true")]
+    #[derivative(Default(value = "true"))]
+    commit_offsets: bool,
     #[serde(default = "default_key_field")]
     key_field: String,
     #[serde(default = "default_topic_key")]
@@ -88,6 +121,13 @@ pub struct KafkaSourceConfig {
     acknowledgements: AcknowledgementsConfig,
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartitionsConfig {
+    topic: String,
+    partitions: Vec<i32>,
+}
+
 const fn default_session_timeout_ms() -> u64 {
     10000 // default in librdkafka
 }
@@ -104,6 +144,10 @@ const fn default_commit_interval_ms() -> u64 {
     5000 // default in librdkafka
 }
 
+const fn default_topic_metadata_refresh_interval_ms() -> u64 {
+    300000 // default in librdkafka
+}
+
 fn default_auto_offset_reset() -> String {
     "largest".into() // default in librdkafka
 }
@@ -138,12 +182,21 @@ impl_generate_config_from_default!(KafkaSourceConfig);
 #[typetag::serde(name = "kafka")]
 impl SourceConfig for KafkaSourceConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
-        let consumer = create_consumer(self)?;
+        // Finding the start offsets queries the brokers.
+        let config = self.clone();
+        let consumer = tokio::task::spawn_blocking(move || create_consumer(&config)).await??;
+        consumer.context().set_component_id(cx.key.id());
         let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
         let acknowledgements = cx.do_acknowledgements(&self.acknowledgements);
 
         Ok(Box::pin(kafka_source(
             consumer,
+            self.group_id.is_some(),
+            // The offsets are committed by the transactions of a `kafka` sink instead.
+            self.group_id.clone().filter(|_| !self.commit_offsets),
+            // The explicit partitions are assigned at their start offsets already.
+            self.start_timestamp.filter(|_| self.partitions.is_empty()),
+            Duration::from_millis(self.socket_timeout_ms),
             self.key_field.clone(),
             self.topic_key.clone(),
             self.partition_key.clone(),
@@ -170,7 +223,11 @@ impl SourceConfig for KafkaSourceConfig {
 }
 
 async fn kafka_source(
-    consumer: StreamConsumer<KafkaStatisticsContext>,
+    consumer: StreamConsumer<KafkaConsumerContext>,
+    store_offsets: bool,
+    transactional_group: Option<String>,
+    start_timestamp: Option<DateTime<Utc>>,
+    timeout: Duration,
     key_field: String,
     topic_key: String,
     partition_key: String,
@@ -181,9 +238,23 @@ async fn kafka_source(
     mut out: SourceSender,
     acknowledgements: bool,
 ) -> Result<(), ()> {
+    consumer.context().set_span(Span::current());
     let consumer = Arc::new(consumer);
+    if let Some(group_id) = &transactional_group {
+        register_group_consumer(group_id, &consumer);
+    }
+    if let Some(timestamp) = start_timestamp {
+        let weak = Arc::downgrade(&consumer);
+        consumer.context().set_on_assign(move |partitions| {
+            if let Some(consumer) = weak.upgrade() {
+                if let Err(error) = set_start_offsets(&consumer, partitions, timestamp, timeout) {
+                    emit!(KafkaStartOffsetsError { error });
+                }
+            }
+        });
+    }
     let shutdown = shutdown.shared();
-    let mut finalizer = acknowledgements
+    let mut finalizer = (acknowledgements && store_offsets)
         .then(|| OrderedFinalizer::new(shutdown.clone(), mark_done(Arc::clone(&consumer))));
     let mut stream = consumer.stream().take_until(shutdown);
     let schema = log_schema();
@@ -279,6 +350,12 @@ async fn kafka_source(
                 .boxed();
 
                 match &mut finalizer {
+                    // Without a group, the offsets aren't stored anywhere.
+                    None if !store_offsets => {
+                        if let Err(error) = out.send_event_stream(&mut stream).await {
+                            emit!(StreamClosedError { error, count });
+                        }
+                    }
                     Some(finalizer) => {
                         let (batch, receiver) = BatchNotifier::new_with_receiver();
                         let mut stream = stream.map(|event| event.with_batch_notifier(&batch));
@@ -331,7 +408,7 @@ impl<'a> From<BorrowedMessage<'a>> for FinalizerEntry {
     }
 }
 
-fn mark_done(consumer: Arc<StreamConsumer<KafkaStatisticsContext>>) -> impl Fn(FinalizerEntry) {
+fn mark_done(consumer: Arc<StreamConsumer<KafkaConsumerContext>>) -> impl Fn(FinalizerEntry) {
     move |entry| {
         if let Err(error) = consumer.store_offset(&entry.topic, entry.partition, entry.offset) {
             emit!(KafkaOffsetUpdateError { error });
@@ -341,23 +418,40 @@ fn mark_done(consumer: Arc<StreamConsumer<KafkaStatisticsContext>>) -> impl Fn(F
 
 fn create_consumer(
     config: &KafkaSourceConfig,
-) -> crate::Result<StreamConsumer<KafkaStatisticsContext>> {
+) -> crate::Result<StreamConsumer<KafkaConsumerContext>> {
+    if config.topics.is_empty() == config.partitions.is_empty() {
+        return Err(BuildError::TopicsOrPartitions.into());
+    }
+    if !config.topics.is_empty() && config.group_id.is_none() {
+        return Err(BuildError::MissingGroupId.into());
+    }
+    validate_topics(&config.topics)?;
+
     let mut client_config = ClientConfig::new();
+    if let Some(group_id) = &config.group_id {
+        client_config.set("group.id", group_id);
+    }
     client_config
-        .set("group.id", &config.group_id)
         .set("bootstrap.servers", &config.bootstrap_servers)
         .set("auto.offset.reset", &config.auto_offset_reset)
         .set("session.timeout.ms", &config.session_timeout_ms.to_string())
         .set("socket.timeout.ms", &config.socket_timeout_ms.to_string())
         .set("fetch.wait.max.ms", &config.fetch_wait_max_ms.to_string())
         .set("enable.partition.eof", "false")
-        .set("enable.auto.commit", "true")
+        .set(
+            "enable.auto.commit",
+            &(config.commit_offsets && config.group_id.is_some()).to_string(),
+        )
         .set(
             "auto.commit.interval.ms",
             &config.commit_interval_ms.to_string(),
         )
         .set("enable.auto.offset.store", "false")
         .set("statistics.interval.ms", "1000")
+        .set(
+            "topic.metadata.refresh.interval.ms",
+            &config.topic_metadata_refresh_interval_ms.to_string(),
+        )
         .set("client.id", "vector");
 
     config.auth.apply(&mut client_config)?;
@@ -369,14 +463,143 @@ fn create_consumer(
     }
 
     let consumer = client_config
-        .create_with_context::<_, StreamConsumer<_>>(KafkaStatisticsContext)
+        .create_with_context::<_, StreamConsumer<_>>(KafkaConsumerContext::default())
         .context(KafkaCreateSnafu)?;
-    let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
-    consumer.subscribe(&topics).context(KafkaSubscribeSnafu)?;
+
+    if config.partitions.is_empty() {
+        let topics: Vec<&str> = config.topics.iter().map(|s| s.as_str()).collect();
+        consumer.subscribe(&topics).context(KafkaSubscribeSnafu)?;
+    } else {
+        let timeout = Duration::from_millis(config.socket_timeout_ms);
+        let assignment = partitions_assignment(&consumer, config, timeout)?;
+        consumer.assign(&assignment).context(KafkaAssignSnafu)?;
+    }
 
     Ok(consumer)
 }
 
+/// Validates the topics, which librdkafka treats as regular expressions when they start with `^`.
+fn validate_topics(topics: &[String]) -> crate::Result<()> {
+    for topic in topics.iter().filter(|topic| topic.starts_with('^')) {
+        Regex::new(topic).context(InvalidTopicPatternSnafu { pattern: topic })?;
+    }
+    Ok(())
+}
+
+/// The offsets at the timestamp of the partitions the group has no committed offsets for.
+fn start_offsets(
+    consumer: &StreamConsumer<KafkaConsumerContext>,
+    partitions: TopicPartitionList,
+    timestamp: DateTime<Utc>,
+    timeout: Duration,
+) -> Result<TopicPartitionList, rdkafka::error::KafkaError> {
+    if partitions.count() == 0 {
+        return Ok(partitions);
+    }
+
+    let committed = consumer.committed_offsets(partitions, timeout)?;
+    let mut timestamps = TopicPartitionList::new();
+    for elem in committed.elements() {
+        if elem.offset() == Offset::Invalid {
+            timestamps.add_partition_offset(
+                elem.topic(),
+                elem.partition(),
+                Offset::Offset(timestamp.timestamp_millis()),
+            )?;
+        }
+    }
+    if timestamps.count() == 0 {
+        return Ok(timestamps);
+    }
+
+    offsets_at_times(consumer, timestamps, timeout)
+}
+
+/// Starts the partitions assigned by the group without committed offsets at the offsets at the
+/// timestamp. This runs on every assignment, before the partitions are assigned, so partitions
+/// created later start there too.
+fn set_start_offsets(
+    consumer: &StreamConsumer<KafkaConsumerContext>,
+    partitions: &mut TopicPartitionList,
+    timestamp: DateTime<Utc>,
+    timeout: Duration,
+) -> Result<(), rdkafka::error::KafkaError> {
+    let offsets = start_offsets(consumer, partitions.clone(), timestamp, timeout)?;
+    for elem in offsets.elements() {
+        partitions.set_partition_offset(elem.topic(), elem.partition(), elem.offset())?;
+    }
+    Ok(())
+}
+
+/// The configured partitions, starting at their committed offsets for a group, or at the
+/// timestamp or `auto_offset_reset` otherwise.
+fn partitions_assignment(
+    consumer: &StreamConsumer<KafkaConsumerContext>,
+    config: &KafkaSourceConfig,
+    timeout: Duration,
+) -> crate::Result<TopicPartitionList> {
+    let start = match config.auto_offset_reset.as_str() {
+        _ if config.group_id.is_some() => Offset::Stored,
+        "smallest" | "earliest" | "beginning" => Offset::Beginning,
+        _ => Offset::End,
+    };
+
+    let mut assignment = TopicPartitionList::new();
+    for topic in &config.partitions {
+        for &partition in &topic.partitions {
+            let offset = match config.start_timestamp {
+                Some(timestamp) if config.group_id.is_none() => {
+                    Offset::Offset(timestamp.timestamp_millis())
+                }
+                _ => start,
+            };
+            assignment
+                .add_partition_offset(&topic.topic, partition, offset)
+                .context(KafkaAssignSnafu)?;
+        }
+    }
+
+    match config.start_timestamp {
+        Some(timestamp) if config.group_id.is_some() => {
+            let offsets = start_offsets(consumer, assignment.clone(), timestamp, timeout)
+                .context(KafkaStartOffsetsSnafu)?;
+            for elem in offsets.elements() {
+                assignment
+                    .set_partition_offset(elem.topic(), elem.partition(), elem.offset())
+                    .context(KafkaAssignSnafu)?;
+            }
+            Ok(assignment)
+        }
+        Some(_) => {
+            Ok(offsets_at_times(consumer, assignment, timeout).context(KafkaStartOffsetsSnafu)?)
+        }
+        None => Ok(assignment),
+    }
+}
+
+/// Finds the offsets of the first messages at or after the timestamps set as offsets of the
+/// partitions, or the ends of the partitions when they have none.
+fn offsets_at_times(
+    consumer: &StreamConsumer<KafkaConsumerContext>,
+    timestamps: TopicPartitionList,
+    timeout: Duration,
+) -> Result<TopicPartitionList, rdkafka::error::KafkaError> {
+    let found = consumer.offsets_for_times(timestamps, timeout)?;
+    let mut offsets = TopicPartitionList::new();
+    for elem in found.elements() {
+        let offset = match elem.offset() {
+            Offset::Offset(offset) => offset,
+            _ => {
+                consumer
+                    .fetch_watermarks(elem.topic(), elem.partition(), timeout)?
+                    .1
+            }
+        };
+        offsets.add_partition_offset(elem.topic(), elem.partition(), Offset::Offset(offset))?;
+    }
+    Ok(offsets)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -398,7 +621,7 @@ mod test {
         KafkaSourceConfig {
             bootstrap_servers: kafka_address(9091),
             topics: vec![topic.into()],
-            group_id: group.into(),
+            group_id: Some(group.into()),
             auto_offset_reset: "beginning".into(),
             session_timeout_ms: 6000,
             commit_interval_ms: 5000,
@@ -419,6 +642,43 @@ mod test {
         assert!(create_consumer(&config).is_ok());
     }
 
+    #[tokio::test]
+    async fn consumer_create_requires_topics_or_partitions() {
+        let config = KafkaSourceConfig {
+            topics: vec![],
+            ..make_config("topic", "group")
+        };
+        assert!(create_consumer(&config).is_err());
+
+        let config = KafkaSourceConfig {
+            partitions: vec![PartitionsConfig {
+                topic: "topic".into(),
+                partitions: vec![0],
+            }],
+            ..make_config("topic", "group")
+        };
+        assert!(create_consumer(&config).is_err());
+
+        let config = KafkaSourceConfig {
+            group_id: None,
+            ..make_config("topic", "group")
+        };
+        assert!(create_consumer(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn consumer_create_incorrect_topic_pattern() {
+        let config = make_config("^topic-(", "group");
+        assert!(create_consumer(&config).is_err());
+    }
+
+    #[test]
+    fn validates_topic_patterns() {
+        assert!(validate_topics(&["logs".into(), "^metrics-.+".into()]).is_ok());
+        assert!(validate_topics(&["logs-(".into()]).is_ok());
+        assert!(validate_topics(&["^logs-(".into()]).is_err());
+    }
+
     #[tokio::test]
     async fn consumer_create_incorrect_auto_offset_reset() {
         let config = KafkaSourceConfig {
@@ -521,6 +781,10 @@ mod integration_test {
         let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Delivered);
         tokio::spawn(kafka_source(
             create_consumer(&config).unwrap(),
+            true,
+            None,
+            None,
+            Duration::from_secs(10),
             config.key_field,
             config.topic_key,
             config.partition_key,
@@ -0,0 +1,282 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use chrono::Utc;
+use flowmsg_parser::{
+    template::{DEFAULT_MAX_TEMPLATES, DEFAULT_TEMPLATE_TIMEOUT},
+    FlowParser,
+};
+use futures::TryFutureExt;
+use serde::{Deserialize, Serialize};
+use vector_core::ByteSizeOf;
+
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Output, Resource, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::Event,
+    internal_events::{
+        EventsReceived, NetflowMissingTemplate, NetflowParseError, NetflowSocketError,
+        StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+    sources::util::UdpReceiver,
+    SourceSender,
+};
+
+mod parser;
+
+/// The largest datagram that can be received over UDP.
+const MAX_DATAGRAM_LENGTH: usize = 65535;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NetflowConfig {
+    address: SocketAddr,
+    receive_buffer_bytes: Option<usize>,
+    host_key: Option<String>,
+    /// The most NetFlow v9 and IPFIX templates cached, across all the exporters.
+    #[serde(default = "default_max_templates")]
+    max_templates: usize,
+    /// How long the templates which aren't received again are cached.
+    #[serde(default = "default_template_timeout_secs")]
+    template_timeout_secs: u64,
+}
+
+const fn default_max_templates() -> usize {
+    DEFAULT_MAX_TEMPLATES
+}
+
+const fn default_template_timeout_secs() -> u64 {
+    DEFAULT_TEMPLATE_TIMEOUT.as_secs()
+}
+
+inventory::submit! {
+    SourceDescription::new::<NetflowConfig>("netflow")
+}
+
+impl GenerateConfig for NetflowConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            address: SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 2055)),
+            receive_buffer_bytes: None,
+            host_key: None,
+            max_templates: default_max_templates(),
+            template_timeout_secs: default_template_timeout_secs(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "netflow")]
+impl SourceConfig for NetflowConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().to_string());
+        let flow_parser = FlowParser::with_template_limits(
+            self.max_templates,
+            Duration::from_secs(self.template_timeout_secs),
+        );
+
+        Ok(Box::pin(netflow_udp(
+            self.address,
+            self.receive_buffer_bytes,
+            host_key,
+            flow_parser,
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log | DataType::Metric)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "netflow"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::udp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// Decodes a packet into events, tagging them with their exporter.
+fn decode(
+    flow_parser: &mut FlowParser,
+    peer: SocketAddr,
+    data: &[u8],
+    host_key: &str,
+) -> Option<Vec<Event>> {
+    let message = match flow_parser.parse(peer, data) {
+        Ok(message) => message,
+        Err(error) => {
+            emit!(NetflowParseError { error, peer });
+            return None;
+        }
+    };
+
+    for &(template_id, byte_size) in parser::missing_templates(&message) {
+        emit!(NetflowMissingTemplate {
+            template_id,
+            byte_size,
+            peer,
+        });
+    }
+
+    let host = peer.ip().to_string();
+    let mut events = parser::convert(message, Utc::now());
+    for event in &mut events {
+        match event {
+            Event::Log(log) => {
+                log.try_insert(log_schema().source_type_key(), Bytes::from("netflow"));
+                log.try_insert(host_key, host.clone());
+            }
+            Event::Metric(metric) => {
+                metric.insert_tag("host".to_string(), host.clone());
+            }
+            Event::Trace(_) => {}
+        }
+    }
+    Some(events)
+}
+
+async fn netflow_udp(
+    address: SocketAddr,
+    receive_buffer_bytes: Option<usize>,
+    host_key: String,
+    mut flow_parser: FlowParser,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let mut receiver = UdpReceiver::bind(address, MAX_DATAGRAM_LENGTH, receive_buffer_bytes)
+        .map_err(|error| emit!(NetflowSocketError::bind(error)))
+        .await?;
+
+    while let Some(recv) = receiver.recv(&mut shutdown).await {
+        let (datagram, peer) = match recv {
+            Ok(recv) => recv,
+            Err(error) => {
+                emit!(NetflowSocketError::read(error));
+                continue;
+            }
+        };
+
+        let events = match decode(&mut flow_parser, peer, &datagram, &host_key) {
+            Some(events) if !events.is_empty() => events,
+            _ => continue,
+        };
+
+        let count = events.len();
+        emit!(EventsReceived {
+            count,
+            byte_size: events.size_of(),
+        });
+
+        tokio::select! {
+            result = out.send_batch(events) => if let Err(error) = result {
+                emit!(StreamClosedError { error, count });
+                return Err(());
+            },
+            _ = &mut shutdown => break,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use futures::StreamExt;
+    use tokio::time::{timeout, Duration};
+
+    use super::*;
+    use crate::test_util::next_addr;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<NetflowConfig>();
+    }
+
+    fn netflow_v5_packet() -> Vec<u8> {
+        let mut data = vec![0, 5, 0, 1];
+        data.extend_from_slice(&1000u32.to_be_bytes());
+        data.extend_from_slice(&1_600_000_000u32.to_be_bytes());
+        data.extend_from_slice(&0u32.to_be_bytes());
+        data.extend_from_slice(&7u32.to_be_bytes());
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        // The addresses, interfaces, counters, times and ports of the record.
+        data.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2, 0, 0, 0, 0]);
+        data.extend_from_slice(&[0, 1, 0, 2]);
+        data.extend_from_slice(&3u32.to_be_bytes());
+        data.extend_from_slice(&180u32.to_be_bytes());
+        data.extend_from_slice(&500u32.to_be_bytes());
+        data.extend_from_slice(&900u32.to_be_bytes());
+        data.extend_from_slice(&[0x9c, 0x40, 0, 53]);
+        data.extend_from_slice(&[0, 0, 17, 0, 0, 0, 0, 0, 24, 24, 0, 0]);
+        data
+    }
+
+    #[test]
+    fn decodes_netflow_v5() {
+        let peer = "192.0.2.1:2055".parse().unwrap();
+        let mut flow_parser = FlowParser::new();
+        let events = decode(&mut flow_parser, peer, &netflow_v5_packet(), "host").unwrap();
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log["flow_type"], "netflow_v5".into());
+        assert_eq!(log["sequence_number"], 7.into());
+        assert_eq!(log["sourceIPv4Address"], "10.0.0.1".into());
+        assert_eq!(log["destinationTransportPort"], 53.into());
+        assert_eq!(log["octetDeltaCount"], 180.into());
+        assert_eq!(log["host"], "192.0.2.1".into());
+        assert_eq!(log[log_schema().source_type_key()], "netflow".into());
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        let peer = "192.0.2.1:2055".parse().unwrap();
+        let mut flow_parser = FlowParser::new();
+        assert!(decode(&mut flow_parser, peer, &[0, 4, 0, 0], "host").is_none());
+    }
+
+    #[tokio::test]
+    async fn receives_udp_packets() {
+        let address = next_addr();
+        let (tx, mut rx) = SourceSender::new_test();
+        let config = NetflowConfig {
+            address,
+            receive_buffer_bytes: None,
+            host_key: None,
+            max_templates: default_max_templates(),
+            template_timeout_secs: default_template_timeout_secs(),
+        };
+        let source = config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        socket.send_to(&netflow_v5_packet(), address).await.unwrap();
+
+        let event = timeout(Duration::from_secs(5), rx.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.as_log()["host"], "127.0.0.1".into());
+        assert_eq!(event.as_log()["flow_type"], "netflow_v5".into());
+    }
+}
@@ -0,0 +1,593 @@
+use std::{collections::BTreeMap, net::IpAddr};
+
+use chrono::{DateTime, TimeZone, Utc};
+use flowmsg_parser::{
+    fields::FieldValue,
+    netflow_v5::NetflowV5Packet,
+    sflow::{
+        CounterRecord, CountersSample, EthernetCounters, FlowRecord as SflowRecord, FlowSample,
+        InterfaceCounters, PacketHeader, ProcessorCounters, Sample, SampledIp, SflowDatagram,
+        SourceId,
+    },
+    template::{FlowRecord, TemplatePacket},
+    FlowMessage,
+};
+use ordered_float::NotNan;
+
+use crate::{
+    config::log_schema,
+    event::{
+        metric::{Metric, MetricKind, MetricTags, MetricValue},
+        Event, LogEvent, Value,
+    },
+};
+
+/// The namespace of the metrics converted from the counter samples of sFlow.
+const SFLOW_NAMESPACE: &str = "sflow";
+
+/// The data sets of a packet which couldn't be decoded because their template
+/// wasn't received yet, along with the length of their records.
+pub(super) fn missing_templates(message: &FlowMessage) -> &[(u16, usize)] {
+    match message {
+        FlowMessage::NetflowV9(packet) | FlowMessage::Ipfix(packet) => &packet.missing_templates,
+        FlowMessage::NetflowV5(_) | FlowMessage::Sflow(_) => &[],
+    }
+}
+
+/// Converts the flow records to log events, and the counters of sFlow to
+/// metrics.
+pub(super) fn convert(message: FlowMessage, now: DateTime<Utc>) -> Vec<Event> {
+    match message {
+        FlowMessage::NetflowV5(packet) => convert_netflow_v5(packet),
+        FlowMessage::NetflowV9(packet) => convert_template_packet(packet, "netflow_v9"),
+        FlowMessage::Ipfix(packet) => convert_template_packet(packet, "ipfix"),
+        FlowMessage::Sflow(datagram) => convert_sflow(datagram, now),
+    }
+}
+
+fn convert_netflow_v5(packet: NetflowV5Packet) -> Vec<Event> {
+    let timestamp = Utc.timestamp(packet.unix_secs as i64, packet.unix_nsecs);
+    packet
+        .records
+        .into_iter()
+        .map(|record| {
+            let mut log = record_log(record);
+            log.insert("flow_type", "netflow_v5");
+            log.insert("sequence_number", packet.flow_sequence);
+            log.insert("sys_uptime", packet.sys_uptime);
+            log.insert("engine_type", packet.engine_type);
+            log.insert("engine_id", packet.engine_id);
+            log.insert("sampling_mode", packet.sampling_mode);
+            log.insert("sampling_interval", packet.sampling_interval);
+            log.insert(log_schema().timestamp_key(), timestamp);
+            log.into()
+        })
+        .collect()
+}
+
+fn convert_template_packet(packet: TemplatePacket, flow_type: &'static str) -> Vec<Event> {
+    let timestamp = Utc.timestamp(packet.export_time as i64, 0);
+    let domain_key = match flow_type {
+        "ipfix" => "observation_domain_id",
+        _ => "source_id",
+    };
+    packet
+        .records
+        .into_iter()
+        .map(|record| {
+            let template_id = record.template_id;
+            let record_type = if record.options { "options" } else { "data" };
+            let mut log = record_log(record);
+            log.insert("flow_type", flow_type);
+            log.insert("record_type", record_type);
+            log.insert("template_id", template_id);
+            log.insert("sequence_number", packet.sequence_number);
+            log.insert(domain_key, packet.domain_id);
+            if let Some(sys_uptime) = packet.sys_uptime {
+                log.insert("sys_uptime", sys_uptime);
+            }
+            log.insert(log_schema().timestamp_key(), timestamp);
+            log.into()
+        })
+        .collect()
+}
+
+/// The fields of a record keep the names of the IPFIX information elements,
+/// like `sourceIPv4Address`.
+fn record_log(record: FlowRecord) -> LogEvent {
+    let mut log = LogEvent::default();
+    for field in record.fields {
+        log.insert(field.name.as_ref(), field_value(field.value));
+    }
+    log
+}
+
+fn field_value(value: FieldValue) -> Value {
+    match value {
+        FieldValue::Unsigned(value) => unsigned(value),
+        FieldValue::Signed(value) => Value::Integer(value),
+        FieldValue::Float(value) => NotNan::new(value).map_or(Value::Null, Value::Float),
+        FieldValue::Boolean(value) => Value::Boolean(value),
+        FieldValue::MacAddress(mac) => mac_address(&mac).into(),
+        FieldValue::Ipv4Address(address) => address.to_string().into(),
+        FieldValue::Ipv6Address(address) => address.to_string().into(),
+        FieldValue::String(value) => value.into(),
+        FieldValue::Bytes(bytes) => hex::encode(bytes).into(),
+        FieldValue::DateTime { seconds, nanos } => match i64::try_from(seconds) {
+            Ok(seconds) => Value::Timestamp(Utc.timestamp(seconds, nanos)),
+            Err(_) => Value::Null,
+        },
+    }
+}
+
+/// Counters may overflow the integers of events, and are then converted to
+/// floats.
+fn unsigned(value: u64) -> Value {
+    match i64::try_from(value) {
+        Ok(value) => Value::Integer(value),
+        Err(_) => Value::Float(NotNan::new(value as f64).expect("not NaN")),
+    }
+}
+
+fn mac_address(mac: &[u8; 6]) -> String {
+    mac.iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn convert_sflow(datagram: SflowDatagram, now: DateTime<Utc>) -> Vec<Event> {
+    let mut events = Vec::new();
+    for sample in &datagram.samples {
+        match sample {
+            Sample::Flow(sample) => events.push(flow_sample_log(&datagram, sample, now).into()),
+            Sample::Counters(sample) => {
+                counters_sample_metrics(&datagram, sample, now, &mut events)
+            }
+            Sample::Unknown { .. } => {}
+        }
+    }
+    events
+}
+
+fn flow_sample_log(datagram: &SflowDatagram, sample: &FlowSample, now: DateTime<Utc>) -> LogEvent {
+    let mut log = LogEvent::default();
+    log.insert("flow_type", "sflow");
+    log.insert("agent_address", datagram.agent_address.to_string());
+    log.insert("sub_agent_id", datagram.sub_agent_id);
+    log.insert("sequence_number", datagram.sequence_number);
+    log.insert("uptime", datagram.uptime);
+    log.insert("sample_sequence_number", sample.sequence_number);
+    log.insert("source_id_type", sample.source_id.source_type);
+    log.insert("source_id_index", sample.source_id.index);
+    log.insert("sampling_rate", sample.sampling_rate);
+    log.insert("sample_pool", sample.sample_pool);
+    log.insert("drops", sample.drops);
+    log.insert("input_interface", sample.input);
+    log.insert("output_interface", sample.output);
+
+    for record in &sample.records {
+        match record {
+            SflowRecord::RawPacketHeader(header) => {
+                log.insert("header_protocol", header.header_protocol);
+                log.insert("frame_length", header.frame_length);
+                log.insert("stripped", header.stripped);
+                if let Some(decoded) = &header.decoded {
+                    insert_packet_header(&mut log, decoded);
+                }
+            }
+            SflowRecord::SampledEthernet {
+                length,
+                source_mac,
+                destination_mac,
+                ethernet_type,
+            } => {
+                log.insert("frame_length", *length);
+                log.insert("source_mac", mac_address(source_mac));
+                log.insert("destination_mac", mac_address(destination_mac));
+                log.insert("ethernet_type", *ethernet_type);
+            }
+            SflowRecord::SampledIpv4(sampled) | SflowRecord::SampledIpv6(sampled) => {
+                insert_sampled_ip(&mut log, sampled);
+            }
+            SflowRecord::ExtendedSwitch {
+                source_vlan,
+                source_priority,
+                destination_vlan,
+                destination_priority,
+            } => {
+                log.insert("source_vlan", *source_vlan);
+                log.insert("source_priority", *source_priority);
+                log.insert("destination_vlan", *destination_vlan);
+                log.insert("destination_priority", *destination_priority);
+            }
+            SflowRecord::ExtendedRouter {
+                next_hop,
+                source_mask_length,
+                destination_mask_length,
+            } => {
+                log.insert("next_hop", next_hop.to_string());
+                log.insert("source_mask_length", *source_mask_length);
+                log.insert("destination_mask_length", *destination_mask_length);
+            }
+            SflowRecord::Unknown { .. } => {}
+        }
+    }
+
+    log.insert(log_schema().timestamp_key(), now);
+    log
+}
+
+fn insert_packet_header(log: &mut LogEvent, header: &PacketHeader) {
+    if let Some(mac) = &header.source_mac {
+        log.insert("source_mac", mac_address(mac));
+    }
+    if let Some(mac) = &header.destination_mac {
+        log.insert("destination_mac", mac_address(mac));
+    }
+    if let Some(ethernet_type) = header.ethernet_type {
+        log.insert("ethernet_type", ethernet_type);
+    }
+    if let Some(vlan_id) = header.vlan_id {
+        log.insert("vlan_id", vlan_id);
+    }
+    if let Some(ip) = header.source_ip {
+        log.insert("source_ip", ip.to_string());
+    }
+    if let Some(ip) = header.destination_ip {
+        log.insert("destination_ip", ip.to_string());
+    }
+    if let Some(protocol) = header.ip_protocol {
+        log.insert("ip_protocol", protocol);
+    }
+    if let Some(tos) = header.ip_tos {
+        log.insert("ip_tos", tos);
+    }
+    if let Some(ttl) = header.ip_ttl {
+        log.insert("ip_ttl", ttl);
+    }
+    if let Some(port) = header.source_port {
+        log.insert("source_port", port);
+    }
+    if let Some(port) = header.destination_port {
+        log.insert("destination_port", port);
+    }
+    if let Some(flags) = header.tcp_flags {
+        log.insert("tcp_flags", flags);
+    }
+}
+
+fn insert_sampled_ip(log: &mut LogEvent, sampled: &SampledIp) {
+    log.insert("ip_length", sampled.length);
+    log.insert("ip_protocol", sampled.protocol);
+    log.insert("source_ip", sampled.source_ip.to_string());
+    log.insert("destination_ip", sampled.destination_ip.to_string());
+    log.insert("source_port", sampled.source_port);
+    log.insert("destination_port", sampled.destination_port);
+    log.insert("tcp_flags", sampled.tcp_flags);
+    log.insert("ip_tos", sampled.tos);
+}
+
+fn counters_sample_metrics(
+    datagram: &SflowDatagram,
+    sample: &CountersSample,
+    now: DateTime<Utc>,
+    events: &mut Vec<Event>,
+) {
+    let tags = sample_tags(
+        datagram.agent_address,
+        datagram.sub_agent_id,
+        sample.source_id,
+    );
+    let mut builder = MetricsBuilder { tags, now, events };
+    for record in &sample.records {
+        match record {
+            CounterRecord::GenericInterface(counters) => builder.interface(counters),
+            CounterRecord::Ethernet(counters) => builder.ethernet(counters),
+            CounterRecord::Processor(counters) => builder.processor(counters),
+            CounterRecord::Unknown { .. } => {}
+        }
+    }
+}
+
+fn sample_tags(agent_address: IpAddr, sub_agent_id: u32, source_id: SourceId) -> MetricTags {
+    let mut tags = BTreeMap::new();
+    tags.insert("agent_address".to_string(), agent_address.to_string());
+    tags.insert("sub_agent_id".to_string(), sub_agent_id.to_string());
+    tags.insert(
+        "source_id_type".to_string(),
+        source_id.source_type.to_string(),
+    );
+    tags.insert("source_id_index".to_string(), source_id.index.to_string());
+    tags
+}
+
+struct MetricsBuilder<'a> {
+    tags: MetricTags,
+    now: DateTime<Utc>,
+    events: &'a mut Vec<Event>,
+}
+
+impl<'a> MetricsBuilder<'a> {
+    fn push(&mut self, name: &str, value: MetricValue, tags: MetricTags) {
+        let metric = Metric::new(name, MetricKind::Absolute, value)
+            .with_namespace(Some(SFLOW_NAMESPACE))
+            .with_tags(Some(tags))
+            .with_timestamp(Some(self.now));
+        self.events.push(metric.into());
+    }
+
+    fn counter(&mut self, name: &str, value: impl Into<f64>, tags: &MetricTags) {
+        let value = MetricValue::Counter {
+            value: value.into(),
+        };
+        self.push(name, value, tags.clone());
+    }
+
+    fn gauge(&mut self, name: &str, value: impl Into<f64>, tags: &MetricTags) {
+        let value = MetricValue::Gauge {
+            value: value.into(),
+        };
+        self.push(name, value, tags.clone());
+    }
+
+    fn interface(&mut self, counters: &InterfaceCounters) {
+        let mut tags = self.tags.clone();
+        tags.insert("if_index".to_string(), counters.if_index.to_string());
+        let tags = &tags;
+
+        self.gauge("interface_speed_bits", counters.if_speed as f64, tags);
+        self.gauge("interface_admin_up", counters.if_status & 1, tags);
+        self.gauge("interface_oper_up", (counters.if_status >> 1) & 1, tags);
+        self.counter(
+            "interface_receive_bytes_total",
+            counters.if_in_octets as f64,
+            tags,
+        );
+        self.counter(
+            "interface_receive_unicast_packets_total",
+            counters.if_in_ucast_pkts,
+            tags,
+        );
+        self.counter(
+            "interface_receive_multicast_packets_total",
+            counters.if_in_multicast_pkts,
+            tags,
+        );
+        self.counter(
+            "interface_receive_broadcast_packets_total",
+            counters.if_in_broadcast_pkts,
+            tags,
+        );
+        self.counter(
+            "interface_receive_discards_total",
+            counters.if_in_discards,
+            tags,
+        );
+        self.counter(
+            "interface_receive_errors_total",
+            counters.if_in_errors,
+            tags,
+        );
+        self.counter(
+            "interface_receive_unknown_protocols_total",
+            counters.if_in_unknown_protos,
+            tags,
+        );
+        self.counter(
+            "interface_transmit_bytes_total",
+            counters.if_out_octets as f64,
+            tags,
+        );
+        self.counter(
+            "interface_transmit_unicast_packets_total",
+            counters.if_out_ucast_pkts,
+            tags,
+        );
+        self.counter(
+            "interface_transmit_multicast_packets_total",
+            counters.if_out_multicast_pkts,
+            tags,
+        );
+        self.counter(
+            "interface_transmit_broadcast_packets_total",
+            counters.if_out_broadcast_pkts,
+            tags,
+        );
+        self.counter(
+            "interface_transmit_discards_total",
+            counters.if_out_discards,
+            tags,
+        );
+        self.counter(
+            "interface_transmit_errors_total",
+            counters.if_out_errors,
+            tags,
+        );
+    }
+
+    fn ethernet(&mut self, counters: &EthernetCounters) {
+        let tags = &self.tags.clone();
+        let values = [
+            ("alignment_errors", counters.alignment_errors),
+            ("fcs_errors", counters.fcs_errors),
+            ("single_collision_frames", counters.single_collision_frames),
+            (
+                "multiple_collision_frames",
+                counters.multiple_collision_frames,
+            ),
+            ("sqe_test_errors", counters.sqe_test_errors),
+            ("deferred_transmissions", counters.deferred_transmissions),
+            ("late_collisions", counters.late_collisions),
+            ("excessive_collisions", counters.excessive_collisions),
+            (
+                "internal_mac_transmit_errors",
+                counters.internal_mac_transmit_errors,
+            ),
+            ("carrier_sense_errors", counters.carrier_sense_errors),
+            ("frame_too_longs", counters.frame_too_longs),
+            (
+                "internal_mac_receive_errors",
+                counters.internal_mac_receive_errors,
+            ),
+            ("symbol_errors", counters.symbol_errors),
+        ];
+        for (name, value) in values {
+            self.counter(&format!("ethernet_{}_total", name), value, tags);
+        }
+    }
+
+    /// The CPU utilization is sent in hundredths of a percent.
+    fn processor(&mut self, counters: &ProcessorCounters) {
+        let tags = &self.tags.clone();
+        self.gauge("cpu_utilization_5s", counters.cpu_5s as f64 / 10000.0, tags);
+        self.gauge("cpu_utilization_1m", counters.cpu_1m as f64 / 10000.0, tags);
+        self.gauge("cpu_utilization_5m", counters.cpu_5m as f64 / 10000.0, tags);
+        self.gauge("memory_total_bytes", counters.total_memory as f64, tags);
+        self.gauge("memory_free_bytes", counters.free_memory as f64, tags);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        net::{Ipv4Addr, Ipv6Addr},
+    };
+
+    use flowmsg_parser::fields::Field;
+
+    use super::*;
+
+    fn ipfix_packet(records: Vec<FlowRecord>) -> TemplatePacket {
+        TemplatePacket {
+            version: 10,
+            export_time: 1_600_000_000,
+            sys_uptime: None,
+            sequence_number: 3,
+            domain_id: 42,
+            records,
+            missing_templates: vec![(257, 20)],
+        }
+    }
+
+    #[test]
+    fn converts_ipfix_records() {
+        let record = FlowRecord {
+            template_id: 256,
+            options: false,
+            fields: vec![
+                Field::new(
+                    "sourceIPv6Address",
+                    FieldValue::Ipv6Address(Ipv6Addr::LOCALHOST),
+                ),
+                Field::new("octetDeltaCount", FieldValue::Unsigned(u64::MAX)),
+                Field::new(
+                    "sourceMacAddress",
+                    FieldValue::MacAddress([0, 0x1b, 0x21, 0, 0, 0xff]),
+                ),
+                Field::new(
+                    Cow::Owned("field_999".to_string()),
+                    FieldValue::Bytes(vec![0xde, 0xad]),
+                ),
+                Field::new(
+                    "flowStartMilliseconds",
+                    FieldValue::DateTime {
+                        seconds: 1_600_000_000,
+                        nanos: 500_000_000,
+                    },
+                ),
+            ],
+        };
+        let message = FlowMessage::Ipfix(ipfix_packet(vec![record]));
+        assert_eq!(missing_templates(&message), &[(257, 20)]);
+
+        let events = convert(message, Utc::now());
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log["flow_type"], "ipfix".into());
+        assert_eq!(log["record_type"], "data".into());
+        assert_eq!(log["template_id"], 256.into());
+        assert_eq!(log["observation_domain_id"], 42.into());
+        assert_eq!(log["sourceIPv6Address"], "::1".into());
+        assert_eq!(
+            log["octetDeltaCount"],
+            Value::Float(NotNan::new(u64::MAX as f64).unwrap())
+        );
+        assert_eq!(log["sourceMacAddress"], "00:1b:21:00:00:ff".into());
+        assert_eq!(log["field_999"], "dead".into());
+        assert_eq!(
+            log["flowStartMilliseconds"],
+            Value::Timestamp(Utc.timestamp(1_600_000_000, 500_000_000))
+        );
+        assert_eq!(
+            log["timestamp"],
+            Value::Timestamp(Utc.timestamp(1_600_000_000, 0))
+        );
+    }
+
+    #[test]
+    fn converts_sflow_samples() {
+        let datagram = SflowDatagram {
+            agent_address: IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)),
+            sub_agent_id: 0,
+            sequence_number: 5,
+            uptime: 1000,
+            samples: vec![
+                Sample::Flow(FlowSample {
+                    sequence_number: 1,
+                    source_id: SourceId {
+                        source_type: 0,
+                        index: 3,
+                    },
+                    sampling_rate: 512,
+                    sample_pool: 1024,
+                    drops: 0,
+                    input: 3,
+                    output: 4,
+                    records: vec![SflowRecord::SampledIpv4(SampledIp {
+                        length: 60,
+                        protocol: 6,
+                        source_ip: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+                        destination_ip: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)),
+                        source_port: 40000,
+                        destination_port: 443,
+                        tcp_flags: 2,
+                        tos: 0,
+                    })],
+                }),
+                Sample::Counters(CountersSample {
+                    sequence_number: 2,
+                    source_id: SourceId {
+                        source_type: 2,
+                        index: 1,
+                    },
+                    records: vec![CounterRecord::Processor(ProcessorCounters {
+                        cpu_5s: 2500,
+                        cpu_1m: 2000,
+                        cpu_5m: 1500,
+                        total_memory: 1 << 30,
+                        free_memory: 1 << 29,
+                    })],
+                }),
+            ],
+        };
+
+        let events = convert(FlowMessage::Sflow(datagram), Utc::now());
+        assert_eq!(events.len(), 6);
+        let log = events[0].as_log();
+        assert_eq!(log["flow_type"], "sflow".into());
+        assert_eq!(log["agent_address"], "192.0.2.1".into());
+        assert_eq!(log["sampling_rate"], 512.into());
+        assert_eq!(log["destination_port"], 443.into());
+
+        let metric = events[1].as_metric();
+        assert_eq!(metric.namespace(), Some("sflow"));
+        assert_eq!(metric.name(), "cpu_utilization_5s");
+        assert_eq!(metric.value(), &MetricValue::Gauge { value: 0.25 });
+        assert_eq!(
+            metric.tags().unwrap().get("source_id_type"),
+            Some(&"2".to_string())
+        );
+    }
+}
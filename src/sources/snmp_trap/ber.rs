@@ -0,0 +1,364 @@
+//! Encoding and decoding of the subset of the Basic Encoding Rules of ASN.1
+//! used by SNMP messages.
+
+use std::{fmt, str::FromStr};
+
+use snafu::Snafu;
+
+pub(super) mod tag {
+    pub const INTEGER: u8 = 0x02;
+    pub const OCTET_STRING: u8 = 0x04;
+    pub const NULL: u8 = 0x05;
+    pub const OBJECT_IDENTIFIER: u8 = 0x06;
+    pub const SEQUENCE: u8 = 0x30;
+    pub const IP_ADDRESS: u8 = 0x40;
+    pub const COUNTER32: u8 = 0x41;
+    pub const GAUGE32: u8 = 0x42;
+    pub const TIME_TICKS: u8 = 0x43;
+    pub const OPAQUE: u8 = 0x44;
+    pub const COUNTER64: u8 = 0x46;
+    pub const NO_SUCH_OBJECT: u8 = 0x80;
+    pub const NO_SUCH_INSTANCE: u8 = 0x81;
+    pub const END_OF_MIB_VIEW: u8 = 0x82;
+    pub const RESPONSE_PDU: u8 = 0xa2;
+    pub const TRAP_V1_PDU: u8 = 0xa4;
+    pub const INFORM_REQUEST_PDU: u8 = 0xa6;
+    pub const TRAP_V2_PDU: u8 = 0xa7;
+}
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum BerError {
+    #[snafu(display("unexpected end of data"))]
+    UnexpectedEnd,
+    #[snafu(display("expected tag 0x{:02x}, found 0x{:02x}", expected, found))]
+    UnexpectedTag { expected: u8, found: u8 },
+    #[snafu(display("unsupported tag 0x{:02x}", tag))]
+    UnsupportedTag { tag: u8 },
+    #[snafu(display("invalid length"))]
+    InvalidLength,
+    #[snafu(display("integer out of range"))]
+    IntegerOutOfRange,
+    #[snafu(display("invalid object identifier"))]
+    InvalidObjectIdentifier,
+}
+
+/// Reads the values of a BER encoded buffer, or of the contents of one of its
+/// constructed values, keeping track of their offsets in the whole buffer.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+    end: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(super) const fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            end: data.len(),
+        }
+    }
+
+    pub(super) const fn is_empty(&self) -> bool {
+        self.position >= self.end
+    }
+
+    /// The offset of the next value in the whole buffer.
+    pub(super) const fn offset(&self) -> usize {
+        self.position
+    }
+
+    /// The bytes left to read.
+    pub(super) fn remaining(&self) -> &'a [u8] {
+        &self.data[self.position..self.end]
+    }
+
+    pub(super) fn peek_tag(&self) -> Result<u8, BerError> {
+        self.remaining()
+            .first()
+            .copied()
+            .ok_or(BerError::UnexpectedEnd)
+    }
+
+    fn read_byte(&mut self) -> Result<u8, BerError> {
+        let byte = self.peek_tag()?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn read_length(&mut self) -> Result<usize, BerError> {
+        let first = self.read_byte()?;
+        if first < 0x80 {
+            return Ok(first as usize);
+        }
+        // The indefinite form (`0x80`) isn't allowed in SNMP.
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 {
+            return Err(BerError::InvalidLength);
+        }
+        let mut length = 0usize;
+        for _ in 0..count {
+            length = (length << 8) | self.read_byte()? as usize;
+        }
+        Ok(length)
+    }
+
+    /// Reads the next value, returning its tag and a reader of its contents.
+    pub(super) fn read_any(&mut self) -> Result<(u8, Reader<'a>), BerError> {
+        let tag = self.read_byte()?;
+        // Only the low tag numbers are used by SNMP.
+        if tag & 0x1f == 0x1f {
+            return Err(BerError::UnsupportedTag { tag });
+        }
+        let length = self.read_length()?;
+        if length > self.end - self.position {
+            return Err(BerError::UnexpectedEnd);
+        }
+        let contents = Reader {
+            data: self.data,
+            position: self.position,
+            end: self.position + length,
+        };
+        self.position += length;
+        Ok((tag, contents))
+    }
+
+    /// Reads the next value, which must have the `expected` tag.
+    pub(super) fn read(&mut self, expected: u8) -> Result<Reader<'a>, BerError> {
+        let (found, contents) = self.read_any()?;
+        if found != expected {
+            return Err(BerError::UnexpectedTag { expected, found });
+        }
+        Ok(contents)
+    }
+
+    /// Reads the next value, returning all of its encoded bytes.
+    pub(super) fn read_raw(&mut self) -> Result<&'a [u8], BerError> {
+        let start = self.position;
+        self.read_any()?;
+        Ok(&self.data[start..self.position])
+    }
+
+    pub(super) fn read_sequence(&mut self) -> Result<Reader<'a>, BerError> {
+        self.read(tag::SEQUENCE)
+    }
+
+    pub(super) fn read_integer(&mut self) -> Result<i64, BerError> {
+        signed(self.read(tag::INTEGER)?.remaining())
+    }
+
+    pub(super) fn read_octet_string(&mut self) -> Result<&'a [u8], BerError> {
+        Ok(self.read(tag::OCTET_STRING)?.remaining())
+    }
+
+    pub(super) fn read_object_identifier(&mut self) -> Result<Oid, BerError> {
+        Oid::decode(self.read(tag::OBJECT_IDENTIFIER)?.remaining())
+    }
+}
+
+/// Decodes the contents of a two's complement integer.
+pub(super) fn signed(contents: &[u8]) -> Result<i64, BerError> {
+    if contents.is_empty() || contents.len() > 8 {
+        return Err(BerError::IntegerOutOfRange);
+    }
+    let initial = if contents[0] & 0x80 != 0 { -1 } else { 0 };
+    Ok(contents
+        .iter()
+        .fold(initial, |value, byte| (value << 8) | *byte as i64))
+}
+
+/// Decodes the contents of an unsigned integer, which has a leading zero byte
+/// when its most significant bit is set.
+pub(super) fn unsigned(contents: &[u8]) -> Result<u64, BerError> {
+    let contents = match contents {
+        [0, rest @ ..] if !rest.is_empty() => rest,
+        contents => contents,
+    };
+    if contents.is_empty() || contents.len() > 8 {
+        return Err(BerError::IntegerOutOfRange);
+    }
+    Ok(contents
+        .iter()
+        .fold(0, |value, byte| (value << 8) | *byte as u64))
+}
+
+/// An object identifier, such as `1.3.6.1.2.1.1.3.0`.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(super) struct Oid(pub(super) Vec<u32>);
+
+impl Oid {
+    pub(super) fn decode(contents: &[u8]) -> Result<Self, BerError> {
+        let mut arcs = Vec::with_capacity(contents.len() + 1);
+        let mut value = 0u32;
+        let mut pending = false;
+        for byte in contents {
+            if value > (u32::MAX >> 7) {
+                return Err(BerError::InvalidObjectIdentifier);
+            }
+            value = (value << 7) | (byte & 0x7f) as u32;
+            pending = byte & 0x80 != 0;
+            if !pending {
+                if arcs.is_empty() {
+                    // The first two arcs are encoded together.
+                    let first = (value / 40).min(2);
+                    arcs.push(first);
+                    arcs.push(value - first * 40);
+                } else {
+                    arcs.push(value);
+                }
+                value = 0;
+            }
+        }
+        if pending || arcs.is_empty() {
+            return Err(BerError::InvalidObjectIdentifier);
+        }
+        Ok(Self(arcs))
+    }
+}
+
+impl fmt::Display for Oid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut arcs = self.0.iter();
+        if let Some(arc) = arcs.next() {
+            write!(f, "{}", arc)?;
+        }
+        for arc in arcs {
+            write!(f, ".{}", arc)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Oid {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim_start_matches('.')
+            .split('.')
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// Appends a value with the given tag and contents.
+pub(super) fn write(tag: u8, contents: &[u8], out: &mut Vec<u8>) {
+    out.push(tag);
+    let length = contents.len();
+    if length < 0x80 {
+        out.push(length as u8);
+    } else {
+        let bytes = length.to_be_bytes();
+        let skip = bytes.iter().take_while(|byte| **byte == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+    out.extend_from_slice(contents);
+}
+
+pub(super) fn write_integer(value: i64, out: &mut Vec<u8>) {
+    let bytes = value.to_be_bytes();
+    // Strip the leading bytes which only extend the sign.
+    let mut skip = 0;
+    while skip < bytes.len() - 1 {
+        let redundant = (bytes[skip] == 0 && bytes[skip + 1] & 0x80 == 0)
+            || (bytes[skip] == 0xff && bytes[skip + 1] & 0x80 != 0);
+        if !redundant {
+            break;
+        }
+        skip += 1;
+    }
+    write(tag::INTEGER, &bytes[skip..], out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_values() {
+        let data = [
+            0x30, 0x0d, 0x02, 0x01, 0x01, 0x04, 0x03, b'a', b'b', b'c', 0x06, 0x03, 0x2b, 0x06,
+            0x01,
+        ];
+        let mut reader = Reader::new(&data);
+        let mut sequence = reader.read_sequence().unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(sequence.read_integer(), Ok(1));
+        assert_eq!(sequence.offset(), 5);
+        assert_eq!(sequence.read_octet_string(), Ok(&b"abc"[..]));
+        assert_eq!(
+            sequence.read_object_identifier().unwrap().to_string(),
+            "1.3.6.1"
+        );
+        assert!(sequence.is_empty());
+        assert_eq!(sequence.read_integer(), Err(BerError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn rejects_truncated_values() {
+        let mut reader = Reader::new(&[0x04, 0x05, b'a']);
+        assert_eq!(reader.read_octet_string(), Err(BerError::UnexpectedEnd));
+
+        let mut reader = Reader::new(&[0x02, 0x01, 0x01]);
+        assert_eq!(
+            reader.read_octet_string(),
+            Err(BerError::UnexpectedTag {
+                expected: tag::OCTET_STRING,
+                found: tag::INTEGER
+            })
+        );
+    }
+
+    #[test]
+    fn reads_long_lengths() {
+        let mut data = vec![0x04, 0x82, 0x01, 0x00];
+        data.extend_from_slice(&[b'x'; 256]);
+        let mut reader = Reader::new(&data);
+        assert_eq!(reader.read_octet_string().unwrap().len(), 256);
+    }
+
+    #[test]
+    fn decodes_integers() {
+        assert_eq!(signed(&[0x7f]), Ok(127));
+        assert_eq!(signed(&[0x00, 0x80]), Ok(128));
+        assert_eq!(signed(&[0xff]), Ok(-1));
+        assert_eq!(signed(&[0xff, 0x7f]), Ok(-129));
+        assert_eq!(signed(&[]), Err(BerError::IntegerOutOfRange));
+        assert_eq!(
+            unsigned(&[0x00, 0xff, 0xff, 0xff, 0xff]),
+            Ok(u32::MAX as u64)
+        );
+    }
+
+    #[test]
+    fn decodes_object_identifiers() {
+        let oid = Oid::decode(&[0x2b, 0x06, 0x01, 0x04, 0x01, 0x82, 0x37, 0x01]).unwrap();
+        assert_eq!(oid, "1.3.6.1.4.1.311.1".parse().unwrap());
+        assert_eq!(
+            Oid::decode(&[0x2b, 0x86]),
+            Err(BerError::InvalidObjectIdentifier)
+        );
+    }
+
+    #[test]
+    fn writes_integers() {
+        for value in [0, 1, 127, 128, 255, 256, -1, -128, -129, i64::MAX, i64::MIN] {
+            let mut out = Vec::new();
+            write_integer(value, &mut out);
+            assert_eq!(Reader::new(&out).read_integer(), Ok(value));
+        }
+        let mut out = Vec::new();
+        write_integer(128, &mut out);
+        assert_eq!(out, [0x02, 0x02, 0x00, 0x80]);
+    }
+
+    #[test]
+    fn writes_long_lengths() {
+        let mut out = Vec::new();
+        write(tag::OCTET_STRING, &[b'x'; 300], &mut out);
+        assert_eq!(&out[..4], &[0x04, 0x82, 0x01, 0x2c]);
+        assert_eq!(Reader::new(&out).read_octet_string().unwrap().len(), 300);
+    }
+}
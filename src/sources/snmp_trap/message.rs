@@ -0,0 +1,401 @@
+//! Decoding of the SNMP messages carrying traps and informs.
+
+use std::net::Ipv4Addr;
+
+use super::ber::{self, tag, BerError, Oid, Reader};
+
+/// `sysUpTime.0`, the first variable of the SNMPv2 traps.
+pub(super) const SYS_UP_TIME: [u32; 9] = [1, 3, 6, 1, 2, 1, 1, 3, 0];
+/// `snmpTrapOID.0`, the second variable of the SNMPv2 traps.
+pub(super) const SNMP_TRAP_OID: [u32; 11] = [1, 3, 6, 1, 6, 3, 1, 1, 4, 1, 0];
+/// `snmpTraps`, the prefix of the generic traps converted from SNMPv1.
+const SNMP_TRAPS: [u32; 10] = [1, 3, 6, 1, 6, 3, 1, 1, 5, 0];
+/// The `enterpriseSpecific` generic trap of SNMPv1.
+const ENTERPRISE_SPECIFIC: i64 = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Version {
+    V1,
+    V2c,
+    V3,
+}
+
+impl Version {
+    pub(super) const fn as_str(&self) -> &'static str {
+        match self {
+            Self::V1 => "1",
+            Self::V2c => "2c",
+            Self::V3 => "3",
+        }
+    }
+}
+
+/// A decoded SNMP message, whose PDU is still to be decrypted and
+/// authenticated for SNMPv3.
+#[derive(Debug)]
+pub(super) enum Message<'a> {
+    Community {
+        version: Version,
+        community: &'a [u8],
+        pdu: Pdu,
+    },
+    V3(V3Message<'a>),
+}
+
+#[derive(Debug)]
+pub(super) struct V3Message<'a> {
+    /// The whole message, over which the authentication digest is computed.
+    pub(super) whole: &'a [u8],
+    pub(super) flags: u8,
+    pub(super) security_model: i64,
+    pub(super) engine_id: &'a [u8],
+    pub(super) engine_boots: i64,
+    pub(super) engine_time: i64,
+    pub(super) user_name: &'a [u8],
+    pub(super) auth_parameters: &'a [u8],
+    /// The offset of the authentication parameters in the whole message.
+    pub(super) auth_parameters_offset: usize,
+    pub(super) priv_parameters: &'a [u8],
+    pub(super) data: ScopedPduData<'a>,
+}
+
+pub(super) const FLAG_AUTH: u8 = 0x01;
+pub(super) const FLAG_PRIV: u8 = 0x02;
+/// The User-based Security Model, the only one defined for SNMPv3.
+pub(super) const USM_SECURITY_MODEL: i64 = 3;
+
+#[derive(Debug)]
+pub(super) enum ScopedPduData<'a> {
+    Plaintext(ScopedPdu),
+    Encrypted(&'a [u8]),
+}
+
+#[derive(Debug)]
+pub(super) struct ScopedPdu {
+    pub(super) context_engine_id: Vec<u8>,
+    pub(super) context_name: Vec<u8>,
+    pub(super) pdu: Pdu,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum PduType {
+    TrapV1,
+    Trap,
+    Inform,
+}
+
+impl PduType {
+    pub(super) const fn tag(&self) -> u8 {
+        match self {
+            Self::TrapV1 => tag::TRAP_V1_PDU,
+            Self::Trap => tag::TRAP_V2_PDU,
+            Self::Inform => tag::INFORM_REQUEST_PDU,
+        }
+    }
+
+    pub(super) const fn as_str(&self) -> &'static str {
+        match self {
+            Self::TrapV1 => "trap_v1",
+            Self::Trap => "trap",
+            Self::Inform => "inform",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct Pdu {
+    pub(super) pdu_type: PduType,
+    pub(super) request_id: Option<i64>,
+    pub(super) v1: Option<TrapV1>,
+    pub(super) variables: Vec<VarBind>,
+    /// The encoded variable bindings, sent back in the responses to informs.
+    pub(super) raw_variables: Vec<u8>,
+}
+
+/// The fields of the SNMPv1 traps, which SNMPv2 carries in variables.
+#[derive(Debug)]
+pub(super) struct TrapV1 {
+    pub(super) enterprise: Oid,
+    pub(super) agent_address: Ipv4Addr,
+    pub(super) generic_trap: i64,
+    pub(super) specific_trap: i64,
+    pub(super) time_stamp: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct VarBind {
+    pub(super) oid: Oid,
+    pub(super) value: VarValue,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum VarValue {
+    Integer(i64),
+    OctetString(Vec<u8>),
+    Null,
+    ObjectIdentifier(Oid),
+    IpAddress(Ipv4Addr),
+    Counter32(u64),
+    Gauge32(u64),
+    TimeTicks(u64),
+    Opaque(Vec<u8>),
+    Counter64(u64),
+    NoSuchObject,
+    NoSuchInstance,
+    EndOfMibView,
+}
+
+impl VarValue {
+    pub(super) const fn type_name(&self) -> &'static str {
+        match self {
+            Self::Integer(_) => "integer",
+            Self::OctetString(_) => "octet_string",
+            Self::Null => "null",
+            Self::ObjectIdentifier(_) => "object_identifier",
+            Self::IpAddress(_) => "ip_address",
+            Self::Counter32(_) => "counter32",
+            Self::Gauge32(_) => "gauge32",
+            Self::TimeTicks(_) => "time_ticks",
+            Self::Opaque(_) => "opaque",
+            Self::Counter64(_) => "counter64",
+            Self::NoSuchObject => "no_such_object",
+            Self::NoSuchInstance => "no_such_instance",
+            Self::EndOfMibView => "end_of_mib_view",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum MessageError {
+    Ber(BerError),
+    UnsupportedVersion(i64),
+    UnsupportedPdu(u8),
+}
+
+impl From<BerError> for MessageError {
+    fn from(error: BerError) -> Self {
+        Self::Ber(error)
+    }
+}
+
+pub(super) fn decode(data: &[u8]) -> Result<Message<'_>, MessageError> {
+    let mut message = Reader::new(data).read_sequence()?;
+    match message.read_integer()? {
+        0 => decode_community(Version::V1, message),
+        1 => decode_community(Version::V2c, message),
+        3 => decode_v3(data, message),
+        version => Err(MessageError::UnsupportedVersion(version)),
+    }
+}
+
+fn decode_community(
+    version: Version,
+    mut message: Reader<'_>,
+) -> Result<Message<'_>, MessageError> {
+    let community = message.read_octet_string()?;
+    let pdu = decode_pdu(&mut message)?;
+    // SNMPv1 only has its own trap PDU, which was replaced in SNMPv2.
+    if (version == Version::V1) != (pdu.pdu_type == PduType::TrapV1) {
+        return Err(MessageError::UnsupportedPdu(pdu.pdu_type.tag()));
+    }
+    Ok(Message::Community {
+        version,
+        community,
+        pdu,
+    })
+}
+
+fn decode_v3<'a>(whole: &'a [u8], mut message: Reader<'a>) -> Result<Message<'a>, MessageError> {
+    let mut global = message.read_sequence()?;
+    let _message_id = global.read_integer()?;
+    let _max_size = global.read_integer()?;
+    let flags = global.read_octet_string()?.first().copied().unwrap_or(0);
+    let security_model = global.read_integer()?;
+
+    // The security parameters are encoded in an octet string.
+    let mut security = message.read(tag::OCTET_STRING)?.read_sequence()?;
+    let engine_id = security.read_octet_string()?;
+    let engine_boots = security.read_integer()?;
+    let engine_time = security.read_integer()?;
+    let user_name = security.read_octet_string()?;
+    let auth = security.read(tag::OCTET_STRING)?;
+    let auth_parameters_offset = auth.offset();
+    let auth_parameters = auth.remaining();
+    let priv_parameters = security.read_octet_string()?;
+
+    let data = match message.peek_tag()? {
+        tag::OCTET_STRING => ScopedPduData::Encrypted(message.read_octet_string()?),
+        _ => ScopedPduData::Plaintext(decode_scoped_pdu(message.remaining())?),
+    };
+
+    Ok(Message::V3(V3Message {
+        whole,
+        flags,
+        security_model,
+        engine_id,
+        engine_boots,
+        engine_time,
+        user_name,
+        auth_parameters,
+        auth_parameters_offset,
+        priv_parameters,
+        data,
+    }))
+}
+
+/// Decodes a scoped PDU, ignoring the padding following it once decrypted.
+pub(super) fn decode_scoped_pdu(data: &[u8]) -> Result<ScopedPdu, MessageError> {
+    let mut scoped = Reader::new(data).read_sequence()?;
+    let context_engine_id = scoped.read_octet_string()?.to_vec();
+    let context_name = scoped.read_octet_string()?.to_vec();
+    let pdu = decode_pdu(&mut scoped)?;
+    Ok(ScopedPdu {
+        context_engine_id,
+        context_name,
+        pdu,
+    })
+}
+
+fn decode_pdu(reader: &mut Reader<'_>) -> Result<Pdu, MessageError> {
+    let (pdu_tag, mut pdu) = reader.read_any()?;
+    let pdu_type = match pdu_tag {
+        tag::TRAP_V1_PDU => PduType::TrapV1,
+        tag::TRAP_V2_PDU => PduType::Trap,
+        tag::INFORM_REQUEST_PDU => PduType::Inform,
+        tag => return Err(MessageError::UnsupportedPdu(tag)),
+    };
+
+    let (request_id, v1) = if pdu_type == PduType::TrapV1 {
+        let enterprise = pdu.read_object_identifier()?;
+        let agent_address = ip_address(pdu.read(tag::IP_ADDRESS)?.remaining())?;
+        let generic_trap = pdu.read_integer()?;
+        let specific_trap = pdu.read_integer()?;
+        let time_stamp = ber::unsigned(pdu.read(tag::TIME_TICKS)?.remaining())?;
+        let v1 = TrapV1 {
+            enterprise,
+            agent_address,
+            generic_trap,
+            specific_trap,
+            time_stamp,
+        };
+        (None, Some(v1))
+    } else {
+        let request_id = pdu.read_integer()?;
+        let _error_status = pdu.read_integer()?;
+        let _error_index = pdu.read_integer()?;
+        (Some(request_id), None)
+    };
+
+    let raw_variables = pdu.read_raw()?;
+    let mut list = Reader::new(raw_variables).read_sequence()?;
+    let mut variables = Vec::new();
+    while !list.is_empty() {
+        let mut variable = list.read_sequence()?;
+        let oid = variable.read_object_identifier()?;
+        let value = decode_value(&mut variable)?;
+        variables.push(VarBind { oid, value });
+    }
+
+    Ok(Pdu {
+        pdu_type,
+        request_id,
+        v1,
+        variables,
+        raw_variables: raw_variables.to_vec(),
+    })
+}
+
+fn decode_value(reader: &mut Reader<'_>) -> Result<VarValue, MessageError> {
+    let (value_tag, value) = reader.read_any()?;
+    let contents = value.remaining();
+    Ok(match value_tag {
+        tag::INTEGER => VarValue::Integer(ber::signed(contents)?),
+        tag::OCTET_STRING => VarValue::OctetString(contents.to_vec()),
+        tag::NULL => VarValue::Null,
+        tag::OBJECT_IDENTIFIER => VarValue::ObjectIdentifier(Oid::decode(contents)?),
+        tag::IP_ADDRESS => VarValue::IpAddress(ip_address(contents)?),
+        tag::COUNTER32 => VarValue::Counter32(ber::unsigned(contents)?),
+        tag::GAUGE32 => VarValue::Gauge32(ber::unsigned(contents)?),
+        tag::TIME_TICKS => VarValue::TimeTicks(ber::unsigned(contents)?),
+        tag::OPAQUE => VarValue::Opaque(contents.to_vec()),
+        tag::COUNTER64 => VarValue::Counter64(ber::unsigned(contents)?),
+        tag::NO_SUCH_OBJECT => VarValue::NoSuchObject,
+        tag::NO_SUCH_INSTANCE => VarValue::NoSuchInstance,
+        tag::END_OF_MIB_VIEW => VarValue::EndOfMibView,
+        tag => return Err(BerError::UnsupportedTag { tag }.into()),
+    })
+}
+
+fn ip_address(contents: &[u8]) -> Result<Ipv4Addr, BerError> {
+    <[u8; 4]>::try_from(contents)
+        .map(Ipv4Addr::from)
+        .map_err(|_| BerError::InvalidLength)
+}
+
+impl Pdu {
+    /// The object identifier of the trap, converting the generic and
+    /// enterprise specific traps of SNMPv1 as described by RFC 3584.
+    pub(super) fn trap_oid(&self) -> Option<Oid> {
+        match &self.v1 {
+            Some(v1) if v1.generic_trap == ENTERPRISE_SPECIFIC => {
+                let mut arcs = v1.enterprise.0.clone();
+                arcs.push(0);
+                arcs.push(v1.specific_trap as u32);
+                Some(Oid(arcs))
+            }
+            Some(v1) => {
+                let mut arcs = SNMP_TRAPS.to_vec();
+                *arcs.last_mut().expect("not empty") = v1.generic_trap as u32 + 1;
+                Some(Oid(arcs))
+            }
+            None => self
+                .variables
+                .iter()
+                .find_map(|variable| match &variable.value {
+                    VarValue::ObjectIdentifier(oid) if variable.oid.0 == SNMP_TRAP_OID => {
+                        Some(oid.clone())
+                    }
+                    _ => None,
+                }),
+        }
+    }
+
+    /// The time since the agent was started, in hundredths of seconds.
+    pub(super) fn uptime(&self) -> Option<u64> {
+        match &self.v1 {
+            Some(v1) => Some(v1.time_stamp),
+            None => self
+                .variables
+                .iter()
+                .find_map(|variable| match variable.value {
+                    VarValue::TimeTicks(ticks) if variable.oid.0 == SYS_UP_TIME => Some(ticks),
+                    _ => None,
+                }),
+        }
+    }
+}
+
+/// Encodes the response to an inform, acknowledging it with its variables.
+pub(super) fn encode_inform_response(version: Version, community: &[u8], pdu: &Pdu) -> Vec<u8> {
+    let mut response = Vec::new();
+    ber::write_integer(pdu.request_id.unwrap_or_default(), &mut response);
+    ber::write_integer(0, &mut response);
+    ber::write_integer(0, &mut response);
+    response.extend_from_slice(&pdu.raw_variables);
+
+    let mut message = Vec::new();
+    ber::write_integer(
+        match version {
+            Version::V1 => 0,
+            Version::V2c => 1,
+            Version::V3 => 3,
+        },
+        &mut message,
+    );
+    ber::write(tag::OCTET_STRING, community, &mut message);
+    ber::write(tag::RESPONSE_PDU, &response, &mut message);
+
+    let mut out = Vec::new();
+    ber::write(tag::SEQUENCE, &message, &mut out);
+    out
+}
@@ -0,0 +1,480 @@
+//! Translation of object identifiers to the names defined in MIB modules.
+//!
+//! Only the object identifier assignments of the modules are parsed, so that
+//! modules can be loaded in any order and without the modules they import.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use snafu::{ResultExt, Snafu};
+
+use super::ber::Oid;
+
+#[derive(Debug, Snafu)]
+pub enum MibError {
+    #[snafu(display("Unable to read MIB file {:?}: {}", path, source))]
+    ReadFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// The names of the nodes which MIB modules are usually defined under, from
+/// the modules defining the SMI and the standard traps.
+const WELL_KNOWN: &[(&str, &str, &[u32])] = &[
+    ("SNMPv2-SMI", "iso", &[1]),
+    ("SNMPv2-SMI", "org", &[1, 3]),
+    ("SNMPv2-SMI", "dod", &[1, 3, 6]),
+    ("SNMPv2-SMI", "internet", &[1, 3, 6, 1]),
+    ("SNMPv2-SMI", "directory", &[1, 3, 6, 1, 1]),
+    ("SNMPv2-SMI", "mgmt", &[1, 3, 6, 1, 2]),
+    ("SNMPv2-SMI", "mib-2", &[1, 3, 6, 1, 2, 1]),
+    ("SNMPv2-SMI", "transmission", &[1, 3, 6, 1, 2, 1, 10]),
+    ("SNMPv2-SMI", "experimental", &[1, 3, 6, 1, 3]),
+    ("SNMPv2-SMI", "private", &[1, 3, 6, 1, 4]),
+    ("SNMPv2-SMI", "enterprises", &[1, 3, 6, 1, 4, 1]),
+    ("SNMPv2-SMI", "security", &[1, 3, 6, 1, 5]),
+    ("SNMPv2-SMI", "snmpV2", &[1, 3, 6, 1, 6]),
+    ("SNMPv2-SMI", "snmpDomains", &[1, 3, 6, 1, 6, 1]),
+    ("SNMPv2-SMI", "snmpProxys", &[1, 3, 6, 1, 6, 2]),
+    ("SNMPv2-SMI", "snmpModules", &[1, 3, 6, 1, 6, 3]),
+    ("SNMPv2-MIB", "system", &[1, 3, 6, 1, 2, 1, 1]),
+    ("SNMPv2-MIB", "sysUpTime", &[1, 3, 6, 1, 2, 1, 1, 3]),
+    ("SNMPv2-MIB", "snmpTrapOID", &[1, 3, 6, 1, 6, 3, 1, 1, 4, 1]),
+    (
+        "SNMPv2-MIB",
+        "snmpTrapEnterprise",
+        &[1, 3, 6, 1, 6, 3, 1, 1, 4, 3],
+    ),
+    ("SNMPv2-MIB", "coldStart", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 1]),
+    ("SNMPv2-MIB", "warmStart", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 2]),
+    ("IF-MIB", "linkDown", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 3]),
+    ("IF-MIB", "linkUp", &[1, 3, 6, 1, 6, 3, 1, 1, 5, 4]),
+    (
+        "SNMPv2-MIB",
+        "authenticationFailure",
+        &[1, 3, 6, 1, 6, 3, 1, 1, 5, 5],
+    ),
+];
+
+/// The macros and types whose values are object identifiers.
+const ASSIGNMENT_MACROS: &[&str] = &[
+    "OBJECT-TYPE",
+    "OBJECT-IDENTITY",
+    "MODULE-IDENTITY",
+    "NOTIFICATION-TYPE",
+    "OBJECT-GROUP",
+    "NOTIFICATION-GROUP",
+    "MODULE-COMPLIANCE",
+    "AGENT-CAPABILITIES",
+];
+
+/// An object identifier assignment, relative to a parent node.
+#[derive(Debug, PartialEq)]
+struct Assignment {
+    module: String,
+    name: String,
+    parent: String,
+    arcs: Vec<u32>,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct Mib {
+    names: HashMap<Vec<u32>, (String, String)>,
+}
+
+impl Mib {
+    /// Loads the MIB modules of files, or of the files in directories.
+    pub(super) fn load(paths: &[PathBuf]) -> Result<Self, MibError> {
+        let mut texts = Vec::new();
+        for path in paths {
+            if path.is_dir() {
+                let entries = fs::read_dir(path).context(ReadFileSnafu { path })?;
+                let mut files = entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.is_file())
+                    .collect::<Vec<_>>();
+                files.sort();
+                for file in files {
+                    texts.push(read(&file)?);
+                }
+            } else {
+                texts.push(read(path)?);
+            }
+        }
+        Ok(Self::parse(texts.iter().map(String::as_str)))
+    }
+
+    fn parse<'a>(texts: impl IntoIterator<Item = &'a str>) -> Self {
+        let assignments = texts
+            .into_iter()
+            .flat_map(|text| parse_assignments(&tokenize(text)))
+            .collect::<Vec<_>>();
+
+        let mut oids = WELL_KNOWN
+            .iter()
+            .map(|(module, name, arcs)| (name.to_string(), (module.to_string(), arcs.to_vec())))
+            .collect::<HashMap<_, _>>();
+        // The assignments are resolved once their parents are, in as many
+        // passes as the depth of the tree they define.
+        let mut pending = assignments.iter().collect::<Vec<_>>();
+        loop {
+            let count = pending.len();
+            pending.retain(|assignment| match oids.get(&assignment.parent) {
+                Some((_, parent)) => {
+                    let mut arcs = parent.clone();
+                    arcs.extend_from_slice(&assignment.arcs);
+                    oids.insert(assignment.name.clone(), (assignment.module.clone(), arcs));
+                    false
+                }
+                None => true,
+            });
+            if pending.is_empty() || pending.len() == count {
+                break;
+            }
+        }
+        for assignment in pending {
+            debug!(
+                message = "Unable to resolve the object identifier of MIB definition.",
+                module = %assignment.module,
+                name = %assignment.name,
+                parent = %assignment.parent,
+            );
+        }
+
+        let names = oids
+            .into_iter()
+            .map(|(name, (module, arcs))| (arcs, (module, name)))
+            .collect();
+        Self { names }
+    }
+
+    /// Translates an object identifier to the name of its closest named
+    /// ancestor, followed by the remaining arcs, like `IF-MIB::ifDescr.2`.
+    pub(super) fn translate(&self, oid: &Oid) -> Option<String> {
+        (1..=oid.0.len()).rev().find_map(|length| {
+            let (module, name) = self.names.get(&oid.0[..length])?;
+            let mut translated = format!("{}::{}", module, name);
+            for arc in &oid.0[length..] {
+                translated.push('.');
+                translated.push_str(&arc.to_string());
+            }
+            Some(translated)
+        })
+    }
+}
+
+fn read(path: &Path) -> Result<String, MibError> {
+    let bytes = fs::read(path).context(ReadFileSnafu { path })?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Word(&'a str),
+    Symbol(&'a str),
+    /// A quoted string, whose contents don't matter.
+    Text,
+}
+
+/// Splits the text of a module into words, symbols and quoted strings,
+/// skipping the comments.
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let bytes = text.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        let start = index;
+        if byte.is_ascii_whitespace() {
+            index += 1;
+        } else if bytes[index..].starts_with(b"--") {
+            // Comments end at the end of the line or at the next `--`.
+            index += 2;
+            while index < bytes.len() && bytes[index] != b'\n' {
+                if bytes[index..].starts_with(b"--") {
+                    index += 2;
+                    break;
+                }
+                index += 1;
+            }
+        } else if byte == b'"' || byte == b'\'' {
+            index += 1;
+            while index < bytes.len() && bytes[index] != byte {
+                index += 1;
+            }
+            index += 1;
+            tokens.push(Token::Text);
+        } else if bytes[index..].starts_with(b"::=") {
+            index += 3;
+            tokens.push(Token::Symbol("::="));
+        } else if byte.is_ascii_alphanumeric() {
+            while index < bytes.len()
+                && (bytes[index].is_ascii_alphanumeric()
+                    || bytes[index] == b'_'
+                    || (bytes[index] == b'-' && !bytes[index..].starts_with(b"--")))
+            {
+                index += 1;
+            }
+            tokens.push(Token::Word(&text[start..index]));
+        } else {
+            index += text[index..].chars().next().map_or(1, char::len_utf8);
+            tokens.push(Token::Symbol(&text[start..index]));
+        }
+    }
+    tokens
+}
+
+fn is_value_name(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_ascii_lowercase())
+}
+
+fn parse_assignments(tokens: &[Token<'_>]) -> Vec<Assignment> {
+    let mut assignments = Vec::new();
+    let mut module = String::new();
+    // The name being defined, and the enterprise of the SNMPv1 traps.
+    let mut pending: Option<(&str, Option<&str>)> = None;
+    let mut index = 0;
+    while index < tokens.len() {
+        let previous = index.checked_sub(1).map(|index| &tokens[index]);
+        match (&tokens[index], previous) {
+            (Token::Word("DEFINITIONS"), Some(Token::Word(name))) => module = name.to_string(),
+            // The macros are defined in the modules of the SMI.
+            (Token::Word("MACRO"), _) => {
+                while index < tokens.len() && tokens[index] != Token::Word("END") {
+                    index += 1;
+                }
+            }
+            (Token::Word(word), Some(Token::Word(name)))
+                if is_value_name(name)
+                    && (ASSIGNMENT_MACROS.contains(word)
+                        || *word == "TRAP-TYPE"
+                        || (*word == "OBJECT"
+                            && tokens.get(index + 1) == Some(&Token::Word("IDENTIFIER")))) =>
+            {
+                pending = Some((name, None));
+            }
+            (Token::Word(enterprise), Some(Token::Word("ENTERPRISE"))) => {
+                if let Some((_, pending_enterprise)) = &mut pending {
+                    *pending_enterprise = Some(enterprise);
+                }
+            }
+            (Token::Symbol("::="), _) => {
+                if let Some((name, enterprise)) = pending.take() {
+                    let (assignment, next) =
+                        parse_value(&tokens[index + 1..], &module, name, enterprise);
+                    assignments.extend(assignment);
+                    index += next;
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    assignments
+}
+
+/// Parses the value of an assignment, like `{ ifEntry 2 }`, returning it and
+/// the number of tokens it spans.
+fn parse_value(
+    tokens: &[Token<'_>],
+    module: &str,
+    name: &str,
+    enterprise: Option<&str>,
+) -> (Option<Assignment>, usize) {
+    let assignment = |parent: &str, arcs| Assignment {
+        module: module.to_string(),
+        name: name.to_string(),
+        parent: parent.to_string(),
+        arcs,
+    };
+
+    match (tokens.first(), enterprise) {
+        // The SNMPv1 traps are numbered within their enterprise.
+        (Some(Token::Word(number)), Some(enterprise)) => {
+            let assignment = number
+                .parse()
+                .ok()
+                .map(|number| assignment(enterprise, vec![0, number]));
+            (assignment, 1)
+        }
+        (Some(Token::Symbol("{")), None) => {
+            let end = tokens
+                .iter()
+                .position(|token| *token == Token::Symbol("}"))
+                .unwrap_or(tokens.len());
+            let mut parent = None;
+            let mut arcs = Vec::new();
+            let mut value = tokens[1..end].iter().peekable();
+            while let Some(token) = value.next() {
+                match token {
+                    Token::Word(word) => match word.parse() {
+                        Ok(number) => arcs.push(number),
+                        // Named numbers, like `org(3)`.
+                        Err(_) if value.peek() == Some(&&Token::Symbol("(")) => {
+                            value.next();
+                            if let Some(Token::Word(number)) = value.next() {
+                                arcs.extend(number.parse().ok());
+                            }
+                            value.next();
+                        }
+                        Err(_) if parent.is_none() && arcs.is_empty() => parent = Some(*word),
+                        Err(_) => return (None, end),
+                    },
+                    _ => return (None, end),
+                }
+            }
+            let assignment = match parent {
+                Some(parent) if !arcs.is_empty() => Some(assignment(parent, arcs)),
+                // Absolute values, like `{ 1 3 6 }`.
+                None if arcs.len() > 1 => {
+                    Some(assignment("iso", arcs[1..].to_vec())).filter(|_| arcs[0] == 1)
+                }
+                _ => None,
+            };
+            (assignment, end)
+        }
+        _ => (None, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IF_MIB: &str = r#"
+IF-MIB DEFINITIONS ::= BEGIN
+
+IMPORTS
+    MODULE-IDENTITY, OBJECT-TYPE, Counter32, Gauge32, Counter64,
+    Integer32, TimeTicks, mib-2,
+    NOTIFICATION-TYPE                        FROM SNMPv2-SMI;
+
+ifMIB MODULE-IDENTITY
+    LAST-UPDATED "200006140000Z"
+    ORGANIZATION "IETF Interfaces MIB Working Group"
+    DESCRIPTION
+            "The MIB module to describe generic objects for network
+            interface sub-layers.  ::= { fake 1 }"
+    ::= { mib-2 31 }
+
+interfaces   OBJECT IDENTIFIER ::= { mib-2 2 }
+
+ifTable OBJECT-TYPE
+    SYNTAX      SEQUENCE OF IfEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    DESCRIPTION
+            "A list of interface entries."
+    ::= { interfaces 2 }
+
+ifEntry OBJECT-TYPE
+    SYNTAX      IfEntry
+    MAX-ACCESS  not-accessible
+    STATUS      current
+    INDEX   { ifIndex }
+    ::= { ifTable 1 }
+
+IfEntry ::=
+    SEQUENCE {
+        ifIndex                 InterfaceIndex,
+        ifDescr                 DisplayString
+    }
+
+-- ifType OBJECT-TYPE ::= { ifEntry 99 }
+-- the descriptions -- ifName OBJECT IDENTIFIER ::= { ifEntry 98 }
+ifDescr OBJECT-TYPE
+    SYNTAX      DisplayString (SIZE (0..255))
+    MAX-ACCESS  read-only
+    STATUS      current
+    ::= { ifEntry 2 }
+
+ifAdminStatus OBJECT-TYPE
+    SYNTAX  INTEGER {
+                up(1),       -- ready to pass packets
+                down(2)
+            }
+    MAX-ACCESS  read-write
+    STATUS      current
+    DEFVAL { up }
+    ::= { ifEntry 7 }
+
+END
+"#;
+
+    const TRAPS_MIB: &str = r#"
+ACME-TRAPS-MIB DEFINITIONS ::= BEGIN
+
+acme OBJECT IDENTIFIER ::= { iso org(3) dod(6) internet(1) private(4) enterprises(1) 99999 }
+
+acmeFailure TRAP-TYPE
+    ENTERPRISE acme
+    VARIABLES { ifDescr }
+    DESCRIPTION "A failure."
+    ::= 3
+
+acmeNotifications OBJECT IDENTIFIER ::= { 1 3 6 1 4 1 99999 2 }
+
+END
+"#;
+
+    fn translate(mib: &Mib, oid: &str) -> Option<String> {
+        mib.translate(&oid.parse().unwrap())
+    }
+
+    #[test]
+    fn translates_object_identifiers() {
+        let mib = Mib::parse([TRAPS_MIB, IF_MIB]);
+        assert_eq!(
+            translate(&mib, "1.3.6.1.2.1.2.2.1.2.3").as_deref(),
+            Some("IF-MIB::ifDescr.3")
+        );
+        assert_eq!(
+            translate(&mib, "1.3.6.1.2.1.2.2.1.7").as_deref(),
+            Some("IF-MIB::ifAdminStatus")
+        );
+        assert_eq!(
+            translate(&mib, "1.3.6.1.2.1.31").as_deref(),
+            Some("IF-MIB::ifMIB")
+        );
+        assert_eq!(
+            translate(&mib, "1.3.6.1.6.3.1.1.5.3").as_deref(),
+            Some("IF-MIB::linkDown")
+        );
+        assert_eq!(translate(&mib, "2.999"), None);
+    }
+
+    #[test]
+    fn skips_comments_and_strings() {
+        let mib = Mib::parse([IF_MIB]);
+        assert_eq!(
+            translate(&mib, "1.3.6.1.2.1.2.2.1.99").as_deref(),
+            Some("IF-MIB::ifEntry.99")
+        );
+        // Comments end at the next `--` too.
+        assert_eq!(
+            translate(&mib, "1.3.6.1.2.1.2.2.1.98").as_deref(),
+            Some("IF-MIB::ifName")
+        );
+        assert_eq!(
+            translate(&mib, "1.3.6.1.2.1.1.3.0").as_deref(),
+            Some("SNMPv2-MIB::sysUpTime.0")
+        );
+    }
+
+    #[test]
+    fn resolves_named_numbers_and_v1_traps() {
+        let mib = Mib::parse([TRAPS_MIB]);
+        assert_eq!(
+            translate(&mib, "1.3.6.1.4.1.99999.0.3").as_deref(),
+            Some("ACME-TRAPS-MIB::acmeFailure")
+        );
+        assert_eq!(
+            translate(&mib, "1.3.6.1.4.1.99999.2.1").as_deref(),
+            Some("ACME-TRAPS-MIB::acmeNotifications.1")
+        );
+    }
+}
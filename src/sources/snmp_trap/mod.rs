@@ -0,0 +1,634 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    path::PathBuf,
+};
+
+use bytes::Bytes;
+use chrono::Utc;
+use futures::TryFutureExt;
+use ordered_float::NotNan;
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+use vector_core::ByteSizeOf;
+
+use self::{
+    ber::{BerError, Oid},
+    message::{Message, MessageError, Pdu, PduType, VarValue, Version},
+    mib::Mib,
+    usm::{UserConfig, Users, UsmError},
+};
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Output, Resource, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::{LogEvent, Value},
+    internal_events::{
+        EventsReceived, SnmpTrapInformResponseError, SnmpTrapInvalidMessage, SnmpTrapSocketError,
+        StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+    sources::util::UdpReceiver,
+    SourceSender,
+};
+
+mod ber;
+mod message;
+mod mib;
+mod usm;
+
+/// The largest datagram that can be received over UDP.
+const MAX_DATAGRAM_LENGTH: usize = 65535;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SnmpTrapConfig {
+    address: SocketAddr,
+    receive_buffer_bytes: Option<usize>,
+    host_key: Option<String>,
+    #[serde(default)]
+    communities: Vec<String>,
+    #[serde(default)]
+    users: Vec<UserConfig>,
+    #[serde(default)]
+    mib_paths: Vec<PathBuf>,
+}
+
+impl SnmpTrapConfig {
+    #[cfg(test)]
+    const fn from_address(address: SocketAddr) -> Self {
+        Self {
+            address,
+            receive_buffer_bytes: None,
+            host_key: None,
+            communities: Vec::new(),
+            users: Vec::new(),
+            mib_paths: Vec::new(),
+        }
+    }
+}
+
+inventory::submit! {
+    SourceDescription::new::<SnmpTrapConfig>("snmp_trap")
+}
+
+impl GenerateConfig for SnmpTrapConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            address: SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 0, 0), 162)),
+            receive_buffer_bytes: None,
+            host_key: None,
+            communities: Vec::new(),
+            users: Vec::new(),
+            mib_paths: Vec::new(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "snmp_trap")]
+impl SourceConfig for SnmpTrapConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let users = Users::new(&self.users)?;
+        let mib = if self.mib_paths.is_empty() {
+            None
+        } else {
+            Some(Mib::load(&self.mib_paths)?)
+        };
+        let decoder = TrapDecoder {
+            communities: self.communities.iter().cloned().collect(),
+            users,
+            mib,
+        };
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().to_string());
+
+        Ok(Box::pin(snmp_trap_udp(
+            self.address,
+            self.receive_buffer_bytes,
+            host_key,
+            decoder,
+            cx.shutdown,
+            cx.out,
+        )))
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "snmp_trap"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::udp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum TrapError {
+    #[snafu(display("Invalid SNMP message: {}", source))]
+    InvalidMessage { source: BerError },
+    #[snafu(display("Unsupported SNMP version {}", version))]
+    UnsupportedVersion { version: i64 },
+    #[snafu(display("Unsupported PDU type 0x{:02x}", tag))]
+    UnsupportedPdu { tag: u8 },
+    #[snafu(display("Community {:?} isn't allowed", community))]
+    CommunityNotAllowed { community: String },
+    #[snafu(display("SNMPv3 security check failed: {}", source))]
+    Usm { source: UsmError },
+}
+
+impl From<MessageError> for TrapError {
+    fn from(error: MessageError) -> Self {
+        match error {
+            MessageError::Ber(source) => Self::InvalidMessage { source },
+            MessageError::UnsupportedVersion(version) => Self::UnsupportedVersion { version },
+            MessageError::UnsupportedPdu(tag) => Self::UnsupportedPdu { tag },
+        }
+    }
+}
+
+/// A received trap, along with the response acknowledging it if it's an
+/// inform.
+#[derive(Debug)]
+struct Trap {
+    log: LogEvent,
+    response: Option<Vec<u8>>,
+}
+
+struct TrapDecoder {
+    /// The communities allowed to send traps, all of them if empty.
+    communities: HashSet<String>,
+    users: Users,
+    mib: Option<Mib>,
+}
+
+impl TrapDecoder {
+    fn decode(&self, data: &[u8]) -> Result<Trap, TrapError> {
+        let mut log = LogEvent::default();
+        let (version, pdu, response) = match message::decode(data)? {
+            Message::Community {
+                version,
+                community,
+                pdu,
+            } => {
+                let community_str = String::from_utf8_lossy(community);
+                if !self.communities.is_empty() && !self.communities.contains(&*community_str) {
+                    return Err(TrapError::CommunityNotAllowed {
+                        community: community_str.into_owned(),
+                    });
+                }
+                log.insert("community", community_str.into_owned());
+                let response = (pdu.pdu_type == PduType::Inform)
+                    .then(|| message::encode_inform_response(version, community, &pdu));
+                (version, pdu, response)
+            }
+            Message::V3(message) => {
+                let user = String::from_utf8_lossy(message.user_name).into_owned();
+                let engine_id = hex::encode(message.engine_id);
+                let scoped = self
+                    .users
+                    .process(message)
+                    .map_err(|source| TrapError::Usm { source })?;
+                log.insert("user", user);
+                log.insert("engine_id", engine_id);
+                log.insert("context_engine_id", hex::encode(&scoped.context_engine_id));
+                log.insert(
+                    "context_name",
+                    String::from_utf8_lossy(&scoped.context_name).into_owned(),
+                );
+                // Acknowledging SNMPv3 informs requires this source to act as
+                // their authoritative engine, which isn't supported.
+                (Version::V3, scoped.pdu, None)
+            }
+        };
+
+        log.insert("version", version.as_str());
+        log.insert("pdu_type", pdu.pdu_type.as_str());
+        if let Some(request_id) = pdu.request_id {
+            log.insert("request_id", request_id);
+        }
+        self.insert_pdu(&mut log, &pdu);
+
+        Ok(Trap { log, response })
+    }
+
+    fn insert_pdu(&self, log: &mut LogEvent, pdu: &Pdu) {
+        if let Some(v1) = &pdu.v1 {
+            log.insert("enterprise", v1.enterprise.to_string());
+            log.insert("agent_address", v1.agent_address.to_string());
+            log.insert("generic_trap", v1.generic_trap);
+            log.insert("specific_trap", v1.specific_trap);
+        }
+        if let Some(uptime) = pdu.uptime() {
+            log.insert("uptime", uptime);
+        }
+
+        let trap_oid = pdu.trap_oid();
+        let trap_name = trap_oid.as_ref().and_then(|oid| self.translate(oid));
+        let message = match (&trap_name, &trap_oid) {
+            (Some(name), _) => name.clone(),
+            (None, Some(oid)) => oid.to_string(),
+            (None, None) => pdu.pdu_type.as_str().to_string(),
+        };
+        log.insert(log_schema().message_key(), message);
+        if let Some(oid) = trap_oid {
+            log.insert("trap_oid", oid.to_string());
+        }
+        if let Some(name) = trap_name {
+            log.insert("trap_name", name);
+        }
+
+        let variables = pdu
+            .variables
+            .iter()
+            .map(|variable| {
+                let mut object = BTreeMap::new();
+                object.insert("oid".to_string(), variable.oid.to_string().into());
+                if let Some(name) = self.translate(&variable.oid) {
+                    object.insert("name".to_string(), name.into());
+                }
+                object.insert("type".to_string(), variable.value.type_name().into());
+                object.insert("value".to_string(), self.value(&variable.value));
+                Value::Object(object)
+            })
+            .collect::<Vec<_>>();
+        log.insert("variables", variables);
+    }
+
+    fn translate(&self, oid: &Oid) -> Option<String> {
+        self.mib.as_ref().and_then(|mib| mib.translate(oid))
+    }
+
+    fn value(&self, value: &VarValue) -> Value {
+        match value {
+            VarValue::Integer(value) => Value::Integer(*value),
+            VarValue::OctetString(bytes) => octet_string(bytes),
+            VarValue::ObjectIdentifier(oid) => self
+                .translate(oid)
+                .unwrap_or_else(|| oid.to_string())
+                .into(),
+            VarValue::IpAddress(address) => address.to_string().into(),
+            VarValue::Counter32(value) | VarValue::Gauge32(value) | VarValue::TimeTicks(value) => {
+                Value::Integer(*value as i64)
+            }
+            VarValue::Counter64(value) => match i64::try_from(*value) {
+                Ok(value) => Value::Integer(value),
+                Err(_) => Value::Float(NotNan::new(*value as f64).expect("not NaN")),
+            },
+            VarValue::Opaque(bytes) => hex_string(bytes).into(),
+            VarValue::Null
+            | VarValue::NoSuchObject
+            | VarValue::NoSuchInstance
+            | VarValue::EndOfMibView => Value::Null,
+        }
+    }
+}
+
+/// Octet strings hold both text and binary data, the latter being rendered as
+/// colon separated hexadecimal bytes like `net-snmp` does.
+fn octet_string(bytes: &[u8]) -> Value {
+    match std::str::from_utf8(bytes) {
+        Ok(text) if !text.chars().any(|c| c.is_control() && !c.is_whitespace()) => {
+            text.to_string().into()
+        }
+        _ => hex_string(bytes).into(),
+    }
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+async fn snmp_trap_udp(
+    address: SocketAddr,
+    receive_buffer_bytes: Option<usize>,
+    host_key: String,
+    decoder: TrapDecoder,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let mut receiver = UdpReceiver::bind(address, MAX_DATAGRAM_LENGTH, receive_buffer_bytes)
+        .map_err(|error| emit!(SnmpTrapSocketError::bind(error)))
+        .await?;
+
+    while let Some(recv) = receiver.recv(&mut shutdown).await {
+        let (datagram, peer) = match recv {
+            Ok(recv) => recv,
+            Err(error) => {
+                emit!(SnmpTrapSocketError::read(error));
+                continue;
+            }
+        };
+
+        let trap = match decoder.decode(&datagram) {
+            Ok(trap) => trap,
+            Err(error) => {
+                emit!(SnmpTrapInvalidMessage {
+                    error: &error,
+                    peer
+                });
+                continue;
+            }
+        };
+
+        if let Some(response) = trap.response {
+            if let Err(error) = receiver.socket().send_to(&response, peer).await {
+                emit!(SnmpTrapInformResponseError { error, peer });
+            }
+        }
+
+        let mut log = trap.log;
+        log.try_insert(log_schema().source_type_key(), Bytes::from("snmp_trap"));
+        log.try_insert(log_schema().timestamp_key(), Utc::now());
+        log.try_insert(host_key.as_str(), peer.ip().to_string());
+
+        emit!(EventsReceived {
+            count: 1,
+            byte_size: log.size_of(),
+        });
+
+        tokio::select! {
+            result = out.send(log.into()) => if let Err(error) = result {
+                emit!(StreamClosedError { error, count: 1 });
+                return Err(());
+            },
+            _ = &mut shutdown => break,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use futures::StreamExt;
+    use tokio::time::{timeout, Duration};
+
+    use super::{
+        ber::{tag, write, write_integer, Reader},
+        *,
+    };
+    use crate::test_util::next_addr;
+
+    const LINK_DOWN: &str = "1.3.6.1.6.3.1.1.5.3";
+
+    fn oid(value: &str) -> Vec<u8> {
+        let arcs = value.parse::<Oid>().unwrap().0;
+        let mut contents = vec![(arcs[0] * 40 + arcs[1]) as u8];
+        for arc in &arcs[2..] {
+            let mut bytes = vec![(arc & 0x7f) as u8];
+            let mut rest = arc >> 7;
+            while rest > 0 {
+                bytes.push((rest & 0x7f) as u8 | 0x80);
+                rest >>= 7;
+            }
+            contents.extend(bytes.iter().rev());
+        }
+        let mut out = Vec::new();
+        write(tag::OBJECT_IDENTIFIER, &contents, &mut out);
+        out
+    }
+
+    fn value(tag: u8, contents: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        write(tag, contents, &mut out);
+        out
+    }
+
+    fn integer(value: i64) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_integer(value, &mut out);
+        out
+    }
+
+    fn variables(variables: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let list = variables
+            .iter()
+            .flat_map(|(name, data)| value(tag::SEQUENCE, &[oid(name), data.clone()].concat()))
+            .collect::<Vec<_>>();
+        value(tag::SEQUENCE, &list)
+    }
+
+    fn v2c_message(pdu_tag: u8, community: &str) -> Vec<u8> {
+        let pdu = [
+            integer(42),
+            integer(0),
+            integer(0),
+            variables(&[
+                ("1.3.6.1.2.1.1.3.0", value(tag::TIME_TICKS, &[0x30, 0x39])),
+                ("1.3.6.1.6.3.1.1.4.1.0", oid(LINK_DOWN)),
+                ("1.3.6.1.2.1.2.2.1.1.2", integer(2)),
+                ("1.3.6.1.2.1.2.2.1.2.2", value(tag::OCTET_STRING, b"eth0")),
+                (
+                    "1.3.6.1.2.1.2.2.1.6.2",
+                    value(tag::OCTET_STRING, &[0, 0x1b, 0x21]),
+                ),
+            ]),
+        ]
+        .concat();
+        let message = [
+            integer(1),
+            value(tag::OCTET_STRING, community.as_bytes()),
+            value(pdu_tag, &pdu),
+        ]
+        .concat();
+        value(tag::SEQUENCE, &message)
+    }
+
+    fn v1_message(generic_trap: i64, specific_trap: i64) -> Vec<u8> {
+        let pdu = [
+            oid("1.3.6.1.4.1.8072.2.3"),
+            value(tag::IP_ADDRESS, &[192, 168, 1, 10]),
+            integer(generic_trap),
+            integer(specific_trap),
+            value(tag::TIME_TICKS, &[0x01, 0x00]),
+            variables(&[("1.3.6.1.2.1.2.2.1.1.2", integer(2))]),
+        ]
+        .concat();
+        let message = [
+            integer(0),
+            value(tag::OCTET_STRING, b"public"),
+            value(tag::TRAP_V1_PDU, &pdu),
+        ]
+        .concat();
+        value(tag::SEQUENCE, &message)
+    }
+
+    fn decoder(communities: &[&str]) -> TrapDecoder {
+        TrapDecoder {
+            communities: communities.iter().map(ToString::to_string).collect(),
+            users: Users::default(),
+            mib: None,
+        }
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnmpTrapConfig>();
+    }
+
+    #[test]
+    fn decodes_v2c_traps() {
+        let trap = decoder(&[])
+            .decode(&v2c_message(tag::TRAP_V2_PDU, "public"))
+            .unwrap();
+        assert!(trap.response.is_none());
+
+        let log = trap.log;
+        assert_eq!(log["version"], "2c".into());
+        assert_eq!(log["community"], "public".into());
+        assert_eq!(log["pdu_type"], "trap".into());
+        assert_eq!(log["request_id"], 42.into());
+        assert_eq!(log["uptime"], 12345.into());
+        assert_eq!(log["trap_oid"], LINK_DOWN.into());
+        assert_eq!(log[log_schema().message_key()], LINK_DOWN.into());
+        assert_eq!(log["variables[2].oid"], "1.3.6.1.2.1.2.2.1.1.2".into());
+        assert_eq!(log["variables[2].type"], "integer".into());
+        assert_eq!(log["variables[2].value"], 2.into());
+        assert_eq!(log["variables[3].value"], "eth0".into());
+        assert_eq!(log["variables[4].value"], "00:1b:21".into());
+        assert!(log.get("variables[0].name").is_none());
+    }
+
+    #[test]
+    fn converts_v1_traps() {
+        let log = decoder(&[]).decode(&v1_message(2, 0)).unwrap().log;
+        assert_eq!(log["version"], "1".into());
+        assert_eq!(log["pdu_type"], "trap_v1".into());
+        assert_eq!(log["enterprise"], "1.3.6.1.4.1.8072.2.3".into());
+        assert_eq!(log["agent_address"], "192.168.1.10".into());
+        assert_eq!(log["uptime"], 256.into());
+        assert_eq!(log["trap_oid"], LINK_DOWN.into());
+        assert!(log.get("request_id").is_none());
+
+        let log = decoder(&[]).decode(&v1_message(6, 17)).unwrap().log;
+        assert_eq!(log["trap_oid"], "1.3.6.1.4.1.8072.2.3.0.17".into());
+    }
+
+    #[test]
+    fn filters_communities() {
+        let message = v2c_message(tag::TRAP_V2_PDU, "public");
+        assert!(decoder(&["public"]).decode(&message).is_ok());
+        assert!(matches!(
+            decoder(&["private"]).decode(&message),
+            Err(TrapError::CommunityNotAllowed { community }) if community == "public"
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_users() {
+        let message = [
+            integer(3),
+            value(
+                tag::SEQUENCE,
+                &[
+                    integer(1),
+                    integer(65507),
+                    value(tag::OCTET_STRING, &[0x04]),
+                    integer(3),
+                ]
+                .concat(),
+            ),
+            value(
+                tag::OCTET_STRING,
+                &value(
+                    tag::SEQUENCE,
+                    &[
+                        value(tag::OCTET_STRING, &[0x80, 0, 0x1f, 0x88, 0x04]),
+                        integer(0),
+                        integer(0),
+                        value(tag::OCTET_STRING, b"operator"),
+                        value(tag::OCTET_STRING, b""),
+                        value(tag::OCTET_STRING, b""),
+                    ]
+                    .concat(),
+                ),
+            ),
+            value(
+                tag::SEQUENCE,
+                &[
+                    value(tag::OCTET_STRING, b""),
+                    value(tag::OCTET_STRING, b""),
+                    value(
+                        tag::TRAP_V2_PDU,
+                        &[integer(1), integer(0), integer(0), variables(&[])].concat(),
+                    ),
+                ]
+                .concat(),
+            ),
+        ]
+        .concat();
+        assert!(matches!(
+            decoder(&[]).decode(&value(tag::SEQUENCE, &message)),
+            Err(TrapError::Usm {
+                source: UsmError::UnknownUser { .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_messages() {
+        assert!(matches!(
+            decoder(&[]).decode(&[0x30, 0x03, 0x02, 0x01]),
+            Err(TrapError::InvalidMessage { .. })
+        ));
+        let message = value(
+            tag::SEQUENCE,
+            &[integer(2), value(tag::OCTET_STRING, b"public")].concat(),
+        );
+        assert!(matches!(
+            decoder(&[]).decode(&message),
+            Err(TrapError::UnsupportedVersion { version: 2 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn acknowledges_informs() {
+        let (tx, mut rx) = SourceSender::new_test();
+        let address = next_addr();
+        let source = SnmpTrapConfig::from_address(address)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+
+        let socket = UdpSocket::bind(next_addr()).await.unwrap();
+        let message = v2c_message(tag::INFORM_REQUEST_PDU, "public");
+        let mut buf = [0; 1024];
+        // The source may not be listening yet, so retry until it answers.
+        let length = loop {
+            socket.send_to(&message, address).await.unwrap();
+            if let Ok(received) = timeout(Duration::from_millis(100), socket.recv(&mut buf)).await {
+                break received.unwrap();
+            }
+        };
+
+        let mut response = Reader::new(&buf[..length]).read_sequence().unwrap();
+        assert_eq!(response.read_integer(), Ok(1));
+        assert_eq!(response.read_octet_string(), Ok(&b"public"[..]));
+        let mut pdu = response.read(tag::RESPONSE_PDU).unwrap();
+        assert_eq!(pdu.read_integer(), Ok(42));
+        assert_eq!(pdu.read_integer(), Ok(0));
+
+        let event = rx.next().await.unwrap();
+        let log = event.as_log();
+        assert_eq!(log["pdu_type"], "inform".into());
+        assert_eq!(log[log_schema().host_key()], "127.0.0.1".into());
+        assert_eq!(log[log_schema().source_type_key()], "snmp_trap".into());
+    }
+}
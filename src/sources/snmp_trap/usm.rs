@@ -0,0 +1,448 @@
+//! The User-based Security Model of SNMPv3, authenticating and decrypting the
+//! messages of the configured users as described by RFC 3414, RFC 3826 and
+//! RFC 7860.
+//!
+//! The agents sending traps are their authoritative engines, so the keys are
+//! localized with the engine ID of each message, and the authenticated messages
+//! are checked against the time of their engine as this receiver knows it.
+
+use std::{collections::HashMap, sync::Mutex, time::Instant};
+
+use cbc::cipher::{block_padding::NoPadding, AsyncStreamCipher, BlockDecryptMut, KeyIvInit};
+use hmac::{
+    digest::{core_api::BlockSizeUser, Digest},
+    Mac, SimpleHmac,
+};
+use serde::{Deserialize, Serialize};
+use snafu::Snafu;
+
+use super::message::{
+    decode_scoped_pdu, ScopedPdu, ScopedPduData, V3Message, FLAG_AUTH, FLAG_PRIV,
+    USM_SECURITY_MODEL,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UserConfig {
+    name: String,
+    auth_protocol: Option<AuthProtocol>,
+    auth_password: Option<String>,
+    priv_protocol: Option<PrivProtocol>,
+    priv_password: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthProtocol {
+    Md5,
+    Sha,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivProtocol {
+    Des,
+    Aes,
+}
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum UserConfigError {
+    #[snafu(display("User {:?} has an `auth_protocol` without `auth_password`", name))]
+    MissingAuthPassword { name: String },
+    #[snafu(display("User {:?} has a `priv_protocol` without `priv_password`", name))]
+    MissingPrivPassword { name: String },
+    #[snafu(display("User {:?} has a `priv_protocol` without `auth_protocol`", name))]
+    PrivWithoutAuth { name: String },
+    #[snafu(display("The passwords of user {:?} must have at least 8 characters", name))]
+    ShortPassword { name: String },
+    #[snafu(display("User {:?} is configured more than once", name))]
+    DuplicateUser { name: String },
+}
+
+#[derive(Debug, PartialEq, Snafu)]
+pub enum UsmError {
+    #[snafu(display("Unsupported security model {}", model))]
+    UnsupportedSecurityModel { model: i64 },
+    #[snafu(display("Unknown user {:?}", name))]
+    UnknownUser { name: String },
+    #[snafu(display("Message of user {:?} isn't authenticated", name))]
+    NotAuthenticated { name: String },
+    #[snafu(display("Message of user {:?} isn't encrypted", name))]
+    NotEncrypted { name: String },
+    #[snafu(display("Message of user {:?} has an unsupported security level", name))]
+    UnsupportedSecurityLevel { name: String },
+    #[snafu(display("Authentication of user {:?} failed", name))]
+    AuthenticationFailed { name: String },
+    #[snafu(display("Decryption of the message of user {:?} failed", name))]
+    DecryptionFailed { name: String },
+    #[snafu(display("Message of user {:?} is outside of the time window", name))]
+    NotInTimeWindow { name: String },
+}
+
+/// How far the time of an authenticated message may be behind the time of its
+/// engine, in seconds (RFC 3414, section 3.2).
+const TIME_WINDOW: i64 = 150;
+
+/// The value of `snmpEngineBoots` at which an engine must be reconfigured.
+const MAX_ENGINE_BOOTS: i64 = 2_147_483_647;
+
+/// The time of an authoritative engine, as known from the messages received
+/// from it (RFC 3414, section 2.3).
+#[derive(Debug)]
+struct EngineTime {
+    boots: i64,
+    time: i64,
+    latest_received_time: i64,
+    updated: Instant,
+}
+
+impl EngineTime {
+    fn new(boots: i64, time: i64, now: Instant) -> Self {
+        Self {
+            boots,
+            time,
+            latest_received_time: time,
+            updated: now,
+        }
+    }
+
+    /// The time of the engine, which keeps running since the last update.
+    fn current_time(&self, now: Instant) -> i64 {
+        self.time + now.saturating_duration_since(self.updated).as_secs() as i64
+    }
+}
+
+#[derive(Debug)]
+struct User {
+    auth: Option<(AuthProtocol, Vec<u8>)>,
+    privacy: Option<(PrivProtocol, Vec<u8>)>,
+}
+
+/// The configured users, with the keys derived from their passwords, and the
+/// times of the engines authenticated messages were received from.
+#[derive(Debug, Default)]
+pub(super) struct Users {
+    users: HashMap<Vec<u8>, User>,
+    engines: Mutex<HashMap<Vec<u8>, EngineTime>>,
+}
+
+impl Users {
+    pub(super) fn new(configs: &[UserConfig]) -> Result<Self, UserConfigError> {
+        let mut users = HashMap::new();
+        for config in configs {
+            let name = config.name.clone();
+            let auth = match (config.auth_protocol, &config.auth_password) {
+                (Some(protocol), Some(password)) => {
+                    check_password(&name, password)?;
+                    Some((protocol, password_to_key(protocol, password.as_bytes())))
+                }
+                (Some(_), None) => return Err(UserConfigError::MissingAuthPassword { name }),
+                (None, _) => None,
+            };
+            let privacy = match (config.priv_protocol, &config.priv_password, &auth) {
+                (Some(_), _, None) => return Err(UserConfigError::PrivWithoutAuth { name }),
+                (Some(_), None, _) => return Err(UserConfigError::MissingPrivPassword { name }),
+                (Some(protocol), Some(password), Some((auth_protocol, _))) => {
+                    check_password(&name, password)?;
+                    // The privacy keys are derived with the authentication
+                    // hash function.
+                    Some((
+                        protocol,
+                        password_to_key(*auth_protocol, password.as_bytes()),
+                    ))
+                }
+                (None, _, _) => None,
+            };
+            if users
+                .insert(name.clone().into_bytes(), User { auth, privacy })
+                .is_some()
+            {
+                return Err(UserConfigError::DuplicateUser { name });
+            }
+        }
+        Ok(Self {
+            users,
+            engines: Mutex::default(),
+        })
+    }
+
+    /// Authenticates and decrypts the scoped PDU of a message, according to its
+    /// security level and the one of its user.
+    pub(super) fn process(&self, message: V3Message<'_>) -> Result<ScopedPdu, UsmError> {
+        if message.security_model != USM_SECURITY_MODEL {
+            return Err(UsmError::UnsupportedSecurityModel {
+                model: message.security_model,
+            });
+        }
+        let name = String::from_utf8_lossy(message.user_name).into_owned();
+        let user = self
+            .users
+            .get(message.user_name)
+            .ok_or_else(|| UsmError::UnknownUser { name: name.clone() })?;
+
+        let authenticated = message.flags & FLAG_AUTH != 0;
+        let encrypted = message.flags & FLAG_PRIV != 0;
+        match (&user.auth, authenticated) {
+            (Some((protocol, key)), true) => {
+                let key = localize(*protocol, key, message.engine_id);
+                if !authenticate(*protocol, &key, &message) {
+                    return Err(UsmError::AuthenticationFailed { name });
+                }
+                if !self.in_time_window(
+                    message.engine_id,
+                    message.engine_boots,
+                    message.engine_time,
+                    Instant::now(),
+                ) {
+                    return Err(UsmError::NotInTimeWindow { name });
+                }
+            }
+            (Some(_), false) => return Err(UsmError::NotAuthenticated { name }),
+            (None, true) => return Err(UsmError::UnsupportedSecurityLevel { name }),
+            (None, false) => {}
+        }
+
+        match (&user.privacy, encrypted, message.data) {
+            (Some((protocol, key)), true, ScopedPduData::Encrypted(data)) => {
+                let auth_protocol = user.auth.as_ref().expect("privacy requires auth").0;
+                let key = localize(auth_protocol, key, message.engine_id);
+                let plaintext = decrypt(
+                    *protocol,
+                    &key,
+                    message.priv_parameters,
+                    message.engine_boots,
+                    message.engine_time,
+                    data,
+                )
+                .ok_or_else(|| UsmError::DecryptionFailed { name: name.clone() })?;
+                decode_scoped_pdu(&plaintext).map_err(|_| UsmError::DecryptionFailed { name })
+            }
+            (Some(_), false, _) => Err(UsmError::NotEncrypted { name }),
+            (None, false, ScopedPduData::Plaintext(scoped)) => Ok(scoped),
+            _ => Err(UsmError::UnsupportedSecurityLevel { name }),
+        }
+    }
+
+    /// Updates the time of the engine of an authenticated message, and checks
+    /// that the message is within its time window (RFC 3414, section 3.2 step 7b).
+    fn in_time_window(&self, engine_id: &[u8], boots: i64, time: i64, now: Instant) -> bool {
+        let mut engines = self.engines.lock().expect("poisoned lock");
+        let engine = engines
+            .entry(engine_id.to_vec())
+            .or_insert_with(|| EngineTime::new(boots, time, now));
+        if boots > engine.boots || (boots == engine.boots && time > engine.latest_received_time) {
+            *engine = EngineTime::new(boots, time, now);
+        }
+
+        // Newer messages updated the engine above, so only older ones are checked.
+        engine.boots != MAX_ENGINE_BOOTS
+            && boots == engine.boots
+            && time >= engine.current_time(now) - TIME_WINDOW
+    }
+}
+
+fn check_password(name: &str, password: &str) -> Result<(), UserConfigError> {
+    if password.len() < 8 {
+        return Err(UserConfigError::ShortPassword { name: name.into() });
+    }
+    Ok(())
+}
+
+/// Calls a generic function with the hash function of a protocol.
+macro_rules! with_digest {
+    ($protocol:expr, $function:ident($($arg:expr),*)) => {
+        match $protocol {
+            AuthProtocol::Md5 => $function::<md5::Md5>($($arg),*),
+            AuthProtocol::Sha => $function::<sha1::Sha1>($($arg),*),
+            AuthProtocol::Sha224 => $function::<sha2::Sha224>($($arg),*),
+            AuthProtocol::Sha256 => $function::<sha2::Sha256>($($arg),*),
+            AuthProtocol::Sha384 => $function::<sha2::Sha384>($($arg),*),
+            AuthProtocol::Sha512 => $function::<sha2::Sha512>($($arg),*),
+        }
+    };
+}
+
+/// Derives a key from a password, hashing it repeated over a megabyte.
+fn password_to_key(protocol: AuthProtocol, password: &[u8]) -> Vec<u8> {
+    fn hash<D: Digest>(password: &[u8]) -> Vec<u8> {
+        const EXPANDED_LENGTH: usize = 1_048_576;
+        let mut hasher = D::new();
+        let mut chunk = [0; 64];
+        let mut index = 0;
+        for _ in 0..EXPANDED_LENGTH / chunk.len() {
+            for byte in chunk.iter_mut() {
+                *byte = password[index % password.len()];
+                index += 1;
+            }
+            hasher.update(&chunk);
+        }
+        hasher.finalize().to_vec()
+    }
+    with_digest!(protocol, hash(password))
+}
+
+/// Localizes a key to an engine.
+fn localize(protocol: AuthProtocol, key: &[u8], engine_id: &[u8]) -> Vec<u8> {
+    fn hash<D: Digest>(key: &[u8], engine_id: &[u8]) -> Vec<u8> {
+        D::new()
+            .chain_update(key)
+            .chain_update(engine_id)
+            .chain_update(key)
+            .finalize()
+            .to_vec()
+    }
+    with_digest!(protocol, hash(key, engine_id))
+}
+
+/// Verifies the truncated HMAC of the message, computed with its authentication
+/// parameters zeroed.
+fn authenticate(protocol: AuthProtocol, key: &[u8], message: &V3Message<'_>) -> bool {
+    fn verify<D: Digest + BlockSizeUser>(key: &[u8], data: &[u8], tag: &[u8]) -> bool {
+        let mut mac =
+            <SimpleHmac<D> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(data);
+        mac.verify_truncated_left(tag).is_ok()
+    }
+
+    let length = match protocol {
+        AuthProtocol::Md5 | AuthProtocol::Sha => 12,
+        AuthProtocol::Sha224 => 16,
+        AuthProtocol::Sha256 => 24,
+        AuthProtocol::Sha384 => 32,
+        AuthProtocol::Sha512 => 48,
+    };
+    if message.auth_parameters.len() != length {
+        return false;
+    }
+    let mut data = message.whole.to_vec();
+    let offset = message.auth_parameters_offset;
+    data[offset..offset + length].fill(0);
+    with_digest!(protocol, verify(key, &data, message.auth_parameters))
+}
+
+/// Decrypts a scoped PDU, returning `None` when the parameters are invalid.
+fn decrypt(
+    protocol: PrivProtocol,
+    key: &[u8],
+    salt: &[u8],
+    engine_boots: i64,
+    engine_time: i64,
+    data: &[u8],
+) -> Option<Vec<u8>> {
+    if salt.len() != 8 {
+        return None;
+    }
+    let mut buffer = data.to_vec();
+    match protocol {
+        PrivProtocol::Des => {
+            // The second half of the key is XORed with the salt for the IV.
+            let key = key.get(..16)?;
+            let iv = key[8..]
+                .iter()
+                .zip(salt)
+                .map(|(key, salt)| key ^ salt)
+                .collect::<Vec<_>>();
+            cbc::Decryptor::<des::Des>::new_from_slices(&key[..8], &iv)
+                .ok()?
+                .decrypt_padded_mut::<NoPadding>(&mut buffer)
+                .ok()?;
+        }
+        PrivProtocol::Aes => {
+            let mut iv = Vec::with_capacity(16);
+            iv.extend_from_slice(&(engine_boots as u32).to_be_bytes());
+            iv.extend_from_slice(&(engine_time as u32).to_be_bytes());
+            iv.extend_from_slice(salt);
+            cfb_mode::Decryptor::<aes::Aes128>::new_from_slices(key.get(..16)?, &iv)
+                .ok()?
+                .decrypt(&mut buffer);
+        }
+    }
+    Some(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(auth: Option<AuthProtocol>, privacy: Option<PrivProtocol>) -> UserConfig {
+        UserConfig {
+            name: "user".into(),
+            auth_protocol: auth,
+            auth_password: auth.map(|_| "authpassword".into()),
+            priv_protocol: privacy,
+            priv_password: privacy.map(|_| "privpassword".into()),
+        }
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn derives_keys_from_passwords() {
+        // The test vectors of RFC 3414, appendix A.3.
+        let engine_id = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+        let key = password_to_key(AuthProtocol::Md5, b"maplesyrup");
+        assert_eq!(hex(&key), "9faf3283884e92834ebc9847d8edd963");
+        assert_eq!(
+            hex(&localize(AuthProtocol::Md5, &key, &engine_id)),
+            "526f5eed9fcce26f8964c2930787d82b"
+        );
+
+        let key = password_to_key(AuthProtocol::Sha, b"maplesyrup");
+        assert_eq!(hex(&key), "9fb5cc0381497b3793528939ff788d5d79145211");
+        assert_eq!(
+            hex(&localize(AuthProtocol::Sha, &key, &engine_id)),
+            "6695febc9288e36282235fc7151f128497b38f3f"
+        );
+    }
+
+    #[test]
+    fn checks_time_windows() {
+        let users = Users::default();
+        let engine = b"engine";
+        let now = Instant::now();
+        assert!(users.in_time_window(engine, 2, 1000, now));
+        // The time of the engine keeps running.
+        let later = now + std::time::Duration::from_secs(100);
+        assert!(users.in_time_window(engine, 2, 950, later));
+        let later = now + std::time::Duration::from_secs(200);
+        assert!(!users.in_time_window(engine, 2, 1000, later));
+        assert!(users.in_time_window(engine, 2, 1100, later));
+        // Messages from before the engine rebooted are replays.
+        assert!(!users.in_time_window(engine, 1, 5000, later));
+        assert!(users.in_time_window(engine, 3, 10, later));
+        assert!(!users.in_time_window(engine, 2, 5000, later));
+        // The engines are independent.
+        assert!(users.in_time_window(b"other", 1, 10, later));
+    }
+
+    #[test]
+    fn validates_users() {
+        assert!(Users::new(&[user(Some(AuthProtocol::Sha), Some(PrivProtocol::Aes))]).is_ok());
+        assert_eq!(
+            Users::new(&[user(None, Some(PrivProtocol::Aes))]).unwrap_err(),
+            UserConfigError::PrivWithoutAuth {
+                name: "user".into()
+            }
+        );
+        let mut short = user(Some(AuthProtocol::Md5), None);
+        short.auth_password = Some("short".into());
+        assert_eq!(
+            Users::new(&[short]).unwrap_err(),
+            UserConfigError::ShortPassword {
+                name: "user".into()
+            }
+        );
+        assert_eq!(
+            Users::new(&[user(None, None), user(None, None)]).unwrap_err(),
+            UserConfigError::DuplicateUser {
+                name: "user".into()
+            }
+        );
+    }
+}
@@ -2,7 +2,7 @@ use std::net::SocketAddr;
 #[cfg(unix)]
 use std::path::PathBuf;
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use chrono::Utc;
 #[cfg(unix)]
 use codecs::Decoder;
@@ -10,15 +10,15 @@ use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use tokio::net::UdpSocket;
-use tokio_util::udp::UdpFramed;
+use tokio_util::{codec::Decoder as _, udp::UdpFramed};
 
 #[cfg(unix)]
 use crate::sources::util::build_unix_stream_source;
 use crate::{
     codecs::{
         self,
-        decoding::{Deserializer, Framer},
-        BytesDecoder, OctetCountingDecoder, SyslogDeserializer,
+        decoding::{BoxedFramingError, Deserializer, Framer},
+        BytesDecoder, NewlineDelimitedDecoder, OctetCountingDecoder, SyslogDeserializer,
     },
     config::{
         log_schema, DataType, GenerateConfig, Output, Resource, SourceConfig, SourceContext,
@@ -29,7 +29,7 @@ use crate::{
     shutdown::ShutdownSignal,
     sources::util::{SocketListenAddr, TcpNullAcker, TcpSource},
     tcp::TcpKeepaliveConfig,
-    tls::{MaybeTlsSettings, TlsConfig},
+    tls::{MaybeTlsSettings, PeerCertificate, TlsConfig},
     udp, SourceSender,
 };
 
@@ -43,6 +43,9 @@ pub struct SyslogConfig {
     max_length: usize,
     /// The host key of the log. (This differs from `hostname`)
     host_key: Option<String>,
+    /// The key of the identity of clients authenticated with a TLS
+    /// certificate, in TCP mode.
+    tls_peer_key: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -68,6 +71,7 @@ impl SyslogConfig {
         Self {
             mode,
             host_key: None,
+            tls_peer_key: None,
             max_length: crate::serde::default_max_length(),
         }
     }
@@ -88,6 +92,7 @@ impl GenerateConfig for SyslogConfig {
                 connection_limit: None,
             },
             host_key: None,
+            tls_peer_key: None,
             max_length: crate::serde::default_max_length(),
         })
         .unwrap()
@@ -114,6 +119,10 @@ impl SourceConfig for SyslogConfig {
                 let source = SyslogTcpSource {
                     max_length: self.max_length,
                     host_key,
+                    tls_peer_key: self
+                        .tls_peer_key
+                        .clone()
+                        .unwrap_or_else(default_tls_peer_key),
                 };
                 let shutdown_secs = 30;
                 let tls = MaybeTlsSettings::from_config(&tls, true)?;
@@ -181,10 +190,15 @@ impl SourceConfig for SyslogConfig {
     }
 }
 
+fn default_tls_peer_key() -> String {
+    "tls_peer".to_string()
+}
+
 #[derive(Debug, Clone)]
 struct SyslogTcpSource {
     max_length: usize,
     host_key: String,
+    tls_peer_key: String,
 }
 
 impl TcpSource for SyslogTcpSource {
@@ -195,7 +209,7 @@ impl TcpSource for SyslogTcpSource {
 
     fn decoder(&self) -> Self::Decoder {
         codecs::Decoder::new(
-            Framer::OctetCounting(OctetCountingDecoder::new_with_max_length(self.max_length)),
+            Framer::Boxed(Box::new(SyslogFramer::new(self.max_length))),
             Deserializer::Syslog(SyslogDeserializer),
         )
     }
@@ -204,11 +218,91 @@ impl TcpSource for SyslogTcpSource {
         handle_events(events, &self.host_key, Some(host));
     }
 
+    fn handle_peer_certificate(&self, events: &mut [Event], certificate: &PeerCertificate) {
+        for event in events {
+            let log = event.as_mut_log();
+            log.insert(
+                format!("{}.subject", self.tls_peer_key).as_str(),
+                certificate.subject.clone(),
+            );
+            log.insert(
+                format!("{}.subject_alt_names", self.tls_peer_key).as_str(),
+                certificate.subject_alt_names.clone(),
+            );
+        }
+    }
+
     fn build_acker(&self, _: &[Self::Item]) -> Self::Acker {
         TcpNullAcker
     }
 }
 
+/// Frames a TCP connection as octet counted (RFC 6587) if its first message
+/// starts with a non-zero digit, and as newline delimited otherwise.
+///
+/// The framing is picked for the whole connection from its first byte, and
+/// never detected again: a client switching framing within a connection isn't
+/// supported. Detecting it per frame instead would mistake newline delimited
+/// messages starting with a digit, like RFC 3164 messages without priority, for
+/// octet counted ones.
+#[derive(Clone, Debug)]
+enum SyslogFramer {
+    Undetected { max_length: usize },
+    OctetCounting(OctetCountingDecoder),
+    NewlineDelimited(NewlineDelimitedDecoder),
+}
+
+impl SyslogFramer {
+    const fn new(max_length: usize) -> Self {
+        Self::Undetected { max_length }
+    }
+
+    /// Picks the framing from the first byte received, if any.
+    fn detect(&mut self, src: &BytesMut) {
+        if let Self::Undetected { max_length } = *self {
+            *self = match src.first() {
+                Some(b'1'..=b'9') => {
+                    Self::OctetCounting(OctetCountingDecoder::new_with_max_length(max_length))
+                }
+                Some(_) => {
+                    Self::NewlineDelimited(NewlineDelimitedDecoder::new_with_max_length(max_length))
+                }
+                None => return,
+            };
+        }
+    }
+}
+
+impl tokio_util::codec::Decoder for SyslogFramer {
+    type Item = Bytes;
+    type Error = BoxedFramingError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.detect(src);
+        match self {
+            Self::Undetected { .. } => Ok(None),
+            Self::OctetCounting(framer) => framer.decode(src),
+            Self::NewlineDelimited(framer) => Ok(framer.decode(src)?.map(trim_carriage_return)),
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.detect(src);
+        match self {
+            Self::Undetected { .. } => Ok(None),
+            Self::OctetCounting(framer) => framer.decode_eof(src),
+            Self::NewlineDelimited(framer) => Ok(framer.decode_eof(src)?.map(trim_carriage_return)),
+        }
+    }
+}
+
+fn trim_carriage_return(frame: Bytes) -> Bytes {
+    match frame.last() {
+        Some(b'\r') => frame.slice(..frame.len() - 1),
+        _ => frame,
+    }
+}
+
 pub fn udp(
     addr: SocketAddr,
     _max_length: usize,
@@ -310,6 +404,7 @@ fn enrich_syslog_event(event: &mut Event, host_key: &str, default_host: Option<B
 #[cfg(test)]
 mod test {
     use chrono::prelude::*;
+    use tokio_util::codec::Decoder;
     use vector_common::assert_event_data_eq;
 
     use super::*;
@@ -683,4 +778,58 @@ mod test {
             expected
         );
     }
+
+    fn frames(mut framer: SyslogFramer, input: &[u8]) -> Vec<Bytes> {
+        let mut buffer = BytesMut::from(input);
+        let mut frames = Vec::new();
+        while let Some(frame) = framer.decode_eof(&mut buffer).unwrap() {
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[test]
+    fn framer_detects_octet_counting() {
+        let input = b"5 first6 second";
+        assert_eq!(
+            frames(SyslogFramer::new(1024), input),
+            vec![Bytes::from("first"), Bytes::from("second")]
+        );
+    }
+
+    #[test]
+    fn framer_detects_newline_delimited() {
+        // A message without priority starting with a digit must not be taken
+        // for an octet count.
+        let input = b"<13>first\r\n2019-02-13T21:53:30 second\n";
+        assert_eq!(
+            frames(SyslogFramer::new(1024), input),
+            vec![
+                Bytes::from("<13>first"),
+                Bytes::from("2019-02-13T21:53:30 second")
+            ]
+        );
+    }
+
+    #[test]
+    fn adds_peer_certificate() {
+        let source = SyslogTcpSource {
+            max_length: 1024,
+            host_key: "host".to_string(),
+            tls_peer_key: default_tls_peer_key(),
+        };
+        let certificate = PeerCertificate {
+            subject: "CN=device-1,O=Example".to_string(),
+            subject_alt_names: vec!["DNS:device-1.example.com".to_string()],
+        };
+        let mut events = vec![Event::from("message")];
+        source.handle_peer_certificate(&mut events, &certificate);
+
+        let log = events[0].as_log();
+        assert_eq!(log["tls_peer.subject"], "CN=device-1,O=Example".into());
+        assert_eq!(
+            log["tls_peer.subject_alt_names[0]"],
+            "DNS:device-1.example.com".into()
+        );
+    }
 }
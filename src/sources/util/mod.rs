@@ -25,6 +25,12 @@ mod http;
 pub mod multiline_config;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
 mod tcp;
+#[cfg(any(
+    feature = "sources-netflow",
+    feature = "sources-snmp_trap",
+    feature = "sources-socket"
+))]
+mod udp;
 #[cfg(all(unix, feature = "sources-socket"))]
 mod unix_datagram;
 #[cfg(all(unix, feature = "sources-utils-unix"))]
@@ -41,6 +47,12 @@ pub use encoding_config::EncodingConfig;
 pub use multiline_config::MultilineConfig;
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
 pub use tcp::{SocketListenAddr, TcpNullAcker, TcpSource, TcpSourceAck, TcpSourceAcker};
+#[cfg(any(
+    feature = "sources-netflow",
+    feature = "sources-snmp_trap",
+    feature = "sources-socket"
+))]
+pub use udp::UdpReceiver;
 #[cfg(all(unix, feature = "sources-socket",))]
 pub use unix_datagram::build_unix_datagram_source;
 #[cfg(all(unix, feature = "sources-utils-unix",))]
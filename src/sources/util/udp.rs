@@ -0,0 +1,66 @@
+use std::{io, net::SocketAddr};
+
+use bytes::{Bytes, BytesMut};
+use tokio::net::UdpSocket;
+
+use crate::{internal_events::BytesReceived, shutdown::ShutdownSignal, udp};
+
+/// A UDP socket receiving the datagrams of a source, up to `max_length` bytes each.
+pub struct UdpReceiver {
+    socket: UdpSocket,
+    max_length: usize,
+    buf: BytesMut,
+}
+
+impl UdpReceiver {
+    /// Binds the socket, with the receive buffer set to `receive_buffer_bytes`, which also
+    /// bounds the length of the datagrams received.
+    pub async fn bind(
+        address: SocketAddr,
+        max_length: usize,
+        receive_buffer_bytes: Option<usize>,
+    ) -> io::Result<Self> {
+        let socket = UdpSocket::bind(&address).await?;
+
+        if let Some(receive_buffer_bytes) = receive_buffer_bytes {
+            if let Err(error) = udp::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+                warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+            }
+        }
+
+        let max_length = if let Some(receive_buffer_bytes) = receive_buffer_bytes {
+            std::cmp::min(max_length, receive_buffer_bytes)
+        } else {
+            max_length
+        };
+
+        info!(message = "Listening.", address = %address);
+
+        Ok(Self {
+            socket,
+            max_length,
+            buf: BytesMut::with_capacity(max_length),
+        })
+    }
+
+    /// Receives the next datagram and the address it was sent from, or `None` once the source
+    /// is shut down.
+    pub async fn recv(
+        &mut self,
+        shutdown: &mut ShutdownSignal,
+    ) -> Option<io::Result<(Bytes, SocketAddr)>> {
+        self.buf.resize(self.max_length, 0);
+        tokio::select! {
+            recv = self.socket.recv_from(&mut self.buf) => Some(recv.map(|(byte_size, address)| {
+                emit!(BytesReceived { byte_size, protocol: "udp" });
+                (self.buf.split_to(byte_size).freeze(), address)
+            })),
+            _ = shutdown => None,
+        }
+    }
+
+    /// The socket, to respond to the datagrams received.
+    pub const fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+}
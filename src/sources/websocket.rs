@@ -0,0 +1,360 @@
+use std::net::SocketAddr;
+
+use bytes::Bytes;
+use chrono::Utc;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_tungstenite::{accept_async, tungstenite::Message, WebSocketStream};
+use tokio_util::codec::FramedRead;
+use vector_core::ByteSizeOf;
+
+use crate::{
+    codecs::{
+        self,
+        decoding::{DecodingConfig, DeserializerConfig, FramingConfig},
+    },
+    config::{
+        log_schema, DataType, GenerateConfig, Output, Resource, SourceConfig, SourceContext,
+        SourceDescription,
+    },
+    event::Event,
+    internal_events::{
+        BytesReceived, EventsReceived, StreamClosedError, TcpSocketTlsConnectionError,
+        WebSocketConnectionError, WebSocketConnectionEstablished, WebSocketConnectionShutdown,
+        WebSocketReceiveError,
+    },
+    serde::{default_decoding, default_framing_message_based},
+    shutdown::ShutdownSignal,
+    tls::{MaybeTlsListener, MaybeTlsSettings, TlsConfig},
+    websocket::WebSocketConnector,
+    SourceSender,
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WebSocketSourceConfig {
+    #[serde(flatten)]
+    mode: Mode,
+    host_key: Option<String>,
+    #[serde(default = "default_framing_message_based")]
+    framing: FramingConfig,
+    #[serde(default = "default_decoding")]
+    decoding: DeserializerConfig,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+enum Mode {
+    /// Accepts connections from WebSocket clients.
+    Server {
+        address: SocketAddr,
+        tls: Option<TlsConfig>,
+    },
+    /// Connects to a WebSocket server, reconnecting when the connection is
+    /// lost.
+    Client { uri: String, tls: Option<TlsConfig> },
+}
+
+inventory::submit! {
+    SourceDescription::new::<WebSocketSourceConfig>("websocket")
+}
+
+impl GenerateConfig for WebSocketSourceConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"
+            mode = "server"
+            address = "0.0.0.0:8080""#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "websocket")]
+impl SourceConfig for WebSocketSourceConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let decoder = DecodingConfig::new(self.framing.clone(), self.decoding.clone()).build();
+        let host_key = self
+            .host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().to_string());
+        let handler = MessageHandler { decoder, host_key };
+
+        match &self.mode {
+            Mode::Server { address, tls } => {
+                let tls = MaybeTlsSettings::from_config(tls, true)?;
+                let listener = tls.bind(address).await?;
+                info!(message = "Listening.", address = %address);
+                Ok(Box::pin(websocket_server(
+                    listener,
+                    handler,
+                    cx.shutdown,
+                    cx.out,
+                )))
+            }
+            Mode::Client { uri, tls } => {
+                let tls = MaybeTlsSettings::from_config(tls, false)?;
+                let connector = WebSocketConnector::new(uri, tls)?;
+                Ok(Box::pin(websocket_client(
+                    connector,
+                    handler,
+                    cx.shutdown,
+                    cx.out,
+                )))
+            }
+        }
+    }
+
+    fn outputs(&self) -> Vec<Output> {
+        vec![Output::default(DataType::Log)]
+    }
+
+    fn source_type(&self) -> &'static str {
+        "websocket"
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        match &self.mode {
+            Mode::Server { address, .. } => vec![Resource::tcp(*address)],
+            Mode::Client { .. } => Vec::new(),
+        }
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// Decodes the data messages of a connection into events.
+#[derive(Clone)]
+struct MessageHandler {
+    decoder: codecs::Decoder,
+    host_key: String,
+}
+
+impl MessageHandler {
+    async fn decode(&self, data: &[u8], host: Option<&str>) -> Vec<Event> {
+        emit!(BytesReceived {
+            byte_size: data.len(),
+            protocol: "websocket",
+        });
+
+        let mut events = Vec::new();
+        let mut stream = FramedRead::new(data, self.decoder.clone());
+        while let Some(next) = stream.next().await {
+            match next {
+                Ok((decoded, _byte_size)) => events.extend(decoded),
+                Err(error) => {
+                    // Error is logged by `crate::codecs::Decoder`, no further
+                    // handling is needed here.
+                    if !error.can_continue() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let now = Utc::now();
+        for event in &mut events {
+            if let Event::Log(log) = event {
+                log.try_insert(log_schema().source_type_key(), Bytes::from("websocket"));
+                log.try_insert(log_schema().timestamp_key(), now);
+                if let Some(host) = host {
+                    log.try_insert(self.host_key.as_str(), host.to_owned());
+                }
+            }
+        }
+        events
+    }
+
+    /// Forwards the events of a connection until it's closed, returning an
+    /// error if the output is closed.
+    async fn handle<S>(
+        &self,
+        mut ws_stream: WebSocketStream<S>,
+        peer_addr: Option<SocketAddr>,
+        host: Option<&str>,
+        mut shutdown: ShutdownSignal,
+        out: &mut SourceSender,
+    ) -> Result<(), ()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        loop {
+            let message = tokio::select! {
+                message = ws_stream.next() => message,
+                _ = &mut shutdown => {
+                    let _ = ws_stream.close(None).await;
+                    return Ok(());
+                }
+            };
+
+            // Pings are answered by the stream itself while reading.
+            let data = match message {
+                Some(Ok(Message::Text(text))) => text.into_bytes(),
+                Some(Ok(Message::Binary(data))) => data,
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => continue,
+                Some(Err(error)) => {
+                    emit!(WebSocketReceiveError { error, peer_addr });
+                    break;
+                }
+            };
+
+            let events = self.decode(&data, host).await;
+            if events.is_empty() {
+                continue;
+            }
+            let count = events.len();
+            emit!(EventsReceived {
+                count,
+                byte_size: events.size_of(),
+            });
+            if let Err(error) = out.send_batch(events).await {
+                emit!(StreamClosedError { error, count });
+                return Err(());
+            }
+        }
+
+        emit!(WebSocketConnectionShutdown { peer_addr });
+        Ok(())
+    }
+}
+
+async fn websocket_server(
+    mut listener: MaybeTlsListener,
+    handler: MessageHandler,
+    mut shutdown: ShutdownSignal,
+    out: SourceSender,
+) -> Result<(), ()> {
+    loop {
+        let stream = tokio::select! {
+            stream = listener.accept() => match stream {
+                Ok(stream) => stream,
+                Err(error) => {
+                    emit!(TcpSocketTlsConnectionError { error });
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let peer_addr = stream.peer_addr();
+        let handler = handler.clone();
+        let shutdown = shutdown.clone();
+        let mut out = out.clone();
+        tokio::spawn(async move {
+            let ws_stream = match accept_async(stream).await {
+                Ok(ws_stream) => ws_stream,
+                Err(error) => {
+                    emit!(WebSocketConnectionError { error });
+                    return;
+                }
+            };
+            emit!(WebSocketConnectionEstablished {
+                peer_addr: Some(peer_addr),
+            });
+            let host = peer_addr.ip().to_string();
+            let _ = handler
+                .handle(ws_stream, Some(peer_addr), Some(&host), shutdown, &mut out)
+                .await;
+        });
+    }
+
+    Ok(())
+}
+
+async fn websocket_client(
+    connector: WebSocketConnector,
+    handler: MessageHandler,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+) -> Result<(), ()> {
+    let host = connector.uri().host().map(ToOwned::to_owned);
+    loop {
+        let ws_stream = tokio::select! {
+            ws_stream = connector.connect_backoff() => ws_stream,
+            _ = &mut shutdown => break,
+        };
+        let peer_addr = ws_stream.get_ref().peer_addr().ok();
+        handler
+            .handle(
+                ws_stream,
+                peer_addr,
+                host.as_deref(),
+                shutdown.clone(),
+                &mut out,
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::{
+        net::TcpStream,
+        time::{sleep, timeout, Duration},
+    };
+    use tokio_tungstenite::client_async;
+
+    use super::*;
+    use crate::test_util::next_addr;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<WebSocketSourceConfig>();
+    }
+
+    #[test]
+    fn parses_client_mode() {
+        let config: WebSocketSourceConfig = toml::from_str(
+            r#"
+            mode = "client"
+            uri = "wss://example.com/events"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(config.mode, Mode::Client { .. }));
+    }
+
+    #[tokio::test]
+    async fn receives_messages_in_server_mode() {
+        let address = next_addr();
+        let config: WebSocketSourceConfig = toml::from_str(&format!(
+            r#"
+            mode = "server"
+            address = "{}"
+            "#,
+            address
+        ))
+        .unwrap();
+        let (tx, mut rx) = SourceSender::new_test();
+        let source = config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+        sleep(Duration::from_millis(100)).await;
+
+        let stream = TcpStream::connect(address).await.unwrap();
+        let (mut ws_stream, _) = client_async(format!("ws://{}/", address), stream)
+            .await
+            .unwrap();
+        ws_stream
+            .send(Message::Text("hello world".to_string()))
+            .await
+            .unwrap();
+
+        let event = timeout(Duration::from_secs(5), rx.next())
+            .await
+            .unwrap()
+            .unwrap();
+        let log = event.as_log();
+        assert_eq!(log[log_schema().message_key()], "hello world".into());
+        assert_eq!(log[log_schema().source_type_key()], "websocket".into());
+        assert_eq!(log[log_schema().host_key()], "127.0.0.1".into());
+    }
+}
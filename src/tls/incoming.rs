@@ -8,6 +8,8 @@ use std::{
 
 use futures::{future::BoxFuture, stream, FutureExt, Stream};
 use openssl::ssl::{Ssl, SslAcceptor, SslMethod};
+#[cfg(feature = "listenfd")]
+use openssl::x509::{X509NameRef, X509Ref};
 use snafu::ResultExt;
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio::{
@@ -195,6 +197,22 @@ impl<S> MaybeTlsIncomingStream<S> {
         }
     }
 
+    /// The certificate the peer authenticated with, if any. None if the
+    /// connection still hasn't been established.
+    #[cfg(feature = "listenfd")]
+    pub(crate) fn peer_certificate(&self) -> Option<PeerCertificate> {
+        use super::MaybeTls;
+
+        match &self.state {
+            StreamState::Accepted(MaybeTls::Tls(stream)) => stream
+                .ssl()
+                .peer_certificate()
+                .as_deref()
+                .map(PeerCertificate::from),
+            _ => None,
+        }
+    }
+
     #[cfg(all(
         test,
         feature = "sinks-socket",
@@ -216,6 +234,68 @@ impl<S> MaybeTlsIncomingStream<S> {
     }
 }
 
+/// The identity of a peer, from the certificate it authenticated with.
+#[cfg(feature = "listenfd")]
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PeerCertificate {
+    /// The subject, formatted as `CN=example.com,O=Example`.
+    pub(crate) subject: String,
+    /// The subject alternative names, prefixed by their type as in `DNS:example.com`.
+    pub(crate) subject_alt_names: Vec<String>,
+}
+
+#[cfg(feature = "listenfd")]
+impl From<&X509Ref> for PeerCertificate {
+    fn from(certificate: &X509Ref) -> Self {
+        let subject_alt_names = certificate
+            .subject_alt_names()
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| {
+                        name.dnsname()
+                            .map(|dns| format!("DNS:{}", dns))
+                            .or_else(|| name.email().map(|email| format!("email:{}", email)))
+                            .or_else(|| name.uri().map(|uri| format!("URI:{}", uri)))
+                            .or_else(|| name.ipaddress().and_then(format_ip_address))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            subject: format_name(certificate.subject_name()),
+            subject_alt_names,
+        }
+    }
+}
+
+#[cfg(feature = "listenfd")]
+fn format_name(name: &X509NameRef) -> String {
+    name.entries()
+        .map(|entry| {
+            let key = entry.object().nid().short_name().unwrap_or("UNDEF");
+            let value = entry
+                .data()
+                .as_utf8()
+                .map(|value| value.to_string())
+                .unwrap_or_default();
+            format!("{}={}", key, value)
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(feature = "listenfd")]
+fn format_ip_address(bytes: &[u8]) -> Option<String> {
+    let ip = match bytes.len() {
+        4 => std::net::IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?),
+        16 => std::net::IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?),
+        _ => return None,
+    };
+    Some(format!("IP:{}", ip))
+}
+
 impl MaybeTlsIncomingStream<TcpStream> {
     pub(super) fn new(
         stream: TcpStream,
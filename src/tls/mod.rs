@@ -17,7 +17,7 @@ mod outgoing;
 mod settings;
 
 #[cfg(all(feature = "sources-utils-tls", feature = "listenfd"))]
-pub(crate) use incoming::{MaybeTlsIncomingStream, MaybeTlsListener};
+pub(crate) use incoming::{MaybeTlsIncomingStream, MaybeTlsListener, PeerCertificate};
 pub(crate) use maybe_tls::MaybeTls;
 pub use settings::{MaybeTlsSettings, TlsConfig, TlsOptions, TlsSettings};
 #[cfg(test)]
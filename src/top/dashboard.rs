@@ -104,7 +104,7 @@ fn format_metric(total: i64, throughput: i64, human_metrics: bool) -> String {
     }
 }
 
-const NUM_COLUMNS: usize = 8;
+const NUM_COLUMNS: usize = 9;
 static HEADER: [&str; NUM_COLUMNS] = [
     "ID",
     "Output",
@@ -114,6 +114,7 @@ static HEADER: [&str; NUM_COLUMNS] = [
     "Events Out",
     "Bytes",
     "Errors",
+    "Lag",
 ];
 
 struct Widgets<'a> {
@@ -209,6 +210,11 @@ impl<'a> Widgets<'a> {
                 } else {
                     r.errors.thousands_format()
                 },
+                if self.opts.human_metrics {
+                    r.consumer_lag.human_format()
+                } else {
+                    r.consumer_lag.thousands_format()
+                },
             ];
 
             data.extend_from_slice(&formatted_metrics);
@@ -238,14 +244,15 @@ impl<'a> Widgets<'a> {
             .block(Block::default().borders(Borders::ALL).title("Components"))
             .column_spacing(2)
             .widths(&[
-                Constraint::Percentage(15), // ID
-                Constraint::Percentage(15), // Output
-                Constraint::Percentage(10), // Kind
+                Constraint::Percentage(14), // ID
+                Constraint::Percentage(12), // Output
+                Constraint::Percentage(8),  // Kind
                 Constraint::Percentage(10), // Type
-                Constraint::Percentage(10), // Events In
-                Constraint::Percentage(10), // Events Out
-                Constraint::Percentage(10), // Bytes
+                Constraint::Percentage(12), // Events In
+                Constraint::Percentage(12), // Events Out
+                Constraint::Percentage(12), // Bytes
                 Constraint::Percentage(10), // Errors
+                Constraint::Percentage(10), // Lag
             ]);
 
         f.render_widget(w, area);
@@ -36,6 +36,7 @@ async fn component_added(client: Arc<SubscriptionClient>, tx: state::EventTx) {
                     processed_bytes_total: 0,
                     processed_bytes_throughput_sec: 0,
                     errors: 0,
+                    consumer_lag: 0,
                 }))
                 .await;
         }
@@ -236,6 +237,30 @@ async fn errors_totals(client: Arc<SubscriptionClient>, tx: state::EventTx, inte
     }
 }
 
+async fn consumer_lags(client: Arc<SubscriptionClient>, tx: state::EventTx, interval: i64) {
+    tokio::pin! {
+        let stream = client.component_consumer_lags_subscription(interval);
+    };
+
+    while let Some(Some(res)) = stream.next().await {
+        if let Some(d) = res.data {
+            let c = d.component_consumer_lags;
+            let _ = tx
+                .send(state::EventType::ConsumerLags(
+                    c.into_iter()
+                        .map(|c| {
+                            (
+                                ComponentKey::from(c.component_id.as_str()),
+                                c.metric.consumer_lag as i64,
+                            )
+                        })
+                        .collect(),
+                ))
+                .await;
+        }
+    }
+}
+
 /// Subscribe to each metrics channel through a separate client. This is a temporary workaround
 /// until client multiplexing is fixed. In future, we should be able to use a single client
 pub fn subscribe(
@@ -278,7 +303,8 @@ pub fn subscribe(
             tx.clone(),
             interval,
         )),
-        tokio::spawn(errors_totals(Arc::clone(&client), tx, interval)),
+        tokio::spawn(errors_totals(Arc::clone(&client), tx.clone(), interval)),
+        tokio::spawn(consumer_lags(Arc::clone(&client), tx, interval)),
     ]
 }
 
@@ -322,6 +348,8 @@ pub async fn init_components(client: &Client) -> Result<state::State, ()> {
                         processed_bytes_total: d.on.processed_bytes_total(),
                         processed_bytes_throughput_sec: 0,
                         errors: 0,
+                        consumer_lag: 0,
+                        consumer_lag: 0,
                     },
                 ))
             })
@@ -28,14 +28,14 @@ use vector_core::{
 
 use super::{
     fanout::{self, Fanout},
-    schema,
+    rejected, schema,
     task::{Task, TaskOutput},
     BuiltBuffer, ConfigDiff,
 };
 use crate::{
     config::{
         ComponentKey, DataType, Input, Output, OutputId, ProxyConfig, SinkContext, SourceContext,
-        TransformContext,
+        TransformContext, REJECTED_OUTPUT,
     },
     event::{EventArray, EventContainer},
     internal_events::EventsReceived,
@@ -339,6 +339,7 @@ pub async fn build_pieces(
 
         let typetag = sink.inner.sink_type();
         let input_type = sink.inner.input().data_type();
+        let reroute_rejected = sink.reroute_rejected;
 
         let (tx, rx, acker) = if let Some(buffer) = buffers.remove(key) {
             buffer
@@ -383,6 +384,12 @@ pub async fn build_pieces(
             Ok(built) => built,
         };
 
+        let rejected = reroute_rejected.then(|| {
+            let (fanout, control) = Fanout::new();
+            outputs.insert(OutputId::from((key, REJECTED_OUTPUT.to_owned())), control);
+            rejected::Tracker::new(fanout)
+        });
+
         let (trigger, tripwire) = Tripwire::new();
 
         let sink = async move {
@@ -400,19 +407,27 @@ pub async fn build_pieces(
 
             let mut rx = wrap(rx);
 
-            sink.run(
-                rx.by_ref()
-                    .filter(|events: &EventArray| ready(filter_events_type(events, input_type)))
-                    .inspect(|events| {
-                        emit!(EventsReceived {
-                            count: events.len(),
-                            byte_size: events.size_of(),
-                        })
+            let input = rx
+                .by_ref()
+                .filter(|events: &EventArray| ready(filter_events_type(events, input_type)))
+                .inspect(|events| {
+                    emit!(EventsReceived {
+                        count: events.len(),
+                        byte_size: events.size_of(),
                     })
-                    .take_until_if(tripwire),
-            )
-            .await
-            .map(|_| {
+                })
+                .take_until_if(tripwire);
+
+            let result = match rejected {
+                Some((tracker, pump)) => {
+                    let input = input.then(move |events| tracker.clone().track(events));
+                    let (result, ()) = futures::join!(sink.run(input), pump);
+                    result
+                }
+                None => sink.run(input).await,
+            };
+
+            result.map(|_| {
                 debug!("Finished.");
                 TaskOutput::Sink(rx, acker)
             })
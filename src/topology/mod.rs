@@ -9,6 +9,7 @@
 pub(super) use vector_core::fanout;
 
 pub mod builder;
+mod rejected;
 mod running;
 mod schema;
 mod task;
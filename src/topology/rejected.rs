@@ -0,0 +1,153 @@
+//! Rerouting of the events permanently rejected by a sink to its `rejected` output.
+//!
+//! The arrays of events entering the sink are detached from their finalizers, and the sink is
+//! given a copy of each array tracked by a notifier of its own, while the originals are held on
+//! to until the sink finalizes its copies. Delivered and errored events then finalize the
+//! originals with the same status, and rejected ones are annotated with the details of their
+//! rejection and sent to the `rejected` output, carrying the original finalizers to whichever
+//! sink consumes them.
+//!
+//! Only the events the sink marks as rejected are rerouted. Events still failing with a
+//! transient error once the sink exhausts its retries are marked as errored, and are finalized
+//! as such rather than rerouted.
+
+use std::sync::Arc;
+
+use futures::Future;
+use tokio::sync::mpsc;
+use vector_core::{event::array::events_into_arrays, internal_event::EventsSent, ByteSizeOf};
+
+use super::fanout::Fanout;
+use crate::{
+    config::REJECTED_OUTPUT,
+    event::{
+        BatchNotifier, BatchStatusReceiver, Event, EventArray, EventContainer, EventFinalizer,
+        EventFinalizers, EventOutcomes, EventStatus, Rejection,
+    },
+};
+
+/// The number of arrays of events the sink may hold on to before the tracker waits for it to
+/// finalize the oldest of them.
+const PENDING_LIMIT: usize = 1024;
+
+/// An array of events held on to until the sink finalizes its copy.
+struct Pending {
+    events: EventArray,
+    finalizers: Vec<EventFinalizers>,
+    receiver: BatchStatusReceiver,
+    outcomes: Arc<EventOutcomes>,
+}
+
+/// Tracks the events entering a sink, holding on to the originals until the sink finalizes them.
+#[derive(Clone)]
+pub(super) struct Tracker {
+    tx: mpsc::Sender<Pending>,
+}
+
+impl Tracker {
+    /// Create a tracker along with the task sending the rejected events to the given fanout.
+    pub(super) fn new(fanout: Fanout) -> (Self, impl Future<Output = ()>) {
+        let (tx, rx) = mpsc::channel(PENDING_LIMIT);
+
+        (Self { tx }, pump(rx, fanout))
+    }
+
+    /// Replace the finalizers of the events with ones tracking the outcome of each event in this
+    /// sink, waiting for room if the sink holds on to too many events already.
+    pub(super) async fn track(self, mut events: EventArray) -> EventArray {
+        let mut finalizers = Vec::with_capacity(events.len());
+        events.for_each_event(|mut event| {
+            finalizers.push(event.metadata_mut().take_finalizers());
+        });
+
+        let (batch, receiver, outcomes) = BatchNotifier::new_tracking_events(events.len());
+        // The fields of logs and traces are shared between the copies rather than cloned.
+        let mut copies = events.clone();
+        let mut index = 0_u32;
+        copies.for_each_event(|mut event| {
+            event
+                .metadata_mut()
+                .add_finalizer(EventFinalizer::new_tracked(Arc::clone(&batch), index));
+            index += 1;
+        });
+
+        let pending = Pending {
+            events,
+            finalizers,
+            receiver,
+            outcomes,
+        };
+        // Sending only fails if the pump is gone along with the sink, leaving nowhere to reroute to.
+        let _ = self.tx.send(pending).await;
+
+        copies
+    }
+}
+
+/// Record the details of the rejection on the event, as fields of a `rejection` object for logs
+/// and traces and as tags for metrics.
+fn annotate(event: &mut Event, rejection: Rejection) {
+    match event {
+        Event::Log(log) => {
+            log.insert("rejection.reason", rejection.reason);
+            if let Some(status) = rejection.status {
+                log.insert("rejection.status", status);
+            }
+        }
+        Event::Trace(trace) => {
+            trace.insert("rejection.reason", rejection.reason);
+            if let Some(status) = rejection.status {
+                trace.insert("rejection.status", status);
+            }
+        }
+        Event::Metric(metric) => {
+            metric.insert_tag("rejection_reason".to_owned(), rejection.reason);
+            if let Some(status) = rejection.status {
+                metric.insert_tag("rejection_status".to_owned(), status.to_string());
+            }
+        }
+    }
+}
+
+async fn pump(mut rx: mpsc::Receiver<Pending>, mut fanout: Fanout) {
+    while let Some(pending) = rx.recv().await {
+        pending.receiver.await;
+
+        let mut rejected = Vec::new();
+        let events = pending.events.into_events().zip(pending.finalizers);
+        for ((mut event, finalizers), outcome) in events.zip(pending.outcomes.take()) {
+            match outcome.status {
+                EventStatus::Rejected => {
+                    let rejection = outcome.rejection.unwrap_or_else(|| Rejection {
+                        reason: "Rejected by the sink.".to_owned(),
+                        status: None,
+                    });
+                    annotate(&mut event, rejection);
+                    event.metadata_mut().merge_finalizers(finalizers);
+                    rejected.push(event);
+                }
+                EventStatus::Errored => finalizers.update_status(EventStatus::Errored),
+                _ => finalizers.update_status(EventStatus::Delivered),
+            }
+        }
+
+        for events in events_into_arrays(rejected, None) {
+            send(&mut fanout, events).await;
+        }
+    }
+
+    debug!("Rejected events pump finished.");
+}
+
+async fn send(fanout: &mut Fanout, events: EventArray) {
+    let count = events.len();
+    let byte_size = events.size_of();
+
+    fanout.send(events).await;
+
+    emit!(EventsSent {
+        count,
+        byte_size,
+        output: Some(REJECTED_OUTPUT),
+    });
+}
@@ -13,7 +13,7 @@ use vector_common::TimeZone;
 use vrl::{
     diagnostic::{Formatter, Note},
     prelude::{DiagnosticError, ExpressionError},
-    Program, Runtime, Terminate, Vm, VrlRuntime,
+    Jit, Program, Runtime, Terminate, Vm, VrlRuntime,
 };
 
 use crate::{
@@ -37,6 +37,10 @@ pub struct RemapConfig {
     pub source: Option<String>,
     pub file: Option<PathBuf>,
     #[serde(default)]
+    pub import_paths: Vec<PathBuf>,
+    #[serde(default)]
+    pub secrets: BTreeMap<String, PathBuf>,
+    #[serde(default)]
     pub timezone: TimeZone,
     pub drop_on_error: bool,
     #[serde(default = "crate::serde::default_true")]
@@ -75,8 +79,20 @@ impl RemapConfig {
         functions.append(&mut enrichment::vrl_functions());
         functions.append(&mut vector_vrl_functions::vrl_functions());
 
+        let secrets = self
+            .secrets
+            .iter()
+            .map(|(name, path)| {
+                std::fs::read(path)
+                    .with_context(|_| SecretReadFailedSnafu { name, path })
+                    .map(|secret| (name.as_str(), secret))
+            })
+            .collect::<std::result::Result<vrl_stdlib::Secrets, _>>()?;
+
         let mut state = vrl::state::Compiler::new_with_kind(merged_schema_definition.into());
         state.set_external_context(enrichment_tables);
+        state.set_external_context(secrets);
+        state.set_import_paths(self.import_paths.clone());
 
         vrl::compile_with_state(&source, &functions, &mut state)
             .map_err(|diagnostics| {
@@ -87,6 +103,17 @@ impl RemapConfig {
             })
             .map(|program| (program, functions, state))
     }
+
+    fn compile_vm(&self, context: &TransformContext) -> Result<(vrl::Program, Arc<Vm>)> {
+        let (program, functions, state) = self.compile_vrl_program(
+            context.enrichment_tables.clone(),
+            context.merged_schema_definition.clone(),
+        )?;
+
+        let vm = Runtime::default().compile(functions, &program, state)?;
+
+        Ok((program, Arc::new(vm)))
+    }
 }
 
 inventory::submit! {
@@ -108,6 +135,10 @@ impl TransformConfig for RemapConfig {
                 let remap = Remap::new_vm(self.clone(), context)?;
                 Ok(Transform::synchronous(remap))
             }
+            VrlRuntime::Jit => match Remap::new_jit(self.clone(), context)? {
+                JitRemap::Native(remap) => Ok(Transform::synchronous(remap)),
+                JitRemap::Vm(remap) => Ok(Transform::synchronous(remap)),
+            },
         }
     }
 
@@ -219,6 +250,32 @@ impl VrlRunner for VmRunner {
     }
 }
 
+#[derive(Debug)]
+pub struct JitRunner {
+    runtime: Runtime,
+    jit: Arc<Jit>,
+}
+
+impl Clone for JitRunner {
+    fn clone(&self) -> Self {
+        Self {
+            runtime: Runtime::default(),
+            jit: Arc::clone(&self.jit),
+        }
+    }
+}
+
+impl VrlRunner for JitRunner {
+    fn run(
+        &mut self,
+        target: &mut VrlTarget,
+        _: &Program,
+        timezone: &TimeZone,
+    ) -> std::result::Result<vrl::Value, Terminate> {
+        self.runtime.run_jit(&self.jit, target, timezone)
+    }
+}
+
 #[derive(Debug)]
 pub struct AstRunner {
     pub runtime: Runtime,
@@ -247,22 +304,59 @@ impl VrlRunner for AstRunner {
 
 impl Remap<VmRunner> {
     pub fn new_vm(config: RemapConfig, context: &TransformContext) -> crate::Result<Self> {
-        let (program, functions, state) = config.compile_vrl_program(
-            context.enrichment_tables.clone(),
-            context.merged_schema_definition.clone(),
-        )?;
-
-        let runtime = Runtime::default();
-        let vm = runtime.compile(functions, &program, state)?;
+        let (program, vm) = config.compile_vm(context)?;
         let runner = VmRunner {
-            runtime,
-            vm: Arc::new(vm),
+            runtime: Runtime::default(),
+            vm,
         };
 
         Self::new(config, context, program, runner)
     }
 }
 
+/// A transform built for the `jit` runtime, which runs the program compiled to
+/// native code, or the VM if native compilation isn't available.
+#[derive(Debug)]
+pub enum JitRemap {
+    Native(Remap<JitRunner>),
+    Vm(Remap<VmRunner>),
+}
+
+impl Remap<JitRunner> {
+    /// Compiles the program to native code, which calls the functions of the
+    /// program and interprets the bodies of their closures with the VM.
+    ///
+    /// If native compilation isn't available, such as when Vector is built
+    /// without the `vrl-jit` feature, the whole program runs on the VM instead.
+    pub fn new_jit(config: RemapConfig, context: &TransformContext) -> crate::Result<JitRemap> {
+        let (program, vm) = config.compile_vm(context)?;
+
+        match Jit::new(Arc::clone(&vm)) {
+            Ok(jit) => {
+                let runner = JitRunner {
+                    runtime: Runtime::default(),
+                    jit: Arc::new(jit),
+                };
+
+                Self::new(config, context, program, runner).map(JitRemap::Native)
+            }
+            Err(error) => {
+                warn!(
+                    message = "Program can't be compiled to native code, falling back to the vm runtime.",
+                    %error,
+                );
+
+                let runner = VmRunner {
+                    runtime: Runtime::default(),
+                    vm,
+                };
+
+                Remap::new(config, context, program, runner).map(JitRemap::Vm)
+            }
+        }
+    }
+}
+
 impl Remap<AstRunner> {
     pub fn new_ast(config: RemapConfig, context: &TransformContext) -> crate::Result<Self> {
         let (program, _, _) = config.compile_vrl_program(
@@ -475,6 +569,12 @@ pub enum BuildError {
     FileOpenFailed { path: PathBuf, source: io::Error },
     #[snafu(display("Could not read vrl program {:?}: {}", path, source))]
     FileReadFailed { path: PathBuf, source: io::Error },
+    #[snafu(display("Could not read secret {:?} from {:?}: {}", name, path, source))]
+    SecretReadFailed {
+        name: String,
+        path: PathBuf,
+        source: io::Error,
+    },
 }
 
 #[cfg(test)]
@@ -557,6 +657,34 @@ mod tests {
         )
     }
 
+    #[test]
+    fn config_missing_secret() {
+        let config = RemapConfig {
+            source: Some(r#".key = get_secret("hmac_key")"#.to_owned()),
+            secrets: BTreeMap::from([("hmac_key".to_owned(), "/nonexistent".into())]),
+            ..Default::default()
+        };
+
+        let err = remap(config).unwrap_err().to_string();
+        assert!(err.starts_with(r#"Could not read secret "hmac_key" from "/nonexistent""#));
+    }
+
+    #[test]
+    fn check_remap_reads_secrets() {
+        let path = crate::test_util::temp_file();
+        std::fs::write(&path, "secret").unwrap();
+
+        let conf = RemapConfig {
+            source: Some(r#".key = get_secret("hmac_key")"#.to_owned()),
+            secrets: BTreeMap::from([("hmac_key".to_owned(), path)]),
+            ..Default::default()
+        };
+        let mut tform = remap(conf).unwrap();
+
+        let result = transform_one(&mut tform, Event::from(LogEvent::from("event"))).unwrap();
+        assert_eq!(get_field_string(&result, "key"), "secret");
+    }
+
     fn get_field_string(event: &Event, field: &str) -> String {
         event.as_log().get(field).unwrap().to_string_lossy()
     }
@@ -638,6 +766,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn check_remap_jit() {
+        let event = {
+            let mut event = LogEvent::from("augment me");
+            event.insert("count", 2);
+            Event::from(event)
+        };
+
+        let conf = RemapConfig {
+            source: Some(
+                indoc! {r#"
+                    .foo = "bar"
+                    if int!(.count) > 1 {
+                        .many = true
+                    } else {
+                        .many = false
+                    }
+                    .tags = map_values({ "a": "x" }) -> |value| { upcase!(value) }
+                    .parsed, .err = int(.message)
+                "#}
+                .to_string(),
+            ),
+            runtime: VrlRuntime::Jit,
+            ..Default::default()
+        };
+        let schema_definitions = HashMap::from([
+            (None, test_default_schema_definition()),
+            (Some(DROPPED.to_owned()), test_dropped_schema_definition()),
+        ]);
+        let context = TransformContext::new_test(schema_definitions);
+
+        // Without the `vrl-jit` feature, the transform runs the VM instead.
+        let remap = Remap::new_jit(conf, &context).unwrap();
+        assert_eq!(
+            matches!(remap, JitRemap::Native(_)),
+            cfg!(feature = "vrl-jit")
+        );
+
+        let result = match remap {
+            JitRemap::Native(mut tform) => transform_one(&mut tform, event),
+            JitRemap::Vm(mut tform) => transform_one(&mut tform, event),
+        }
+        .unwrap();
+
+        assert_eq!(get_field_string(&result, "foo"), "bar");
+        assert_eq!(get_field_string(&result, "many"), "true");
+        assert_eq!(get_field_string(&result, "tags.a"), "X");
+        assert_eq!(get_field_string(&result, "parsed"), "0");
+        assert!(get_field_string(&result, "err").contains("expected integer"));
+    }
+
     #[test]
     fn check_remap_emits_multiple() {
         let event = {
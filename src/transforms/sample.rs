@@ -160,6 +160,7 @@ mod tests {
         VrlConfig {
             source: format!(r#"contains!(."{}", "{}")"#, key, needle),
             runtime: Default::default(),
+            import_paths: Vec::new(),
         }
         .build(&Default::default())
         .unwrap()
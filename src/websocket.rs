@@ -0,0 +1,152 @@
+use std::{net::SocketAddr, time::Duration};
+
+use http::Uri;
+use snafu::{ResultExt, Snafu};
+use tokio::{net::TcpStream, time::sleep};
+use tokio_tungstenite::{client_async, tungstenite::error::Error as WsError, WebSocketStream};
+
+use crate::{
+    dns,
+    internal_events::{WebSocketConnectionError, WebSocketConnectionEstablished},
+    sinks::util::retries::ExponentialBackoff,
+    tls::{MaybeTlsSettings, MaybeTlsStream, TlsError},
+};
+
+#[derive(Debug, Snafu)]
+pub enum WebSocketError {
+    #[snafu(display("Invalid WebSocket URI {:?}: {}", uri, source))]
+    InvalidUri {
+        uri: String,
+        source: http::uri::InvalidUri,
+    },
+    #[snafu(display("Unsupported WebSocket scheme {:?}, expected `ws` or `wss`", scheme))]
+    UnsupportedScheme { scheme: String },
+    #[snafu(display("Missing host in WebSocket URI"))]
+    MissingHost,
+    #[snafu(display("Connect error: {}", source))]
+    Connect { source: TlsError },
+    #[snafu(display("Unable to resolve DNS: {}", source))]
+    Dns { source: dns::DnsError },
+    #[snafu(display("No addresses returned."))]
+    NoAddresses,
+    #[snafu(display("WebSocket handshake failed: {}", source))]
+    Handshake { source: WsError },
+}
+
+/// Connects to a WebSocket server, over TLS for the `wss` scheme.
+#[derive(Clone, Debug)]
+pub(crate) struct WebSocketConnector {
+    uri: Uri,
+    host: String,
+    port: u16,
+    tls: MaybeTlsSettings,
+}
+
+impl WebSocketConnector {
+    pub(crate) fn new(uri: &str, tls: MaybeTlsSettings) -> Result<Self, WebSocketError> {
+        let uri = uri.parse::<Uri>().context(InvalidUriSnafu { uri })?;
+        let (default_port, tls) = match uri.scheme_str() {
+            Some("ws") => (80, tls),
+            Some("wss") => match tls {
+                MaybeTlsSettings::Raw(()) => (
+                    443,
+                    MaybeTlsSettings::enable_client().context(ConnectSnafu)?,
+                ),
+                tls => (443, tls),
+            },
+            scheme => {
+                return Err(WebSocketError::UnsupportedScheme {
+                    scheme: scheme.unwrap_or_default().to_string(),
+                })
+            }
+        };
+        let host = uri.host().ok_or(WebSocketError::MissingHost)?.to_string();
+        let port = uri.port_u16().unwrap_or(default_port);
+
+        Ok(Self {
+            uri,
+            host,
+            port,
+            tls,
+        })
+    }
+
+    pub(crate) const fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    const fn fresh_backoff() -> ExponentialBackoff {
+        ExponentialBackoff::from_millis(2)
+            .factor(250)
+            .max_delay(Duration::from_secs(60))
+    }
+
+    pub(crate) async fn connect(
+        &self,
+    ) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, WebSocketError> {
+        let ip = dns::Resolver
+            .lookup_ip(self.host.clone())
+            .await
+            .context(DnsSnafu)?
+            .next()
+            .ok_or(WebSocketError::NoAddresses)?;
+
+        let addr = SocketAddr::new(ip, self.port);
+        let stream = self
+            .tls
+            .connect(&self.host, &addr)
+            .await
+            .context(ConnectSnafu)?;
+
+        let (ws_stream, _response) = client_async(&self.uri, stream)
+            .await
+            .context(HandshakeSnafu)?;
+
+        Ok(ws_stream)
+    }
+
+    /// Connects until it succeeds, backing off exponentially between the
+    /// attempts.
+    pub(crate) async fn connect_backoff(&self) -> WebSocketStream<MaybeTlsStream<TcpStream>> {
+        let mut backoff = Self::fresh_backoff();
+        loop {
+            match self.connect().await {
+                Ok(ws_stream) => {
+                    emit!(WebSocketConnectionEstablished {
+                        peer_addr: ws_stream.get_ref().peer_addr().ok(),
+                    });
+                    return ws_stream;
+                }
+                Err(error) => {
+                    emit!(WebSocketConnectionError { error });
+                    sleep(backoff.next().unwrap()).await;
+                }
+            }
+        }
+    }
+
+    pub(crate) async fn healthcheck(&self) -> crate::Result<()> {
+        self.connect().await.map(|_| ()).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_uris() {
+        let connector = WebSocketConnector::new("ws://localhost/events", None.into()).unwrap();
+        assert_eq!(connector.host, "localhost");
+        assert_eq!(connector.port, 80);
+
+        let connector = WebSocketConnector::new("wss://localhost:8443", None.into()).unwrap();
+        assert_eq!(connector.port, 8443);
+        assert!(matches!(connector.tls, MaybeTlsSettings::Tls(_)));
+
+        assert!(matches!(
+            WebSocketConnector::new("http://localhost", None.into()),
+            Err(WebSocketError::UnsupportedScheme { .. })
+        ));
+    }
+}
@@ -28,7 +28,7 @@ use vector::{
     },
     event::{
         metric::{self, MetricData, MetricValue},
-        Event, EventArray, EventContainer, Value,
+        Event, EventArray, EventContainer, Finalizable, Rejection, Value,
     },
     schema,
     sinks::{util::StreamSink, Healthcheck, VectorSink},
@@ -66,6 +66,13 @@ pub fn sink_dead() -> MockSinkConfig {
     MockSinkConfig::new_dead(false)
 }
 
+pub fn sink_rejecting(reason: &str, status: Option<u16>) -> MockSinkConfig {
+    MockSinkConfig::new_rejecting(Rejection {
+        reason: reason.into(),
+        status,
+    })
+}
+
 pub fn source() -> (SourceSender, MockSourceConfig) {
     let (tx, rx) = SourceSender::new_with_buffer(1);
     let source = MockSourceConfig::new(rx);
@@ -340,6 +347,7 @@ pub struct MockSinkConfig {
 #[derive(Debug, Clone)]
 enum Mode {
     Normal(SourceSender),
+    Rejecting(Rejection),
     Dead,
 }
 
@@ -366,6 +374,14 @@ impl MockSinkConfig {
         }
     }
 
+    pub fn new_rejecting(rejection: Rejection) -> Self {
+        Self {
+            sink: Mode::Rejecting(rejection),
+            healthy: true,
+            data: None,
+        }
+    }
+
     pub fn new_with_data(sink: SourceSender, healthy: bool, data: &str) -> Self {
         Self {
             sink: Mode::Normal(sink),
@@ -449,6 +465,18 @@ impl StreamSink<Event> for MockSink {
                     self.acker.ack(1);
                 }
             }
+            Mode::Rejecting(rejection) => {
+                if let Some(tx) = self.health_tx.take() {
+                    let _ = tx.send(Ok(()));
+                }
+
+                // Permanently reject every event, as a sink giving up on a request would
+                while let Some(mut event) = input.next().await {
+                    event.take_finalizers().reject(&rejection);
+
+                    self.acker.ack(1);
+                }
+            }
             Mode::Dead => {
                 // Simulate a dead sink and never poll the input
                 futures::future::pending::<()>().await;
@@ -23,7 +23,7 @@ use vector::{
 use vector_buffers::{BufferConfig, BufferType, WhenFull};
 
 use crate::support::{
-    sink, sink_failing_healthcheck, sink_with_data, source, source_with_data,
+    sink, sink_failing_healthcheck, sink_rejecting, sink_with_data, source, source_with_data,
     source_with_event_counter, transform,
 };
 
@@ -126,6 +126,40 @@ async fn topology_source_and_sink() {
     assert_eq!(vec![event], res);
 }
 
+#[tokio::test]
+async fn topology_sink_rejected_output() {
+    let (mut in1, source1) = source();
+    let (out1, sink1) = sink(10);
+
+    let mut rejecting = SinkOuter::new(
+        vec!["in1".to_owned()],
+        Box::new(sink_rejecting("400 Bad Request", Some(400))),
+    );
+    rejecting.reroute_rejected = true;
+
+    let mut config = Config::builder();
+    config.add_source("in1", source1);
+    config.add_sink_outer("rejecting", rejecting);
+    config.add_sink("out1", &["rejecting.rejected"], sink1);
+
+    let (topology, _crash) = start_topology(config.build().unwrap(), false).await;
+
+    in1.send_event(Event::from("this")).await.unwrap();
+
+    topology.stop().await;
+
+    let res = out1.flat_map(into_event_stream).collect::<Vec<_>>().await;
+
+    assert_eq!(res.len(), 1);
+    let log = res[0].as_log();
+    assert_eq!(
+        log[&vector::config::log_schema().message_key()],
+        "this".into()
+    );
+    assert_eq!(log["rejection.reason"], "400 Bad Request".into());
+    assert_eq!(log["rejection.status"], 400.into());
+}
+
 #[tokio::test]
 async fn topology_multiple_sources() {
     let (mut in1, source1) = source();
@@ -464,6 +464,84 @@ cli: {
 				}
 			}
 		}
+
+		"vrl test": {
+			description: """
+				Test VRL programs against fixtures of input events and their expected output
+				events, reporting the statements and branches of each program left uncovered.
+
+				The fixtures of a program such as `remap.vrl` are stored in the `remap` directory
+				next to it, with each input event in a `<name>.input.json` file, and the event
+				expected once the program ran in `<name>.expected.json`.
+				"""
+			example: "vector vrl test transforms/"
+
+			flags: _default_flags & {
+				"update": {
+					_short: "u"
+					description: """
+						Write the output events to the expected output files, instead of comparing
+						them.
+						"""
+				}
+				"no-diff": {
+					_short:      "n"
+					description: "Don't print the difference between the expected and the output events."
+				}
+			}
+
+			options: {
+				"timezone": {
+					_short:      "z"
+					description: "The timezone used to parse dates."
+					type:        "string"
+				}
+			}
+
+			args: {
+				paths: {
+					description: """
+						The VRL programs to test, or the directories searched for programs with
+						fixtures.
+						"""
+					type:    "list"
+					default: "."
+				}
+			}
+		}
+
+		"vrl fmt": {
+			description: """
+				Format VRL programs, and the programs of the `remap` transforms in TOML and YAML
+				configuration files, keeping their comments. Files are formatted in place, while
+				a program read from stdin is written formatted to stdout.
+
+				In configuration files, the `source` of transforms defined in their own table,
+				such as `[transforms.my_remap]` in TOML, or as a literal block scalar (`|`) in
+				YAML, is formatted.
+				"""
+			example: "vector vrl fmt --check remap.vrl vector.toml"
+
+			flags: _default_flags & {
+				"check": {
+					_short: "c"
+					description: """
+						Don't write the formatted files, but list the files that aren't formatted
+						and exit with an error if there are any.
+						"""
+				}
+			}
+
+			args: {
+				paths: {
+					description: """
+						The VRL programs (`.vrl`) and configuration files (`.toml`, `.yaml` or
+						`.yml`) to format.
+						"""
+					type: "list"
+				}
+			}
+		}
 	}
 
 	env_vars: {
@@ -279,6 +279,17 @@ components: sinks: [Name=string]: {
 			}
 		}
 
+		reroute_rejected: {
+			common:   false
+			required: false
+			description: """
+				Send the events permanently rejected by the downstream service to the `rejected`
+				output instead of dropping them. The events carry the details of the rejection,
+				see the [rejected events](#rejected-events) section.
+				"""
+			type: bool: default: false
+		}
+
 		if features.send != _|_ {
 			if features.send.proxy != _|_ {
 				if features.send.proxy.enabled {
@@ -469,6 +480,29 @@ components: sinks: [Name=string]: {
 	}
 
 	how_it_works: {
+		rejected_events: {
+			title: "Rejected events"
+			body: #"""
+				Events the downstream service permanently rejects, for example with a `400 Bad Request`
+				response, are dropped by default. When `reroute_rejected` is set to
+				`true`, the sink exposes an additional `rejected` output receiving these events instead.
+				For a sink component named `foo`, this output can be used by specifying `foo.rejected` as
+				the input to another sink, for example to park the events in object storage for replay.
+
+				Rerouted events are sent in their original form along with the details of the
+				rejection. Log and trace events get a `rejection` object with the `reason` of the
+				rejection and the `status` code of the response, if any. Metric events get the
+				`rejection_reason` and `rejection_status` tags instead.
+
+				The acknowledgement of rerouted events is then handed off to the sinks consuming the
+				`rejected` output.
+
+				Only permanent rejections are rerouted. Events still failing with a transient error, such
+				as a timeout or a `503 Service Unavailable` response, once the sink exhausts its retries
+				are considered errored rather than rejected, and aren't sent to the `rejected` output.
+				"""#
+		}
+
 		if features.buffer.enabled {
 			if features.send != _|_ {
 				if features.send.batch != _|_ {
@@ -54,7 +54,7 @@ components: sinks: clickhouse: {
 							url:   urls.clickhouse_http
 						}
 						direction: "outgoing"
-						protocols: ["http"]
+						protocols: ["http", "tcp"]
 						ssl: "optional"
 					}
 				}
@@ -79,25 +79,51 @@ components: sinks: clickhouse: {
 		}}
 		database: {
 			common:      true
-			description: "The database that contains the table that data will be inserted into."
+			description: "The database that contains the table that data will be inserted into. Defaults to the `default` database."
 			required:    false
 			type: string: {
 				default: null
-				examples: ["mydatabase"]
+				examples: ["mydatabase", "{{ tenant }}"]
+				syntax: "template"
 			}
 		}
 		endpoint: {
 			description: "The endpoint of the [Clickhouse](\(urls.clickhouse)) server."
 			required:    true
 			type: string: {
-				examples: ["http://localhost:8123"]
+				examples: ["http://localhost:8123", "tcp://localhost:9000"]
+			}
+		}
+		format: {
+			common:      false
+			description: "The format of the events inserted over HTTP. Ignored by the native protocol, which always sends typed column blocks."
+			required:    false
+			type: string: {
+				default: "json_each_row"
+				enum: {
+					json_each_row: "Events are sent as JSON objects and converted by Clickhouse."
+					row_binary:    "Events are converted to the types of the table columns and sent in the `RowBinary` format."
+				}
+			}
+		}
+		protocol: {
+			common:      false
+			description: "The protocol used to insert events."
+			required:    false
+			type: string: {
+				default: "http"
+				enum: {
+					http:   "The [HTTP interface](\(urls.clickhouse_http)). The endpoint uses the `http` or `https` scheme."
+					native: "The [native TCP protocol](\(urls.clickhouse_native)). The endpoint uses the `tcp` scheme, or `tls` for encrypted connections. Only basic authentication is supported."
+				}
 			}
 		}
 		table: {
 			description: "The table that data will be inserted into."
 			required:    true
 			type: string: {
-				examples: ["mytable"]
+				examples: ["mytable", "logs_{{ application }}"]
+				syntax: "template"
 			}
 		}
 		skip_unknown_fields: {
@@ -108,6 +134,23 @@ components: sinks: clickhouse: {
 		}
 	}
 
+	how_it_works: {
+		type_conversion: {
+			title: "Type conversion"
+			body: """
+				With the `row_binary` format and the native protocol, Vector fetches the schema
+				of each table it inserts into and converts the fields of the events to the types
+				of the columns itself. Schemas are cached for a minute, and refetched when an
+				insert fails. Events with a field that can't be converted to the type of its
+				column, like a string that isn't a valid IP address for an `IPv4` column, are
+				rejected on their own without failing the rest of the batch. Missing fields are
+				set to the default value of their column. Fetching a schema is retried up to five
+				times while ClickHouse is unavailable, after which the events of the batch are
+				rejected.
+				"""
+		}
+	}
+
 	input: {
 		logs:    true
 		metrics: null
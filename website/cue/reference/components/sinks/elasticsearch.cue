@@ -334,6 +334,14 @@ components: sinks: elasticsearch: {
 				due to Elasticsearch index mapping errors, where data keys aren't consistently
 				typed. To change this behavior, refer to the Elasticsearch [`ignore_malformed`
 				setting](\(urls.elasticsearch_ignore_malformed)).
+
+				Vector inspects the result of each document in a bulk response. Documents that
+				failed with a transient error (`429 Too Many Requests` or a server error) are
+				retried on their own, without resending the documents that were already indexed.
+				Documents that failed permanently, such as mapping conflicts, are dropped and
+				logged along with the index, error type and reason given by Elasticsearch.
+				Only the events of these documents are marked as rejected, the other events of
+				the same bulk request are acknowledged as delivered once indexed.
 				"""
 		}
 
@@ -341,6 +349,8 @@ components: sinks: elasticsearch: {
 	}
 
 	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
 		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
 		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
 		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
@@ -52,6 +52,93 @@ components: sinks: kafka: {
 
 	configuration: {
 		bootstrap_servers: components._kafka.configuration.bootstrap_servers
+		exactly_once: {
+			common:      false
+			description: "Produces events in Kafka transactions, so that consumers using the `read_committed` isolation level see each event exactly once. See [exactly-once delivery](#exactly-once-delivery)."
+			required:    false
+			type: object: {
+				examples: []
+				options: {
+					max_events: {
+						common:      false
+						description: "The maximum number of events produced in a single transaction."
+						required:    false
+						type: uint: {
+							default: 1000
+							unit:    "events"
+						}
+					}
+					on_fenced: {
+						common:      false
+						description: "What to do when the producer is fenced by another producer with the same `transactional_id`, or fails otherwise."
+						required:    false
+						type: string: {
+							default: "shutdown"
+							enum: {
+								shutdown:     "Stop the sink, leaving the other producer in charge."
+								reinitialize: "Create a new producer, which fences the other one in turn."
+							}
+						}
+					}
+					source_offsets: {
+						common:      false
+						description: "Commits the offsets of events read by a `kafka` source in the same transactions as the events themselves. The source must run in the same Vector instance, with `commit_offsets` disabled."
+						required:    false
+						type: object: {
+							examples: []
+							options: {
+								group_id: {
+									description: "The consumer group of the `kafka` source."
+									required:    true
+									type: string: examples: ["billing"]
+								}
+								offset_key: {
+									common:      false
+									description: "The field holding the offset of the event, as set by the source's `offset_key`."
+									required:    false
+									type: string: default: "offset"
+								}
+								partition_key: {
+									common:      false
+									description: "The field holding the partition of the event, as set by the source's `partition_key`."
+									required:    false
+									type: string: default: "partition"
+								}
+								topic_key: {
+									common:      false
+									description: "The field holding the topic of the event, as set by the source's `topic_key`."
+									required:    false
+									type: string: default: "topic"
+								}
+							}
+						}
+					}
+					timeout_secs: {
+						common:      false
+						description: "The maximum time events are buffered before their transaction is committed."
+						required:    false
+						type: float: {
+							default: 1.0
+							unit:    "seconds"
+						}
+					}
+					transaction_timeout_ms: {
+						common:      false
+						description: "The maximum duration of a transaction before the broker aborts it. Also caps `message_timeout_ms`."
+						required:    false
+						type: uint: {
+							default: 60000
+							unit:    "milliseconds"
+						}
+					}
+					transactional_id: {
+						description: "Identifies the producer across restarts. Each instance of the sink needs its own id: a producer using the same id fences the previous one and aborts its open transaction."
+						required:    true
+						type: string: examples: ["billing-1"]
+					}
+				}
+			}
+		}
 		key_field: {
 			common:      true
 			description: "The log field name or tags key to use for the topic key. If the field does not exist in the log or in tags, a blank value will be used. If unspecified, the key is not sent. Kafka uses a hash of the key to choose the partition or uses round-robin if the record has no key."
@@ -147,14 +234,40 @@ components: sinks: kafka: {
 		}
 	}
 
-	how_it_works: components._kafka.how_it_works
+	how_it_works: components._kafka.how_it_works & {
+		exactly_once_delivery: {
+			title: "Exactly-once delivery"
+			body: """
+				With `exactly_once` set, events are batched and each batch is produced in a
+				Kafka transaction. A failed transaction is aborted and the whole batch retried,
+				which doesn't duplicate records for consumers reading with the
+				`read_committed` isolation level, as the records of aborted transactions are
+				never shown to them. Errors that fail again on retry, such as records over the
+				maximum message size, unknown topics or authorization failures, abort the
+				transaction and reject the events of the batch instead.
+
+				When the events come from a `kafka` source, setting `source_offsets` commits the
+				offsets of the consumed events in the same transaction as the produced records.
+				Disable `commit_offsets` on the source so that offsets are only committed with
+				the transactions: after a crash, consumption restarts right after the last
+				committed transaction.
+
+				The offsets are committed on behalf of the consumer of the source, which must run
+				in the same Vector instance. Transactions carrying offsets wait, retrying, until
+				that source is running. The group generation of the consumer is read for each
+				transaction, as it changes every time the group rebalances.
+				"""
+		}
+	}
 
 	telemetry: metrics: {
 		component_sent_events_total:         components.sources.internal_metrics.output.metrics.component_sent_events_total
 		component_sent_event_bytes_total:    components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
 		component_sent_bytes_total:          components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_errors_total:              components.sources.internal_metrics.output.metrics.component_errors_total
 		events_discarded_total:              components.sources.internal_metrics.output.metrics.events_discarded_total
 		processing_errors_total:             components.sources.internal_metrics.output.metrics.processing_errors_total
+		kafka_transactions_aborted_total:    components.sources.internal_metrics.output.metrics.kafka_transactions_aborted_total
 		kafka_queue_messages:                components.sources.internal_metrics.output.metrics.kafka_queue_messages
 		kafka_queue_messages_bytes:          components.sources.internal_metrics.output.metrics.kafka_queue_messages_bytes
 		kafka_requests_total:                components.sources.internal_metrics.output.metrics.kafka_requests_total
@@ -136,6 +136,50 @@ components: sinks: loki: {
 			required:    false
 			type: bool: default: false
 		}
+		remove_structured_metadata_fields: {
+			common:      false
+			description: "If this is set to `true` then the fields used to build the structured metadata will also get removed from the event."
+			required:    false
+			type: bool: default: false
+		}
+		reorder_window_ms: {
+			common: false
+			description: """
+				How long, in milliseconds, events of the same stream are held before being sent so that events arriving
+				late can be put back in order. Events arriving after their stream was sent are still handled by
+				`out_of_order_action`. To bound memory usage, a stream is sent early once it holds 10,000 events, and
+				the oldest stream is sent early once 10,000 streams are held. Setting this to `0` disables reordering.
+				"""
+			required: false
+			type: uint: {
+				default: 0
+				unit:    "milliseconds"
+			}
+		}
+		structured_metadata: {
+			common: false
+			description: """
+				Key/value pairs attached to each log line as [structured metadata](\(urls.loki_structured_metadata)).
+				Unlike labels, structured metadata doesn't create new streams, making it suitable for high cardinality
+				values such as trace IDs. Both keys and values are templateable. Requires Loki 2.9.0 or newer.
+				"""
+			required: false
+			type: object: {
+				examples: [{"trace_id": "{{ trace_id }}"}]
+				options: {
+					"*": {
+						common:      false
+						description: "Any Loki structured metadata, such as `trace_id`"
+						required:    false
+						type: string: {
+							default: null
+							examples: ["{{ trace_id }}"]
+							syntax: "template"
+						}
+					}
+				}
+			}
+		}
 
 		remove_timestamp: {
 			common:      false
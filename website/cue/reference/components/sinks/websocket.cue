@@ -0,0 +1,111 @@
+package metadata
+
+components: sinks: websocket: {
+	title: "WebSocket"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		development:   "beta"
+		egress_method: "stream"
+		service_providers: []
+		stateful: false
+	}
+
+	features: {
+		acknowledgements: true
+		healthcheck: enabled: true
+		send: {
+			compression: enabled: false
+			encoding: {
+				enabled: true
+				codec: {
+					enabled: true
+					enum: ["json", "text"]
+				}
+			}
+			request: enabled: false
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				can_verify_hostname:    true
+				enabled_default:        false
+			}
+			to: {
+				service: services.websocket
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["tcp"]
+						ssl: "optional"
+					}
+				}
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	configuration: {
+		ping_interval_secs: {
+			common:      false
+			description: "The interval between the pings sent to keep the connection alive. Pings are disabled if unset."
+			required:    false
+			type: uint: {
+				default: null
+				examples: [30]
+				unit: "seconds"
+			}
+		}
+		ping_timeout_secs: {
+			common:        false
+			description:   "How long to wait for a pong after a ping before the connection is considered lost and re-established. Defaults to `ping_interval_secs`."
+			relevant_when: "`ping_interval_secs` is set"
+			required:      false
+			type: uint: {
+				default: null
+				examples: [10]
+				unit: "seconds"
+			}
+		}
+		uri: {
+			description: "The URI of the WebSocket server to send events to. The `wss` scheme enables TLS."
+			required:    true
+			type: string: {
+				examples: ["ws://127.0.0.1:8080/events", "wss://example.com/events"]
+			}
+		}
+	}
+
+	input: {
+		logs:    true
+		metrics: null
+	}
+
+	how_it_works: {
+		reconnects: {
+			title: "Reconnects"
+			body: """
+				Each event is sent as its own message, text for the `json` and `text` codecs. When the connection is
+				closed, fails, or a ping isn't answered in time, the sink reconnects with an exponential backoff.
+				An event which fails to be sent is sent again once reconnected, and dropped as errored after 3 attempts.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_discarded_events_total: components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:           components.sources.internal_metrics.output.metrics.component_errors_total
+		component_sent_bytes_total:       components.sources.internal_metrics.output.metrics.component_sent_bytes_total
+		component_sent_event_bytes_total: components.sources.internal_metrics.output.metrics.component_sent_event_bytes_total
+		component_sent_events_total:      components.sources.internal_metrics.output.metrics.component_sent_events_total
+		connection_established_total:     components.sources.internal_metrics.output.metrics.connection_established_total
+		connection_shutdown_total:        components.sources.internal_metrics.output.metrics.connection_shutdown_total
+	}
+}
@@ -69,6 +69,25 @@ components: sources: exec: {
 			required:    false
 			type: bool: default: true
 		}
+		include_run_events: {
+			common:      false
+			description: "Emit a log event at the end of each run, reporting its exit code, signal and duration."
+			required:    false
+			type: bool: default: false
+		}
+		stdout_protocol: {
+			common:      false
+			description: "The protocol of the command's stdout. Metric protocols decode stdout into metric events, stderr is still decoded as logs."
+			required:    false
+			type: string: {
+				default: "logs"
+				enum: {
+					logs:       "Logs decoded with the configured `framing` and `decoding`."
+					influx:     "Metrics in the [Influx line protocol](\(urls.influxdb_line_protocol)), one per line. Each numeric or boolean field becomes a gauge named `<measurement>_<field>`, or `<measurement>` for a field named `value`."
+					prometheus: "Metrics in the [Prometheus text exposition format](\(urls.prometheus_text_based_exposition_format)), read from the whole output of each run, which is discarded if longer than `maximum_buffer_size_bytes`. Only supported in the `scheduled` mode."
+				}
+			}
+		}
 		maximum_buffer_size_bytes: {
 			common:      false
 			description: "The maximum buffer size allowed before a log event will be generated."
@@ -87,7 +106,7 @@ components: sources: exec: {
 				options: {
 					exec_interval_secs: {
 						common:        true
-						description:   "The interval in seconds between scheduled command runs. The command will be killed if it takes longer than exec_interval_secs to run."
+						description:   "The interval in seconds between scheduled command runs."
 						relevant_when: "mode = `scheduled`"
 						required:      false
 						type: uint: {
@@ -95,6 +114,16 @@ components: sources: exec: {
 							unit:    "seconds"
 						}
 					}
+					timeout_secs: {
+						common:        false
+						description:   "The time in seconds after which a run is killed. Defaults to `exec_interval_secs`. The output written before the run was killed is still read."
+						relevant_when: "mode = `scheduled`"
+						required:      false
+						type: uint: {
+							default: null
+							unit:    "seconds"
+						}
+					}
 				}
 			}
 		}
@@ -127,6 +156,56 @@ components: sources: exec: {
 		}
 	}
 
+	output: logs: run: {
+		description: "The end of a run, emitted when `include_run_events` is set."
+		fields: {
+			host:      fields._local_host
+			timestamp: fields._current_timestamp
+			message: {
+				description: "A constant message."
+				required:    true
+				type: string: examples: ["Command executed."]
+			}
+			pid: {
+				description: "The process ID of the command."
+				required:    true
+				type: uint: {
+					examples: [60085, 668]
+					unit: null
+				}
+			}
+			exit_code: {
+				description: "The exit code of the command, absent if it was killed by a signal."
+				required:    false
+				common:      true
+				type: int: default: null
+			}
+			signal: {
+				description: "The signal that killed the command, on Unix."
+				required:    false
+				common:      false
+				type: int: default: null
+			}
+			duration_seconds: {
+				description: "The duration of the run."
+				required:    true
+				type: float: examples: [0.25]
+			}
+			timed_out: {
+				description: "Whether the command was killed for exceeding `scheduled.timeout_secs`."
+				required:    true
+				type: bool: {}
+			}
+		}
+	}
+
+	output: metrics: {
+		counter:   output._passthrough_counter
+		gauge:     output._passthrough_gauge
+		histogram: output._passthrough_histogram
+		summary:   output._passthrough_summary
+	}
+
 	output: logs: line: {
 		description: "An individual event from exec."
 		fields: {
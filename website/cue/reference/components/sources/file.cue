@@ -49,6 +49,18 @@ components: sources: file: {
 
 	configuration: {
 		acknowledgements: configuration._source_acknowledgements
+		discovery: {
+			common:      false
+			description: "How Vector discovers files matching the include patterns and new data in them. See [Autodiscovery](#autodiscovery) for more info."
+			required:    false
+			type: string: {
+				default: "glob"
+				enum: {
+					glob:   "Search for files every `glob_minimum_cooldown_ms`, and poll each file for new data."
+					notify: "Watch the directories of the include patterns for changes, using inotify on Linux and the native file system notifications on other platforms. Files are searched for only when files are created, renamed or removed, and only the files written to are read. Falls back to `glob` for directories that can't be watched."
+				}
+			}
+		}
 		exclude: {
 			common:      false
 			description: "Array of file patterns to exclude. [Globbing](#globbing) is supported.*Takes precedence over the [`include` option](#include).*"
@@ -280,6 +292,17 @@ components: sources: file: {
 				maintains a unique list of files and will not tail a file more than
 				once, even if it matches multiple patterns. You can read more about
 				how we identify files in the Identification section.
+
+				With `discovery` set to `notify`, Vector instead watches the
+				directories of your include patterns for changes, which saves
+				searching for files and polling each of them on hosts with many log
+				files. The directory of each pattern is the longest part of it
+				without wildcards, watched recursively if the pattern matches files
+				in its subdirectories. If a directory can't be watched, for example
+				because it doesn't exist yet or because the file system doesn't
+				support notifications, Vector falls back to searching for files
+				periodically and tries again to watch it every 10 seconds. Note that
+				file systems such as NFS don't notify changes made by other hosts.
 				"""
 		}
 
@@ -0,0 +1,146 @@
+package metadata
+
+components: sources: http_client: {
+	title: "HTTP Client"
+
+	classes: {
+		commonly_used: false
+		delivery:      "at_least_once"
+		deployment_roles: ["daemon", "sidecar", "aggregator"]
+		development:   "beta"
+		egress_method: "batch"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		codecs: {
+			enabled:         true
+			default_framing: "`bytes`"
+		}
+		collect: {
+			checkpoint: enabled: false
+			from: {
+				service: services.http_client
+
+				interface: {
+					socket: {
+						direction: "outgoing"
+						protocols: ["http"]
+						ssl: "optional"
+					}
+				}
+			}
+			proxy: enabled: true
+		}
+		multiline: enabled: false
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		endpoints: {
+			description: "The HTTP/HTTPS endpoints to poll."
+			required:    true
+			type: array: {
+				items: type: string: {
+					examples: ["http://localhost:8080/api/events"]
+				}
+			}
+		}
+		headers: {
+			common:      false
+			description: "Headers added to the requests."
+			required:    false
+			type: object: {
+				examples: [{"Accept": "application/json"}]
+				options: {}
+			}
+		}
+		interval_secs: {
+			description: "The interval between polls, which must be greater than zero. Requests taking longer than the interval are abandoned."
+			common:      true
+			required:    false
+			type: uint: {
+				default: 15
+				unit:    "seconds"
+			}
+		}
+		query: {
+			common:      false
+			description: """
+				Query parameters added to the endpoints. Values are templates rendered against the last event received from the
+				endpoint, for example to only request the events after the last seen one. Parameters that can't be rendered before
+				the first event was received are left out.
+				"""
+			required:    false
+			type: object: {
+				examples: [{"after": "{{ id }}", "since": "%Y-%m-%dT%H:%M:%SZ"}]
+				options: {}
+			}
+		}
+		split_field: {
+			common:      false
+			description: "A field holding an array, each element of which is emitted as its own event. Objects become the fields of the event, other values its `message`."
+			required:    false
+			type: string: {
+				default: null
+				examples: ["items", "data.results"]
+			}
+		}
+		tls: configuration._tls_connect & {_args: {
+			can_enable:             true
+			can_verify_certificate: true
+			can_verify_hostname:    true
+			enabled_default:        false
+		}}
+		auth: configuration._http_auth & {_args: {
+			password_example: "${HTTP_PASSWORD}"
+			username_example: "${HTTP_USERNAME}"
+		}}
+	}
+
+	output: logs: event: {
+		description: "An event decoded from the response of an endpoint."
+		fields: {
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["http_client"]
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	how_it_works: {
+		polling: {
+			title: "Polling"
+			body: """
+				Each endpoint is requested with `GET` every `interval_secs`. Responses without a `2xx` status, and requests taking longer
+				than `interval_secs`, are reported as errors and don't produce events. The response body is decoded with the configured `framing` and `decoding`, the `json`
+				codec emitting an event per element of a top-level array.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		http_error_response_total:            components.sources.internal_metrics.output.metrics.http_error_response_total
+		http_request_errors_total:            components.sources.internal_metrics.output.metrics.http_request_errors_total
+		request_duration_seconds:             components.sources.internal_metrics.output.metrics.request_duration_seconds
+		requests_completed_total:             components.sources.internal_metrics.output.metrics.requests_completed_total
+	}
+}
@@ -613,6 +613,12 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		kafka_transactions_aborted_total: {
+			description:       "The total number of Kafka transactions aborted and retried."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
 		kafka_queue_messages: {
 			description:       "Current number of messages in producer queues."
 			type:              "gauge"
@@ -673,6 +679,55 @@ components: sources: internal_metrics: {
 			default_namespace: "vector"
 			tags:              _component_tags
 		}
+		kafka_consumer_coordinator_rtt_seconds: {
+			description:       "The average round trip time of the requests to the consumer group coordinator, which the offsets are committed to, over each statistics interval. This includes all the requests to the coordinator, not only the offset commits."
+			type:              "histogram"
+			default_namespace: "vector"
+			tags:              _component_tags
+		}
+		kafka_consumer_fetched_messages_total: {
+			description:       "Total number of messages fetched from a Kafka partition."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				topic_id:     _topic_id
+				partition_id: _partition_id
+			}
+		}
+		kafka_consumer_fetched_messages_bytes_total: {
+			description:       "Total number of message bytes fetched from a Kafka partition."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				topic_id:     _topic_id
+				partition_id: _partition_id
+			}
+		}
+		kafka_consumer_lag: {
+			description:       "The Kafka consumer lag of a partition, in messages. Removed when the partition is revoked from the consumer."
+			type:              "gauge"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				topic_id:     _topic_id
+				partition_id: _partition_id
+			}
+		}
+		kafka_consumer_rebalances_total: {
+			description:       "Total number of times the Kafka consumer group rebalanced."
+			type:              "counter"
+			default_namespace: "vector"
+			tags:              _component_tags & {
+				kind: {
+					description: "The kind of rebalance."
+					required:    true
+					enum: {
+						assign: "Partitions were assigned to the consumer."
+						revoke: "Partitions were revoked from the consumer."
+						error:  "The rebalance failed."
+					}
+				}
+			}
+		}
 		file_delete_errors_total: {
 			description:       "The total number of failures to delete a file. This metric is deprecated in favor of `component_errors_total`."
 			type:              "counter"
@@ -1203,6 +1258,10 @@ components: sources: internal_metrics: {
 				unix: "Unix domain socket"
 			}
 		}
+		_partition_id: {
+			description: "The Kafka partition."
+			required:    true
+		}
 		_output: {
 			description: "The specific output of the component."
 			required:    false
@@ -1224,6 +1283,10 @@ components: sources: internal_metrics: {
 			description: "The path that produced the error."
 			required:    true
 		}
+		_topic_id: {
+			description: "The Kafka topic."
+			required:    true
+		}
 		_reason: {
 			description: "The type of the error"
 			required:    true
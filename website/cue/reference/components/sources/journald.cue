@@ -19,9 +19,8 @@ components: sources: journald: {
 			from: {
 				service: services.journald
 
-				interface: binary: {
-					name: "journalctl"
-					permissions: unix: group: "systemd-journal"
+				interface: file_system: {
+					directory: "/var/log/journal"
 				}
 			}
 		}
@@ -142,7 +141,7 @@ components: sources: journald: {
 		}
 		journalctl_path: {
 			common:      false
-			description: "The full path of the `journalctl` executable. If not set, Vector will search the path for `journalctl`."
+			description: "The full path of the `journalctl` executable, used by the `journalctl` reader. If not set, Vector will search the path for `journalctl`. It cannot be combined with the `native` reader."
 			required:    false
 			type: string: {
 				default: "journalctl"
@@ -151,13 +150,25 @@ components: sources: journald: {
 		}
 		journal_directory: {
 			common:      false
-			description: "The full path of the journal directory. If not set, `journalctl` will use the default system journal paths"
+			description: "The full path of the journal directory. The journal files of the directory and of its subdirectories are read. If not set, the default system journal paths, `/var/log/journal` and `/run/log/journal`, are used."
 			required:    false
 			type: string: {
 				default: null
 				examples: ["/run/log/journal"]
 			}
 		}
+		reader: {
+			common:      false
+			description: "How the journal is read."
+			required:    false
+			type: string: {
+				default: "journalctl"
+				enum: {
+					native:     "Read the journal files directly, without spawning any process."
+					journalctl: "Read the output of a `journalctl` subprocess."
+				}
+			}
+		}
 	}
 
 	output: logs: {
@@ -228,13 +239,28 @@ components: sources: journald: {
 		communication_strategy: {
 			title: "Communication Strategy"
 			body:  """
-				To ensure the `journald` source works across all platforms, Vector interacts
-				with the Systemd journal via the `journalctl` command. This is accomplished by
-				spawning a [subprocess](\(urls.rust_subprocess)) that Vector interacts
-				with. If the `journalctl` command is not in the environment path you can
-				specify the exact location via the `journalctl_path` option. For more
-				information on this communication strategy please see
-				[issue #1473](\(urls.vector_issues)/1437).
+				By default, Vector interacts with the Systemd journal via the `journalctl`
+				command. This is accomplished by spawning a
+				[subprocess](\(urls.rust_subprocess)) that Vector interacts with. If the
+				`journalctl` command is not in the environment path you can specify the exact
+				location via the `journalctl_path` option. For more information on this
+				communication strategy please see [issue #1473](\(urls.vector_issues)/1437).
+
+				Setting `reader` to `native` makes Vector read the journal files directly
+				instead, without spawning any process, following the active files as they are
+				written to and picking up the files created when the journal is rotated.
+				Entries of all the files are read in the order they were written, like
+				`journalctl --follow` does. Reading the files requires Vector to be a member of
+				the `systemd-journal` group. Files compressed with XZ are not supported and are
+				skipped.
+				"""
+		}
+		journal_cursors: {
+			title: "Journal Cursors"
+			body: """
+				Vector checkpoints the cursor of the last entry read, in the same format as
+				`journalctl --show-cursor`, so both readers can resume from the checkpoints
+				of the other.
 				"""
 		}
 		non_ascii: {
@@ -242,9 +268,9 @@ components: sources: journald: {
 			body: """
 				When `journald` has stored a message that is not strict ASCII,
 				`journalctl` will output it in an alternate format to prevent data
-				loss. Vector handles this alternate format by translating such messages
-				into UTF-8 in "lossy" mode, where characters that are not valid UTF-8
-				are replaced with the Unicode replacement character, `�`.
+				loss. Vector handles this alternate format, as well as such messages read
+				from the journal files, by keeping the raw bytes of the values that are not
+				valid UTF-8, so no data is lost.
 				"""
 		}
 	}
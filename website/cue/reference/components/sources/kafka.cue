@@ -64,6 +64,12 @@ components: sources: kafka: {
 				unit: "milliseconds"
 			}
 		}
+		commit_offsets: {
+			common:      false
+			description: "Whether the consumer commits the offsets of the events it read. Disable it when a `kafka` sink with `exactly_once.source_offsets` commits them in its transactions instead, which also lets that sink commit the offsets as a member of the group of this source."
+			required:    false
+			type: bool: default: true
+		}
 		fetch_wait_max_ms: {
 			common:      false
 			description: "Maximum time the broker may wait to fill the response."
@@ -75,9 +81,11 @@ components: sources: kafka: {
 			}
 		}
 		group_id: {
-			description: "The consumer group name to be used to consume events from Kafka."
-			required:    true
+			common:      true
+			description: "The consumer group name to be used to consume events from Kafka. Required to subscribe to `topics`. When consuming `partitions` without a group, offsets aren't committed, so partitions start at `start_timestamp` or `auto_offset_reset` each time Vector starts."
+			required:    false
 			type: string: {
+				default: null
 				examples: ["consumer-group-name"]
 			}
 		}
@@ -99,6 +107,29 @@ components: sources: kafka: {
 				examples: ["topic"]
 			}
 		}
+		partitions: {
+			common:      false
+			description: "Explicit partitions to consume, instead of subscribing to `topics`. Partitions aren't balanced between the consumers of a group, and partitions added to the topics aren't consumed. Exactly one of `topics` and `partitions` must be set."
+			required:    false
+			type: array: {
+				default: []
+				items: type: object: options: {
+					topic: {
+						description: "The topic of the partitions."
+						required:    true
+						type: string: examples: ["topic-1"]
+					}
+					partitions: {
+						description: "The partitions of the topic to consume."
+						required:    true
+						type: array: items: type: uint: {
+							examples: [0, 1]
+							unit: null
+						}
+					}
+				}
+			}
+		}
 		partition_key: {
 			common:      false
 			description: "The log field name to use for the Kafka partition name."
@@ -181,11 +212,37 @@ components: sources: kafka: {
 			}
 		}
 		socket_timeout_ms: components._kafka.configuration.socket_timeout_ms
+		start_timestamp: {
+			common:      false
+			description: """
+				Start consuming partitions without committed offsets at the first message at or after this
+				time, instead of using `auto_offset_reset`. Useful to backfill with a new consumer group. When
+				subscribing to `topics`, the partitions are assigned to Vector at these offsets by each rebalance,
+				so partitions created later start there too. Nothing is committed until events are
+				consumed.
+				"""
+			required:    false
+			type: timestamp: default: null
+		}
+		topic_metadata_refresh_interval_ms: {
+			common:      false
+			description: "How often the topics are listed, so that new topics matching the regular expressions of `topics` are consumed."
+			required:    false
+			type: uint: {
+				default: 300000
+				examples: [10000]
+				unit: "milliseconds"
+			}
+		}
 		topics: {
-			description: "The Kafka topics names to read events from. Regex is supported if the topic begins with `^`."
-			required:    true
-			type: array: items: type: string: {
-				examples: ["^(prefix1|prefix2)-.+", "topic-1", "topic-2"]
+			common:      true
+			description: "The Kafka topics names to read events from. Regex is supported if the topic begins with `^`, and new topics matching it are consumed once found. Exactly one of `topics` and `partitions` must be set."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["^(prefix1|prefix2)-.+", "topic-1", "topic-2"]
+				}
 			}
 		}
 	}
@@ -229,26 +286,31 @@ components: sources: kafka: {
 	}
 
 	telemetry: metrics: {
-		events_failed_total:                  components.sources.internal_metrics.output.metrics.events_failed_total
-		events_in_total:                      components.sources.internal_metrics.output.metrics.events_in_total
-		consumer_offset_updates_failed_total: components.sources.internal_metrics.output.metrics.consumer_offset_updates_failed_total
-		kafka_queue_messages:                 components.sources.internal_metrics.output.metrics.kafka_queue_messages
-		kafka_queue_messages_bytes:           components.sources.internal_metrics.output.metrics.kafka_queue_messages_bytes
-		kafka_requests_total:                 components.sources.internal_metrics.output.metrics.kafka_requests_total
-		kafka_requests_bytes_total:           components.sources.internal_metrics.output.metrics.kafka_requests_bytes_total
-		kafka_responses_total:                components.sources.internal_metrics.output.metrics.kafka_responses_total
-		kafka_responses_bytes_total:          components.sources.internal_metrics.output.metrics.kafka_responses_bytes_total
-		kafka_produced_messages_total:        components.sources.internal_metrics.output.metrics.kafka_produced_messages_total
-		kafka_produced_messages_bytes_total:  components.sources.internal_metrics.output.metrics.kafka_produced_messages_bytes_total
-		kafka_consumed_messages_total:        components.sources.internal_metrics.output.metrics.kafka_consumed_messages_total
-		kafka_consumed_messages_bytes_total:  components.sources.internal_metrics.output.metrics.kafka_consumed_messages_bytes_total
-		processed_bytes_total:                components.sources.internal_metrics.output.metrics.processed_bytes_total
-		processed_events_total:               components.sources.internal_metrics.output.metrics.processed_events_total
-		component_discarded_events_total:     components.sources.internal_metrics.output.metrics.component_discarded_events_total
-		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
-		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
-		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
-		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		events_failed_total:                         components.sources.internal_metrics.output.metrics.events_failed_total
+		events_in_total:                             components.sources.internal_metrics.output.metrics.events_in_total
+		consumer_offset_updates_failed_total:        components.sources.internal_metrics.output.metrics.consumer_offset_updates_failed_total
+		kafka_queue_messages:                        components.sources.internal_metrics.output.metrics.kafka_queue_messages
+		kafka_queue_messages_bytes:                  components.sources.internal_metrics.output.metrics.kafka_queue_messages_bytes
+		kafka_requests_total:                        components.sources.internal_metrics.output.metrics.kafka_requests_total
+		kafka_requests_bytes_total:                  components.sources.internal_metrics.output.metrics.kafka_requests_bytes_total
+		kafka_responses_total:                       components.sources.internal_metrics.output.metrics.kafka_responses_total
+		kafka_responses_bytes_total:                 components.sources.internal_metrics.output.metrics.kafka_responses_bytes_total
+		kafka_produced_messages_total:               components.sources.internal_metrics.output.metrics.kafka_produced_messages_total
+		kafka_produced_messages_bytes_total:         components.sources.internal_metrics.output.metrics.kafka_produced_messages_bytes_total
+		kafka_consumed_messages_total:               components.sources.internal_metrics.output.metrics.kafka_consumed_messages_total
+		kafka_consumed_messages_bytes_total:         components.sources.internal_metrics.output.metrics.kafka_consumed_messages_bytes_total
+		kafka_consumer_coordinator_rtt_seconds:      components.sources.internal_metrics.output.metrics.kafka_consumer_coordinator_rtt_seconds
+		kafka_consumer_fetched_messages_total:       components.sources.internal_metrics.output.metrics.kafka_consumer_fetched_messages_total
+		kafka_consumer_fetched_messages_bytes_total: components.sources.internal_metrics.output.metrics.kafka_consumer_fetched_messages_bytes_total
+		kafka_consumer_lag:                          components.sources.internal_metrics.output.metrics.kafka_consumer_lag
+		kafka_consumer_rebalances_total:             components.sources.internal_metrics.output.metrics.kafka_consumer_rebalances_total
+		processed_bytes_total:                       components.sources.internal_metrics.output.metrics.processed_bytes_total
+		processed_events_total:                      components.sources.internal_metrics.output.metrics.processed_events_total
+		component_discarded_events_total:            components.sources.internal_metrics.output.metrics.component_discarded_events_total
+		component_errors_total:                      components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:              components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:             components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total:        components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
 	}
 
 	how_it_works: components._kafka.how_it_works
@@ -0,0 +1,298 @@
+package metadata
+
+components: sources: netflow: {
+	_port: 2055
+
+	title: "NetFlow"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.netflow
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			keepalive: enabled:            false
+			tls: enabled:                  false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The address to listen for flow packets on. It _must_ include a port."
+			required:    true
+			type: string: {
+				examples: ["0.0.0.0:\(_port)", "0.0.0.0:4739", "0.0.0.0:6343"]
+			}
+		}
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: """
+				The key name added to each event representing the address of the exporter. This can also be globally set via the
+				[global `host_key` option](\(urls.vector_configuration)/global-options#log_schema.host_key).
+				"""
+			required:    false
+			type: string: {
+				default: "host"
+			}
+		}
+		max_templates: {
+			common:      false
+			description: "The maximum number of NetFlow v9 and IPFIX templates cached across all the exporters. The least recently received templates are evicted to make room for new ones."
+			required:    false
+			type: uint: {
+				default: 10000
+				unit:    null
+			}
+		}
+		template_timeout_secs: {
+			common:      false
+			description: "How long the NetFlow v9 and IPFIX templates are cached when they aren't received again. Exporters resend their templates periodically, so this should be a few times their template refresh interval."
+			required:    false
+			type: uint: {
+				default: 1800
+				unit:    "seconds"
+			}
+		}
+	}
+
+	output: logs: flow: {
+		description: "A flow record of NetFlow or IPFIX, or a flow sample of sFlow."
+		fields: {
+			"*": {
+				description: "The fields of NetFlow v9 and IPFIX records, named after their [IPFIX information element](\(urls.ipfix_information_elements)). NetFlow v5 records use the names of the matching information elements. Unknown fields are named `field_<id>`, or `enterprise_<enterprise>_field_<id>`."
+				required:    true
+				type: string: {
+					examples: ["192.168.1.10"]
+				}
+			}
+			flow_type: {
+				description: "The protocol of the flow."
+				required:    true
+				type: string: {
+					enum: {
+						netflow_v5: "NetFlow v5."
+						netflow_v9: "NetFlow v9, as described by [RFC 3954](\(urls.rfc_3954))."
+						ipfix:      "IPFIX, as described by [RFC 7011](\(urls.rfc_7011))."
+						sflow:      "sFlow v5."
+					}
+				}
+			}
+			host: {
+				description: "The IP address of the exporter."
+				required:    true
+				type: string: {
+					examples: ["192.0.2.1"]
+				}
+			}
+			observation_domain_id: {
+				description:   "The observation domain of the record."
+				relevant_when: "`flow_type` is `ipfix`"
+				required:      false
+				type: uint: {
+					default: null
+					examples: [42]
+					unit: null
+				}
+			}
+			record_type: {
+				description:   "Whether the record describes a flow, or the exporter itself."
+				relevant_when: "`flow_type` is `netflow_v9` or `ipfix`"
+				required:      false
+				type: string: {
+					default: null
+					enum: {
+						data:    "A flow record."
+						options: "An options record, describing the exporter."
+					}
+				}
+			}
+			sequence_number: {
+				description: "The sequence number of the packet, used to detect lost packets."
+				required:    true
+				type: uint: {
+					examples: [1234]
+					unit: null
+				}
+			}
+			source_id: {
+				description:   "The observation domain of the record."
+				relevant_when: "`flow_type` is `netflow_v9`"
+				required:      false
+				type: uint: {
+					default: null
+					examples: [0]
+					unit: null
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["netflow"]
+				}
+			}
+			template_id: {
+				description:   "The ID of the template describing the record."
+				relevant_when: "`flow_type` is `netflow_v9` or `ipfix`"
+				required:      false
+				type: uint: {
+					default: null
+					examples: [256]
+					unit: null
+				}
+			}
+			timestamp: {
+				description: "The export time of NetFlow and IPFIX packets, or the time sFlow samples were received."
+				required:    true
+				type: timestamp: {}
+			}
+		}
+	}
+
+	output: metrics: {
+		_sflow_tags: {
+			agent_address: {
+				description: "The address of the sFlow agent."
+				required:    true
+				examples: ["192.0.2.1"]
+			}
+			host: {
+				description: "The IP address of the exporter."
+				required:    true
+				examples: ["192.0.2.1"]
+			}
+			source_id_index: {
+				description: "The index of the data source of the counters, like the `ifIndex` of an interface."
+				required:    true
+				examples: ["3"]
+			}
+			source_id_type: {
+				description: "The type of the data source of the counters."
+				required:    true
+				examples: ["0"]
+			}
+			sub_agent_id: {
+				description: "The ID of the sub-agent of the sFlow agent."
+				required:    true
+				examples: ["0"]
+			}
+		}
+		_interface_tags: _sflow_tags & {
+			if_index: {
+				description: "The `ifIndex` of the interface."
+				required:    true
+				examples: ["3"]
+			}
+		}
+		_sflow: default_namespace: "sflow"
+		_interface_counter: _sflow & {
+			type: "counter"
+			tags: _interface_tags
+		}
+		_interface_gauge: _sflow & {
+			type: "gauge"
+			tags: _interface_tags
+		}
+		_ethernet_counter: _sflow & {
+			type: "counter"
+			tags: _interface_tags
+		}
+		_processor_gauge: _sflow & {
+			type: "gauge"
+			tags: _sflow_tags
+		}
+
+		interface_speed_bits:                       _interface_gauge & {description:   "The speed of the interface, in bits per second."}
+		interface_admin_up:                         _interface_gauge & {description:   "Whether the interface is administratively up."}
+		interface_oper_up:                          _interface_gauge & {description:   "Whether the interface is operationally up."}
+		interface_receive_bytes_total:              _interface_counter & {description: "The number of bytes received."}
+		interface_receive_unicast_packets_total:    _interface_counter & {description: "The number of unicast packets received."}
+		interface_receive_multicast_packets_total:  _interface_counter & {description: "The number of multicast packets received."}
+		interface_receive_broadcast_packets_total:  _interface_counter & {description: "The number of broadcast packets received."}
+		interface_receive_discards_total:           _interface_counter & {description: "The number of received packets discarded."}
+		interface_receive_errors_total:             _interface_counter & {description: "The number of received packets with errors."}
+		interface_receive_unknown_protocols_total:  _interface_counter & {description: "The number of received packets of unknown protocols."}
+		interface_transmit_bytes_total:             _interface_counter & {description: "The number of bytes transmitted."}
+		interface_transmit_unicast_packets_total:   _interface_counter & {description: "The number of unicast packets transmitted."}
+		interface_transmit_multicast_packets_total: _interface_counter & {description: "The number of multicast packets transmitted."}
+		interface_transmit_broadcast_packets_total: _interface_counter & {description: "The number of broadcast packets transmitted."}
+		interface_transmit_discards_total:          _interface_counter & {description: "The number of packets discarded before being transmitted."}
+		interface_transmit_errors_total:            _interface_counter & {description: "The number of packets which couldn't be transmitted because of errors."}
+
+		ethernet_alignment_errors_total:             _ethernet_counter & {description: "The number of frames which aren't an integral number of octets."}
+		ethernet_fcs_errors_total:                   _ethernet_counter & {description: "The number of frames failing the frame check sequence."}
+		ethernet_single_collision_frames_total:      _ethernet_counter & {description: "The number of frames transmitted after a single collision."}
+		ethernet_multiple_collision_frames_total:    _ethernet_counter & {description: "The number of frames transmitted after multiple collisions."}
+		ethernet_sqe_test_errors_total:              _ethernet_counter & {description: "The number of SQE test errors."}
+		ethernet_deferred_transmissions_total:       _ethernet_counter & {description: "The number of frames whose transmission was delayed because the medium was busy."}
+		ethernet_late_collisions_total:              _ethernet_counter & {description: "The number of collisions detected late in the transmission of frames."}
+		ethernet_excessive_collisions_total:         _ethernet_counter & {description: "The number of frames which couldn't be transmitted because of excessive collisions."}
+		ethernet_internal_mac_transmit_errors_total: _ethernet_counter & {description: "The number of frames which couldn't be transmitted because of internal MAC errors."}
+		ethernet_carrier_sense_errors_total:         _ethernet_counter & {description: "The number of times the carrier sense condition was lost."}
+		ethernet_frame_too_longs_total:              _ethernet_counter & {description: "The number of frames received exceeding the maximum frame size."}
+		ethernet_internal_mac_receive_errors_total:  _ethernet_counter & {description: "The number of frames which couldn't be received because of internal MAC errors."}
+		ethernet_symbol_errors_total:                _ethernet_counter & {description: "The number of invalid data symbols received."}
+
+		cpu_utilization_5s: _processor_gauge & {description: "The ratio of CPU utilization over the last 5 seconds."}
+		cpu_utilization_1m: _processor_gauge & {description: "The ratio of CPU utilization over the last minute."}
+		cpu_utilization_5m: _processor_gauge & {description: "The ratio of CPU utilization over the last 5 minutes."}
+		memory_total_bytes: _processor_gauge & {description: "The total memory of the agent, in bytes."}
+		memory_free_bytes:  _processor_gauge & {description: "The free memory of the agent, in bytes."}
+	}
+
+	how_it_works: {
+		protocols: {
+			title: "Protocols"
+			body:  """
+				The protocol of each packet is detected from its version, so a single source can receive NetFlow
+				v5 and v9, IPFIX and sFlow v5 packets. Each flow record becomes a log event. The counter samples of
+				sFlow become metrics, and its flow samples become log events with the fields of their decoded packet
+				headers.
+				"""
+		}
+		templates: {
+			title: "Templates"
+			body:  """
+				NetFlow v9 and IPFIX records are described by templates, which are sent periodically by exporters.
+				Templates are cached per exporter and observation domain until they're updated, withdrawn or
+				expire after `template_timeout_secs`, and records received before their template are discarded.
+				At most `max_templates` templates are cached.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
@@ -0,0 +1,371 @@
+package metadata
+
+components: sources: snmp_trap: {
+	_port: 162
+
+	title: "SNMP Trap"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		multiline: enabled: false
+		receive: {
+			from: {
+				service: services.snmp
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["udp"]
+					ssl: "disabled"
+				}
+			}
+			receive_buffer_bytes: enabled: true
+			keepalive: enabled:            false
+			tls: enabled:                  false
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description: "The address to listen for traps on. It _must_ include a port."
+			required:    true
+			type: string: {
+				examples: ["0.0.0.0:\(_port)"]
+			}
+		}
+		communities: {
+			common:      true
+			description: "The communities allowed to send SNMPv1 and SNMPv2c traps. Traps of any community are accepted when empty."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: examples: ["public"]
+			}
+		}
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: """
+				The key name added to each event representing the address of the agent sending the trap. This can also be globally set via the
+				[global `host_key` option](\(urls.vector_configuration)/global-options#log_schema.host_key).
+				"""
+			required:    false
+			type: string: {
+				default: "host"
+			}
+		}
+		mib_paths: {
+			common:      false
+			description: "MIB files, or directories of MIB files, used to translate the object identifiers of the traps and their variables to names such as `IF-MIB::linkDown`. Object identifiers aren't translated when empty."
+			required:    false
+			type: array: {
+				default: []
+				items: type: string: examples: ["/usr/share/snmp/mibs"]
+			}
+		}
+		users: {
+			common:      false
+			description: "The SNMPv3 users allowed to send traps, using the User-based Security Model. SNMPv3 traps are rejected when empty. Messages must use the exact security level of their user."
+			required:    false
+			type: array: {
+				default: []
+				items: type: object: options: {
+					name: {
+						description: "The name of the user."
+						required:    true
+						type: string: examples: ["operator"]
+					}
+					auth_protocol: {
+						description: "The protocol authenticating the messages of the user, which are unauthenticated if unset."
+						required:    false
+						common:      true
+						type: string: {
+							default: null
+							enum: {
+								md5:    "HMAC-MD5-96."
+								sha:    "HMAC-SHA-96."
+								sha224: "HMAC-SHA-224 as described by RFC 7860."
+								sha256: "HMAC-SHA-256 as described by RFC 7860."
+								sha384: "HMAC-SHA-384 as described by RFC 7860."
+								sha512: "HMAC-SHA-512 as described by RFC 7860."
+							}
+						}
+					}
+					auth_password: {
+						description:   "The authentication password of the user, of at least 8 characters."
+						relevant_when: "`auth_protocol` is set"
+						required:      false
+						common:        true
+						type: string: {
+							default: null
+							examples: ["${SNMP_AUTH_PASSWORD}"]
+						}
+					}
+					priv_protocol: {
+						description: "The protocol encrypting the messages of the user, which are unencrypted if unset. Requires `auth_protocol`."
+						required:    false
+						common:      true
+						type: string: {
+							default: null
+							enum: {
+								des: "CBC-DES."
+								aes: "CFB128-AES-128 as described by RFC 3826."
+							}
+						}
+					}
+					priv_password: {
+						description:   "The privacy password of the user, of at least 8 characters."
+						relevant_when: "`priv_protocol` is set"
+						required:      false
+						common:        true
+						type: string: {
+							default: null
+							examples: ["${SNMP_PRIV_PASSWORD}"]
+						}
+					}
+				}
+			}
+		}
+	}
+
+	output: logs: trap: {
+		description: "A trap or inform received from an SNMP agent."
+		fields: {
+			agent_address: {
+				description:   "The address of the agent which generated the trap."
+				relevant_when: "`version` is `1`"
+				required:      false
+				type: string: {
+					default: null
+					examples: ["192.168.1.10"]
+				}
+			}
+			community: {
+				description:   "The community of the trap."
+				relevant_when: "`version` is `1` or `2c`"
+				required:      false
+				type: string: {
+					default: null
+					examples: ["public"]
+				}
+			}
+			context_engine_id: {
+				description:   "The hex encoded context engine ID of the trap."
+				relevant_when: "`version` is `3`"
+				required:      false
+				type: string: {
+					default: null
+					examples: ["80001f8804"]
+				}
+			}
+			context_name: {
+				description:   "The context name of the trap."
+				relevant_when: "`version` is `3`"
+				required:      false
+				type: string: {
+					default: null
+					examples: [""]
+				}
+			}
+			engine_id: {
+				description:   "The hex encoded ID of the engine which sent the trap."
+				relevant_when: "`version` is `3`"
+				required:      false
+				type: string: {
+					default: null
+					examples: ["80001f8804"]
+				}
+			}
+			enterprise: {
+				description:   "The object identifier of the agent which generated the trap."
+				relevant_when: "`version` is `1`"
+				required:      false
+				type: string: {
+					default: null
+					examples: ["1.3.6.1.4.1.8072.3.2.10"]
+				}
+			}
+			generic_trap: {
+				description:   "The generic trap number."
+				relevant_when: "`version` is `1`"
+				required:      false
+				type: uint: {
+					default: null
+					examples: [2]
+					unit: null
+				}
+			}
+			host: {
+				description: "The IP address of the agent sending the trap."
+				required:    true
+				type: string: {
+					examples: ["192.168.1.10"]
+				}
+			}
+			message: {
+				description: "The name of the trap if translated, its object identifier otherwise."
+				required:    true
+				type: string: {
+					examples: ["IF-MIB::linkDown", "1.3.6.1.6.3.1.1.5.3"]
+				}
+			}
+			pdu_type: {
+				description: "The type of the PDU."
+				required:    true
+				type: string: {
+					enum: {
+						trap_v1: "An SNMPv1 trap."
+						trap:    "An SNMPv2 trap."
+						inform:  "An inform, acknowledged for SNMPv2c."
+					}
+				}
+			}
+			request_id: {
+				description:   "The request ID of the trap."
+				relevant_when: "`version` is `2c` or `3`"
+				required:      false
+				type: uint: {
+					default: null
+					examples: [1804289383]
+					unit: null
+				}
+			}
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["snmp_trap"]
+				}
+			}
+			specific_trap: {
+				description:   "The enterprise specific trap number."
+				relevant_when: "`version` is `1`"
+				required:      false
+				type: uint: {
+					default: null
+					examples: [0]
+					unit: null
+				}
+			}
+			timestamp: fields._current_timestamp
+			trap_name: {
+				description: "The translated name of the trap, if `mib_paths` are configured."
+				required:    false
+				type: string: {
+					default: null
+					examples: ["IF-MIB::linkDown"]
+				}
+			}
+			trap_oid: {
+				description: "The object identifier of the trap. SNMPv1 traps are converted as described by [RFC 3584](\(urls.rfc_3584))."
+				required:    true
+				type: string: {
+					examples: ["1.3.6.1.6.3.1.1.5.3"]
+				}
+			}
+			uptime: {
+				description: "The time since the agent was started, in hundredths of seconds."
+				required:    false
+				type: uint: {
+					default: null
+					examples: [12345]
+					unit: null
+				}
+			}
+			user: {
+				description:   "The name of the user which sent the trap."
+				relevant_when: "`version` is `3`"
+				required:      false
+				type: string: {
+					default: null
+					examples: ["operator"]
+				}
+			}
+			variables: {
+				description: "The variables of the trap. Printable octet strings are decoded as text, and other ones are rendered as colon separated hexadecimal bytes."
+				required:    true
+				type: array: items: type: object: {
+					examples: [
+						{
+							oid:   "1.3.6.1.2.1.2.2.1.2.2"
+							name:  "IF-MIB::ifDescr.2"
+							type:  "octet_string"
+							value: "eth0"
+						},
+					]
+				}
+			}
+			version: {
+				description: "The SNMP version of the trap."
+				required:    true
+				type: string: {
+					enum: {
+						"1":  "SNMPv1."
+						"2c": "SNMPv2c."
+						"3":  "SNMPv3."
+					}
+				}
+			}
+		}
+	}
+
+	how_it_works: {
+		security: {
+			title: "Security"
+			body:  """
+				SNMPv1 and SNMPv2c traps are only identified by their community, which is sent in clear text, so
+				`communities` only protects against misconfigured agents.
+
+				SNMPv3 traps are authenticated and decrypted with the keys of the configured `users`, as
+				described by [RFC 3414](\(urls.rfc_3414)) and [RFC 3826](\(urls.rfc_3826)). The agents are the
+				authoritative engines of their traps, so the keys are localized with their engine ID and no engine
+				discovery is required. The boots and time of each engine are learnt from its authenticated traps,
+				and the authenticated traps more than 150 seconds behind their engine, or from a previous boot,
+				are rejected as replays.
+				"""
+		}
+		informs: {
+			title: "Informs"
+			body:  """
+				SNMPv2c informs are acknowledged with a response once decoded. SNMPv3 informs require the
+				receiver to be their authoritative engine, which isn't supported, so they're forwarded without
+				being acknowledged.
+				"""
+		}
+		mibs: {
+			title: "MIB Translation"
+			body:  """
+				The `OBJECT IDENTIFIER`, `OBJECT-TYPE`, `NOTIFICATION-TYPE` and `TRAP-TYPE` assignments of the
+				MIB files in `mib_paths` are used to translate object identifiers. The longest known prefix of an
+				object identifier is translated, keeping the remaining arcs as its instance, such as
+				`IF-MIB::ifDescr.2`. Object identifiers outside of the loaded modules are translated from their closest standard ancestor, such as
+				`SNMPv2-SMI::enterprises.8072.3.2.10`.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+	}
+}
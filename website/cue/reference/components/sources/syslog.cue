@@ -107,6 +107,16 @@ components: sources: syslog: {
 				unit:    "concurrency"
 			}
 		}
+		tls_peer_key: {
+			category:      "Context"
+			common:        false
+			description:   "The key name added to each event holding the identity of a client that authenticated with a TLS certificate."
+			relevant_when: "mode = `tcp`"
+			required:      false
+			type: string: {
+				default: "tls_peer"
+			}
+		}
 	}
 
 	output: logs: line: {
@@ -169,6 +179,30 @@ components: sources: syslog: {
 					examples: ["127.0.0.1"]
 				}
 			}
+			tls_peer: {
+				description:   "The identity of the client, from the certificate it authenticated with. Only added when `tls.verify_certificate` is enabled and the client presents a certificate."
+				relevant_when: "mode = `tcp`"
+				required:      false
+				type: object: {
+					examples: []
+					options: {
+						subject: {
+							description: "The subject of the certificate."
+							required:    true
+							type: string: {
+								examples: ["CN=device-1,O=Example"]
+							}
+						}
+						subject_alt_names: {
+							description: "The subject alternative names of the certificate, prefixed by their type."
+							required:    true
+							type: array: items: type: string: {
+								examples: ["DNS:device-1.example.com", "IP:192.0.2.1"]
+							}
+						}
+					}
+				}
+			}
 			timestamp: {
 				description: "The time extracted from the Syslog formatted line. If parsing fails, then the exact time the event was ingested into Vector is used."
 				required:    true
@@ -231,6 +265,12 @@ components: sources: syslog: {
 			title: "Line Delimiters"
 			body: """
 				Each line is read until a new line delimiter, the `0xA` byte, is found.
+
+				In `tcp` mode the framing is detected for each connection from its first byte: connections starting
+				with a non-zero digit use [octet counting](\(urls.rfc_6587_3_4_1)), as sent by RFC 5425 and RFC 6587
+				clients, and the others are newline delimited. Clients sending both kinds of framing can share the same
+				port, but each connection keeps the framing of its first message: a client switching framing within a
+				connection has its later messages split incorrectly.
 				"""
 		}
 
@@ -0,0 +1,137 @@
+package metadata
+
+components: sources: websocket: {
+	_port: 8080
+
+	title: "WebSocket"
+
+	classes: {
+		commonly_used: false
+		delivery:      "best_effort"
+		deployment_roles: ["aggregator", "sidecar"]
+		development:   "beta"
+		egress_method: "stream"
+		stateful:      false
+	}
+
+	features: {
+		acknowledgements: false
+		multiline: enabled: false
+		codecs: {
+			enabled:         true
+			default_framing: "`bytes`"
+		}
+		receive: {
+			from: {
+				service: services.websocket
+				interface: socket: {
+					direction: "incoming"
+					port:      _port
+					protocols: ["tcp"]
+					ssl: "optional"
+				}
+			}
+			receive_buffer_bytes: enabled: false
+			keepalive: enabled:            false
+			tls: {
+				enabled:                true
+				can_enable:             true
+				can_verify_certificate: true
+				enabled_default:        false
+			}
+		}
+	}
+
+	support: {
+		requirements: []
+		warnings: []
+		notices: []
+	}
+
+	installation: {
+		platform_name: null
+	}
+
+	configuration: {
+		address: {
+			description:   "The address to listen for WebSocket connections on. It _must_ include a port."
+			relevant_when: "mode = `server`"
+			required:      true
+			type: string: {
+				examples: ["0.0.0.0:\(_port)"]
+			}
+		}
+		host_key: {
+			category:    "Context"
+			common:      false
+			description: """
+				The key name added to each event representing the address of the peer: the client in `server` mode, the
+				host of the `uri` in `client` mode. This can also be globally set via the
+				[global `host_key` option](\(urls.vector_configuration)/global-options#log_schema.host_key).
+				"""
+			required:    false
+			type: string: {
+				default: "host"
+			}
+		}
+		mode: {
+			description: "Whether to accept connections from WebSocket clients, or to connect to a WebSocket server."
+			required:    true
+			type: string: {
+				enum: {
+					server: "Listen on `address` for WebSocket clients."
+					client: "Connect to the server at `uri`, reconnecting with a backoff when the connection is lost."
+				}
+			}
+		}
+		uri: {
+			description:   "The URI of the WebSocket server to connect to. The `wss` scheme enables TLS."
+			relevant_when: "mode = `client`"
+			required:      true
+			type: string: {
+				examples: ["ws://127.0.0.1:\(_port)/events", "wss://example.com/events"]
+			}
+		}
+	}
+
+	output: logs: message: {
+		description: "An event decoded from a text or binary WebSocket message."
+		fields: {
+			host: {
+				description: "The address of the peer."
+				required:    true
+				type: string: {
+					examples: ["127.0.0.1", "example.com"]
+				}
+			}
+			message: fields._raw_line
+			source_type: {
+				description: "The name of the source type."
+				required:    true
+				type: string: {
+					examples: ["websocket"]
+				}
+			}
+			timestamp: fields._current_timestamp
+		}
+	}
+
+	how_it_works: {
+		messages: {
+			title: "Messages"
+			body: """
+				Each text and binary message is decoded on its own, with the configured `framing` and `decoding`.
+				Pings are answered automatically, and control messages don't produce events.
+				"""
+		}
+	}
+
+	telemetry: metrics: {
+		component_errors_total:               components.sources.internal_metrics.output.metrics.component_errors_total
+		component_received_bytes_total:       components.sources.internal_metrics.output.metrics.component_received_bytes_total
+		component_received_event_bytes_total: components.sources.internal_metrics.output.metrics.component_received_event_bytes_total
+		component_received_events_total:      components.sources.internal_metrics.output.metrics.component_received_events_total
+		connection_established_total:         components.sources.internal_metrics.output.metrics.connection_established_total
+		connection_shutdown_total:            components.sources.internal_metrics.output.metrics.connection_shutdown_total
+	}
+}
@@ -75,6 +75,41 @@ components: transforms: "remap": {
 				]
 			}
 		}
+		import_paths: {
+			common:      false
+			description: """
+				Directories in which the files imported by the program with [`import`](\(urls.vrl_expressions)#import)
+				expressions are looked up, in order.
+
+				If a relative path is provided, its root is the current working directory.
+				"""
+			required: false
+			type: array: {
+				default: []
+				items: type: string: {
+					examples: ["./vrl/modules"]
+				}
+			}
+		}
+		secrets: {
+			common:      false
+			description: """
+				Secrets the program can read with [`get_secret`](\(urls.vrl_functions)#get_secret), such as the
+				keys of cryptographic functions, so they aren't written into the program. Each secret is read from
+				the file at the given path when the program is compiled.
+				"""
+			required: false
+			type: object: {
+				examples: [{"hmac_key": "/etc/vector/secrets/hmac_key"}]
+				options: {
+					"*": {
+						description: "The path of the file holding the secret, read as is."
+						required:    true
+						type: string: {}
+					}
+				}
+			}
+		}
 		drop_on_error: {
 			common:   false
 			required: false
@@ -104,6 +139,26 @@ components: transforms: "remap": {
 				"""
 			type: bool: default: false
 		}
+		runtime: {
+			common:   false
+			required: false
+			description: """
+				The runtime executing the VRL program. All runtimes produce the same results.
+				"""
+			type: string: {
+				default: "ast"
+				enum: {
+					ast: "Evaluates the expressions of the program."
+					vm:  "Interprets the program compiled to the instructions of a virtual machine."
+					jit: """
+						Compiles the program to native code when the transform is built. Functions, and the
+						closures passed to them, still run on the virtual machine of the `vm` runtime.
+						Requires Vector to be built with the `vrl-jit` feature, the whole program runs on the
+						`vm` runtime otherwise.
+						"""
+				}
+			}
+		}
 	}
 
 	input: {
@@ -0,0 +1,26 @@
+package metadata
+
+remap: errors: "109": {
+	title:       "Unexpected function closure"
+	description: """
+		A [function call expression](\(urls.vrl_expressions)#function-call) passes a closure to a
+		function that doesn't accept one.
+		"""
+	resolution: """
+		Remove the closure, or call one of the functions that iterate using a closure, such as
+		`map_values` or `for_each`.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				upcase(.message) -> |value| { value }
+				"""#
+			diff: #"""
+				-upcase(.message) -> |value| { value }
+				+upcase(.message)
+				"""#
+		},
+	]
+}
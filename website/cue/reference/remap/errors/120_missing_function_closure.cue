@@ -0,0 +1,25 @@
+package metadata
+
+remap: errors: "120": {
+	title:       "Missing function closure"
+	description: """
+		A [function call expression](\(urls.vrl_expressions)#function-call) fails to pass a closure
+		to a function that requires one.
+		"""
+	resolution: """
+		Pass a closure to the function, adhering to the function's documented closure variables.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				filter(.tags)
+				"""#
+			diff: #"""
+				-filter(.tags)
+				+filter(.tags) -> |_key, value| { !is_null(value) }
+				"""#
+		},
+	]
+}
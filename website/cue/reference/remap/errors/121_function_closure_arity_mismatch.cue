@@ -0,0 +1,26 @@
+package metadata
+
+remap: errors: "121": {
+	title:       "Function closure arity mismatch"
+	description: """
+		A [function call expression](\(urls.vrl_expressions)#function-call) passes a closure that
+		defines a different number of variables than the function passes to it.
+		"""
+	resolution: """
+		Define the closure variables documented for the function. Variables that aren't used can be
+		prefixed with an underscore (`_`).
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				for_each(.tags) -> |value| { .seen = value }
+				"""#
+			diff: #"""
+				-for_each(.tags) -> |value| { .seen = value }
+				+for_each(.tags) -> |_key, value| { .seen = value }
+				"""#
+		},
+	]
+}
@@ -0,0 +1,25 @@
+package metadata
+
+remap: errors: "122": {
+	title:       "Function closure return type mismatch"
+	description: """
+		A [function call expression](\(urls.vrl_expressions)#function-call) passes a closure that
+		returns a value of a different type than the function expects.
+		"""
+	resolution: """
+		Change the last expression of the closure to return a value of the expected type.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				filter(.) -> |_key, value| { value }
+				"""#
+			diff: #"""
+				-filter(.) -> |_key, value| { value }
+				+filter(.) -> |_key, value| { !is_null(value) }
+				"""#
+		},
+	]
+}
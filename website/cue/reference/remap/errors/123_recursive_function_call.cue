@@ -0,0 +1,32 @@
+package metadata
+
+remap: errors: "123": {
+	title:       "Recursive function call"
+	description: """
+		A [user-defined function](\(urls.vrl_expressions)#function-definition) calls itself, either directly or
+		through another user-defined function.
+		"""
+	resolution: """
+		Rewrite the function so that it doesn't call itself. Functions such as `map_values` or `for_each` can be used
+		to process nested values.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				fn count(value) {
+					count(value) + 1
+				}
+				"""#
+			diff: #"""
+				-fn count(value) {
+				-	count(value) + 1
+				-}
+				+fn count(value) {
+				+	length(value) ?? 0
+				+}
+				"""#
+		},
+	]
+}
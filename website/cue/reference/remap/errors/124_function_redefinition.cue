@@ -0,0 +1,27 @@
+package metadata
+
+remap: errors: "124": {
+	title:       "Function redefinition"
+	description: """
+		A [function definition](\(urls.vrl_expressions)#function-definition) uses the name of a built-in function, or of
+		a function already defined by the program or by one of the files it imports.
+		"""
+	resolution: """
+		Rename the function.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				fn upcase(value) {
+					value
+				}
+				"""#
+			diff: #"""
+				-fn upcase(value) {
+				+fn identity(value) {
+				"""#
+		},
+	]
+}
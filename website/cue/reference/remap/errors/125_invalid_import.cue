@@ -0,0 +1,25 @@
+package metadata
+
+remap: errors: "125": {
+	title:       "Invalid import"
+	description: """
+		An [import](\(urls.vrl_expressions)#import) expression refers to a file that can't be found in the configured
+		import paths, can't be read, or whose path contains `..` components.
+		"""
+	resolution: """
+		Make sure the file exists within one of the import paths, and that the imported path is relative to it.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				import "../http.vrl"
+				"""#
+			diff: #"""
+				-import "../http.vrl"
+				+import "http.vrl"
+				"""#
+		},
+	]
+}
@@ -0,0 +1,34 @@
+package metadata
+
+remap: errors: "126": {
+	title:       "Unexpected imported expression"
+	description: """
+		A file [imported](\(urls.vrl_expressions)#import) by the program contains expressions other than function
+		definitions and imports.
+		"""
+	resolution: """
+		Move the expression into a function, or into the importing program.
+		"""
+
+	examples: [
+		{
+			"title": title
+			source: #"""
+				fn normalize(event) {
+					event
+				}
+
+				.normalized = true
+				"""#
+			diff: #"""
+				 fn normalize(event) {
+				-	event
+				+	.normalized = true
+				+	event
+				 }
+				-
+				-.normalized = true
+				"""#
+		},
+	]
+}
@@ -0,0 +1,32 @@
+package metadata
+
+remap: errors: "632": {
+	title: "Fallible closure"
+	description: """
+		You've passed a closure containing unhandled errors to a function.
+		"""
+
+	rationale: """
+		In VRL, closures passed to functions need to be infallible. The function runs the closure for
+		each element it iterates over, and an error from the closure would leave the outcome of the
+		function indeterminate.
+		"""
+
+	resolution: """
+		Handle the errors within the closure, potentially by aborting on error using `!`, coalescing
+		the error using `??`, or via some other method.
+		"""
+
+	examples: [
+		{
+			"title": "\(title)"
+			source: #"""
+				map_values(.) -> |value| { upcase(value) }
+				"""#
+			diff: #"""
+				- 	map_values(.) -> |value| { upcase(value) }
+				+ 	map_values(.) -> |value| { upcase(value) ?? value }
+				"""#
+		},
+	]
+}
@@ -15,7 +15,7 @@ remap: expressions: function_call: {
 
 	grammar: {
 		source: """
-			function ~ abort? ~ "(" ~ arguments? ~ ")"
+			function ~ abort? ~ "(" ~ arguments? ~ ")" ~ closure?
 			"""
 		definitions: {
 			function: {
@@ -95,6 +95,23 @@ remap: expressions: function_call: {
 					}
 				}
 			}
+			closure: {
+				description: """
+					Some functions accept a `closure`, a block of expressions run by the function for each element
+					of the value it iterates over. The closure follows the call, prefixed with `->`, and lists its
+					variables between pipes:
+
+					```coffee
+					map_values(.tags) -> |value| { downcase(value) ?? value }
+					```
+
+					The number of variables and their types are defined by the function. Variables assigned
+					within the closure are only in scope within the closure, while changes to variables
+					defined outside of it and to the event persist. Closures must be infallible, and can only
+					be passed to functions that accept them, so programs still run a bounded number of
+					expressions.
+					"""
+			}
 		}
 	}
 
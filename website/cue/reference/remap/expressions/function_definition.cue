@@ -0,0 +1,68 @@
+package metadata
+
+remap: expressions: function_definition: {
+	title: "Function definition"
+	description: """
+		A _function definition_ expression defines a function that can be called by the rest of the program,
+		the same way [built-in functions](\(urls.vrl_functions)) are.
+
+		Functions can only be defined at the root of a program. The variables of the caller aren't visible within
+		the body of the function, but the event is, and variables assigned within the body of the function are
+		removed once it returns.
+
+		The body of a function is type-checked for each call, using the types of the arguments it's called with.
+		Functions that are never called, including imported ones, are still checked, with arguments of any type.
+		Functions can't call themselves, directly or indirectly.
+		"""
+	return: """
+		Function definitions don't return a value. A call to the function returns the value of the last expression
+		of its body.
+		"""
+
+	grammar: {
+		source: """
+			"fn" ~ name ~ "(" ~ parameters? ~ ")" ~ "{" ~ expressions ~ "}"
+			"""
+		definitions: {
+			name: {
+				description: """
+					`name` is the name of the function, which can't be the name of a built-in function or of another
+					function of the program.
+					"""
+			}
+			parameters: {
+				description: """
+					`parameters` is a comma-separated list of parameter names. All parameters are required, and can
+					be passed positionally or by keyword.
+					"""
+			}
+			expressions: {
+				description: """
+					`expressions` are the expressions evaluated when the function is called.
+					"""
+			}
+		}
+	}
+
+	examples: [
+		{
+			title: "Function definition"
+			input: log: {
+				method: "post"
+				status: 204
+			}
+			source: #"""
+				fn is_success(status) {
+					status >= 200 && status < 300
+				}
+
+				.success = is_success(.status)
+				"""#
+			output: log: {
+				method:  "post"
+				status:  204
+				success: true
+			}
+		},
+	]
+}
@@ -0,0 +1,46 @@
+package metadata
+
+remap: expressions: import: {
+	title: "Import"
+	description: """
+		An _import_ expression makes the [functions defined](#function-definition) in another VRL file available to
+		the program.
+
+		Imported files are looked up in the import paths configured for the program, such as the `import_paths`
+		option of the [`remap` transform](\(urls.vector_remap_transform)) or of `vrl` conditions, and can only contain function definitions
+		and other imports. Importing the same file more than once has no effect.
+		"""
+	return: """
+		Imports don't return a value.
+		"""
+
+	grammar: {
+		source: """
+			"import" ~ path
+			"""
+		definitions: {
+			path: {
+				description: """
+					`path` is a string literal holding the path of the file to import, relative to one of the import
+					paths. It can't contain `..` components.
+					"""
+			}
+		}
+	}
+
+	examples: [
+		{
+			title: "Import"
+			input: log: status: 204
+			source: #"""
+				import "http/status.vrl"
+
+				.success = is_success(.status)
+				"""#
+			output: log: {
+				status:  204
+				success: true
+			}
+		},
+	]
+}
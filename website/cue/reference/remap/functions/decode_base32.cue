@@ -0,0 +1,31 @@
+package metadata
+
+remap: functions: decode_base32: {
+	category:    "Codec"
+	description: """
+		Decodes the `value` (a [Base32](\(urls.base32)) string) into its original string.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The [Base32](\(urls.base32)) data to decode. The trailing padding is optional."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid encoded Base32 string.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decode Base32 data"
+			source: """
+				decode_base32!("PFXXKIDIMF3GKIDTOVRWGZLTONTHK3DMPEQGIZLDN5SGKZBANVSQ====")
+				"""
+			return: "you have successfully decoded me"
+		},
+	]
+}
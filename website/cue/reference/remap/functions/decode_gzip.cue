@@ -0,0 +1,44 @@
+package metadata
+
+remap: functions: decode_gzip: {
+	category:    "Codec"
+	description: """
+		Decodes the `value` (a [Gzip](\(urls.gzip)) string) into its original string.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The [Gzip](\(urls.gzip)) data to decode."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name: "max_size"
+			description: """
+				The maximum size, in bytes, of the decompressed value. Decoding fails if the
+				decompressed value would exceed this size, which guards against decompression
+				bombs.
+				"""
+			required: false
+			type: ["integer"]
+			default: 104857600
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid encoded Gzip string.",
+		"The decompressed value exceeds `max_size`.",
+		"`max_size` isn't a positive integer.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decode Gzip data"
+			source: """
+				decode_gzip!(decode_base64!("H4sIAAAAAAAC/6vML1XISCxLVSguTU5OLS5OK83JqVRISU3OT0lNUchNBQD7BGDaIAAAAA=="))
+				"""
+			return: "you have successfully decoded me"
+		},
+	]
+}
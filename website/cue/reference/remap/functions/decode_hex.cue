@@ -0,0 +1,31 @@
+package metadata
+
+remap: functions: decode_hex: {
+	category:    "Codec"
+	description: """
+		Decodes the `value` (a [hexadecimal](\(urls.hexadecimal)) string) into its original string.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The [hexadecimal](\(urls.hexadecimal)) data to decode. Both lowercase and uppercase digits are accepted."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid encoded hexadecimal string.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decode hexadecimal data"
+			source: """
+				decode_hex!("796f752068617665207375636365737366756c6c79206465636f646564206d65")
+				"""
+			return: "you have successfully decoded me"
+		},
+	]
+}
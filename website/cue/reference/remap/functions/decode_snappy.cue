@@ -0,0 +1,44 @@
+package metadata
+
+remap: functions: decode_snappy: {
+	category:    "Codec"
+	description: """
+		Decodes the `value` (a [Snappy](\(urls.snappy)) string) into its original string.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The [Snappy](\(urls.snappy)) data to decode."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name: "max_size"
+			description: """
+				The maximum size, in bytes, of the decompressed value. Decoding fails if the
+				decompressed value would exceed this size, which guards against decompression
+				bombs.
+				"""
+			required: false
+			type: ["integer"]
+			default: 104857600
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid encoded Snappy string.",
+		"The decompressed value exceeds `max_size`.",
+		"`max_size` isn't a positive integer.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decode Snappy data"
+			source: """
+				decode_snappy!(decode_base64!("IHx5b3UgaGF2ZSBzdWNjZXNzZnVsbHkgZGVjb2RlZCBtZQ=="))
+				"""
+			return: "you have successfully decoded me"
+		},
+	]
+}
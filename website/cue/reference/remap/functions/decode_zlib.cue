@@ -0,0 +1,44 @@
+package metadata
+
+remap: functions: decode_zlib: {
+	category:    "Codec"
+	description: """
+		Decodes the `value` (a [Zlib](\(urls.zlib)) string) into its original string.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The [Zlib](\(urls.zlib)) data to decode."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name: "max_size"
+			description: """
+				The maximum size, in bytes, of the decompressed value. Decoding fails if the
+				decompressed value would exceed this size, which guards against decompression
+				bombs.
+				"""
+			required: false
+			type: ["integer"]
+			default: 104857600
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid encoded Zlib string.",
+		"The decompressed value exceeds `max_size`.",
+		"`max_size` isn't a positive integer.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decode Zlib data"
+			source: """
+				decode_zlib!(decode_base64!("eJyrzC9VyEgsS1UoLk1OTi0uTivNyalUSElNzk9JTVHITQUAzLIMQQ=="))
+				"""
+			return: "you have successfully decoded me"
+		},
+	]
+}
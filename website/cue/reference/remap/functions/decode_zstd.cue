@@ -0,0 +1,44 @@
+package metadata
+
+remap: functions: decode_zstd: {
+	category:    "Codec"
+	description: """
+		Decodes the `value` (a [Zstandard](\(urls.zstd)) string) into its original string.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The [Zstandard](\(urls.zstd)) data to decode."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name: "max_size"
+			description: """
+				The maximum size, in bytes, of the decompressed value. Decoding fails if the
+				decompressed value would exceed this size, which guards against decompression
+				bombs.
+				"""
+			required: false
+			type: ["integer"]
+			default: 104857600
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid encoded Zstandard string.",
+		"The decompressed value exceeds `max_size`.",
+		"`max_size` isn't a positive integer.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decode Zstandard data"
+			source: """
+				decode_zstd!(decode_base64!("KLUv/SAgAQEAeW91IGhhdmUgc3VjY2Vzc2Z1bGx5IGRlY29kZWQgbWU="))
+				"""
+			return: "you have successfully decoded me"
+		},
+	]
+}
@@ -0,0 +1,56 @@
+package metadata
+
+remap: functions: decrypt: {
+	category:    "Cryptography"
+	description: """
+		Decrypts the `ciphertext` returned by [`encrypt`](\(urls.vrl_functions)#encrypt), with the same `algorithm`
+		and `key`, and the `iv` it was encrypted with.
+		"""
+
+	arguments: [
+		{
+			name:        "ciphertext"
+			description: "The encrypted value to decrypt."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The authenticated encryption algorithm the value was encrypted with."
+			enum: {
+				"AES-256-GCM":       "[AES-256](\(urls.aes_gcm)) in Galois/Counter Mode, with a 12-byte IV."
+				"CHACHA20-POLY1305": "[ChaCha20-Poly1305](\(urls.chacha20_poly1305)), with a 12-byte IV."
+			}
+			required: true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The 32-byte key the value was encrypted with."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "iv"
+			description: "The 12-byte initialization vector the value was encrypted with."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`key` isn't 32 bytes long.",
+		"`iv` isn't 12 bytes long.",
+		"`ciphertext` wasn't encrypted with the given `algorithm`, `key` and `iv`, or was tampered with.",
+	]
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Decrypt a value"
+			source: #"""
+				decrypt!(decode_base64!("7zWmxaEPOvpyGyQCp0fQo16ZCw=="), "AES-256-GCM", get_secret("encryption_key"), "123456789012")
+				"""#
+			return: "foo"
+		},
+	]
+}
@@ -0,0 +1,43 @@
+package metadata
+
+remap: functions: encode_base32: {
+	category:    "Codec"
+	description: """
+		Encodes the `value` to [Base32](\(urls.base32)).
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to encode."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "padding"
+			description: "Whether the Base32 output is [padded](\(urls.base32_padding))."
+			required:    false
+			type: ["boolean"]
+			default: true
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encode to Base32 (default)"
+			source: """
+				encode_base32("please encode me")
+				"""
+			return: "OBWGKYLTMUQGK3TDN5SGKIDNMU======"
+		},
+		{
+			title: "Encode to Base32 (without padding)"
+			source: """
+				encode_base32("please encode me", padding: false)
+				"""
+			return: "OBWGKYLTMUQGK3TDN5SGKIDNMU"
+		},
+	]
+}
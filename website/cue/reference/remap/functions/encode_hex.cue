@@ -0,0 +1,29 @@
+package metadata
+
+remap: functions: encode_hex: {
+	category:    "Codec"
+	description: """
+		Encodes the `value` to [hexadecimal](\(urls.hexadecimal)), using lowercase digits.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to encode."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Encode to hexadecimal"
+			source: """
+				encode_hex("please encode me")
+				"""
+			return: "706c6561736520656e636f6465206d65"
+		},
+	]
+}
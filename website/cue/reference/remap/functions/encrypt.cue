@@ -0,0 +1,62 @@
+package metadata
+
+remap: functions: encrypt: {
+	category:    "Cryptography"
+	description: """
+		Encrypts the `plaintext` with the given `algorithm` and `key`, using a random initialization vector (IV)
+		generated for each call.
+
+		The encrypted value is returned along with its IV, both of which are needed to
+		[`decrypt`](\(urls.vrl_functions)#decrypt) it. Both are raw bytes, which can be encoded with
+		[`encode_base64`](\(urls.vrl_functions)#encode_base64) before being stored.
+
+		To avoid exposing the key, it can be read from the secrets of the `remap` transform with
+		[`get_secret`](\(urls.vrl_functions)#get_secret) rather than written into the program.
+		"""
+
+	arguments: [
+		{
+			name:        "plaintext"
+			description: "The string to encrypt."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The authenticated encryption algorithm to use."
+			enum: {
+				"AES-256-GCM":       "[AES-256](\(urls.aes_gcm)) in Galois/Counter Mode, with a 12-byte IV."
+				"CHACHA20-POLY1305": "[ChaCha20-Poly1305](\(urls.chacha20_poly1305)), with a 12-byte IV."
+			}
+			required: true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The 32-byte key to encrypt with."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`key` isn't 32 bytes long.",
+	]
+	return: {
+		types: ["object"]
+		rules: [
+			"The object contains the encrypted value in the `ciphertext` field, and the IV in the `iv` field.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Encrypt and decrypt a value"
+			source: #"""
+				key = get_secret("encryption_key")
+				encrypted = encrypt!("foo", "AES-256-GCM", key)
+				decrypt!(encrypted.ciphertext, "AES-256-GCM", key, encrypted.iv)
+				"""#
+			return: "foo"
+		},
+	]
+}
@@ -0,0 +1,53 @@
+package metadata
+
+remap: functions: filter: {
+	category: "Enumerate"
+	description: #"""
+		Filters the elements of an object or array, keeping the elements for which the closure
+		passed to the function returns `true`.
+
+		The closure receives the `key` and `value` of each field of an object, or the `index` and
+		`value` of each element of an array, and must return a boolean.
+		"""#
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object or array to filter."
+			required:    true
+			type: ["array", "object"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["array", "object"]
+		rules: [
+			"The return type matches the `value` type.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Drop null fields"
+			input: log: {
+				foo: "foo"
+				bar: null
+				baz: "baz"
+			}
+			source: #"""
+				filter(.) -> |_key, value| { !is_null(value) }
+				"""#
+			return: {
+				foo: "foo"
+				baz: "baz"
+			}
+		},
+		{
+			title: "Keep elements by index"
+			source: #"""
+				filter(["a", "b", "c", "d"]) -> |index, _value| { index < 2 }
+				"""#
+			return: ["a", "b"]
+		},
+	]
+}
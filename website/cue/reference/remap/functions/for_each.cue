@@ -0,0 +1,49 @@
+package metadata
+
+remap: functions: for_each: {
+	category: "Enumerate"
+	description: #"""
+		Iterates over an object or array, running the closure passed to the function for each
+		element.
+
+		The closure receives the `key` and `value` of each field of an object, or the `index` and
+		`value` of each element of an array. The result of the closure is discarded, so assign to
+		variables or to the event within the closure to keep results.
+		"""#
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object or array to iterate."
+			required:    true
+			type: ["array", "object"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["null"]
+	}
+
+	examples: [
+		{
+			title: "Tally elements"
+			input: log: {
+				tags: ["foo", "bar", "foo", "baz"]
+			}
+			source: #"""
+				tally = {}
+				for_each(array!(.tags)) -> |_index, value| {
+					value = string!(value)
+					count = int(get!(tally, [value])) ?? 0
+					tally = set!(tally, [value], count + 1)
+				}
+				tally
+				"""#
+			return: {
+				foo: 2
+				bar: 1
+				baz: 1
+			}
+		},
+	]
+}
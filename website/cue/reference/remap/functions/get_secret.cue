@@ -0,0 +1,31 @@
+package metadata
+
+remap: functions: get_secret: {
+	category: "System"
+	description: """
+		Returns the secret specified by `name`, as read from the files configured in the `secrets` option of
+		the `remap` transform. Secrets are read when the program is compiled, so they are never written into
+		the program itself.
+		"""
+
+	arguments: [
+		{
+			name:        "name"
+			description: "The name of the secret, which must be a string literal."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Get a secret"
+			source: #"""
+				hmac("foo", get_secret("hmac_key"))
+				"""#
+			return: "773ba44693c7553d6ee20f61ea5d2757a9a4f4a44d2841ae4e95b52e4cd62db4"
+		},
+	]
+}
@@ -0,0 +1,59 @@
+package metadata
+
+remap: functions: hmac: {
+	category:    "Hash"
+	description: """
+		Calculates a [HMAC](\(urls.hmac)) of the `value` using the given `key`, encoded as a hexadecimal string.
+
+		To pseudonymise fields without exposing the key, it can be read from the secrets of the `remap`
+		transform with [`get_secret`](\(urls.vrl_functions)#get_secret) rather than written into the program.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to calculate the HMAC for."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "key"
+			description: "The secret key to use."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "algorithm"
+			description: "The hashing algorithm to use."
+			enum: {
+				"SHA-1":   "SHA-1 algorithm"
+				"SHA-224": "SHA-224 algorithm"
+				"SHA-256": "SHA-256 algorithm"
+				"SHA-384": "SHA-384 algorithm"
+				"SHA-512": "SHA-512 algorithm"
+			}
+			required: false
+			default:  "SHA-256"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: types: ["string"]
+
+	examples: [
+		{
+			title: "Calculate a HMAC"
+			source: #"""
+				hmac("foo", get_secret("hmac_key"))
+				"""#
+			return: "773ba44693c7553d6ee20f61ea5d2757a9a4f4a44d2841ae4e95b52e4cd62db4"
+		},
+		{
+			title: "Calculate a HMAC with a custom algorithm"
+			source: #"""
+				hmac("foo", get_secret("hmac_key"), algorithm: "SHA-1")
+				"""#
+			return: "9baed91be7f58b57c824b60da7cb262b2ecafbd2"
+		},
+	]
+}
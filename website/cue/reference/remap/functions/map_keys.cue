@@ -0,0 +1,68 @@
+package metadata
+
+remap: functions: map_keys: {
+	category: "Enumerate"
+	description: #"""
+		Maps the keys within an object, renaming each key to the string returned by the closure
+		passed to the function.
+
+		The closure receives the `key` of each field. If `recursive` is enabled, the function also
+		maps the keys of nested objects, including objects within arrays.
+
+		If the closure returns the same key for multiple fields, the field that comes last in key
+		order is kept.
+		"""#
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object to iterate."
+			required:    true
+			type: ["object"]
+		},
+		{
+			name:        "recursive"
+			description: "Whether to recursively iterate the collection."
+			required:    false
+			default:     false
+			type: ["boolean"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["object"]
+	}
+
+	examples: [
+		{
+			title: "Upcase keys"
+			input: log: {
+				foo: "foo"
+				bar: "bar"
+			}
+			source: #"""
+				map_keys(.) -> |key| { upcase(key) }
+				"""#
+			return: {
+				FOO: "foo"
+				BAR: "bar"
+			}
+		},
+		{
+			title: "Replace dots in nested keys"
+			input: log: {
+				labels: {
+					"app.kubernetes.io/name": "vector"
+				}
+			}
+			source: #"""
+				map_keys(., recursive: true) -> |key| { replace(key, ".", "_") }
+				"""#
+			return: {
+				labels: {
+					"app_kubernetes_io/name": "vector"
+				}
+			}
+		},
+	]
+}
@@ -0,0 +1,60 @@
+package metadata
+
+remap: functions: map_values: {
+	category: "Enumerate"
+	description: #"""
+		Maps the values within an object or array, replacing each value with the result of the closure
+		passed to the function.
+
+		The closure receives the `value` of each element. If `recursive` is enabled, the function
+		iterates into nested objects and arrays first, and then calls the closure with the mapped
+		object or array.
+		"""#
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The object or array to iterate."
+			required:    true
+			type: ["array", "object"]
+		},
+		{
+			name:        "recursive"
+			description: "Whether to recursively iterate the collection."
+			required:    false
+			default:     false
+			type: ["boolean"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["array", "object"]
+		rules: [
+			"The return type matches the `value` type.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Upcase values"
+			input: log: {
+				foo: "foo"
+				bar: "bar"
+			}
+			source: #"""
+				map_values(.) -> |value| { upcase!(value) }
+				"""#
+			return: {
+				foo: "FOO"
+				bar: "BAR"
+			}
+		},
+		{
+			title: "Multiply array values"
+			source: #"""
+				map_values([1, 2, 3]) -> |value| { (int(value) ?? 0) * 10 }
+				"""#
+			return: [10, 20, 30]
+		},
+	]
+}
@@ -0,0 +1,82 @@
+package metadata
+
+remap: functions: parse_cef: {
+	category:    "Parse"
+	description: """
+		Parses the `value` in the [Common Event Format (CEF)](\(urls.cef)) used by ArcSight.
+		Header fields are returned as `cefVersion`, `deviceVendor`, `deviceProduct`,
+		`deviceVersion`, `deviceEventClassId`, `name`, and `severity`, alongside the extension
+		fields. Extension fields named after a header field are prefixed with `ext_`, such as `ext_name`.
+		Any syslog prefix before the `CEF:` header is ignored.
+		"""
+	notices: [
+		"""
+			All values are returned as strings. Use the coercion functions, such as
+			[`to_int`](#to_int), to convert them.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name: "translate_custom_fields"
+			description: """
+				Replace custom fields, such as `cs1`, with their label, as set by the matching
+				label field, such as `cs1Label`. Fields whose label is the name of another field, such as
+				a header field, are kept as is.
+				"""
+			required: false
+			default:  false
+			type: ["boolean"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a properly formatted CEF message",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse CEF message"
+			source: #"""
+				parse_cef!("CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|src=10.0.0.1 dst=2.1.2.2 spt=1232")
+				"""#
+			return: {
+				cefVersion:         "0"
+				deviceVendor:       "Security"
+				deviceProduct:      "threatmanager"
+				deviceVersion:      "1.0"
+				deviceEventClassId: "100"
+				name:               "worm successfully stopped"
+				severity:           "10"
+				src:                "10.0.0.1"
+				dst:                "2.1.2.2"
+				spt:                "1232"
+			}
+		},
+		{
+			title: "Parse CEF message with custom fields"
+			source: #"""
+				parse_cef!(
+					"CEF:0|Security|threatmanager|1.0|100|worm successfully stopped|10|cs1Label=policy cs1=block all",
+					translate_custom_fields: true
+				)
+				"""#
+			return: {
+				cefVersion:         "0"
+				deviceVendor:       "Security"
+				deviceProduct:      "threatmanager"
+				deviceVersion:      "1.0"
+				deviceEventClassId: "100"
+				name:               "worm successfully stopped"
+				severity:           "10"
+				policy:             "block all"
+			}
+		},
+	]
+}
@@ -0,0 +1,66 @@
+package metadata
+
+remap: functions: parse_leef: {
+	category:    "Parse"
+	description: """
+		Parses the `value` in the [Log Event Extended Format (LEEF)](\(urls.leef)) used by
+		QRadar. Header fields are returned as `leefVersion`, `vendor`, `productName`,
+		`productVersion`, and `eventId`, alongside the event attributes. Any syslog prefix
+		before the `LEEF:` header is ignored.
+		"""
+	notices: [
+		"""
+			Attributes are separated by tabs, unless a LEEF 2.0 header sets another delimiter.
+			All values are returned as strings.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to parse."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a properly formatted LEEF message",
+		"the delimiter of the LEEF 2.0 header is invalid",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse LEEF 1.0 message"
+			source: #"""
+				parse_leef!("LEEF:1.0|Microsoft|MSExchange|4.0 SP1|15345|src=192.0.2.0\tdst=172.50.123.1\tsev=5")
+				"""#
+			return: {
+				leefVersion:    "1.0"
+				vendor:         "Microsoft"
+				productName:    "MSExchange"
+				productVersion: "4.0 SP1"
+				eventId:        "15345"
+				src:            "192.0.2.0"
+				dst:            "172.50.123.1"
+				sev:            "5"
+			}
+		},
+		{
+			title: "Parse LEEF 2.0 message with custom delimiter"
+			source: #"""
+				parse_leef!("LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.1.8^dst=10.0.0.5^sev=5")
+				"""#
+			return: {
+				leefVersion:    "2.0"
+				vendor:         "Lancope"
+				productName:    "StealthWatch"
+				productVersion: "1.0"
+				eventId:        "41"
+				src:            "10.0.1.8"
+				dst:            "10.0.0.5"
+				sev:            "5"
+			}
+		},
+	]
+}
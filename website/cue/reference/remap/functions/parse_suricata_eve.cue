@@ -0,0 +1,53 @@
+package metadata
+
+remap: functions: parse_suricata_eve: {
+	category:    "Parse"
+	description: """
+		Parses the `value` as a [Suricata EVE JSON](\(urls.suricata_eve)) event. The
+		`timestamp`, `flow.start`, and `flow.end` fields are converted to timestamps, and the
+		types of the common event fields are checked, so that they can be used without
+		coercion.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The EVE JSON event to parse."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid JSON object",
+		"`value` is missing the `event_type` or `timestamp` field",
+		"a common event field doesn't have the expected type",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse Suricata EVE alert"
+			source: #"""
+				parse_suricata_eve!(s'{"timestamp":"2009-11-24T21:27:09.534255+0100","event_type":"alert","src_ip":"192.168.2.7","src_port":1041,"dest_ip":"x.x.250.50","dest_port":80,"proto":"TCP","alert":{"action":"allowed","gid":1,"signature_id":2001999,"rev":9,"signature":"ET MALWARE BTGrab.com Spyware Downloading Ads","category":"A Network Trojan was detected","severity":1}}')
+				"""#
+			return: {
+				timestamp:  "2009-11-24T20:27:09.534255Z"
+				event_type: "alert"
+				src_ip:     "192.168.2.7"
+				src_port:   1041
+				dest_ip:    "x.x.250.50"
+				dest_port:  80
+				proto:      "TCP"
+				alert: {
+					action:       "allowed"
+					gid:          1
+					signature_id: 2001999
+					rev:          9
+					signature:    "ET MALWARE BTGrab.com Spyware Downloading Ads"
+					category:     "A Network Trojan was detected"
+					severity:     1
+				}
+			}
+		},
+	]
+}
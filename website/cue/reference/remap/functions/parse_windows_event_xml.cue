@@ -0,0 +1,55 @@
+package metadata
+
+remap: functions: parse_windows_event_xml: {
+	category:    "Parse"
+	description: """
+		Parses the `value` as a Windows event rendered as XML, following the
+		[Windows event schema](\(urls.windows_event_schema)). The elements of `System` are returned as
+		`event_id`, `version`, `level`, `task`, `opcode`, `record_id`, `keywords`, `channel`,
+		`computer_name`, `time_created`, `process_id`, `thread_id`, `provider_name`, `provider_guid`,
+		`activity_id`, and `user_id`, when present.
+		"""
+	notices: [
+		"""
+			The `Data` elements of `EventData` are returned in `event_data`, keyed by their `Name`
+			attribute. Unnamed elements are keyed as `param1`, `param2`, and so on, in order.
+			`UserData` is returned in `user_data` as nested objects.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The XML representation of the Windows event to parse."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` isn't a valid XML document",
+		"the root element isn't `Event`, or it has no `System` or `EventID` element",
+		"an integer field, such as `EventID`, or the `TimeCreated` timestamp is malformed",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse Windows event"
+			source: #"""
+				parse_windows_event_xml!(s'<Event><System><Provider Name="Service Control Manager"/><EventID>7036</EventID><Level>4</Level><TimeCreated SystemTime="2022-03-01T12:00:00Z"/><Channel>System</Channel><Computer>host01</Computer></System><EventData><Data Name="param1">Windows Update</Data><Data Name="param2">running</Data></EventData></Event>')
+				"""#
+			return: {
+				provider_name: "Service Control Manager"
+				event_id:      7036
+				level:         4
+				time_created:  "2022-03-01T12:00:00Z"
+				channel:       "System"
+				computer_name: "host01"
+				event_data: {
+					param1: "Windows Update"
+					param2: "running"
+				}
+			}
+		},
+	]
+}
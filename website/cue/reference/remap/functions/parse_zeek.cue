@@ -0,0 +1,84 @@
+package metadata
+
+remap: functions: parse_zeek: {
+	category:    "Parse"
+	description: """
+		Parses the `value` as a line of a [Zeek TSV log](\(urls.zeek_log_formats)), naming the
+		values after `fields`. Unset (`-`) values are omitted, and `\\xHH` escape sequences are
+		decoded.
+		"""
+	notices: [
+		"""
+			Header lines, starting with `#`, aren't parsed. Their `#fields` and `#types` lines
+			can be passed as the `fields` and `types` arguments instead.
+			""",
+	]
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The log line to parse."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "fields"
+			description: "The names of the fields of the line, as listed in the `#fields` header."
+			required:    true
+			type: ["array"]
+		},
+		{
+			name: "types"
+			description: """
+				The Zeek types of the fields, as listed in the `#types` header. `bool`, `count`,
+				`int`, `port`, `double`, `interval`, `time`, `set`, and `vector` values are
+				converted to the matching VRL types. Without types, all values are returned as
+				strings.
+				"""
+			required: false
+			type: ["array"]
+		},
+	]
+	internal_failure_reasons: [
+		"`value` is a header line",
+		"`value` doesn't have as many values as `fields`",
+		"`types` doesn't have as many types as `fields`",
+		"a value doesn't match its type",
+	]
+	return: types: ["object"]
+
+	examples: [
+		{
+			title: "Parse Zeek log line"
+			source: #"""
+				parse_zeek!(
+					"1320279566.452687\tCkVxvQ1NqdKqBvCBx2\t10.0.0.1\t-",
+					["ts", "uid", "id.orig_h", "service"]
+				)
+				"""#
+			return: {
+				ts:          "1320279566.452687"
+				uid:         "CkVxvQ1NqdKqBvCBx2"
+				"id.orig_h": "10.0.0.1"
+			}
+		},
+		{
+			title: "Parse Zeek log line with types"
+			source: #"""
+				parse_zeek!(
+					"1320279566.452687\tCkVxvQ1NqdKqBvCBx2\t10.0.0.1\t80\tT\t(empty)",
+					["ts", "uid", "id.orig_h", "id.orig_p", "local_orig", "tunnel_parents"],
+					["time", "string", "addr", "port", "bool", "set[string]"]
+				)
+				"""#
+			return: {
+				ts:             "2011-11-03T00:19:26.452687Z"
+				uid:            "CkVxvQ1NqdKqBvCBx2"
+				"id.orig_h":    "10.0.0.1"
+				"id.orig_p":    80
+				local_orig:     true
+				tunnel_parents: []
+			}
+		},
+	]
+}
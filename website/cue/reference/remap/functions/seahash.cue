@@ -0,0 +1,36 @@
+package metadata
+
+remap: functions: seahash: {
+	category:    "Hash"
+	description: """
+		Calculates a [SeaHash](\(urls.seahash)) hash of the `value`.
+
+		SeaHash is a fast, non-cryptographic hash function, suited to spreading events across buckets.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to calculate the hash for."
+			required:    true
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["integer"]
+		rules: [
+			"Hashes larger than the maximum integer wrap around to negative integers.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Calculate a SeaHash hash"
+			source: #"""
+				seahash("foo")
+				"""#
+			return: 4413582353838009230
+		},
+	]
+}
@@ -0,0 +1,61 @@
+package metadata
+
+remap: functions: xxhash: {
+	category:    "Hash"
+	description: """
+		Calculates a [xxHash](\(urls.xxhash)) hash of the `value`.
+
+		xxHash is a fast, non-cryptographic hash function, suited to spreading events across buckets.
+		"""
+
+	arguments: [
+		{
+			name:        "value"
+			description: "The string to calculate the hash for."
+			required:    true
+			type: ["string"]
+		},
+		{
+			name:        "variant"
+			description: "The variant of the algorithm to use."
+			enum: {
+				XXH32: "32-bit xxHash algorithm"
+				XXH64: "64-bit xxHash algorithm"
+			}
+			required: false
+			default:  "XXH32"
+			type: ["string"]
+		},
+	]
+	internal_failure_reasons: []
+	return: {
+		types: ["integer"]
+		rules: [
+			"64-bit hashes larger than the maximum integer wrap around to negative integers.",
+		]
+	}
+
+	examples: [
+		{
+			title: "Calculate a xxHash hash"
+			source: #"""
+				xxhash("foo")
+				"""#
+			return: 3792637401
+		},
+		{
+			title: "Calculate a 64-bit xxHash hash"
+			source: #"""
+				xxhash("foo", variant: "XXH64")
+				"""#
+			return: 3728699739546630719
+		},
+		{
+			title: "Assign an event to one of ten buckets"
+			source: #"""
+				xxhash("foo") % 10
+				"""#
+			return: 1
+		},
+	]
+}
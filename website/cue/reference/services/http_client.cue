@@ -0,0 +1,8 @@
+package metadata
+
+services: http_client: {
+	name:     "HTTP"
+	thing:    "an \(name) server"
+	url:      urls.http_server
+	versions: null
+}
@@ -0,0 +1,10 @@
+package metadata
+
+services: netflow: {
+	name:     "NetFlow"
+	thing:    "a \(name), IPFIX or sFlow exporter"
+	url:      urls.netflow
+	versions: null
+
+	description: "[NetFlow](\(urls.netflow)), its successor [IPFIX](\(urls.rfc_7011)) and [sFlow](\(urls.sflow)) are used by routers and switches to export the IP flows going through them and their interface counters."
+}
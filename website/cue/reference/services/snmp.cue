@@ -0,0 +1,10 @@
+package metadata
+
+services: snmp: {
+	name:     "SNMP"
+	thing:    "an \(name) agent"
+	url:      urls.snmp
+	versions: null
+
+	description: "The [Simple Network Management Protocol](\(urls.snmp)) is used to monitor and manage network devices, which notify managers of significant events by sending traps and informs."
+}
@@ -0,0 +1,8 @@
+package metadata
+
+services: websocket: {
+	name:     "WebSocket"
+	thing:    "a \(name) peer"
+	url:      urls.websocket
+	versions: null
+}
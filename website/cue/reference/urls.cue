@@ -4,6 +4,7 @@ urls: {
 	azure_blob_storage:                                       "https://azure.microsoft.com/en-us/services/storage/blobs/"
 	affine_type_system:                                       "\(wikipedia)/wiki/Substructural_type_system#Affine_type_systems"
 	adaptive_request_concurrency_post:                        "/blog/adaptive-request-concurrency/"
+	aes_gcm:                                                  "\(wikipedia)/wiki/Galois/Counter_Mode"
 	amazon_linux:                                             "https://aws.amazon.com/amazon-linux-ami/"
 	ansi_escape_codes:                                        "\(wikipedia)/wiki/ANSI_escape_code"
 	apache:                                                   "https://httpd.apache.org"
@@ -85,6 +86,8 @@ urls: {
 	azure_blob_endpoints:                                     "https://docs.microsoft.com/en-us/rest/api/storageservices/blob-service-rest-api"
 	azure_monitor:                                            "https://azure.microsoft.com/en-us/services/monitor/"
 	azure_monitor_logs_endpoints:                             "https://docs.microsoft.com/en-us/rest/api/monitor/"
+	base32:                                                   "https://tools.ietf.org/html/rfc4648#section-6"
+	base32_padding:                                           "https://tools.ietf.org/html/rfc4648#section-3.2"
 	base64:                                                   "\(wikipedia)/wiki/Base64"
 	base64_padding:                                           "\(wikipedia)/wiki/Base64#Output_padding"
 	base64_standard:                                          "https://tools.ietf.org/html/rfc4648#section-4"
@@ -94,11 +97,14 @@ urls: {
 	bind_dnstap:                                              "https://kb.isc.org/docs/aa-01342"
 	b_tree_map:                                               "https://doc.rust-lang.org/std/collections/struct.BTreeMap.html"
 	cargo_audit:                                              "\(github)/RustSec/cargo-audit"
+	cef:                                                      "https://www.microfocus.com/documentation/arcsight/arcsight-smartconnectors/pdfdoc/common-event-format-v25/common-event-format-v25.pdf"
 	centos:                                                   "https://www.centos.org/"
+	chacha20_poly1305:                                        "\(wikipedia)/wiki/ChaCha20-Poly1305"
 	chrono_time_formats:                                      "https://docs.rs/chrono/latest/chrono/format/strftime/index.html#specifiers"
 	cgroups_limit_resources:                                  "https://the.binbashtheory.com/control-resources-cgroups/"
 	clickhouse:                                               "https://clickhouse.yandex/"
 	clickhouse_http:                                          "https://clickhouse.yandex/docs/en/interfaces/http/"
+	clickhouse_native:                                        "https://clickhouse.com/docs/en/interfaces/tcp/"
 	cloudsmith:                                               "https://cloudsmith.io/~timber/repos/vector/packages/"
 	cloudsmith_apt:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-deb"
 	cloudsmith_yum:                                           "https://cloudsmith.io/~timber/repos/vector/setup/#formats-rpm"
@@ -226,6 +232,8 @@ urls: {
 	heroku:                                                   "https://www.heroku.com"
 	heroku_http_log_drain:                                    "https://devcenter.heroku.com/articles/log-drains#https-drains"
 	heroku_start:                                             "https://devcenter.heroku.com/start"
+	hexadecimal:                                              "\(wikipedia)/wiki/Hexadecimal"
+	hmac:                                                     "\(wikipedia)/wiki/HMAC"
 	homebrew:                                                 "https://brew.sh/"
 	homebrew_services:                                        "\(github)/Homebrew/homebrew-services"
 	honeycomb:                                                "https://honeycomb.io"
@@ -254,6 +262,7 @@ urls: {
 	ip_ntoa:                                                  "https://linux.die.net/man/3/inet_ntoa"
 	ip_ntop:                                                  "https://linux.die.net/man/3/inet_ntop"
 	ip_pton:                                                  "https://linux.die.net/man/3/inet_pton"
+	ipfix_information_elements:                               "https://www.iana.org/assignments/ipfix/ipfix.xhtml"
 	iso_8601:                                                 "\(wikipedia)/wiki/ISO_8601"
 	iso3166_2:                                                "\(wikipedia)/wiki/ISO_3166-2"
 	issue_1694:                                               "\(vector_repo)/issues/1694"
@@ -280,6 +289,7 @@ urls: {
 	kubernetes_rbac:                                          "\(kubernetes)/docs/reference/access-authn-authz/rbac/"
 	kubernetes_request_verbs:                                 "\(kubernetes)/docs/reference/access-authn-authz/authorization/#determine-the-request-verb"
 	kubernetes_watch_api:                                     "\(kubernetes)/docs/reference/generated/kubernetes-api/v1.10/#watch-30"
+	leef:                                                     "https://www.ibm.com/docs/en/dsm?topic=overview-leef-event-components"
 	leveldb:                                                  "\(github)/google/leveldb"
 	leveldb_sys_2:                                            "https://crates.io/crates/leveldb-sys"
 	leveldb_sys_3:                                            "\(github)/vectordotdev/leveldb-sys/tree/v3.0.0"
@@ -293,6 +303,7 @@ urls: {
 	logstash_protocol:                                        "https://github.com/elastic/logstash-forwarder/blob/master/PROTOCOL.md"
 	loki:                                                     "https://grafana.com/oss/loki/"
 	loki_multi_tenancy:                                       "\(github)/grafana/loki/blob/master/docs/operations/multi-tenancy.md"
+	loki_structured_metadata:                                 "https://grafana.com/docs/loki/latest/get-started/labels/structured-metadata/"
 	log_event_source:                                         "\(vector_repo)/blob/master/src/event/"
 	logplex:                                                  "https://devcenter.heroku.com/articles/logplex"
 	logplex_protocol:                                         "\(github)/heroku/logplex/blob/master/doc/README.http_drains.md"
@@ -336,6 +347,7 @@ urls: {
 	musl_builder_docker_image:                                "\(vector_repo)/blob/master/scripts/ci-docker-images/builder-x86_64-unknown-linux-musl/Dockerfile"
 	nats:                                                     "https://nats.io/"
 	nats_rs:                                                  "\(github)/nats-io/nats.rs"
+	netflow:                                                  "\(wikipedia)/wiki/NetFlow"
 	new_bug_report:                                           "\(vector_repo)/issues/new?labels=type%3A+bug"
 	new_feature_request:                                      "\(vector_repo)/issues/new?labels=type%3A+new+feature"
 	new_relic:                                                "https://newrelic.com/"
@@ -369,6 +381,7 @@ urls: {
 	percent_encoding_www_form_urlencoded:                     "https://url.spec.whatwg.org/#application-x-www-form-urlencoded-percent-encode-set"
 	posix_acls:                                               "https://www.usenix.org/legacy/publications/library/proceedings/usenix03/tech/freenix03/full_papers/gruenbacher/gruenbacher_html/main.html"
 	postgresql:                                               "https://www.postgresql.org/"
+	postgresql_copy:                                          "https://www.postgresql.org/docs/current/sql-copy.html"
 	postgresql_csvlog:                                        "https://www.postgresql.org/docs/current/runtime-config-logging.html#RUNTIME-CONFIG-LOGGING-CSVLOG"
 	postgresql_matching:                                      "https://www.postgresql.org/docs/current/functions-matching.html#FUNCTIONS-POSIX-REGEXP"
 	procfs:                                                   "https://en.wikipedia.org/wiki/Procfs#:~:text=The%20proc%20filesystem%20(procfs)%20is,in%20the%20kernel%20than%20traditional"
@@ -404,9 +417,14 @@ urls: {
 	rfc_2460:                                                 "https://tools.ietf.org/html/rfc2460"
 	rfc_2822:                                                 "https://tools.ietf.org/html/rfc2822#section-3.3"
 	rfc_3339:                                                 "https://tools.ietf.org/html/rfc3339"
+	rfc_3954:                                                 "https://tools.ietf.org/html/rfc3954"
+	rfc_3414:                                                 "https://tools.ietf.org/html/rfc3414"
+	rfc_3584:                                                 "https://tools.ietf.org/html/rfc3584"
+	rfc_3826:                                                 "https://tools.ietf.org/html/rfc3826"
 	rfc_4180:                                                 "https://tools.ietf.org/html/rfc4180"
 	rfc_6587_3_4_1:                                           "https://tools.ietf.org/html/rfc6587#section-3.4.1"
 	rfc_6891:                                                 "https://tools.ietf.org/html/rfc6891"
+	rfc_7011:                                                 "https://tools.ietf.org/html/rfc7011"
 	rhel:                                                     "https://www.redhat.com/en/technologies/linux-platforms/enterprise-linux"
 	rpm:                                                      "https://rpm.org/"
 	rust:                                                     "https://www.rust-lang.org/"
@@ -423,6 +441,7 @@ urls: {
 	rustup:                                                   "https://rustup.rs"
 	redis:                                                    "https://redis.io"
 	redis_rs:                                                 "https://github.com/mitsuhiko/redis-rs"
+	seahash:                                                  "https://docs.rs/seahash"
 	sematext:                                                 "https://sematext.com"
 	sematext_create_logs_app:                                 "https://apps.sematext.com/ui/integrations"
 	sematext_es:                                              "https://sematext.com/docs/logs/index-events-via-elasticsearch-api/"
@@ -430,12 +449,14 @@ urls: {
 	sematext_monitoring:                                      "https://sematext.com/docs/monitoring/"
 	sematext_registration:                                    "https://apps.sematext.com/ui/registration"
 	semver:                                                   "https://semver.org/"
+	sflow:                                                    "https://sflow.org/sflow_version_5.txt"
 	sha1:                                                     "\(wikipedia)/wiki/SHA-1"
 	sha2:                                                     "\(wikipedia)/wiki/SHA-2"
 	sha3:                                                     "\(wikipedia)/wiki/SHA-3"
 	signal:                                                   "\(wikipedia)/wiki/Signal_(IPC)"
 	snake_case:                                               "\(wikipedia)/wiki/Snake_case"
 	snappy:                                                   "https://google.github.io/snappy/"
+	snmp:                                                     "\(wikipedia)/wiki/Simple_Network_Management_Protocol"
 	socket:                                                   "\(wikipedia)/wiki/Network_socket"
 	splunk:                                                   "https://www.splunk.com"
 	splunk_hec:                                               "https://dev.splunk.com/enterprise/docs/dataapps/httpeventcollector/"
@@ -458,6 +479,7 @@ urls: {
 	stdout:                                                   "\(wikipedia)/wiki/Standard_streams#Standard_output_(stdout)"
 	stripe_blog_canonical_log_lines:                          "https://stripe.com/blog/canonical-log-lines"
 	strptime_specifiers:                                      "https://docs.rs/chrono/latest/chrono/format/strftime/index.html#specifiers"
+	suricata_eve:                                             "https://docs.suricata.io/en/latest/output/eve/eve-json-format.html"
 	sysfs:                                                    "https://www.kernel.org/doc/html/latest/filesystems/sysfs.html"
 	syslog:                                                   "\(wikipedia)/wiki/Syslog"
 	syslog_3164:                                              "https://tools.ietf.org/html/rfc3164"
@@ -615,13 +637,17 @@ urls: {
 	vote_feature:                                             "\(vector_repo)/issues?q=is%3Aissue+is%3Aopen+sort%3Areactions-%2B1-desc+label%3A%22Type%3A+New+Feature%22"
 	wasm:                                                     "https://webassembly.org/"
 	wasm_languages:                                           "\(github)/appcypher/awesome-wasm-langs"
+	websocket:                                                "\(wikipedia)/wiki/WebSocket"
 	wikipedia:                                                "https://en.wikipedia.org"
 	windows:                                                  "https://www.microsoft.com/en-us/windows"
+	windows_event_schema:                                     "https://docs.microsoft.com/en-us/windows/win32/wes/eventschema-schema"
 	windows_installer:                                        "\(wikipedia)/wiki/Windows_Installer"
 	windows_service:                                          "https://docs.microsoft.com/en-us/powershell/module/microsoft.powershell.management/new-service"
 	woothee:                                                  "https://github.com/woothee/woothee"
+	xxhash:                                                   "https://cyan4973.github.io/xxHash/"
 	yaml:                                                     "https://yaml.org/"
 	yum:                                                      "\(wikipedia)/wiki/Yum_(software)"
+	zeek_log_formats:                                         "https://docs.zeek.org/en/master/log-formats.html"
 	zlib:                                                     "https://www.zlib.net"
 	zstd:                                                     "https://zstd.net"
 }